@@ -120,6 +120,32 @@ impl AddressInfo {
     }
 }
 
+/// Deferred credits of an address: slots at which coins will be credited to it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddressDeferredCredits {
+    /// the address
+    pub address: Address,
+    /// deferred credits in the final state
+    pub final_deferred_credits: Vec<SlotAmount>,
+    /// deferred credits including the changes of the candidate slots
+    pub candidate_deferred_credits: Vec<SlotAmount>,
+}
+
+impl std::fmt::Display for AddressDeferredCredits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Deferred credits of address {}:", self.address)?;
+        writeln!(f, "\tFinal:")?;
+        for credit in &self.final_deferred_credits {
+            writeln!(f, "\t\t{}: {}", credit.slot, credit.amount)?;
+        }
+        writeln!(f, "\tCandidate:")?;
+        for credit in &self.candidate_deferred_credits {
+            writeln!(f, "\t\t{}: {}", credit.slot, credit.amount)?;
+        }
+        Ok(())
+    }
+}
+
 /// Less information about an address
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CompactAddressInfo {
@@ -155,3 +181,26 @@ impl std::fmt::Display for CompactAddressInfo {
         Ok(())
     }
 }
+
+/// Production stats of an address over one cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressProductionStats {
+    /// the address
+    pub address: Address,
+    /// the cycle
+    pub cycle: u64,
+    /// number of blocks produced
+    pub block_success_count: u64,
+    /// number of missed blocks
+    pub block_failure_count: u64,
+}
+
+impl std::fmt::Display for AddressProductionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Address {} cycle {}: ok_count={}, nok_count={}",
+            self.address, self.cycle, self.block_success_count, self.block_failure_count
+        )
+    }
+}
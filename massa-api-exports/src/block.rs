@@ -1,11 +1,45 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_consensus_exports::block_graph_export::BlockGraphSnapshot;
+pub use massa_consensus_exports::block_status::{DiscardReasonKind, DiscardedBlockInfo};
 use massa_models::{address::Address, block::Block, block_id::BlockId, slot::Slot};
 
 use serde::{Deserialize, Serialize};
 
 use crate::display_if_true;
 
+/// Format of an exported block graph
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockGraphFormat {
+    /// structured snapshot of the graph
+    #[default]
+    Json,
+    /// Graphviz DOT representation of the graph
+    Dot,
+}
+
+/// Block graph exported in the requested format
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportedBlockGraph {
+    /// structured snapshot of the graph
+    Json(BlockGraphSnapshot),
+    /// Graphviz DOT representation of the graph
+    Dot(String),
+}
+
+/// Filter of the discarded blocks to inspect
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DiscardedBlocksFilter {
+    /// only the blocks discarded for this kind of reason, if set
+    #[serde(default)]
+    pub reason: Option<DiscardReasonKind>,
+    /// only the blocks created by this address, if set
+    #[serde(default)]
+    pub creator: Option<Address>,
+}
+
 /// refactor to delete
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BlockInfo {
@@ -27,6 +27,8 @@ pub struct APIConfig {
     pub bootstrap_whitelist_path: PathBuf,
     /// bootstrap blacklist path
     pub bootstrap_blacklist_path: PathBuf,
+    /// bootstrap audit log path, if the bootstrap server records its sessions
+    pub bootstrap_audit_log_path: Option<PathBuf>,
     /// maximum size in bytes of a request.
     pub max_request_body_size: u32,
     /// maximum size in bytes of a response.
@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::address::Address;
+use massa_models::datastore::DatastoreKeysFilter;
 use serde::{Deserialize, Serialize};
 
 /// Datastore entry query input structure
@@ -28,3 +29,33 @@ impl std::fmt::Display for DatastoreEntryOutput {
         Ok(())
     }
 }
+
+/// Datastore keys query input structure
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct DatastoreKeysInput {
+    /// address whose datastore keys are listed
+    pub address: Address,
+    /// selection of the keys: prefix, key range and page
+    #[serde(flatten)]
+    pub filter: DatastoreKeysFilter,
+}
+
+/// Datastore keys query output structure
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct DatastoreKeysOutput {
+    /// address whose datastore keys are listed
+    pub address: Address,
+    /// selected final datastore keys, None if the address does not exist
+    pub final_keys: Option<Vec<Vec<u8>>>,
+    /// selected candidate datastore keys, None if the address does not exist
+    pub candidate_keys: Option<Vec<Vec<u8>>>,
+}
+
+impl std::fmt::Display for DatastoreKeysOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "address: {}", self.address)?;
+        writeln!(f, "final keys: {:?}", self.final_keys)?;
+        writeln!(f, "candidate keys: {:?}", self.candidate_keys)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,16 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_async_pool::AsyncMessageTrigger;
+use massa_execution_exports::{
+    AbiCallStats, AbiTrace, AbiTraceCall, AsyncMessageFilter, AsyncPoolMessage,
+    BytecodeDiagnosticSeverity, BytecodeValidation, ExecutionCacheSizes, ReadOnlyStateOverride,
+    TransferInfo, TransferOrigin,
+};
 use massa_final_state::StateChanges;
-use massa_models::{address::Address, amount::Amount, output_event::SCOutputEvent, slot::Slot};
+use massa_models::{
+    address::Address, amount::Amount, bytecode::Bytecode, operation::OperationId,
+    output_event::SCOutputEvent, slot::Slot,
+};
 use serde::{Deserialize, Serialize};
 use std::{collections::VecDeque, fmt::Display};
 
@@ -27,6 +36,9 @@ pub struct ExecuteReadOnlyResponse {
     pub gas_cost: u64,
     /// state changes caused by the execution step
     pub state_changes: StateChanges,
+    /// ABI calls made by the execution, if requested and if the execution succeeded
+    #[serde(default)]
+    pub trace: Option<Vec<ExecutionTrace>>,
 }
 
 impl Display for ExecuteReadOnlyResponse {
@@ -48,10 +60,300 @@ impl Display for ExecuteReadOnlyResponse {
                 writeln!(f, "{}", event)?; // id already displayed in event
             }
         }
+        if let Some(trace) = &self.trace {
+            writeln!(f, "Trace:")?;
+            for item in trace {
+                writeln!(f, "{}{}", "  ".repeat(item.depth), item.call)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Gas estimation of a read-only call or bytecode execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GasEstimationResponse {
+    /// error message if the execution fails even with the full `max_gas` of the request
+    pub error: Option<String>,
+    /// gas consumed by the execution when run with the full `max_gas` of the request
+    pub gas_cost: u64,
+    /// smallest `max_gas` found for which the execution succeeds
+    pub required_gas: u64,
+    /// `required_gas` increased by a safety margin, to be used as the `max_gas` of the operation
+    pub estimated_max_gas: u64,
+}
+
+impl Display for GasEstimationResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(error) = &self.error {
+            return writeln!(f, "Gas estimation failed: {}", error);
+        }
+        writeln!(f, "Gas cost: {}", self.gas_cost)?;
+        writeln!(f, "Required gas: {}", self.required_gas)?;
+        writeln!(f, "Estimated max gas: {}", self.estimated_max_gas)
+    }
+}
+
+/// Cause of a native coin transfer
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CoinTransferOrigin {
+    /// execution of an operation (including the fee debit)
+    Operation(OperationId),
+    /// execution or cancellation of an asynchronous message
+    AsyncMessage,
+    /// block production and endorsement rewards
+    BlockReward,
+    /// deferred credits (roll sales for example)
+    DeferredCredit,
+    /// any other cause
+    Other,
+}
+
+impl From<TransferOrigin> for CoinTransferOrigin {
+    fn from(origin: TransferOrigin) -> Self {
+        match origin {
+            TransferOrigin::Operation(operation_id) => CoinTransferOrigin::Operation(operation_id),
+            TransferOrigin::AsyncMessage => CoinTransferOrigin::AsyncMessage,
+            TransferOrigin::BlockReward => CoinTransferOrigin::BlockReward,
+            TransferOrigin::DeferredCredit => CoinTransferOrigin::DeferredCredit,
+            TransferOrigin::Other => CoinTransferOrigin::Other,
+        }
+    }
+}
+
+/// Native coin transfer that happened at a slot
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AddressTransfer {
+    /// slot at which the transfer happened
+    pub slot: Slot,
+    /// whether the slot execution is final
+    pub is_final: bool,
+    /// spending address (None for coin creation)
+    pub from: Option<Address>,
+    /// credited address (None for coin destruction)
+    pub to: Option<Address>,
+    /// amount of coins transferred
+    pub amount: Amount,
+    /// cause of the transfer
+    pub origin: CoinTransferOrigin,
+}
+
+impl AddressTransfer {
+    /// Creates the API representation of a transfer that happened at a slot
+    pub fn new(slot: Slot, is_final: bool, transfer: TransferInfo) -> Self {
+        AddressTransfer {
+            slot,
+            is_final,
+            from: transfer.from,
+            to: transfer.to,
+            amount: transfer.amount,
+            origin: transfer.origin.into(),
+        }
+    }
+}
+
+/// Filter used when listing the messages of the asynchronous pool
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AsyncPoolFilter {
+    /// optional sender address
+    #[serde(default)]
+    pub sender: Option<Address>,
+    /// optional destination address
+    #[serde(default)]
+    pub destination: Option<Address>,
+    /// optional emission slot lower bound (included)
+    #[serde(default)]
+    pub emission_start: Option<Slot>,
+    /// optional emission slot upper bound (excluded)
+    #[serde(default)]
+    pub emission_end: Option<Slot>,
+    /// optional slot that must be within the validity window of the message
+    #[serde(default)]
+    pub valid_at: Option<Slot>,
+    /// optional message status
+    ///
+    /// Some(true) means messages of the final pool, without the candidate changes
+    /// Some(false) means messages emitted or updated in candidate slots
+    /// None means the final pool with the candidate changes applied
+    #[serde(default)]
+    pub is_final: Option<bool>,
+}
+
+impl From<AsyncPoolFilter> for AsyncMessageFilter {
+    fn from(filter: AsyncPoolFilter) -> Self {
+        AsyncMessageFilter {
+            sender: filter.sender,
+            destination: filter.destination,
+            emission_start: filter.emission_start,
+            emission_end: filter.emission_end,
+            valid_at: filter.valid_at,
+            is_final: filter.is_final,
+        }
+    }
+}
+
+/// Message of the asynchronous pool
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AsyncPoolMessageInfo {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message emitted in this slot
+    pub emission_index: u64,
+    /// emitting address
+    pub sender: Address,
+    /// destination address
+    pub destination: Address,
+    /// name of the function to call at the destination
+    pub function: String,
+    /// parameters of the call
+    pub function_params: Vec<u8>,
+    /// maximum amount of gas available to the call
+    pub max_gas: u64,
+    /// fee paid by the sender
+    pub fee: Amount,
+    /// coins sent to the destination
+    pub coins: Amount,
+    /// first slot at which the message can be executed
+    pub validity_start: Slot,
+    /// slot from which the message can no longer be executed
+    pub validity_end: Slot,
+    /// datastore change that the message waits for before being executed, if any
+    pub trigger: Option<AsyncMessageTrigger>,
+    /// whether the trigger of the message was matched
+    pub can_be_executed: bool,
+    /// whether the message is in the final pool and unchanged by the candidate slots
+    pub is_final: bool,
+}
+
+impl From<AsyncPoolMessage> for AsyncPoolMessageInfo {
+    fn from(pool_message: AsyncPoolMessage) -> Self {
+        let message = pool_message.message;
+        AsyncPoolMessageInfo {
+            emission_slot: message.emission_slot,
+            emission_index: message.emission_index,
+            sender: message.sender,
+            destination: message.destination,
+            function: message.function,
+            function_params: message.function_params,
+            max_gas: message.max_gas,
+            fee: message.fee,
+            coins: message.coins,
+            validity_start: message.validity_start,
+            validity_end: message.validity_end,
+            trigger: message.trigger,
+            can_be_executed: message.can_be_executed,
+            is_final: pool_message.is_final,
+        }
+    }
+}
+
+impl Display for AsyncPoolMessageInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Message {} of slot {}{}",
+            self.emission_index,
+            self.emission_slot,
+            if self.is_final { " (final)" } else { "" }
+        )?;
+        writeln!(
+            f,
+            "\t{} -> {}::{} ({} coins, {} fee, {} max gas)",
+            self.sender, self.destination, self.function, self.coins, self.fee, self.max_gas
+        )?;
+        writeln!(
+            f,
+            "\tValid from {} to {}{}",
+            self.validity_start,
+            self.validity_end,
+            match (&self.trigger, self.can_be_executed) {
+                (Some(_), false) => ", waiting for its trigger",
+                _ => "",
+            }
+        )
+    }
+}
+
+/// Completeness of the final events matching a filter
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EventsTruncation {
+    /// whether final events matching the filter may have been pruned
+    pub truncated: bool,
+    /// latest slot of which final events were pruned, because of their age or of the store capacity
+    pub pruned_until: Option<Slot>,
+}
+
+/// Static validation of a bytecode against the limits of the execution engine
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BytecodeValidationResponse {
+    /// whether the bytecode can be executed by the node
+    pub valid: bool,
+    /// size of the bytecode in bytes
+    pub bytecode_size: u64,
+    /// functions imported by the module, as `module.name`
+    pub imports: Vec<String>,
+    /// names of the functions exported by the module
+    pub exports: Vec<String>,
+    /// number of functions defined by the module
+    pub function_count: u64,
+    /// initial number of 64KiB pages of the memory, if any
+    pub memory_initial_pages: Option<u64>,
+    /// maximum number of 64KiB pages of the memory, if any
+    pub memory_maximum_pages: Option<u64>,
+    /// errors preventing the execution of the bytecode
+    pub errors: Vec<String>,
+    /// warnings about the bytecode
+    pub warnings: Vec<String>,
+}
+
+impl Display for BytecodeValidationResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Valid: {}", self.valid)?;
+        writeln!(f, "Bytecode size: {} bytes", self.bytecode_size)?;
+        writeln!(f, "Functions: {}", self.function_count)?;
+        if let Some(initial) = self.memory_initial_pages {
+            match self.memory_maximum_pages {
+                Some(maximum) => writeln!(f, "Memory pages: {} (max {})", initial, maximum)?,
+                None => writeln!(f, "Memory pages: {}", initial)?,
+            }
+        }
+        writeln!(f, "Imports: {}", self.imports.join(", "))?;
+        writeln!(f, "Exports: {}", self.exports.join(", "))?;
+        for error in &self.errors {
+            writeln!(f, "Error: {}", error)?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "Warning: {}", warning)?;
+        }
         Ok(())
     }
 }
 
+impl From<BytecodeValidation> for BytecodeValidationResponse {
+    fn from(validation: BytecodeValidation) -> Self {
+        let valid = validation.is_valid();
+        let (errors, warnings) = validation
+            .diagnostics
+            .into_iter()
+            .partition::<Vec<_>, _>(|d| d.severity == BytecodeDiagnosticSeverity::Error);
+        BytecodeValidationResponse {
+            valid,
+            bytecode_size: validation.bytecode_size,
+            imports: validation
+                .imports
+                .into_iter()
+                .map(|import| format!("{}.{}", import.module, import.name))
+                .collect(),
+            exports: validation.exports,
+            function_count: validation.function_count,
+            memory_initial_pages: validation.memory_pages.map(|(initial, _)| initial),
+            memory_maximum_pages: validation.memory_pages.and_then(|(_, maximum)| maximum),
+            errors: errors.into_iter().map(|d| d.message).collect(),
+            warnings: warnings.into_iter().map(|d| d.message).collect(),
+        }
+    }
+}
+
 /// read only bytecode execution request
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct ReadOnlyBytecodeExecution {
@@ -68,6 +370,15 @@ pub struct ReadOnlyBytecodeExecution {
     /// whether to start execution from final or active state. Default false
     #[serde(default)]
     pub is_final: bool,
+    /// past final slot at the end of which the ledger is read, requires the archival mode. Default none
+    #[serde(default)]
+    pub historical_slot: Option<Slot>,
+    /// temporary address states applied for this execution only. Default empty
+    #[serde(default)]
+    pub state_overrides: Vec<StateOverride>,
+    /// whether to return the ABI calls made by the execution. Default false
+    #[serde(default)]
+    pub trace: bool,
 }
 
 /// read SC call request
@@ -90,4 +401,298 @@ pub struct ReadOnlyCall {
     /// whether to start execution from final or active state. Default false
     #[serde(default)]
     pub is_final: bool,
+    /// past final slot at the end of which the ledger is read, requires the archival mode. Default none
+    #[serde(default)]
+    pub historical_slot: Option<Slot>,
+    /// temporary address states applied for this execution only. Default empty
+    #[serde(default)]
+    pub state_overrides: Vec<StateOverride>,
+    /// whether to return the ABI calls made by the execution. Default false
+    #[serde(default)]
+    pub trace: bool,
+}
+
+/// temporary state of an address, applied for a single read-only execution
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct StateOverride {
+    /// overridden address, created if it does not exist
+    pub address: Address,
+    /// balance replacing the current one
+    #[serde(default)]
+    pub balance: Option<Amount>,
+    /// bytecode replacing the current one
+    #[serde(default)]
+    pub bytecode: Option<Vec<u8>>,
+    /// datastore entries to set (with a value) or to delete (with `null`)
+    #[serde(default)]
+    pub datastore: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+impl From<StateOverride> for ReadOnlyStateOverride {
+    fn from(state_override: StateOverride) -> Self {
+        ReadOnlyStateOverride {
+            balance: state_override.balance,
+            bytecode: state_override.bytecode.map(Bytecode),
+            datastore: state_override.datastore.into_iter().collect(),
+        }
+    }
+}
+
+/// ABI call made by a read-only execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExecutionTrace {
+    /// depth of the call stack when the call was made
+    pub depth: usize,
+    /// recorded call
+    pub call: ExecutionTraceCall,
+}
+
+/// kind of a traced ABI call
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ExecutionTraceCall {
+    /// a smart contract call started
+    CallStart {
+        /// called address
+        target: Address,
+        /// coins sent with the call
+        coins: Amount,
+    },
+    /// the current smart contract call ended
+    CallEnd,
+    /// coins were transferred
+    Transfer {
+        /// sender, none if the coins were minted
+        from: Option<Address>,
+        /// recipient, none if the coins were burned
+        to: Option<Address>,
+        /// transferred amount
+        amount: Amount,
+    },
+    /// a datastore entry was read
+    DatastoreRead {
+        /// address owning the datastore
+        address: Address,
+        /// read key
+        key: Vec<u8>,
+        /// read value, none if the entry does not exist
+        value: Option<Vec<u8>>,
+    },
+    /// a datastore entry was set
+    DatastoreWrite {
+        /// address owning the datastore
+        address: Address,
+        /// written key
+        key: Vec<u8>,
+        /// written value
+        value: Vec<u8>,
+    },
+    /// data was appended to a datastore entry
+    DatastoreAppend {
+        /// address owning the datastore
+        address: Address,
+        /// appended key
+        key: Vec<u8>,
+        /// appended data
+        value: Vec<u8>,
+    },
+    /// a datastore entry was deleted
+    DatastoreDelete {
+        /// address owning the datastore
+        address: Address,
+        /// deleted key
+        key: Vec<u8>,
+    },
+    /// an event was emitted
+    Event {
+        /// event data
+        data: String,
+    },
+}
+
+impl Display for ExecutionTraceCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionTraceCall::CallStart { target, coins } => {
+                write!(f, "call {} with {} coins", target, coins)
+            }
+            ExecutionTraceCall::CallEnd => write!(f, "end of call"),
+            ExecutionTraceCall::Transfer { from, to, amount } => write!(
+                f,
+                "transfer {} from {} to {}",
+                amount,
+                from.map_or_else(|| "none".to_string(), |from| from.to_string()),
+                to.map_or_else(|| "none".to_string(), |to| to.to_string())
+            ),
+            ExecutionTraceCall::DatastoreRead {
+                address,
+                key,
+                value,
+            } => write!(f, "read {:?} of {}: {:?}", key, address, value),
+            ExecutionTraceCall::DatastoreWrite {
+                address,
+                key,
+                value,
+            } => write!(f, "write {:?} of {}: {:?}", key, address, value),
+            ExecutionTraceCall::DatastoreAppend {
+                address,
+                key,
+                value,
+            } => write!(f, "append to {:?} of {}: {:?}", key, address, value),
+            ExecutionTraceCall::DatastoreDelete { address, key } => {
+                write!(f, "delete {:?} of {}", key, address)
+            }
+            ExecutionTraceCall::Event { data } => write!(f, "event: {}", data),
+        }
+    }
+}
+
+impl From<AbiTrace> for ExecutionTrace {
+    fn from(trace: AbiTrace) -> Self {
+        ExecutionTrace {
+            depth: trace.depth,
+            call: match trace.call {
+                AbiTraceCall::CallStart { target, coins } => {
+                    ExecutionTraceCall::CallStart { target, coins }
+                }
+                AbiTraceCall::CallEnd => ExecutionTraceCall::CallEnd,
+                AbiTraceCall::Transfer { from, to, amount } => {
+                    ExecutionTraceCall::Transfer { from, to, amount }
+                }
+                AbiTraceCall::DatastoreRead {
+                    address,
+                    key,
+                    value,
+                } => ExecutionTraceCall::DatastoreRead {
+                    address,
+                    key,
+                    value,
+                },
+                AbiTraceCall::DatastoreWrite {
+                    address,
+                    key,
+                    value,
+                } => ExecutionTraceCall::DatastoreWrite {
+                    address,
+                    key,
+                    value,
+                },
+                AbiTraceCall::DatastoreAppend {
+                    address,
+                    key,
+                    value,
+                } => ExecutionTraceCall::DatastoreAppend {
+                    address,
+                    key,
+                    value,
+                },
+                AbiTraceCall::DatastoreDelete { address, key } => {
+                    ExecutionTraceCall::DatastoreDelete { address, key }
+                }
+                AbiTraceCall::Event { data } => ExecutionTraceCall::Event { data },
+            },
+        }
+    }
+}
+
+/// Profiling counters of a host ABI function over the last executed slots
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AbiCallProfile {
+    /// name of the host ABI function
+    pub function: String,
+    /// number of calls
+    pub call_count: u64,
+    /// total wall time spent in the calls, in microseconds
+    pub total_time_us: u64,
+}
+
+impl Display for AbiCallProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} calls, {} µs",
+            self.function, self.call_count, self.total_time_us
+        )
+    }
+}
+
+impl From<AbiCallStats> for AbiCallProfile {
+    fn from(stats: AbiCallStats) -> Self {
+        AbiCallProfile {
+            function: stats.function,
+            call_count: stats.call_count,
+            total_time_us: stats.total_time.as_micros().try_into().unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// Sizes of the execution caches, that can be changed while the node is running
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CacheSizes {
+    /// maximum number of compiled modules kept in RAM
+    pub module_lru_cache_size: u32,
+    /// maximum number of compiled modules kept on disk
+    pub module_hd_cache_size: usize,
+    /// number of final slots for which the ledger changes and failure reasons of each operation are kept in RAM
+    pub max_final_operation_changes_slots: usize,
+}
+
+impl Display for CacheSizes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Module cache size in RAM: {}",
+            self.module_lru_cache_size
+        )?;
+        writeln!(
+            f,
+            "Module cache size on disk: {}",
+            self.module_hd_cache_size
+        )?;
+        write!(
+            f,
+            "Final slots of kept operation changes: {}",
+            self.max_final_operation_changes_slots
+        )
+    }
+}
+
+impl From<ExecutionCacheSizes> for CacheSizes {
+    fn from(sizes: ExecutionCacheSizes) -> Self {
+        CacheSizes {
+            module_lru_cache_size: sizes.module_lru_cache_size,
+            module_hd_cache_size: sizes.module_hd_cache_size,
+            max_final_operation_changes_slots: sizes.max_final_operation_changes_slots,
+        }
+    }
+}
+
+/// New sizes of the execution caches, the omitted ones being left unchanged
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CacheSizesUpdate {
+    /// maximum number of compiled modules kept in RAM
+    #[serde(default)]
+    pub module_lru_cache_size: Option<u32>,
+    /// maximum number of compiled modules kept on disk
+    #[serde(default)]
+    pub module_hd_cache_size: Option<usize>,
+    /// number of final slots for which the ledger changes and failure reasons of each operation are kept in RAM
+    #[serde(default)]
+    pub max_final_operation_changes_slots: Option<usize>,
+}
+
+impl CacheSizesUpdate {
+    /// Applies the update to the current cache sizes
+    pub fn apply(&self, current: ExecutionCacheSizes) -> ExecutionCacheSizes {
+        ExecutionCacheSizes {
+            module_lru_cache_size: self
+                .module_lru_cache_size
+                .unwrap_or(current.module_lru_cache_size),
+            module_hd_cache_size: self
+                .module_hd_cache_size
+                .unwrap_or(current.module_hd_cache_size),
+            max_final_operation_changes_slots: self
+                .max_final_operation_changes_slots
+                .unwrap_or(current.max_final_operation_changes_slots),
+        }
+    }
 }
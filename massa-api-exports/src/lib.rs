@@ -31,10 +31,14 @@ pub mod node;
 pub mod operation;
 /// page
 pub mod page;
+/// operation pool
+pub mod pool;
 /// rolls
 pub mod rolls;
 /// slots
 pub mod slot;
+/// network upgrades
+pub mod versioning;
 
 /// Dumb utils function to display nicely boolean value
 fn display_if_true(value: bool, text: &str) -> String {
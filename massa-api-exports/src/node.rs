@@ -1,12 +1,16 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_db_exports::MassaDBIntegrityReport;
 use massa_models::node::NodeId;
-use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::stats::{
+    ConsensusStats, DiskUsageForecast, EndorsementPoolStats, ExecutionStats, NetworkStats,
+};
 use massa_models::{config::CompactConfig, slot::Slot, version::Version};
+use massa_protocol_exports::{PeerId, PeerRecord};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// node status
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -35,10 +39,15 @@ pub struct NodeStatus {
     pub consensus_stats: ConsensusStats,
     /// pool stats (operation count and endorsement count)
     pub pool_stats: (usize, usize),
+    /// endorsements available for the last blocks produced by the node
+    #[serde(default)]
+    pub endorsement_pool_stats: EndorsementPoolStats,
     /// network stats
     pub network_stats: NetworkStats,
     /// execution stats
     pub execution_stats: ExecutionStats,
+    /// final state disk usage and forecast, none if not measured yet
+    pub disk_usage: Option<DiskUsageForecast>,
     /// compact configuration
     pub config: CompactConfig,
 }
@@ -72,10 +81,16 @@ impl std::fmt::Display for NodeStatus {
         writeln!(f, "\tEndorsements count: {}", self.pool_stats.1)?;
         writeln!(f)?;
 
+        writeln!(f, "{}", self.endorsement_pool_stats)?;
+
         writeln!(f, "{}", self.network_stats)?;
 
         writeln!(f, "{}", self.execution_stats)?;
 
+        if let Some(disk_usage) = &self.disk_usage {
+            writeln!(f, "{}", disk_usage)?;
+        }
+
         writeln!(f, "Connected nodes:")?;
         for (node_id, (ip_addr, is_outgoing)) in &self.connected_nodes {
             writeln!(
@@ -89,3 +104,124 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// result of the verification of the final state database
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DbIntegrityReport {
+    /// slot of the verified final state, if one is stored
+    pub slot: Option<Slot>,
+    /// number of entries of the final state
+    pub key_count: u64,
+    /// final state hash stored in the database
+    pub stored_hash: String,
+    /// final state hash recomputed from the entries of the database
+    pub computed_hash: String,
+    /// true if both hashes match and every entry is valid
+    pub is_valid: bool,
+    /// number of invalid entries
+    pub invalid_key_count: u64,
+    /// first invalid keys
+    pub invalid_keys: Vec<Vec<u8>>,
+}
+
+impl From<MassaDBIntegrityReport> for DbIntegrityReport {
+    fn from(report: MassaDBIntegrityReport) -> Self {
+        DbIntegrityReport {
+            slot: report.change_id,
+            key_count: report.key_count,
+            stored_hash: report.stored_hash.to_string(),
+            computed_hash: report.computed_hash.to_string(),
+            is_valid: report.is_valid(),
+            invalid_key_count: report.invalid_key_count,
+            invalid_keys: report.invalid_keys,
+        }
+    }
+}
+
+impl std::fmt::Display for DbIntegrityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(slot) = self.slot {
+            writeln!(f, "Slot: {}", slot)?;
+        }
+        writeln!(f, "Entries: {}", self.key_count)?;
+        writeln!(f, "Stored hash: {}", self.stored_hash)?;
+        writeln!(f, "Computed hash: {}", self.computed_hash)?;
+        writeln!(f, "Invalid entries: {}", self.invalid_key_count)?;
+        for key in &self.invalid_keys {
+            writeln!(f, "\t{}", String::from_utf8_lossy(key))?;
+        }
+        write!(
+            f,
+            "Database is {}",
+            if self.is_valid { "valid" } else { "corrupted" }
+        )
+    }
+}
+
+/// entry of the audit log of the bootstrap server: a bootstrap session, or a refused connection
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BootstrapSessionAudit {
+    /// IP of the client
+    pub ip: IpAddr,
+    /// timestamp of the start of the session
+    pub start: MassaTime,
+    /// duration of the session, 0 for a refused connection
+    pub duration: MassaTime,
+    /// bytes sent to the client
+    pub bytes_sent: u64,
+    /// "success", or the error that ended the session
+    pub termination_reason: String,
+    /// limits violated by the client: "blacklist", "whitelist", "per_ip_min_interval",
+    /// "max_simultaneous_bootstraps", "max_simultaneous_challenges", "challenge", "timeout"
+    /// or "max_session_duration"
+    pub violated_limits: Vec<String>,
+}
+
+/// persisted trust state of a peer
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PeerStoreEntry {
+    /// id of the peer
+    pub node_id: NodeId,
+    /// address of the last connection with the peer
+    pub address: Option<SocketAddr>,
+    /// behaviour score, negative after bans
+    pub score: i64,
+    /// last time a handshake with the peer succeeded
+    pub last_seen: Option<MassaTime>,
+    /// time at which the ban of the peer ends, none if the peer is not banned
+    pub ban_expiry: Option<MassaTime>,
+    /// number of handshake failures since the last successful handshake
+    pub handshake_failures: u64,
+}
+
+impl From<(PeerId, PeerRecord)> for PeerStoreEntry {
+    fn from((peer_id, record): (PeerId, PeerRecord)) -> Self {
+        PeerStoreEntry {
+            node_id: NodeId::new(peer_id.get_public_key()),
+            address: record.addr,
+            score: record.score,
+            last_seen: record.last_seen,
+            ban_expiry: record.ban_expiry,
+            handshake_failures: record.handshake_failures,
+        }
+    }
+}
+
+impl std::fmt::Display for PeerStoreEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Node {}: score {}", self.node_id, self.score)?;
+        if let Some(address) = self.address {
+            write!(f, ", address {}", address)?;
+        }
+        if let Some(last_seen) = self.last_seen {
+            write!(f, ", last seen {}", last_seen.format_instant())?;
+        }
+        if self.handshake_failures > 0 {
+            write!(f, ", {} handshake failures", self.handshake_failures)?;
+        }
+        if let Some(ban_expiry) = self.ban_expiry {
+            write!(f, ", banned until {}", ban_expiry.format_instant())?;
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,12 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_execution_exports::{OperationFailure, OperationFailureCode};
+use massa_ledger_exports::LedgerChanges;
 use massa_models::{
+    address::Address,
     block_id::BlockId,
     operation::{OperationId, SecureShareOperation},
+    slot::Slot,
 };
 
 use massa_signature::{PublicKey, Signature};
@@ -39,6 +43,15 @@ pub struct OperationInfo {
     pub operation: SecureShareOperation,
     /// true if the operation execution succeeded, false if failed, None means unknown
     pub op_exec_status: Option<bool>,
+    /// slot at which the operation was executed, None means unknown
+    #[serde(default)]
+    pub op_exec_slot: Option<Slot>,
+    /// ledger changes caused by the operation execution, fees included, None means unknown
+    #[serde(default)]
+    pub op_ledger_changes: Option<LedgerChanges>,
+    /// reason of the operation execution failure, None if it succeeded or if unknown
+    #[serde(default)]
+    pub op_failure: Option<OperationFailureReason>,
 }
 
 impl std::fmt::Display for OperationInfo {
@@ -56,6 +69,18 @@ impl std::fmt::Display for OperationInfo {
             ),
             display_option_bool(self.op_exec_status, "succes", "failed", "status unknown")
         )?;
+        if let Some(slot) = self.op_exec_slot {
+            writeln!(f, "Executed at slot {}", slot)?;
+        }
+        if let Some(failure) = &self.op_failure {
+            writeln!(f, "Failure: {}", failure)?;
+        }
+        if let Some(ledger_changes) = &self.op_ledger_changes {
+            writeln!(f, "Changed ledger entries:")?;
+            for address in ledger_changes.0.keys() {
+                writeln!(f, "\t- {}", address)?;
+            }
+        }
         writeln!(f, "In blocks:")?;
         for block_id in &self.in_blocks {
             writeln!(f, "\t- {}", block_id)?;
@@ -65,6 +90,67 @@ impl std::fmt::Display for OperationInfo {
     }
 }
 
+/// Category of the error that made an operation execution fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OperationFailureKind {
+    /// the execution ran out of gas
+    OutOfGas,
+    /// the smart contract code failed (assertion failure, abort, invalid ABI call...)
+    ScriptFailure,
+    /// the coin or roll operation could not be applied (insufficient balance or rolls...)
+    OperationRejected,
+    /// any other execution error
+    RuntimeError,
+}
+
+impl From<OperationFailureCode> for OperationFailureKind {
+    fn from(code: OperationFailureCode) -> Self {
+        match code {
+            OperationFailureCode::OutOfGas => OperationFailureKind::OutOfGas,
+            OperationFailureCode::ScriptFailure => OperationFailureKind::ScriptFailure,
+            OperationFailureCode::OperationRejected => OperationFailureKind::OperationRejected,
+            OperationFailureCode::RuntimeError => OperationFailureKind::RuntimeError,
+        }
+    }
+}
+
+/// Structured reason of an operation execution failure
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationFailureReason {
+    /// category of the error
+    pub code: OperationFailureKind,
+    /// full error message
+    pub message: String,
+    /// address of the smart contract being executed when the error happened, if any
+    pub address: Option<Address>,
+    /// gas remaining when the error happened, if known
+    pub remaining_gas: Option<u64>,
+}
+
+impl std::fmt::Display for OperationFailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.code)?;
+        if let Some(address) = &self.address {
+            write!(f, " in {}", address)?;
+        }
+        if let Some(remaining_gas) = self.remaining_gas {
+            write!(f, " with {} remaining gas", remaining_gas)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl From<OperationFailure> for OperationFailureReason {
+    fn from(failure: OperationFailure) -> Self {
+        OperationFailureReason {
+            code: failure.code.into(),
+            message: failure.message,
+            address: failure.address,
+            remaining_gas: failure.remaining_gas,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use jsonrpsee::core::__reexports::serde_json::{self, Value};
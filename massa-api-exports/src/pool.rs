@@ -0,0 +1,270 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, amount::Amount, operation::OperationId, slot::Slot};
+use massa_pool_exports::{
+    AdmissionStageStats, DenunciationPoolEntry, DenunciationProgress, FeeHistogramBucket,
+    FeeRecommendation, OperationAdmissionStage, OperationPoolSummary, PendingOperationInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// A pending operation of the operation pool
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PendingOperation {
+    /// operation id
+    pub id: OperationId,
+    /// address of the creator of the operation
+    pub creator_address: Address,
+    /// thread of the creator of the operation
+    pub thread: u8,
+    /// fee paid by the operation
+    pub fee: Amount,
+    /// max gas of the operation
+    pub max_gas: u64,
+    /// serialized size of the operation, in bytes
+    pub size: usize,
+    /// priority score of the operation in the pool: raw fee paid per unit of weighted gas
+    pub score: f64,
+    /// last period at which the operation can be included in a block
+    pub expire_period: u64,
+    /// number of pending operations of the creator of the operation
+    pub creator_operation_count: usize,
+}
+
+impl Display for PendingOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Operation {}", self.id)?;
+        writeln!(
+            f,
+            "\tCreator: {} (thread {}, {} pending operations)",
+            self.creator_address, self.thread, self.creator_operation_count
+        )?;
+        writeln!(
+            f,
+            "\tFee: {}, max gas: {}, size: {} bytes",
+            self.fee, self.max_gas, self.size
+        )?;
+        write!(
+            f,
+            "\tScore: {:.6}, expire period: {}",
+            self.score, self.expire_period
+        )
+    }
+}
+
+impl From<PendingOperationInfo> for PendingOperation {
+    fn from(info: PendingOperationInfo) -> Self {
+        PendingOperation {
+            id: info.id,
+            creator_address: info.creator_address,
+            thread: info.thread,
+            fee: info.fee,
+            max_gas: info.max_gas,
+            size: info.size,
+            score: info.score,
+            expire_period: info.expire_period,
+            creator_operation_count: info.creator_operation_count,
+        }
+    }
+}
+
+/// Number of pending operations whose fee is in a range
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FeeBucket {
+    /// lowest fee of the range (inclusive)
+    pub min_fee: Amount,
+    /// highest fee of the range (inclusive)
+    pub max_fee: Amount,
+    /// number of pending operations paying a fee in the range
+    pub count: usize,
+}
+
+impl From<FeeHistogramBucket> for FeeBucket {
+    fn from(bucket: FeeHistogramBucket) -> Self {
+        FeeBucket {
+            min_fee: bucket.min_fee,
+            max_fee: bucket.max_fee,
+            count: bucket.count,
+        }
+    }
+}
+
+/// Summary statistics of the operation pool
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PoolSummary {
+    /// number of pending operations
+    pub operation_count: usize,
+    /// number of pending operations of each thread
+    pub operations_per_thread: Vec<usize>,
+    /// distribution of the fees of the pending operations, by increasing fee range, empty ranges omitted
+    pub fee_histogram: Vec<FeeBucket>,
+    /// addresses with the most pending operations, with their operation count, biggest first
+    pub top_senders: Vec<(Address, usize)>,
+}
+
+impl Display for PoolSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Pending operations: {}", self.operation_count)?;
+        writeln!(f, "Pending operations per thread:")?;
+        for (thread, count) in self.operations_per_thread.iter().enumerate() {
+            writeln!(f, "\tThread {}: {}", thread, count)?;
+        }
+        writeln!(f, "Fee distribution:")?;
+        for bucket in &self.fee_histogram {
+            writeln!(
+                f,
+                "\t{} to {}: {}",
+                bucket.min_fee, bucket.max_fee, bucket.count
+            )?;
+        }
+        write!(f, "Top senders:")?;
+        for (address, count) in &self.top_senders {
+            write!(f, "\n\t{}: {}", address, count)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<OperationPoolSummary> for PoolSummary {
+    fn from(summary: OperationPoolSummary) -> Self {
+        PoolSummary {
+            operation_count: summary.operation_count,
+            operations_per_thread: summary.operations_per_thread,
+            fee_histogram: summary
+                .fee_histogram
+                .into_iter()
+                .map(FeeBucket::from)
+                .collect(),
+            top_senders: summary.top_senders,
+        }
+    }
+}
+
+/// Fees to pay for an operation to enter the pool and to be likely included in a block
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FeeRecommendationInfo {
+    /// minimal fee accepted by the pool
+    pub minimal_fee: Amount,
+    /// fee likely to get an operation included in the next blocks
+    pub recommended_fee: Amount,
+}
+
+impl Display for FeeRecommendationInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Minimal fee: {}", self.minimal_fee)?;
+        write!(f, "Recommended fee: {}", self.recommended_fee)
+    }
+}
+
+impl From<FeeRecommendation> for FeeRecommendationInfo {
+    fn from(recommendation: FeeRecommendation) -> Self {
+        FeeRecommendationInfo {
+            minimal_fee: recommendation.minimal_fee,
+            recommended_fee: recommendation.recommended_fee,
+        }
+    }
+}
+
+/// Progress of a denunciation pool item
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenunciationItemStatus {
+    /// a single endorsement or block header was seen: no double-staking detected
+    Accumulating,
+    /// a double-staking was detected and its denunciation waits to be included in a block
+    Pending,
+    /// the denunciation was executed in a candidate block
+    Included,
+    /// the denunciation was executed in a final block
+    Final,
+}
+
+impl From<DenunciationProgress> for DenunciationItemStatus {
+    fn from(progress: DenunciationProgress) -> Self {
+        match progress {
+            DenunciationProgress::Accumulating => DenunciationItemStatus::Accumulating,
+            DenunciationProgress::Pending => DenunciationItemStatus::Pending,
+            DenunciationProgress::Included => DenunciationItemStatus::Included,
+            DenunciationProgress::Final => DenunciationItemStatus::Final,
+        }
+    }
+}
+
+/// An item of the denunciation pool
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DenunciationPoolItem {
+    /// slot of the denounced endorsements or block headers
+    pub slot: Slot,
+    /// index of the denounced endorsements, none for block headers
+    pub index: Option<u32>,
+    /// address of the creator of the denounced endorsements or block headers
+    pub address: Address,
+    /// progress of the denunciation
+    pub status: DenunciationItemStatus,
+    /// last final period at which the item is kept in the pool
+    pub expire_period: u64,
+}
+
+impl Display for DenunciationPoolItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "Endorsements at slot {} index {}", self.slot, index)?,
+            None => write!(f, "Block headers at slot {}", self.slot)?,
+        }
+        write!(
+            f,
+            " by {}: {:?} (expires after period {})",
+            self.address, self.status, self.expire_period
+        )
+    }
+}
+
+impl From<DenunciationPoolEntry> for DenunciationPoolItem {
+    fn from(entry: DenunciationPoolEntry) -> Self {
+        DenunciationPoolItem {
+            slot: entry.slot,
+            index: entry.index,
+            address: entry.address,
+            status: entry.progress.into(),
+            expire_period: entry.expire_period,
+        }
+    }
+}
+
+/// Statistics of a stage of the operation admission pipeline
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AdmissionStageInfo {
+    /// admission stage
+    pub stage: OperationAdmissionStage,
+    /// number of operations that entered the stage
+    pub checked: u64,
+    /// number of operations rejected by the stage
+    pub rejected: u64,
+    /// cumulated time spent in the stage, in microseconds
+    pub total_duration_us: u64,
+}
+
+impl Display for AdmissionStageInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?}: {} checked, {} rejected, {} µs spent",
+            self.stage, self.checked, self.rejected, self.total_duration_us
+        )
+    }
+}
+
+impl From<(OperationAdmissionStage, AdmissionStageStats)> for AdmissionStageInfo {
+    fn from((stage, stats): (OperationAdmissionStage, AdmissionStageStats)) -> Self {
+        AdmissionStageInfo {
+            stage,
+            checked: stats.checked,
+            rejected: stats.rejected,
+            total_duration_us: stats
+                .total_duration
+                .as_micros()
+                .try_into()
+                .unwrap_or(u64::MAX),
+        }
+    }
+}
@@ -21,3 +21,50 @@ impl std::fmt::Display for RollsInfo {
         Ok(())
     }
 }
+
+/// Roll distribution statistics of a cycle
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct StakersStatistics {
+    /// cycle number
+    pub cycle: u64,
+    /// number of addresses owning rolls
+    pub staker_count: u64,
+    /// total number of rolls
+    pub total_rolls: u64,
+    /// rolls taken into account by the selector for the cycle, if known
+    pub active_rolls: Option<u64>,
+    /// `active_rolls / total_rolls`, if known
+    pub active_roll_ratio: Option<f64>,
+    /// Gini coefficient of the roll counts, 0 meaning perfect equality
+    pub gini_coefficient: f64,
+    /// number of biggest stakers taken into account by `top_stakers_share`
+    pub top_n: u64,
+    /// share of the total rolls owned by the `top_n` biggest stakers
+    pub top_stakers_share: f64,
+}
+
+impl std::fmt::Display for StakersStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Cycle {}:", self.cycle)?;
+        writeln!(f, "\tStakers: {}", self.staker_count)?;
+        writeln!(f, "\tTotal rolls: {}", self.total_rolls)?;
+        match (self.active_rolls, self.active_roll_ratio) {
+            (Some(active_rolls), Some(ratio)) => writeln!(
+                f,
+                "\tActive rolls: {} ({:.2}% of total)",
+                active_rolls,
+                ratio * 100.0
+            )?,
+            (Some(active_rolls), None) => writeln!(f, "\tActive rolls: {}", active_rolls)?,
+            _ => writeln!(f, "\tActive rolls: unknown")?,
+        }
+        writeln!(f, "\tGini coefficient: {:.4}", self.gini_coefficient)?;
+        writeln!(
+            f,
+            "\tTop {} stakers share: {:.2}%",
+            self.top_n,
+            self.top_stakers_share * 100.0
+        )?;
+        Ok(())
+    }
+}
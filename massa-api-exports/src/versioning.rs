@@ -0,0 +1,107 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_time::MassaTime;
+use massa_versioning::versioning::{ComponentStateTypeId, MipComponent, MipInfo, MipStatus};
+use num::rational::Ratio;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Status of a MIP (network upgrade) as seen by the node
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MipStatusInfo {
+    /// MIP name
+    pub name: String,
+    /// network version announced in the block headers
+    pub version: u32,
+    /// new version of each component concerned by the MIP
+    pub components: BTreeMap<String, u32>,
+    /// when the MIP can start being announced
+    pub start: MassaTime,
+    /// when the MIP is considered failed if not locked in
+    pub timeout: MassaTime,
+    /// delay between the lock in and the activation of the MIP
+    pub activation_delay: MassaTime,
+    /// current state: Defined, Started, LockedIn, Active, Failed or Error
+    pub state: String,
+    /// ratio of the last considered blocks announcing the MIP version, as [numerator, denominator]
+    pub vote_ratio: Ratio<u64>,
+    /// when the MIP becomes active (only known once LockedIn)
+    pub activation_at: Option<MassaTime>,
+}
+
+impl From<MipStatus> for MipStatusInfo {
+    fn from(status: MipStatus) -> Self {
+        MipStatusInfo {
+            name: status.mip_info.name,
+            version: status.mip_info.version,
+            components: status
+                .mip_info
+                .components
+                .into_iter()
+                .map(|(component, version)| (format!("{:?}", component), version))
+                .collect(),
+            start: status.mip_info.start,
+            timeout: status.mip_info.timeout,
+            activation_delay: status.mip_info.activation_delay,
+            state: format!("{:?}", status.state),
+            vote_ratio: status.vote_ratio,
+            activation_at: status.activation_at,
+        }
+    }
+}
+
+impl TryFrom<MipStatusInfo> for MipStatus {
+    type Error = String;
+
+    fn try_from(info: MipStatusInfo) -> Result<Self, Self::Error> {
+        let components = info
+            .components
+            .into_iter()
+            .map(|(name, version)| {
+                (0..MipComponent::VARIANT_COUNT as u32)
+                    .map(MipComponent::from)
+                    .find(|component| format!("{:?}", component) == name)
+                    .map(|component| (component, version))
+                    .ok_or_else(|| format!("unknown MIP component: {}", name))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        let state = (0..ComponentStateTypeId::VARIANT_COUNT as u32)
+            .filter_map(|id| ComponentStateTypeId::try_from(id).ok())
+            .find(|state| format!("{:?}", state) == info.state)
+            .ok_or_else(|| format!("unknown MIP state: {}", info.state))?;
+        Ok(MipStatus {
+            mip_info: MipInfo {
+                name: info.name,
+                version: info.version,
+                components,
+                start: info.start,
+                timeout: info.timeout,
+                activation_delay: info.activation_delay,
+            },
+            state,
+            vote_ratio: info.vote_ratio,
+            activation_at: info.activation_at,
+        })
+    }
+}
+
+impl Display for MipStatusInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "MIP {} (version {}): {}",
+            self.name, self.version, self.state
+        )?;
+        writeln!(
+            f,
+            "\tVotes: {}/{}",
+            self.vote_ratio.numer(),
+            self.vote_ratio.denom()
+        )?;
+        if let Some(activation_at) = self.activation_at {
+            writeln!(f, "\tActivation at: {}", activation_at)?;
+        }
+        Ok(())
+    }
+}
@@ -17,6 +17,7 @@ use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
 use massa_execution_exports::ExecutionController;
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::operation::OperationLifecycleFilter;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_models::version::Version;
@@ -146,12 +147,37 @@ impl MassaApiServer for API<ApiV2> {
         .await
     }
 
+    async fn subscribe_blockclique_reorgs(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        broadcast_via_ws(
+            self.0.consensus_broadcasts.blockclique_reorg_sender.clone(),
+            pending,
+        )
+        .await
+    }
+
     async fn subscribe_new_operations(
         &self,
         pending: PendingSubscriptionSink,
     ) -> SubscriptionResult {
         broadcast_via_ws(self.0.pool_broadcasts.operation_sender.clone(), pending).await
     }
+
+    async fn subscribe_operation_lifecycle(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: Option<OperationLifecycleFilter>,
+    ) -> SubscriptionResult {
+        let filter = filter.unwrap_or_default();
+        broadcast_filtered_via_ws(
+            self.0.pool_broadcasts.operation_lifecycle_sender.clone(),
+            pending,
+            move |event| filter.matches(event),
+        )
+        .await
+    }
 }
 
 // Brodcast the stream(sender) content via a WebSocket
@@ -159,6 +185,19 @@ async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
     sender: tokio::sync::broadcast::Sender<T>,
     pending: PendingSubscriptionSink,
 ) -> SubscriptionResult {
+    broadcast_filtered_via_ws(sender, pending, |_| true).await
+}
+
+// Brodcast the stream(sender) items selected by `filter` via a WebSocket
+async fn broadcast_filtered_via_ws<T, F>(
+    sender: tokio::sync::broadcast::Sender<T>,
+    pending: PendingSubscriptionSink,
+    filter: F,
+) -> SubscriptionResult
+where
+    T: Serialize + Send + Clone + 'static,
+    F: Fn(&T) -> bool + Send,
+{
     let sink = pending.accept().await?;
     let closed = sink.closed();
     let stream = BroadcastStream::new(sender.subscribe());
@@ -171,6 +210,10 @@ async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
 
             // received new item from the stream.
             Either::Right((Some(Ok(item)), c)) => {
+                if !filter(&item) {
+                    closed = c;
+                    continue;
+                }
                 let notif = SubscriptionMessage::from_json(&item)?;
 
                 if sink.send(notif).await.is_err() {
@@ -6,6 +6,7 @@ use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::operation::OperationLifecycleFilter;
 use massa_models::version::Version;
 
 /// Exposed API methods
@@ -50,6 +51,14 @@ pub trait MassaApi {
 	)]
     async fn subscribe_new_filled_blocks(&self) -> SubscriptionResult;
 
+    /// Blockclique changes that demoted non-final blocks of the previous blockclique.
+    #[subscription(
+		name = "subscribe_blockclique_reorgs" => "blockclique_reorgs",
+		unsubscribe = "unsubscribe_blockclique_reorgs",
+		item = BlockcliqueReorg
+	)]
+    async fn subscribe_blockclique_reorgs(&self) -> SubscriptionResult;
+
     /// New produced operations.
     #[subscription(
 		name = "subscribe_new_operations" => "new_operations",
@@ -57,4 +66,16 @@ pub trait MassaApi {
 		item = Operation
 	)]
     async fn subscribe_new_operations(&self) -> SubscriptionResult;
+
+    /// Pending operations leaving the pool because they were included in a block, expired or were evicted,
+    /// restricted to the given operations and senders if any.
+    #[subscription(
+		name = "subscribe_operation_lifecycle" => "operation_lifecycle",
+		unsubscribe = "unsubscribe_operation_lifecycle",
+		item = OperationLifecycleEvent
+	)]
+    async fn subscribe_operation_lifecycle(
+        &self,
+        filter: Option<OperationLifecycleFilter>,
+    ) -> SubscriptionResult;
 }
@@ -12,34 +12,57 @@ use jsonrpsee::server::middleware::HostFilterLayer;
 use jsonrpsee::server::{BatchRequestConfig, ServerBuilder, ServerHandle};
 use jsonrpsee::RpcModule;
 use massa_api_exports::{
-    address::AddressInfo,
-    block::{BlockInfo, BlockSummary},
+    address::{AddressDeferredCredits, AddressInfo, AddressProductionStats},
+    block::{
+        BlockGraphFormat, BlockInfo, BlockSummary, DiscardedBlockInfo, DiscardedBlocksFilter,
+        ExportedBlockGraph,
+    },
     config::APIConfig,
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeysInput, DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        AbiCallProfile, AddressTransfer, AsyncPoolFilter, AsyncPoolMessageInfo,
+        BytecodeValidationResponse, CacheSizes, CacheSizesUpdate, EventsTruncation,
+        ExecuteReadOnlyResponse, GasEstimationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{BootstrapSessionAudit, DbIntegrityReport, NodeStatus, PeerStoreEntry},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    pool::{
+        AdmissionStageInfo, DenunciationPoolItem, FeeRecommendationInfo, PendingOperation,
+        PoolSummary,
+    },
+    rolls::StakersStatistics,
+    versioning::MipStatusInfo,
     TimeInterval,
 };
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
 use massa_execution_exports::ExecutionController;
+use massa_final_state::FinalState;
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::node::NodeId;
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
+use massa_models::stats::ThroughputBucket;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
-    execution::EventFilter, slot::Slot, version::Version,
+    address::Address,
+    block::Block,
+    block_id::BlockId,
+    endorsement::EndorsementId,
+    execution::{EventFilter, TransferFilter},
+    slot::Slot,
+    version::Version,
 };
 use massa_pool_exports::{PoolBroadcasts, PoolController};
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{ProtocolConfig, ProtocolController};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
@@ -96,6 +119,12 @@ pub struct Private {
     pub stop_cv: Arc<(Mutex<bool>, Condvar)>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// final state, to verify its database
+    pub final_state: Arc<RwLock<FinalState>>,
+    /// link to the consensus component, to export the block graph
+    pub consensus_controller: Box<dyn ConsensusController>,
+    /// link to the pool component, to inspect the pending operations
+    pub pool_controller: Box<dyn PoolController>,
 }
 
 /// API v2 content
@@ -247,6 +276,36 @@ pub trait MassaRpc {
         arg: Vec<ReadOnlyCall>,
     ) -> RpcResult<Vec<ExecuteReadOnlyResponse>>;
 
+    /// Execute a batch of SC functions in read-only mode against the same state.
+    /// No slot is executed in the middle of the batch, and the calls reading the same snapshot share their speculative context.
+    /// The total `max_gas` of the batch is bounded by the read-only gas limit of a single call.
+    #[method(name = "execute_read_only_call_batch")]
+    async fn execute_read_only_call_batch(
+        &self,
+        arg: Vec<ReadOnlyCall>,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>>;
+
+    /// Estimate the `max_gas` needed by bytecode executions, searching up to their `max_gas`.
+    #[method(name = "estimate_gas_bytecode")]
+    async fn estimate_gas_bytecode(
+        &self,
+        arg: Vec<ReadOnlyBytecodeExecution>,
+    ) -> RpcResult<Vec<GasEstimationResponse>>;
+
+    /// Estimate the `max_gas` needed by SC function calls, searching up to their `max_gas`.
+    #[method(name = "estimate_gas_call")]
+    async fn estimate_gas_call(
+        &self,
+        arg: Vec<ReadOnlyCall>,
+    ) -> RpcResult<Vec<GasEstimationResponse>>;
+
+    /// Check bytecodes against the limits of the execution engine without executing them.
+    #[method(name = "validate_bytecode")]
+    async fn validate_bytecode(
+        &self,
+        arg: Vec<Vec<u8>>,
+    ) -> RpcResult<Vec<BytecodeValidationResponse>>;
+
     /// Remove a vector of addresses used to stake.
     /// No confirmation to expect.
     #[method(name = "remove_staking_addresses")]
@@ -321,10 +380,134 @@ pub trait MassaRpc {
     #[method(name = "node_unban_by_id")]
     async fn node_unban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Returns the persisted trust state of the peers (score, last seen, ban expiry, handshake failures),
+    /// lowest scores first.
+    #[method(name = "node_get_peer_store")]
+    async fn node_get_peer_store(&self) -> RpcResult<Vec<PeerStoreEntry>>;
+
+    /// Overwrite the score of the given node ids.
+    /// No confirmation to expect.
+    #[method(name = "node_set_peer_scores")]
+    async fn node_set_peer_scores(&self, arg: Vec<(NodeId, i64)>) -> RpcResult<()>;
+
+    /// Forget everything persisted about the given node ids, lifting their ban.
+    /// No confirmation to expect.
+    #[method(name = "node_forget_peers")]
+    async fn node_forget_peers(&self, arg: Vec<NodeId>) -> RpcResult<()>;
+
+    /// Returns the last entries of the audit log of the bootstrap server, the most recent last:
+    /// at most the given count, 100 by default.
+    #[method(name = "node_bootstrap_audit_log")]
+    async fn node_bootstrap_audit_log(
+        &self,
+        arg: Option<usize>,
+    ) -> RpcResult<Vec<BootstrapSessionAudit>>;
+
+    /// Exports the block graph (active and discarded blocks, parents, cliques),
+    /// as a structured snapshot by default or in the Graphviz DOT format.
+    #[method(name = "node_export_block_graph")]
+    async fn node_export_block_graph(
+        &self,
+        arg: Option<BlockGraphFormat>,
+    ) -> RpcResult<ExportedBlockGraph>;
+
+    /// Returns the discarded blocks kept by consensus (id, slot, creator, reason, sequence number),
+    /// optionally filtered by kind of discard reason and creator.
+    #[method(name = "node_get_discarded_blocks")]
+    async fn node_get_discarded_blocks(
+        &self,
+        arg: Option<DiscardedBlocksFilter>,
+    ) -> RpcResult<Vec<DiscardedBlockInfo>>;
+
+    /// Recomputes the final state hash from the database, compares it to the stored one,
+    /// and reports the invalid entries. Writes to the final state are blocked meanwhile.
+    #[method(name = "node_verify_db_integrity")]
+    async fn node_verify_db_integrity(&self) -> RpcResult<DbIntegrityReport>;
+
+    /// Starts compacting a whole column family of the final state database
+    /// ("state", "metadata", "versioning", "history", "production_stats", "events" or "transfers") in the background.
+    /// Meant to be scheduled by operators during low-traffic periods.
+    #[method(name = "node_trigger_compaction")]
+    async fn node_trigger_compaction(&self, arg: String) -> RpcResult<()>;
+
+    /// Production stats of the given addresses for each of the last `cycle_count` cycles,
+    /// including the cycles already pruned from the PoS cycle history.
+    #[method(name = "node_get_production_stats")]
+    async fn node_get_production_stats(
+        &self,
+        addresses: Vec<Address>,
+        cycle_count: u64,
+    ) -> RpcResult<Vec<AddressProductionStats>>;
+
+    /// Call count and time of each host ABI function over the last `slot_count` executed slots,
+    /// most time-consuming functions first. Empty if the ABI profiling is disabled in the node config.
+    #[method(name = "node_get_abi_profile")]
+    async fn node_get_abi_profile(&self, slot_count: usize) -> RpcResult<Vec<AbiCallProfile>>;
+
+    /// Current sizes of the execution caches.
+    #[method(name = "node_get_cache_sizes")]
+    async fn node_get_cache_sizes(&self) -> RpcResult<CacheSizes>;
+
+    /// Resizes the execution caches without restarting the node, the omitted sizes being left unchanged.
+    /// The entries in excess are evicted right away. Returns the new sizes.
+    #[method(name = "node_set_cache_sizes")]
+    async fn node_set_cache_sizes(&self, update: CacheSizesUpdate) -> RpcResult<CacheSizes>;
+
+    /// Pending operations of the pool, from the highest to the lowest priority,
+    /// optionally restricted to the operations created by an address.
+    /// At most `max_arguments` operations are returned per page.
+    #[method(name = "node_get_pending_operations")]
+    async fn node_get_pending_operations(
+        &self,
+        address: Option<Address>,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<PendingOperation>>;
+
+    /// Summary of the operation pool: operation count per thread, fee distribution,
+    /// and the `top_senders` addresses with the most pending operations.
+    #[method(name = "node_get_operation_pool_summary")]
+    async fn node_get_operation_pool_summary(&self, top_senders: usize) -> RpcResult<PoolSummary>;
+
+    /// Entries of the denunciation pool: the slots (and endorsement indexes) where an endorsement or block header was seen,
+    /// their creator, whether a double-staking was detected and whether its denunciation was included in a block.
+    #[method(name = "node_get_denunciation_pool")]
+    async fn node_get_denunciation_pool(&self) -> RpcResult<Vec<DenunciationPoolItem>>;
+
+    /// Statistics of each stage of the operation admission pipeline since the node started:
+    /// checked and rejected operations and time spent.
+    #[method(name = "node_get_operation_admission_stats")]
+    async fn node_get_operation_admission_stats(&self) -> RpcResult<Vec<AdmissionStageInfo>>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
 
+    /// Status of every MIP (network upgrade) known by the node, with its vote ratio and activation time.
+    #[method(name = "get_mip_status")]
+    async fn get_mip_status(&self) -> RpcResult<Vec<MipStatusInfo>>;
+
+    /// MIPs that are new or whose state changed since a previous `get_mip_status` or `get_versioning_announcements` call.
+    /// Vote ratio changes alone are not reported.
+    #[method(name = "get_versioning_announcements")]
+    async fn get_versioning_announcements(
+        &self,
+        arg: Vec<MipStatusInfo>,
+    ) -> RpcResult<Vec<MipStatusInfo>>;
+
+    /// Minimal fee accepted by the operation pool, following the congestion of the final blocks if enabled,
+    /// and fee likely to get an operation included in the next blocks.
+    #[method(name = "get_fee_recommendation")]
+    async fn get_fee_recommendation(&self) -> RpcResult<FeeRecommendationInfo>;
+
+    /// Returns the number of final executed operations over the last `window` milliseconds,
+    /// split in buckets of `resolution` milliseconds.
+    #[method(name = "get_throughput_history")]
+    async fn get_throughput_history(
+        &self,
+        window: MassaTime,
+        resolution: MassaTime,
+    ) -> RpcResult<Vec<ThroughputBucket>>;
+
     /// Get cliques.
     #[method(name = "get_cliques")]
     async fn get_cliques(&self) -> RpcResult<Vec<Clique>>;
@@ -336,6 +519,11 @@ pub trait MassaRpc {
         page_request: Option<PageRequest>,
     ) -> RpcResult<PagedVec<(Address, u64)>>;
 
+    /// Returns the roll distribution statistics (Gini coefficient, share of the `top_n` biggest stakers,
+    /// active vs total rolls) of every cycle in the final PoS history, oldest first.
+    #[method(name = "get_stakers_statistics")]
+    async fn get_stakers_statistics(&self, top_n: u64) -> RpcResult<Vec<StakersStatistics>>;
+
     /// Returns operation(s) information associated to a given list of operation(s) ID(s).
     #[method(name = "get_operations")]
     async fn get_operations(&self, arg: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>>;
@@ -365,10 +553,28 @@ pub trait MassaRpc {
         arg: Vec<DatastoreEntryInput>,
     ) -> RpcResult<Vec<DatastoreEntryOutput>>;
 
+    /// Get pages of datastore keys, selected by prefix and key range.
+    /// To get the next page, set `cursor` to the last key of the current one.
+    #[method(name = "get_datastore_keys")]
+    async fn get_datastore_keys(
+        &self,
+        arg: Vec<DatastoreKeysInput>,
+    ) -> RpcResult<Vec<DatastoreKeysOutput>>;
+
     /// Get addresses.
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
+    /// Get the deferred credits of an address (e.g. roll sale refunds) whose slot is
+    /// between `start_slot` and `end_slot`, both included and optional.
+    #[method(name = "get_deferred_credits")]
+    async fn get_deferred_credits(
+        &self,
+        address: Address,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> RpcResult<AddressDeferredCredits>;
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     #[method(name = "send_operations")]
     async fn send_operations(&self, arg: Vec<OperationInput>) -> RpcResult<Vec<OperationId>>;
@@ -383,6 +589,32 @@ pub trait MassaRpc {
     async fn get_filtered_sc_output_event(&self, arg: EventFilter)
         -> RpcResult<Vec<SCOutputEvent>>;
 
+    /// Get the native coin transfers spending or crediting an address, oldest first.
+    /// Final transfers are kept for a configurable number of periods.
+    #[method(name = "get_address_transfers")]
+    async fn get_address_transfers(&self, arg: TransferFilter) -> RpcResult<Vec<AddressTransfer>>;
+
+    /// Get the native coin transfers that happened between two slots (inclusive), oldest first.
+    /// Final transfers are kept for a configurable number of periods.
+    #[method(name = "get_slot_transfers")]
+    async fn get_slot_transfers(&self, start: Slot, end: Slot) -> RpcResult<Vec<AddressTransfer>>;
+
+    /// Get the messages of the asynchronous pool matching a filter.
+    /// The changes of the candidate slots are applied on top of the final pool unless final messages are requested.
+    #[method(name = "get_async_messages")]
+    async fn get_async_messages(
+        &self,
+        arg: AsyncPoolFilter,
+    ) -> RpcResult<Vec<AsyncPoolMessageInfo>>;
+
+    /// Tell whether final events matching the filter may be missing,
+    /// because they were pruned from the node store by age or capacity.
+    #[method(name = "get_sc_output_events_truncation")]
+    async fn get_sc_output_events_truncation(
+        &self,
+        arg: EventFilter,
+    ) -> RpcResult<EventsTruncation>;
+
     /// Get OpenRPC specification.
     #[method(name = "rpc.discover")]
     async fn get_openrpc_spec(&self) -> RpcResult<Value>;
@@ -5,34 +5,66 @@ use crate::{MassaRpcServer, Private, RpcServer, StopHandle, Value, API};
 use async_trait::async_trait;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
-    address::AddressInfo,
-    block::{BlockInfo, BlockSummary},
+    address::{AddressDeferredCredits, AddressInfo, AddressProductionStats},
+    block::{
+        BlockGraphFormat, BlockInfo, BlockSummary, DiscardedBlockInfo, DiscardedBlocksFilter,
+        ExportedBlockGraph,
+    },
     config::APIConfig,
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeysInput, DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        AbiCallProfile, AddressTransfer, AsyncPoolFilter, AsyncPoolMessageInfo,
+        BytecodeValidationResponse, CacheSizes, CacheSizesUpdate, EventsTruncation,
+        ExecuteReadOnlyResponse, GasEstimationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{BootstrapSessionAudit, DbIntegrityReport, NodeStatus, PeerStoreEntry},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    pool::{
+        AdmissionStageInfo, DenunciationPoolItem, FeeRecommendationInfo, PendingOperation,
+        PoolSummary,
+    },
+    rolls::StakersStatistics,
+    versioning::MipStatusInfo,
     ListType, ScrudOperation, TimeInterval,
 };
+use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::ExecutionController;
+use massa_final_state::FinalState;
 use massa_hash::Hash;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    address::Address,
+    block::Block,
+    block_id::BlockId,
+    clique::Clique,
+    composite::PubkeySig,
+    endorsement::EndorsementId,
+    execution::{EventFilter, TransferFilter},
+    node::NodeId,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
+    stats::ThroughputBucket,
 };
-use massa_protocol_exports::{PeerId, ProtocolController};
+use massa_pool_exports::PoolController;
+use massa_protocol_exports::{PeerId, PeerRecordUpdate, ProtocolController};
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::{collections::BTreeSet, sync::Mutex};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    sync::Mutex,
+};
 use std::{
     fs::{remove_file, OpenOptions},
     sync::Condvar,
@@ -46,6 +78,9 @@ impl API<Private> {
         api_settings: APIConfig,
         stop_cv: Arc<(Mutex<bool>, Condvar)>,
         node_wallet: Arc<RwLock<Wallet>>,
+        final_state: Arc<RwLock<FinalState>>,
+        consensus_controller: Box<dyn ConsensusController>,
+        pool_controller: Box<dyn PoolController>,
     ) -> Self {
         API(Private {
             protocol_controller,
@@ -53,6 +88,9 @@ impl API<Private> {
             api_settings,
             stop_cv,
             node_wallet,
+            final_state,
+            consensus_controller,
+            pool_controller,
         })
     }
 }
@@ -125,6 +163,34 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<_>()
     }
 
+    async fn execute_read_only_call_batch(
+        &self,
+        _reqs: Vec<ReadOnlyCall>,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
+        crate::wrong_api::<_>()
+    }
+
+    async fn estimate_gas_bytecode(
+        &self,
+        _reqs: Vec<ReadOnlyBytecodeExecution>,
+    ) -> RpcResult<Vec<GasEstimationResponse>> {
+        crate::wrong_api::<_>()
+    }
+
+    async fn estimate_gas_call(
+        &self,
+        _reqs: Vec<ReadOnlyCall>,
+    ) -> RpcResult<Vec<GasEstimationResponse>> {
+        crate::wrong_api::<_>()
+    }
+
+    async fn validate_bytecode(
+        &self,
+        _bytecodes: Vec<Vec<u8>>,
+    ) -> RpcResult<Vec<BytecodeValidationResponse>> {
+        crate::wrong_api::<_>()
+    }
+
     async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
         let node_wallet = self.0.node_wallet.clone();
 
@@ -183,6 +249,46 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
+    async fn node_get_peer_store(&self) -> RpcResult<Vec<PeerStoreEntry>> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        let records = protocol_controller
+            .get_peer_records()
+            .map_err(ApiError::ProtocolError)?;
+        Ok(records.into_iter().map(PeerStoreEntry::from).collect())
+    }
+
+    async fn node_set_peer_scores(&self, scores: Vec<(NodeId, i64)>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        let updates = scores
+            .into_iter()
+            .map(|(id, score)| {
+                (
+                    PeerId::from_public_key(id.get_public_key()),
+                    PeerRecordUpdate::SetScore(score),
+                )
+            })
+            .collect();
+        protocol_controller
+            .update_peer_records(updates)
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn node_forget_peers(&self, ids: Vec<NodeId>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        let updates = ids
+            .into_iter()
+            .map(|id| {
+                (
+                    PeerId::from_public_key(id.get_public_key()),
+                    PeerRecordUpdate::Forget,
+                )
+            })
+            .collect();
+        protocol_controller
+            .update_peer_records(updates)
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
     async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -195,10 +301,201 @@ impl MassaRpcServer for API<Private> {
         );
     }
 
+    async fn node_bootstrap_audit_log(
+        &self,
+        count: Option<usize>,
+    ) -> RpcResult<Vec<BootstrapSessionAudit>> {
+        let Some(path) = self.0.api_settings.bootstrap_audit_log_path.clone() else {
+            return Err(
+                ApiError::BadRequest("the bootstrap audit log is disabled".to_string()).into(),
+            );
+        };
+        read_bootstrap_audit_log(path, count.unwrap_or(DEFAULT_BOOTSTRAP_AUDIT_ENTRIES))
+    }
+
+    async fn node_export_block_graph(
+        &self,
+        format: Option<BlockGraphFormat>,
+    ) -> RpcResult<ExportedBlockGraph> {
+        let snapshot = self
+            .0
+            .consensus_controller
+            .get_block_graph_snapshot(None, None);
+        Ok(match format.unwrap_or_default() {
+            BlockGraphFormat::Json => ExportedBlockGraph::Json(snapshot),
+            BlockGraphFormat::Dot => ExportedBlockGraph::Dot(snapshot.to_dot()),
+        })
+    }
+
+    async fn node_get_discarded_blocks(
+        &self,
+        filter: Option<DiscardedBlocksFilter>,
+    ) -> RpcResult<Vec<DiscardedBlockInfo>> {
+        let filter = filter.unwrap_or_default();
+        Ok(self
+            .0
+            .consensus_controller
+            .get_discarded_blocks(filter.reason, filter.creator))
+    }
+
+    async fn node_verify_db_integrity(&self) -> RpcResult<DbIntegrityReport> {
+        let final_state = self.0.final_state.clone();
+        // the whole final state is read: do not block the runtime meanwhile
+        let report = tokio::task::spawn_blocking(move || final_state.read().verify_db_integrity())
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+        Ok(report.into())
+    }
+
+    async fn node_trigger_compaction(&self, cf: String) -> RpcResult<()> {
+        // the compaction runs in the background: the database is only locked to start it
+        let db = self.0.final_state.read().db.clone();
+        let result = db.read().trigger_compaction(&cf);
+        result.map_err(|e| ApiError::BadRequest(e.to_string()).into())
+    }
+
+    async fn node_get_production_stats(
+        &self,
+        addresses: Vec<Address>,
+        cycle_count: u64,
+    ) -> RpcResult<Vec<AddressProductionStats>> {
+        let addresses: PreHashSet<Address> = addresses.into_iter().collect();
+        let history = self
+            .0
+            .final_state
+            .read()
+            .pos_state
+            .get_production_stats_history(&addresses, cycle_count);
+        Ok(history
+            .into_iter()
+            .flat_map(|(cycle, stats)| {
+                stats
+                    .into_iter()
+                    .map(move |(address, stats)| AddressProductionStats {
+                        address,
+                        cycle,
+                        block_success_count: stats.block_success_count,
+                        block_failure_count: stats.block_failure_count,
+                    })
+            })
+            .collect())
+    }
+
+    async fn node_get_abi_profile(&self, slot_count: usize) -> RpcResult<Vec<AbiCallProfile>> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_abi_call_stats(slot_count)
+            .into_iter()
+            .map(AbiCallProfile::from)
+            .collect())
+    }
+
+    async fn node_get_cache_sizes(&self) -> RpcResult<CacheSizes> {
+        Ok(self.0.execution_controller.get_cache_sizes().into())
+    }
+
+    async fn node_set_cache_sizes(&self, update: CacheSizesUpdate) -> RpcResult<CacheSizes> {
+        let execution_controller = &self.0.execution_controller;
+        let sizes = update.apply(execution_controller.get_cache_sizes());
+        execution_controller
+            .set_cache_sizes(sizes)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        Ok(sizes.into())
+    }
+
+    async fn node_get_pending_operations(
+        &self,
+        address: Option<Address>,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<PendingOperation>> {
+        let max_count = self.0.api_settings.max_arguments as usize;
+        let (offset, limit) = match page_request {
+            Some(PageRequest { limit, offset }) => {
+                if limit > max_count {
+                    return Err(ApiError::BadRequest(format!(
+                        "too many operations requested, the maximum is {}",
+                        max_count
+                    ))
+                    .into());
+                }
+                (offset.saturating_mul(limit), limit)
+            }
+            None => (0, max_count),
+        };
+        Ok(self
+            .0
+            .pool_controller
+            .get_pending_operations(address, offset, limit)
+            .into_iter()
+            .map(PendingOperation::from)
+            .collect())
+    }
+
+    async fn node_get_operation_pool_summary(&self, top_senders: usize) -> RpcResult<PoolSummary> {
+        let max_count = self.0.api_settings.max_arguments as usize;
+        if top_senders > max_count {
+            return Err(ApiError::BadRequest(format!(
+                "too many top senders requested, the maximum is {}",
+                max_count
+            ))
+            .into());
+        }
+        Ok(self
+            .0
+            .pool_controller
+            .get_operation_pool_summary(top_senders)
+            .into())
+    }
+
+    async fn node_get_denunciation_pool(&self) -> RpcResult<Vec<DenunciationPoolItem>> {
+        Ok(self
+            .0
+            .pool_controller
+            .get_denunciation_pool_entries()
+            .into_iter()
+            .map(DenunciationPoolItem::from)
+            .collect())
+    }
+
+    async fn node_get_operation_admission_stats(&self) -> RpcResult<Vec<AdmissionStageInfo>> {
+        Ok(self
+            .0
+            .pool_controller
+            .get_operation_admission_stats()
+            .into_iter()
+            .map(AdmissionStageInfo::from)
+            .collect())
+    }
+
+    async fn get_fee_recommendation(&self) -> RpcResult<FeeRecommendationInfo> {
+        crate::wrong_api::<FeeRecommendationInfo>()
+    }
+
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         crate::wrong_api::<NodeStatus>()
     }
 
+    async fn get_mip_status(&self) -> RpcResult<Vec<MipStatusInfo>> {
+        crate::wrong_api::<Vec<MipStatusInfo>>()
+    }
+
+    async fn get_versioning_announcements(
+        &self,
+        _: Vec<MipStatusInfo>,
+    ) -> RpcResult<Vec<MipStatusInfo>> {
+        crate::wrong_api::<Vec<MipStatusInfo>>()
+    }
+
+    async fn get_throughput_history(
+        &self,
+        _: MassaTime,
+        _: MassaTime,
+    ) -> RpcResult<Vec<ThroughputBucket>> {
+        crate::wrong_api::<Vec<ThroughputBucket>>()
+    }
+
     async fn get_cliques(&self) -> RpcResult<Vec<Clique>> {
         crate::wrong_api::<Vec<Clique>>()
     }
@@ -207,6 +504,10 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<PagedVec<(Address, u64)>>()
     }
 
+    async fn get_stakers_statistics(&self, _: u64) -> RpcResult<Vec<StakersStatistics>> {
+        crate::wrong_api::<Vec<StakersStatistics>>()
+    }
+
     async fn get_operations(&self, _: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>> {
         crate::wrong_api::<Vec<OperationInfo>>()
     }
@@ -234,10 +535,26 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api()
     }
 
+    async fn get_datastore_keys(
+        &self,
+        _: Vec<DatastoreKeysInput>,
+    ) -> RpcResult<Vec<DatastoreKeysOutput>> {
+        crate::wrong_api()
+    }
+
     async fn get_addresses(&self, _: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    async fn get_deferred_credits(
+        &self,
+        _: Address,
+        _: Option<Slot>,
+        _: Option<Slot>,
+    ) -> RpcResult<AddressDeferredCredits> {
+        crate::wrong_api::<AddressDeferredCredits>()
+    }
+
     async fn send_operations(&self, _: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         crate::wrong_api::<Vec<OperationId>>()
     }
@@ -246,6 +563,22 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<SCOutputEvent>>()
     }
 
+    async fn get_address_transfers(&self, _: TransferFilter) -> RpcResult<Vec<AddressTransfer>> {
+        crate::wrong_api::<Vec<AddressTransfer>>()
+    }
+
+    async fn get_slot_transfers(&self, _: Slot, _: Slot) -> RpcResult<Vec<AddressTransfer>> {
+        crate::wrong_api::<Vec<AddressTransfer>>()
+    }
+
+    async fn get_async_messages(&self, _: AsyncPoolFilter) -> RpcResult<Vec<AsyncPoolMessageInfo>> {
+        crate::wrong_api::<Vec<AsyncPoolMessageInfo>>()
+    }
+
+    async fn get_sc_output_events_truncation(&self, _: EventFilter) -> RpcResult<EventsTruncation> {
+        crate::wrong_api::<EventsTruncation>()
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -359,13 +692,23 @@ fn run_scrud_operation(
         ScrudOperation::Create => get_file_len(bootstrap_list_file.clone(), &list_type, true)
             .and_then(|length| {
                 if length == 0 {
-                    write_ips_to_jsonfile(bootstrap_list_file, BTreeSet::from_iter(ips), &list_type)
+                    write_ips_to_jsonfile(
+                        bootstrap_list_file,
+                        BTreeSet::from_iter(ips),
+                        Vec::new(),
+                        &list_type,
+                    )
                 } else {
-                    read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
-                        .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
+                    read_entries_from_jsonfile(bootstrap_list_file.clone(), &list_type)
+                        .map(split_list_entries)
+                        .and_then(|(mut list_ips, list_ranges)| {
                             list_ips.extend(ips);
-                            write_ips_to_jsonfile(bootstrap_list_file, list_ips, &list_type)
+                            write_ips_to_jsonfile(
+                                bootstrap_list_file,
+                                list_ips,
+                                list_ranges,
+                                &list_type,
+                            )
                         })
                 }
             }),
@@ -378,9 +721,9 @@ fn run_scrud_operation(
                     ))
                     .into())
                 } else {
-                    read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
-                        .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
+                    read_entries_from_jsonfile(bootstrap_list_file.clone(), &list_type)
+                        .map(split_list_entries)
+                        .and_then(|(mut list_ips, list_ranges)| {
                             if list_ips.is_empty() {
                                 return Err(ApiError::InternalServerError(format!(
                                     "failed to execute delete operation, bootstrap {} is empty",
@@ -391,7 +734,12 @@ fn run_scrud_operation(
                             ips.into_iter().for_each(|ip| {
                                 list_ips.remove(&ip);
                             });
-                            write_ips_to_jsonfile(bootstrap_list_file, list_ips, &list_type)
+                            write_ips_to_jsonfile(
+                                bootstrap_list_file,
+                                list_ips,
+                                list_ranges,
+                                &list_type,
+                            )
                         })
                 }
             }),
@@ -432,10 +780,20 @@ fn get_file_len(
 }
 
 /// Read bootstrap list IP(s) from json file
+/// The IP ranges of the list are left out
 fn read_ips_from_jsonfile(
     bootstrap_list_file: PathBuf,
     list_type: &ListType,
 ) -> RpcResult<Vec<IpAddr>> {
+    read_entries_from_jsonfile(bootstrap_list_file, list_type)
+        .map(|entries| split_list_entries(entries).0.into_iter().collect())
+}
+
+/// Read bootstrap list entries from json file: IP addresses and IP ranges in CIDR notation
+fn read_entries_from_jsonfile(
+    bootstrap_list_file: PathBuf,
+    list_type: &ListType,
+) -> RpcResult<Vec<String>> {
     std::fs::read_to_string(bootstrap_list_file)
         .map_err(|e| {
             ApiError::InternalServerError(format!(
@@ -455,12 +813,29 @@ fn read_ips_from_jsonfile(
         })
 }
 
-/// Write bootstrap list IP(s) from json file
+/// Split bootstrap list entries into IP addresses and IP ranges
+fn split_list_entries(entries: Vec<String>) -> (BTreeSet<IpAddr>, Vec<String>) {
+    let mut ips = BTreeSet::new();
+    let mut ranges = Vec::new();
+    for entry in entries {
+        match entry.parse::<IpAddr>() {
+            Ok(ip) => {
+                ips.insert(ip);
+            }
+            Err(_) => ranges.push(entry),
+        }
+    }
+    (ips, ranges)
+}
+
+/// Write bootstrap list IP(s) and IP ranges to json file
 fn write_ips_to_jsonfile(
     bootstrap_list_file: PathBuf,
     ips: BTreeSet<IpAddr>,
+    ranges: Vec<String>,
     list_type: &ListType,
 ) -> RpcResult<()> {
+    let entries: Vec<String> = ips.iter().map(|ip| ip.to_string()).chain(ranges).collect();
     OpenOptions::new()
         .write(true)
         .create(true)
@@ -474,7 +849,7 @@ fn write_ips_to_jsonfile(
             .into()
         })
         .and_then(|file| {
-            serde_json::to_writer_pretty(file, &ips).map_err(|e| {
+            serde_json::to_writer_pretty(file, &entries).map_err(|e| {
                 ApiError::InternalServerError(format!(
                     "failed to write bootstrap {} configuration file: {}",
                     list_type, e
@@ -483,3 +858,45 @@ fn write_ips_to_jsonfile(
             })
         })
 }
+
+/// Number of entries of the bootstrap audit log returned if none is requested
+const DEFAULT_BOOTSTRAP_AUDIT_ENTRIES: usize = 100;
+
+/// Read the last `count` entries of the bootstrap audit log,
+/// from the rotated file (with a `.1` extension) then from the current one
+fn read_bootstrap_audit_log(
+    audit_log_file: PathBuf,
+    count: usize,
+) -> RpcResult<Vec<BootstrapSessionAudit>> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let mut rotated_file = audit_log_file.clone().into_os_string();
+    rotated_file.push(".1");
+    let mut entries: VecDeque<BootstrapSessionAudit> = VecDeque::with_capacity(count);
+    for file in [PathBuf::from(rotated_file), audit_log_file] {
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "failed to read bootstrap audit log {}: {}",
+                    file.display(),
+                    e
+                ))
+                .into())
+            }
+        };
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            // a line may be partially written by the bootstrap server
+            let Ok(entry) = serde_json::from_str(line) else {
+                continue;
+            };
+            if entries.len() == count {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+    Ok(entries.into())
+}
@@ -6,23 +6,41 @@ use async_trait::async_trait;
 use itertools::{izip, Itertools};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
-    address::AddressInfo,
-    block::{BlockInfo, BlockInfoContent, BlockSummary},
+    address::{AddressDeferredCredits, AddressInfo, AddressProductionStats},
+    block::{
+        BlockGraphFormat, BlockInfo, BlockInfoContent, BlockSummary, DiscardedBlockInfo,
+        DiscardedBlocksFilter, ExportedBlockGraph,
+    },
     config::APIConfig,
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeysInput, DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
-    node::NodeStatus,
+    execution::{
+        AbiCallProfile, AddressTransfer, AsyncPoolFilter, AsyncPoolMessageInfo,
+        BytecodeValidationResponse, CacheSizes, CacheSizesUpdate, EventsTruncation,
+        ExecuteReadOnlyResponse, GasEstimationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+        ReadOnlyResult,
+    },
+    node::{BootstrapSessionAudit, DbIntegrityReport, NodeStatus, PeerStoreEntry},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    pool::{
+        AdmissionStageInfo, DenunciationPoolItem, FeeRecommendationInfo, PendingOperation,
+        PoolSummary,
+    },
+    rolls::StakersStatistics,
     slot::SlotAmount,
+    versioning::MipStatusInfo,
     TimeInterval,
 };
 use massa_consensus_exports::block_status::DiscardReason;
 use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::{
-    ExecutionController, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ExecutionController, ExecutionError, ExecutionQueryError, ExecutionQueryRequest,
+    ExecutionQueryRequestItem, ExecutionQueryResponseItem, ExecutionStackElement,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
 };
 use massa_models::{
     address::Address,
@@ -36,7 +54,7 @@ use massa_models::{
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
     error::ModelsError,
-    execution::EventFilter,
+    execution::{EventFilter, TransferFilter},
     node::NodeId,
     operation::OperationDeserializer,
     operation::OperationId,
@@ -45,6 +63,7 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShareDeserializer,
     slot::{IndexedSlot, Slot},
+    stats::ThroughputBucket,
     timeslots,
     timeslots::{get_latest_block_slot_at_timestamp, time_range_to_slot_range},
     version::Version,
@@ -57,7 +76,9 @@ use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_versioning::versioning_factory::FactoryStrategy;
 use massa_versioning::{
-    keypair_factory::KeyPairFactory, versioning::MipStore, versioning_factory::VersioningFactory,
+    keypair_factory::KeyPairFactory,
+    versioning::{get_versioning_announcements, MipComponent, MipStatus, MipStore},
+    versioning_factory::VersioningFactory,
 };
 use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
@@ -91,6 +112,189 @@ impl API<Public> {
             keypair_factory: KeyPairFactory { mip_store },
         })
     }
+
+    /// Returns the given caller address of a read-only execution, or a random one if none is given
+    fn read_only_caller_address(&self, address: Option<Address>) -> RpcResult<Address> {
+        if let Some(addr) = address {
+            return Ok(addr);
+        }
+        let now = MassaTime::now().map_err(|e| {
+            ApiError::InconsistencyError(format!("Unable to get current time: {}", e))
+        })?;
+        let keypair = self
+            .0
+            .keypair_factory
+            .create(&(), FactoryStrategy::At(now))
+            .map_err(ApiError::from)?;
+        Ok(Address::from_public_key(&keypair.get_public_key()))
+    }
+
+    /// Translates the outcome of a read-only execution into an API response
+    fn read_only_response(
+        result: Result<ReadOnlyExecutionOutput, ExecutionError>,
+    ) -> ExecuteReadOnlyResponse {
+        ExecuteReadOnlyResponse {
+            executed_at: result
+                .as_ref()
+                .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
+            result: result.as_ref().map_or_else(
+                |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
+                |res| ReadOnlyResult::Ok(res.call_result.clone()),
+            ),
+            gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
+            output_events: result
+                .as_ref()
+                .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
+            trace: result
+                .as_ref()
+                .ok()
+                .and_then(|v| v.trace.clone())
+                .map(|trace| trace.into_iter().map(Into::into).collect()),
+            state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
+        }
+    }
+
+    /// Estimates the gas of a read-only execution request
+    fn estimate_gas(&self, req: ReadOnlyExecutionRequest) -> GasEstimationResponse {
+        match self.0.execution_controller.estimate_gas(req) {
+            Ok(estimation) => GasEstimationResponse {
+                error: None,
+                gas_cost: estimation.gas_cost,
+                required_gas: estimation.required_gas,
+                estimated_max_gas: estimation.estimated_max_gas,
+            },
+            Err(err) => GasEstimationResponse {
+                error: Some(format!("readonly execution failed: {}", err)),
+                gas_cost: 0,
+                required_gas: 0,
+                estimated_max_gas: 0,
+            },
+        }
+    }
+
+    /// Translates a read-only bytecode execution into an execution request
+    fn read_only_bytecode_request(
+        &self,
+        req: ReadOnlyBytecodeExecution,
+    ) -> RpcResult<ReadOnlyExecutionRequest> {
+        let ReadOnlyBytecodeExecution {
+            max_gas,
+            address,
+            bytecode,
+            operation_datastore,
+            is_final,
+            fee,
+            historical_slot,
+            state_overrides,
+            trace,
+        } = req;
+
+        let address = self.read_only_caller_address(address)?;
+
+        let op_datastore = match operation_datastore {
+            Some(v) => {
+                let deserializer = DatastoreDeserializer::new(
+                    self.0.api_settings.max_op_datastore_entry_count,
+                    self.0.api_settings.max_op_datastore_key_length,
+                    self.0.api_settings.max_op_datastore_value_length,
+                );
+                match deserializer.deserialize::<DeserializeError>(&v) {
+                    Ok((_, deserialized)) => Some(deserialized),
+                    Err(e) => {
+                        return Err(ApiError::InconsistencyError(format!(
+                            "Operation datastore error: {}",
+                            e
+                        ))
+                        .into())
+                    }
+                }
+            }
+            None => None,
+        };
+
+        // TODO:
+        // * set a maximum gas value for read-only executions to prevent attacks
+        // * stop mapping request and result, reuse execution's structures
+        // * remove async stuff
+
+        // translate request
+        Ok(ReadOnlyExecutionRequest {
+            max_gas,
+            target: ReadOnlyExecutionTarget::BytecodeExecution(bytecode),
+            call_stack: vec![ExecutionStackElement {
+                address,
+                coins: Default::default(),
+                owned_addresses: vec![address],
+                operation_datastore: op_datastore,
+            }],
+            is_final,
+            coins: None,
+            fee,
+            historical_slot,
+            state_overrides: state_overrides
+                .into_iter()
+                .map(|state_override| (state_override.address, state_override.into()))
+                .collect(),
+            trace,
+        })
+    }
+
+    /// Translates a read-only call into an execution request
+    fn read_only_call_request(&self, req: ReadOnlyCall) -> RpcResult<ReadOnlyExecutionRequest> {
+        let ReadOnlyCall {
+            max_gas,
+            target_address,
+            target_function,
+            parameter,
+            caller_address,
+            is_final,
+            coins,
+            fee,
+            historical_slot,
+            state_overrides,
+            trace,
+        } = req;
+
+        let caller_address = self.read_only_caller_address(caller_address)?;
+
+        // TODO:
+        // * set a maximum gas value for read-only executions to prevent attacks
+        // * stop mapping request and result, reuse execution's structures
+        // * remove async stuff
+
+        // translate request
+        Ok(ReadOnlyExecutionRequest {
+            max_gas,
+            target: ReadOnlyExecutionTarget::FunctionCall {
+                target_func: target_function,
+                target_addr: target_address,
+                parameter,
+            },
+            call_stack: vec![
+                ExecutionStackElement {
+                    address: caller_address,
+                    coins: Default::default(),
+                    owned_addresses: vec![caller_address],
+                    operation_datastore: None, // should always be None
+                },
+                ExecutionStackElement {
+                    address: target_address,
+                    coins: coins.unwrap_or(Amount::default()),
+                    owned_addresses: vec![target_address],
+                    operation_datastore: None, // should always be None
+                },
+            ],
+            is_final,
+            coins,
+            fee,
+            historical_slot,
+            state_overrides: state_overrides
+                .into_iter()
+                .map(|state_override| (state_override.address, state_override.into()))
+                .collect(),
+            trace,
+        })
+    }
 }
 
 #[async_trait]
@@ -128,90 +332,14 @@ impl MassaRpcServer for API<Public> {
         }
 
         let mut res: Vec<ExecuteReadOnlyResponse> = Vec::with_capacity(reqs.len());
-        for ReadOnlyBytecodeExecution {
-            max_gas,
-            address,
-            bytecode,
-            operation_datastore,
-            is_final,
-            fee,
-        } in reqs
-        {
-            let address = if let Some(addr) = address {
-                addr
-            } else {
-                let now = MassaTime::now().map_err(|e| {
-                    ApiError::InconsistencyError(format!("Unable to get current time: {}", e))
-                })?;
-                let keypair = self
-                    .0
-                    .keypair_factory
-                    .create(&(), FactoryStrategy::At(now))
-                    .map_err(ApiError::from)?;
-                Address::from_public_key(&keypair.get_public_key())
-            };
-
-            let op_datastore = match operation_datastore {
-                Some(v) => {
-                    let deserializer = DatastoreDeserializer::new(
-                        self.0.api_settings.max_op_datastore_entry_count,
-                        self.0.api_settings.max_op_datastore_key_length,
-                        self.0.api_settings.max_op_datastore_value_length,
-                    );
-                    match deserializer.deserialize::<DeserializeError>(&v) {
-                        Ok((_, deserialized)) => Some(deserialized),
-                        Err(e) => {
-                            return Err(ApiError::InconsistencyError(format!(
-                                "Operation datastore error: {}",
-                                e
-                            ))
-                            .into())
-                        }
-                    }
-                }
-                None => None,
-            };
-
-            // TODO:
-            // * set a maximum gas value for read-only executions to prevent attacks
-            // * stop mapping request and result, reuse execution's structures
-            // * remove async stuff
-
-            // translate request
-            let req = ReadOnlyExecutionRequest {
-                max_gas,
-                target: ReadOnlyExecutionTarget::BytecodeExecution(bytecode),
-                call_stack: vec![ExecutionStackElement {
-                    address,
-                    coins: Default::default(),
-                    owned_addresses: vec![address],
-                    operation_datastore: op_datastore,
-                }],
-                is_final,
-                coins: None,
-                fee,
-            };
+        for req in reqs {
+            let req = self.read_only_bytecode_request(req)?;
 
             // run
             let result = self.0.execution_controller.execute_readonly_request(req);
 
             // map result
-            let result = ExecuteReadOnlyResponse {
-                executed_at: result
-                    .as_ref()
-                    .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
-                result: result.as_ref().map_or_else(
-                    |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
-                    |res| ReadOnlyResult::Ok(res.call_result.clone()),
-                ),
-                gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
-                output_events: result
-                    .as_ref()
-                    .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
-                state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
-            };
-
-            res.push(result);
+            res.push(Self::read_only_response(result));
         }
 
         // return result
@@ -228,89 +356,88 @@ impl MassaRpcServer for API<Public> {
         }
 
         let mut res: Vec<ExecuteReadOnlyResponse> = Vec::with_capacity(reqs.len());
-        for ReadOnlyCall {
-            max_gas,
-            target_address,
-            target_function,
-            parameter,
-            caller_address,
-            is_final,
-            coins,
-            fee,
-        } in reqs
-        {
-            let caller_address = if let Some(addr) = caller_address {
-                addr
-            } else {
-                let now = MassaTime::now().map_err(|e| {
-                    ApiError::InconsistencyError(format!("Unable to get current time: {}", e))
-                })?;
-                let keypair = self
-                    .0
-                    .keypair_factory
-                    .create(&(), FactoryStrategy::At(now))
-                    .map_err(ApiError::from)?;
-                Address::from_public_key(&keypair.get_public_key())
-            };
-
-            // TODO:
-            // * set a maximum gas value for read-only executions to prevent attacks
-            // * stop mapping request and result, reuse execution's structures
-            // * remove async stuff
-
-            // translate request
-            let req = ReadOnlyExecutionRequest {
-                max_gas,
-                target: ReadOnlyExecutionTarget::FunctionCall {
-                    target_func: target_function,
-                    target_addr: target_address,
-                    parameter,
-                },
-                call_stack: vec![
-                    ExecutionStackElement {
-                        address: caller_address,
-                        coins: Default::default(),
-                        owned_addresses: vec![caller_address],
-                        operation_datastore: None, // should always be None
-                    },
-                    ExecutionStackElement {
-                        address: target_address,
-                        coins: coins.unwrap_or(Amount::default()),
-                        owned_addresses: vec![target_address],
-                        operation_datastore: None, // should always be None
-                    },
-                ],
-                is_final,
-                coins,
-                fee,
-            };
+        for req in reqs {
+            let req = self.read_only_call_request(req)?;
 
             // run
             let result = self.0.execution_controller.execute_readonly_request(req);
 
             // map result
-            let result = ExecuteReadOnlyResponse {
-                executed_at: result
-                    .as_ref()
-                    .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
-                result: result.as_ref().map_or_else(
-                    |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
-                    |res| ReadOnlyResult::Ok(res.call_result.clone()),
-                ),
-                gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
-                output_events: result
-                    .as_ref()
-                    .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
-                state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
-            };
-
-            res.push(result);
+            res.push(Self::read_only_response(result));
         }
 
         // return result
         Ok(res)
     }
 
+    async fn execute_read_only_call_batch(
+        &self,
+        reqs: Vec<ReadOnlyCall>,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
+        if reqs.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let reqs = reqs
+            .into_iter()
+            .map(|req| self.read_only_call_request(req))
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        // run all the calls in a single round trip to the execution worker
+        let results = self
+            .0
+            .execution_controller
+            .execute_readonly_batch(reqs)
+            .map_err(ApiError::from)?;
+
+        Ok(results.into_iter().map(Self::read_only_response).collect())
+    }
+
+    async fn estimate_gas_bytecode(
+        &self,
+        reqs: Vec<ReadOnlyBytecodeExecution>,
+    ) -> RpcResult<Vec<GasEstimationResponse>> {
+        if reqs.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        reqs.into_iter()
+            .map(|req| Ok(self.estimate_gas(self.read_only_bytecode_request(req)?)))
+            .collect()
+    }
+
+    async fn estimate_gas_call(
+        &self,
+        reqs: Vec<ReadOnlyCall>,
+    ) -> RpcResult<Vec<GasEstimationResponse>> {
+        if reqs.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        reqs.into_iter()
+            .map(|req| Ok(self.estimate_gas(self.read_only_call_request(req)?)))
+            .collect()
+    }
+
+    async fn validate_bytecode(
+        &self,
+        bytecodes: Vec<Vec<u8>>,
+    ) -> RpcResult<Vec<BytecodeValidationResponse>> {
+        if bytecodes.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        Ok(bytecodes
+            .iter()
+            .map(|bytecode| {
+                self.0
+                    .execution_controller
+                    .validate_bytecode(bytecode)
+                    .into()
+            })
+            .collect())
+    }
+
     async fn remove_staking_addresses(&self, _: Vec<Address>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -335,6 +462,91 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_get_peer_store(&self) -> RpcResult<Vec<PeerStoreEntry>> {
+        crate::wrong_api::<Vec<PeerStoreEntry>>()
+    }
+
+    async fn node_set_peer_scores(&self, _: Vec<(NodeId, i64)>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_forget_peers(&self, _: Vec<NodeId>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_bootstrap_audit_log(
+        &self,
+        _: Option<usize>,
+    ) -> RpcResult<Vec<BootstrapSessionAudit>> {
+        crate::wrong_api::<Vec<BootstrapSessionAudit>>()
+    }
+
+    async fn node_export_block_graph(
+        &self,
+        _: Option<BlockGraphFormat>,
+    ) -> RpcResult<ExportedBlockGraph> {
+        crate::wrong_api::<ExportedBlockGraph>()
+    }
+
+    async fn node_get_discarded_blocks(
+        &self,
+        _: Option<DiscardedBlocksFilter>,
+    ) -> RpcResult<Vec<DiscardedBlockInfo>> {
+        crate::wrong_api::<Vec<DiscardedBlockInfo>>()
+    }
+
+    async fn node_verify_db_integrity(&self) -> RpcResult<DbIntegrityReport> {
+        crate::wrong_api::<DbIntegrityReport>()
+    }
+
+    async fn node_trigger_compaction(&self, _: String) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_get_production_stats(
+        &self,
+        _: Vec<Address>,
+        _: u64,
+    ) -> RpcResult<Vec<AddressProductionStats>> {
+        crate::wrong_api::<Vec<AddressProductionStats>>()
+    }
+
+    async fn node_get_abi_profile(&self, _: usize) -> RpcResult<Vec<AbiCallProfile>> {
+        crate::wrong_api::<Vec<AbiCallProfile>>()
+    }
+
+    async fn node_get_cache_sizes(&self) -> RpcResult<CacheSizes> {
+        crate::wrong_api::<CacheSizes>()
+    }
+
+    async fn node_set_cache_sizes(&self, _: CacheSizesUpdate) -> RpcResult<CacheSizes> {
+        crate::wrong_api::<CacheSizes>()
+    }
+
+    async fn node_get_pending_operations(
+        &self,
+        _: Option<Address>,
+        _: Option<PageRequest>,
+    ) -> RpcResult<Vec<PendingOperation>> {
+        crate::wrong_api::<Vec<PendingOperation>>()
+    }
+
+    async fn node_get_operation_pool_summary(&self, _: usize) -> RpcResult<PoolSummary> {
+        crate::wrong_api::<PoolSummary>()
+    }
+
+    async fn node_get_denunciation_pool(&self) -> RpcResult<Vec<DenunciationPoolItem>> {
+        crate::wrong_api::<Vec<DenunciationPoolItem>>()
+    }
+
+    async fn node_get_operation_admission_stats(&self) -> RpcResult<Vec<AdmissionStageInfo>> {
+        crate::wrong_api::<Vec<AdmissionStageInfo>>()
+    }
+
+    async fn get_fee_recommendation(&self) -> RpcResult<FeeRecommendationInfo> {
+        Ok(self.0.pool_command_sender.get_fee_recommendation().into())
+    }
+
     /// get status
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         let version = self.0.version;
@@ -359,6 +571,7 @@ impl MassaRpcServer for API<Public> {
         };
 
         let execution_stats = self.0.execution_controller.get_stats();
+        let disk_usage = self.0.execution_controller.get_disk_usage_forecast();
         let consensus_stats_result = self.0.consensus_controller.get_stats();
         let consensus_stats = match consensus_stats_result {
             Ok(consensus_stats) => consensus_stats,
@@ -374,6 +587,7 @@ impl MassaRpcServer for API<Public> {
             self.0.pool_command_sender.get_operation_count(),
             self.0.pool_command_sender.get_endorsement_count(),
         );
+        let endorsement_pool_stats = self.0.pool_command_sender.get_endorsement_pool_stats();
 
         let next_slot_result = last_slot
             .unwrap_or_else(|| Slot::new(0, 0))
@@ -428,7 +642,9 @@ impl MassaRpcServer for API<Public> {
 
         Ok(NodeStatus {
             node_id,
-            node_ip: protocol_config.routable_ip,
+            node_ip: protocol_config
+                .routable_ip
+                .or(protocol_config.routable_ipv6.map(IpAddr::V6)),
             version,
             current_time: now,
             current_cycle_time,
@@ -437,14 +653,58 @@ impl MassaRpcServer for API<Public> {
             last_slot,
             next_slot,
             execution_stats,
+            disk_usage,
             consensus_stats,
             network_stats,
             pool_stats,
+            endorsement_pool_stats,
             config,
             current_cycle,
         })
     }
 
+    async fn get_mip_status(&self) -> RpcResult<Vec<MipStatusInfo>> {
+        Ok(self
+            .0
+            .keypair_factory
+            .mip_store
+            .get_mip_status_with_votes()
+            .into_iter()
+            .map(MipStatusInfo::from)
+            .collect())
+    }
+
+    async fn get_versioning_announcements(
+        &self,
+        previous: Vec<MipStatusInfo>,
+    ) -> RpcResult<Vec<MipStatusInfo>> {
+        if previous.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        let previous = previous
+            .into_iter()
+            .map(MipStatus::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ApiError::BadRequest)?;
+        let current = self.0.keypair_factory.mip_store.get_mip_status_with_votes();
+        Ok(get_versioning_announcements(&previous, &current)
+            .into_iter()
+            .map(MipStatusInfo::from)
+            .collect())
+    }
+
+    /// get final operations throughput history
+    async fn get_throughput_history(
+        &self,
+        window: MassaTime,
+        resolution: MassaTime,
+    ) -> RpcResult<Vec<ThroughputBucket>> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_throughput_history(window, resolution))
+    }
+
     /// get cliques
     async fn get_cliques(&self) -> RpcResult<Vec<Clique>> {
         Ok(self.0.consensus_controller.get_cliques())
@@ -493,6 +753,35 @@ impl MassaRpcServer for API<Public> {
         Ok(paged_vec)
     }
 
+    /// get stakers statistics
+    async fn get_stakers_statistics(&self, top_n: u64) -> RpcResult<Vec<StakersStatistics>> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_staker_distribution_history(usize::try_from(top_n).unwrap_or(usize::MAX))
+            .into_iter()
+            .map(|distribution| {
+                let ratio = |rolls: u64| {
+                    if distribution.total_rolls == 0 {
+                        0.0
+                    } else {
+                        rolls as f64 / distribution.total_rolls as f64
+                    }
+                };
+                StakersStatistics {
+                    cycle: distribution.cycle,
+                    staker_count: distribution.staker_count,
+                    total_rolls: distribution.total_rolls,
+                    active_rolls: distribution.active_rolls,
+                    active_roll_ratio: distribution.active_rolls.map(ratio),
+                    gini_coefficient: distribution.gini_coefficient,
+                    top_n,
+                    top_stakers_share: ratio(distribution.top_stakers_rolls),
+                }
+            })
+            .collect())
+    }
+
     /// get operations
     async fn get_operations(
         &self,
@@ -535,22 +824,24 @@ impl MassaRpcServer for API<Public> {
         // ask pool whether it carries the operations
         let in_pool = self.0.pool_command_sender.contains_operations(&ops);
 
-        let op_exec_statuses = self.0.execution_controller.get_ops_exec_status(&ops);
-
-        // compute operation finality and operation execution status from *_op_exec_statuses
-        let (is_operation_final, statuses): (Vec<Option<bool>>, Vec<Option<bool>>) =
-            op_exec_statuses
-                .into_iter()
-                .map(|(spec_exec, final_exec)| match (spec_exec, final_exec) {
-                    (Some(true), Some(true)) => (Some(true), Some(true)),
-                    (Some(false), Some(false)) => (Some(true), Some(false)),
-                    (Some(true), None) => (Some(false), Some(true)),
-                    (Some(false), None) => (Some(false), Some(false)),
-                    _ => (None, None),
-                })
-                .collect::<Vec<(Option<bool>, Option<bool>)>>()
-                .into_iter()
-                .unzip();
+        let op_exec_infos = self.0.execution_controller.get_ops_exec_info(&ops);
+        let op_ledger_changes = self.0.execution_controller.get_ops_ledger_changes(&ops);
+        let op_failures = self.0.execution_controller.get_ops_failures(&ops);
+
+        // compute operation finality, execution status and execution slot from the speculative and final executions
+        let mut is_operation_final: Vec<Option<bool>> = Vec::with_capacity(ops.len());
+        let mut statuses: Vec<Option<bool>> = Vec::with_capacity(ops.len());
+        let mut exec_slots: Vec<Option<Slot>> = Vec::with_capacity(ops.len());
+        for (spec_exec, final_exec) in op_exec_infos {
+            let (is_final, exec) = match (spec_exec, final_exec) {
+                (Some(spec), Some(fin)) if spec.success == fin.success => (Some(true), Some(fin)),
+                (Some(spec), None) => (Some(false), Some(spec)),
+                _ => (None, None),
+            };
+            is_operation_final.push(is_final);
+            statuses.push(exec.map(|exec| exec.success));
+            exec_slots.push(exec.and_then(|exec| exec.slot));
+        }
 
         // gather all values into a vector of OperationInfo instances
         let mut res: Vec<OperationInfo> = Vec::with_capacity(ops.len());
@@ -560,9 +851,20 @@ impl MassaRpcServer for API<Public> {
             in_pool.into_iter(),
             is_operation_final.into_iter(),
             statuses.into_iter(),
+            exec_slots.into_iter(),
+            op_ledger_changes.into_iter(),
+            op_failures.into_iter(),
         );
-        for (id, (operation, in_blocks), in_pool, is_operation_final, op_exec_status) in
-            zipped_iterator
+        for (
+            id,
+            (operation, in_blocks),
+            in_pool,
+            is_operation_final,
+            op_exec_status,
+            op_exec_slot,
+            op_ledger_changes,
+            op_failure,
+        ) in zipped_iterator
         {
             res.push(OperationInfo {
                 id,
@@ -574,6 +876,9 @@ impl MassaRpcServer for API<Public> {
                 operation,
                 in_blocks: in_blocks.into_iter().collect(),
                 op_exec_status,
+                op_exec_slot,
+                op_ledger_changes,
+                op_failure: op_failure.map(Into::into),
             });
         }
 
@@ -812,6 +1117,62 @@ impl MassaRpcServer for API<Public> {
             .collect())
     }
 
+    async fn get_datastore_keys(
+        &self,
+        inputs: Vec<DatastoreKeysInput>,
+    ) -> RpcResult<Vec<DatastoreKeysOutput>> {
+        if inputs.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let response = self
+            .0
+            .execution_controller
+            .query_state(ExecutionQueryRequest {
+                requests: inputs
+                    .iter()
+                    .flat_map(|input| {
+                        [
+                            ExecutionQueryRequestItem::AddressDatastoreKeysFinal {
+                                addr: input.address,
+                                filter: input.filter.clone(),
+                            },
+                            ExecutionQueryRequestItem::AddressDatastoreKeysCandidate {
+                                addr: input.address,
+                                filter: input.filter.clone(),
+                            },
+                        ]
+                    })
+                    .collect(),
+            });
+
+        let mut keys = response.responses.into_iter().map(|res| match res {
+            Ok(ExecutionQueryResponseItem::KeyList(keys)) => {
+                Ok(Some(keys.into_iter().collect::<Vec<_>>()))
+            }
+            Ok(_) => Err(ApiError::InternalServerError(
+                "unexpected execution query response".into(),
+            )),
+            Err(ExecutionQueryError::NotFound(_)) => Ok(None),
+            Err(e) => Err(ApiError::InternalServerError(e.to_string())),
+        });
+        let mut res = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let (Some(final_keys), Some(candidate_keys)) = (keys.next(), keys.next()) else {
+                return Err(ApiError::InternalServerError(
+                    "missing execution query response".into(),
+                )
+                .into());
+            };
+            res.push(DatastoreKeysOutput {
+                address: input.address,
+                final_keys: final_keys?,
+                candidate_keys: candidate_keys?,
+            });
+        }
+        Ok(res)
+    }
+
     /// get addresses
     async fn get_addresses(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
         // get info from storage about which blocks the addresses have created
@@ -967,12 +1328,67 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn get_deferred_credits(
+        &self,
+        address: Address,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> RpcResult<AddressDeferredCredits> {
+        if let (Some(start), Some(end)) = (start_slot, end_slot) {
+            if start > end {
+                return Err(
+                    ApiError::BadRequest("start_slot must not be after end_slot".into()).into(),
+                );
+            }
+        }
+
+        let response = self
+            .0
+            .execution_controller
+            .query_state(ExecutionQueryRequest {
+                requests: vec![
+                    ExecutionQueryRequestItem::AddressDeferredCreditsFinal(address),
+                    ExecutionQueryRequestItem::AddressDeferredCreditsCandidate(address),
+                ],
+            });
+
+        let in_range = |slot: &Slot| {
+            start_slot.map_or(true, |start| *slot >= start)
+                && end_slot.map_or(true, |end| *slot <= end)
+        };
+        let mut credits = response.responses.into_iter().map(|res| match res {
+            Ok(ExecutionQueryResponseItem::DeferredCredits(credits)) => Ok(credits
+                .into_iter()
+                .filter(|(slot, _)| in_range(slot))
+                .map(|(slot, amount)| SlotAmount { slot, amount })
+                .collect::<Vec<_>>()),
+            Ok(_) => Err(ApiError::InternalServerError(
+                "unexpected execution query response".into(),
+            )),
+            Err(e) => Err(ApiError::InternalServerError(e.to_string())),
+        });
+        let (Some(final_deferred_credits), Some(candidate_deferred_credits)) =
+            (credits.next(), credits.next())
+        else {
+            return Err(
+                ApiError::InternalServerError("missing execution query response".into()).into(),
+            );
+        };
+
+        Ok(AddressDeferredCredits {
+            address,
+            final_deferred_credits: final_deferred_credits?,
+            candidate_deferred_credits: candidate_deferred_credits?,
+        })
+    }
+
     /// send operations
     async fn send_operations(&self, ops: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         let mut cmd_sender = self.0.pool_command_sender.clone();
         let protocol_sender = self.0.protocol_controller.clone();
         let api_cfg = self.0.api_settings.clone();
         let mut to_send = self.0.storage.clone_without_refs();
+        let mip_store = &self.0.keypair_factory.mip_store;
 
         if ops.len() as u64 > api_cfg.max_arguments {
             return Err(ApiError::BadRequest("too many arguments".into()).into());
@@ -1013,6 +1429,11 @@ impl MassaRpcServer for API<Public> {
                             return Err(ApiError::InconsistencyError("Gas limit of the operation is higher than the block gas limit. Your operation will never be included in a block.".into()).into());
                         }
                     },
+                    OperationType::RollTransfer { .. } => {
+                        if mip_store.get_latest_component_version_at(&MipComponent::RollTransfer, now) == 0 {
+                            return Err(ApiError::InconsistencyError("Roll transfers are not active yet. Your operation will never be included in a block.".into()).into());
+                        }
+                    },
                     _ => {}
                 };
                 if let Some(slot) = last_slot {
@@ -1072,6 +1493,70 @@ impl MassaRpcServer for API<Public> {
         Ok(events)
     }
 
+    async fn get_address_transfers(
+        &self,
+        filter: TransferFilter,
+    ) -> RpcResult<Vec<AddressTransfer>> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_address_transfers(filter)
+            .into_iter()
+            .flat_map(|slot_transfers| {
+                let (slot, is_final) = (slot_transfers.slot, slot_transfers.is_final);
+                slot_transfers
+                    .transfers
+                    .into_iter()
+                    .map(move |transfer| AddressTransfer::new(slot, is_final, transfer))
+            })
+            .collect())
+    }
+
+    async fn get_slot_transfers(&self, start: Slot, end: Slot) -> RpcResult<Vec<AddressTransfer>> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_slot_transfers(start, end)
+            .into_iter()
+            .flat_map(|slot_transfers| {
+                let (slot, is_final) = (slot_transfers.slot, slot_transfers.is_final);
+                slot_transfers
+                    .transfers
+                    .into_iter()
+                    .map(move |transfer| AddressTransfer::new(slot, is_final, transfer))
+            })
+            .collect())
+    }
+
+    async fn get_async_messages(
+        &self,
+        filter: AsyncPoolFilter,
+    ) -> RpcResult<Vec<AsyncPoolMessageInfo>> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_async_messages(filter.into())
+            .into_iter()
+            .map(AsyncPoolMessageInfo::from)
+            .collect())
+    }
+
+    async fn get_sc_output_events_truncation(
+        &self,
+        filter: EventFilter,
+    ) -> RpcResult<EventsTruncation> {
+        let pruned_until = self.0.execution_controller.get_final_events_pruned_until();
+        // candidate events are never pruned
+        let truncated = filter.is_final != Some(false)
+            && pruned_until.map_or(false, |pruned_until| {
+                filter.start.map_or(true, |start| start <= pruned_until)
+            });
+        Ok(EventsTruncation {
+            truncated,
+            pruned_until,
+        })
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         crate::wrong_api::<Vec<IpAddr>>()
     }
@@ -14,9 +14,14 @@ use massa_models::{
     block::{FilledBlock, SecureShareBlock},
     block_header::BlockHeader,
     block_id::BlockId,
+    clique::{blockclique_fingerprint, BlockcliqueReorg},
     config::VERSION,
-    operation::SecureShareOperation,
+    operation::{
+        OperationLifecycleEvent, OperationLifecycleFilter, OperationLifecycleStatus,
+        SecureShareOperation,
+    },
     secure_share::SecureShare,
+    slot::Slot,
 };
 use massa_protocol_exports::test_exports::tools::{
     create_block, create_operation_with_expire_period,
@@ -287,6 +292,65 @@ async fn subscribe_new_filled_blocks() {
     api_handle.stop().await;
 }
 
+#[tokio::test]
+async fn subscribe_blockclique_reorgs() {
+    let addr: SocketAddr = "[::]:5037".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').into_iter().last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<BlockcliqueReorg>(10);
+
+    api_server.0.consensus_broadcasts.blockclique_reorg_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+    let demoted = create_block(&KeyPair::generate(0).unwrap());
+    let promoted = create_block(&KeyPair::generate(0).unwrap());
+    let slot = Slot::new(1, 0);
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_blockclique_reorgs",
+            rpc_params![],
+            "unsubscribe_blockclique_reorgs",
+        )
+        .await
+        .unwrap();
+
+    let reorg = BlockcliqueReorg {
+        old_blockclique_fingerprint: blockclique_fingerprint([&demoted.id]),
+        new_blockclique_fingerprint: blockclique_fingerprint([&promoted.id]),
+        demoted_blocks: vec![(slot, demoted.id)],
+        promoted_blocks: vec![(slot, promoted.id)],
+        affected_slots: vec![slot],
+    };
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = tx.send(reorg).unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    let value = result.unwrap().unwrap();
+    assert_eq!(
+        value["demoted_blocks"][0][1].as_str().unwrap(),
+        &demoted.id.to_string()
+    );
+    assert_eq!(value["affected_slots"].as_array().unwrap().len(), 1);
+
+    api_handle.stop().await;
+}
+
 #[tokio::test]
 async fn subscribe_new_operations() {
     let addr: SocketAddr = "[::]:5036".parse().unwrap();
@@ -333,3 +397,73 @@ async fn subscribe_new_operations() {
 
     api_handle.stop().await;
 }
+
+#[tokio::test]
+async fn subscribe_operation_lifecycle() {
+    let addr: SocketAddr = "[::]:5021".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').into_iter().last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<OperationLifecycleEvent>(10);
+
+    api_server.0.pool_broadcasts.operation_lifecycle_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+    let keypair = KeyPair::generate(0).unwrap();
+    let other_keypair = KeyPair::generate(0).unwrap();
+    let followed = create_operation_with_expire_period(&keypair, 500000);
+    let other = create_operation_with_expire_period(&other_keypair, 500000);
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let filter = OperationLifecycleFilter {
+        operation_ids: vec![],
+        creator_addresses: vec![followed.content_creator_address],
+    };
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_operation_lifecycle",
+            rpc_params![filter],
+            "unsubscribe_operation_lifecycle",
+        )
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // the event of an operation of another sender is filtered out
+        let _ = tx
+            .send(OperationLifecycleEvent {
+                operation_id: other.id,
+                creator_address: other.content_creator_address,
+                status: OperationLifecycleStatus::Expired,
+            })
+            .unwrap();
+        let _ = tx
+            .send(OperationLifecycleEvent {
+                operation_id: followed.id,
+                creator_address: followed.content_creator_address,
+                status: OperationLifecycleStatus::Included,
+            })
+            .unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    let value = result.unwrap().unwrap();
+    assert_eq!(
+        value["operation_id"].as_str().unwrap(),
+        &followed.id.to_string()
+    );
+    assert_eq!(value["status"].as_str().unwrap(), "Included");
+
+    api_handle.stop().await;
+}
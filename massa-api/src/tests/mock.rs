@@ -40,6 +40,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
+        bootstrap_audit_log_path: None,
         max_request_body_size: 52428800,
         max_response_body_size: 52428800,
         max_connections: 100,
@@ -80,12 +81,15 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
     let pool_broadcasts = PoolBroadcasts {
         endorsement_sender: broadcast::channel(100).0,
         operation_sender: broadcast::channel(100).0,
+        operation_lifecycle_sender: broadcast::channel(100).0,
     };
 
     let consensus_broadcasts = ConsensusBroadcasts {
         block_header_sender: broadcast::channel(100).0,
         block_sender: broadcast::channel(100).0,
         filled_block_sender: broadcast::channel(100).0,
+        finalized_block_sender: broadcast::channel(100).0,
+        blockclique_reorg_sender: broadcast::channel(100).0,
     };
 
     let api = API::<ApiV2>::new(
@@ -111,6 +115,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
+        bootstrap_audit_log_path: None,
         max_request_body_size: 52428800,
         max_response_body_size: 52428800,
         max_connections: 100,
@@ -230,8 +235,15 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
             read_write_limit_bytes_per_second: 1024 * 1000,
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
-            unban_everyone_timer: MassaTime::from_millis(3600000),
+            peer_ban_duration: MassaTime::from_millis(3600000),
+            peer_store_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
+            peer_store_interval: MassaTime::from_millis(60000),
+            peer_score_retention_percent: 99,
             routable_ip: None,
+            routable_ipv6: None,
             max_in_connections: 10,
             debug: true,
             peers_categories: HashMap::default(),
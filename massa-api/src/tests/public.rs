@@ -17,8 +17,12 @@ use massa_api_exports::{
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
+    execution::{
+        AddressTransfer, AsyncPoolFilter, AsyncPoolMessageInfo, ExecuteReadOnlyResponse,
+        ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult, StateOverride,
+    },
     operation::{OperationInfo, OperationInput},
+    versioning::MipStatusInfo,
     TimeInterval,
 };
 use massa_consensus_exports::{
@@ -30,7 +34,8 @@ use massa_pos_exports::MockSelectorController;
 
 use crate::{tests::mock::start_public_api, RpcServer};
 use massa_execution_exports::{
-    ExecutionAddressInfo, MockExecutionController, ReadOnlyExecutionOutput,
+    AsyncPoolMessage, ExecutionAddressInfo, ExecutionError, MockExecutionController,
+    OpExecutionInfo, ReadOnlyExecutionOutput, SlotTransfers, TransferInfo, TransferOrigin,
 };
 use massa_models::{
     address::Address,
@@ -44,7 +49,10 @@ use massa_models::{
     output_event::SCOutputEvent,
     prehash::{CapacityAllocator, PreHashMap},
     slot::Slot,
-    stats::{ConsensusStats, ExecutionStats, NetworkStats},
+    stats::{
+        ConsensusStats, DiskUsageForecast, EndorsementPoolStats, ExecutionStats, NetworkStats,
+        ThroughputBucket,
+    },
 };
 use massa_protocol_exports::{
     test_exports::tools::{create_block, create_endorsement, create_operation_with_expire_period},
@@ -70,6 +78,15 @@ async fn get_status() {
         active_cursor: Slot::new(0, 0),
         final_cursor: Slot::new(0, 0),
     });
+    exec_ctrl.expect_get_disk_usage_forecast().returning(|| {
+        Some(DiskUsageForecast {
+            timestamp: MassaTime::now().unwrap(),
+            column_family_sizes: BTreeMap::from([("state".to_string(), 1000)]),
+            growth_rate: 10.0,
+            available_space: Some(100_000),
+            time_to_full: Some(MassaTime::from_millis(10_000_000)),
+        })
+    });
 
     let mut consensus_ctrl = MockConsensusController::new();
     consensus_ctrl.expect_get_stats().returning(|| {
@@ -79,6 +96,11 @@ async fn get_status() {
             final_block_count: 50,
             stale_block_count: 40,
             clique_count: 30,
+            max_clique_count: 30,
+            cliques: Default::default(),
+            fork_depths: Default::default(),
+            discarded_blocks_per_cycle: Default::default(),
+            discarded_blocks_per_creator: Default::default(),
         })
     });
 
@@ -99,6 +121,14 @@ async fn get_status() {
     let mut pool_ctrl = MockPoolController::new();
     pool_ctrl.expect_get_operation_count().returning(|| 1024);
     pool_ctrl.expect_get_endorsement_count().returning(|| 2048);
+    pool_ctrl
+        .expect_get_endorsement_pool_stats()
+        .returning(|| EndorsementPoolStats {
+            produced_block_count: 4,
+            fill_rate: Some(0.75),
+            missed_endorsement_count: 16,
+            ..Default::default()
+        });
 
     api_public.0.pool_command_sender = Box::new(pool_ctrl);
     api_public.0.protocol_controller = Box::new(protocol_ctrl);
@@ -123,6 +153,14 @@ async fn get_status() {
     assert_eq!(response.network_stats.in_connection_count, 10);
     assert_eq!(response.network_stats.out_connection_count, 5);
     assert_eq!(response.config.thread_count, 32);
+    let disk_usage = response.disk_usage.unwrap();
+    assert_eq!(disk_usage.total_size(), 1000);
+    assert_eq!(
+        disk_usage.time_to_full,
+        Some(MassaTime::from_millis(10_000_000))
+    );
+    assert_eq!(response.endorsement_pool_stats.fill_rate, Some(0.75));
+    assert_eq!(response.endorsement_pool_stats.missed_endorsement_count, 16);
 
     api_public_handle.stop().await;
 }
@@ -159,6 +197,50 @@ async fn get_cliques() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_throughput_history() {
+    let addr: SocketAddr = "[::]:5018".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_throughput_history()
+        .returning(|window, resolution| {
+            let count = window.to_millis() / resolution.to_millis();
+            (0..count)
+                .map(|i| ThroughputBucket {
+                    start: resolution.saturating_mul(i),
+                    end: resolution.saturating_mul(i + 1),
+                    final_executed_operations_count: 10,
+                })
+                .collect()
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').into_iter().last().unwrap()
+        ))
+        .unwrap();
+    let params = rpc_params![MassaTime::from_millis(60000), MassaTime::from_millis(10000)];
+    let response: Vec<ThroughputBucket> = client
+        .request("get_throughput_history", params)
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 6);
+    assert_eq!(response[0].operations_per_second(), 1.0);
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_operations() {
     let addr: SocketAddr = "[::]:5003".parse().unwrap();
@@ -174,9 +256,19 @@ async fn get_operations() {
         .returning(|ids| ids.into_iter().map(|_id| true).collect());
 
     let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl.expect_get_ops_exec_info().returning(|op| {
+        let info = OpExecutionInfo {
+            success: true,
+            slot: Some(Slot::new(3, 1)),
+        };
+        op.iter().map(|_op| (Some(info), Some(info))).collect()
+    });
+    exec_ctrl
+        .expect_get_ops_ledger_changes()
+        .returning(|op| op.iter().map(|_op| Some(Default::default())).collect());
     exec_ctrl
-        .expect_get_ops_exec_status()
-        .returning(|op| op.iter().map(|_op| (Some(true), Some(true))).collect());
+        .expect_get_ops_failures()
+        .returning(|op| op.iter().map(|_op| None).collect());
 
     api_public.0.execution_controller = Box::new(exec_ctrl);
     api_public.0.pool_command_sender = Box::new(pool_ctrl);
@@ -199,6 +291,11 @@ async fn get_operations() {
     let response: Vec<OperationInfo> = client.request("get_operations", params).await.unwrap();
 
     assert_eq!(response.len(), 1);
+    assert_eq!(response[0].is_operation_final, Some(true));
+    assert_eq!(response[0].op_exec_status, Some(true));
+    assert_eq!(response[0].op_exec_slot, Some(Slot::new(3, 1)));
+    assert!(response[0].op_ledger_changes.is_some());
+    assert!(response[0].op_failure.is_none());
 
     api_public_handle.stop().await;
 }
@@ -495,6 +592,186 @@ async fn send_operations() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_slot_transfers() {
+    let addr: SocketAddr = "[::]:5038".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_slot_transfers()
+        .returning(|start, _end| {
+            vec![SlotTransfers {
+                slot: start,
+                is_final: true,
+                transfers: vec![
+                    TransferInfo {
+                        from: None,
+                        to: Some(
+                            Address::from_str(
+                                "AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x",
+                            )
+                            .unwrap(),
+                        ),
+                        amount: Amount::from_str("1").unwrap(),
+                        origin: TransferOrigin::BlockReward,
+                    };
+                    2
+                ],
+            }]
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').into_iter().last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Result<Vec<AddressTransfer>, Error> = client
+        .request("get_slot_transfers", rpc_params![Slot::new(1, 0)])
+        .await;
+
+    // assert invalid params
+    assert!(response.unwrap_err().to_string().contains("Invalid params"));
+
+    let response: Vec<AddressTransfer> = client
+        .request(
+            "get_slot_transfers",
+            rpc_params![Slot::new(1, 0), Slot::new(1, 5)],
+        )
+        .await
+        .unwrap();
+
+    // the transfers of each slot are flattened
+    assert_eq!(response.len(), 2);
+    assert!(response
+        .iter()
+        .all(|transfer| transfer.slot == Slot::new(1, 0) && transfer.is_final));
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_async_messages() {
+    let addr: SocketAddr = "[::]:5039".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let sender =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_async_messages()
+        .returning(move |filter| {
+            // the API filter is forwarded to the execution controller
+            assert_eq!(filter.sender, Some(sender));
+            assert_eq!(filter.is_final, Some(false));
+            let message = massa_async_pool::AsyncMessage {
+                sender,
+                function: "receive".to_string(),
+                ..Default::default()
+            };
+            vec![AsyncPoolMessage {
+                id: message.compute_id(),
+                message,
+                is_final: false,
+            }]
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').into_iter().last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Vec<AsyncPoolMessageInfo> = client
+        .request(
+            "get_async_messages",
+            rpc_params![AsyncPoolFilter {
+                sender: Some(sender),
+                is_final: Some(false),
+                ..Default::default()
+            }],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].sender, sender);
+    assert_eq!(response[0].function, "receive");
+    assert!(!response[0].is_final);
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_versioning_announcements() {
+    let addr: SocketAddr = "[::]:5040".parse().unwrap();
+    let (api_public, config) = start_public_api(addr);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').into_iter().last().unwrap()
+        ))
+        .unwrap();
+
+    // the node does not know any MIP
+    let response: Vec<MipStatusInfo> = client
+        .request("get_mip_status", rpc_params![])
+        .await
+        .unwrap();
+    assert!(response.is_empty());
+
+    let mut previous = MipStatusInfo {
+        name: "MIP-0001".to_string(),
+        version: 1,
+        components: [("Address".to_string(), 1)].into_iter().collect(),
+        start: MassaTime::from_millis(2),
+        timeout: MassaTime::from_millis(5),
+        activation_delay: MassaTime::from_millis(2),
+        state: "Started".to_string(),
+        vote_ratio: num::rational::Ratio::new(1, 2),
+        activation_at: None,
+    };
+    let response: Vec<MipStatusInfo> = client
+        .request(
+            "get_versioning_announcements",
+            rpc_params![vec![previous.clone()]],
+        )
+        .await
+        .unwrap();
+    assert!(response.is_empty());
+
+    // statuses that cannot come from the node are rejected
+    previous.state = "Unknown".to_string();
+    let response: Result<Vec<MipStatusInfo>, Error> = client
+        .request("get_versioning_announcements", rpc_params![vec![previous]])
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("unknown MIP state"));
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_filtered_sc_output_event() {
     let addr: SocketAddr = "[::]:5013".parse().unwrap();
@@ -582,9 +859,13 @@ async fn execute_read_only_bytecode() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: massa_execution_exports::EventStore::default(),
+                    transfers: Default::default(),
+                    operation_changes: Default::default(),
+                    operation_failures: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                trace: None,
             })
         });
 
@@ -609,7 +890,10 @@ async fn execute_read_only_bytecode() {
         ),
         operation_datastore: None,
         is_final: false,
-        fee: None
+        fee: None,
+        historical_slot: None,
+        state_overrides: Vec::new(),
+        trace: false,
     }]];
     let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
         .request("execute_read_only_bytecode", params.clone())
@@ -624,6 +908,9 @@ async fn execute_read_only_bytecode() {
         operation_datastore: None,
         is_final: false,
         fee: None,
+        historical_slot: None,
+        state_overrides: Vec::new(),
+        trace: false,
     }]];
     let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
         .request("execute_read_only_bytecode", params.clone())
@@ -637,7 +924,10 @@ async fn execute_read_only_bytecode() {
         address: None,
         operation_datastore: Some("hi".as_bytes().to_vec()),
         is_final: false,
-        fee: None
+        fee: None,
+        historical_slot: None,
+        state_overrides: Vec::new(),
+        trace: false,
     }]];
     let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
         .request("execute_read_only_bytecode", params.clone())
@@ -655,7 +945,20 @@ async fn execute_read_only_call() {
     let mut exec_ctrl = MockExecutionController::new();
     exec_ctrl
         .expect_execute_readonly_request()
-        .returning(|_req| {
+        .returning(|req| {
+            // the state overrides are forwarded to execution
+            let state_override = req
+                .state_overrides
+                .get(
+                    &Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+                        .unwrap(),
+                )
+                .unwrap();
+            assert_eq!(
+                state_override.balance,
+                Some(Amount::from_str("10").unwrap())
+            );
+            assert_eq!(state_override.datastore.get(&vec![1u8]), Some(&None));
             Ok(ReadOnlyExecutionOutput {
                 out: massa_execution_exports::ExecutionOutput {
                     slot: Slot {
@@ -665,9 +968,13 @@ async fn execute_read_only_call() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: massa_execution_exports::EventStore::default(),
+                    transfers: Default::default(),
+                    operation_changes: Default::default(),
+                    operation_failures: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                trace: None,
             })
         });
 
@@ -701,6 +1008,15 @@ async fn execute_read_only_call() {
         is_final: false,
         fee: None,
         coins: None,
+        historical_slot: None,
+        state_overrides: vec![StateOverride {
+            address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+                .unwrap(),
+            balance: Some(Amount::from_str("10").unwrap()),
+            bytecode: None,
+            datastore: vec![(vec![1], None)],
+        }],
+        trace: false,
     }]];
     let response: Vec<ExecuteReadOnlyResponse> = client
         .request("execute_read_only_call", params.clone())
@@ -711,6 +1027,83 @@ async fn execute_read_only_call() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn execute_read_only_call_batch() {
+    let addr: SocketAddr = "[::]:5019".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_execute_readonly_batch()
+        .times(1)
+        .returning(|reqs| {
+            // all the calls are forwarded to execution at once
+            assert_eq!(reqs.len(), 2);
+            Ok(vec![
+                Ok(ReadOnlyExecutionOutput {
+                    out: massa_execution_exports::ExecutionOutput {
+                        slot: Slot {
+                            period: 1,
+                            thread: 5,
+                        },
+                        block_info: None,
+                        state_changes: massa_final_state::StateChanges::default(),
+                        events: massa_execution_exports::EventStore::default(),
+                        transfers: Default::default(),
+                        operation_changes: Default::default(),
+                        operation_failures: Default::default(),
+                    },
+                    gas_cost: 100,
+                    call_result: "toto".as_bytes().to_vec(),
+                    trace: None,
+                }),
+                Err(ExecutionError::RuntimeError("call failed".to_string())),
+            ])
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').into_iter().last().unwrap()
+        ))
+        .unwrap();
+
+    let call = ReadOnlyCall {
+        max_gas: 1000000,
+        target_address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+            .unwrap(),
+        target_function: "hello".to_string(),
+        parameter: vec![],
+        caller_address: None,
+        is_final: false,
+        fee: None,
+        coins: None,
+        historical_slot: None,
+        state_overrides: vec![],
+        trace: false,
+    };
+    let response: Vec<ExecuteReadOnlyResponse> = client
+        .request(
+            "execute_read_only_call_batch",
+            rpc_params![vec![call.clone(), call]],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].gas_cost, 100);
+    assert_eq!(response[0].executed_at, Slot::new(1, 5));
+    assert!(matches!(response[1].result, ReadOnlyResult::Error(_)));
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_addresses() {
     let addr: SocketAddr = "[::]:5010".parse().unwrap();
@@ -906,6 +1299,28 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client
+        .request("node_get_operation_admission_stats", params.clone())
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
+    let response: Result<(), Error> = client.request("node_get_peer_store", params.clone()).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
+    let response: Result<(), Error> = client
+        .request("node_forget_peers", rpc_params![Vec::<NodeId>::new()])
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client.request("node_peers_whitelist", params.clone()).await;
     assert!(response
         .unwrap_err()
@@ -1096,8 +1096,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db: ShareableMassaDBController = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
@@ -1136,8 +1141,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db: ShareableMassaDBController = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
@@ -1196,8 +1206,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db: ShareableMassaDBController = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
@@ -1251,8 +1266,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db: ShareableMassaDBController = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
@@ -1304,8 +1324,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db: ShareableMassaDBController = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
@@ -1352,8 +1377,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db: ShareableMassaDBController = Arc::new(RwLock::new(Box::new(MassaDB::new(
             db_config.clone(),
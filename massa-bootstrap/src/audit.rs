@@ -0,0 +1,157 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Audit log of the bootstrap server.
+//!
+//! With `audit_log_path` set, the server appends a JSON line to the file for each bootstrap session,
+//! and for each connection it refuses: the IP of the client, the start and duration of the session,
+//! the bytes sent, the reason it ended and the limits the client violated. The file is renamed
+//! with a `.1` extension once it exceeds `audit_log_max_size` bytes, replacing the previous one.
+//!
+//! The private API reads the recent entries back from both files (see `node_bootstrap_audit_log`).
+//! The bootstrap protocol does not identify the clients by a node id: the entries only hold their IP.
+
+use massa_time::MassaTime;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    net::IpAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tracing::warn;
+
+/// Limit of the bootstrap server violated by a client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BootstrapLimit {
+    /// the IP of the client is blacklisted
+    Blacklist,
+    /// the IP of the client is not whitelisted
+    Whitelist,
+    /// the client bootstrapped again before `per_ip_min_interval`
+    PerIpMinInterval,
+    /// all the `max_simultaneous_bootstraps` slots were taken
+    MaxSimultaneousBootstraps,
+    /// all the `max_simultaneous_challenges` slots were taken
+    MaxSimultaneousChallenges,
+    /// the client did not answer the handshake challenge
+    Challenge,
+    /// the session did not make progress before a timeout
+    Timeout,
+    /// the session lasted longer than `max_session_duration`
+    MaxSessionDuration,
+}
+
+/// Entry of the audit log
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BootstrapSessionAudit {
+    /// IP of the client
+    pub ip: IpAddr,
+    /// timestamp of the start of the session
+    pub start: MassaTime,
+    /// duration of the session, 0 for a refused connection
+    pub duration: MassaTime,
+    /// bytes sent to the client, the final error message included
+    pub bytes_sent: u64,
+    /// "success", or the error that ended the session
+    pub termination_reason: String,
+    /// limits violated by the client
+    pub violated_limits: Vec<BootstrapLimit>,
+}
+
+impl BootstrapSessionAudit {
+    /// Entry of a connection refused because of `limit`
+    pub(crate) fn refused(ip: IpAddr, reason: String, limit: BootstrapLimit) -> Self {
+        BootstrapSessionAudit {
+            ip,
+            start: MassaTime::now().unwrap_or(MassaTime::from_millis(0)),
+            duration: MassaTime::from_millis(0),
+            bytes_sent: 0,
+            termination_reason: reason,
+            violated_limits: vec![limit],
+        }
+    }
+
+    /// Entry of a session started `elapsed` ago
+    pub(crate) fn session(
+        ip: IpAddr,
+        elapsed: Duration,
+        bytes_sent: u64,
+        termination_reason: String,
+        violated_limits: Vec<BootstrapLimit>,
+    ) -> Self {
+        let duration = MassaTime::from_millis(elapsed.as_millis() as u64);
+        BootstrapSessionAudit {
+            ip,
+            start: MassaTime::now()
+                .map(|now| now.saturating_sub(duration))
+                .unwrap_or(MassaTime::from_millis(0)),
+            duration,
+            bytes_sent,
+            termination_reason,
+            violated_limits,
+        }
+    }
+}
+
+/// Rotating file of the audit log, shared by the sessions of the server
+pub(crate) struct BootstrapAuditLog {
+    path: PathBuf,
+    max_size: u64,
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl BootstrapAuditLog {
+    pub(crate) fn new(path: PathBuf, max_size: u64) -> Self {
+        BootstrapAuditLog {
+            path,
+            max_size,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Appends `entry` to the log. A write error is only logged: it must not end the session.
+    pub(crate) fn record(&self, entry: &BootstrapSessionAudit) {
+        if let Err(e) = self.append(entry) {
+            warn!(
+                "could not write the bootstrap audit log {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+
+    fn append(&self, entry: &BootstrapSessionAudit) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock();
+        if let Some((_, size)) = file.as_ref() {
+            if *size > 0 && size.saturating_add(line.len() as u64) > self.max_size {
+                *file = None;
+                fs::rename(&self.path, rotated_path(&self.path))?;
+            }
+        }
+        if file.is_none() {
+            let opened = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            let size = opened.metadata()?.len();
+            *file = Some((opened, size));
+        }
+        let (opened, size) = file.as_mut().expect("audit log file opened above");
+        opened.write_all(&line)?;
+        *size = size.saturating_add(line.len() as u64);
+        Ok(())
+    }
+}
+
+/// Path of the previous file of the audit log at `path`
+pub(crate) fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
@@ -0,0 +1,265 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Bandwidth scheduler of the bootstrap server.
+//!
+//! The sessions ask the scheduler for the bandwidth of each message before sending it.
+//! The requests are served by weighted fair queuing: the weight of a session is shared with
+//! the other sessions of its IP, so that opening several sessions does not grant more bandwidth.
+//! Each request gets a virtual finish tag, and the pending request with the lowest tag is served
+//! first, as long as the global cap and the cap of its IP allow it.
+//!
+//! The caps are token buckets refilled every second, that may go into debt for a message
+//! larger than what remains: the next requests then wait for the debt to be refilled.
+
+use crate::error::BootstrapError;
+use humantime::format_duration;
+use parking_lot::{Condvar, Mutex};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::ErrorKind,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Token bucket of a bandwidth cap, in bytes
+struct TokenBucket {
+    /// bytes per second
+    rate: u64,
+    /// available bytes, negative when in debt
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        TokenBucket {
+            rate,
+            tokens: rate as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.rate as f64);
+        self.last_refill = now;
+    }
+
+    fn available(&self) -> bool {
+        self.tokens >= 0.0
+    }
+
+    /// Time until the debt of the bucket is refilled
+    fn wait_time(&self) -> Duration {
+        if self.available() {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(-self.tokens / self.rate as f64)
+    }
+}
+
+/// Sessions and bucket of an IP
+struct IpState {
+    sessions: u64,
+    bucket: Option<TokenBucket>,
+}
+
+struct SchedulerState {
+    global_bucket: Option<TokenBucket>,
+    ips: HashMap<IpAddr, IpState>,
+    /// virtual time: finish tag of the last served request
+    virtual_time: u128,
+    /// pending requests by finish tag and arrival order
+    pending: BTreeMap<(u128, u64), IpAddr>,
+    next_request_id: u64,
+}
+
+impl SchedulerState {
+    fn refill(&mut self, now: Instant) {
+        if let Some(bucket) = self.global_bucket.as_mut() {
+            bucket.refill(now);
+        }
+        for ip_state in self.ips.values_mut() {
+            if let Some(bucket) = ip_state.bucket.as_mut() {
+                bucket.refill(now);
+            }
+        }
+    }
+
+    fn ip_available(&self, ip: &IpAddr) -> bool {
+        self.ips
+            .get(ip)
+            .and_then(|ip_state| ip_state.bucket.as_ref())
+            .map_or(true, TokenBucket::available)
+    }
+
+    /// Pending request served next: the one with the lowest finish tag among the IPs that are not capped
+    fn next_request(&self) -> Option<(u128, u64)> {
+        self.pending
+            .iter()
+            .find(|(_, ip)| self.ip_available(ip))
+            .map(|(key, _)| *key)
+    }
+}
+
+/// Shares the bandwidth of the bootstrap server between its sessions
+pub(crate) struct BandwidthScheduler {
+    per_ip_limit: Option<u64>,
+    max_session_duration: Duration,
+    state: Mutex<SchedulerState>,
+    condvar: Condvar,
+}
+
+impl BandwidthScheduler {
+    /// Creates a scheduler with the caps in bytes per second of the whole server and of each IP,
+    /// a cap being disabled if 0, and the max duration of a session
+    pub(crate) fn new(
+        global_limit: u64,
+        per_ip_limit: u64,
+        max_session_duration: Duration,
+    ) -> Arc<Self> {
+        Arc::new(BandwidthScheduler {
+            per_ip_limit: (per_ip_limit != 0).then_some(per_ip_limit),
+            max_session_duration,
+            state: Mutex::new(SchedulerState {
+                global_bucket: (global_limit != 0)
+                    .then(|| TokenBucket::new(global_limit, Instant::now())),
+                ips: HashMap::new(),
+                virtual_time: 0,
+                pending: BTreeMap::new(),
+                next_request_id: 0,
+            }),
+            condvar: Condvar::new(),
+        })
+    }
+
+    /// Registers a new session of `ip`
+    pub(crate) fn open_session(self: &Arc<Self>, ip: IpAddr) -> BandwidthSession {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        let ip_state = state.ips.entry(ip).or_insert_with(|| IpState {
+            sessions: 0,
+            bucket: self.per_ip_limit.map(|rate| TokenBucket::new(rate, now)),
+        });
+        ip_state.sessions += 1;
+        BandwidthSession {
+            scheduler: self.clone(),
+            ip,
+            started: now,
+            last_finish_tag: 0,
+        }
+    }
+}
+
+/// Session of a client with the bandwidth scheduler, released when dropped
+pub(crate) struct BandwidthSession {
+    scheduler: Arc<BandwidthScheduler>,
+    ip: IpAddr,
+    started: Instant,
+    last_finish_tag: u128,
+}
+
+impl BandwidthSession {
+    /// Blocks until `bytes` can be sent, or until `deadline`
+    pub(crate) fn acquire(
+        &mut self,
+        bytes: u64,
+        deadline: Option<Instant>,
+    ) -> Result<(), BootstrapError> {
+        let scheduler = &self.scheduler;
+        if self.started.elapsed() > scheduler.max_session_duration {
+            return Err(BootstrapError::GeneralError(format!(
+                "bootstrap session exceeded its max duration of {}",
+                format_duration(scheduler.max_session_duration)
+            )));
+        }
+
+        let mut state = scheduler.state.lock();
+        // the weight of the session is shared with the other sessions of its IP
+        let ip_sessions = state
+            .ips
+            .get(&self.ip)
+            .map_or(1, |ip_state| ip_state.sessions);
+        let start_tag = state.virtual_time.max(self.last_finish_tag);
+        let finish_tag = start_tag.saturating_add(u128::from(bytes) * u128::from(ip_sessions));
+        let request = (finish_tag, state.next_request_id);
+        state.next_request_id += 1;
+        state.pending.insert(request, self.ip);
+
+        loop {
+            let now = Instant::now();
+            state.refill(now);
+            let global_available = state
+                .global_bucket
+                .as_ref()
+                .map_or(true, TokenBucket::available);
+            if global_available && state.next_request() == Some(request) {
+                state.pending.remove(&request);
+                state.virtual_time = state.virtual_time.max(finish_tag);
+                if let Some(bucket) = state.global_bucket.as_mut() {
+                    bucket.tokens -= bytes as f64;
+                }
+                if let Some(bucket) = state
+                    .ips
+                    .get_mut(&self.ip)
+                    .and_then(|ip_state| ip_state.bucket.as_mut())
+                {
+                    bucket.tokens -= bytes as f64;
+                }
+                self.last_finish_tag = finish_tag;
+                scheduler.condvar.notify_all();
+                return Ok(());
+            }
+
+            // wait for a bucket to be refilled, or for another request to be served
+            let refill_wait = if !state.ip_available(&self.ip) {
+                state
+                    .ips
+                    .get(&self.ip)
+                    .and_then(|ip_state| ip_state.bucket.as_ref())
+                    .map(TokenBucket::wait_time)
+            } else if !global_available {
+                state.global_bucket.as_ref().map(TokenBucket::wait_time)
+            } else {
+                None
+            };
+            let wake_up = match (refill_wait.map(|wait| now + wait), deadline) {
+                (Some(refill), Some(deadline)) => Some(refill.min(deadline)),
+                (refill, deadline) => refill.or(deadline),
+            };
+            if let Some(deadline) = deadline {
+                if now >= deadline {
+                    state.pending.remove(&request);
+                    scheduler.condvar.notify_all();
+                    return Err(std::io::Error::new(
+                        ErrorKind::TimedOut,
+                        "deadline has elapsed while waiting for bandwidth",
+                    )
+                    .into());
+                }
+            }
+            match wake_up {
+                Some(wake_up) => {
+                    scheduler.condvar.wait_until(&mut state, wake_up);
+                }
+                None => scheduler.condvar.wait(&mut state),
+            }
+        }
+    }
+}
+
+impl Drop for BandwidthSession {
+    fn drop(&mut self) {
+        let mut state = self.scheduler.state.lock();
+        if let Some(ip_state) = state.ips.get_mut(&self.ip) {
+            ip_state.sessions = ip_state.sessions.saturating_sub(1);
+            if ip_state.sessions == 0 {
+                state.ips.remove(&self.ip);
+            }
+        }
+        self.scheduler.condvar.notify_all();
+    }
+}
@@ -8,6 +8,13 @@ use std::{
 pub(crate) use client::*;
 pub(crate) use server::*;
 
+/// Handshake flag of a client that only accepts uncompressed messages,
+/// and prefix of an uncompressed server message once compression is negotiated
+const NO_COMPRESSION: u8 = 0;
+/// Handshake flag of a client that accepts zstd-compressed messages,
+/// and prefix of a zstd-compressed server message once compression is negotiated
+const ZSTD_COMPRESSION: u8 = 1;
+
 trait BindingReadExact: io::Read {
     /// similar to std::io::Read::read_exact, but with a timeout that is function-global instead of per-individual-read
     fn read_exact_timeout(
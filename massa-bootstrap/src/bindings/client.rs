@@ -1,12 +1,15 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::bindings::{BindingReadExact, BindingWriteExact};
+use crate::bindings::{BindingReadExact, BindingWriteExact, NO_COMPRESSION, ZSTD_COMPRESSION};
 use crate::error::BootstrapError;
 use crate::messages::{
     BootstrapClientMessage, BootstrapClientMessageSerializer, BootstrapServerMessage,
     BootstrapServerMessageDeserializer,
 };
+use crate::progress::BootstrapProgressReporter;
 use crate::settings::BootstrapClientConfig;
+use crate::tls::BootstrapTransport;
+use crate::transport::BootstrapStream;
 use massa_hash::Hash;
 use massa_models::config::{
     MAX_BOOTSTRAP_MESSAGE_SIZE, MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES, SIGNATURE_DESER_SIZE,
@@ -16,17 +19,24 @@ use massa_models::version::{Version, VersionSerializer};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::{PublicKey, Signature};
 use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rustls::ClientConfig;
+use std::borrow::Cow;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::Duration;
 use std::time::Instant;
-use std::{net::TcpStream, time::Duration};
 use stream_limiter::{Limiter, LimiterOptions};
 
 /// Bootstrap client binder
 pub struct BootstrapClientBinder {
     remote_pubkey: PublicKey,
-    duplex: Limiter<TcpStream>,
+    duplex: Limiter<BootstrapTransport>,
     prev_message: Option<Hash>,
     version_serializer: VersionSerializer,
     cfg: BootstrapClientConfig,
+    /// whether the server messages are prefixed with a compression flag
+    compression_negotiated: bool,
+    progress: Option<BootstrapProgressReporter>,
 }
 
 const KNOWN_PREFIX_LEN: usize = SIGNATURE_DESER_SIZE + MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES;
@@ -44,41 +54,64 @@ impl BootstrapClientBinder {
     /// * limit: limit max bytes per second (up and down)
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        duplex: TcpStream,
+        duplex: impl BootstrapStream + 'static,
         remote_pubkey: PublicKey,
         cfg: BootstrapClientConfig,
         limit: Option<u64>,
     ) -> Self {
         let limit_opts =
             limit.map(|limit| LimiterOptions::new(limit, Duration::from_millis(1000), limit));
-        let duplex = Limiter::new(duplex, limit_opts.clone(), limit_opts);
+        let duplex = Limiter::new(
+            BootstrapTransport::new(duplex),
+            limit_opts.clone(),
+            limit_opts,
+        );
         BootstrapClientBinder {
             remote_pubkey,
             duplex,
             prev_message: None,
             version_serializer: VersionSerializer::new(),
             cfg,
+            compression_negotiated: false,
+            progress: None,
         }
     }
 
+    /// Accounts for the bytes received in the progress of the bootstrap
+    pub(crate) fn set_progress_reporter(&mut self, progress: BootstrapProgressReporter) {
+        self.progress = Some(progress);
+    }
+
+    /// Starts the TLS layer of the connection, the TLS handshake being made with the bootstrap handshake
+    pub(crate) fn start_tls(&mut self, config: Arc<ClientConfig>) -> Result<(), BootstrapError> {
+        self.duplex.stream.start_client_tls(config)
+    }
+
     /// Performs a handshake. Should be called after connection
     /// NOT cancel-safe
     pub fn handshake(&mut self, version: Version) -> Result<(), BootstrapError> {
-        // send version and randomn bytes
+        // send version, randomn bytes and compression flag
         let msg_hash = {
             let mut version_ser = Vec::new();
             self.version_serializer
                 .serialize(&version, &mut version_ser)?;
-            let mut version_random_bytes =
-                vec![0u8; version_ser.len() + self.cfg.randomness_size_bytes];
+            let random_end = version_ser.len() + self.cfg.randomness_size_bytes;
+            let mut version_random_bytes = vec![0u8; random_end + 1];
             version_random_bytes[..version_ser.len()].clone_from_slice(&version_ser);
-            StdRng::from_entropy().fill_bytes(&mut version_random_bytes[version_ser.len()..]);
+            StdRng::from_entropy()
+                .fill_bytes(&mut version_random_bytes[version_ser.len()..random_end]);
+            version_random_bytes[random_end] = if self.cfg.compression_level != 0 {
+                ZSTD_COMPRESSION
+            } else {
+                NO_COMPRESSION
+            };
             self.write_all_timeout(&version_random_bytes, None)
                 .map_err(|(e, _)| e)?;
             Hash::compute_from(&version_random_bytes)
         };
 
         self.prev_message = Some(msg_hash);
+        self.compression_negotiated = self.cfg.compression_level != 0;
 
         Ok(())
     }
@@ -121,8 +154,9 @@ impl BootstrapClientBinder {
                 self.remote_pubkey.verify_signature(&msg_hash, &sig)?;
 
                 // ...And deserialize
+                let msg_bytes = self.decompress(msg_bytes)?;
                 let (_, msg) = message_deserializer
-                    .deserialize::<DeserializeError>(msg_bytes)
+                    .deserialize::<DeserializeError>(&msg_bytes)
                     .map_err(|err| BootstrapError::DeserializeError(format!("{}", err)))?;
                 msg
             } else {
@@ -139,15 +173,55 @@ impl BootstrapClientBinder {
                 self.remote_pubkey.verify_signature(&msg_hash, &sig)?;
 
                 // ...And deserialize
+                let sig_msg_bytes = self.decompress(sig_msg_bytes)?;
                 let (_, msg) = message_deserializer
-                    .deserialize::<DeserializeError>(sig_msg_bytes)
+                    .deserialize::<DeserializeError>(&sig_msg_bytes)
                     .map_err(|err| BootstrapError::DeserializeError(format!("{}", err)))?;
                 msg
             }
         };
+        if let Some(progress) = &self.progress {
+            progress.add_bytes(KNOWN_PREFIX_LEN as u64 + u64::from(msg_len));
+        }
         Ok(message)
     }
 
+    /// Strips the compression flag of a received message and decompresses it if needed.
+    /// The decompressed message must fit in the size limit of bootstrap messages.
+    fn decompress<'a>(&self, msg_bytes: &'a [u8]) -> Result<Cow<'a, [u8]>, BootstrapError> {
+        if !self.compression_negotiated {
+            return Ok(Cow::Borrowed(msg_bytes));
+        }
+        match msg_bytes.split_first() {
+            Some((&NO_COMPRESSION, payload)) => Ok(Cow::Borrowed(payload)),
+            Some((&ZSTD_COMPRESSION, payload)) => {
+                // stop reading past the size limit instead of trusting the size announced by the frame
+                let mut decompressed = Vec::new();
+                zstd::stream::read::Decoder::with_buffer(payload)
+                    .and_then(|decoder| {
+                        decoder
+                            .take(u64::from(MAX_BOOTSTRAP_MESSAGE_SIZE) + 1)
+                            .read_to_end(&mut decompressed)
+                    })
+                    .map_err(|err| {
+                        BootstrapError::DeserializeError(format!(
+                            "could not decompress bootstrap message: {}",
+                            err
+                        ))
+                    })?;
+                if decompressed.len() > MAX_BOOTSTRAP_MESSAGE_SIZE as usize {
+                    return Err(BootstrapError::DeserializeError(
+                        "decompressed bootstrap message is too large".to_string(),
+                    ));
+                }
+                Ok(Cow::Owned(decompressed))
+            }
+            _ => Err(BootstrapError::DeserializeError(
+                "bootstrap message has an unknown compression flag".to_string(),
+            )),
+        }
+    }
+
     // TODO: use a proper (de)serializer: https://github.com/massalabs/massa/pull/3745#discussion_r1169733161
     /// Send a message to the bootstrap server
     pub fn send_timeout(
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::bandwidth::BandwidthSession;
 use crate::bindings::BindingReadExact;
 use crate::error::BootstrapError;
 use crate::messages::{
@@ -7,6 +8,8 @@ use crate::messages::{
     BootstrapServerMessageSerializer,
 };
 use crate::settings::BootstrapSrvBindCfg;
+use crate::tls::{BootstrapTransport, ServerTls};
+use crate::transport::BootstrapStream;
 use massa_hash::Hash;
 use massa_hash::HASH_SIZE_BYTES;
 use massa_models::config::{MAX_BOOTSTRAP_MESSAGE_SIZE, MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES};
@@ -17,17 +20,11 @@ use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::io;
 use std::time::Instant;
-use std::{
-    convert::TryInto,
-    io::ErrorKind,
-    net::{SocketAddr, TcpStream},
-    thread,
-    time::Duration,
-};
+use std::{convert::TryInto, io::ErrorKind, net::SocketAddr, thread, time::Duration};
 use stream_limiter::{Limiter, LimiterOptions};
 use tracing::error;
 
-use super::BindingWriteExact;
+use super::{BindingWriteExact, NO_COMPRESSION, ZSTD_COMPRESSION};
 
 const KNOWN_PREFIX_LEN: usize = HASH_SIZE_BYTES + MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES;
 /// The known-length component of a message to be received.
@@ -43,11 +40,20 @@ pub struct BootstrapServerBinder {
     max_datastore_key_length: u8,
     randomness_size_bytes: usize,
     local_keypair: KeyPair,
-    duplex: Limiter<TcpStream>,
+    duplex: Limiter<BootstrapTransport>,
     prev_message: Option<Hash>,
     version_serializer: VersionSerializer,
     version_deserializer: VersionDeserializer,
     write_error_timeout: MassaTime,
+    compression_level: i32,
+    /// whether the client accepted compressed messages during the handshake
+    compression_negotiated: bool,
+    /// share of the bandwidth of the server, if any
+    bandwidth: Option<BandwidthSession>,
+    /// TLS settings of the server, until the TLS layer is negotiated with the client
+    tls: Option<ServerTls>,
+    /// bytes written to the client
+    bytes_sent: u64,
 }
 
 impl BootstrapServerBinder {
@@ -59,7 +65,7 @@ impl BootstrapServerBinder {
     /// * `limit`: limit max bytes per second (up and down)
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        duplex: TcpStream,
+        duplex: impl BootstrapStream + 'static,
         local_keypair: KeyPair,
         cfg: BootstrapSrvBindCfg,
         rw_limit: Option<u64>,
@@ -71,12 +77,17 @@ impl BootstrapServerBinder {
             randomness_size_bytes,
             consensus_bootstrap_part_size,
             write_error_timeout,
+            compression_level,
         } = cfg;
 
         let limit_opts = rw_limit.map(|limit| -> LimiterOptions {
             LimiterOptions::new(limit, Duration::from_millis(1000), limit)
         });
-        let duplex = Limiter::new(duplex, limit_opts.clone(), limit_opts);
+        let duplex = Limiter::new(
+            BootstrapTransport::new(duplex),
+            limit_opts.clone(),
+            limit_opts,
+        );
         BootstrapServerBinder {
             max_consensus_block_ids: consensus_bootstrap_part_size,
             local_keypair,
@@ -88,22 +99,54 @@ impl BootstrapServerBinder {
             version_serializer: VersionSerializer::new(),
             version_deserializer: VersionDeserializer::new(),
             write_error_timeout,
+            compression_level,
+            compression_negotiated: false,
+            bandwidth: None,
+            tls: None,
+            bytes_sent: 0,
+        }
+    }
+
+    /// Bytes written to the client so far
+    pub(crate) fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Sets the share of the bandwidth of the server that the messages are sent with,
+    /// the previous one being released
+    pub(crate) fn set_bandwidth_session(&mut self, bandwidth: Option<BandwidthSession>) {
+        self.bandwidth = bandwidth;
+    }
+
+    /// Sets the TLS settings the connection is negotiated with, plain connections only being accepted if `None`
+    pub(crate) fn set_tls(&mut self, tls: Option<ServerTls>) {
+        self.tls = tls;
+    }
+
+    /// Starts the TLS layer if the client opens the connection with a TLS handshake.
+    /// Only done once, before the first message.
+    fn negotiate_tls(&mut self, deadline: Option<Instant>) -> Result<(), BootstrapError> {
+        if let Some(tls) = self.tls.take() {
+            self.duplex.stream.accept_tls(&tls, deadline)?;
         }
+        Ok(())
     }
+
     /// Performs a handshake. Should be called after connection
-    /// MUST always be followed by a send of the `BootstrapMessage::BootstrapTime`
+    /// MUST always be followed by a send of the `BootstrapMessage::BootstrapTime`, after the challenge if any
     pub fn handshake_timeout(
         &mut self,
         version: Version,
         duration: Option<Duration>,
     ) -> Result<(), BootstrapError> {
         let deadline = duration.map(|d| Instant::now() + d);
-        // read version and random bytes, send signature
+        self.negotiate_tls(deadline)?;
+        // read version, random bytes and compression flag
         let msg_hash = {
             let mut version_bytes = Vec::new();
             self.version_serializer
                 .serialize(&version, &mut version_bytes)?;
-            let mut msg_bytes = vec![0u8; version_bytes.len() + self.randomness_size_bytes];
+            let mut msg_bytes = vec![0u8; version_bytes.len() + self.randomness_size_bytes + 1];
             self.read_exact_timeout(&mut msg_bytes, deadline)
                 .map_err(|(e, _)| e)?;
             let (_, received_version) = self
@@ -113,6 +156,16 @@ impl BootstrapServerBinder {
             if !received_version.is_compatible(&version) {
                 return Err(BootstrapError::IncompatibleVersionError(format!("Received a bad incompatible version in handshake. (excepted: {}, received: {})", version, received_version)));
             }
+            self.compression_negotiated = match msg_bytes[msg_bytes.len() - 1] {
+                NO_COMPRESSION => false,
+                ZSTD_COMPRESSION => true,
+                flag => {
+                    return Err(BootstrapError::GeneralError(format!(
+                        "Received an unknown compression flag in handshake: {}",
+                        flag
+                    )))
+                }
+            };
             Hash::compute_from(&msg_bytes)
         };
 
@@ -156,6 +209,10 @@ impl BootstrapServerBinder {
         thread::Builder::new()
             .name("bootstrap-error-send".to_string())
             .spawn(move || {
+                // answer a TLS client through TLS, and a refused plain client in plain
+                let _ = self.negotiate_tls(Some(
+                    Instant::now() + self.write_error_timeout.to_duration(),
+                ));
                 let msg_cloned = msg.clone();
                 let err_send = self.send_error_timeout(msg_cloned);
                 match err_send {
@@ -198,6 +255,15 @@ impl BootstrapServerBinder {
         // serialize the message to bytes
         let mut msg_bytes = Vec::new();
         BootstrapServerMessageSerializer::new().serialize(&msg, &mut msg_bytes)?;
+        if msg_bytes.len() > MAX_BOOTSTRAP_MESSAGE_SIZE as usize {
+            return Err(BootstrapError::GeneralError(format!(
+                "bootstrap message too large: {} bytes",
+                msg_bytes.len()
+            )));
+        }
+        if self.compression_negotiated {
+            msg_bytes = self.compress(msg_bytes)?;
+        }
         let msg_len: u32 = msg_bytes.len().try_into().map_err(|e| {
             BootstrapError::GeneralError(format!("bootstrap message too large to encode: {}", e))
         })?;
@@ -223,7 +289,10 @@ impl BootstrapServerBinder {
         // organize the bytes into a sendable array
         let stream_data = [sig.to_bytes().as_slice(), &msg_len_bytes, &msg_bytes].concat();
 
-        // send the data
+        // wait for our share of the bandwidth, then send the data
+        if let Some(bandwidth) = self.bandwidth.as_mut() {
+            bandwidth.acquire(stream_data.len() as u64, deadline)?;
+        }
         self.write_all_timeout(&stream_data, deadline)
             .map_err(|(e, _)| e)?;
 
@@ -233,6 +302,24 @@ impl BootstrapServerBinder {
         Ok(())
     }
 
+    /// Prefixes the serialized message with its compression flag, compressing it if it makes it smaller.
+    /// The size limit of bootstrap messages applies to the uncompressed bytes.
+    fn compress(&self, msg_bytes: Vec<u8>) -> Result<Vec<u8>, BootstrapError> {
+        if self.compression_level != 0 {
+            let compressed =
+                zstd::bulk::compress(&msg_bytes, self.compression_level).map_err(|e| {
+                    BootstrapError::GeneralError(format!(
+                        "could not compress bootstrap message: {}",
+                        e
+                    ))
+                })?;
+            if compressed.len() < msg_bytes.len() {
+                return Ok([&[ZSTD_COMPRESSION][..], &compressed].concat());
+            }
+        }
+        Ok([&[NO_COMPRESSION][..], &msg_bytes].concat())
+    }
+
     // TODO: use a proper (de)serializer: https://github.com/massalabs/massa/pull/3745#discussion_r1169733161
     /// Read a message sent from the client (not signed).
     pub fn next_timeout(
@@ -335,7 +422,9 @@ impl crate::bindings::BindingReadExact for BootstrapServerBinder {
 
 impl io::Write for BootstrapServerBinder {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.duplex.write(buf)
+        let written = self.duplex.write(buf)?;
+        self.bytes_sent = self.bytes_sent.saturating_add(written as u64);
+        Ok(written)
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -0,0 +1,155 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Challenge of the bootstrap handshake.
+//!
+//! With `challenge_mode` set, the server sends a random nonce to the client right after the handshake,
+//! and only begins the bootstrap once the client has answered it:
+//! * in `ProofOfWork` mode, with a number whose hash with the nonce starts with `challenge_difficulty` zero bits;
+//! * in `Token` mode, with the hash of the nonce and of one of the `challenge_tokens` of the server,
+//!   so that only the clients the tokens were shared with can bootstrap.
+//!
+//! A client waiting to answer only takes one of the `max_simultaneous_challenges` slots of the server:
+//! it takes one of the `max_simultaneous_bootstraps` slots once its answer is verified.
+//! Filling the bootstrap slots with connections thus costs the attacker the proof of work (or a token)
+//! for each of them, while an honest client only solves one challenge per bootstrap.
+
+use crate::error::BootstrapError;
+use crate::messages::{BootstrapClientMessage, BootstrapServerMessage};
+use crate::settings::BootstrapChallengeMode;
+use massa_hash::Hash;
+use rand::{thread_rng, RngCore};
+
+/// Size of the nonce of a challenge
+pub(crate) const CHALLENGE_NONCE_SIZE: usize = 32;
+
+/// Max difficulty of a proof of work: a client refuses the challenges of a higher difficulty
+pub(crate) const MAX_CHALLENGE_DIFFICULTY: u8 = 28;
+
+/// Domain separator of the token proofs, so that they are never the hash of a proof of work
+const TOKEN_PROOF_DOMAIN: &[u8] = b"massa-bootstrap-token";
+
+/// Challenge sent by the server to a client
+pub(crate) struct HandshakeChallenge {
+    mode: BootstrapChallengeMode,
+    nonce: Vec<u8>,
+    difficulty: u8,
+}
+
+impl HandshakeChallenge {
+    /// Draws the nonce of a new challenge.
+    /// The difficulty is only used in `ProofOfWork` mode, and capped to `MAX_CHALLENGE_DIFFICULTY`.
+    pub(crate) fn new(mode: BootstrapChallengeMode, difficulty: u8) -> Self {
+        let mut nonce = vec![0u8; CHALLENGE_NONCE_SIZE];
+        thread_rng().fill_bytes(&mut nonce);
+        let difficulty = match mode {
+            BootstrapChallengeMode::ProofOfWork => difficulty.min(MAX_CHALLENGE_DIFFICULTY),
+            BootstrapChallengeMode::Disabled | BootstrapChallengeMode::Token => 0,
+        };
+        HandshakeChallenge {
+            mode,
+            nonce,
+            difficulty,
+        }
+    }
+
+    /// Message sending the challenge to the client
+    pub(crate) fn message(&self) -> BootstrapServerMessage {
+        BootstrapServerMessage::BootstrapChallenge {
+            nonce: self.nonce.clone(),
+            difficulty: self.difficulty,
+        }
+    }
+
+    /// Checks the answer of the client against the challenge and the tokens accepted by the server
+    pub(crate) fn check(
+        &self,
+        solution: u64,
+        token_proof: Option<&[u8]>,
+        tokens: &[String],
+    ) -> Result<(), BootstrapError> {
+        match self.mode {
+            BootstrapChallengeMode::Disabled => Ok(()),
+            BootstrapChallengeMode::ProofOfWork => {
+                if check_proof_of_work(&self.nonce, self.difficulty, solution) {
+                    Ok(())
+                } else {
+                    Err(BootstrapError::ChallengeFailed(
+                        "invalid proof of work".to_string(),
+                    ))
+                }
+            }
+            BootstrapChallengeMode::Token => {
+                let Some(token_proof) = token_proof else {
+                    return Err(BootstrapError::ChallengeFailed(
+                        "this bootstrap server requires a token".to_string(),
+                    ));
+                };
+                // every token is checked, so that the time taken does not reveal which one matched
+                if tokens.iter().fold(false, |matched, token| {
+                    constant_time_eq(&compute_token_proof(&self.nonce, token), token_proof)
+                        | matched
+                }) {
+                    Ok(())
+                } else {
+                    Err(BootstrapError::ChallengeFailed("invalid token".to_string()))
+                }
+            }
+        }
+    }
+}
+
+/// Answer of the client to a challenge: its token proof if it has a token,
+/// and the proof of work if the difficulty is not 0
+pub(crate) fn answer_challenge(
+    nonce: &[u8],
+    difficulty: u8,
+    token: Option<&str>,
+) -> BootstrapClientMessage {
+    BootstrapClientMessage::BootstrapChallengeResponse {
+        solution: if difficulty == 0 {
+            0
+        } else {
+            solve_proof_of_work(nonce, difficulty)
+        },
+        token_proof: token.map(|token| compute_token_proof(nonce, token)),
+    }
+}
+
+/// Finds the first solution of a proof of work, which takes 2^`difficulty` hashes on average
+pub(crate) fn solve_proof_of_work(nonce: &[u8], difficulty: u8) -> u64 {
+    (0..=u64::MAX)
+        .find(|solution| check_proof_of_work(nonce, difficulty, *solution))
+        .expect("no solution found for the proof of work")
+}
+
+/// Whether the hash of the nonce and of the solution starts with `difficulty` zero bits
+pub(crate) fn check_proof_of_work(nonce: &[u8], difficulty: u8, solution: u64) -> bool {
+    let hash = Hash::compute_from_tuple(&[nonce, &solution.to_be_bytes()]);
+    leading_zero_bits(hash.to_bytes()) >= u32::from(difficulty)
+}
+
+/// Proves the knowledge of `token` without revealing it
+pub(crate) fn compute_token_proof(nonce: &[u8], token: &str) -> Vec<u8> {
+    Hash::compute_from_tuple(&[TOKEN_PROOF_DOMAIN, nonce, token.as_bytes()])
+        .to_bytes()
+        .to_vec()
+}
+
+/// Compares two byte strings in a time that only depends on their length
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut count = 0;
+    for byte in bytes {
+        count += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    count
+}
@@ -1,6 +1,12 @@
+use crossbeam::channel::Sender;
 use humantime::format_duration;
-use massa_db_exports::DBBatch;
+use massa_consensus_exports::{
+    bootstrapable_graph::BootstrapableGraphDeserializer, state_cache::take_state_cache,
+};
+use massa_db_exports::{DBBatch, MassaIteratorMode, ShareableMassaDBController, VERSIONING_CF};
 use massa_final_state::{FinalState, FinalStateError};
+use massa_hash::Hash;
+use massa_ledger_exports::{ledger_key_position, ledger_stripe_split_keys};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{node::NodeId, slot::Slot, streaming_step::StreamingStep, version::Version};
@@ -24,10 +30,17 @@ use tracing::{debug, info, warn};
 
 use crate::{
     bindings::BootstrapClientBinder,
+    challenge::answer_challenge,
     error::BootstrapError,
     messages::{BootstrapClientMessage, BootstrapServerMessage},
+    progress::{BootstrapPhase, BootstrapProgress, BootstrapProgressReporter},
+    quic::QuicStream,
+    resume::BootstrapCursorStore,
+    retry::BootstrapRetryPolicy,
     settings::IpType,
-    BootstrapConfig, GlobalBootstrapState,
+    snapshot_archive::import_snapshot_archive,
+    tls::client_tls_config,
+    BootstrapConfig, BootstrapServerMessageDeserializerArgs, GlobalBootstrapState,
 };
 
 /// Specifies a common interface that can be used by standard, or mockers
@@ -65,11 +78,14 @@ impl BSConnector for DefaultConnector {
 /// This function will send the starting point to receive a stream of the ledger and will receive and process each part until receive a `BootstrapServerMessage::FinalStateFinished` message from the server.
 /// `next_bootstrap_message` passed as parameter must be `BootstrapClientMessage::AskFinalStatePart` enum variant.
 /// `next_bootstrap_message` will be updated after receiving each part so that in case of connection lost we can restart from the last message we processed.
+/// The cursors are also saved to `cursor_store` after each part, to resume the bootstrap after a restart.
 fn stream_final_state_and_consensus(
     cfg: &BootstrapConfig,
     client: &mut BootstrapClientBinder,
     next_bootstrap_message: &mut BootstrapClientMessage,
     global_bootstrap_state: &mut GlobalBootstrapState,
+    cursor_store: Option<&BootstrapCursorStore>,
+    progress: &BootstrapProgressReporter,
 ) -> Result<(), BootstrapError> {
     if let BootstrapClientMessage::AskBootstrapPart { .. } = &next_bootstrap_message {
         client.send_timeout(
@@ -99,6 +115,13 @@ fn stream_final_state_and_consensus(
                         write_final_state.last_slot_before_downtime = last_slot_before_downtime;
                     }
 
+                    let new_keys = state_part.new_elements.len() as u64;
+                    let last_key_position = state_part
+                        .new_elements
+                        .keys()
+                        .next_back()
+                        .map(|key| ledger_key_position(key));
+
                     let (last_state_step, last_versioning_step) = write_final_state
                         .db
                         .write()
@@ -121,6 +144,21 @@ fn stream_final_state_and_consensus(
                     } else {
                         global_bootstrap_state.graph = Some(consensus_part);
                     }
+                    // Report the progress, the ledger being streamed in the order of its keys
+                    let estimated_remaining = if last_state_step.finished() {
+                        Some(0.0)
+                    } else {
+                        last_key_position.map(|position| 1.0 - position)
+                    };
+                    progress.add_state_keys(new_keys, estimated_remaining);
+                    progress.set_phase(if !last_state_step.finished() {
+                        BootstrapPhase::State
+                    } else if !last_versioning_step.finished() {
+                        BootstrapPhase::Versioning
+                    } else {
+                        BootstrapPhase::Consensus
+                    });
+
                     let last_consensus_step = StreamingStep::Ongoing(
                         // Note that this unwrap call is safe because of the above conditional statement
                         global_bootstrap_state
@@ -142,6 +180,13 @@ fn stream_final_state_and_consensus(
                         send_last_start_period: false,
                     };
 
+                    // Persist the cursors in case of restart
+                    if let Some(cursor_store) = cursor_store {
+                        if let Err(err) = cursor_store.save(next_bootstrap_message) {
+                            warn!("could not save the bootstrap cursors: {}", err);
+                        }
+                    }
+
                     // Logs for an easier diagnostic if needed
                     debug!(
                         "client final state bootstrap cursors: {:?}",
@@ -152,6 +197,9 @@ fn stream_final_state_and_consensus(
                     info!("State bootstrap complete");
                     // Set next bootstrap message
                     *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPeers;
+                    if let Some(cursor_store) = cursor_store {
+                        cursor_store.clear();
+                    }
 
                     // Update MIP store by reading from the disk
                     let mut guard = global_bootstrap_state.final_state.write();
@@ -167,15 +215,11 @@ fn stream_final_state_and_consensus(
                 }
                 BootstrapServerMessage::SlotTooOld => {
                     info!("Slot is too old retry bootstrap from scratch");
-                    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
-                        last_slot: None,
-                        last_state_step: StreamingStep::Started,
-                        last_versioning_step: StreamingStep::Started,
-                        last_consensus_step: StreamingStep::Started,
-                        send_last_start_period: true,
-                    };
-                    let mut write_final_state = global_bootstrap_state.final_state.write();
-                    write_final_state.reset();
+                    restart_from_scratch(
+                        next_bootstrap_message,
+                        global_bootstrap_state,
+                        cursor_store,
+                    );
                     return Err(BootstrapError::GeneralError(String::from("Slot too old")));
                 }
                 // At this point, we have successfully received the next message from the server, and it's an error-message String
@@ -197,17 +241,13 @@ fn stream_final_state_and_consensus(
     }
 }
 
-/// Gets the state from a bootstrap server (internal private function)
-/// needs to be CANCELLABLE
-fn bootstrap_from_server(
+/// Reads the error sent by the server at connection if any, then does the handshake
+/// and checks the ping and the clock delta with the server
+fn handshake_with_server(
     cfg: &BootstrapConfig,
     client: &mut BootstrapClientBinder,
-    next_bootstrap_message: &mut BootstrapClientMessage,
-    global_bootstrap_state: &mut GlobalBootstrapState,
     our_version: Version,
 ) -> Result<(), BootstrapError> {
-    massa_trace!("bootstrap.lib.bootstrap_from_server", {});
-
     // read error (if sent by the server)
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
     match client.next_timeout(Some(cfg.read_error_timeout.to_duration())) {
@@ -225,7 +265,7 @@ fn bootstrap_from_server(
     };
 
     // handshake
-    let send_time_uncompensated = MassaTime::now()?;
+    let mut send_time_uncompensated = MassaTime::now()?;
     // client.handshake() is not cancel-safe but we drop the whole client object if cancelled => it's OK
     client.handshake(our_version)?;
 
@@ -237,9 +277,20 @@ fn bootstrap_from_server(
         ));
     }
 
-    // First, clock and version.
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
-    let server_time = match client.next_timeout(Some(cfg.read_timeout.into())) {
+    let mut message = client.next_timeout(Some(cfg.read_timeout.into()));
+
+    // Answer the challenge of the server, if it sent one
+    if let Ok(BootstrapServerMessage::BootstrapChallenge { nonce, difficulty }) = &message {
+        let response = answer_challenge(nonce, *difficulty, cfg.challenge_token.as_deref());
+        // the ping is measured from the answer, the time spent solving the challenge is not part of it
+        send_time_uncompensated = MassaTime::now()?;
+        client.send_timeout(&response, Some(cfg.write_timeout.into()))?;
+        message = client.next_timeout(Some(cfg.read_timeout.into()));
+    }
+
+    // First, clock and version.
+    let server_time = match message {
         Err(e) => return Err(e),
         Ok(BootstrapServerMessage::BootstrapTime {
             server_time,
@@ -285,8 +336,79 @@ fn bootstrap_from_server(
         );
         return Err(BootstrapError::ClockError(message));
     }
+    Ok(())
+}
+
+/// Gets the state from a bootstrap server (internal private function)
+/// needs to be CANCELLABLE
+///
+/// After a parallel bootstrap of the state, `stripes_slot` is the most recent slot of the stripes:
+/// the server must have reached it to stream the changes that happened since the oldest one.
+fn bootstrap_from_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    our_version: Version,
+    cursor_store: Option<&BootstrapCursorStore>,
+    stripes_slot: Option<Slot>,
+    progress: &BootstrapProgressReporter,
+) -> Result<(), BootstrapError> {
+    massa_trace!("bootstrap.lib.bootstrap_from_server", {});
+
+    handshake_with_server(cfg, client, our_version)?;
 
     let write_timeout: std::time::Duration = cfg.write_timeout.into();
+
+    // When resuming an interrupted state streaming, check that the change history
+    // of the server covers the changes since our cursors before asking for the next part
+    if let BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(last_slot),
+        ..
+    } = next_bootstrap_message
+    {
+        let last_slot = *last_slot;
+        // a server that has not reached the slot of a stripe sends an error
+        if let Some(stripes_slot) = stripes_slot.filter(|slot| *slot > last_slot) {
+            match send_client_message(
+                &BootstrapClientMessage::AskBootstrapResume {
+                    last_slot: stripes_slot,
+                },
+                client,
+                write_timeout,
+                cfg.read_timeout.into(),
+                "ask bootstrap resume timed out",
+            )? {
+                BootstrapServerMessage::BootstrapResumeAccepted => {}
+                BootstrapServerMessage::SlotTooOld => {
+                    return Err(BootstrapError::ResumeRefused(stripes_slot))
+                }
+                BootstrapServerMessage::BootstrapError { error } => {
+                    return Err(BootstrapError::ReceivedError(error))
+                }
+                other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+            }
+        }
+        match send_client_message(
+            &BootstrapClientMessage::AskBootstrapResume { last_slot },
+            client,
+            write_timeout,
+            cfg.read_timeout.into(),
+            "ask bootstrap resume timed out",
+        )? {
+            BootstrapServerMessage::BootstrapResumeAccepted => {
+                info!("Resuming bootstrap from slot {}", last_slot);
+            }
+            BootstrapServerMessage::SlotTooOld => {
+                return Err(BootstrapError::ResumeRefused(last_slot))
+            }
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        }
+    }
+
     // Loop to ask data to the server depending on the last message we sent
     loop {
         match next_bootstrap_message {
@@ -296,6 +418,8 @@ fn bootstrap_from_server(
                     client,
                     next_bootstrap_message,
                     global_bootstrap_state,
+                    cursor_store,
+                    progress,
                 )?;
             }
             BootstrapClientMessage::AskBootstrapPeers => {
@@ -322,12 +446,434 @@ fn bootstrap_from_server(
             BootstrapClientMessage::BootstrapError { error: _ } => {
                 panic!("The next message to send shouldn't be BootstrapError");
             }
+            BootstrapClientMessage::AskBootstrapResume { .. } => {
+                panic!("The next message to send shouldn't be AskBootstrapResume");
+            }
+            BootstrapClientMessage::AskBootstrapStripe { .. } => {
+                panic!("The next message to send shouldn't be AskBootstrapStripe");
+            }
+            BootstrapClientMessage::AskBootstrapVersioning => {
+                panic!("The next message to send shouldn't be AskBootstrapVersioning");
+            }
+            BootstrapClientMessage::BootstrapChallengeResponse { .. } => {
+                panic!("The next message to send shouldn't be BootstrapChallengeResponse");
+            }
         };
     }
     info!("Successful bootstrap");
     Ok(())
 }
 
+/// Resets the final state and the cursors, so that the bootstrap starts again from scratch
+/// Prefills the consensus graph of a bootstrap starting from scratch with the final blocks
+/// that consensus saved on its last clean shutdown: the server then only sends the missing blocks,
+/// and the ids of the cached blocks that are no longer required
+fn load_consensus_state_cache(
+    cfg: &BootstrapConfig,
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+) {
+    let Some(path) = &cfg.consensus_state_cache_path else {
+        return;
+    };
+    let BootstrapClientMessage::AskBootstrapPart {
+        last_consensus_step: last_consensus_step @ StreamingStep::Started,
+        ..
+    } = next_bootstrap_message
+    else {
+        return;
+    };
+    let args: BootstrapServerMessageDeserializerArgs = cfg.into();
+    let deserializer =
+        BootstrapableGraphDeserializer::new((&args).into(), args.max_bootstrap_blocks_length);
+    let graph = match take_state_cache(path, &deserializer) {
+        Ok(Some(graph)) => graph,
+        Ok(None) => return,
+        Err(err) => {
+            warn!("ignoring the consensus state cache: {}", err);
+            return;
+        }
+    };
+    if graph.final_blocks.is_empty()
+        || graph.final_blocks.len() as u64 > cfg.max_consensus_block_ids
+    {
+        return;
+    }
+    info!(
+        "Loaded {} final blocks from the consensus state cache",
+        graph.final_blocks.len()
+    );
+    *last_consensus_step = StreamingStep::Ongoing(
+        graph
+            .final_blocks
+            .iter()
+            .map(|b_export| b_export.block.id)
+            .collect(),
+    );
+    global_bootstrap_state.graph = Some(graph);
+}
+
+fn restart_from_scratch(
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    cursor_store: Option<&BootstrapCursorStore>,
+) {
+    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: None,
+        last_state_step: StreamingStep::Started,
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    };
+    global_bootstrap_state.graph = None;
+    global_bootstrap_state.final_state.write().reset();
+    if let Some(cursor_store) = cursor_store {
+        cursor_store.clear();
+    }
+}
+
+/// Streams a stripe of the state from a bootstrap server, until the server has sent all of it
+/// and caught up with its final state. Returns the slot the stripe is attached to.
+fn stream_state_stripe(
+    cfg: &BootstrapConfig,
+    mut client: BootstrapClientBinder,
+    our_version: Version,
+    range_start: Vec<u8>,
+    range_end: Option<Vec<u8>>,
+    db: &ShareableMassaDBController,
+    progress: &BootstrapProgressReporter,
+) -> Result<Slot, BootstrapError> {
+    handshake_with_server(cfg, &mut client, our_version)?;
+    progress.set_phase(BootstrapPhase::State);
+
+    client.send_timeout(
+        &BootstrapClientMessage::AskBootstrapStripe {
+            range_start,
+            range_end,
+            last_slot: None,
+            last_state_step: StreamingStep::Started,
+        },
+        Some(cfg.write_timeout.to_duration()),
+    )?;
+
+    let mut last_slot = None;
+    loop {
+        match client.next_timeout(Some(cfg.read_timeout.to_duration()))? {
+            BootstrapServerMessage::BootstrapPart {
+                slot, state_part, ..
+            } => {
+                progress.add_state_keys(state_part.new_elements.len() as u64, None);
+                db.write()
+                    .write_stripe_bootstrap_client(state_part)
+                    .map_err(|e| {
+                        BootstrapError::GeneralError(format!(
+                            "Cannot write received stream batch to disk: {}",
+                            e
+                        ))
+                    })?;
+                last_slot = Some(slot);
+            }
+            BootstrapServerMessage::BootstrapFinished => {
+                // We don't care if the server misses it, the socket is closed anyway
+                let _ = client.send_timeout(
+                    &BootstrapClientMessage::BootstrapSuccess,
+                    Some(cfg.write_timeout.to_duration()),
+                );
+                return last_slot.ok_or_else(|| {
+                    BootstrapError::GeneralError(String::from(
+                        "State stripe finished without any part",
+                    ))
+                });
+            }
+            BootstrapServerMessage::SlotTooOld => {
+                return Err(BootstrapError::GeneralError(String::from("Slot too old")));
+            }
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        }
+    }
+}
+
+/// Streams the versioning column family from a bootstrap server.
+/// Returns the received entries, the updates made while streaming them applied.
+fn stream_versioning(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    our_version: Version,
+) -> Result<DBBatch, BootstrapError> {
+    handshake_with_server(cfg, client, our_version)?;
+    client.send_timeout(
+        &BootstrapClientMessage::AskBootstrapVersioning,
+        Some(cfg.write_timeout.to_duration()),
+    )?;
+
+    let mut versioning = DBBatch::new();
+    loop {
+        match client.next_timeout(Some(cfg.read_timeout.to_duration()))? {
+            BootstrapServerMessage::BootstrapPart {
+                versioning_part, ..
+            } => {
+                versioning.extend(versioning_part.updates_on_previous_elements);
+                versioning.extend(
+                    versioning_part
+                        .new_elements
+                        .into_iter()
+                        .map(|(key, value)| (key, Some(value))),
+                );
+            }
+            BootstrapServerMessage::BootstrapFinished => {
+                // We don't care if the server misses it, the socket is closed anyway
+                let _ = client.send_timeout(
+                    &BootstrapClientMessage::BootstrapSuccess,
+                    Some(cfg.write_timeout.to_duration()),
+                );
+                return Ok(versioning);
+            }
+            BootstrapServerMessage::SlotTooOld => {
+                return Err(BootstrapError::GeneralError(String::from("Slot too old")));
+            }
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        }
+    }
+}
+
+/// Replaces the versioning column family of our database by the one of a bootstrap server,
+/// and reloads the MIP store from the database.
+///
+/// Used when our MIP store diverged from the network (e.g. after a missed upgrade) while our state
+/// is still consistent: only the versioning is downloaded. The servers of the bootstrap list are tried
+/// in turn, until one of them succeeds.
+pub fn resync_versioning(
+    bootstrap_config: &BootstrapConfig,
+    final_state: Arc<RwLock<FinalState>>,
+    mut connector: impl BSConnector,
+    version: Version,
+) -> Result<(), BootstrapError> {
+    let progress = BootstrapProgressReporter::new(None);
+    for (addr, node_id) in get_bootstrap_list_iter(bootstrap_config)? {
+        info!("Re-syncing the versioning from {}", addr);
+        let mut client = match connect_to_server(
+            &mut connector,
+            bootstrap_config,
+            &addr,
+            &node_id.get_public_key(),
+            Some(bootstrap_config.rate_limit),
+            &progress,
+        ) {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Error while connecting to bootstrap server: {}", e);
+                continue;
+            }
+        };
+        let versioning = match stream_versioning(bootstrap_config, &mut client, version) {
+            Ok(versioning) => versioning,
+            Err(e) => {
+                warn!("Error while re-syncing the versioning from {}: {}", addr, e);
+                continue;
+            }
+        };
+
+        let final_state_read = final_state.read();
+        let db = final_state_read.db.clone();
+        {
+            // the entries we have but the server does not are deleted in the same batch
+            let mut db_write = db.write();
+            let mut versioning_batch: DBBatch = db_write
+                .iterator_cf(VERSIONING_CF, MassaIteratorMode::Start)
+                .map(|(key, _)| (key, None))
+                .collect();
+            versioning_batch.extend(versioning);
+            db_write.write_batch(DBBatch::new(), versioning_batch, None);
+        }
+        final_state_read
+            .mip_store
+            .reset_from_db(db)
+            .map_err(|e| BootstrapError::from(FinalStateError::from(e)))?;
+        info!("Versioning re-synced from {}", addr);
+        return Ok(());
+    }
+    Err(BootstrapError::GeneralError(String::from(
+        "no bootstrap server could re-sync the versioning",
+    )))
+}
+
+/// Bootstraps the state from `servers` in parallel, each of them streaming a stripe of the keyspace.
+///
+/// Returns the oldest and the most recent slots of the stripes: the changes since the oldest one
+/// are streamed afterwards by another server, along with the versioning and the consensus graph.
+fn bootstrap_state_stripes(
+    cfg: &BootstrapConfig,
+    connector: &mut impl BSConnector,
+    servers: &[(SocketAddr, NodeId)],
+    our_version: Version,
+    db: &ShareableMassaDBController,
+    progress: &BootstrapProgressReporter,
+) -> Result<(Slot, Slot), BootstrapError> {
+    let split_keys = ledger_stripe_split_keys(servers.len());
+    let range_starts = std::iter::once(Vec::new()).chain(split_keys.clone());
+    let range_ends = split_keys
+        .into_iter()
+        .map(Some)
+        .chain(std::iter::once(None));
+
+    // The connector is not shared with the streaming threads: connect to every server first
+    let mut stripes = Vec::with_capacity(servers.len());
+    for ((addr, node_id), (range_start, range_end)) in
+        servers.iter().zip(range_starts.zip(range_ends))
+    {
+        let client = connect_to_server(
+            connector,
+            cfg,
+            addr,
+            &node_id.get_public_key(),
+            Some(cfg.rate_limit),
+            progress,
+        )?;
+        stripes.push((addr, client, range_start, range_end));
+    }
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = stripes
+            .into_iter()
+            .map(|(addr, client, range_start, range_end)| {
+                let handle = scope.spawn(move || {
+                    stream_state_stripe(
+                        cfg,
+                        client,
+                        our_version,
+                        range_start,
+                        range_end,
+                        db,
+                        progress,
+                    )
+                });
+                (addr, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(addr, handle)| {
+                let result = handle.join().unwrap_or_else(|_| {
+                    Err(BootstrapError::GeneralError(String::from(
+                        "State stripe thread panicked",
+                    )))
+                });
+                (addr, result)
+            })
+            .collect()
+    });
+
+    let mut slots = Vec::with_capacity(results.len());
+    for (addr, result) in results {
+        match result {
+            Ok(slot) => {
+                info!("State stripe bootstrapped from {} at slot {}", addr, slot);
+                slots.push(slot);
+            }
+            Err(err) => {
+                return Err(BootstrapError::GeneralError(format!(
+                    "State stripe bootstrap from {} failed: {}",
+                    addr, err
+                )))
+            }
+        }
+    }
+    match (slots.iter().min(), slots.iter().max()) {
+        (Some(min_slot), Some(max_slot)) => Ok((*min_slot, *max_slot)),
+        _ => Err(BootstrapError::GeneralError(String::from(
+            "no server to bootstrap the state stripes from",
+        ))),
+    }
+}
+
+/// Checks the hash of a state merged from stripes against its entries
+fn verify_merged_state(final_state: &RwLock<FinalState>) -> Result<(), BootstrapError> {
+    let report = final_state.read().verify_db_integrity().map_err(|e| {
+        BootstrapError::GeneralError(format!("Cannot verify the merged state: {}", e))
+    })?;
+    if !report.is_valid() {
+        return Err(BootstrapError::GeneralError(format!(
+            "Merged state is invalid: stored hash {}, computed hash {}, {} invalid keys",
+            report.stored_hash, report.computed_hash, report.invalid_key_count
+        )));
+    }
+    info!(
+        "Merged state verified: {} keys, hash {}",
+        report.key_count, report.computed_hash
+    );
+    Ok(())
+}
+
+/// Checks the bootstrapped state against the trusted checkpoint, if any.
+///
+/// The hash of the state is recomputed from its entries. The checkpoint is then checked against it,
+/// or against the state hash snapshot of its cycle, which is part of the state.
+pub(crate) fn verify_trusted_checkpoint(
+    cfg: &BootstrapConfig,
+    final_state: &RwLock<FinalState>,
+) -> Result<(), BootstrapError> {
+    let Some(checkpoint) = &cfg.trusted_checkpoint else {
+        return Ok(());
+    };
+    let final_state = final_state.read();
+    let report = final_state.verify_db_integrity().map_err(|e| {
+        BootstrapError::GeneralError(format!("Cannot verify the bootstrapped state: {}", e))
+    })?;
+    if !report.is_valid() {
+        return Err(BootstrapError::GeneralError(format!(
+            "Bootstrapped state is invalid: stored hash {}, computed hash {}, {} invalid keys",
+            report.stored_hash, report.computed_hash, report.invalid_key_count
+        )));
+    }
+    let state_slot = report.change_id.ok_or_else(|| {
+        BootstrapError::GeneralError("Bootstrapped state has no slot".to_string())
+    })?;
+
+    let checkpoint_hash = if checkpoint.slot == state_slot {
+        report.computed_hash
+    } else if checkpoint.slot < state_slot
+        && checkpoint
+            .slot
+            .is_last_of_cycle(cfg.periods_per_cycle, cfg.thread_count)
+    {
+        let cycle = checkpoint.slot.get_cycle(cfg.periods_per_cycle);
+        final_state
+            .pos_state
+            .get_cycle_info(cycle)
+            .and_then(|cycle_info| cycle_info.final_state_hash_snapshot)
+            .ok_or_else(|| {
+                BootstrapError::CheckpointError(format!(
+                    "the state hash of cycle {} of the checkpoint is not in the history of the bootstrapped state",
+                    cycle
+                ))
+            })?
+    } else {
+        return Err(BootstrapError::CheckpointError(format!(
+            "the checkpoint at slot {} cannot be checked against the bootstrapped state at slot {}",
+            checkpoint.slot, state_slot
+        )));
+    };
+
+    // the state hashes are logged and configured in their short form
+    if Hash::compute_from(checkpoint_hash.to_bytes()) != checkpoint.state_hash {
+        return Err(BootstrapError::CheckpointError(format!(
+            "state hash {} at slot {} instead of {}",
+            checkpoint_hash, checkpoint.slot, checkpoint.state_hash
+        )));
+    }
+    info!(
+        "Bootstrapped state verified against the trusted checkpoint at slot {}",
+        checkpoint.slot
+    );
+    Ok(())
+}
+
 fn send_client_message(
     message_to_send: &BootstrapClientMessage,
     client: &mut BootstrapClientBinder,
@@ -353,15 +899,21 @@ fn connect_to_server(
     addr: &SocketAddr,
     pub_key: &PublicKey,
     rw_limit: Option<u64>,
+    progress: &BootstrapProgressReporter,
 ) -> Result<BootstrapClientBinder, BootstrapError> {
-    let socket = connector.connect_timeout(*addr, Some(bootstrap_config.connect_timeout))?;
-    socket.set_nonblocking(false)?;
-    Ok(BootstrapClientBinder::new(
-        socket,
-        *pub_key,
-        bootstrap_config.into(),
-        rw_limit,
-    ))
+    let mut client = if bootstrap_config.connect_with_quic {
+        let stream = QuicStream::connect(*addr, Some(bootstrap_config.connect_timeout.into()))?;
+        BootstrapClientBinder::new(stream, *pub_key, bootstrap_config.into(), rw_limit)
+    } else {
+        let socket = connector.connect_timeout(*addr, Some(bootstrap_config.connect_timeout))?;
+        socket.set_nonblocking(false)?;
+        BootstrapClientBinder::new(socket, *pub_key, bootstrap_config.into(), rw_limit)
+    };
+    if bootstrap_config.connect_with_tls {
+        client.start_tls(client_tls_config())?;
+    }
+    client.set_progress_reporter(progress.clone());
+    Ok(client)
 }
 
 fn filter_bootstrap_list(
@@ -393,6 +945,8 @@ fn filter_bootstrap_list(
 /// Uses the cond-var pattern to handle sig-int cancellation.
 /// Make sure that the passed in `interrupted` shares its Arc
 /// with a sig-int handler setup.
+///
+/// The progress of the bootstrap is sent on `progress_sender` if any.
 #[allow(clippy::too_many_arguments)]
 pub fn get_state(
     bootstrap_config: &BootstrapConfig,
@@ -404,6 +958,7 @@ pub fn get_state(
     restart_from_snapshot_at_period: Option<u64>,
     interupted: Arc<(Mutex<bool>, Condvar)>,
     massa_metrics: MassaMetrics,
+    progress_sender: Option<Sender<BootstrapProgress>>,
 ) -> Result<GlobalBootstrapState, BootstrapError> {
     massa_trace!("bootstrap.lib.get_state", {});
 
@@ -474,6 +1029,7 @@ pub fn get_state(
     // If the two conditions above are not verified, we need to bootstrap
     // we filter the bootstrap list to keep only the ip addresses we are compatible with
     let filtered_bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
+    let progress = BootstrapProgressReporter::new(progress_sender);
 
     let mut next_bootstrap_message: BootstrapClientMessage =
         BootstrapClientMessage::AskBootstrapPart {
@@ -485,7 +1041,147 @@ pub fn get_state(
         };
     let mut global_bootstrap_state = GlobalBootstrapState::new(final_state);
 
+    // Resume the bootstrap interrupted by a restart, if its cursors were saved
+    let cursor_store = BootstrapCursorStore::new(bootstrap_config);
+    if let Some(cursor_store) = &cursor_store {
+        let saved_cursor = cursor_store.load(&global_bootstrap_state.final_state.read().db);
+        match saved_cursor {
+            Some(cursor) => {
+                info!("Found the cursors of an interrupted bootstrap");
+                next_bootstrap_message = cursor;
+            }
+            // the state may hold a partial bootstrap that cannot be resumed
+            None if cursor_store.exists() => {
+                restart_from_scratch(
+                    &mut next_bootstrap_message,
+                    &mut global_bootstrap_state,
+                    Some(cursor_store),
+                );
+            }
+            None => {}
+        }
+    }
+
+    // Bootstrap the state and the versioning from a snapshot archive, then only stream the changes since its slot
+    if let Some(snapshot_path) = &bootstrap_config.bootstrap_snapshot_path {
+        if matches!(
+            next_bootstrap_message,
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot: None,
+                ..
+            }
+        ) {
+            match import_snapshot_archive(
+                bootstrap_config,
+                snapshot_path,
+                &global_bootstrap_state.final_state,
+            ) {
+                Ok(info) => {
+                    info!(
+                        "Bootstrapped the state from the snapshot archive of {} at slot {}",
+                        info.node_id, info.slot
+                    );
+                    next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+                        last_slot: Some(info.slot),
+                        last_state_step: StreamingStep::Finished(None),
+                        last_versioning_step: StreamingStep::Finished(None),
+                        last_consensus_step: StreamingStep::Started,
+                        send_last_start_period: false,
+                    };
+                    if let Some(cursor_store) = &cursor_store {
+                        if let Err(err) = cursor_store.save(&next_bootstrap_message) {
+                            warn!("could not save the bootstrap cursors: {}", err);
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!(
+                        "Could not bootstrap from the snapshot archive {}, bootstrapping from the network: {}",
+                        snapshot_path.display(),
+                        err
+                    );
+                    restart_from_scratch(
+                        &mut next_bootstrap_message,
+                        &mut global_bootstrap_state,
+                        cursor_store.as_ref(),
+                    );
+                }
+            }
+        }
+    }
+
+    // Number of consecutive servers that could not resume the bootstrap from our cursors
+    let mut resume_refusals = 0;
+
+    // Bootstrap the stripes of the state from several servers in parallel: the first server of the list
+    // then streams the changes since the oldest stripe, the versioning and the consensus graph
+    let mut stripes_slot = None;
+    let parallel_servers = bootstrap_config
+        .parallel_bootstrap_servers
+        .min(filtered_bootstrap_list.len());
+    if parallel_servers >= 2
+        && matches!(
+            next_bootstrap_message,
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot: None,
+                ..
+            }
+        )
+    {
+        let servers = &filtered_bootstrap_list[1..parallel_servers];
+        info!(
+            "Bootstrapping the state from {} servers in parallel",
+            servers.len()
+        );
+        let db = global_bootstrap_state.final_state.read().db.clone();
+        match bootstrap_state_stripes(
+            bootstrap_config,
+            &mut connector,
+            servers,
+            version,
+            &db,
+            &progress,
+        ) {
+            Ok((min_slot, max_slot)) => {
+                // Attach the state to the oldest stripe, so that the changes since then are streamed
+                db.write()
+                    .write_batch(DBBatch::new(), DBBatch::new(), Some(min_slot));
+                next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+                    last_slot: Some(min_slot),
+                    last_state_step: StreamingStep::Finished(None),
+                    last_versioning_step: StreamingStep::Started,
+                    last_consensus_step: StreamingStep::Started,
+                    send_last_start_period: true,
+                };
+                if let Some(cursor_store) = &cursor_store {
+                    if let Err(err) = cursor_store.save(&next_bootstrap_message) {
+                        warn!("could not save the bootstrap cursors: {}", err);
+                    }
+                }
+                stripes_slot = Some(max_slot);
+            }
+            Err(err) => {
+                warn!(
+                    "Parallel bootstrap of the state failed, bootstrapping from a single server: {}",
+                    err
+                );
+                restart_from_scratch(
+                    &mut next_bootstrap_message,
+                    &mut global_bootstrap_state,
+                    cursor_store.as_ref(),
+                );
+            }
+        }
+    }
+
+    load_consensus_state_cache(
+        bootstrap_config,
+        &mut next_bootstrap_message,
+        &mut global_bootstrap_state,
+    );
+
     let limit = bootstrap_config.rate_limit;
+    let mut retry_policy = BootstrapRetryPolicy::new(bootstrap_config, &filtered_bootstrap_list);
     loop {
         // check for interuption
         if *interupted.0.lock().expect("double-lock on interupt-mutex") {
@@ -493,85 +1189,133 @@ pub fn get_state(
                 "Sig INT received while getting state".to_string(),
             ));
         }
-        for (addr, node_id) in filtered_bootstrap_list.iter() {
-            if let Some(end) = end_timestamp {
-                if MassaTime::now().expect("could not get now time") > end {
-                    panic!("This episode has come to an end, please get the latest testnet node version to continue");
-                }
+        let (addr, node_id) = retry_policy.next_server()?;
+        if let Some(end) = end_timestamp {
+            if MassaTime::now().expect("could not get now time") > end {
+                panic!("This episode has come to an end, please get the latest testnet node version to continue");
             }
-            info!("Start bootstrapping from {}", addr);
-            let conn = connect_to_server(
-                &mut connector,
-                bootstrap_config,
-                addr,
-                &node_id.get_public_key(),
-                Some(limit),
-            );
-            match conn {
-                Ok(mut client) => {
-                    massa_metrics.inc_bootstrap_counter();
-                    let bs = bootstrap_from_server(
-                        bootstrap_config,
-                        &mut client,
-                        &mut next_bootstrap_message,
-                        &mut global_bootstrap_state,
-                        version,
-                    );
-                    // cancellable
-                    match bs {
-                        Err(BootstrapError::ReceivedError(error)) => {
-                            warn!("Error received from bootstrap server: {}", error)
-                        }
-                        Err(e) => {
-                            warn!("Error while bootstrapping: {}", &e);
-                            // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
-                            let _ = client.send_timeout(
-                                &BootstrapClientMessage::BootstrapError {
-                                    error: e.to_string(),
-                                },
-                                Some(bootstrap_config.write_error_timeout.into()),
+        }
+        info!("Start bootstrapping from {}", addr);
+        let conn = connect_to_server(
+            &mut connector,
+            bootstrap_config,
+            &addr,
+            &node_id.get_public_key(),
+            Some(limit),
+            &progress,
+        );
+        match conn {
+            Ok(mut client) => {
+                massa_metrics.inc_bootstrap_counter();
+                progress.set_phase(BootstrapPhase::Handshake);
+                let bs = bootstrap_from_server(
+                    bootstrap_config,
+                    &mut client,
+                    &mut next_bootstrap_message,
+                    &mut global_bootstrap_state,
+                    version,
+                    cursor_store.as_ref(),
+                    stripes_slot,
+                    &progress,
+                );
+                if !matches!(bs, Err(BootstrapError::ResumeRefused(_))) {
+                    resume_refusals = 0;
+                }
+                // cancellable
+                match bs {
+                    Err(BootstrapError::ResumeRefused(slot)) => {
+                        warn!("Bootstrap server {} cannot resume from slot {}", addr, slot);
+                        resume_refusals += 1;
+                        if resume_refusals >= filtered_bootstrap_list.len() {
+                            info!("No bootstrap server can resume from slot {}: restarting bootstrap from scratch", slot);
+                            restart_from_scratch(
+                                &mut next_bootstrap_message,
+                                &mut global_bootstrap_state,
+                                cursor_store.as_ref(),
                             );
+                            resume_refusals = 0;
+                        }
+                    }
+                    Err(BootstrapError::ReceivedError(error)) => {
+                        warn!("Error received from bootstrap server: {}", error)
+                    }
+                    Err(e) => {
+                        warn!("Error while bootstrapping: {}", &e);
+                        // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
+                        let _ = client.send_timeout(
+                            &BootstrapClientMessage::BootstrapError {
+                                error: e.to_string(),
+                            },
+                            Some(bootstrap_config.write_error_timeout.into()),
+                        );
+                    }
+                    Ok(()) => {
+                        // a state merged from stripes is checked against its entries
+                        let verified = match stripes_slot {
+                            Some(_) => verify_merged_state(&global_bootstrap_state.final_state),
+                            None => Ok(()),
+                        }
+                        .and_then(|()| {
+                            verify_trusted_checkpoint(
+                                bootstrap_config,
+                                &global_bootstrap_state.final_state,
+                            )
+                        });
+                        match verified {
+                            Ok(()) => {
+                                progress.set_phase(BootstrapPhase::Finished);
+                                return Ok(global_bootstrap_state);
+                            }
+                            Err(err) => {
+                                warn!("{}: restarting bootstrap from scratch", err);
+                                restart_from_scratch(
+                                    &mut next_bootstrap_message,
+                                    &mut global_bootstrap_state,
+                                    cursor_store.as_ref(),
+                                );
+                                stripes_slot = None;
+                            }
                         }
-                        Ok(()) => return Ok(global_bootstrap_state),
                     }
                 }
-                Err(e) => {
-                    warn!("Error while connecting to bootstrap server: {}", e);
-                }
-            };
-
-            info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {}.", addr, format_duration(bootstrap_config.retry_delay.to_duration()).to_string());
-
-            // Before, we would use a simple sleep(...), and that was fine
-            // in a cancellable async context: the runtime could
-            // catch the interupt signal, and just cancel this thread:
-            //
-            // let state = tokio::select!{
-            //    /* detect interupt */ => /* return, cancelling the async get_state */
-            //    get_state(...) => well, we got the state, and it didn't have to worry about interupts
-            // };
-            //
-            // Without an external system to preempt this context, we use a condvar to manage the sleep.
-            //
-            // Condvar::wait is basically std::thread::sleep(/* until some magic happens */)
-            // Condvar::wait_timeout(..., duration) is much the same, but for a max-len of `duration`
-            //
-            // The _magic_ happens when, somewhere else, a clone of the Arc<(Mutex<bool>, Condvar)>\
-            // calls Condvar::notify_[one | all], which prompts this thread to wake up. Assuming that
-            // the mutex-wrapped variable has been set appropriately before the notify, this thread
-            let int_sig = interupted
-                .0
-                .lock()
-                .expect("double-lock() on interupted signal mutex");
-            let wake = interupted
-                .1
-                .wait_timeout(int_sig, bootstrap_config.retry_delay.to_duration())
-                .expect("interupt signal mutex poisoned");
-            if *wake.0 {
-                return Err(BootstrapError::Interupted(
-                    "Sig INT during bootstrap retry-wait".to_string(),
-                ));
             }
+            Err(e) => {
+                warn!("Error while connecting to bootstrap server: {}", e);
+            }
+        };
+
+        let retry_delay = retry_policy.record_failure(&addr);
+        info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {}.", addr, format_duration(retry_delay).to_string());
+
+        // Before, we would use a simple sleep(...), and that was fine
+        // in a cancellable async context: the runtime could
+        // catch the interupt signal, and just cancel this thread:
+        //
+        // let state = tokio::select!{
+        //    /* detect interupt */ => /* return, cancelling the async get_state */
+        //    get_state(...) => well, we got the state, and it didn't have to worry about interupts
+        // };
+        //
+        // Without an external system to preempt this context, we use a condvar to manage the sleep.
+        //
+        // Condvar::wait is basically std::thread::sleep(/* until some magic happens */)
+        // Condvar::wait_timeout(..., duration) is much the same, but for a max-len of `duration`
+        //
+        // The _magic_ happens when, somewhere else, a clone of the Arc<(Mutex<bool>, Condvar)>\
+        // calls Condvar::notify_[one | all], which prompts this thread to wake up. Assuming that
+        // the mutex-wrapped variable has been set appropriately before the notify, this thread
+        let int_sig = interupted
+            .0
+            .lock()
+            .expect("double-lock() on interupted signal mutex");
+        let wake = interupted
+            .1
+            .wait_timeout(int_sig, retry_delay)
+            .expect("interupt signal mutex poisoned");
+        if *wake.0 {
+            return Err(BootstrapError::Interupted(
+                "Sig INT during bootstrap retry-wait".to_string(),
+            ));
         }
     }
 }
@@ -67,6 +67,18 @@ pub enum BootstrapError {
     WhiteListed(String),
     /// The bootstrap process ended prematurely - e.g. too much time elapsed
     Interupted(String),
+    /// the change history of the server does not cover the changes since slot {0}
+    ResumeRefused(massa_models::slot::Slot),
+    /// snapshot archive error: {0}
+    SnapshotArchiveError(String),
+    /// bootstrapped state does not match the trusted checkpoint: {0}
+    CheckpointError(String),
+    /// TLS error: {0}
+    TlsError(String),
+    /// handshake challenge failed: {0}
+    ChallengeFailed(String),
+    /// Bootstrap failed because the bootstrap server currently has no slots available.
+    NoSlotAvailable,
 }
 
 /// # Platform-specific behavior
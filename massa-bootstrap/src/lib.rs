@@ -7,6 +7,55 @@
 //! On server side, the server will query consensus for the graph and the ledger,
 //! execution for execution related data and network for the peer list.
 //!
+//! The final blocks of the consensus graph are streamed along with the final state: the parts carry
+//! the missing blocks up to the slot of their state batch until the graph has caught up with it.
+//! The blocks required or outdated since then are sent once the final state has caught up too.
+//!
+//! The client saves its cursors after each part of the state it receives (see `resume.rs`):
+//! after a connection loss or a restart, it resumes the bootstrap from them with any server
+//! whose change history still covers the changes since the slot of the cursors.
+//!
+//! With `parallel_bootstrap_servers` set, the client first streams disjoint stripes of the state
+//! from several servers at once and merges them in its database. The first server of the list then
+//! streams the changes since the oldest stripe, the versioning and the consensus graph,
+//! and the state hash is checked against the merged entries.
+//!
+//! The client tells the server in the handshake whether it accepts zstd-compressed messages
+//! (see `compression_level`). The server messages are then signed once compressed, while their
+//! size limit still applies to the uncompressed bytes.
+//!
+//! With `bootstrap_snapshot_path` set, the client writes the state of a snapshot archive exported
+//! by a node of the bootstrap list (see `snapshot_archive.rs`), then only streams the changes since its slot.
+//!
+//! With `trusted_checkpoint` set, the client recomputes the hash of the bootstrapped state
+//! and checks it, or the state hash snapshot of the cycle of the checkpoint, against the checkpoint.
+//!
+//! The server shares its bandwidth between its sessions by weighted fair queuing (see `bandwidth.rs`),
+//! with a global cap, a cap per IP and a max session duration.
+//!
+//! With `tls_mode` set, the server also accepts connections wrapped in TLS (see `tls.rs`), which the client
+//! opens with `connect_with_tls`. The signatures of the bootstrap protocol still authenticate the server:
+//! TLS only keeps the network from observing the streamed state.
+//!
+//! The bindings read and write through a transport (see `transport.rs`): TCP, or the experimental QUIC
+//! transport (see `quic.rs`) the server accepts with `accept_quic` and the client uses with `connect_with_quic`,
+//! which keeps lossy long-distance links from drip-feeding the messages until the timeouts expire.
+//!
+//! With `challenge_mode` set, the server challenges the clients after the handshake with a proof of work
+//! or a pre-shared token (see `challenge.rs`), before it spends any bandwidth on them.
+//!
+//! A node whose MIP store diverged from the network while its state is still consistent can download
+//! the versioning alone with `resync_versioning`, instead of bootstrapping the whole state again.
+//!
+//! After a failed attempt, the client retries with an exponential backoff with jitter, from the servers
+//! that failed the least (see `retry.rs`), until `max_bootstrap_duration` has elapsed.
+//!
+//! With `audit_log_path` set, the server records each session and each refused connection in a rotating
+//! JSON lines file (see `audit.rs`), which the private API serves back to the operator.
+//!
+//! The progress of the client (phase, bytes downloaded, state keys received and estimated
+//! remaining share of the ledger) is sent on the channel passed to `get_state`.
+//!
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
@@ -19,27 +68,40 @@ use std::io::{self, ErrorKind};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+mod audit;
+mod bandwidth;
 mod bindings;
+mod challenge;
 mod client;
 mod error;
 pub use error::BootstrapError;
 mod listener;
 mod messages;
+mod progress;
+mod quic;
+mod resume;
+mod retry;
 mod server;
 mod settings;
+mod snapshot_archive;
+mod tls;
 mod tools;
+mod transport;
 /// white/black list
 pub mod white_black_list;
 
-pub use client::{get_state, DefaultConnector};
+pub use client::{get_state, resync_versioning, DefaultConnector};
 pub use listener::BootstrapTcpListener;
 pub use messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
     BootstrapServerMessage, BootstrapServerMessageDeserializer, BootstrapServerMessageSerializer,
 };
+pub use progress::{BootstrapPhase, BootstrapProgress};
+pub use resume::BOOTSTRAP_CURSOR_FILE;
 pub use server::{start_bootstrap_server, BootstrapManager};
-pub use settings::IpType;
-pub use settings::{BootstrapConfig, BootstrapServerMessageDeserializerArgs};
+pub use settings::{BootstrapChallengeMode, BootstrapTlsMode, IpType};
+pub use settings::{BootstrapConfig, BootstrapServerMessageDeserializerArgs, TrustedCheckpoint};
+pub use snapshot_archive::{export_snapshot_archive, SnapshotArchiveInfo, SNAPSHOT_ARCHIVE_MAGIC};
 
 #[cfg(test)]
 pub(crate) mod tests;
@@ -1,26 +1,39 @@
+use crossbeam::channel::{unbounded, Receiver};
 use mio::net::TcpListener;
 use mio::{Events, Interest, Poll, Token, Waker};
 use std::io::ErrorKind;
-use std::net::{SocketAddr, TcpStream};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tracing::{info, warn};
 
 use crate::error::BootstrapError;
+use crate::quic::{QuicListener, QuicStream};
 use crate::tools::mio_stream_to_std;
+use crate::transport::BootstrapStream;
 
 const NEW_CONNECTION: Token = Token(0);
-const STOP_LISTENER: Token = Token(10);
+/// woken up to stop the listener, or on new QUIC connections
+const WAKE_LISTENER: Token = Token(10);
 
 /// TODO: this should be crate-private. currently needed for models testing
 pub struct BootstrapTcpListener {
     poll: Poll,
     events: Events,
     server: TcpListener,
+    waker: Arc<Waker>,
+    stopped: Arc<AtomicBool>,
+    /// QUIC endpoint, and the connections it accepted
+    quic: Option<(QuicListener, Receiver<(QuicStream, SocketAddr)>)>,
 }
 
-pub struct BootstrapListenerStopHandle(Waker);
+pub struct BootstrapListenerStopHandle {
+    waker: Arc<Waker>,
+    stopped: Arc<AtomicBool>,
+}
 
 pub enum PollEvent {
-    NewConnections(Vec<(TcpStream, SocketAddr)>),
+    NewConnections(Vec<(Box<dyn BootstrapStream>, SocketAddr)>),
     Stop,
 }
 
@@ -57,7 +70,8 @@ impl BootstrapTcpListener {
         let poll = Poll::new()?;
 
         // wake up the poll when we want to stop the listener
-        let waker = BootstrapListenerStopHandle(Waker::new(poll.registry(), STOP_LISTENER)?);
+        let waker = Arc::new(Waker::new(poll.registry(), WAKE_LISTENER)?);
+        let stopped = Arc::new(AtomicBool::new(false));
 
         poll.registry()
             .register(&mut server, NEW_CONNECTION, Interest::READABLE)?;
@@ -65,15 +79,36 @@ impl BootstrapTcpListener {
         // TODO use config for capacity ?
         let events = Events::with_capacity(128);
         Ok((
-            waker,
+            BootstrapListenerStopHandle {
+                waker: waker.clone(),
+                stopped: stopped.clone(),
+            },
             BootstrapTcpListener {
                 poll,
                 server,
                 events,
+                waker,
+                stopped,
+                quic: None,
             },
         ))
     }
 
+    /// Also accepts the QUIC connections on the UDP port `addr` (experimental)
+    pub fn listen_quic(&mut self, addr: &SocketAddr) -> Result<(), BootstrapError> {
+        let (sender, receiver) = unbounded();
+        let waker = self.waker.clone();
+        let listener = QuicListener::bind(addr, move |stream, remote_addr| {
+            if sender.send((stream, remote_addr)).is_ok() {
+                if let Err(e) = waker.wake() {
+                    warn!("Error waking the bootstrap listener up: {:?}", e);
+                }
+            }
+        })?;
+        self.quic = Some((listener, receiver));
+        Ok(())
+    }
+
     pub(crate) fn poll(&mut self) -> Result<PollEvent, BootstrapError> {
         self.poll.poll(&mut self.events, None).unwrap();
 
@@ -88,7 +123,8 @@ impl BootstrapTcpListener {
                             let _ = self.poll.registry().deregister(&mut stream);
                             let stream: std::net::TcpStream = mio_stream_to_std(stream);
                             stream.set_nonblocking(false)?;
-                            results.push((stream, remote_addr));
+                            results
+                                .push((Box::new(stream) as Box<dyn BootstrapStream>, remote_addr));
                         }
                         Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
                             break;
@@ -99,8 +135,15 @@ impl BootstrapTcpListener {
                         }
                     }
                 },
-                STOP_LISTENER => {
-                    return Ok(PollEvent::Stop);
+                WAKE_LISTENER => {
+                    if self.stopped.load(Ordering::Acquire) {
+                        return Ok(PollEvent::Stop);
+                    }
+                    if let Some((_, receiver)) = &self.quic {
+                        results.extend(receiver.try_iter().map(|(stream, remote_addr)| {
+                            (Box::new(stream) as Box<dyn BootstrapStream>, remote_addr)
+                        }));
+                    }
                 }
                 _ => unreachable!(),
             }
@@ -113,6 +156,7 @@ impl BootstrapTcpListener {
 impl BootstrapListenerStopHandle {
     /// Stop the bootstrap listener.
     pub fn stop(&self) -> Result<(), BootstrapError> {
-        self.0.wake().map_err(BootstrapError::from)
+        self.stopped.store(true, Ordering::Release);
+        self.waker.wake().map_err(BootstrapError::from)
     }
 }
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::challenge::MAX_CHALLENGE_DIFFICULTY;
 use crate::settings::BootstrapServerMessageDeserializerArgs;
 use massa_consensus_exports::bootstrapable_graph::{
     BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
@@ -73,6 +74,15 @@ pub enum BootstrapServerMessage {
     BootstrapFinished,
     /// Slot sent to get state changes is too old
     SlotTooOld,
+    /// The change history covers the changes since the slot sent to resume the bootstrap
+    BootstrapResumeAccepted,
+    /// Challenge the client must answer before the bootstrap begins
+    BootstrapChallenge {
+        /// Random nonce of the challenge
+        nonce: Vec<u8>,
+        /// Leading zero bits of the proof of work, no proof of work is asked if 0
+        difficulty: u8,
+    },
     /// Bootstrap error
     BootstrapError {
         /// Error message
@@ -88,6 +98,12 @@ impl ToString for BootstrapServerMessage {
             BootstrapServerMessage::BootstrapPart { .. } => "BootstrapPart".to_string(),
             BootstrapServerMessage::BootstrapFinished => "BootstrapFinished".to_string(),
             BootstrapServerMessage::SlotTooOld => "SlotTooOld".to_string(),
+            BootstrapServerMessage::BootstrapResumeAccepted => {
+                "BootstrapResumeAccepted".to_string()
+            }
+            BootstrapServerMessage::BootstrapChallenge { difficulty, .. } => {
+                format!("BootstrapChallenge {{ difficulty: {} }}", difficulty)
+            }
             BootstrapServerMessage::BootstrapError { error } => {
                 format!("BootstrapError {{ error: {} }}", error)
             }
@@ -104,6 +120,8 @@ enum MessageServerTypeId {
     FinalStateFinished = 3u32,
     SlotTooOld = 4u32,
     BootstrapError = 5u32,
+    BootstrapResumeAccepted = 6u32,
+    BootstrapChallenge = 7u32,
 }
 
 /// Serializer for `BootstrapServerMessage`
@@ -257,6 +275,19 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::SlotTooOld), buffer)?;
             }
+            BootstrapServerMessage::BootstrapResumeAccepted => {
+                self.u32_serializer.serialize(
+                    &u32::from(MessageServerTypeId::BootstrapResumeAccepted),
+                    buffer,
+                )?;
+            }
+            BootstrapServerMessage::BootstrapChallenge { nonce, difficulty } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::BootstrapChallenge), buffer)?;
+                self.vec_u8_serializer.serialize(nonce, buffer)?;
+                self.u32_serializer
+                    .serialize(&u32::from(*difficulty), buffer)?;
+            }
             BootstrapServerMessage::BootstrapError { error } => {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::BootstrapError), buffer)?;
@@ -286,6 +317,7 @@ pub struct BootstrapServerMessageDeserializer {
     bootstrapable_graph_deserializer: BootstrapableGraphDeserializer,
     block_id_set_deserializer: PreHashSetDeserializer<BlockId, BlockIdDeserializer>,
     length_bootstrap_error: U64VarIntDeserializer,
+    challenge_difficulty_deserializer: U32VarIntDeserializer,
     slot_deserializer: SlotDeserializer,
     opt_last_start_period_deserializer: OptionDeserializer<u64, U64VarIntDeserializer>,
     opt_last_slot_before_downtime_deserializer:
@@ -328,6 +360,10 @@ impl BootstrapServerMessageDeserializer {
                 Included(0),
                 Included(args.max_bootstrap_error_length),
             ),
+            challenge_difficulty_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(u32::from(MAX_CHALLENGE_DIFFICULTY)),
+            ),
             state_new_elements_length_deserializer: U64VarIntDeserializer::new(
                 Included(0),
                 Included(args.max_new_elements),
@@ -564,6 +600,25 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     Ok((input, BootstrapServerMessage::BootstrapFinished))
                 }
                 MessageServerTypeId::SlotTooOld => Ok((input, BootstrapServerMessage::SlotTooOld)),
+                MessageServerTypeId::BootstrapResumeAccepted => {
+                    Ok((input, BootstrapServerMessage::BootstrapResumeAccepted))
+                }
+                MessageServerTypeId::BootstrapChallenge => tuple((
+                    context("Failed nonce deserialization", |input| {
+                        self.vec_u8_deserializer.deserialize(input)
+                    }),
+                    context("Failed difficulty deserialization", |input| {
+                        self.challenge_difficulty_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(nonce, difficulty)| BootstrapServerMessage::BootstrapChallenge {
+                        nonce,
+                        // bounded by MAX_CHALLENGE_DIFFICULTY
+                        difficulty: difficulty as u8,
+                    },
+                )
+                .parse(input),
                 MessageServerTypeId::BootstrapError => context(
                     "Failed BootstrapError deserialization",
                     length_data(context("Failed length deserialization", |input| {
@@ -599,6 +654,32 @@ pub enum BootstrapClientMessage {
         /// Should be true only for the first part, false later
         send_last_start_period: bool,
     },
+    /// Ask whether the change history covers the changes since `last_slot`,
+    /// to resume an interrupted bootstrap from its cursors
+    AskBootstrapResume {
+        /// Slot the bootstrapped state is attached to
+        last_slot: Slot,
+    },
+    /// Ask for a part of a stripe of the state, when bootstrapping from several servers in parallel
+    AskBootstrapStripe {
+        /// First key of the stripe
+        range_start: Vec<u8>,
+        /// Key ending the stripe (excluded), the stripe ends with the state if `None`
+        range_end: Option<Vec<u8>>,
+        /// Slot we are attached to for changes
+        last_slot: Option<Slot>,
+        /// Last received state key
+        last_state_step: StreamingStep<Vec<u8>>,
+    },
+    /// Ask for the versioning alone, to replace a MIP store that diverged from the network
+    AskBootstrapVersioning,
+    /// Answer to the challenge of the server
+    BootstrapChallengeResponse {
+        /// Solution of the proof of work, 0 if none was asked
+        solution: u64,
+        /// Proof that the client knows a token of the server
+        token_proof: Option<Vec<u8>>,
+    },
     /// Bootstrap error
     BootstrapError {
         /// Error message
@@ -615,12 +696,20 @@ enum MessageClientTypeId {
     AskFinalStatePart = 1u32,
     BootstrapError = 2u32,
     BootstrapSuccess = 3u32,
+    AskBootstrapResume = 4u32,
+    AskBootstrapStripe = 5u32,
+    BootstrapChallengeResponse = 6u32,
+    AskBootstrapVersioning = 7u32,
 }
 
 /// Serializer for `BootstrapClientMessage`
 pub struct BootstrapClientMessageSerializer {
     u32_serializer: U32VarIntSerializer,
+    u64_serializer: U64VarIntSerializer,
     slot_serializer: SlotSerializer,
+    opt_slot_serializer: OptionSerializer<Slot, SlotSerializer>,
+    key_serializer: VecU8Serializer,
+    opt_key_serializer: OptionSerializer<Vec<u8>, VecU8Serializer>,
     state_step_serializer: StreamingStepSerializer<Vec<u8>, VecU8Serializer>,
     block_ids_step_serializer: StreamingStepSerializer<
         PreHashSet<BlockId>,
@@ -634,7 +723,11 @@ impl BootstrapClientMessageSerializer {
     pub fn new() -> Self {
         Self {
             u32_serializer: U32VarIntSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
             slot_serializer: SlotSerializer::new(),
+            opt_slot_serializer: OptionSerializer::new(SlotSerializer::new()),
+            key_serializer: VecU8Serializer::new(),
+            opt_key_serializer: OptionSerializer::new(VecU8Serializer::new()),
             state_step_serializer: StreamingStepSerializer::new(VecU8Serializer::new()),
             block_ids_step_serializer: StreamingStepSerializer::new(PreHashSetSerializer::new(
                 BlockIdSerializer::new(),
@@ -710,6 +803,42 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageClientTypeId::BootstrapSuccess), buffer)?;
             }
+            BootstrapClientMessage::AskBootstrapResume { last_slot } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskBootstrapResume), buffer)?;
+                self.slot_serializer.serialize(last_slot, buffer)?;
+            }
+            BootstrapClientMessage::AskBootstrapStripe {
+                range_start,
+                range_end,
+                last_slot,
+                last_state_step,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskBootstrapStripe), buffer)?;
+                self.key_serializer.serialize(range_start, buffer)?;
+                self.opt_key_serializer.serialize(range_end, buffer)?;
+                self.opt_slot_serializer.serialize(last_slot, buffer)?;
+                self.state_step_serializer
+                    .serialize(last_state_step, buffer)?;
+            }
+            BootstrapClientMessage::AskBootstrapVersioning => {
+                self.u32_serializer.serialize(
+                    &u32::from(MessageClientTypeId::AskBootstrapVersioning),
+                    buffer,
+                )?;
+            }
+            BootstrapClientMessage::BootstrapChallengeResponse {
+                solution,
+                token_proof,
+            } => {
+                self.u32_serializer.serialize(
+                    &u32::from(MessageClientTypeId::BootstrapChallengeResponse),
+                    buffer,
+                )?;
+                self.u64_serializer.serialize(solution, buffer)?;
+                self.opt_key_serializer.serialize(token_proof, buffer)?;
+            }
         }
         Ok(())
     }
@@ -718,8 +847,12 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
 /// Deserializer for `BootstrapClientMessage`
 pub struct BootstrapClientMessageDeserializer {
     id_deserializer: U32VarIntDeserializer,
+    solution_deserializer: U64VarIntDeserializer,
     length_error_deserializer: U32VarIntDeserializer,
     slot_deserializer: SlotDeserializer,
+    opt_slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
+    key_deserializer: VecU8Deserializer,
+    opt_key_deserializer: OptionDeserializer<Vec<u8>, VecU8Deserializer>,
     state_step_deserializer: StreamingStepDeserializer<Vec<u8>, VecU8Deserializer>,
     block_ids_step_deserializer: StreamingStepDeserializer<
         PreHashSet<BlockId>,
@@ -737,11 +870,24 @@ impl BootstrapClientMessageDeserializer {
     ) -> Self {
         Self {
             id_deserializer: U32VarIntDeserializer::new(Included(0), Included(u32::MAX)),
+            solution_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
             length_error_deserializer: U32VarIntDeserializer::new(Included(0), Included(100000)),
             slot_deserializer: SlotDeserializer::new(
                 (Included(0), Included(u64::MAX)),
                 (Included(0), Excluded(thread_count)),
             ),
+            opt_slot_deserializer: OptionDeserializer::new(SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            )),
+            key_deserializer: VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_value_length as u64),
+            ),
+            opt_key_deserializer: OptionDeserializer::new(VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_value_length as u64),
+            )),
             state_step_deserializer: StreamingStepDeserializer::new(VecU8Deserializer::new(
                 Included(0),
                 Included(max_datastore_value_length as u64),
@@ -863,6 +1009,54 @@ impl Deserializer<BootstrapClientMessage> for BootstrapClientMessageDeserializer
                 MessageClientTypeId::BootstrapSuccess => {
                     Ok((input, BootstrapClientMessage::BootstrapSuccess))
                 }
+                MessageClientTypeId::AskBootstrapResume => {
+                    context("Failed last_slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    })
+                    .map(|last_slot| BootstrapClientMessage::AskBootstrapResume { last_slot })
+                    .parse(input)
+                }
+                MessageClientTypeId::AskBootstrapStripe => tuple((
+                    context("Failed range_start deserialization", |input| {
+                        self.key_deserializer.deserialize(input)
+                    }),
+                    context("Failed range_end deserialization", |input| {
+                        self.opt_key_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_slot deserialization", |input| {
+                        self.opt_slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_state_step deserialization", |input| {
+                        self.state_step_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(range_start, range_end, last_slot, last_state_step)| {
+                    BootstrapClientMessage::AskBootstrapStripe {
+                        range_start,
+                        range_end,
+                        last_slot,
+                        last_state_step,
+                    }
+                })
+                .parse(input),
+                MessageClientTypeId::AskBootstrapVersioning => {
+                    Ok((input, BootstrapClientMessage::AskBootstrapVersioning))
+                }
+                MessageClientTypeId::BootstrapChallengeResponse => tuple((
+                    context("Failed solution deserialization", |input| {
+                        self.solution_deserializer.deserialize(input)
+                    }),
+                    context("Failed token_proof deserialization", |input| {
+                        self.opt_key_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(solution, token_proof)| BootstrapClientMessage::BootstrapChallengeResponse {
+                        solution,
+                        token_proof,
+                    },
+                )
+                .parse(input),
             }
         })
         .parse(buffer)
@@ -0,0 +1,108 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Progress of the bootstrap of the client.
+//!
+//! `get_state` sends the progress of the bootstrap on the channel given by the node
+//! after each message received from a bootstrap server, so that a bootstrap
+//! lasting several minutes can be followed by the operator.
+
+use crossbeam::channel::Sender;
+use displaydoc::Display;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// Phase of the bootstrap of the client
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum BootstrapPhase {
+    /// handshake
+    Handshake,
+    /// versioning
+    Versioning,
+    /// state
+    State,
+    /// consensus
+    Consensus,
+    /// finished
+    Finished,
+}
+
+/// Progress of the bootstrap of the client
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootstrapProgress {
+    /// current phase
+    pub phase: BootstrapPhase,
+    /// bytes received from the bootstrap servers since the start of the bootstrap
+    pub bytes_downloaded: u64,
+    /// state keys received since the start of the bootstrap
+    pub keys_received: u64,
+    /// estimated share of the ledger that remains to be received, between 0 and 1.
+    /// Derived from the last key received, as the ledger keys are streamed in order
+    /// and the addresses are evenly spread in the keyspace.
+    pub estimated_remaining: Option<f64>,
+}
+
+impl std::fmt::Display for BootstrapProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "phase: {}, downloaded: {} bytes, state keys received: {}",
+            self.phase, self.bytes_downloaded, self.keys_received
+        )?;
+        if let Some(remaining) = self.estimated_remaining {
+            write!(f, ", ledger remaining: ~{:.1}%", remaining * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Updates the progress of the bootstrap and sends it on the progress channel.
+/// Cloned for each connection to a bootstrap server.
+#[derive(Clone)]
+pub(crate) struct BootstrapProgressReporter {
+    progress: Arc<Mutex<BootstrapProgress>>,
+    sender: Option<Sender<BootstrapProgress>>,
+}
+
+impl BootstrapProgressReporter {
+    pub(crate) fn new(sender: Option<Sender<BootstrapProgress>>) -> Self {
+        BootstrapProgressReporter {
+            progress: Arc::new(Mutex::new(BootstrapProgress {
+                phase: BootstrapPhase::Handshake,
+                bytes_downloaded: 0,
+                keys_received: 0,
+                estimated_remaining: None,
+            })),
+            sender,
+        }
+    }
+
+    /// Updates the progress and sends it.
+    /// The progress is dropped if the channel is full: the next one holds the same totals.
+    fn update<F: FnOnce(&mut BootstrapProgress)>(&self, f: F) {
+        let mut progress = self.progress.lock();
+        f(&mut progress);
+        if let Some(sender) = &self.sender {
+            let _ = sender.try_send(progress.clone());
+        }
+    }
+
+    pub(crate) fn set_phase(&self, phase: BootstrapPhase) {
+        self.update(|progress| progress.phase = phase);
+    }
+
+    pub(crate) fn add_bytes(&self, count: u64) {
+        self.update(|progress| {
+            progress.bytes_downloaded = progress.bytes_downloaded.saturating_add(count)
+        });
+    }
+
+    /// Accounts for the new keys of a part of the state, updating the estimated remaining share of the ledger if any
+    pub(crate) fn add_state_keys(&self, count: u64, estimated_remaining: Option<f64>) {
+        self.update(|progress| {
+            progress.keys_received = progress.keys_received.saturating_add(count);
+            if estimated_remaining.is_some() {
+                progress.estimated_remaining = estimated_remaining;
+            }
+        });
+    }
+}
@@ -0,0 +1,266 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Experimental QUIC transport of the bootstrap connections.
+//!
+//! The bootstrap session runs on one bidirectional QUIC stream, opened by the client. QUIC recovers
+//! the lost packets without stalling the stream as long as TCP, and paces the sending with the BBR
+//! congestion control, so that the lossy long-distance links no longer drip-feed the messages until
+//! the timeouts of the bindings expire.
+//!
+//! Like the TLS layer, the QUIC handshake does not authenticate the server: the client accepts any
+//! certificate and the server uses a self-signed one, while the bootstrap protocol signs the messages.
+//! The server listens on the UDP port of its bootstrap address.
+
+use crate::error::BootstrapError;
+use crate::tls::{client_tls_config, self_signed_certificate};
+use crate::transport::BootstrapStream;
+use futures::executor::block_on;
+use quinn::{
+    congestion::BbrConfig, ClientConfig, Connection, Endpoint, IdleTimeout, RecvStream, SendStream,
+    ServerConfig, TransportConfig,
+};
+use std::{
+    cell::Cell,
+    future::Future,
+    io::{self, ErrorKind, Read, Write},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::runtime::{Builder, Runtime};
+use tracing::{info, warn};
+
+/// Server name of the QUIC handshake: the client does not check the certificate
+const QUIC_SERVER_NAME: &str = "massa-bootstrap";
+
+/// Interval of the keep-alive packets, so that the connection is not closed while the peer is busy
+const QUIC_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Duration without any packet from the peer after which the connection is lost
+const QUIC_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Max duration of the QUIC handshake of an incoming connection
+const QUIC_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Max duration waited on drop for the peer to acknowledge the last bytes sent
+const QUIC_FINISH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runtime of the QUIC endpoints, shared by all the connections
+fn quic_runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("bootstrap-quic")
+            .enable_all()
+            .build()
+            .expect("could not start the QUIC runtime of the bootstrap")
+    })
+}
+
+fn transport_config() -> Arc<TransportConfig> {
+    let mut transport = TransportConfig::default();
+    transport
+        .max_idle_timeout(Some(
+            IdleTimeout::try_from(QUIC_IDLE_TIMEOUT).expect("invalid QUIC idle timeout"),
+        ))
+        .keep_alive_interval(Some(QUIC_KEEP_ALIVE_INTERVAL))
+        .congestion_controller_factory(Arc::new(BbrConfig::default()));
+    Arc::new(transport)
+}
+
+/// Blocking QUIC stream of a bootstrap connection
+pub(crate) struct QuicStream {
+    connection: Connection,
+    send: SendStream,
+    recv: RecvStream,
+    /// bytes read by `peek` and not consumed yet
+    peeked: Vec<u8>,
+    read_timeout: Cell<Option<Duration>>,
+    write_timeout: Cell<Option<Duration>>,
+    /// endpoint of a client connection, closed with it
+    _endpoint: Option<Endpoint>,
+}
+
+impl QuicStream {
+    fn new(
+        connection: Connection,
+        (send, recv): (SendStream, RecvStream),
+        endpoint: Option<Endpoint>,
+    ) -> Self {
+        QuicStream {
+            connection,
+            send,
+            recv,
+            peeked: Vec::new(),
+            read_timeout: Cell::new(None),
+            write_timeout: Cell::new(None),
+            _endpoint: endpoint,
+        }
+    }
+
+    /// Connects to the QUIC endpoint of a bootstrap server, and opens the stream of the session
+    pub(crate) fn connect(addr: SocketAddr, timeout: Option<Duration>) -> io::Result<Self> {
+        let runtime = quic_runtime();
+        let _guard = runtime.enter();
+        let bind_addr: SocketAddr = if addr.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let mut endpoint = Endpoint::client(bind_addr)?;
+        let mut config = ClientConfig::new(client_tls_config());
+        config.transport_config(transport_config());
+        endpoint.set_default_client_config(config);
+
+        let connecting = endpoint
+            .connect(addr, QUIC_SERVER_NAME)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))?;
+        let (connection, streams) = block_with_timeout(timeout, async {
+            let connection = connecting.await?;
+            let streams = connection.open_bi().await?;
+            Ok::<_, quinn::ConnectionError>((connection, streams))
+        })?
+        .map_err(io::Error::from)?;
+        Ok(QuicStream::new(connection, streams, Some(endpoint)))
+    }
+}
+
+/// Runs `future` to completion on the current thread, for at most `timeout`.
+/// The timer is driven by the QUIC runtime, so that this also works within another runtime.
+fn block_with_timeout<F: Future>(timeout: Option<Duration>, future: F) -> io::Result<F::Output> {
+    let _guard = quic_runtime().enter();
+    match timeout {
+        None => Ok(block_on(future)),
+        Some(timeout) => block_on(tokio::time::timeout(timeout, future))
+            .map_err(|_| io::Error::new(ErrorKind::TimedOut, "QUIC stream timed out")),
+    }
+}
+
+impl Read for QuicStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.peeked.is_empty() {
+            let len = buf.len().min(self.peeked.len());
+            buf[..len].copy_from_slice(&self.peeked[..len]);
+            self.peeked.drain(..len);
+            return Ok(len);
+        }
+        let read = block_with_timeout(self.read_timeout.get(), self.recv.read(buf))?
+            .map_err(io::Error::from)?;
+        // `None` once the peer has finished the stream
+        Ok(read.unwrap_or(0))
+    }
+}
+
+impl Write for QuicStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        block_with_timeout(self.write_timeout.get(), self.send.write(buf))?.map_err(io::Error::from)
+    }
+
+    /// The written bytes are sent by the QUIC runtime without any flush
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl BootstrapStream for QuicStream {
+    fn set_read_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        self.read_timeout.set(duration);
+        Ok(())
+    }
+
+    fn set_write_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        self.write_timeout.set(duration);
+        Ok(())
+    }
+
+    fn peer_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.connection.remote_address())
+    }
+
+    fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.peeked.is_empty() {
+            let mut bytes = vec![0u8; buf.len()];
+            let read = self.read(&mut bytes)?;
+            bytes.truncate(read);
+            self.peeked = bytes;
+        }
+        let len = buf.len().min(self.peeked.len());
+        buf[..len].copy_from_slice(&self.peeked[..len]);
+        Ok(len)
+    }
+
+    fn is_encrypted(&self) -> bool {
+        true
+    }
+}
+
+/// Closing the connection drops the bytes not received yet by the peer:
+/// waits for it to acknowledge them first.
+impl Drop for QuicStream {
+    fn drop(&mut self) {
+        let _ = block_with_timeout(Some(QUIC_FINISH_TIMEOUT), self.send.finish());
+        self.connection.close(0u32.into(), b"");
+    }
+}
+
+/// QUIC endpoint of a bootstrap server, accepting the connections until dropped
+pub(crate) struct QuicListener {
+    endpoint: Endpoint,
+}
+
+impl QuicListener {
+    /// Listens on the UDP port `addr`, and passes the stream of each new session to `on_connection`
+    pub(crate) fn bind<F>(addr: &SocketAddr, on_connection: F) -> Result<Self, BootstrapError>
+    where
+        F: Fn(QuicStream, SocketAddr) + Send + Sync + 'static,
+    {
+        let (certificates, private_key) = self_signed_certificate()?;
+        let mut config = ServerConfig::with_single_cert(certificates, private_key)
+            .map_err(|e| BootstrapError::TlsError(e.to_string()))?;
+        config.transport_config(transport_config());
+
+        let runtime = quic_runtime();
+        let endpoint = {
+            let _guard = runtime.enter();
+            Endpoint::server(config, *addr)?
+        };
+        info!("Starting bootstrap QUIC listener on {}", addr);
+
+        let on_connection = Arc::new(on_connection);
+        let accepting = endpoint.clone();
+        runtime.spawn(async move {
+            while let Some(connecting) = accepting.accept().await {
+                let on_connection = on_connection.clone();
+                tokio::spawn(async move {
+                    let remote_addr = connecting.remote_address();
+                    let accepted = tokio::time::timeout(QUIC_HANDSHAKE_TIMEOUT, async {
+                        let connection = connecting.await?;
+                        let streams = connection.accept_bi().await?;
+                        Ok::<_, quinn::ConnectionError>((connection, streams))
+                    })
+                    .await;
+                    match accepted {
+                        Ok(Ok((connection, streams))) => {
+                            on_connection(QuicStream::new(connection, streams, None), remote_addr)
+                        }
+                        Ok(Err(e)) => warn!(
+                            "Error accepting QUIC connection in bootstrap from {}: {}",
+                            remote_addr, e
+                        ),
+                        Err(_) => {
+                            warn!("QUIC handshake with {} timed out in bootstrap", remote_addr)
+                        }
+                    }
+                });
+            }
+        });
+        Ok(QuicListener { endpoint })
+    }
+}
+
+impl Drop for QuicListener {
+    fn drop(&mut self) {
+        self.endpoint.close(0u32.into(), b"");
+    }
+}
@@ -0,0 +1,150 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistence of the bootstrap cursors of the client.
+//!
+//! The cursors of the last part written to the state database are saved to a file,
+//! so that a bootstrap interrupted by a connection loss or a restart of the node
+//! resumes from them, possibly with another server, instead of starting from scratch.
+
+use crate::{
+    error::BootstrapError,
+    messages::{
+        BootstrapClientMessage, BootstrapClientMessageDeserializer,
+        BootstrapClientMessageSerializer,
+    },
+    BootstrapConfig,
+};
+use massa_db_exports::{ShareableMassaDBController, STATE_CF, VERSIONING_CF};
+use massa_models::streaming_step::StreamingStep;
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Name of the file holding the bootstrap cursors, in the state database directory
+pub const BOOTSTRAP_CURSOR_FILE: &str = "bootstrap_cursor";
+
+/// Saves and loads the bootstrap cursors of the client
+pub(crate) struct BootstrapCursorStore {
+    path: PathBuf,
+    serializer: BootstrapClientMessageSerializer,
+    deserializer: BootstrapClientMessageDeserializer,
+}
+
+impl BootstrapCursorStore {
+    /// Creates the store if a cursor file is configured
+    pub(crate) fn new(config: &BootstrapConfig) -> Option<Self> {
+        let path = config.bootstrap_cursor_path.clone()?;
+        Some(BootstrapCursorStore {
+            path,
+            serializer: BootstrapClientMessageSerializer::new(),
+            deserializer: BootstrapClientMessageDeserializer::new(
+                config.thread_count,
+                config.max_datastore_key_length,
+                config.max_consensus_block_ids,
+            ),
+        })
+    }
+
+    /// Saves the cursors of a `BootstrapClientMessage::AskBootstrapPart` attached to a slot.
+    /// The consensus cursor is not saved: the consensus graph is kept in memory only,
+    /// so it is streamed again when the bootstrap is resumed after a restart.
+    pub(crate) fn save(&self, message: &BootstrapClientMessage) -> Result<(), BootstrapError> {
+        let BootstrapClientMessage::AskBootstrapPart {
+            last_slot: Some(last_slot),
+            last_state_step,
+            last_versioning_step,
+            ..
+        } = message
+        else {
+            return Ok(());
+        };
+        let cursor = BootstrapClientMessage::AskBootstrapPart {
+            last_slot: Some(*last_slot),
+            last_state_step: last_state_step.clone(),
+            last_versioning_step: last_versioning_step.clone(),
+            last_consensus_step: StreamingStep::Started,
+            send_last_start_period: true,
+        };
+        let mut buffer = Vec::new();
+        self.serializer.serialize(&cursor, &mut buffer)?;
+
+        // write then rename, so that the cursor file is never partially written
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, buffer)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Loads the saved cursors, if they match the content of the state database
+    pub(crate) fn load(&self, db: &ShareableMassaDBController) -> Option<BootstrapClientMessage> {
+        if !self.path.exists() {
+            return None;
+        }
+        let cursor = match self.read_cursor() {
+            Ok(cursor) => cursor,
+            Err(err) => {
+                warn!("ignoring the saved bootstrap cursor: {}", err);
+                return None;
+            }
+        };
+        let BootstrapClientMessage::AskBootstrapPart {
+            last_slot: Some(last_slot),
+            last_state_step,
+            last_versioning_step,
+            ..
+        } = &cursor
+        else {
+            warn!("ignoring the saved bootstrap cursor: unexpected message");
+            return None;
+        };
+
+        // The cursor file is written after the part: check that the part was written to the db
+        let db = db.read();
+        let db_slot_matches = db.get_change_id().map_or(false, |slot| slot == *last_slot);
+        let key_written = |cf: &str, step: &StreamingStep<Vec<u8>>| match step {
+            StreamingStep::Ongoing(key) => matches!(db.get_cf(cf, key.clone()), Ok(Some(_))),
+            StreamingStep::Started | StreamingStep::Finished(_) => true,
+        };
+        if !db_slot_matches
+            || !key_written(STATE_CF, last_state_step)
+            || !key_written(VERSIONING_CF, last_versioning_step)
+        {
+            warn!("ignoring the saved bootstrap cursor: it does not match the state database");
+            return None;
+        }
+        Some(cursor)
+    }
+
+    /// Whether cursors were saved
+    pub(crate) fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Removes the saved cursors, once the bootstrap is finished or must start from scratch
+    pub(crate) fn clear(&self) {
+        if let Err(err) = remove_file_if_exists(&self.path) {
+            warn!("could not remove the bootstrap cursor file: {}", err);
+        }
+    }
+
+    fn read_cursor(&self) -> Result<BootstrapClientMessage, BootstrapError> {
+        let buffer = std::fs::read(&self.path)?;
+        let (rest, cursor) = self
+            .deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .map_err(|err| BootstrapError::DeserializeError(err.to_string()))?;
+        if !rest.is_empty() {
+            return Err(BootstrapError::DeserializeError(String::from(
+                "trailing bytes in the bootstrap cursor file",
+            )));
+        }
+        Ok(cursor)
+    }
+}
+
+fn remove_file_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,113 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Retry policy of the bootstrap client.
+//!
+//! After a failed attempt, the client waits for a delay doubled at each consecutive failure,
+//! from `retry_delay` up to `max_retry_delay`, and shortened by a random share of at most
+//! `retry_jitter` so that the nodes of a restarted network do not retry in lockstep.
+//!
+//! The next server is the one with the fewest failures, the least recently tried first:
+//! the dead servers of a large bootstrap list are thus tried less and less often.
+//!
+//! The bootstrap is given up once `max_bootstrap_duration` has elapsed, if it is not 0.
+
+use crate::error::BootstrapError;
+use crate::settings::BootstrapConfig;
+use humantime::format_duration;
+use massa_models::node::NodeId;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// Bootstrap server with its failures
+struct ServerScore {
+    addr: SocketAddr,
+    node_id: NodeId,
+    failures: u32,
+    /// index of the last attempt to bootstrap from this server
+    last_attempt: Option<u64>,
+}
+
+/// Picks the bootstrap servers and the delays between the attempts
+pub(crate) struct BootstrapRetryPolicy {
+    servers: Vec<ServerScore>,
+    attempts: u64,
+    consecutive_failures: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: f64,
+    max_duration: Option<Duration>,
+    started: Instant,
+    rng: StdRng,
+}
+
+impl BootstrapRetryPolicy {
+    /// Creates the policy of the servers of `bootstrap_list`, tried first in the order of the list
+    pub(crate) fn new(cfg: &BootstrapConfig, bootstrap_list: &[(SocketAddr, NodeId)]) -> Self {
+        let max_duration = cfg.max_bootstrap_duration.to_duration();
+        BootstrapRetryPolicy {
+            servers: bootstrap_list
+                .iter()
+                .map(|(addr, node_id)| ServerScore {
+                    addr: *addr,
+                    node_id: *node_id,
+                    failures: 0,
+                    last_attempt: None,
+                })
+                .collect(),
+            attempts: 0,
+            consecutive_failures: 0,
+            base_delay: cfg.retry_delay.to_duration(),
+            max_delay: cfg.max_retry_delay.to_duration(),
+            jitter: cfg.retry_jitter.clamp(0.0, 1.0),
+            max_duration: (!max_duration.is_zero()).then_some(max_duration),
+            started: Instant::now(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Server of the next attempt: the one with the fewest failures, the least recently tried first
+    pub(crate) fn next_server(&mut self) -> Result<(SocketAddr, NodeId), BootstrapError> {
+        if let Some(max_duration) = self.max_duration {
+            if self.started.elapsed() >= max_duration {
+                return Err(BootstrapError::GeneralError(format!(
+                    "bootstrap did not succeed within {}",
+                    format_duration(max_duration)
+                )));
+            }
+        }
+        let attempt = self.attempts;
+        let server = self
+            .servers
+            .iter_mut()
+            .min_by_key(|server| (server.failures, server.last_attempt))
+            .ok_or_else(|| {
+                BootstrapError::GeneralError("no bootstrap nodes found in list".into())
+            })?;
+        server.last_attempt = Some(attempt);
+        self.attempts += 1;
+        Ok((server.addr, server.node_id))
+    }
+
+    /// Accounts for a failed attempt with the server at `addr`,
+    /// and returns the delay to wait before the next attempt
+    pub(crate) fn record_failure(&mut self, addr: &SocketAddr) -> Duration {
+        if let Some(server) = self.servers.iter_mut().find(|server| &server.addr == addr) {
+            server.failures = server.failures.saturating_add(1);
+        }
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(self.consecutive_failures - 1))
+            .min(self.max_delay.max(self.base_delay));
+        let delay = backoff.mul_f64(1.0 - self.jitter * self.rng.gen::<f64>());
+        match self.max_duration {
+            // no need to wait past the end of the budget
+            Some(max_duration) => delay.min(max_duration.saturating_sub(self.started.elapsed())),
+            None => delay,
+        }
+    }
+}
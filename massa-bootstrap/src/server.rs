@@ -20,15 +20,19 @@
 //!
 //! 1. Checks if the stopper has been invoked.
 //! 2. Checks if the client is permited under the white/black list rules
-//! 3. Checks if there are not too many active sessions already
+//! 3. Checks if there are not too many active sessions already. With a challenge, only the sessions
+//!    whose answer was verified count toward `max_simultaneous_bootstraps`: the sessions waiting for
+//!    the answer of their client take one of the `max_simultaneous_challenges` slots meanwhile
 //! 4. Checks if the client has attempted too recently
 //! 5. All checks have passed: spawn a thread on which to run the bootstrap session
 //!    This thread creates a new tokio runtime, and runs it with `block_on`
+//!
+//! The sessions share the bandwidth of the server through the scheduler of `bandwidth.rs`.
 
 use crossbeam::channel::tick;
 use humantime::format_duration;
 use massa_consensus_exports::{bootstrapable_graph::BootstrapableGraph, ConsensusController};
-use massa_db_exports::CHANGE_ID_DESER_ERROR;
+use massa_db_exports::{StreamBatch, CHANGE_ID_DESER_ERROR, STATE_CF, VERSIONING_CF};
 use massa_final_state::FinalState;
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
@@ -45,7 +49,10 @@ use parking_lot::RwLock;
 use std::{
     collections::HashMap,
     net::{IpAddr, SocketAddr},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     thread,
     time::{Duration, Instant},
 };
@@ -56,10 +63,15 @@ use crate::listener::BootstrapTcpListener;
 #[cfg(test)]
 use crate::listener::MockBootstrapTcpListener as BootstrapTcpListener;
 use crate::{
+    audit::{BootstrapAuditLog, BootstrapLimit, BootstrapSessionAudit},
+    bandwidth::BandwidthScheduler,
     bindings::BootstrapServerBinder,
+    challenge::HandshakeChallenge,
     error::BootstrapError,
     listener::{BootstrapListenerStopHandle, PollEvent},
     messages::{BootstrapClientMessage, BootstrapServerMessage},
+    settings::BootstrapChallengeMode,
+    tls::ServerTls,
     white_black_list::SharedWhiteBlackList,
     BootstrapConfig,
 };
@@ -152,12 +164,28 @@ pub fn start_bootstrap_server(
             "Fail to convert u32 to usize".to_string(),
         ));
     };
+    let Ok(max_challenges) = config.max_simultaneous_challenges.try_into() else {
+        return Err(BootstrapError::GeneralError(
+            "Fail to convert u32 to usize".to_string(),
+        ));
+    };
+
+    let tls = ServerTls::new(
+        config.tls_mode,
+        config.tls_certificate_path.as_deref(),
+        config.tls_private_key_path.as_deref(),
+    )?;
 
     let white_black_list = SharedWhiteBlackList::new(
         config.bootstrap_whitelist_path.clone(),
         config.bootstrap_blacklist_path.clone(),
     )?;
 
+    let audit_log = config
+        .audit_log_path
+        .clone()
+        .map(|path| Arc::new(BootstrapAuditLog::new(path, config.audit_log_max_size)));
+
     let updater_lists = white_black_list.clone();
     let update_handle = thread::Builder::new()
         .name("wb_list_updater".to_string())
@@ -188,10 +216,17 @@ pub fn start_bootstrap_server(
                 keypair,
                 version,
                 ip_hist_map: HashMap::with_capacity(config.ip_list_max_size),
+                bandwidth_scheduler: BandwidthScheduler::new(
+                    config.global_rate_limit,
+                    config.per_ip_rate_limit,
+                    config.max_session_duration.to_duration(),
+                ),
+                tls,
+                audit_log,
                 bootstrap_config: config,
                 massa_metrics,
             }
-            .event_loop(max_bootstraps)
+            .event_loop(max_bootstraps, max_challenges)
         })
         .expect("in `start_bootstrap_server`, OS failed to spawn main-loop thread");
     // Give the runtime to the bootstrap manager, otherwise it will be dropped, forcibly aborting the spawned tasks.
@@ -215,6 +250,11 @@ struct BootstrapServer<'a> {
     bootstrap_config: BootstrapConfig,
     version: Version,
     ip_hist_map: HashMap<IpAddr, Instant>,
+    bandwidth_scheduler: Arc<BandwidthScheduler>,
+    /// TLS settings of the connections, if TLS is enabled
+    tls: Option<ServerTls>,
+    /// audit log of the sessions, if enabled
+    audit_log: Option<Arc<BootstrapAuditLog>>,
     massa_metrics: MassaMetrics,
 }
 
@@ -239,9 +279,16 @@ impl BootstrapServer<'_> {
         }
     }
 
-    fn event_loop(mut self, max_bootstraps: usize) -> Result<(), BootstrapError> {
-        // Use the strong-count of this variable to track the session count
-        let bootstrap_sessions_counter: Arc<()> = Arc::new(());
+    fn event_loop(
+        mut self,
+        max_bootstraps: usize,
+        max_challenges: usize,
+    ) -> Result<(), BootstrapError> {
+        let bootstrap_slots = SessionSlots::new(max_bootstraps);
+        // With a challenge, the sessions take a bootstrap slot once the answer of their client is verified
+        let challenge_slots = (self.bootstrap_config.challenge_mode
+            != BootstrapChallengeMode::Disabled)
+            .then(|| SessionSlots::new(max_challenges));
         let per_ip_min_interval = self.bootstrap_config.per_ip_min_interval.to_duration();
         // TODO: Work out how to integration-test this
         let limit = self.bootstrap_config.rate_limit;
@@ -262,15 +309,21 @@ impl BootstrapServer<'_> {
 
             for (dplx, remote_addr) in connections {
                 // claim a slot in the max_bootstrap_sessions
-                let server_binding = BootstrapServerBinder::new(
+                let mut server_binding = BootstrapServerBinder::new(
                     dplx,
                     self.keypair.clone(),
                     (&self.bootstrap_config).into(),
                     Some(limit),
                 );
+                server_binding.set_tls(self.tls.clone());
 
                 // check whether incoming peer IP is allowed.
                 if let Err(error_msg) = self.white_black_list.is_ip_allowed(&remote_addr) {
+                    let limit = match &error_msg {
+                        BootstrapError::WhiteListed(_) => BootstrapLimit::Whitelist,
+                        _ => BootstrapLimit::Blacklist,
+                    };
+                    self.audit_refusal(remote_addr, error_msg.to_string(), limit);
                     server_binding.close_and_send_error(
                         error_msg.to_string(),
                         remote_addr,
@@ -280,13 +333,12 @@ impl BootstrapServer<'_> {
                     continue;
                 };
 
-                // the `- 1` is to account for the top-level Arc that is created at the top
-                // of this method. subsequent counts correspond to each `clone` that is passed
-                // into a thread
-                // TODO: If we don't find a way to handle the counting automagically, make
-                //       a dedicated wrapper-type with doc-comments, manual drop impl that
-                //       integrates logging, etc...
-                if Arc::strong_count(&bootstrap_sessions_counter) - 1 < max_bootstraps {
+                // the slot is released when the session ends
+                let session_slot = match &challenge_slots {
+                    Some(challenge_slots) => challenge_slots.try_take(),
+                    None => bootstrap_slots.try_take(),
+                };
+                if let Some(session_slot) = session_slot {
                     massa_trace!("bootstrap.lib.run.select.accept", {
                         "remote_addr": remote_addr
                     });
@@ -321,8 +373,14 @@ impl BootstrapServer<'_> {
                                 "remote_addr": remote_addr
                             })
                         };
+                        self.audit_refusal(
+                            remote_addr,
+                            msg.clone(),
+                            BootstrapLimit::PerIpMinInterval,
+                        );
                         server_binding.close_and_send_error(msg, remote_addr, tracer);
                         self.massa_metrics.inc_bootstrap_peers_failed();
+                        self.massa_metrics.inc_bootstrap_cooldown_refusals();
                         continue;
                     };
 
@@ -330,21 +388,27 @@ impl BootstrapServer<'_> {
                     massa_trace!("bootstrap.lib.run.select.accept.cache_available", {});
 
                     // launch bootstrap
+                    server_binding.set_bandwidth_session(Some(
+                        self.bandwidth_scheduler.open_session(remote_addr.ip()),
+                    ));
                     let version = self.version;
                     let data_execution = self.final_state.clone();
                     let consensus_command_sender = self.consensus_controller.clone();
                     let protocol_controller = self.protocol_controller.clone();
                     let config = self.bootstrap_config.clone();
 
-                    let bootstrap_count_token = bootstrap_sessions_counter.clone();
+                    let session_bootstrap_slots = bootstrap_slots.clone();
+                    self.massa_metrics.inc_bootstrap_sessions_started();
                     let massa_metrics = self.massa_metrics.clone();
+                    let audit_log = self.audit_log.clone();
 
                     let _ = thread::Builder::new()
                         .name(format!("bootstrap thread, peer: {}", remote_addr))
                         .spawn(move || {
                             run_bootstrap_session(
                                 server_binding,
-                                bootstrap_count_token,
+                                session_slot,
+                                session_bootstrap_slots,
                                 config,
                                 remote_addr,
                                 data_execution,
@@ -352,24 +416,41 @@ impl BootstrapServer<'_> {
                                 consensus_command_sender,
                                 protocol_controller,
                                 massa_metrics,
+                                audit_log,
                             )
                         });
 
                     massa_trace!("bootstrap.session.started", {
-                        "active_count": Arc::strong_count(&bootstrap_sessions_counter) - 1
+                        "active_count": bootstrap_slots.taken()
                     });
                 } else {
-                    server_binding.close_and_send_error(
-                        "Bootstrap failed because the bootstrap server currently has no slots available.".to_string(),
-                        remote_addr,
-                        move || debug!("did not bootstrap {}: no available slots", remote_addr),
-                    );
+                    let msg = BootstrapError::NoSlotAvailable.to_string();
+                    let limit = if challenge_slots.is_some() {
+                        BootstrapLimit::MaxSimultaneousChallenges
+                    } else {
+                        BootstrapLimit::MaxSimultaneousBootstraps
+                    };
+                    self.audit_refusal(remote_addr, msg.clone(), limit);
+                    server_binding.close_and_send_error(msg, remote_addr, move || {
+                        debug!("did not bootstrap {}: no available slots", remote_addr)
+                    });
                     self.massa_metrics.inc_bootstrap_peers_failed();
                 }
             }
         }
     }
 
+    /// Records a connection refused because of `limit` in the audit log
+    fn audit_refusal(&self, remote_addr: SocketAddr, reason: String, limit: BootstrapLimit) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(&BootstrapSessionAudit::refused(
+                remote_addr.ip(),
+                reason,
+                limit,
+            ));
+        }
+    }
+
     /// Checks latest attempt. If too recent, provides the bad news (as an error).
     /// Updates the latest attempt to "now" if it's all good.
     ///
@@ -398,18 +479,59 @@ impl BootstrapServer<'_> {
     }
 }
 
+/// Limited number of slots shared by the bootstrap sessions
+#[derive(Clone)]
+struct SessionSlots {
+    taken: Arc<AtomicUsize>,
+    max: usize,
+}
+
+/// Slot taken by a session, released when dropped
+struct SessionSlot(Arc<AtomicUsize>);
+
+impl SessionSlots {
+    fn new(max: usize) -> Self {
+        SessionSlots {
+            taken: Arc::new(AtomicUsize::new(0)),
+            max,
+        }
+    }
+
+    /// Takes a slot if one is free
+    fn try_take(&self) -> Option<SessionSlot> {
+        self.taken
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |taken| {
+                (taken < self.max).then_some(taken + 1)
+            })
+            .ok()
+            .map(|_| SessionSlot(self.taken.clone()))
+    }
+
+    /// Number of slots taken
+    fn taken(&self) -> usize {
+        self.taken.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for SessionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// To be called from a `thread::spawn` invocation
 ///
 /// Runs the bootstrap management in a dedicated thread, handling the async by using
 /// a multi-thread-aware tokio runtime (the bs-main-loop runtime, to be exact). When this
 /// function blocks in the `block_on`, it should thread-block, and switch to another session
 ///
-/// The arc_counter variable is used as a proxy to keep track the number of active bootstrap
-/// sessions.
+/// `session_slot` is the slot taken by the session when the connection was accepted:
+/// a challenge slot if the client has to answer a challenge, a bootstrap slot otherwise.
 #[allow(clippy::too_many_arguments)]
 fn run_bootstrap_session(
     mut server: BootstrapServerBinder,
-    arc_counter: Arc<()>,
+    session_slot: SessionSlot,
+    bootstrap_slots: SessionSlots,
     config: BootstrapConfig,
     remote_addr: SocketAddr,
     data_execution: Arc<RwLock<FinalState>>,
@@ -417,26 +539,49 @@ fn run_bootstrap_session(
     consensus_command_sender: Box<dyn ConsensusController>,
     protocol_controller: Box<dyn ProtocolController>,
     massa_metrics: MassaMetrics,
+    audit_log: Option<Arc<BootstrapAuditLog>>,
 ) {
     debug!("running bootstrap for peer {}", remote_addr);
-    let deadline = Instant::now() + config.bootstrap_timeout.to_duration();
+    let start = Instant::now();
+    let deadline = start + config.bootstrap_timeout.to_duration();
     // TODO: reinstate prevention of bootstrap slot camping. Deadline cancellation is one option
     let res = manage_bootstrap(
         &config,
         &mut server,
+        session_slot,
+        &bootstrap_slots,
         data_execution,
         version,
         consensus_command_sender,
         protocol_controller,
         deadline,
+        &massa_metrics,
     );
 
-    // This drop allows the server to accept new connections before having to complete the error notifications
-    // account for this session being finished, as well as the root-instance
+    // The slot of the session was released when `manage_bootstrap` returned,
+    // so that the server accepts new connections before having to complete the error notifications
     massa_trace!("bootstrap.session.finished", {
-        "sessions_remaining": Arc::strong_count(&arc_counter) - 2
+        "sessions_remaining": bootstrap_slots.taken()
     });
-    drop(arc_counter);
+    massa_metrics.dec_bootstrap_sessions_active();
+    // Release the bandwidth of the session, the error notifications are not scheduled
+    server.set_bandwidth_session(None);
+    let termination_reason = match &res {
+        Ok(_) => "success".to_string(),
+        Err(err) => err.to_string(),
+    };
+    let mut violated_limits = Vec::new();
+    match &res {
+        Err(BootstrapError::TimedOut(_)) => violated_limits.push(BootstrapLimit::Timeout),
+        Err(BootstrapError::ChallengeFailed(_)) => violated_limits.push(BootstrapLimit::Challenge),
+        Err(BootstrapError::NoSlotAvailable) => {
+            violated_limits.push(BootstrapLimit::MaxSimultaneousBootstraps)
+        }
+        _ => {}
+    }
+    if res.is_err() && start.elapsed() > config.max_session_duration.to_duration() {
+        violated_limits.push(BootstrapLimit::MaxSessionDuration);
+    }
     match res {
         Err(BootstrapError::TimedOut(_)) => {
             debug!("bootstrap timeout for peer {}", remote_addr);
@@ -447,6 +592,7 @@ fn run_bootstrap_session(
                 format_duration(config.bootstrap_timeout.to_duration())
             ));
             massa_metrics.inc_bootstrap_peers_failed();
+            massa_metrics.inc_bootstrap_sessions_failed(start.elapsed());
         }
         Err(BootstrapError::ReceivedError(error)) => {
             debug!(
@@ -454,6 +600,7 @@ fn run_bootstrap_session(
                 remote_addr, error
             );
             massa_metrics.inc_bootstrap_peers_failed();
+            massa_metrics.inc_bootstrap_sessions_failed(start.elapsed());
         }
         Err(err) => {
             debug!("bootstrap serving error for peer {}: {}", remote_addr, err);
@@ -461,12 +608,23 @@ fn run_bootstrap_session(
             // sending the error message to the server we will close the socket anyway.
             let _ = server.send_error_timeout(err.to_string());
             massa_metrics.inc_bootstrap_peers_failed();
+            massa_metrics.inc_bootstrap_sessions_failed(start.elapsed());
         }
         Ok(_) => {
             info!("bootstrapped peer {}", remote_addr);
             massa_metrics.inc_bootstrap_peers_success();
+            massa_metrics.inc_bootstrap_sessions_completed(start.elapsed());
         }
     }
+    if let Some(audit_log) = audit_log {
+        audit_log.record(&BootstrapSessionAudit::session(
+            remote_addr.ip(),
+            start.elapsed(),
+            server.bytes_sent(),
+            termination_reason,
+            violated_limits,
+        ));
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -481,7 +639,10 @@ pub fn stream_bootstrap_information(
     mut send_last_start_period: bool,
     bs_deadline: &Instant,
     write_timeout: Duration,
+    massa_metrics: &MassaMetrics,
 ) -> Result<(), BootstrapError> {
+    // Whether every block required up to the slot of the final state has already been streamed
+    let mut consensus_caught_up = false;
     loop {
         #[cfg(test)]
         {
@@ -490,12 +651,18 @@ pub fn stream_bootstrap_information(
         }
 
         let current_slot;
-        let state_part;
-        let versioning_part;
+        let mut state_part;
+        let mut versioning_part;
         let last_start_period;
         let last_slot_before_downtime;
+        let final_state_caught_up;
+
+        // The client has to restart from scratch if the changes since `last_slot` are missing
+        let slot_too_old = last_slot.map_or(false, |slot| is_slot_too_old(&final_state, slot));
 
-        let slot_too_old = false;
+        if slot_too_old {
+            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+        }
 
         // Scope of the final state read
         {
@@ -613,40 +780,64 @@ pub fn stream_bootstrap_information(
                 }
             }
 
+            // The updates of a batch may stop before db_slot to limit its size:
+            // the next batch continues from the oldest slot reached by the parts
+            let batch_slot = std::cmp::min(state_part.change_id, versioning_part.change_id);
+            state_part.change_id = batch_slot;
+            versioning_part.change_id = batch_slot;
+
             // Update cursors for next turn
             last_state_step = new_state_step;
             last_versioning_step = new_versioning_step;
-            last_slot = Some(db_slot);
-            current_slot = db_slot;
+            last_slot = Some(batch_slot);
+            current_slot = batch_slot;
+            final_state_caught_up = batch_slot == db_slot;
             send_last_start_period = false;
         }
 
-        if slot_too_old {
-            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
-        }
-
         // Setup final state global cursor
-        let final_state_global_step =
-            if last_state_step.finished() && last_versioning_step.finished() {
-                StreamingStep::Finished(Some(current_slot))
+        let final_state_global_step = if last_state_step.finished()
+            && last_versioning_step.finished()
+            && final_state_caught_up
+        {
+            StreamingStep::Finished(Some(current_slot))
+        } else {
+            StreamingStep::Ongoing(current_slot)
+        };
+
+        // Stream the consensus blocks along with the final state, up to its slot:
+        // the blocks that are no longer required once the final state moves on are sent as outdated.
+        // Once the graph has caught up with the final state, the blocks required by the slots
+        // streamed in the meantime are only fetched when the final state has caught up too,
+        // instead of locking the consensus state for every state batch.
+        let (consensus_part, consensus_outdated_ids) =
+            if consensus_caught_up && !final_state_global_step.finished() {
+                (
+                    BootstrapableGraph {
+                        final_blocks: Default::default(),
+                    },
+                    PreHashSet::default(),
+                )
             } else {
-                StreamingStep::Ongoing(current_slot)
+                let (part, outdated_ids, new_consensus_step) = consensus_controller
+                    .get_bootstrap_part(last_consensus_step.clone(), final_state_global_step)?;
+                consensus_caught_up = new_consensus_step.finished();
+                last_consensus_step = match new_consensus_step {
+                    // No block is missing up to the slot of the final state,
+                    // but the final state has not caught up yet: the next parts may require new blocks
+                    StreamingStep::Finished(_) if !final_state_global_step.finished() => {
+                        match last_consensus_step {
+                            StreamingStep::Ongoing(ids) => StreamingStep::Ongoing(
+                                ids.difference(&outdated_ids).cloned().collect(),
+                            ),
+                            step => step,
+                        }
+                    }
+                    step => step,
+                };
+                (part, outdated_ids)
             };
 
-        // Stream consensus blocks if final state base bootstrap is finished
-        let mut consensus_part = BootstrapableGraph {
-            final_blocks: Default::default(),
-        };
-        let mut consensus_outdated_ids: PreHashSet<BlockId> = PreHashSet::default();
-
-        if final_state_global_step.finished() {
-            let (part, outdated_ids, new_consensus_step) = consensus_controller
-                .get_bootstrap_part(last_consensus_step, final_state_global_step)?;
-            consensus_part = part;
-            consensus_outdated_ids = outdated_ids;
-            last_consensus_step = new_consensus_step;
-        }
-
         // Logs for an easier diagnostic if needed
         debug!(
             "Final state bootstrap cursor: {:?}",
@@ -673,6 +864,9 @@ pub fn stream_bootstrap_information(
             ));
         };
         // At this point we know that consensus, final state or both are not finished
+        massa_metrics.inc_bootstrap_streamed_bytes(STATE_CF, stream_batch_size(&state_part));
+        massa_metrics
+            .inc_bootstrap_streamed_bytes(VERSIONING_CF, stream_batch_size(&versioning_part));
         server.send_msg(
             write_timeout,
             BootstrapServerMessage::BootstrapPart {
@@ -689,6 +883,212 @@ pub fn stream_bootstrap_information(
     Ok(())
 }
 
+/// Streams a stripe of the state, restricted to the keys from `range_start` included to `range_end` excluded,
+/// to a client bootstrapping from several servers in parallel.
+/// The parts only carry the state: the versioning and the consensus graph are streamed by the main server.
+#[allow(clippy::too_many_arguments)]
+fn stream_bootstrap_stripe(
+    server: &mut BootstrapServerBinder,
+    final_state: Arc<RwLock<FinalState>>,
+    range_start: Vec<u8>,
+    range_end: Option<Vec<u8>>,
+    mut last_slot: Option<Slot>,
+    mut last_state_step: StreamingStep<Vec<u8>>,
+    bs_deadline: &Instant,
+    write_timeout: Duration,
+    massa_metrics: &MassaMetrics,
+) -> Result<(), BootstrapError> {
+    loop {
+        if last_slot.map_or(false, |slot| is_slot_too_old(&final_state, slot)) {
+            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+        }
+
+        // At least one part is sent, so that the client knows the slot of the stripe even if it is empty
+        let attached = last_slot.is_some();
+        let state_part;
+        let current_slot;
+        let final_state_caught_up;
+
+        // Scope of the final state read
+        {
+            let final_state_read = final_state.read();
+            let db = final_state_read.db.read();
+
+            let db_slot = db.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+            if let Some(slot) = last_slot {
+                if slot > db_slot {
+                    return Err(BootstrapError::GeneralError(
+                        "Bootstrap cursor set to future slot".to_string(),
+                    ));
+                }
+            }
+
+            state_part = db
+                .get_stripe_batch_to_stream(
+                    &range_start,
+                    range_end.as_deref(),
+                    &last_state_step,
+                    last_slot,
+                )
+                .map_err(|e| {
+                    BootstrapError::GeneralError(format!("Error get_stripe_batch_to_stream: {}", e))
+                })?;
+
+            // A stripe may hold no key at all: it is finished as soon as its range is exhausted
+            last_state_step = match (&last_state_step, state_part.new_elements.last_key_value()) {
+                (StreamingStep::Finished(_), _) | (_, None) => StreamingStep::Finished(None),
+                (_, Some((new_last_key, _))) => StreamingStep::Ongoing(new_last_key.clone()),
+            };
+            current_slot = state_part.change_id;
+            last_slot = Some(current_slot);
+            final_state_caught_up = current_slot == db_slot;
+        }
+
+        if attached && last_state_step.finished() && final_state_caught_up && state_part.is_empty()
+        {
+            server.send_msg(write_timeout, BootstrapServerMessage::BootstrapFinished)?;
+            break;
+        }
+
+        let Some(write_timeout) = step_timeout_duration(bs_deadline, &write_timeout) else {
+            return Err(BootstrapError::Interupted(
+                "insufficient time left to provide next bootstrap stripe part".to_string(),
+            ));
+        };
+        massa_metrics.inc_bootstrap_streamed_bytes(STATE_CF, stream_batch_size(&state_part));
+        server.send_msg(
+            write_timeout,
+            BootstrapServerMessage::BootstrapPart {
+                slot: current_slot,
+                state_part,
+                versioning_part: StreamBatch {
+                    new_elements: Default::default(),
+                    updates_on_previous_elements: Default::default(),
+                    change_id: current_slot,
+                },
+                consensus_part: BootstrapableGraph {
+                    final_blocks: Default::default(),
+                },
+                consensus_outdated_ids: PreHashSet::default(),
+                last_start_period: None,
+                last_slot_before_downtime: None,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Streams the versioning column family alone, to a client whose MIP store diverged from the network.
+/// The parts only carry the versioning: the state and the consensus graph of the client are kept.
+fn stream_bootstrap_versioning(
+    server: &mut BootstrapServerBinder,
+    final_state: Arc<RwLock<FinalState>>,
+    bs_deadline: &Instant,
+    write_timeout: Duration,
+    massa_metrics: &MassaMetrics,
+) -> Result<(), BootstrapError> {
+    let mut last_slot = None;
+    let mut last_versioning_step = StreamingStep::Started;
+    loop {
+        if last_slot.map_or(false, |slot| is_slot_too_old(&final_state, slot)) {
+            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+        }
+
+        let attached = last_slot.is_some();
+        let versioning_part;
+        let current_slot;
+        let final_state_caught_up;
+
+        // Scope of the final state read
+        {
+            let final_state_read = final_state.read();
+            let db = final_state_read.db.read();
+
+            versioning_part = db
+                .get_versioning_batch_to_stream(&last_versioning_step, last_slot)
+                .map_err(|e| {
+                    BootstrapError::GeneralError(format!(
+                        "Error get_versioning_batch_to_stream: {}",
+                        e
+                    ))
+                })?;
+
+            last_versioning_step = match (
+                &last_versioning_step,
+                versioning_part.new_elements.last_key_value(),
+            ) {
+                (StreamingStep::Finished(_), _) | (_, None) => StreamingStep::Finished(None),
+                (_, Some((new_last_key, _))) => StreamingStep::Ongoing(new_last_key.clone()),
+            };
+            current_slot = versioning_part.change_id;
+            last_slot = Some(current_slot);
+            final_state_caught_up =
+                current_slot == db.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+        }
+
+        if attached
+            && last_versioning_step.finished()
+            && final_state_caught_up
+            && versioning_part.is_empty()
+        {
+            server.send_msg(write_timeout, BootstrapServerMessage::BootstrapFinished)?;
+            break;
+        }
+
+        let Some(write_timeout) = step_timeout_duration(bs_deadline, &write_timeout) else {
+            return Err(BootstrapError::Interupted(
+                "insufficient time left to provide next bootstrap versioning part".to_string(),
+            ));
+        };
+        massa_metrics
+            .inc_bootstrap_streamed_bytes(VERSIONING_CF, stream_batch_size(&versioning_part));
+        server.send_msg(
+            write_timeout,
+            BootstrapServerMessage::BootstrapPart {
+                slot: current_slot,
+                state_part: StreamBatch {
+                    new_elements: Default::default(),
+                    updates_on_previous_elements: Default::default(),
+                    change_id: current_slot,
+                },
+                versioning_part,
+                consensus_part: BootstrapableGraph {
+                    final_blocks: Default::default(),
+                },
+                consensus_outdated_ids: PreHashSet::default(),
+                last_start_period: None,
+                last_slot_before_downtime: None,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Size of the keys and values of a batch of the final state, in bytes
+fn stream_batch_size(batch: &StreamBatch<Slot>) -> u64 {
+    let new_elements_size = batch
+        .new_elements
+        .iter()
+        .map(|(key, value)| key.len() + value.len());
+    let updates_size = batch
+        .updates_on_previous_elements
+        .iter()
+        .map(|(key, value)| key.len() + value.as_ref().map_or(0, Vec::len));
+    new_elements_size.chain(updates_size).sum::<usize>() as u64
+}
+
+/// Whether the changes since `slot` have been evicted from the change history
+/// (by count or by memory footprint), so that a client attached to `slot` cannot catch up
+fn is_slot_too_old(final_state: &RwLock<FinalState>, slot: Slot) -> bool {
+    let final_state_read = final_state.read();
+    let db = final_state_read.db.read();
+    let db_slot = db.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+    slot < db_slot
+        && db
+            .get_change_history_horizon()
+            .map_or(true, |horizon| slot < horizon)
+}
+
 // derives the duration allowed for a step in the bootstrap process.
 // Returns None if the deadline for the entire bs-process has been reached
 fn step_timeout_duration(bs_deadline: &Instant, step_timeout: &Duration) -> Option<Duration> {
@@ -700,15 +1100,61 @@ fn step_timeout_duration(bs_deadline: &Instant, step_timeout: &Duration) -> Opti
     let remaining = *bs_deadline - now;
     Some(std::cmp::min(remaining, *step_timeout))
 }
+
+/// Sends a challenge to the client and checks its answer, before any bootstrap data is sent to it
+fn challenge_client(
+    bootstrap_config: &BootstrapConfig,
+    server: &mut BootstrapServerBinder,
+    deadline: &Instant,
+) -> Result<(), BootstrapError> {
+    let challenge = HandshakeChallenge::new(
+        bootstrap_config.challenge_mode,
+        bootstrap_config.challenge_difficulty,
+    );
+    let Some(write_timeout) =
+        step_timeout_duration(deadline, &bootstrap_config.write_timeout.to_duration())
+    else {
+        return Err(BootstrapError::Interupted(
+            "insufficient time left to send the challenge".to_string(),
+        ));
+    };
+    server.send_msg(write_timeout, challenge.message())?;
+
+    let Some(read_timeout) =
+        step_timeout_duration(deadline, &bootstrap_config.challenge_timeout.to_duration())
+    else {
+        return Err(BootstrapError::Interupted(
+            "insufficient time left to receive the answer to the challenge".to_string(),
+        ));
+    };
+    match server.next_timeout(Some(read_timeout))? {
+        BootstrapClientMessage::BootstrapChallengeResponse {
+            solution,
+            token_proof,
+        } => challenge.check(
+            solution,
+            token_proof.as_deref(),
+            &bootstrap_config.challenge_tokens,
+        ),
+        BootstrapClientMessage::BootstrapError { error } => {
+            Err(BootstrapError::ReceivedError(error))
+        }
+        msg => Err(BootstrapError::UnexpectedClientMessage(Box::new(msg))),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn manage_bootstrap(
     bootstrap_config: &BootstrapConfig,
     server: &mut BootstrapServerBinder,
+    session_slot: SessionSlot,
+    bootstrap_slots: &SessionSlots,
     final_state: Arc<RwLock<FinalState>>,
     version: Version,
     consensus_controller: Box<dyn ConsensusController>,
     protocol_controller: Box<dyn ProtocolController>,
     deadline: Instant,
+    massa_metrics: &MassaMetrics,
 ) -> Result<(), BootstrapError> {
     massa_trace!("bootstrap.lib.manage_bootstrap", {});
     let read_error_timeout: Duration = bootstrap_config.read_error_timeout.into();
@@ -738,6 +1184,18 @@ fn manage_bootstrap(
         Ok(msg) => return Err(BootstrapError::UnexpectedClientMessage(Box::new(msg))),
     };
 
+    // Challenge the client before spending bandwidth on it,
+    // it only takes a bootstrap slot once its answer is verified
+    let _session_slot = if bootstrap_config.challenge_mode != BootstrapChallengeMode::Disabled {
+        challenge_client(bootstrap_config, server, &deadline)?;
+        drop(session_slot);
+        bootstrap_slots
+            .try_take()
+            .ok_or(BootstrapError::NoSlotAvailable)?
+    } else {
+        session_slot
+    };
+
     // Sync clocks
     let send_time_timeout =
         step_timeout_duration(&deadline, &bootstrap_config.write_timeout.to_duration());
@@ -801,12 +1259,71 @@ fn manage_bootstrap(
                         send_last_start_period,
                         &deadline,
                         bootstrap_config.write_timeout.to_duration(),
+                        massa_metrics,
                     )?;
                 }
+                BootstrapClientMessage::AskBootstrapStripe {
+                    range_start,
+                    range_end,
+                    last_slot,
+                    last_state_step,
+                } => {
+                    stream_bootstrap_stripe(
+                        server,
+                        final_state.clone(),
+                        range_start,
+                        range_end,
+                        last_slot,
+                        last_state_step,
+                        &deadline,
+                        bootstrap_config.write_timeout.to_duration(),
+                        massa_metrics,
+                    )?;
+                }
+                BootstrapClientMessage::AskBootstrapVersioning => {
+                    stream_bootstrap_versioning(
+                        server,
+                        final_state.clone(),
+                        &deadline,
+                        bootstrap_config.write_timeout.to_duration(),
+                        massa_metrics,
+                    )?;
+                }
+                BootstrapClientMessage::AskBootstrapResume { last_slot } => {
+                    let Some(write_timeout) = step_timeout_duration(
+                        &deadline,
+                        &bootstrap_config.write_timeout.to_duration(),
+                    ) else {
+                        return Err(BootstrapError::Interupted(
+                            "insufficient time left to respond to the request for resume"
+                                .to_string(),
+                        ));
+                    };
+
+                    let db_slot = final_state
+                        .read()
+                        .db
+                        .read()
+                        .get_change_id()
+                        .expect(CHANGE_ID_DESER_ERROR);
+                    let response = if last_slot > db_slot {
+                        BootstrapServerMessage::BootstrapError {
+                            error: "Bootstrap cursor set to future slot".to_string(),
+                        }
+                    } else if is_slot_too_old(&final_state, last_slot) {
+                        BootstrapServerMessage::SlotTooOld
+                    } else {
+                        BootstrapServerMessage::BootstrapResumeAccepted
+                    };
+                    server.send_msg(write_timeout, response)?;
+                }
                 BootstrapClientMessage::BootstrapSuccess => break Ok(()),
                 BootstrapClientMessage::BootstrapError { error } => {
                     break Err(BootstrapError::ReceivedError(error));
                 }
+                msg @ BootstrapClientMessage::BootstrapChallengeResponse { .. } => {
+                    break Err(BootstrapError::UnexpectedClientMessage(Box::new(msg)));
+                }
             },
         };
     }
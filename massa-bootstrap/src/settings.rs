@@ -1,7 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_hash::Hash;
 use massa_models::block::BlockDeserializerArgs;
 use massa_models::node::NodeId;
+use massa_models::slot::Slot;
 use massa_time::MassaTime;
 use serde::Deserialize;
 use std::{net::SocketAddr, path::PathBuf};
@@ -19,6 +21,37 @@ pub enum IpType {
     IPv6,
 }
 
+/// TLS layer of the connections of the bootstrap server
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BootstrapTlsMode {
+    /// Only plain connections are accepted (default).
+    Disabled,
+    /// Both TLS and plain connections are accepted.
+    Optional,
+    /// Only TLS connections are accepted.
+    Required,
+}
+
+/// Challenge a client must answer before the bootstrap server begins streaming to it
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BootstrapChallengeMode {
+    /// No challenge is sent (default).
+    Disabled,
+    /// The client must solve a proof of work.
+    ProofOfWork,
+    /// The client must prove that it knows one of the tokens of the server.
+    Token,
+}
+
+/// State trusted by the operator, that the bootstrapped state must descend from
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct TrustedCheckpoint {
+    /// slot of the checkpoint: the slot of the bootstrapped state, or the last slot of a cycle still in its history
+    pub slot: Slot,
+    /// final state hash at that slot, as logged by the nodes
+    pub state_hash: Hash,
+}
+
 /// Bootstrap configuration.
 #[derive(Debug, Deserialize, Clone)]
 pub struct BootstrapConfig {
@@ -45,8 +78,14 @@ pub struct BootstrapConfig {
     pub read_error_timeout: MassaTime,
     /// write error timeout
     pub write_error_timeout: MassaTime,
-    /// Time we wait before retrying a bootstrap
+    /// Time we wait before retrying a bootstrap, doubled after each consecutive failure
     pub retry_delay: MassaTime,
+    /// Max time we wait before retrying a bootstrap
+    pub max_retry_delay: MassaTime,
+    /// Max share of the retry delay that is randomly cut from it, between 0 and 1
+    pub retry_jitter: f64,
+    /// Time after which the client stops retrying to bootstrap, unlimited if 0
+    pub max_bootstrap_duration: MassaTime,
     /// Max ping delay.
     pub max_ping: MassaTime,
     /// Maximum allowed time between server and client clocks
@@ -55,6 +94,24 @@ pub struct BootstrapConfig {
     pub cache_duration: MassaTime,
     /// Keep ledger or not if not bootstrap
     pub keep_ledger: bool,
+    /// File where the client saves its bootstrap cursors to resume an interrupted bootstrap.
+    /// An interrupted bootstrap starts from scratch after a restart if `None`.
+    pub bootstrap_cursor_path: Option<PathBuf>,
+    /// Snapshot archive exported by a node of the bootstrap list to bootstrap the state from.
+    /// Only the changes since its slot are then streamed from the bootstrap servers.
+    pub bootstrap_snapshot_path: Option<PathBuf>,
+    /// File where consensus saves its final blocks on a clean shutdown.
+    /// They are loaded by a bootstrap starting from scratch, so that only the missing blocks are downloaded.
+    pub consensus_state_cache_path: Option<PathBuf>,
+    /// Checkpoint the bootstrapped state is verified against before the bootstrap is finished.
+    /// A state that does not match it is dropped, and the bootstrap restarts with another server.
+    pub trusted_checkpoint: Option<TrustedCheckpoint>,
+    /// Number of servers the client bootstraps from in parallel: one of them streams the consensus graph,
+    /// the others stream disjoint stripes of the state. The client bootstraps from a single server if lower than 2.
+    pub parallel_bootstrap_servers: usize,
+    /// zstd level of the compression of the messages sent by the server, compression is disabled if 0.
+    /// The client only asks for compressed messages if it is not 0.
+    pub compression_level: i32,
     /// Max simultaneous bootstraps
     pub max_simultaneous_bootstraps: u32,
     /// Minimum interval between two bootstrap attempts from a given IP
@@ -63,6 +120,41 @@ pub struct BootstrapConfig {
     pub ip_list_max_size: usize,
     /// Read-Write limitation for a connection in bytes per seconds
     pub rate_limit: u64,
+    /// Whether the server accepts or requires TLS connections
+    pub tls_mode: BootstrapTlsMode,
+    /// PEM file of the TLS certificate chain of the server. A self-signed certificate is generated if `None`.
+    pub tls_certificate_path: Option<PathBuf>,
+    /// PEM file of the private key of the TLS certificate of the server
+    pub tls_private_key_path: Option<PathBuf>,
+    /// Whether the client connects to the bootstrap servers with TLS
+    pub connect_with_tls: bool,
+    /// Whether the server also accepts QUIC connections on the UDP port of its address (experimental)
+    pub accept_quic: bool,
+    /// Whether the client connects to the bootstrap servers with QUIC (experimental)
+    pub connect_with_quic: bool,
+    /// Challenge the server sends to the clients after the handshake
+    pub challenge_mode: BootstrapChallengeMode,
+    /// Number of leading zero bits of the proofs of work asked by the server
+    pub challenge_difficulty: u8,
+    /// Tokens accepted by the server in `Token` challenge mode
+    pub challenge_tokens: Vec<String>,
+    /// Token the client answers the challenges of the servers with
+    pub challenge_token: Option<String>,
+    /// Time the server waits for the answer of a client to its challenge
+    pub challenge_timeout: MassaTime,
+    /// Max simultaneous clients waiting for the verification of their answer to the challenge.
+    /// They only take one of the `max_simultaneous_bootstraps` slots once their answer is verified.
+    pub max_simultaneous_challenges: u32,
+    /// Bandwidth shared by all the bootstrap sessions of the server in bytes per seconds, unlimited if 0
+    pub global_rate_limit: u64,
+    /// Bandwidth shared by the bootstrap sessions of an IP in bytes per seconds, unlimited if 0
+    pub per_ip_rate_limit: u64,
+    /// Time after which the server stops sending data to a bootstrap session
+    pub max_session_duration: MassaTime,
+    /// JSON lines file the server records its bootstrap sessions in, if any
+    pub audit_log_path: Option<PathBuf>,
+    /// Size in bytes after which the audit log file is rotated
+    pub audit_log_max_size: u64,
     /// thread count
     pub thread_count: u8,
     /// period per cycle
@@ -136,6 +228,7 @@ pub struct BootstrapSrvBindCfg {
     pub randomness_size_bytes: usize,
     pub consensus_bootstrap_part_size: u64,
     pub write_error_timeout: MassaTime,
+    pub compression_level: i32,
 }
 
 /// Bootstrap client config
@@ -151,6 +244,7 @@ pub struct BootstrapClientConfig {
     pub max_operations_per_block: u32,
     pub thread_count: u8,
     pub randomness_size_bytes: usize,
+    pub compression_level: i32,
     pub max_bootstrap_error_length: u64,
     pub max_new_elements: u64,
     pub max_datastore_entry_count: u64,
@@ -0,0 +1,330 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Offline bootstrap of the state from a snapshot archive.
+//!
+//! A stopped node exports its final state to an archive file signed with its node keypair.
+//! Another node bootstraps its state from that file instead of streaming it from a server,
+//! then only streams the changes since the slot of the snapshot from the bootstrap servers.
+//!
+//! The archive starts with `SNAPSHOT_ARCHIVE_MAGIC`, followed by the state and versioning parts,
+//! serialized as `BootstrapServerMessage::BootstrapPart` and prefixed with their length.
+//! An empty record ends the parts. Then come the slot and the hash of the state,
+//! the public key of the exporting node and its signature of the hash chain of the archive.
+
+use crate::{
+    error::BootstrapError,
+    messages::{
+        BootstrapServerMessage, BootstrapServerMessageDeserializer,
+        BootstrapServerMessageSerializer,
+    },
+    BootstrapConfig,
+};
+use massa_consensus_exports::bootstrapable_graph::BootstrapableGraph;
+use massa_db_exports::StreamBatch;
+use massa_final_state::FinalState;
+use massa_hash::{Hash, HashXof, HASH_XOF_SIZE_BYTES};
+use massa_models::{
+    config::{MAX_BOOTSTRAP_MESSAGE_SIZE, SIGNATURE_DESER_SIZE},
+    node::NodeId,
+    prehash::PreHashSet,
+    slot::{Slot, SLOT_KEY_SIZE},
+    streaming_step::StreamingStep,
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use massa_signature::{KeyPair, PublicKeyDeserializer, Signature};
+use parking_lot::RwLock;
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// Magic bytes starting a snapshot archive, holding the version of its format
+pub const SNAPSHOT_ARCHIVE_MAGIC: &[u8] = b"massa-bootstrap-snapshot-v1";
+
+/// Description of a snapshot archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotArchiveInfo {
+    /// slot of the state in the archive
+    pub slot: Slot,
+    /// hash of the state in the archive
+    pub state_hash: HashXof<HASH_XOF_SIZE_BYTES>,
+    /// node that exported and signed the archive
+    pub node_id: NodeId,
+}
+
+/// Hash chain of the records of an archive
+struct ArchiveHasher(Hash);
+
+impl ArchiveHasher {
+    fn new() -> Self {
+        ArchiveHasher(Hash::compute_from(SNAPSHOT_ARCHIVE_MAGIC))
+    }
+
+    fn update(&mut self, record: &[u8]) {
+        self.0 = Hash::compute_from(&[self.0.to_bytes().as_slice(), record].concat());
+    }
+
+    /// Hash signed by the exporting node: the hash chain, the slot and the hash of the state
+    fn signed_hash(&self, slot: &Slot, state_hash: &HashXof<HASH_XOF_SIZE_BYTES>) -> Hash {
+        Hash::compute_from(
+            &[
+                self.0.to_bytes().as_slice(),
+                &slot.to_bytes_key(),
+                state_hash.to_bytes(),
+            ]
+            .concat(),
+        )
+    }
+}
+
+/// Exports the final state to a snapshot archive at `path`, signed with `keypair`.
+/// The state must not change meanwhile: the node has to be stopped.
+pub fn export_snapshot_archive(
+    final_state: &FinalState,
+    keypair: &KeyPair,
+    path: &Path,
+) -> Result<SnapshotArchiveInfo, BootstrapError> {
+    let db = final_state.db.read();
+    let slot = db.get_change_id().map_err(|e| {
+        BootstrapError::SnapshotArchiveError(format!("could not read the slot of the state: {}", e))
+    })?;
+    let state_hash = db.get_xof_db_hash();
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(SNAPSHOT_ARCHIVE_MAGIC)?;
+    let mut hasher = ArchiveHasher::new();
+    let serializer = BootstrapServerMessageSerializer::new();
+
+    let mut last_state_step = StreamingStep::Started;
+    let mut last_versioning_step = StreamingStep::Started;
+    let mut last_slot = None;
+    while !last_state_step.finished() || !last_versioning_step.finished() {
+        let state_part = if last_state_step.finished() {
+            empty_batch(slot)
+        } else {
+            db.get_batch_to_stream(&last_state_step, last_slot)
+                .map_err(|e| {
+                    BootstrapError::GeneralError(format!("Error get_batch_to_stream: {}", e))
+                })?
+        };
+        let versioning_part = if last_versioning_step.finished() {
+            empty_batch(slot)
+        } else {
+            db.get_versioning_batch_to_stream(&last_versioning_step, last_slot)
+                .map_err(|e| {
+                    BootstrapError::GeneralError(format!(
+                        "Error get_versioning_batch_to_stream: {}",
+                        e
+                    ))
+                })?
+        };
+        last_state_step = next_step(&state_part);
+        last_versioning_step = next_step(&versioning_part);
+        if state_part.is_empty() && versioning_part.is_empty() {
+            continue;
+        }
+
+        let message = BootstrapServerMessage::BootstrapPart {
+            slot,
+            state_part,
+            versioning_part,
+            consensus_part: BootstrapableGraph {
+                final_blocks: Default::default(),
+            },
+            consensus_outdated_ids: PreHashSet::default(),
+            last_start_period: last_slot.is_none().then_some(final_state.last_start_period),
+            last_slot_before_downtime: last_slot
+                .is_none()
+                .then_some(final_state.last_slot_before_downtime),
+        };
+        last_slot = Some(slot);
+        let mut record = Vec::new();
+        serializer.serialize(&message, &mut record)?;
+        write_record(&mut writer, &mut hasher, &record)?;
+    }
+    // an empty record ends the parts
+    write_record(&mut writer, &mut hasher, &[])?;
+
+    let signature = keypair.sign(&hasher.signed_hash(&slot, &state_hash))?;
+    writer.write_all(&slot.to_bytes_key())?;
+    writer.write_all(state_hash.to_bytes())?;
+    writer.write_all(&keypair.get_public_key().to_bytes())?;
+    writer.write_all(&signature.to_bytes())?;
+    writer.flush()?;
+
+    Ok(SnapshotArchiveInfo {
+        slot,
+        state_hash,
+        node_id: NodeId::new(keypair.get_public_key()),
+    })
+}
+
+/// Writes the state of the snapshot archive at `path` to the final state.
+///
+/// The archive must be signed by a node of the bootstrap list, and the written state must match
+/// the slot and the hash it holds. The final state has to be reset if the import fails.
+pub(crate) fn import_snapshot_archive(
+    cfg: &BootstrapConfig,
+    path: &Path,
+    final_state: &RwLock<FinalState>,
+) -> Result<SnapshotArchiveInfo, BootstrapError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = vec![0u8; SNAPSHOT_ARCHIVE_MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != SNAPSHOT_ARCHIVE_MAGIC {
+        return Err(BootstrapError::SnapshotArchiveError(String::from(
+            "not a bootstrap snapshot archive",
+        )));
+    }
+    let mut hasher = ArchiveHasher::new();
+    let deserializer = BootstrapServerMessageDeserializer::new(cfg.into());
+
+    loop {
+        let record = read_record(&mut reader, &mut hasher)?;
+        if record.is_empty() {
+            break;
+        }
+        let (_, message) = deserializer
+            .deserialize::<DeserializeError>(&record)
+            .map_err(|err| BootstrapError::DeserializeError(format!("{}", err)))?;
+        let BootstrapServerMessage::BootstrapPart {
+            state_part,
+            versioning_part,
+            last_start_period,
+            last_slot_before_downtime,
+            ..
+        } = message
+        else {
+            return Err(BootstrapError::SnapshotArchiveError(format!(
+                "unexpected message in the archive: {}",
+                message.to_string()
+            )));
+        };
+
+        let mut write_final_state = final_state.write();
+        if let Some(last_start_period) = last_start_period {
+            write_final_state.last_start_period = last_start_period;
+        }
+        if let Some(last_slot_before_downtime) = last_slot_before_downtime {
+            write_final_state.last_slot_before_downtime = last_slot_before_downtime;
+        }
+        write_final_state
+            .db
+            .write()
+            .write_batch_bootstrap_client(state_part, versioning_part)
+            .map_err(|e| {
+                BootstrapError::GeneralError(format!(
+                    "Cannot write archived stream batch to disk: {}",
+                    e
+                ))
+            })?;
+    }
+
+    // the trailer is small: do not read a whole file appended by mistake
+    let mut trailer = Vec::new();
+    reader
+        .take((SLOT_KEY_SIZE + HASH_XOF_SIZE_BYTES + 2 * SIGNATURE_DESER_SIZE) as u64)
+        .read_to_end(&mut trailer)?;
+    let invalid_trailer =
+        || BootstrapError::SnapshotArchiveError(String::from("invalid archive trailer"));
+    if trailer.len() < SLOT_KEY_SIZE + HASH_XOF_SIZE_BYTES {
+        return Err(invalid_trailer());
+    }
+    let (slot_bytes, rest) = trailer.split_at(SLOT_KEY_SIZE);
+    let (hash_bytes, rest) = rest.split_at(HASH_XOF_SIZE_BYTES);
+    let slot = Slot::from_bytes_key(slot_bytes.try_into().map_err(|_| invalid_trailer())?);
+    let state_hash = HashXof::from_bytes(hash_bytes.try_into().map_err(|_| invalid_trailer())?);
+    let (rest, public_key) = PublicKeyDeserializer::new()
+        .deserialize::<DeserializeError>(rest)
+        .map_err(|_| invalid_trailer())?;
+    if rest.len() != SIGNATURE_DESER_SIZE {
+        return Err(invalid_trailer());
+    }
+    let signature = Signature::from_bytes(rest)?;
+
+    let node_id = NodeId::new(public_key);
+    if !cfg.bootstrap_list.iter().any(|(_, id)| *id == node_id) {
+        return Err(BootstrapError::SnapshotArchiveError(format!(
+            "archive signed by {}, which is not in the bootstrap list",
+            node_id
+        )));
+    }
+    public_key.verify_signature(&hasher.signed_hash(&slot, &state_hash), &signature)?;
+
+    // check the written state against the signed slot and hash
+    let final_state_read = final_state.read();
+    let db = final_state_read.db.read();
+    let db_slot = db.get_change_id().map_err(|e| {
+        BootstrapError::SnapshotArchiveError(format!("could not read the slot of the state: {}", e))
+    })?;
+    if db_slot != slot {
+        return Err(BootstrapError::SnapshotArchiveError(format!(
+            "state written at slot {} instead of {}",
+            db_slot, slot
+        )));
+    }
+    let db_hash = db.get_xof_db_hash();
+    if db_hash != state_hash {
+        return Err(BootstrapError::SnapshotArchiveError(format!(
+            "state hash {} instead of {}",
+            db_hash, state_hash
+        )));
+    }
+
+    Ok(SnapshotArchiveInfo {
+        slot,
+        state_hash,
+        node_id,
+    })
+}
+
+fn empty_batch(slot: Slot) -> StreamBatch<Slot> {
+    StreamBatch {
+        new_elements: Default::default(),
+        updates_on_previous_elements: Default::default(),
+        change_id: slot,
+    }
+}
+
+/// The parts hold no changes as the state does not change: a part without new elements ends the streaming
+fn next_step(part: &StreamBatch<Slot>) -> StreamingStep<Vec<u8>> {
+    match part.new_elements.last_key_value() {
+        Some((last_key, _)) => StreamingStep::Ongoing(last_key.clone()),
+        None => StreamingStep::Finished(None),
+    }
+}
+
+fn write_record(
+    writer: &mut impl Write,
+    hasher: &mut ArchiveHasher,
+    record: &[u8],
+) -> Result<(), BootstrapError> {
+    let len: u32 = record.len().try_into().map_err(|e| {
+        BootstrapError::GeneralError(format!("archive record too large to encode: {}", e))
+    })?;
+    let len_bytes = len.to_be_bytes();
+    writer.write_all(&len_bytes)?;
+    writer.write_all(record)?;
+    hasher.update(&[len_bytes.as_slice(), record].concat());
+    Ok(())
+}
+
+fn read_record(
+    reader: &mut impl Read,
+    hasher: &mut ArchiveHasher,
+) -> Result<Vec<u8>, BootstrapError> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes);
+    if len > MAX_BOOTSTRAP_MESSAGE_SIZE {
+        return Err(BootstrapError::SnapshotArchiveError(format!(
+            "archive record of {} bytes exceeds the size limit of bootstrap messages",
+            len
+        )));
+    }
+    let mut record = vec![0u8; len as usize];
+    reader.read_exact(&mut record)?;
+    hasher.update(&[len_bytes.as_slice(), &record].concat());
+    Ok(record)
+}
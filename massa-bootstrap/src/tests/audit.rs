@@ -0,0 +1,77 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::audit::{rotated_path, BootstrapAuditLog, BootstrapLimit, BootstrapSessionAudit};
+use std::net::IpAddr;
+use std::time::Duration;
+
+fn read_entries(path: &std::path::Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_audit_log_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bootstrap_audit.jsonl");
+    let audit_log = BootstrapAuditLog::new(path.clone(), 1_000_000);
+    let ip: IpAddr = "192.168.0.1".parse().unwrap();
+
+    audit_log.record(&BootstrapSessionAudit::refused(
+        ip,
+        "no slots".to_string(),
+        BootstrapLimit::MaxSimultaneousBootstraps,
+    ));
+    audit_log.record(&BootstrapSessionAudit::session(
+        ip,
+        Duration::from_millis(1500),
+        4096,
+        "success".to_string(),
+        vec![],
+    ));
+
+    let entries = read_entries(&path);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["ip"], "192.168.0.1");
+    assert_eq!(entries[0]["duration"], 0);
+    assert_eq!(
+        entries[0]["violated_limits"],
+        serde_json::json!(["max_simultaneous_bootstraps"])
+    );
+    assert_eq!(entries[1]["duration"], 1500);
+    assert_eq!(entries[1]["bytes_sent"], 4096);
+    assert_eq!(entries[1]["termination_reason"], "success");
+    assert!(entries[1]["start"].as_u64().unwrap() + 1500 >= entries[0]["start"].as_u64().unwrap());
+}
+
+#[test]
+fn test_audit_log_rotation() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bootstrap_audit.jsonl");
+    let entry = BootstrapSessionAudit::session(
+        "::1".parse().unwrap(),
+        Duration::from_secs(10),
+        0,
+        "Bootstrap process timeout: timed out".to_string(),
+        vec![BootstrapLimit::Timeout],
+    );
+    let line_size = serde_json::to_vec(&entry).unwrap().len() as u64 + 1;
+    // three entries per file
+    let audit_log = BootstrapAuditLog::new(path.clone(), 3 * line_size);
+
+    for _ in 0..5 {
+        audit_log.record(&entry);
+    }
+    assert_eq!(read_entries(&rotated_path(&path)).len(), 3);
+    assert_eq!(read_entries(&path).len(), 2);
+
+    // the previous rotated file is replaced
+    for _ in 0..2 {
+        audit_log.record(&entry);
+    }
+    assert_eq!(read_entries(&rotated_path(&path)).len(), 3);
+    assert_eq!(read_entries(&path).len(), 1);
+    assert_eq!(read_entries(&path)[0]["violated_limits"][0], "timeout");
+}
@@ -0,0 +1,44 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::bandwidth::BandwidthScheduler;
+use crate::error::BootstrapError;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_bandwidth_per_ip_limit() {
+    let scheduler = BandwidthScheduler::new(0, 1000, Duration::from_secs(60));
+    let ip: IpAddr = "192.168.0.1".parse().unwrap();
+    let mut session = scheduler.open_session(ip);
+
+    // the bucket of the IP is full at first, and goes into debt for a large message
+    session.acquire(1500, None).unwrap();
+
+    // the debt is shared by the sessions of the IP
+    let mut other_session = scheduler.open_session(ip);
+    let res = other_session.acquire(10, Some(Instant::now() + Duration::from_millis(100)));
+    assert!(matches!(res, Err(BootstrapError::TimedOut(_))));
+
+    // another IP is not limited by it
+    let mut other_ip_session = scheduler.open_session("192.168.0.2".parse().unwrap());
+    other_ip_session
+        .acquire(10, Some(Instant::now() + Duration::from_millis(100)))
+        .unwrap();
+
+    // the debt is refilled after about half a second
+    let start = Instant::now();
+    session.acquire(10, None).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn test_bandwidth_max_session_duration() {
+    let scheduler = BandwidthScheduler::new(0, 0, Duration::from_millis(100));
+    let mut session = scheduler.open_session("192.168.0.1".parse().unwrap());
+    session.acquire(10, None).unwrap();
+    std::thread::sleep(Duration::from_millis(150));
+    assert!(matches!(
+        session.acquire(10, None),
+        Err(BootstrapError::GeneralError(_))
+    ));
+}
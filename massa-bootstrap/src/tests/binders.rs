@@ -1,5 +1,8 @@
+use crate::listener::{BootstrapTcpListener, PollEvent};
 use crate::messages::{BootstrapClientMessage, BootstrapServerMessage};
-use crate::settings::{BootstrapClientConfig, BootstrapSrvBindCfg};
+use crate::quic::QuicStream;
+use crate::settings::{BootstrapClientConfig, BootstrapSrvBindCfg, BootstrapTlsMode};
+use crate::tls::{client_tls_config, ServerTls};
 use crate::{
     bindings::{BootstrapClientBinder, BootstrapServerBinder},
     tests::tools::get_bootstrap_config,
@@ -24,7 +27,7 @@ use massa_time::MassaTime;
 use serial_test::serial;
 use std::collections::HashMap;
 use std::io::Write;
-use std::net::TcpStream;
+use std::net::{SocketAddr, TcpStream};
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 
@@ -50,6 +53,7 @@ impl BootstrapClientBinder {
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             thread_count: THREAD_COUNT,
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+            compression_level: 0,
             max_bootstrap_error_length: MAX_BOOTSTRAP_ERROR_LENGTH,
             max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS,
             max_datastore_entry_count: MAX_DATASTORE_ENTRY_COUNT,
@@ -89,6 +93,7 @@ fn test_binders() {
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
             write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
         },
         Some(u64::MAX),
     );
@@ -239,6 +244,7 @@ fn test_binders_double_send_server_works() {
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
             write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
         },
         Some(u64::MAX),
     );
@@ -349,6 +355,284 @@ fn test_binders_double_send_server_works() {
     client_thread.join().unwrap();
 }
 
+/// The server and the client will negotiate compression during the handshake,
+/// then the server will send a compressible message and a message that is sent uncompressed
+#[test]
+fn test_binders_compression() {
+    let (bootstrap_config, server_keypair): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+
+    let server = std::net::TcpListener::bind("localhost:0").unwrap();
+    let client = std::net::TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    let server = server.accept().unwrap();
+    let version = || Version::from_str("TEST.1.10").unwrap();
+
+    let mut server = BootstrapServerBinder::new(
+        server.0,
+        server_keypair.clone(),
+        BootstrapSrvBindCfg {
+            rate_limit: std::u64::MAX,
+            thread_count: THREAD_COUNT,
+            max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+            randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+            consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+            write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 3,
+        },
+        Some(u64::MAX),
+    );
+    let mut client_cfg = BootstrapClientBinder::test_default_config();
+    client_cfg.compression_level = 3;
+    let mut client = BootstrapClientBinder::new(
+        client,
+        bootstrap_config.bootstrap_list[0].1.get_public_key(),
+        client_cfg,
+        None,
+    );
+    let err_str = ['A'; 10_000].iter().collect::<String>();
+    let srv_err_str = err_str.clone();
+
+    let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+    let mut listeners = HashMap::default();
+    listeners.insert(
+        bootstrap_config.bootstrap_list[0].0.clone(),
+        TransportType::Tcp,
+    );
+    let vector_peers = vec![(peer_id, listeners)];
+    let srv_vector_peers = vector_peers.clone();
+
+    let server_thread = std::thread::Builder::new()
+        .name("test_binders_compression::server_thread".to_string())
+        .spawn(move || {
+            server.handshake_timeout(version(), None).unwrap();
+            server
+                .send_timeout(
+                    BootstrapServerMessage::BootstrapError { error: srv_err_str },
+                    None,
+                )
+                .unwrap();
+            server
+                .send_timeout(
+                    BootstrapServerMessage::BootstrapPeers {
+                        peers: BootstrapPeers(srv_vector_peers),
+                    },
+                    None,
+                )
+                .unwrap();
+        })
+        .unwrap();
+
+    let client_thread = std::thread::Builder::new()
+        .name("test_binders_compression::client_thread".to_string())
+        .spawn(move || {
+            client.handshake(version()).unwrap();
+            match client.next_timeout(None).unwrap() {
+                BootstrapServerMessage::BootstrapError { error } => {
+                    assert_eq!(error, err_str);
+                }
+                _ => panic!("Bad message receive: Expected an error message"),
+            }
+            match client.next_timeout(None).unwrap() {
+                BootstrapServerMessage::BootstrapPeers { peers } => {
+                    assert_eq!(vector_peers, peers.0);
+                }
+                _ => panic!("Bad message receive: Expected a peers list message"),
+            }
+        })
+        .unwrap();
+
+    server_thread.join().unwrap();
+    client_thread.join().unwrap();
+}
+
+/// The server only accepts TLS connections: the client handshakes through TLS, then they send messages in both ways
+#[test]
+fn test_binders_tls() {
+    let (bootstrap_config, server_keypair): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+
+    let server = std::net::TcpListener::bind("localhost:0").unwrap();
+    let client = std::net::TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    let server = server.accept().unwrap();
+    let version = || Version::from_str("TEST.1.10").unwrap();
+
+    let mut server = BootstrapServerBinder::new(
+        server.0,
+        server_keypair.clone(),
+        BootstrapSrvBindCfg {
+            rate_limit: std::u64::MAX,
+            thread_count: THREAD_COUNT,
+            max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+            randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+            consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+            write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
+        },
+        Some(u64::MAX),
+    );
+    server.set_tls(ServerTls::new(BootstrapTlsMode::Required, None, None).unwrap());
+    let mut client = BootstrapClientBinder::test_default(
+        client,
+        bootstrap_config.bootstrap_list[0].1.get_public_key(),
+    );
+    client.start_tls(client_tls_config()).unwrap();
+    let err_str = ['A'; 100_000].iter().collect::<String>();
+    let srv_err_str = err_str.clone();
+
+    let server_thread = std::thread::Builder::new()
+        .name("test_binders_tls::server_thread".to_string())
+        .spawn(move || {
+            server
+                .handshake_timeout(version(), Some(Duration::from_secs(5)))
+                .unwrap();
+            server
+                .send_timeout(
+                    BootstrapServerMessage::BootstrapError { error: srv_err_str },
+                    None,
+                )
+                .unwrap();
+            match server.next_timeout(None).unwrap() {
+                BootstrapClientMessage::BootstrapSuccess => {}
+                _ => panic!("Bad message receive: Expected a success message"),
+            }
+        })
+        .unwrap();
+
+    let client_thread = std::thread::Builder::new()
+        .name("test_binders_tls::client_thread".to_string())
+        .spawn(move || {
+            client.handshake(version()).unwrap();
+            match client.next_timeout(None).unwrap() {
+                BootstrapServerMessage::BootstrapError { error } => {
+                    assert_eq!(error, err_str);
+                }
+                _ => panic!("Bad message receive: Expected an error message"),
+            }
+            client
+                .send_timeout(&BootstrapClientMessage::BootstrapSuccess, None)
+                .unwrap();
+        })
+        .unwrap();
+
+    server_thread.join().unwrap();
+    client_thread.join().unwrap();
+}
+
+/// A server only accepting TLS connections refuses a plain client
+#[test]
+fn test_binders_tls_required() {
+    let (bootstrap_config, server_keypair): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+
+    let server = std::net::TcpListener::bind("localhost:0").unwrap();
+    let client = std::net::TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    let server = server.accept().unwrap();
+    let version = || Version::from_str("TEST.1.10").unwrap();
+
+    let mut server = BootstrapServerBinder::new(
+        server.0,
+        server_keypair.clone(),
+        BootstrapSrvBindCfg {
+            rate_limit: std::u64::MAX,
+            thread_count: THREAD_COUNT,
+            max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+            randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+            consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+            write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
+        },
+        Some(u64::MAX),
+    );
+    server.set_tls(ServerTls::new(BootstrapTlsMode::Required, None, None).unwrap());
+    let mut client = BootstrapClientBinder::test_default(
+        client,
+        bootstrap_config.bootstrap_list[0].1.get_public_key(),
+    );
+
+    client.handshake(version()).unwrap();
+    match server.handshake_timeout(version(), Some(Duration::from_secs(5))) {
+        Err(BootstrapError::TlsError(_)) => {}
+        other => panic!("expected a TLS error, got {:?}", other),
+    }
+}
+
+/// The client connects to the QUIC endpoint of the listener, then they handshake and send messages in both ways.
+/// QUIC connections are already encrypted: a server only accepting TLS connections accepts them.
+#[test]
+#[serial]
+fn test_binders_quic() {
+    let (bootstrap_config, server_keypair): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+    let addr: SocketAddr = "127.0.0.1:8073".parse().unwrap();
+    let (_listener_stopper, mut listener) = BootstrapTcpListener::create(&addr).unwrap();
+    listener.listen_quic(&addr).unwrap();
+    let version = || Version::from_str("TEST.1.10").unwrap();
+    let err_str = ['A'; 100_000].iter().collect::<String>();
+    let srv_err_str = err_str.clone();
+
+    // the server only sees the stream of the client once it sends its handshake
+    let remote_pubkey = bootstrap_config.bootstrap_list[0].1.get_public_key();
+    let client_thread = std::thread::Builder::new()
+        .name("test_binders_quic::client_thread".to_string())
+        .spawn(move || {
+            let stream = QuicStream::connect(addr, Some(Duration::from_secs(5))).unwrap();
+            let mut client = BootstrapClientBinder::new(
+                stream,
+                remote_pubkey,
+                BootstrapClientBinder::test_default_config(),
+                None,
+            );
+            client.start_tls(client_tls_config()).unwrap();
+            client.handshake(version()).unwrap();
+            match client.next_timeout(Some(Duration::from_secs(5))).unwrap() {
+                BootstrapServerMessage::BootstrapError { error } => {
+                    assert_eq!(error, err_str);
+                }
+                _ => panic!("Bad message receive: Expected an error message"),
+            }
+            client
+                .send_timeout(&BootstrapClientMessage::BootstrapSuccess, None)
+                .unwrap();
+        })
+        .unwrap();
+
+    let (stream, _) = loop {
+        match listener.poll().unwrap() {
+            PollEvent::NewConnections(mut connections) if !connections.is_empty() => {
+                break connections.remove(0)
+            }
+            PollEvent::NewConnections(_) => {}
+            PollEvent::Stop => panic!("the listener was stopped"),
+        }
+    };
+    let mut server = BootstrapServerBinder::new(
+        stream,
+        server_keypair.clone(),
+        BootstrapSrvBindCfg {
+            rate_limit: std::u64::MAX,
+            thread_count: THREAD_COUNT,
+            max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+            randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+            consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+            write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
+        },
+        Some(u64::MAX),
+    );
+    server.set_tls(ServerTls::new(BootstrapTlsMode::Required, None, None).unwrap());
+    server
+        .handshake_timeout(version(), Some(Duration::from_secs(5)))
+        .unwrap();
+    server
+        .send_timeout(
+            BootstrapServerMessage::BootstrapError { error: srv_err_str },
+            None,
+        )
+        .unwrap();
+    match server.next_timeout(Some(Duration::from_secs(5))).unwrap() {
+        BootstrapClientMessage::BootstrapSuccess => {}
+        _ => panic!("Bad message receive: Expected a success message"),
+    }
+
+    client_thread.join().unwrap();
+}
+
 /// The server and the client will handshake and then send message in both ways but the client will try to send two messages without answer
 #[test]
 fn test_binders_try_double_send_client_works() {
@@ -368,6 +652,7 @@ fn test_binders_try_double_send_client_works() {
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
             write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
         },
         Some(u64::MAX),
     );
@@ -505,6 +790,7 @@ fn test_partial_msg() {
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
             write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
         },
         None,
     );
@@ -574,6 +860,7 @@ fn test_client_drip_feed() {
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
             write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
         },
         None,
     );
@@ -665,6 +952,7 @@ fn test_bandwidth() {
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
             write_error_timeout: MassaTime::from_millis(1000),
+            compression_level: 0,
         },
         Some(100),
     );
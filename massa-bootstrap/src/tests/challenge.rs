@@ -0,0 +1,122 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::challenge::{
+    answer_challenge, check_proof_of_work, solve_proof_of_work, HandshakeChallenge,
+    CHALLENGE_NONCE_SIZE,
+};
+use crate::error::BootstrapError;
+use crate::messages::{
+    BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
+    BootstrapServerMessage,
+};
+use crate::settings::BootstrapChallengeMode;
+use massa_models::config::THREAD_COUNT;
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+
+/// Nonce and difficulty sent by the server, and the answer of a client with `token`
+fn answer(challenge: &HandshakeChallenge, token: Option<&str>) -> (u8, u64, Option<Vec<u8>>) {
+    let BootstrapServerMessage::BootstrapChallenge { nonce, difficulty } = challenge.message()
+    else {
+        panic!("the challenge must be sent with a BootstrapChallenge message");
+    };
+    assert_eq!(nonce.len(), CHALLENGE_NONCE_SIZE);
+    let BootstrapClientMessage::BootstrapChallengeResponse {
+        solution,
+        token_proof,
+    } = answer_challenge(&nonce, difficulty, token)
+    else {
+        panic!("the answer must be a BootstrapChallengeResponse message");
+    };
+    (difficulty, solution, token_proof)
+}
+
+#[test]
+fn test_challenge_proof_of_work() {
+    let nonce = vec![7u8; CHALLENGE_NONCE_SIZE];
+    let solution = solve_proof_of_work(&nonce, 12);
+    assert!(check_proof_of_work(&nonce, 12, solution));
+    assert!(check_proof_of_work(&nonce, 0, solution));
+    // the first solution is the lowest one
+    assert!((0..solution).all(|other| !check_proof_of_work(&nonce, 12, other)));
+
+    let challenge = HandshakeChallenge::new(BootstrapChallengeMode::ProofOfWork, 10);
+    let (difficulty, solution, token_proof) = answer(&challenge, None);
+    assert_eq!(difficulty, 10);
+    assert!(token_proof.is_none());
+    challenge.check(solution, None, &[]).unwrap();
+    // a wrong solution is refused
+    let wrong_solution = (0..)
+        .find(|other| challenge.check(*other, None, &[]).is_err())
+        .unwrap();
+    assert!(matches!(
+        challenge.check(wrong_solution, None, &[]),
+        Err(BootstrapError::ChallengeFailed(_))
+    ));
+}
+
+#[test]
+fn test_challenge_token() {
+    let tokens = vec!["first token".to_string(), "second token".to_string()];
+    let challenge = HandshakeChallenge::new(BootstrapChallengeMode::Token, 10);
+
+    // no proof of work is asked in token mode
+    let (difficulty, solution, token_proof) = answer(&challenge, Some("second token"));
+    assert_eq!(difficulty, 0);
+    assert_eq!(solution, 0);
+    challenge
+        .check(solution, token_proof.as_deref(), &tokens)
+        .unwrap();
+
+    // a truncated proof is refused
+    let token_proof = token_proof.unwrap();
+    assert!(challenge
+        .check(
+            solution,
+            Some(&token_proof[..token_proof.len() - 1]),
+            &tokens
+        )
+        .is_err());
+
+    let (_, solution, token_proof) = answer(&challenge, Some("unknown token"));
+    assert!(matches!(
+        challenge.check(solution, token_proof.as_deref(), &tokens),
+        Err(BootstrapError::ChallengeFailed(_))
+    ));
+    assert!(matches!(
+        challenge.check(solution, None, &tokens),
+        Err(BootstrapError::ChallengeFailed(_))
+    ));
+
+    // a proof only holds for the nonce of its challenge
+    let other_challenge = HandshakeChallenge::new(BootstrapChallengeMode::Token, 10);
+    let (_, solution, token_proof) = answer(&other_challenge, Some("first token"));
+    assert!(challenge
+        .check(solution, token_proof.as_deref(), &tokens)
+        .is_err());
+}
+
+#[test]
+fn test_challenge_response_serialization() {
+    let message = BootstrapClientMessage::BootstrapChallengeResponse {
+        solution: 123456,
+        token_proof: Some(vec![42u8; 32]),
+    };
+    let mut bytes = Vec::new();
+    BootstrapClientMessageSerializer::new()
+        .serialize(&message, &mut bytes)
+        .unwrap();
+    let (rest, deserialized) = BootstrapClientMessageDeserializer::new(THREAD_COUNT, 255, 50)
+        .deserialize::<DeserializeError>(&bytes)
+        .unwrap();
+    assert!(rest.is_empty());
+    match deserialized {
+        BootstrapClientMessage::BootstrapChallengeResponse {
+            solution,
+            token_proof,
+        } => {
+            assert_eq!(solution, 123456);
+            assert_eq!(token_proof, Some(vec![42u8; 32]));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+}
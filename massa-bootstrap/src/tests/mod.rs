@@ -1,5 +1,10 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+mod audit;
+mod bandwidth;
 mod binders;
+mod challenge;
+mod retry;
 mod scenarios;
 pub(crate) mod tools;
+mod white_black_list;
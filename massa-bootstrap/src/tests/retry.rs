@@ -0,0 +1,84 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::BootstrapError;
+use crate::retry::BootstrapRetryPolicy;
+use crate::tests::tools::get_bootstrap_config;
+use massa_models::node::NodeId;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+fn bootstrap_list(count: u16) -> Vec<(SocketAddr, NodeId)> {
+    (0..count)
+        .map(|i| {
+            (
+                SocketAddr::from(([127, 0, 0, 1], 31245 + i)),
+                NodeId::new(KeyPair::generate(0).unwrap().get_public_key()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_retry_server_order() {
+    let list = bootstrap_list(3);
+    let config = get_bootstrap_config(list[0].1);
+    let mut policy = BootstrapRetryPolicy::new(&config, &list);
+
+    // the servers are tried in the order of the list at first
+    assert_eq!(policy.next_server().unwrap(), list[0]);
+    policy.record_failure(&list[0].0);
+    assert_eq!(policy.next_server().unwrap(), list[1]);
+    assert_eq!(policy.next_server().unwrap(), list[2]);
+
+    // then the servers that failed the least, the least recently tried first
+    assert_eq!(policy.next_server().unwrap(), list[1]);
+    policy.record_failure(&list[1].0);
+    assert_eq!(policy.next_server().unwrap(), list[2]);
+    assert_eq!(policy.next_server().unwrap(), list[2]);
+    policy.record_failure(&list[2].0);
+    assert_eq!(policy.next_server().unwrap(), list[0]);
+}
+
+#[test]
+fn test_retry_backoff() {
+    let list = bootstrap_list(1);
+    let mut config = get_bootstrap_config(list[0].1);
+    config.retry_delay = MassaTime::from_millis(200);
+    config.max_retry_delay = MassaTime::from_millis(1000);
+    config.retry_jitter = 0.0;
+    let mut policy = BootstrapRetryPolicy::new(&config, &list);
+
+    let delays: Vec<_> = (0..5).map(|_| policy.record_failure(&list[0].0)).collect();
+    assert_eq!(
+        delays,
+        [200, 400, 800, 1000, 1000].map(Duration::from_millis)
+    );
+
+    // the jitter only shortens the delay
+    config.retry_jitter = 0.5;
+    let mut policy = BootstrapRetryPolicy::new(&config, &list);
+    for _ in 0..10 {
+        let delay = policy.record_failure(&list[0].0);
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(1000));
+    }
+}
+
+#[test]
+fn test_retry_max_duration() {
+    let list = bootstrap_list(1);
+    let mut config = get_bootstrap_config(list[0].1);
+    config.retry_delay = MassaTime::from_millis(1000);
+    config.max_bootstrap_duration = MassaTime::from_millis(100);
+    let mut policy = BootstrapRetryPolicy::new(&config, &list);
+
+    policy.next_server().unwrap();
+    // no need to wait past the end of the budget
+    assert!(policy.record_failure(&list[0].0) <= Duration::from_millis(100));
+    std::thread::sleep(Duration::from_millis(150));
+    assert!(matches!(
+        policy.next_server(),
+        Err(BootstrapError::GeneralError(_))
+    ));
+}
@@ -1,36 +1,48 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use super::tools::{
-    get_boot_state, get_peers, get_random_final_state_bootstrap, get_random_ledger_changes,
+    get_boot_state, get_final_block_at, get_peers, get_random_final_state_bootstrap,
+    get_random_ledger_changes,
 };
+use crate::client::verify_trusted_checkpoint;
 use crate::listener::PollEvent;
+use crate::resume::BootstrapCursorStore;
+use crate::snapshot_archive::import_snapshot_archive;
 use crate::tests::tools::{
     assert_eq_bootstrap_graph, get_random_async_pool_changes, get_random_executed_de_changes,
     get_random_executed_ops_changes, get_random_execution_trail_hash_change,
     get_random_pos_changes,
 };
-use crate::BootstrapError;
+use crate::transport::BootstrapStream;
 use crate::{
     client::MockBSConnector, get_state, start_bootstrap_server, tests::tools::get_bootstrap_config,
 };
+use crate::{
+    export_snapshot_archive, BootstrapClientMessage, BootstrapError, BootstrapPhase,
+    TrustedCheckpoint, BOOTSTRAP_CURSOR_FILE,
+};
 use crate::{
     listener::MockBootstrapTcpListener, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
 };
 use massa_async_pool::AsyncPoolConfig;
 use massa_consensus_exports::{bootstrapable_graph::BootstrapableGraph, MockConsensusController};
-use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController};
+use massa_db_exports::{
+    DBBatch, DBPreviousValues, MassaDBConfig, MassaDBController, ShareableMassaDBController,
+};
 use massa_db_worker::MassaDB;
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_final_state::{
     test_exports::{assert_eq_final_state, assert_eq_final_state_hash},
     FinalState, FinalStateConfig, StateChanges,
 };
+use massa_hash::Hash;
 use massa_ledger_exports::LedgerConfig;
 use massa_metrics::MassaMetrics;
 use massa_models::config::{
     DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP,
     KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_DEFERRED_CREDITS_LENGTH,
-    MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, T0,
+    MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
+    POS_PRODUCTION_STATS_HISTORY_CYCLES, T0,
 };
 use massa_models::{
     address::Address, config::MAX_DATASTORE_VALUE_LENGTH, node::NodeId, slot::Slot,
@@ -102,8 +114,13 @@ fn mock_bootstrap_manager(
     let db_config = MassaDBConfig {
         path: temp_dir.path().to_path_buf(),
         max_history_length: 10,
+        max_history_bytes: None,
         max_new_elements: 100,
+        max_stream_batch_bytes: None,
         thread_count: 2,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -130,6 +147,7 @@ fn mock_bootstrap_manager(
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
             initial_deferred_credits_path: None,
         },
         executed_ops_config: ExecutedOpsConfig {
@@ -152,6 +170,7 @@ fn mock_bootstrap_manager(
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        snapshot_interval_cycles: 0,
     };
 
     let final_state_server = Arc::new(RwLock::new(get_random_final_state_bootstrap(
@@ -237,8 +256,13 @@ fn test_bootstrap_server() {
     let db_server_config = MassaDBConfig {
         path: temp_dir_server.path().to_path_buf(),
         max_history_length: 10,
+        max_history_bytes: None,
         max_new_elements: 100,
+        max_stream_batch_bytes: None,
         thread_count,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
     };
     let db_server = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_server_config)) as Box<(dyn MassaDBController + 'static)>
@@ -247,8 +271,13 @@ fn test_bootstrap_server() {
     let db_client_config = MassaDBConfig {
         path: temp_dir_client.path().to_path_buf(),
         max_history_length: 10,
+        max_history_bytes: None,
         max_new_elements: 100,
+        max_stream_batch_bytes: None,
         thread_count,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
     };
     let db_client = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_client_config)) as Box<(dyn MassaDBController + 'static)>
@@ -275,6 +304,7 @@ fn test_bootstrap_server() {
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
             initial_deferred_credits_path: None,
         },
         executed_ops_config: ExecutedOpsConfig {
@@ -297,6 +327,7 @@ fn test_bootstrap_server() {
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        snapshot_interval_cycles: 0,
     };
 
     // setup selector local config
@@ -354,9 +385,11 @@ fn test_bootstrap_server() {
             .pos_state
             .apply_changes_to_batch(changes.pos_changes.clone(), next, false, &mut batch)
             .unwrap();
-        final_write
-            .ledger
-            .apply_changes_to_batch(changes.ledger_changes.clone(), &mut batch);
+        final_write.ledger.apply_changes_to_batch(
+            changes.ledger_changes.clone(),
+            &mut batch,
+            &mut DBPreviousValues::new(),
+        );
         final_write
             .async_pool
             .apply_changes_to_batch(&changes.async_pool_changes, &mut batch);
@@ -424,7 +457,9 @@ fn test_bootstrap_server() {
     let sent_graph_clone = sent_graph.clone();
     stream_mock3
         .expect_get_bootstrap_part()
-        .times(2)
+        // the graph is sent with the first part and has caught up with the final state at the second one,
+        // the last call streams the blocks required since then, once the final state has caught up too
+        .times(3)
         .in_sequence(&mut seq)
         .returning(
             move |last_consensus_step, _slot| match last_consensus_step {
@@ -509,9 +544,11 @@ fn test_bootstrap_server() {
                     .pos_state
                     .apply_changes_to_batch(changes.pos_changes.clone(), next, false, &mut batch)
                     .unwrap();
-                final_write
-                    .ledger
-                    .apply_changes_to_batch(changes.ledger_changes.clone(), &mut batch);
+                final_write.ledger.apply_changes_to_batch(
+                    changes.ledger_changes.clone(),
+                    &mut batch,
+                    &mut DBPreviousValues::new(),
+                );
                 final_write
                     .async_pool
                     .apply_changes_to_batch(&changes.async_pool_changes, &mut batch);
@@ -546,6 +583,7 @@ fn test_bootstrap_server() {
         .unwrap();
 
     // launch the get_state process
+    let (progress_sender, progress_receiver) = crossbeam::channel::unbounded();
     let bootstrap_res = get_state(
         bootstrap_config,
         final_state_client_clone,
@@ -558,9 +596,17 @@ fn test_bootstrap_server() {
         None,
         Arc::new((Mutex::new(false), Condvar::new())),
         metrics,
+        Some(progress_sender),
     )
     .unwrap();
 
+    // check the reported progress
+    let progress = progress_receiver.try_iter().last().unwrap();
+    assert_eq!(progress.phase, BootstrapPhase::Finished);
+    assert!(progress.bytes_downloaded > 0);
+    assert!(progress.keys_received > 0);
+    assert_eq!(progress.estimated_remaining, Some(0.0));
+
     // Make sure the modifier thread has done its job
     mod_thread.join().unwrap();
 
@@ -579,6 +625,49 @@ fn test_bootstrap_server() {
     assert_eq_final_state(&final_state_server.read(), &final_state_client.read());
     assert_eq_final_state_hash(&final_state_server.read(), &final_state_client.read());
 
+    // check the client state against trusted checkpoints
+    let state_slot = final_state_client.read().db.read().get_change_id().unwrap();
+    let state_hash = final_state_client.read().db.read().get_xof_db_hash();
+    let mut checkpoint_config = bootstrap_config.clone();
+    checkpoint_config.trusted_checkpoint = Some(TrustedCheckpoint {
+        slot: state_slot,
+        state_hash: Hash::compute_from(state_hash.to_bytes()),
+    });
+    verify_trusted_checkpoint(&checkpoint_config, &final_state_client).unwrap();
+    checkpoint_config.trusted_checkpoint = Some(TrustedCheckpoint {
+        slot: state_slot,
+        state_hash: Hash::compute_from(b"untrusted state"),
+    });
+    assert!(matches!(
+        verify_trusted_checkpoint(&checkpoint_config, &final_state_client),
+        Err(BootstrapError::CheckpointError(_))
+    ));
+
+    // check the bootstrap from a snapshot archive of the server state
+    let temp_dir_archive = TempDir::new().unwrap();
+    let archive_path = temp_dir_archive.path().join("snapshot_archive");
+    let untrusted_keypair = KeyPair::generate(0).unwrap();
+    export_snapshot_archive(
+        &final_state_server.read(),
+        &untrusted_keypair,
+        &archive_path,
+    )
+    .unwrap();
+    final_state_client.write().reset();
+    assert!(matches!(
+        import_snapshot_archive(bootstrap_config, &archive_path, &final_state_client),
+        Err(BootstrapError::SnapshotArchiveError(_))
+    ));
+    let exported =
+        export_snapshot_archive(&final_state_server.read(), keypair, &archive_path).unwrap();
+    final_state_client.write().reset();
+    let imported =
+        import_snapshot_archive(bootstrap_config, &archive_path, &final_state_client).unwrap();
+    assert_eq!(exported, imported);
+    assert_eq!(imported.node_id, NodeId::new(keypair.get_public_key()));
+    final_state_client.write().recompute_caches();
+    assert_eq_final_state_hash(&final_state_server.read(), &final_state_client.read());
+
     // compute initial draws
     final_state_server.write().compute_initial_draws().unwrap();
     final_state_client.write().compute_initial_draws().unwrap();
@@ -616,6 +705,392 @@ fn test_bootstrap_server() {
     client_selector_manager.stop();
 }
 
+#[test]
+#[serial]
+fn test_bootstrap_graph_follows_final_state() {
+    let thread_count = 2;
+    let periods_per_cycle = 2;
+    let (bootstrap_config, keypair): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+    let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+    let genesis_address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    // setup final state local config
+    let temp_dir_server = TempDir::new().unwrap();
+    let db_server_config = MassaDBConfig {
+        path: temp_dir_server.path().to_path_buf(),
+        max_history_length: 10,
+        max_history_bytes: None,
+        max_new_elements: 100,
+        max_stream_batch_bytes: None,
+        thread_count,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
+    };
+    let db_server = Arc::new(RwLock::new(
+        Box::new(MassaDB::new(db_server_config)) as Box<(dyn MassaDBController + 'static)>
+    ));
+    let temp_dir_client = TempDir::new().unwrap();
+    let db_client_config = MassaDBConfig {
+        path: temp_dir_client.path().to_path_buf(),
+        max_history_length: 10,
+        max_history_bytes: None,
+        max_new_elements: 100,
+        max_stream_batch_bytes: None,
+        thread_count,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
+    };
+    let db_client = Arc::new(RwLock::new(
+        Box::new(MassaDB::new(db_client_config)) as Box<(dyn MassaDBController + 'static)>
+    ));
+    let final_state_local_config = FinalStateConfig {
+        ledger_config: LedgerConfig {
+            thread_count,
+            initial_ledger_path: "".into(),
+            disk_ledger_path: temp_dir_server.path().to_path_buf(),
+            max_key_length: MAX_DATASTORE_KEY_LENGTH,
+            max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        },
+        async_pool_config: AsyncPoolConfig {
+            thread_count,
+            max_length: MAX_ASYNC_POOL_LENGTH,
+            max_function_length: MAX_FUNCTION_NAME_LENGTH,
+            max_function_params_length: MAX_PARAMETERS_SIZE as u64,
+            max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
+        },
+        pos_config: PoSConfig {
+            periods_per_cycle,
+            thread_count,
+            cycle_history_length: POS_SAVED_CYCLES,
+            max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+            max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+            max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
+            initial_deferred_credits_path: None,
+        },
+        executed_ops_config: ExecutedOpsConfig {
+            thread_count,
+            keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+        },
+        executed_denunciations_config: ExecutedDenunciationsConfig {
+            denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+            thread_count,
+            endorsement_count: ENDORSEMENT_COUNT,
+            keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+        },
+        final_history_length: 100,
+        initial_seed_string: "".into(),
+        initial_rolls_path: "".into(),
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_executed_denunciations_length: 1000,
+        thread_count,
+        periods_per_cycle,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        t0: T0,
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+        snapshot_interval_cycles: 0,
+    };
+
+    // setup selector local config
+    let selector_local_config = SelectorConfig {
+        thread_count,
+        periods_per_cycle,
+        genesis_address,
+        ..Default::default()
+    };
+
+    // start proof-of-stake selectors
+    let (mut server_selector_manager, server_selector_controller) =
+        start_selector_worker(selector_local_config.clone())
+            .expect("could not start server selector controller");
+    let (mut client_selector_manager, client_selector_controller) =
+        start_selector_worker(selector_local_config)
+            .expect("could not start client selector controller");
+
+    let pos_server = PoSFinalState::new(
+        final_state_local_config.pos_config.clone(),
+        "",
+        &rolls_path,
+        server_selector_controller.clone(),
+        db_server.clone(),
+    );
+
+    // setup final states
+    let final_state_server = Arc::new(RwLock::new(get_random_final_state_bootstrap(
+        pos_server.unwrap(),
+        final_state_local_config.clone(),
+        db_server.clone(),
+    )));
+
+    let mut current_slot: Slot = Slot::new(0, thread_count - 1);
+
+    for _ in 0..10 {
+        std::thread::sleep(Duration::from_millis(500));
+
+        let mut final_write = final_state_server.write();
+
+        let changes = StateChanges {
+            pos_changes: get_random_pos_changes(10),
+            ledger_changes: get_random_ledger_changes(10),
+            async_pool_changes: get_random_async_pool_changes(10, thread_count),
+            executed_ops_changes: get_random_executed_ops_changes(10),
+            executed_denunciations_changes: get_random_executed_de_changes(10),
+            execution_trail_hash_change: get_random_execution_trail_hash_change(true),
+        };
+
+        let next = current_slot.get_next_slot(thread_count).unwrap();
+
+        let mut batch = DBBatch::new();
+
+        final_write
+            .pos_state
+            .apply_changes_to_batch(changes.pos_changes.clone(), next, false, &mut batch)
+            .unwrap();
+        final_write.ledger.apply_changes_to_batch(
+            changes.ledger_changes.clone(),
+            &mut batch,
+            &mut DBPreviousValues::new(),
+        );
+        final_write
+            .async_pool
+            .apply_changes_to_batch(&changes.async_pool_changes, &mut batch);
+        final_write.executed_ops.apply_changes_to_batch(
+            changes.executed_ops_changes.clone(),
+            next,
+            &mut batch,
+        );
+        final_write.executed_denunciations.apply_changes_to_batch(
+            changes.executed_denunciations_changes.clone(),
+            next,
+            &mut batch,
+        );
+
+        final_write
+            .db
+            .write()
+            .write_batch(batch, Default::default(), Some(next));
+
+        let final_state_hash = final_write.db.read().get_xof_db_hash();
+        let cycle = next.get_cycle(final_state_local_config.periods_per_cycle.clone());
+        final_write
+            .pos_state
+            .feed_cycle_state_hash(cycle, final_state_hash);
+
+        current_slot = next;
+    }
+
+    let final_state_client = Arc::new(RwLock::new(FinalState::create_final_state(
+        PoSFinalState::new(
+            final_state_local_config.pos_config.clone(),
+            "",
+            &rolls_path,
+            client_selector_controller.clone(),
+            db_client.clone(),
+        )
+        .unwrap(),
+        final_state_local_config.clone(),
+        db_client.clone(),
+    )));
+
+    // setup final state mocks.
+    // TODO: work out a way to handle the clone shenanigans in a cleaner manner
+    let final_state_client_clone = final_state_client.clone();
+    let final_state_server_clone1 = final_state_server.clone();
+    let final_state_server_clone2 = final_state_server.clone();
+
+    let (mock_bs_listener, mock_remote_connector) = conn_establishment_mocks();
+    // Setup network command mock-story: hard-code the result of getting bootstrap peers
+    let mut mocked1 = MockProtocolController::new();
+    let mut mocked2 = Box::new(MockProtocolController::new());
+    mocked2
+        .expect_get_bootstrap_peers()
+        .times(1)
+        .returning(|| Ok(get_peers(&keypair.clone())));
+
+    mocked1.expect_clone_box().return_once(move || mocked2);
+
+    let mut stream_mock1 = Box::new(MockConsensusController::new());
+    let mut stream_mock2 = Box::new(MockConsensusController::new());
+    let mut stream_mock3 = Box::new(MockConsensusController::new());
+    // the graph has a final block at each slot of the final state it is streamed with,
+    // and no block is missing once it was streamed with the current slot of the final state
+    let mut streamed_slot: Option<Slot> = None;
+    stream_mock3.expect_get_bootstrap_part().returning(
+        move |last_consensus_step, execution_cursor| {
+            let slot = match execution_cursor {
+                StreamingStep::Ongoing(slot) | StreamingStep::Finished(Some(slot)) => slot,
+                _ => panic!("the graph should be streamed along with the final state"),
+            };
+            if streamed_slot == Some(slot) {
+                return Ok((
+                    BootstrapableGraph {
+                        final_blocks: vec![],
+                    },
+                    PreHashSet::default(),
+                    StreamingStep::Finished(None),
+                ));
+            }
+            streamed_slot = Some(slot);
+            let block = get_final_block_at(slot);
+            let mut ids = match last_consensus_step {
+                StreamingStep::Ongoing(ids) => ids,
+                _ => PreHashSet::default(),
+            };
+            ids.insert(block.block.id);
+            Ok((
+                BootstrapableGraph {
+                    final_blocks: vec![block],
+                },
+                PreHashSet::default(),
+                StreamingStep::Ongoing(ids),
+            ))
+        },
+    );
+
+    stream_mock2
+        .expect_clone_box()
+        .return_once(move || stream_mock3);
+    stream_mock1
+        .expect_clone_box()
+        .return_once(move || stream_mock2);
+
+    let metrics = MassaMetrics::new(
+        false,
+        "0.0.0.0:31248".parse().unwrap(),
+        thread_count,
+        Duration::from_secs(5),
+    )
+    .0;
+
+    let metrics_cloned = metrics.clone();
+
+    // Start the bootstrap server thread
+    let bootstrap_manager_thread = std::thread::Builder::new()
+        .name("bootstrap_thread".to_string())
+        .spawn(move || {
+            let (listener_stopper, _) =
+                BootstrapTcpListener::create(&"127.0.0.1:0".parse().unwrap()).unwrap();
+            start_bootstrap_server(
+                mock_bs_listener,
+                listener_stopper,
+                stream_mock1,
+                Box::new(mocked1),
+                final_state_server_clone1,
+                bootstrap_config.clone(),
+                keypair.clone(),
+                Version::from_str("TEST.1.10").unwrap(),
+                metrics_cloned,
+            )
+            .unwrap()
+        })
+        .unwrap();
+
+    // launch the modifier thread
+    let mod_thread = std::thread::Builder::new()
+        .name("modifier thread".to_string())
+        .spawn(move || {
+            let mut current_slot = Slot::new(5, 1);
+
+            for _ in 0..10 {
+                std::thread::sleep(Duration::from_millis(500));
+                let mut final_write = final_state_server_clone2.write();
+                let next = current_slot.get_next_slot(thread_count).unwrap();
+
+                let changes = StateChanges {
+                    pos_changes: get_random_pos_changes(10),
+                    ledger_changes: get_random_ledger_changes(10),
+                    async_pool_changes: get_random_async_pool_changes(10, thread_count),
+                    executed_ops_changes: get_random_executed_ops_changes(10),
+                    executed_denunciations_changes: get_random_executed_de_changes(10),
+                    execution_trail_hash_change: get_random_execution_trail_hash_change(true),
+                };
+
+                let mut batch = DBBatch::new();
+
+                final_write
+                    .pos_state
+                    .apply_changes_to_batch(changes.pos_changes.clone(), next, false, &mut batch)
+                    .unwrap();
+                final_write.ledger.apply_changes_to_batch(
+                    changes.ledger_changes.clone(),
+                    &mut batch,
+                    &mut DBPreviousValues::new(),
+                );
+                final_write
+                    .async_pool
+                    .apply_changes_to_batch(&changes.async_pool_changes, &mut batch);
+                final_write.executed_ops.apply_changes_to_batch(
+                    changes.executed_ops_changes.clone(),
+                    next,
+                    &mut batch,
+                );
+                final_write.executed_denunciations.apply_changes_to_batch(
+                    changes.executed_denunciations_changes.clone(),
+                    next,
+                    &mut batch,
+                );
+
+                final_write
+                    .db
+                    .write()
+                    .write_batch(batch, Default::default(), Some(next));
+
+                let final_state_hash = final_write.db.read().get_xof_db_hash();
+                let cycle = next.get_cycle(final_state_local_config.periods_per_cycle.clone());
+                final_write
+                    .pos_state
+                    .feed_cycle_state_hash(cycle, final_state_hash);
+
+                current_slot = next;
+            }
+        })
+        .unwrap();
+
+    // launch the get_state process
+    let bootstrap_res = get_state(
+        bootstrap_config,
+        final_state_client_clone,
+        mock_remote_connector,
+        Version::from_str("TEST.1.10").unwrap(),
+        MassaTime::now()
+            .unwrap()
+            .saturating_sub(MassaTime::from_millis(1000)),
+        None,
+        None,
+        Arc::new((Mutex::new(false), Condvar::new())),
+        metrics,
+        None,
+    )
+    .unwrap();
+
+    // Make sure the modifier thread has done its job
+    mod_thread.join().unwrap();
+
+    // check that the graph ends on the slot of the final state
+    let state_slot = final_state_client.read().db.read().get_change_id().unwrap();
+    let graph_slot = bootstrap_res
+        .graph
+        .unwrap()
+        .final_blocks
+        .iter()
+        .map(|block| block.block.content.header.content.slot)
+        .max();
+    assert_eq!(graph_slot, Some(state_slot));
+
+    // stop bootstrap server
+    bootstrap_manager_thread
+        .join()
+        .unwrap()
+        .stop()
+        .expect("could not stop bootstrap server");
+
+    // stop selector controllers
+    server_selector_manager.stop();
+    client_selector_manager.stop();
+}
+
 // Regression test for Issue #3932
 #[test]
 #[serial]
@@ -631,8 +1106,13 @@ fn test_bootstrap_accept_err() {
     let db_server_config = MassaDBConfig {
         path: temp_dir_server.path().to_path_buf(),
         max_history_length: 10,
+        max_history_bytes: None,
         max_new_elements: 100,
+        max_stream_batch_bytes: None,
         thread_count,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
     };
     let db_server = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_server_config)) as Box<(dyn MassaDBController + 'static)>
@@ -659,6 +1139,7 @@ fn test_bootstrap_accept_err() {
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
             initial_deferred_credits_path: None,
         },
         executed_ops_config: ExecutedOpsConfig {
@@ -681,6 +1162,7 @@ fn test_bootstrap_accept_err() {
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        snapshot_interval_cycles: 0,
     };
 
     // setup selector local config
@@ -785,7 +1267,13 @@ fn conn_establishment_mocks() -> (MockBootstrapTcpListener, MockBSConnector) {
         .expect_poll()
         .times(1)
         // Mock the `accept` method here by receiving from the listen-loop thread
-        .returning(move || Ok(PollEvent::NewConnections(vec![conn_rx.recv().unwrap()])))
+        .returning(move || {
+            let (stream, remote_addr) = conn_rx.recv().unwrap();
+            Ok(PollEvent::NewConnections(vec![(
+                Box::new(stream) as Box<dyn BootstrapStream>,
+                remote_addr,
+            )]))
+        })
         .in_sequence(&mut seq);
     mock_bs_listener
         .expect_poll()
@@ -822,3 +1310,70 @@ fn accept_err_accept_stop_mocks() -> (MockBootstrapTcpListener, MockBSConnector)
 
     (mock_bs_listener, MockBSConnector::new())
 }
+
+#[test]
+fn test_bootstrap_cursor_store() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_config = MassaDBConfig {
+        path: temp_dir.path().join("db"),
+        max_history_length: 10,
+        max_history_bytes: None,
+        max_new_elements: 100,
+        max_stream_batch_bytes: None,
+        thread_count: 2,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
+    };
+    let db: ShareableMassaDBController = Arc::new(RwLock::new(
+        Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+    ));
+    let mut config = BOOTSTRAP_CONFIG_KEYPAIR.0.clone();
+    config.bootstrap_cursor_path = Some(temp_dir.path().join(BOOTSTRAP_CURSOR_FILE));
+    let cursor_store = BootstrapCursorStore::new(&config).unwrap();
+    assert!(cursor_store.load(&db).is_none());
+
+    // write a first state part
+    let slot = Slot::new(3, 1);
+    let mut batch = DBBatch::new();
+    batch.insert(b"ledger/a".to_vec(), Some(vec![1]));
+    db.write().write_batch(batch, DBBatch::new(), Some(slot));
+
+    let cursor = |key: &[u8]| BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(slot),
+        last_state_step: StreamingStep::Ongoing(key.to_vec()),
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Ongoing(PreHashSet::default()),
+        send_last_start_period: false,
+    };
+
+    // the cursors of a part written to the db are loaded back, without the consensus cursor
+    cursor_store.save(&cursor(b"ledger/a")).unwrap();
+    match cursor_store.load(&db) {
+        Some(BootstrapClientMessage::AskBootstrapPart {
+            last_slot,
+            last_state_step,
+            last_versioning_step,
+            last_consensus_step,
+            send_last_start_period,
+        }) => {
+            assert_eq!(last_slot, Some(slot));
+            assert_eq!(
+                last_state_step,
+                StreamingStep::Ongoing(b"ledger/a".to_vec())
+            );
+            assert_eq!(last_versioning_step, StreamingStep::Started);
+            assert_eq!(last_consensus_step, StreamingStep::Started);
+            assert!(send_last_start_period);
+        }
+        other => panic!("unexpected cursor: {:?}", other),
+    }
+
+    // the cursors of a part missing from the db are ignored
+    cursor_store.save(&cursor(b"ledger/b")).unwrap();
+    assert!(cursor_store.exists());
+    assert!(cursor_store.load(&db).is_none());
+
+    cursor_store.clear();
+    assert!(!cursor_store.exists());
+}
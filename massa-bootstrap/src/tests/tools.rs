@@ -1,6 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::settings::{BootstrapConfig, IpType};
+use crate::settings::{BootstrapChallengeMode, BootstrapConfig, BootstrapTlsMode, IpType};
 use bitvec::vec::BitVec;
 use massa_async_pool::AsyncPoolChanges;
 use massa_async_pool::{test_exports::get_random_message, AsyncPool};
@@ -387,6 +387,9 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
         bootstrap_timeout: MassaTime::from_millis(120000),
         connect_timeout: MassaTime::from_millis(200),
         retry_delay: MassaTime::from_millis(200),
+        max_retry_delay: MassaTime::from_millis(1000),
+        retry_jitter: 0.0,
+        max_bootstrap_duration: MassaTime::from_millis(0),
         max_ping: MassaTime::from_millis(500),
         read_timeout: MassaTime::from_millis(1000),
         write_timeout: MassaTime::from_millis(1000),
@@ -398,6 +401,12 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
             bootstrap_public_key,
         )],
         keep_ledger: false,
+        bootstrap_cursor_path: None,
+        bootstrap_snapshot_path: None,
+        consensus_state_cache_path: None,
+        trusted_checkpoint: None,
+        parallel_bootstrap_servers: 1,
+        compression_level: 0,
         bootstrap_whitelist_path: PathBuf::from(
             "../massa-node/base_config/bootstrap_whitelist.json",
         ),
@@ -410,6 +419,23 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
         ip_list_max_size: 10,
         per_ip_min_interval: MassaTime::from_millis(10000),
         rate_limit: std::u64::MAX,
+        global_rate_limit: 0,
+        per_ip_rate_limit: 0,
+        max_session_duration: MassaTime::from_millis(120000),
+        audit_log_path: None,
+        audit_log_max_size: 10_485_760,
+        tls_mode: BootstrapTlsMode::Disabled,
+        tls_certificate_path: None,
+        tls_private_key_path: None,
+        connect_with_tls: false,
+        accept_quic: false,
+        connect_with_quic: false,
+        challenge_mode: BootstrapChallengeMode::Disabled,
+        challenge_difficulty: 8,
+        challenge_tokens: Vec::new(),
+        challenge_token: None,
+        challenge_timeout: MassaTime::from_millis(5000),
+        max_simultaneous_challenges: 8,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -459,7 +485,8 @@ pub fn assert_eq_bootstrap_graph(v1: &BootstrapableGraph, v2: &BootstrapableGrap
     assert_eq!(data1, data2, "BootstrapableGraph mismatch")
 }
 
-pub fn get_boot_state() -> BootstrapableGraph {
+/// Creates a final block at `slot`, with dummy parents
+pub fn get_final_block_at(slot: Slot) -> ExportActiveBlock {
     let keypair = KeyPair::generate(0).unwrap();
 
     let block = Block::new_verifiable(
@@ -470,13 +497,13 @@ pub fn get_boot_state() -> BootstrapableGraph {
                     announced_version: None,
                     // associated slot
                     // all header endorsements are supposed to point towards this one
-                    slot: Slot::new(1, 0),
+                    slot,
                     parents: vec![get_dummy_block_id("p1"); THREAD_COUNT as usize],
                     operation_merkle_root: Hash::compute_from("op_hash".as_bytes()),
                     endorsements: vec![
                         Endorsement::new_verifiable(
                             Endorsement {
-                                slot: Slot::new(1, 0),
+                                slot,
                                 index: 1,
                                 endorsed_block: get_dummy_block_id("p1"),
                             },
@@ -486,7 +513,7 @@ pub fn get_boot_state() -> BootstrapableGraph {
                         .unwrap(),
                         Endorsement::new_verifiable(
                             Endorsement {
-                                slot: Slot::new(1, 0),
+                                slot,
                                 index: 3,
                                 endorsed_block: get_dummy_block_id("p1"),
                             },
@@ -509,11 +536,15 @@ pub fn get_boot_state() -> BootstrapableGraph {
     .unwrap();
 
     // TODO: We currently lost information. Need to use shared storage
-    let block1 = ExportActiveBlock {
+    ExportActiveBlock {
         block,
         parents: vec![(get_dummy_block_id("b1"), 4777); THREAD_COUNT as usize],
         is_final: true,
-    };
+    }
+}
+
+pub fn get_boot_state() -> BootstrapableGraph {
+    let block1 = get_final_block_at(Slot::new(1, 0));
 
     let boot_graph = BootstrapableGraph {
         final_blocks: vec![block1],
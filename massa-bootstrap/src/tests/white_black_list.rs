@@ -0,0 +1,102 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::BootstrapError;
+use crate::white_black_list::{IpRange, SharedWhiteBlackList};
+use std::net::IpAddr;
+use tempfile::TempDir;
+
+fn check(list: &SharedWhiteBlackList, ip: &str) -> Result<(), BootstrapError> {
+    list.check_ip(ip.parse::<IpAddr>().unwrap())
+}
+
+#[test]
+fn test_ip_range() {
+    let range: IpRange = "192.168.12.34/16".parse().unwrap();
+    assert_eq!(range.to_string(), "192.168.0.0/16");
+    assert!(range.contains(&"192.168.255.1".parse().unwrap()));
+    assert!(!range.contains(&"192.169.0.1".parse().unwrap()));
+    assert!(!range.contains(&"::ffff:c0a8:1".parse().unwrap()));
+
+    let range: IpRange = "2001:db8::/32".parse().unwrap();
+    assert!(range.contains(&"2001:db8:1::1".parse().unwrap()));
+    assert!(!range.contains(&"2001:db9::1".parse().unwrap()));
+
+    // IPv4-mapped ranges are IPv4 ranges
+    let range: IpRange = "::ffff:10.0.0.0/104".parse().unwrap();
+    assert_eq!(range.to_string(), "10.0.0.0/8");
+
+    assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+    assert!("10.0.0.0".parse::<IpRange>().is_err());
+}
+
+#[test]
+fn test_white_black_list_precedence() {
+    let temp_dir = TempDir::new().unwrap();
+    let white_path = temp_dir.path().join("whitelist.json");
+    let black_path = temp_dir.path().join("blacklist.json");
+    std::fs::write(
+        &white_path,
+        r#"["10.1.2.3", "192.168.0.0/16", "2001:db8::/32"]"#,
+    )
+    .unwrap();
+    std::fs::write(
+        &black_path,
+        r#"["10.0.0.0/8", "192.168.1.1", "192.168.2.0/24"]"#,
+    )
+    .unwrap();
+    let list = SharedWhiteBlackList::new(white_path.clone(), black_path).unwrap();
+
+    // a whitelisted IP inside a blacklisted range is allowed
+    assert!(check(&list, "10.1.2.3").is_ok());
+    assert!(check(&list, "::ffff:10.1.2.3").is_ok());
+    assert!(matches!(
+        check(&list, "10.1.2.4"),
+        Err(BootstrapError::BlackListed(_))
+    ));
+    // a blacklisted IP or range inside a whitelisted range is refused
+    assert!(check(&list, "192.168.3.1").is_ok());
+    assert!(matches!(
+        check(&list, "192.168.1.1"),
+        Err(BootstrapError::BlackListed(_))
+    ));
+    assert!(matches!(
+        check(&list, "192.168.2.7"),
+        Err(BootstrapError::BlackListed(_))
+    ));
+    // an IP outside of the whitelist is refused
+    assert!(check(&list, "2001:db8::1").is_ok());
+    assert!(matches!(
+        check(&list, "2001:db9::1"),
+        Err(BootstrapError::WhiteListed(_))
+    ));
+
+    // the ranges are kept when IPs are added to the list file
+    list.add_ips_to_whitelist(vec!["172.16.0.1".parse().unwrap()])
+        .unwrap();
+    let entries: Vec<String> =
+        serde_json::from_str(&std::fs::read_to_string(&white_path).unwrap()).unwrap();
+    assert_eq!(
+        entries,
+        vec!["10.1.2.3", "172.16.0.1", "192.168.0.0/16", "2001:db8::/32"]
+    );
+}
+
+#[test]
+fn test_white_black_list_reload() {
+    let temp_dir = TempDir::new().unwrap();
+    let white_path = temp_dir.path().join("whitelist.json");
+    let black_path = temp_dir.path().join("blacklist.json");
+    let mut list = SharedWhiteBlackList::new(white_path.clone(), black_path.clone()).unwrap();
+    assert!(check(&list, "10.0.0.1").is_ok());
+
+    std::fs::write(&black_path, r#"["10.0.0.0/24"]"#).unwrap();
+    list.update().unwrap();
+    assert!(matches!(
+        check(&list, "10.0.0.1"),
+        Err(BootstrapError::BlackListed(_))
+    ));
+    assert_eq!(
+        list.get_black_list_ranges().unwrap(),
+        ["10.0.0.0/24".parse().unwrap()].into_iter().collect()
+    );
+}
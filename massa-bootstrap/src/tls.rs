@@ -0,0 +1,287 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Optional TLS layer of the bootstrap connections.
+//!
+//! The server and its messages are still authenticated by the signatures of the bootstrap protocol:
+//! TLS only hides the content of the connection from the network. The client thus accepts any
+//! certificate, and the server generates a self-signed one if none is configured.
+//!
+//! A TLS client is told apart from a plain one by the first byte it sends, so that a server
+//! accepting TLS connections keeps bootstrapping the clients that do not use it.
+
+use crate::error::BootstrapError;
+use crate::settings::BootstrapTlsMode;
+use crate::transport::BootstrapStream;
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{
+    Certificate, ClientConfig, ClientConnection, PrivateKey, ServerConfig, ServerConnection,
+    ServerName, Stream,
+};
+use rustls_pemfile::Item;
+use std::{
+    fs::File,
+    io::{self, BufReader, ErrorKind, Read, Write},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Content type of a TLS handshake record: the first byte sent by a TLS client
+const TLS_HANDSHAKE_RECORD: u8 = 0x16;
+
+/// TLS settings of the bootstrap server
+#[derive(Clone)]
+pub(crate) struct ServerTls {
+    config: Arc<ServerConfig>,
+    /// whether plain connections are refused
+    required: bool,
+}
+
+impl ServerTls {
+    /// Loads the certificate of the server, or generates a self-signed one if no file is given.
+    /// Returns `None` if TLS is disabled.
+    pub(crate) fn new(
+        mode: BootstrapTlsMode,
+        certificate_path: Option<&Path>,
+        private_key_path: Option<&Path>,
+    ) -> Result<Option<Self>, BootstrapError> {
+        if mode == BootstrapTlsMode::Disabled {
+            return Ok(None);
+        }
+        let (certificates, private_key) = match (certificate_path, private_key_path) {
+            (Some(certificate_path), Some(private_key_path)) => (
+                load_certificates(certificate_path)?,
+                load_private_key(private_key_path)?,
+            ),
+            (None, None) => self_signed_certificate()?,
+            _ => {
+                return Err(BootstrapError::TlsError(
+                    "the TLS certificate and private key files must be set together".to_string(),
+                ))
+            }
+        };
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certificates, private_key)
+            .map_err(|e| BootstrapError::TlsError(e.to_string()))?;
+        Ok(Some(ServerTls {
+            config: Arc::new(config),
+            required: mode == BootstrapTlsMode::Required,
+        }))
+    }
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>, BootstrapError> {
+    let file = File::open(path).map_err(|e| {
+        BootstrapError::TlsError(format!("could not open {}: {}", path.display(), e))
+    })?;
+    let certificates = rustls_pemfile::certs(&mut BufReader::new(file)).map_err(|e| {
+        BootstrapError::TlsError(format!("could not read {}: {}", path.display(), e))
+    })?;
+    if certificates.is_empty() {
+        return Err(BootstrapError::TlsError(format!(
+            "no certificate found in {}",
+            path.display()
+        )));
+    }
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey, BootstrapError> {
+    let file = File::open(path).map_err(|e| {
+        BootstrapError::TlsError(format!("could not open {}: {}", path.display(), e))
+    })?;
+    let items = rustls_pemfile::read_all(&mut BufReader::new(file)).map_err(|e| {
+        BootstrapError::TlsError(format!("could not read {}: {}", path.display(), e))
+    })?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            BootstrapError::TlsError(format!("no private key found in {}", path.display()))
+        })
+}
+
+pub(crate) fn self_signed_certificate() -> Result<(Vec<Certificate>, PrivateKey), BootstrapError> {
+    let certificate = rcgen::generate_simple_self_signed(vec!["massa-bootstrap".to_string()])
+        .map_err(|e| BootstrapError::TlsError(e.to_string()))?;
+    let certificate_der = certificate
+        .serialize_der()
+        .map_err(|e| BootstrapError::TlsError(e.to_string()))?;
+    Ok((
+        vec![Certificate(certificate_der)],
+        PrivateKey(certificate.serialize_private_key_der()),
+    ))
+}
+
+/// Accepts any certificate: the server is authenticated by the signatures of its messages
+struct AnyServerCertificate;
+
+impl ServerCertVerifier for AnyServerCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// TLS settings of the bootstrap client
+pub(crate) fn client_tls_config() -> Arc<ClientConfig> {
+    Arc::new(
+        ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(AnyServerCertificate))
+            .with_no_client_auth(),
+    )
+}
+
+/// TLS side of a connection
+enum TlsConnection {
+    Client(Box<ClientConnection>),
+    Server(Box<ServerConnection>),
+}
+
+/// Stream of a bootstrap connection, with its TLS layer once started
+pub(crate) struct BootstrapTransport {
+    stream: Box<dyn BootstrapStream>,
+    tls: Option<TlsConnection>,
+}
+
+impl BootstrapTransport {
+    pub(crate) fn new(stream: impl BootstrapStream + 'static) -> Self {
+        BootstrapTransport {
+            stream: Box::new(stream),
+            tls: None,
+        }
+    }
+
+    pub(crate) fn set_read_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        self.stream.set_read_timeout(duration)
+    }
+
+    pub(crate) fn set_write_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        self.stream.set_write_timeout(duration)
+    }
+
+    /// Starts the TLS layer of a client connection, unless the transport is already encrypted.
+    /// The TLS handshake is made when the first bytes are sent.
+    pub(crate) fn start_client_tls(
+        &mut self,
+        config: Arc<ClientConfig>,
+    ) -> Result<(), BootstrapError> {
+        if self.stream.is_encrypted() {
+            return Ok(());
+        }
+        let server_name = ServerName::IpAddress(self.stream.peer_addr()?.ip());
+        let connection = ClientConnection::new(config, server_name)
+            .map_err(|e| BootstrapError::TlsError(e.to_string()))?;
+        self.tls = Some(TlsConnection::Client(Box::new(connection)));
+        Ok(())
+    }
+
+    /// Starts the TLS layer of a server connection if the client opens it with a TLS handshake.
+    /// Waits for the first byte sent by the client until `deadline`.
+    /// The connections of an encrypted transport are accepted as they are.
+    pub(crate) fn accept_tls(
+        &mut self,
+        tls: &ServerTls,
+        deadline: Option<Instant>,
+    ) -> Result<(), BootstrapError> {
+        if self.stream.is_encrypted() {
+            return Ok(());
+        }
+        if self.peek_first_byte(deadline)? != TLS_HANDSHAKE_RECORD {
+            if tls.required {
+                return Err(BootstrapError::TlsError(
+                    "this bootstrap server only accepts TLS connections".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        let connection = ServerConnection::new(tls.config.clone())
+            .map_err(|e| BootstrapError::TlsError(e.to_string()))?;
+        self.tls = Some(TlsConnection::Server(Box::new(connection)));
+        Ok(())
+    }
+
+    fn peek_first_byte(&mut self, deadline: Option<Instant>) -> io::Result<u8> {
+        let mut byte = [0u8; 1];
+        loop {
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let dur = deadline.saturating_duration_since(Instant::now());
+                    if dur.is_zero() {
+                        return Err(io::Error::new(ErrorKind::TimedOut, "deadline has elapsed"));
+                    }
+                    Some(dur)
+                }
+                None => None,
+            };
+            self.stream.set_read_timeout(timeout)?;
+            match self.stream.peek(&mut byte) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(_) => return Ok(byte[0]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Read for BootstrapTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.tls {
+            None => self.stream.read(buf),
+            Some(TlsConnection::Client(connection)) => {
+                Stream::new(connection.as_mut(), &mut self.stream).read(buf)
+            }
+            Some(TlsConnection::Server(connection)) => {
+                Stream::new(connection.as_mut(), &mut self.stream).read(buf)
+            }
+        }
+    }
+}
+
+/// The TLS records of the written bytes are sent before `write` returns,
+/// so that the write timeouts of the bindings apply to them.
+impl Write for BootstrapTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.tls {
+            None => self.stream.write(buf),
+            Some(TlsConnection::Client(connection)) => {
+                let mut stream = Stream::new(connection.as_mut(), &mut self.stream);
+                let written = stream.write(buf)?;
+                stream.flush()?;
+                Ok(written)
+            }
+            Some(TlsConnection::Server(connection)) => {
+                let mut stream = Stream::new(connection.as_mut(), &mut self.stream);
+                let written = stream.write(buf)?;
+                stream.flush()?;
+                Ok(written)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.tls {
+            None => self.stream.flush(),
+            Some(TlsConnection::Client(connection)) => {
+                Stream::new(connection.as_mut(), &mut self.stream).flush()
+            }
+            Some(TlsConnection::Server(connection)) => {
+                Stream::new(connection.as_mut(), &mut self.stream).flush()
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Transports of the bootstrap connections.
+//!
+//! The bindings read and write the messages through a blocking [`BootstrapStream`] with timeouts:
+//! a TCP stream, or a QUIC stream (see `quic.rs`) whose congestion control holds up better
+//! on lossy long-distance links.
+
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream},
+    time::Duration,
+};
+
+/// Blocking duplex stream of a bootstrap connection
+pub trait BootstrapStream: Read + Write + Send {
+    /// Timeout of the reads, `None` to wait forever
+    fn set_read_timeout(&self, duration: Option<Duration>) -> io::Result<()>;
+
+    /// Timeout of the writes, `None` to wait forever
+    fn set_write_timeout(&self, duration: Option<Duration>) -> io::Result<()>;
+
+    /// Address of the remote end of the stream
+    fn peer_addr(&self) -> io::Result<SocketAddr>;
+
+    /// Reads the next bytes without consuming them
+    fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Whether the transport already encrypts the connection, so that no TLS layer is needed
+    fn is_encrypted(&self) -> bool {
+        false
+    }
+}
+
+impl BootstrapStream for TcpStream {
+    fn set_read_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, duration)
+    }
+
+    fn set_write_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_write_timeout(self, duration)
+    }
+
+    fn peer_addr(&self) -> io::Result<SocketAddr> {
+        TcpStream::peer_addr(self)
+    }
+
+    fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        TcpStream::peek(self, buf)
+    }
+}
+
+impl<S: BootstrapStream + ?Sized> BootstrapStream for Box<S> {
+    fn set_read_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        (**self).set_read_timeout(duration)
+    }
+
+    fn set_write_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
+        (**self).set_write_timeout(duration)
+    }
+
+    fn peer_addr(&self) -> io::Result<SocketAddr> {
+        (**self).peer_addr()
+    }
+
+    fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).peek(buf)
+    }
+
+    fn is_encrypted(&self) -> bool {
+        (**self).is_encrypted()
+    }
+}
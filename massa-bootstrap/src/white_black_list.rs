@@ -1,8 +1,19 @@
+//! White and black lists of the IPs allowed to bootstrap on the server.
+//!
+//! The list files hold a JSON array of IP addresses and CIDR ranges, like `"192.168.0.0/16"`
+//! or `"2001:db8::/32"`. They are reloaded every `cache_duration` by the server.
+//!
+//! An IP matching both lists is allowed if its most specific entry is in the whitelist:
+//! a whitelisted IP inside a blacklisted range is allowed, a blacklisted IP inside a
+//! whitelisted range is refused, and the blacklist wins between entries of the same prefix length.
+
 use std::{
     borrow::Cow,
     collections::HashSet,
-    net::{IpAddr, SocketAddr},
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     path::{Path, PathBuf},
+    str::FromStr,
     sync::Arc,
 };
 
@@ -13,6 +24,136 @@ use tracing::{info, warn};
 
 use crate::tools::to_canonical;
 
+/// Range of IP addresses in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpRange {
+    /// Creates the range of the addresses sharing the first `prefix_len` bits of `ip`.
+    /// IPv4-mapped IPv6 ranges are converted to IPv4 ranges.
+    pub fn new(ip: IpAddr, prefix_len: u8) -> Result<Self, BootstrapError> {
+        let (ip, prefix_len) = match ip {
+            IpAddr::V6(v6) if prefix_len >= 96 => match v6.to_ipv4_mapped() {
+                Some(v4) => (IpAddr::V4(v4), prefix_len - 96),
+                None => (ip, prefix_len),
+            },
+            _ => (ip, prefix_len),
+        };
+        if prefix_len > max_prefix_len(&ip) {
+            return Err(BootstrapError::InitListError(format!(
+                "invalid prefix length {} for {}",
+                prefix_len, ip
+            )));
+        }
+        Ok(IpRange {
+            network: mask(ip, prefix_len),
+            prefix_len,
+        })
+    }
+
+    /// Number of leading bits shared by the addresses of the range
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Whether the (canonical) `ip` is in the range
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        ip.is_ipv4() == self.network.is_ipv4() && mask(*ip, self.prefix_len) == self.network
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = BootstrapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BootstrapError::InitListError(format!("invalid IP range: {}", s));
+        let (ip, prefix_len) = s.split_once('/').ok_or_else(invalid)?;
+        IpRange::new(
+            ip.parse().map_err(|_| invalid())?,
+            prefix_len.parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+fn max_prefix_len(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// Keeps the first `prefix_len` bits of `ip`
+fn mask(ip: IpAddr, prefix_len: u8) -> IpAddr {
+    match ip {
+        IpAddr::V4(v4) => {
+            let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+        }
+        IpAddr::V6(v6) => {
+            let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+        }
+    }
+}
+
+/// IPs and IP ranges of a white or black list
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct IpList {
+    ips: HashSet<IpAddr>,
+    ranges: HashSet<IpRange>,
+}
+
+impl IpList {
+    /// Parses the entries of a list file: IP addresses and CIDR ranges
+    fn from_entries(entries: Vec<String>) -> Result<Self, BootstrapError> {
+        let mut list = IpList::default();
+        for entry in entries {
+            if entry.contains('/') {
+                list.ranges.insert(entry.parse()?);
+            } else {
+                let ip = entry.parse::<IpAddr>().map_err(|e| {
+                    BootstrapError::InitListError(format!("invalid IP {}: {}", entry, e))
+                })?;
+                list.ips.insert(to_canonical(ip));
+            }
+        }
+        Ok(list)
+    }
+
+    /// Entries of the list file, the IPs first
+    fn to_entries(&self) -> Vec<String> {
+        let mut ips: Vec<_> = self.ips.iter().collect();
+        ips.sort();
+        let mut ranges: Vec<_> = self.ranges.iter().collect();
+        ranges.sort();
+        ips.into_iter()
+            .map(|ip| ip.to_string())
+            .chain(ranges.into_iter().map(|range| range.to_string()))
+            .collect()
+    }
+
+    /// Prefix length of the most specific entry matching the (canonical) `ip`, an IP entry matching all its bits
+    fn longest_match(&self, ip: &IpAddr) -> Option<u8> {
+        if self.ips.contains(ip) {
+            return Some(max_prefix_len(ip));
+        }
+        self.ranges
+            .iter()
+            .filter(|range| range.contains(ip))
+            .map(IpRange::prefix_len)
+            .max()
+    }
+}
+
 /// A wrapper around the white/black lists that allows efficient sharing between threads
 // TODO: don't clone the path-bufs...
 #[derive(Clone)]
@@ -35,25 +176,48 @@ impl SharedWhiteBlackList<'_> {
         })
     }
 
-    /// get the white list
+    /// get the IPs of the white list, without its ranges
     pub fn get_white_list(&self) -> Option<HashSet<IpAddr>> {
-        self.inner.read().white_list.clone()
+        self.inner
+            .read()
+            .white_list
+            .as_ref()
+            .map(|list| list.ips.clone())
     }
 
-    /// get the black list
+    /// get the IPs of the black list, without its ranges
     pub fn get_black_list(&self) -> Option<HashSet<IpAddr>> {
-        self.inner.read().black_list.clone()
+        self.inner
+            .read()
+            .black_list
+            .as_ref()
+            .map(|list| list.ips.clone())
+    }
+
+    /// get the ranges of the white list
+    pub fn get_white_list_ranges(&self) -> Option<HashSet<IpRange>> {
+        self.inner
+            .read()
+            .white_list
+            .as_ref()
+            .map(|list| list.ranges.clone())
+    }
+
+    /// get the ranges of the black list
+    pub fn get_black_list_ranges(&self) -> Option<HashSet<IpRange>> {
+        self.inner
+            .read()
+            .black_list
+            .as_ref()
+            .map(|list| list.ranges.clone())
     }
 
     /// Add IP address to the black list
     pub fn add_ips_to_blacklist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
-        if let Some(black_list) = &mut write_lock.black_list {
-            black_list.extend(ips);
-        } else {
-            write_lock.black_list = Some(HashSet::from_iter(ips));
-        };
-        self.write_to_file(&self.black_path, write_lock.black_list.as_ref().unwrap())?;
+        let black_list = write_lock.black_list.get_or_insert_with(Default::default);
+        black_list.ips.extend(ips.into_iter().map(to_canonical));
+        self.write_to_file(&self.black_path, black_list)?;
         Ok(())
     }
 
@@ -62,7 +226,7 @@ impl SharedWhiteBlackList<'_> {
         let mut write_lock = self.inner.write();
         if let Some(black_list) = &mut write_lock.black_list {
             for ip in ips {
-                black_list.remove(&ip);
+                black_list.ips.remove(&to_canonical(ip));
             }
             self.write_to_file(&self.black_path, black_list)?;
         }
@@ -72,12 +236,9 @@ impl SharedWhiteBlackList<'_> {
     /// Add IP address to the white list
     pub fn add_ips_to_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
-        if let Some(white_list) = &mut write_lock.white_list {
-            white_list.extend(ips);
-        } else {
-            write_lock.white_list = Some(HashSet::from_iter(ips));
-        };
-        self.write_to_file(&self.white_path, write_lock.white_list.as_ref().unwrap())?;
+        let white_list = write_lock.white_list.get_or_insert_with(Default::default);
+        white_list.ips.extend(ips.into_iter().map(to_canonical));
+        self.write_to_file(&self.white_path, white_list)?;
         Ok(())
     }
 
@@ -86,7 +247,7 @@ impl SharedWhiteBlackList<'_> {
         let mut write_lock = self.inner.write();
         if let Some(white_list) = &mut write_lock.white_list {
             for ip in ips {
-                white_list.remove(&ip);
+                white_list.ips.remove(&to_canonical(ip));
             }
             self.write_to_file(&self.white_path, white_list)?;
         }
@@ -94,12 +255,8 @@ impl SharedWhiteBlackList<'_> {
     }
 
     /// write list to file
-    fn write_to_file(
-        &self,
-        file_path: &Path,
-        data: &HashSet<IpAddr>,
-    ) -> Result<(), BootstrapError> {
-        let list = serde_json::to_string(data).map_err(|e| {
+    fn write_to_file(&self, file_path: &Path, data: &IpList) -> Result<(), BootstrapError> {
+        let list = serde_json::to_string(&data.to_entries()).map_err(|e| {
             warn!(error = ?e, "failed to serialize list");
             BootstrapError::SerializationError(e.to_string())
         })?;
@@ -137,37 +294,57 @@ impl SharedWhiteBlackList<'_> {
         Ok(())
     }
 
+    /// Checks the IP against the lists, its most specific entry deciding if it matches both
+    pub(crate) fn check_ip(&self, ip: IpAddr) -> Result<(), BootstrapError> {
+        self.inner.read().check_ip(ip)
+    }
+
     #[cfg_attr(test, allow(unreachable_code, unused_variables))]
     pub(crate) fn is_ip_allowed(&self, remote_addr: &SocketAddr) -> Result<(), BootstrapError> {
         #[cfg(test)]
         return Ok(());
 
-        let ip = to_canonical(remote_addr.ip());
-        // whether the peer IP address is blacklisted
-        let read = self.inner.read();
-        if let Some(ip_list) = &read.black_list {
-            if ip_list.contains(&ip) {
+        let res = self.check_ip(remote_addr.ip());
+        match &res {
+            Err(BootstrapError::BlackListed(_)) => {
                 massa_trace!("bootstrap.lib.run.select.accept.refuse_blacklisted", {"remote_addr": remote_addr});
-                return Err(BootstrapError::BlackListed(ip.to_string()));
             }
-            // whether the peer IP address is not present in the whitelist
-        }
-        if let Some(ip_list) = &read.white_list {
-            if !ip_list.contains(&ip) {
+            Err(BootstrapError::WhiteListed(_)) => {
                 massa_trace!("bootstrap.lib.run.select.accept.refuse_not_whitelisted", {"remote_addr": remote_addr});
-                return Err(BootstrapError::WhiteListed(ip.to_string()));
             }
+            _ => {}
         }
-        Ok(())
+        res
     }
 }
 
 impl WhiteBlackListInner {
+    fn check_ip(&self, ip: IpAddr) -> Result<(), BootstrapError> {
+        let ip = to_canonical(ip);
+        let white_match = self.white_list.as_ref().map(|list| list.longest_match(&ip));
+        let black_match = self
+            .black_list
+            .as_ref()
+            .and_then(|list| list.longest_match(&ip));
+        if let Some(black_prefix_len) = black_match {
+            // the blacklist wins between entries of the same prefix length
+            if !matches!(white_match, Some(Some(white_prefix_len)) if white_prefix_len > black_prefix_len)
+            {
+                return Err(BootstrapError::BlackListed(ip.to_string()));
+            }
+        }
+        // whether the peer IP address is not present in the whitelist
+        if let Some(None) = white_match {
+            return Err(BootstrapError::WhiteListed(ip.to_string()));
+        }
+        Ok(())
+    }
+
     #[allow(clippy::type_complexity)]
     fn update_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<IpList>, Option<IpList>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, false)?,
             Self::load_list(blacklist_path, false)?,
@@ -178,17 +355,14 @@ impl WhiteBlackListInner {
     fn init_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<IpList>, Option<IpList>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, true)?,
             Self::load_list(blacklist_path, true)?,
         ))
     }
 
-    fn load_list(
-        list_path: &Path,
-        is_init: bool,
-    ) -> Result<Option<HashSet<IpAddr>>, BootstrapError> {
+    fn load_list(list_path: &Path, is_init: bool) -> Result<Option<IpList>, BootstrapError> {
         match std::fs::read_to_string(list_path) {
             Err(e) => {
                 if is_init {
@@ -201,19 +375,13 @@ impl WhiteBlackListInner {
                 Ok(None)
             }
             Ok(list) => {
-                let res = Some(
-                    serde_json::from_str::<HashSet<IpAddr>>(list.as_str())
-                        .map_err(|e| {
-                            BootstrapError::InitListError(format!(
-                                "Failed to parse bootstrap whitelist : {}",
-                                e
-                            ))
-                        })?
-                        .into_iter()
-                        .map(to_canonical)
-                        .collect(),
-                );
-                Ok(res)
+                let entries = serde_json::from_str::<Vec<String>>(list.as_str()).map_err(|e| {
+                    BootstrapError::InitListError(format!(
+                        "Failed to parse bootstrap whitelist : {}",
+                        e
+                    ))
+                })?;
+                Ok(Some(IpList::from_entries(entries)?))
             }
         }
     }
@@ -221,6 +389,6 @@ impl WhiteBlackListInner {
 
 #[derive(Default)]
 pub(crate) struct WhiteBlackListInner {
-    white_list: Option<HashSet<IpAddr>>,
-    black_list: Option<HashSet<IpAddr>>,
+    white_list: Option<IpList>,
+    black_list: Option<IpList>,
 }
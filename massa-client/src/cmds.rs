@@ -5,10 +5,11 @@ use crate::{client_warning, rpc_error};
 use anyhow::{anyhow, bail, Result};
 use console::style;
 use massa_api_exports::{
-    address::{AddressInfo, CompactAddressInfo},
+    address::{AddressInfo, AddressProductionStats, CompactAddressInfo},
     datastore::DatastoreEntryInput,
-    execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
+    execution::{CacheSizesUpdate, ReadOnlyBytecodeExecution, ReadOnlyCall},
     operation::OperationInput,
+    page::PageRequest,
 };
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
@@ -87,6 +88,27 @@ pub enum Command {
     )]
     node_ban_by_id,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the persisted score, last seen time, ban expiry and handshake failures of the known peers"
+    )]
+    node_get_peer_store,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Id Score", pwd_not_needed = "true"),
+        message = "overwrite the persisted score of a peer"
+    )]
+    node_set_peer_score,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Id1 Id2 ...", pwd_not_needed = "true"),
+        message = "forget everything persisted about given id(s), lifting their ban"
+    )]
+    node_forget_peers,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -101,6 +123,71 @@ pub enum Command {
     )]
     node_get_staking_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "CycleCount Address1 Address2 ... [output=Path.csv|Path.json]",
+            pwd_not_needed = "true"
+        ),
+        message = "show the production stats of the given addresses over the last cycles, optionally exported to a CSV or JSON file"
+    )]
+    node_get_production_stats,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SlotCount", pwd_not_needed = "true"),
+        message = "show the call count and time of each host ABI function over the last executed slots"
+    )]
+    node_get_abi_profile,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the current sizes of the execution caches"
+    )]
+    node_get_cache_sizes,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "[module_lru_cache_size=Size] [module_hd_cache_size=Size] [max_final_operation_changes_slots=Size]",
+            pwd_not_needed = "true"
+        ),
+        message = "resize the execution caches without restarting the node, the omitted sizes being left unchanged"
+    )]
+    node_set_cache_sizes,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "[address=Address] [limit=Count] [page=Index]",
+            pwd_not_needed = "true"
+        ),
+        message = "show the pending operations of the pool, from the highest to the lowest priority, optionally only those of an address"
+    )]
+    node_get_pending_operations,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[TopSenderCount]", pwd_not_needed = "true"),
+        message = "show the operation count per thread, the fee distribution and the top senders of the operation pool"
+    )]
+    node_get_operation_pool_summary,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the entries of the denunciation pool: detected double-stakes and whether their denunciation was included"
+    )]
+    node_get_denunciation_pool,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the number of operations checked and rejected by each stage of the pool admission pipeline, and the time spent"
+    )]
+    node_get_operation_admission_stats,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address1 Address2 ..."),
@@ -150,6 +237,20 @@ pub enum Command {
     )]
     get_status,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the network upgrades (MIPs) known by the node, with their state and vote ratio"
+    )]
+    get_mip_status,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the minimal fee accepted by the node's operation pool and the fee likely to get an operation included in the next blocks"
+    )]
+    get_fee_recommendation,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address1 Address2 ...", pwd_not_needed = "true"),
@@ -157,6 +258,23 @@ pub enum Command {
     )]
     get_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "Address start=Period,Thread end=Period,Thread",
+            pwd_not_needed = "true"
+        ),
+        message = "show when deferred coins (e.g. roll sale refunds) will be credited to an address, optionally within a slot range"
+    )]
+    get_deferred_credits,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "TopN", pwd_not_needed = "true"),
+        message = "show the roll distribution statistics of the recent cycles (Gini coefficient, share of the TopN biggest stakers, active rolls)"
+    )]
+    get_stakers_statistics,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address Key", pwd_not_needed = "true"),
@@ -188,7 +306,7 @@ pub enum Command {
     #[strum(
         ascii_case_insensitive,
         props(
-            args = "start=slot_period,slot_thread end=slot_period,slot_thread emitter_address=Address caller_address=Address operation_id=OperationId is_final=bool is_error=bool",
+            args = "start=slot_period,slot_thread end=slot_period,slot_thread emitter_address=Address caller_address=Address operation_id=OperationId is_final=bool is_error=bool offset=usize limit=usize",
             pwd_not_needed = "true"
         ),
         message = "show events emitted by smart contracts with various filters"
@@ -257,6 +375,13 @@ pub enum Command {
     )]
     sell_rolls,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress ReceiverAddress RollCount Fee"),
+        message = "transfer rolls from a wallet address to another address, without selling them"
+    )]
+    transfer_rolls,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress ReceiverAddress Amount Fee"),
@@ -298,6 +423,13 @@ pub enum Command {
     )]
     read_only_call,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToBytecode", pwd_not_needed = "true"),
+        message = "check a bytecode against the limits of the node execution engine without executing it"
+    )]
+    validate_bytecode,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -517,6 +649,46 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_get_peer_store => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                match client.private.node_get_peer_store().await {
+                    Ok(entries) => Ok(Box::new(entries)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_set_peer_score => {
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let id = parameters[0].parse::<NodeId>()?;
+                let score = parameters[1].parse::<i64>()?;
+                match client.private.node_set_peer_scores(vec![(id, score)]).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Request of score update successfully sent!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_forget_peers => {
+                let ids = parse_vec::<NodeId>(parameters)?;
+                match client.private.node_forget_peers(ids).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Request of forgetting successfully sent!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
             Command::node_stop => {
                 match client.private.stop_node().await {
                     Ok(()) => {
@@ -536,6 +708,150 @@ impl Command {
                 }
             }
 
+            Command::node_get_production_stats => {
+                let (parameters, output_path) =
+                    match parameters.last().and_then(|p| p.strip_prefix("output=")) {
+                        Some(path) => (
+                            &parameters[..parameters.len() - 1],
+                            Some(PathBuf::from(path)),
+                        ),
+                        None => (parameters, None),
+                    };
+                if parameters.len() < 2 {
+                    bail!("wrong number of parameters");
+                }
+                let cycle_count = parameters[0].parse::<u64>()?;
+                let addresses = parse_vec::<Address>(&parameters[1..])?;
+                match client
+                    .private
+                    .node_get_production_stats(addresses, cycle_count)
+                    .await
+                {
+                    Ok(stats) => {
+                        if let Some(path) = output_path {
+                            export_production_stats(&stats, &path)?;
+                            if !json {
+                                println!("Production stats exported to {}", path.display());
+                            }
+                        }
+                        Ok(Box::new(stats))
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_get_abi_profile => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let slot_count = parameters[0].parse::<usize>()?;
+                match client.private.node_get_abi_profile(slot_count).await {
+                    Ok(profile) => Ok(Box::new(profile)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_get_cache_sizes => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                match client.private.node_get_cache_sizes().await {
+                    Ok(sizes) => Ok(Box::new(sizes)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_set_cache_sizes => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let mut update = CacheSizesUpdate::default();
+                for parameter in parameters {
+                    let Some((name, size)) = parameter.split_once('=') else {
+                        bail!("expected Name=Size, got {}", parameter);
+                    };
+                    match name {
+                        "module_lru_cache_size" => {
+                            update.module_lru_cache_size = Some(size.parse()?)
+                        }
+                        "module_hd_cache_size" => update.module_hd_cache_size = Some(size.parse()?),
+                        "max_final_operation_changes_slots" => {
+                            update.max_final_operation_changes_slots = Some(size.parse()?)
+                        }
+                        _ => bail!("unknown cache {}", name),
+                    }
+                }
+                match client.private.node_set_cache_sizes(update).await {
+                    Ok(sizes) => Ok(Box::new(sizes)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_get_pending_operations => {
+                let mut address = None;
+                let mut limit = None;
+                let mut page = 0;
+                for parameter in parameters {
+                    let Some((name, value)) = parameter.split_once('=') else {
+                        bail!("expected Name=Value, got {}", parameter);
+                    };
+                    match name {
+                        "address" => address = Some(value.parse::<Address>()?),
+                        "limit" => limit = Some(value.parse::<usize>()?),
+                        "page" => page = value.parse::<usize>()?,
+                        _ => bail!("unknown parameter {}", name),
+                    }
+                }
+                let page_request = limit.map(|limit| PageRequest {
+                    limit,
+                    offset: page,
+                });
+                match client
+                    .private
+                    .node_get_pending_operations(address, page_request)
+                    .await
+                {
+                    Ok(operations) => Ok(Box::new(operations)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_get_operation_pool_summary => {
+                let top_senders = match parameters {
+                    [] => 10,
+                    [count] => count.parse::<usize>()?,
+                    _ => bail!("wrong number of parameters"),
+                };
+                match client
+                    .private
+                    .node_get_operation_pool_summary(top_senders)
+                    .await
+                {
+                    Ok(summary) => Ok(Box::new(summary)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_get_denunciation_pool => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                match client.private.node_get_denunciation_pool().await {
+                    Ok(items) => Ok(Box::new(items)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_get_operation_admission_stats => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                match client.private.node_get_operation_admission_stats().await {
+                    Ok(stages) => Ok(Box::new(stages)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::node_testnet_rewards_program_ownership_proof => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -574,6 +890,16 @@ impl Command {
                 Err(e) => rpc_error!(e),
             },
 
+            Command::get_mip_status => match client.public.get_mip_status().await {
+                Ok(mip_status) => Ok(Box::new(mip_status)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::get_fee_recommendation => match client.public.get_fee_recommendation().await {
+                Ok(recommendation) => Ok(Box::new(recommendation)),
+                Err(e) => rpc_error!(e),
+            },
+
             Command::get_addresses => {
                 let addresses = parse_vec::<Address>(parameters)?;
                 match client.public.get_addresses(addresses).await {
@@ -582,6 +908,46 @@ impl Command {
                 }
             }
 
+            Command::get_deferred_credits => {
+                let Some((address, range_parameters)) = parameters.split_first() else {
+                    bail!("wrong number of parameters");
+                };
+                let address = address.parse::<Address>()?;
+                let p_list: [&str; 2] = ["start", "end"];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in range_parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help get_deferred_credits\" to get the list of valid parameters", v);
+                    }
+                }
+                match client
+                    .public
+                    .get_deferred_credits(
+                        address,
+                        parse_key_value(&p, p_list[0])?,
+                        parse_key_value(&p, p_list[1])?,
+                    )
+                    .await
+                {
+                    Ok(deferred_credits) => Ok(Box::new(deferred_credits)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_stakers_statistics => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let top_n = parameters[0].parse::<u64>()?;
+                match client.public.get_stakers_statistics(top_n).await {
+                    Ok(statistics) => Ok(Box::new(statistics)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_datastore_entry => {
                 if parameters.len() != 2 {
                     bail!("invalid number of parameters");
@@ -626,7 +992,7 @@ impl Command {
             }
 
             Command::get_filtered_sc_output_event => {
-                let p_list: [&str; 7] = [
+                let p_list: [&str; 9] = [
                     "start",
                     "end",
                     "emitter_address",
@@ -634,6 +1000,8 @@ impl Command {
                     "operation_id",
                     "is_final",
                     "is_error",
+                    "offset",
+                    "limit",
                 ];
                 let mut p: HashMap<&str, &str> = HashMap::new();
                 for v in parameters {
@@ -652,6 +1020,8 @@ impl Command {
                     original_operation_id: parse_key_value(&p, p_list[4])?,
                     is_final: parse_key_value(&p, p_list[5])?,
                     is_error: parse_key_value(&p, p_list[6])?,
+                    offset: parse_key_value(&p, p_list[7])?,
+                    limit: parse_key_value(&p, p_list[8])?,
                 };
                 match client.public.get_filtered_sc_output_event(filter).await {
                     Ok(events) => Ok(Box::new(events)),
@@ -940,6 +1310,46 @@ impl Command {
                 .await
             }
 
+            Command::transfer_rolls => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let recipient_address = parameters[1].parse::<Address>()?;
+                let roll_count = parameters[2].parse::<u64>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+
+                if !json {
+                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
+                        match addresses_info.get(0) {
+                            Some(info) => {
+                                if info.candidate_balance < fee
+                                    || roll_count > info.candidate_roll_count
+                                {
+                                    client_warning!("this operation may be rejected due to insufficient balance or roll count");
+                                }
+                            }
+                            None => client_warning!(format!("address {} not found", addr)),
+                        }
+                    }
+                }
+
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::RollTransfer {
+                        recipient_address,
+                        roll_count,
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+
             Command::send_transaction => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -1157,6 +1567,9 @@ impl Command {
                         operation_datastore: None, // TODO - #3072
                         is_final,
                         fee,
+                        historical_slot: None,
+                        state_overrides: Vec::new(),
+                        trace: false,
                     })
                     .await
                 {
@@ -1164,6 +1577,18 @@ impl Command {
                     Err(e) => rpc_error!(e),
                 }
             }
+            Command::validate_bytecode => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+
+                let path = parameters[0].parse::<PathBuf>()?;
+                let bytecode = get_file_as_byte_vec(&path).await?;
+                match client.public.validate_bytecode(bytecode).await {
+                    Ok(res) => Ok(Box::new(res)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
             Command::read_only_call => {
                 if parameters.len() < 4 || parameters.len() > 6 {
                     bail!("wrong number of parameters");
@@ -1199,6 +1624,9 @@ impl Command {
                         is_final,
                         coins,
                         fee,
+                        historical_slot: None,
+                        state_overrides: Vec::new(),
+                        trace: false,
                     })
                     .await
                 {
@@ -1452,6 +1880,25 @@ where
         .collect()
 }
 
+/// writes production stats to a file, as JSON if its extension is `json` and as CSV otherwise
+fn export_production_stats(stats: &[AddressProductionStats], path: &std::path::Path) -> Result<()> {
+    let content = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::to_string_pretty(stats)?
+    } else {
+        let mut csv = String::from("address,cycle,block_success_count,block_failure_count\n");
+        for entry in stats {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{}",
+                entry.address, entry.cycle, entry.block_success_count, entry.block_failure_count
+            );
+        }
+        csv
+    };
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
 /// reads a file
 async fn get_file_as_byte_vec(filename: &std::path::Path) -> Result<Vec<u8>> {
     Ok(tokio::fs::read(filename).await?)
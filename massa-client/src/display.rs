@@ -4,14 +4,26 @@ use crate::cmds::ExtendedWallet;
 use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
+    address::{AddressDeferredCredits, AddressInfo, AddressProductionStats},
+    block::BlockInfo,
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::{AbiCallProfile, BytecodeValidationResponse, CacheSizes, ExecuteReadOnlyResponse},
+    node::{NodeStatus, PeerStoreEntry},
     operation::OperationInfo,
+    pool::{
+        AdmissionStageInfo, DenunciationPoolItem, FeeRecommendationInfo, PendingOperation,
+        PoolSummary,
+    },
+    rolls::StakersStatistics,
+    versioning::MipStatusInfo,
 };
 use massa_models::composite::PubkeySig;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
-use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::stats::{
+    ConsensusStats, DiskUsageForecast, EndorsementPoolStats, ExecutionStats, NetworkStats,
+};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
 use massa_signature::{KeyPair, PublicKey};
 use massa_wallet::Wallet;
@@ -258,8 +270,12 @@ impl Output for NodeStatus {
         );
         println!();
 
+        self.endorsement_pool_stats.pretty_print();
         self.network_stats.pretty_print();
         self.execution_stats.pretty_print();
+        if let Some(disk_usage) = &self.disk_usage {
+            disk_usage.pretty_print();
+        }
 
         if !self.connected_nodes.is_empty() {
             println!("Connected nodes:");
@@ -305,6 +321,66 @@ impl Output for ExecutionStats {
     }
 }
 
+impl Output for EndorsementPoolStats {
+    fn pretty_print(&self) {
+        println!("Endorsement pool stats:");
+        println!(
+            "\tProduced block count: {}",
+            Style::Block.style(self.produced_block_count)
+        );
+        if let Some(fill_rate) = self.fill_rate {
+            println!(
+                "\tEndorsement fill rate: {}%",
+                Style::Protocol.style(format!("{:.2}", fill_rate * 100.0))
+            );
+        }
+        println!(
+            "\tMissed endorsement count: {}",
+            Style::Protocol.style(self.missed_endorsement_count)
+        );
+        for (address, count) in &self.missed_endorsements {
+            println!(
+                "\tMissed by {}: {}",
+                Style::Wallet.style(address),
+                Style::Protocol.style(count)
+            );
+        }
+    }
+}
+
+impl Output for DiskUsageForecast {
+    fn pretty_print(&self) {
+        println!("Disk usage:");
+        for (cf, size) in &self.column_family_sizes {
+            println!("\t{}: {} bytes", cf, Style::Protocol.style(size));
+        }
+        println!(
+            "\tTotal: {} bytes",
+            Style::Protocol.style(self.total_size())
+        );
+        println!(
+            "\tGrowth rate: {} bytes/s",
+            Style::Protocol.style(format!("{:.2}", self.growth_rate))
+        );
+        if let Some(available_space) = self.available_space {
+            println!(
+                "\tAvailable space: {} bytes",
+                Style::Protocol.style(available_space)
+            );
+        }
+        if let Some(time_to_full) = self.time_to_full {
+            println!(
+                "\tEstimated time before disk is full: {}",
+                Style::Time.style(
+                    time_to_full
+                        .format_duration()
+                        .unwrap_or_else(|_| format!("{} ms", time_to_full))
+                )
+            );
+        }
+    }
+}
+
 impl Output for NetworkStats {
     fn pretty_print(&self) {
         println!("Network stats:");
@@ -387,6 +463,31 @@ impl Output for ConsensusStats {
             "\tClique count: {}",
             Style::Protocol.style(self.clique_count)
         );
+        println!(
+            "\tMax clique count: {}",
+            Style::Protocol.style(self.max_clique_count)
+        );
+        for (index, clique) in self.cliques.iter().enumerate() {
+            println!(
+                "\tClique {}: {}",
+                Style::Protocol.style(index),
+                Style::Block.style(clique)
+            );
+        }
+        for (thread, depth) in self.fork_depths.iter().enumerate() {
+            println!(
+                "\tFork depth in thread {}: {}",
+                Style::Protocol.style(thread),
+                Style::Block.style(depth)
+            );
+        }
+        for (cycle, counts) in &self.discarded_blocks_per_cycle {
+            println!(
+                "\tDiscarded blocks in cycle {}: {}",
+                Style::Protocol.style(cycle),
+                Style::Block.style(counts)
+            );
+        }
     }
 }
 
@@ -467,6 +568,44 @@ impl Output for Vec<AddressInfo> {
     }
 }
 
+impl Output for AddressDeferredCredits {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for Vec<AddressProductionStats> {
+    fn pretty_print(&self) {
+        for stats in self {
+            println!("{}", stats);
+        }
+    }
+}
+
+impl Output for Vec<AbiCallProfile> {
+    fn pretty_print(&self) {
+        for profile in self {
+            println!("{}", profile);
+        }
+    }
+}
+
+impl Output for Vec<MipStatusInfo> {
+    fn pretty_print(&self) {
+        for status in self {
+            println!("{}", status);
+        }
+    }
+}
+
+impl Output for Vec<StakersStatistics> {
+    fn pretty_print(&self) {
+        for statistics in self {
+            println!("{}", statistics);
+        }
+    }
+}
+
 impl Output for Vec<DatastoreEntryOutput> {
     fn pretty_print(&self) {
         for data_entry in self {
@@ -519,6 +658,9 @@ impl Output for Vec<OperationInfo> {
                     None => Style::Unknown.style("unknown status"),
                 }
             );
+            if let Some(slot) = info.op_exec_slot {
+                println!("Executed at slot {}", Style::Protocol.style(slot));
+            }
             if info.in_blocks.is_empty() {
                 println!("{}", Style::Block.style("Not in any blocks"));
             } else {
@@ -595,3 +737,59 @@ impl Output for ExecuteReadOnlyResponse {
         println!("{}", self);
     }
 }
+
+impl Output for BytecodeValidationResponse {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for CacheSizes {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for Vec<PendingOperation> {
+    fn pretty_print(&self) {
+        for operation in self {
+            println!("{}", operation);
+        }
+    }
+}
+
+impl Output for PoolSummary {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for Vec<DenunciationPoolItem> {
+    fn pretty_print(&self) {
+        for item in self {
+            println!("{}", item);
+        }
+    }
+}
+
+impl Output for Vec<AdmissionStageInfo> {
+    fn pretty_print(&self) {
+        for stage in self {
+            println!("{}", stage);
+        }
+    }
+}
+
+impl Output for Vec<PeerStoreEntry> {
+    fn pretty_print(&self) {
+        for entry in self {
+            println!("{}", entry);
+        }
+    }
+}
+
+impl Output for FeeRecommendationInfo {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
@@ -5,6 +5,8 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     slot::Slot,
 };
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
 
 use crate::block_status::{DiscardReason, ExportCompiledBlock};
 
@@ -27,3 +29,77 @@ pub struct BlockGraphExport {
     /// List of maximal cliques of compatible blocks.
     pub max_cliques: Vec<Clique>,
 }
+
+/// Status of a block in a [`BlockGraphSnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockGraphNodeStatus {
+    /// active and final
+    Final,
+    /// active, in the blockclique
+    Blockclique,
+    /// active, in another clique
+    Candidate,
+    /// discarded, with the reason
+    Discarded(DiscardReason),
+}
+
+/// Block of a [`BlockGraphSnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockGraphNode {
+    /// id of the block
+    pub id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// creator of the block
+    pub creator: Address,
+    /// parents of the block, one per thread
+    pub parents: Vec<BlockId>,
+    /// status of the block
+    pub status: BlockGraphNodeStatus,
+}
+
+/// Serializable snapshot of the block graph, for debugging and visualization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockGraphSnapshot {
+    /// genesis blocks
+    pub genesis_blocks: Vec<BlockId>,
+    /// active and discarded blocks, sorted by slot
+    pub blocks: Vec<BlockGraphNode>,
+    /// maximal cliques of compatible blocks
+    pub max_cliques: Vec<Clique>,
+    /// best parents in each thread
+    pub best_parents: Vec<(BlockId, u64)>,
+    /// latest final block and period in each thread
+    pub latest_final_blocks_periods: Vec<(BlockId, u64)>,
+}
+
+impl BlockGraphSnapshot {
+    /// Graphviz DOT representation of the graph: one node per block, one edge per parent,
+    /// colored by status
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph block_graph {\n    rankdir=LR;\n    node [style=filled];\n");
+        for block in &self.blocks {
+            let color = match &block.status {
+                BlockGraphNodeStatus::Final
+                | BlockGraphNodeStatus::Discarded(DiscardReason::Final) => "palegreen",
+                BlockGraphNodeStatus::Blockclique => "lightblue",
+                BlockGraphNodeStatus::Candidate => "white",
+                BlockGraphNodeStatus::Discarded(DiscardReason::Invalid(_)) => "salmon",
+                BlockGraphNodeStatus::Discarded(_) => "lightgrey",
+            };
+            // writing to a String cannot fail
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\\n{}\", fillcolor={}];",
+                block.id, block.id, block.slot, color
+            );
+            for parent in &block.parents {
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\";", block.id, parent);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -42,6 +42,42 @@ pub enum DiscardReason {
     Final,
 }
 
+/// Kind of a `DiscardReason`, without its details
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiscardReasonKind {
+    /// Block is invalid
+    Invalid,
+    /// Block is incompatible with a final block
+    Stale,
+    /// Block has enough fitness
+    Final,
+}
+
+impl From<&DiscardReason> for DiscardReasonKind {
+    fn from(reason: &DiscardReason) -> Self {
+        match reason {
+            DiscardReason::Invalid(_) => DiscardReasonKind::Invalid,
+            DiscardReason::Stale => DiscardReasonKind::Stale,
+            DiscardReason::Final => DiscardReasonKind::Final,
+        }
+    }
+}
+
+/// A discarded block, as exposed for inspection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscardedBlockInfo {
+    /// Id of the block
+    pub block_id: BlockId,
+    /// Slot of the block
+    pub slot: Slot,
+    /// Address of the creator of the block
+    pub creator: Address,
+    /// Why it was discarded
+    pub reason: DiscardReason,
+    /// Discard order, the highest being the most recent
+    pub sequence_number: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BlockStatusId {
     Incoming = 0,
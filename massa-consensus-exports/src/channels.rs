@@ -3,7 +3,10 @@ use massa_execution_exports::ExecutionController;
 use massa_models::block::{FilledBlock, SecureShareBlock};
 use massa_models::block_header::BlockHeader;
 use massa_models::block_id::BlockId;
+use massa_models::clique::BlockcliqueReorg;
+use massa_models::operation::OperationId;
 use massa_models::secure_share::SecureShare;
+use massa_models::slot::Slot;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::ProtocolController;
@@ -36,4 +39,19 @@ pub struct ConsensusBroadcasts {
     pub block_header_sender: tokio::sync::broadcast::Sender<SecureShare<BlockHeader, BlockId>>,
     /// Channel use by Websocket (if they are enable) to broadcast a new block integrated
     pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    /// Channel used to broadcast (if enabled) the blocks becoming final, in slot order
+    pub finalized_block_sender: tokio::sync::broadcast::Sender<FinalizedBlock>,
+    /// Channel used to broadcast (if enabled) the blockclique changes demoting non-final blocks
+    pub blockclique_reorg_sender: tokio::sync::broadcast::Sender<BlockcliqueReorg>,
+}
+
+/// Block that became final
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizedBlock {
+    /// slot of the block
+    pub slot: Slot,
+    /// id of the block
+    pub block_id: BlockId,
+    /// ids of the operations of the block
+    pub operation_ids: Vec<OperationId>,
 }
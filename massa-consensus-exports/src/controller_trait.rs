@@ -1,10 +1,11 @@
-use crate::block_graph_export::BlockGraphExport;
+use crate::block_graph_export::{BlockGraphExport, BlockGraphSnapshot};
+use crate::block_status::{DiscardReasonKind, DiscardedBlockInfo};
 use crate::{bootstrapable_graph::BootstrapableGraph, error::ConsensusError};
 use massa_models::prehash::PreHashSet;
 use massa_models::streaming_step::StreamingStep;
 use massa_models::{
-    block::BlockGraphStatus, block_header::BlockHeader, block_id::BlockId, clique::Clique,
-    secure_share::SecureShare, slot::Slot, stats::ConsensusStats,
+    address::Address, block::BlockGraphStatus, block_header::BlockHeader, block_id::BlockId,
+    clique::Clique, secure_share::SecureShare, slot::Slot, stats::ConsensusStats,
 };
 use massa_storage::Storage;
 
@@ -25,6 +26,31 @@ pub trait ConsensusController: Send + Sync {
         end_slot: Option<Slot>,
     ) -> Result<BlockGraphExport, ConsensusError>;
 
+    /// Get a serializable snapshot of a part of the graph, for debugging and visualization
+    ///
+    /// # Arguments
+    /// * `start_slot`: the slot to start the snapshot from, if None, the snapshot starts from the genesis
+    /// * `end_slot`: the slot to end the snapshot at (excluded), if None, the snapshot ends at the current slot
+    ///
+    /// # Returns
+    /// The active and discarded blocks of the graph, with its cliques
+    fn get_block_graph_snapshot(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> BlockGraphSnapshot;
+
+    /// Get the discarded blocks kept by the graph, sorted by discard order
+    ///
+    /// # Arguments
+    /// * `reason`: if set, only the blocks discarded for this kind of reason are returned
+    /// * `creator`: if set, only the blocks created by this address are returned
+    fn get_discarded_blocks(
+        &self,
+        reason: Option<DiscardReasonKind>,
+        creator: Option<Address>,
+    ) -> Vec<DiscardedBlockInfo>;
+
     /// Get statuses of a list of blocks
     ///
     /// # Arguments
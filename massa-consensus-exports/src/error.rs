@@ -45,6 +45,8 @@ pub enum ConsensusError {
     ProtocolError(#[from] ProtocolError),
     /// Invalid transition {0}
     InvalidTransition(String),
+    /// replay diverged from the expected outcome: {0}
+    ReplayDivergence(String),
 }
 
 /// Internal error
@@ -11,8 +11,9 @@ pub mod bootstrapable_graph;
 pub mod error;
 pub mod events;
 pub mod export_active_block;
+pub mod state_cache;
 
-pub use channels::{ConsensusBroadcasts, ConsensusChannels};
+pub use channels::{ConsensusBroadcasts, ConsensusChannels, FinalizedBlock};
 pub use controller_trait::{ConsensusController, ConsensusManager};
 pub use settings::ConsensusConfig;
 
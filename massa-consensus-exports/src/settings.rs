@@ -1,6 +1,7 @@
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConsensusConfig {
@@ -50,6 +51,12 @@ pub struct ConsensusConfig {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// finalized blocks channel capacity
+    pub broadcast_finalized_blocks_channel_capacity: usize,
+    /// blockclique reorgs channel capacity
+    pub broadcast_blockclique_reorgs_channel_capacity: usize,
     /// last start period
     pub last_start_period: u64,
+    /// file where the final blocks are saved on a clean shutdown, to restart without downloading them again
+    pub state_cache_path: Option<PathBuf>,
 }
@@ -0,0 +1,61 @@
+//! Final blocks of the graph saved on a clean shutdown,
+//! so that a restarting node does not download them again during its bootstrap.
+
+use std::path::Path;
+
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+
+use crate::{
+    bootstrapable_graph::{
+        BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
+    },
+    error::ConsensusError,
+};
+
+/// Saves the final blocks of the graph to the cache file at `path`
+pub fn save_state_cache(path: &Path, graph: &BootstrapableGraph) -> Result<(), ConsensusError> {
+    let mut buffer = Vec::new();
+    BootstrapableGraphSerializer::new()
+        .serialize(graph, &mut buffer)
+        .map_err(|err| ConsensusError::SerializationError(err.to_string()))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // write then rename, so that the cache file is never partially written
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, buffer)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Loads the final blocks saved in the cache file at `path`, and removes the file:
+/// the cached blocks are outdated as soon as the node runs again
+pub fn take_state_cache(
+    path: &Path,
+    deserializer: &BootstrapableGraphDeserializer,
+) -> Result<Option<BootstrapableGraph>, ConsensusError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let graph = load_state_cache(path, deserializer)?;
+    std::fs::remove_file(path)?;
+    Ok(Some(graph))
+}
+
+/// Loads the final blocks saved in the cache file at `path`, keeping the file
+pub fn load_state_cache(
+    path: &Path,
+    deserializer: &BootstrapableGraphDeserializer,
+) -> Result<BootstrapableGraph, ConsensusError> {
+    let buffer = std::fs::read(path)?;
+    let (rest, graph) = deserializer
+        .deserialize::<DeserializeError>(&buffer)
+        .map_err(|err| ConsensusError::SerializationError(err.to_string()))?;
+    if !rest.is_empty() {
+        return Err(ConsensusError::SerializationError(
+            "trailing bytes in the consensus state cache".to_string(),
+        ));
+    }
+    Ok(graph)
+}
@@ -35,7 +35,10 @@ impl Default for ConsensusConfig {
             broadcast_blocks_headers_channel_capacity: 128,
             broadcast_blocks_channel_capacity: 128,
             broadcast_filled_blocks_channel_capacity: 128,
+            broadcast_finalized_blocks_channel_capacity: 128,
+            broadcast_blockclique_reorgs_channel_capacity: 128,
             last_start_period: 0,
+            state_cache_path: None,
         }
     }
 }
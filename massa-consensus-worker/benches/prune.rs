@@ -0,0 +1,90 @@
+#[cfg(feature = "benchmarking")]
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[cfg(feature = "benchmarking")]
+fn criterion_benchmark(c: &mut Criterion) {
+    use massa_consensus_exports::block_status::{BlockStatus, StorageOrBlock};
+    use massa_consensus_worker::BlocksState;
+    use massa_hash::Hash;
+    use massa_models::{
+        active_block::ActiveBlock, address::Address, block_id::BlockId, secure_share::Id,
+        slot::Slot,
+    };
+    use massa_signature::KeyPair;
+    use massa_storage::Storage;
+
+    const THREAD_COUNT: u8 = 32;
+    const PERIOD_COUNT: u64 = 160;
+    const PRUNED_PERIOD: u64 = PERIOD_COUNT / 2;
+
+    /// Builds a block graph holding one active block per slot over `PERIOD_COUNT` periods
+    fn prepare_blocks_state() -> BlocksState {
+        let creator_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let mut blocks_state = BlocksState::new();
+        for period in 0..PERIOD_COUNT {
+            for thread in 0..THREAD_COUNT {
+                let slot = Slot::new(period, thread);
+                let block_id = BlockId::new(Hash::compute_from(&slot.to_bytes_key()));
+                let a_block = ActiveBlock {
+                    creator_address,
+                    block_id,
+                    parents: Vec::new(),
+                    children: vec![Default::default(); THREAD_COUNT as usize],
+                    descendants: Default::default(),
+                    is_final: true,
+                    slot,
+                    fitness: 1,
+                    same_thread_parent_creator: None,
+                };
+                blocks_state.transition_map(&block_id, |_, _| {
+                    Some(BlockStatus::Active {
+                        a_block: Box::new(a_block),
+                        storage_or_block: StorageOrBlock::Storage(Storage::create_root()),
+                    })
+                });
+            }
+        }
+        blocks_state
+    }
+
+    let blocks_state = prepare_blocks_state();
+
+    c.bench_function("prune candidates from a full walk", |b| {
+        b.iter(|| {
+            let mut candidates = Vec::new();
+            for block_id in blocks_state.active_blocks().clone() {
+                if let Some(BlockStatus::Active { a_block, .. }) = blocks_state.get(&block_id) {
+                    if a_block.slot.period == black_box(PRUNED_PERIOD) {
+                        candidates.push(block_id);
+                    }
+                }
+            }
+            candidates
+        })
+    });
+
+    c.bench_function("prune candidates from the period index", |b| {
+        b.iter(|| {
+            let period = black_box(PRUNED_PERIOD);
+            (0..THREAD_COUNT)
+                .flat_map(|thread| {
+                    blocks_state
+                        .active_blocks_in_periods(thread, period..period + 1)
+                        .map(|(_, block_id)| *block_id)
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+}
+
+#[cfg(feature = "benchmarking")]
+criterion_group!(benches, criterion_benchmark);
+
+#[cfg(feature = "benchmarking")]
+criterion_main!(benches);
+
+#[cfg(not(feature = "benchmarking"))]
+fn main() {
+    println!("Please use the `--features benchmarking` flag to run this benchmark.");
+}
@@ -1,11 +1,15 @@
 use massa_channel::sender::MassaSender;
 use massa_consensus_exports::{
-    block_graph_export::BlockGraphExport, block_status::BlockStatus,
-    bootstrapable_graph::BootstrapableGraph, error::ConsensusError,
-    export_active_block::ExportActiveBlock, ConsensusChannels, ConsensusController,
+    block_graph_export::{BlockGraphExport, BlockGraphSnapshot},
+    block_status::{BlockStatus, DiscardReasonKind, DiscardedBlockInfo},
+    bootstrapable_graph::BootstrapableGraph,
+    error::ConsensusError,
+    export_active_block::ExportActiveBlock,
+    ConsensusChannels, ConsensusController,
 };
 use massa_models::denunciation::DenunciationPrecursor;
 use massa_models::{
+    address::Address,
     block::{BlockGraphStatus, FilledBlock},
     block_header::BlockHeader,
     block_id::BlockId,
@@ -77,6 +81,42 @@ impl ConsensusController for ConsensusControllerImpl {
             .extract_block_graph_part(start_slot, end_slot)
     }
 
+    /// Get a serializable snapshot of the block graph in a given period.
+    ///
+    /// # Arguments:
+    /// * `start_slot`: the start slot
+    /// * `end_slot`: the end slot
+    ///
+    /// # Returns:
+    /// A snapshot of the block graph in this period
+    fn get_block_graph_snapshot(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> BlockGraphSnapshot {
+        self.shared_state
+            .read()
+            .extract_block_graph_snapshot(start_slot, end_slot)
+    }
+
+    /// Get the discarded blocks kept by the graph, sorted by discard order
+    ///
+    /// # Arguments:
+    /// * `reason`: only keep the blocks discarded for this kind of reason, if set
+    /// * `creator`: only keep the blocks created by this address, if set
+    ///
+    /// # Returns:
+    /// The discarded blocks matching the filters
+    fn get_discarded_blocks(
+        &self,
+        reason: Option<DiscardReasonKind>,
+        creator: Option<Address>,
+    ) -> Vec<DiscardedBlockInfo> {
+        self.shared_state
+            .read()
+            .get_discarded_blocks(reason, creator)
+    }
+
     /// Get statuses of blocks present in the graph
     ///
     /// # Arguments:
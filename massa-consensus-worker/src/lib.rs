@@ -28,10 +28,16 @@
 mod commands;
 mod controller;
 mod manager;
+mod replay;
 mod state;
 mod worker;
 
+pub use replay::{replay_blocks, replay_recorded_blocks, ReplayOutcome};
 pub use worker::start_consensus_worker;
 
+#[cfg(feature = "benchmarking")]
+#[doc(hidden)]
+pub use state::blocks_state::BlocksState;
+
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,174 @@
+//! Deterministic replay of stored blocks through the graph and fitness logic of consensus.
+//!
+//! No worker thread is started and nothing is sent on the network: the blocks are registered in slot order,
+//! each one being processed as if it was received at its own slot. Comparing the outcome of a replay with the
+//! final blocks observed by several nodes helps to find where their consensus diverged.
+//!
+//! The graph is never pruned during a replay, so the replayed range should stay reasonably small.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use massa_channel::MassaChannel;
+use massa_consensus_exports::{
+    block_status::BlockStatus, bootstrapable_graph::BootstrapableGraph, error::ConsensusError,
+    ConsensusChannels, ConsensusConfig,
+};
+use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
+use massa_models::{block_id::BlockId, secure_share::Id, slot::Slot};
+use massa_storage::Storage;
+use parking_lot::RwLock;
+
+use crate::state::ConsensusState;
+use crate::worker::{new_consensus_state, ConsensusWorker};
+
+/// Outcome of a replay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayOutcome {
+    /// final blocks of the graph at the end of the replay, by slot
+    pub final_blocks: BTreeMap<Slot, BlockId>,
+    /// blocks of the blockclique at the end of the replay, by slot
+    pub blockclique: BTreeMap<Slot, BlockId>,
+    /// hash of the final block ids in slot order, to compare replays run on different nodes
+    pub final_blocks_hash: Hash,
+}
+
+impl ReplayOutcome {
+    /// Check that every expected final block is final at its slot at the end of the replay
+    pub fn check_final_blocks(
+        &self,
+        expected: &BTreeMap<Slot, BlockId>,
+    ) -> Result<(), ConsensusError> {
+        for (slot, block_id) in expected {
+            match self.final_blocks.get(slot) {
+                Some(final_block_id) if final_block_id == block_id => {}
+                Some(final_block_id) => {
+                    return Err(ConsensusError::ReplayDivergence(format!(
+                        "block {} is final at slot {} instead of block {}",
+                        final_block_id, slot, block_id
+                    )))
+                }
+                None => {
+                    return Err(ConsensusError::ReplayDivergence(format!(
+                        "no final block at slot {} while block {} was expected",
+                        slot, block_id
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replay blocks through the graph and fitness logic of consensus, without networking.
+///
+/// # Arguments:
+/// * `config`: Configuration of the consensus
+/// * `channels`: Channels to the other modules. Protocol and pool notifications can be ignored by the receivers,
+///   but the selector must give the producers of the replayed slots.
+/// * `init_graph`: Optional initial graph. if None, the replay starts from the genesis blocks.
+/// * `storage`: Storage containing the replayed blocks
+/// * `block_ids`: Ids of the replayed blocks, in any order
+///
+/// # Returns:
+/// The final blocks and blockclique at the end of the replay
+pub fn replay_blocks(
+    config: ConsensusConfig,
+    channels: ConsensusChannels,
+    init_graph: Option<BootstrapableGraph>,
+    storage: Storage,
+    block_ids: Vec<BlockId>,
+    massa_metrics: MassaMetrics,
+) -> Result<ReplayOutcome, ConsensusError> {
+    let mut to_replay = {
+        let blocks = storage.read_blocks();
+        block_ids
+            .into_iter()
+            .map(|block_id| {
+                blocks
+                    .get(&block_id)
+                    .map(|block| (block.content.header.content.slot, block_id))
+                    .ok_or_else(|| ConsensusError::MissingBlock(block_id.to_string()))
+            })
+            .collect::<Result<Vec<_>, ConsensusError>>()?
+    };
+    to_replay.sort_unstable();
+
+    let shared_state = Arc::new(RwLock::new(new_consensus_state(
+        &config,
+        channels,
+        storage.clone(),
+        massa_metrics,
+    )));
+    // the worker is only used to initialize the graph: its command channel stays unused
+    let (_command_sender, command_receiver) =
+        MassaChannel::new("consensus_replay_command".to_string(), Some(1));
+    ConsensusWorker::new(
+        config,
+        command_receiver,
+        shared_state.clone(),
+        init_graph,
+        storage.clone(),
+    )?;
+
+    let mut state = shared_state.write();
+    for (slot, block_id) in to_replay {
+        let mut block_storage = storage.clone_without_refs();
+        block_storage.claim_block_refs(&[block_id].into_iter().collect());
+        state.register_block(block_id, slot, Some(slot), block_storage, false)?;
+        state.block_db_changed()?;
+    }
+    Ok(replay_outcome(&state))
+}
+
+/// Replay blocks recorded in a file written by `save_state_cache`, see `replay_blocks`
+pub fn replay_recorded_blocks(
+    config: ConsensusConfig,
+    channels: ConsensusChannels,
+    init_graph: Option<BootstrapableGraph>,
+    recorded: BootstrapableGraph,
+    mut storage: Storage,
+    massa_metrics: MassaMetrics,
+) -> Result<ReplayOutcome, ConsensusError> {
+    let mut block_ids = Vec::with_capacity(recorded.final_blocks.len());
+    for export_block in recorded.final_blocks {
+        block_ids.push(export_block.block.id);
+        storage.store_block(export_block.block);
+    }
+    replay_blocks(
+        config,
+        channels,
+        init_graph,
+        storage,
+        block_ids,
+        massa_metrics,
+    )
+}
+
+/// Gather the final blocks and the blockclique of the graph
+fn replay_outcome(state: &ConsensusState) -> ReplayOutcome {
+    let final_blocks: BTreeMap<Slot, BlockId> = state
+        .get_all_final_blocks()
+        .into_iter()
+        .map(|(block_id, (slot, _))| (slot, block_id))
+        .collect();
+    let blockclique = state
+        .get_blockclique()
+        .into_iter()
+        .filter_map(|block_id| match state.blocks_state.get(&block_id) {
+            Some(BlockStatus::Active { a_block, .. }) => Some((a_block.slot, block_id)),
+            _ => None,
+        })
+        .collect();
+    let final_blocks_hash = Hash::compute_from(
+        &final_blocks
+            .values()
+            .flat_map(|block_id| block_id.get_hash().to_bytes().to_vec())
+            .collect::<Vec<u8>>(),
+    );
+    ReplayOutcome {
+        final_blocks,
+        blockclique,
+        final_blocks_hash,
+    }
+}
@@ -1,7 +1,9 @@
 use core::panic;
+use std::{collections::BTreeMap, ops::Range};
 
-use massa_consensus_exports::block_status::{BlockStatus, BlockStatusId};
+use massa_consensus_exports::block_status::{BlockStatus, BlockStatusId, DiscardReasonKind};
 use massa_models::{
+    address::Address,
     block_id::BlockId,
     prehash::{PreHashMap, PreHashSet},
     slot::Slot,
@@ -23,6 +25,10 @@ pub struct BlocksState {
     discarded_index: PreHashSet<BlockId>,
     /// ids of active blocks
     active_index: PreHashSet<BlockId>,
+    /// ids of active blocks, per thread and period
+    active_period_index: Vec<BTreeMap<u64, PreHashSet<BlockId>>>,
+    /// slot, creator and reason of the blocks discarded since the last call to `take_new_discards`
+    new_discards: Vec<(Slot, Address, DiscardReasonKind)>,
 }
 
 impl BlocksState {
@@ -36,6 +42,8 @@ impl BlocksState {
             waiting_for_dependencies_index: PreHashSet::default(),
             discarded_index: PreHashSet::default(),
             active_index: PreHashSet::default(),
+            active_period_index: Vec::new(),
+            new_discards: Vec::new(),
         }
     }
 
@@ -79,6 +87,50 @@ impl BlocksState {
         &self.active_index
     }
 
+    /// Get the ids of the active blocks of `thread` whose period is in `periods`, with their period,
+    /// without walking the other active blocks
+    pub fn active_blocks_in_periods(
+        &self,
+        thread: u8,
+        periods: Range<u64>,
+    ) -> impl Iterator<Item = (u64, &BlockId)> + '_ {
+        self.active_period_index
+            .get(thread as usize)
+            .filter(|_| periods.start < periods.end)
+            .into_iter()
+            .flat_map(move |by_period| by_period.range(periods.clone()))
+            .flat_map(|(period, ids)| ids.iter().map(move |id| (*period, id)))
+    }
+
+    // Internal function to update the index of the active blocks per thread and period
+    fn update_active_period_index(
+        &mut self,
+        block_id: &BlockId,
+        old_active_slot: Option<Slot>,
+        new_active_slot: Option<Slot>,
+    ) {
+        if let Some(slot) = old_active_slot {
+            if let Some(by_period) = self.active_period_index.get_mut(slot.thread as usize) {
+                if let Some(ids) = by_period.get_mut(&slot.period) {
+                    ids.remove(block_id);
+                    if ids.is_empty() {
+                        by_period.remove(&slot.period);
+                    }
+                }
+            }
+        }
+        if let Some(slot) = new_active_slot {
+            if self.active_period_index.len() <= slot.thread as usize {
+                self.active_period_index
+                    .resize_with(slot.thread as usize + 1, BTreeMap::new);
+            }
+            self.active_period_index[slot.thread as usize]
+                .entry(slot.period)
+                .or_default()
+                .insert(*block_id);
+        }
+    }
+
     // Internal function to update the indexes
     fn update_indexes(
         &mut self,
@@ -169,6 +221,11 @@ impl BlocksState {
         self.block_statuses.iter()
     }
 
+    /// Take the slot, creator and reason of the blocks discarded since the last call
+    pub fn take_new_discards(&mut self) -> Vec<(Slot, Address, DiscardReasonKind)> {
+        std::mem::take(&mut self.new_discards)
+    }
+
     /// Get the number of blocks stored in the `BlocksState`
     pub fn len(&self) -> usize {
         self.block_statuses.len()
@@ -191,10 +248,23 @@ impl BlocksState {
             Some(block) => {
                 let old_state_id = BlockStatusId::from(&block);
                 self.update_indexes(block_id, Some(&old_state_id), None);
+                self.update_active_period_index(block_id, active_slot(&block), None);
                 let Some(mut new_state) = callback(Some(block), &mut self.block_statuses) else {
                     return;
                 };
                 let new_state_id = BlockStatusId::from(&new_state);
+                let new_active_slot = active_slot(&new_state);
+                let new_discard = match &new_state {
+                    BlockStatus::Discarded {
+                        slot,
+                        creator,
+                        reason,
+                        ..
+                    } if old_state_id != BlockStatusId::Discarded => {
+                        Some((*slot, *creator, DiscardReasonKind::from(reason)))
+                    }
+                    _ => None,
+                };
                 match (&old_state_id, &new_state_id) {
                     // From incoming status
                     (BlockStatusId::Incoming, BlockStatusId::WaitingForDependencies) => {
@@ -309,6 +379,8 @@ impl BlocksState {
                     }
                 }
                 self.update_indexes(block_id, None, Some(&new_state_id));
+                self.update_active_period_index(block_id, None, new_active_slot);
+                self.new_discards.extend(new_discard);
             }
             None => {
                 let new_state = callback(None, &mut self.block_statuses);
@@ -320,10 +392,20 @@ impl BlocksState {
                             state, block_id
                         );
                     }
+                    let new_active_slot = active_slot(&new_state);
                     self.block_statuses.insert(*block_id, new_state);
                     self.update_indexes(block_id, None, Some(&state));
+                    self.update_active_period_index(block_id, None, new_active_slot);
                 }
             }
         };
     }
 }
+
+/// Slot of a block if it is active
+fn active_slot(block_status: &BlockStatus) -> Option<Slot> {
+    match block_status {
+        BlockStatus::Active { a_block, .. } => Some(a_block.slot),
+        _ => None,
+    }
+}
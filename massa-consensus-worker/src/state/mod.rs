@@ -1,12 +1,19 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     vec,
 };
 
 use massa_consensus_exports::{
-    block_graph_export::BlockGraphExport,
-    block_status::{BlockStatus, ExportCompiledBlock, HeaderOrBlock, StorageOrBlock},
+    block_graph_export::{
+        BlockGraphExport, BlockGraphNode, BlockGraphNodeStatus, BlockGraphSnapshot,
+    },
+    block_status::{
+        BlockStatus, DiscardReasonKind, DiscardedBlockInfo, ExportCompiledBlock, HeaderOrBlock,
+        StorageOrBlock,
+    },
+    bootstrapable_graph::BootstrapableGraph,
     error::ConsensusError,
+    export_active_block::ExportActiveBlock,
     ConsensusChannels, ConsensusConfig,
 };
 use massa_execution_exports::ExecutionBlockMetadata;
@@ -20,6 +27,7 @@ use massa_models::{
     clique::Clique,
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
+    stats::DiscardedBlockCounts,
 };
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -30,6 +38,7 @@ use self::blocks_state::BlocksState;
 pub mod blocks_state;
 mod clique_computation;
 mod graph;
+mod priority;
 mod process;
 mod process_commands;
 mod prune;
@@ -54,6 +63,8 @@ pub struct ConsensusState {
     pub max_cliques: Vec<Clique>,
     /// ids of active blocks without ops
     pub active_index_without_ops: PreHashSet<BlockId>,
+    /// Per thread, period below which the operations of the active blocks were already dropped
+    pub ops_pruned_periods: Vec<u64>,
     /// Save of latest periods
     pub save_final_periods: Vec<u64>,
     /// One (block id, period) per thread
@@ -78,6 +89,10 @@ pub struct ConsensusState {
     pub protocol_blocks: VecDeque<(MassaTime, BlockId)>,
     /// Stale block timestamp
     pub stale_block_stats: VecDeque<MassaTime>,
+    /// Clique count at each stats tick `(time, clique count)`
+    pub clique_count_stats: VecDeque<(MassaTime, usize)>,
+    /// Discarded block counts of the last cycles, per cycle and per creator
+    pub discarded_block_stats: BTreeMap<u64, PreHashMap<Address, DiscardedBlockCounts>>,
     /// the time span considered for stats
     pub stats_history_timespan: MassaTime,
     /// the time span considered for desynchronization detection
@@ -300,16 +315,18 @@ impl ConsensusState {
         lower_bound: &[(BlockId, u64)],
         end_slot: Option<Slot>,
     ) {
-        for id in self.blocks_state.active_blocks().iter() {
-            if let Some((block, _storage)) = self.get_full_active_block(id) {
+        for (thread, (_id, lower_period)) in lower_bound.iter().enumerate() {
+            let thread = thread as u8;
+            for (period, id) in self
+                .blocks_state
+                .active_blocks_in_periods(thread, *lower_period..u64::MAX)
+            {
                 if let Some(slot) = end_slot {
-                    if block.slot > slot {
+                    if Slot::new(period, thread) > slot {
                         continue;
                     }
                 }
-                if block.slot.period >= lower_bound[block.slot.thread as usize].1 {
-                    kept_blocks.insert(*id);
-                }
+                kept_blocks.insert(*id);
             }
         }
     }
@@ -396,19 +413,7 @@ impl ConsensusState {
             max_cliques: self.max_cliques.clone(),
         };
 
-        let filter = |&s| {
-            if let Some(s_start) = slot_start {
-                if s < s_start {
-                    return false;
-                }
-            }
-            if let Some(s_end) = slot_end {
-                if s >= s_end {
-                    return false;
-                }
-            }
-            true
-        };
+        let filter = |s: &Slot| slot_in_range(s, slot_start, slot_end);
 
         for (block_id, block) in self.blocks_state.iter() {
             match block {
@@ -454,6 +459,115 @@ impl ConsensusState {
         Ok(export)
     }
 
+    /// Serializable snapshot of the graph, without the block contents
+    pub fn extract_block_graph_snapshot(
+        &self,
+        slot_start: Option<Slot>,
+        slot_end: Option<Slot>,
+    ) -> BlockGraphSnapshot {
+        let blockclique = self.max_cliques.iter().find(|c| c.is_blockclique);
+        let mut blocks = Vec::new();
+        for (block_id, block) in self.blocks_state.iter() {
+            let node = match block {
+                BlockStatus::Discarded {
+                    slot,
+                    creator,
+                    parents,
+                    reason,
+                    ..
+                } => BlockGraphNode {
+                    id: *block_id,
+                    slot: *slot,
+                    creator: *creator,
+                    parents: parents.clone(),
+                    status: BlockGraphNodeStatus::Discarded(reason.clone()),
+                },
+                BlockStatus::Active { a_block, .. } => BlockGraphNode {
+                    id: *block_id,
+                    slot: a_block.slot,
+                    creator: a_block.creator_address,
+                    parents: a_block.parents.iter().map(|(id, _)| *id).collect(),
+                    status: if a_block.is_final {
+                        BlockGraphNodeStatus::Final
+                    } else if blockclique.is_some_and(|c| c.block_ids.contains(block_id)) {
+                        BlockGraphNodeStatus::Blockclique
+                    } else {
+                        BlockGraphNodeStatus::Candidate
+                    },
+                },
+                _ => continue,
+            };
+            if slot_in_range(&node.slot, slot_start, slot_end) {
+                blocks.push(node);
+            }
+        }
+        blocks.sort_unstable_by_key(|node| (node.slot, node.id));
+
+        BlockGraphSnapshot {
+            genesis_blocks: self.genesis_hashes.clone(),
+            blocks,
+            max_cliques: self.max_cliques.clone(),
+            best_parents: self.best_parents.clone(),
+            latest_final_blocks_periods: self.latest_final_blocks_periods.clone(),
+        }
+    }
+
+    /// Discarded blocks, optionally filtered by kind of discard reason and creator, sorted by discard order
+    pub fn get_discarded_blocks(
+        &self,
+        reason: Option<DiscardReasonKind>,
+        creator: Option<Address>,
+    ) -> Vec<DiscardedBlockInfo> {
+        let mut discarded_blocks: Vec<DiscardedBlockInfo> = self
+            .blocks_state
+            .discarded_blocks()
+            .iter()
+            .filter_map(|block_id| match self.blocks_state.get(block_id) {
+                Some(BlockStatus::Discarded {
+                    slot,
+                    creator: block_creator,
+                    reason: block_reason,
+                    sequence_number,
+                    ..
+                }) => Some(DiscardedBlockInfo {
+                    block_id: *block_id,
+                    slot: *slot,
+                    creator: *block_creator,
+                    reason: block_reason.clone(),
+                    sequence_number: *sequence_number,
+                }),
+                _ => None,
+            })
+            .filter(|info| {
+                (reason.is_none() || reason == Some(DiscardReasonKind::from(&info.reason)))
+                    && (creator.is_none() || creator == Some(info.creator))
+            })
+            .collect();
+        discarded_blocks.sort_unstable_by_key(|info| info.sequence_number);
+        discarded_blocks
+    }
+
+    /// Exports the final blocks a bootstrap server would send, sorted by slot,
+    /// to save them in the state cache on shutdown
+    pub fn export_final_blocks(&self) -> Result<BootstrapableGraph, ConsensusError> {
+        let mut final_blocks: Vec<ExportActiveBlock> = self
+            .list_required_active_blocks(None)?
+            .iter()
+            .filter_map(|block_id| match self.blocks_state.get(block_id) {
+                Some(BlockStatus::Active {
+                    a_block,
+                    storage_or_block,
+                }) if a_block.is_final => Some(ExportActiveBlock::from_active_block(
+                    a_block,
+                    storage_or_block,
+                )),
+                _ => None,
+            })
+            .collect();
+        final_blocks.sort_unstable_by_key(|block| block.block.content.header.content.slot);
+        Ok(BootstrapableGraph { final_blocks })
+    }
+
     /// Gets all stored final blocks, not only the still-useful ones
     /// This is used when initializing Execution from Consensus.
     /// Since the Execution bootstrap snapshot is older than the Consensus snapshot,
@@ -545,3 +659,18 @@ impl ConsensusState {
         result
     }
 }
+
+/// Whether `slot` is in `[slot_start, slot_end)`, each bound being optional
+fn slot_in_range(slot: &Slot, slot_start: Option<Slot>, slot_end: Option<Slot>) -> bool {
+    if let Some(s_start) = slot_start {
+        if *slot < s_start {
+            return false;
+        }
+    }
+    if let Some(s_end) = slot_end {
+        if *slot >= s_end {
+            return false;
+        }
+    }
+    true
+}
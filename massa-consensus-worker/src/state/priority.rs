@@ -0,0 +1,120 @@
+use std::collections::BTreeSet;
+
+use massa_consensus_exports::block_status::{BlockStatus, HeaderOrBlock};
+use massa_hash::Hash;
+use massa_models::{
+    block_id::BlockId, clique::blockclique_fingerprint, prehash::PreHashMap, slot::Slot,
+};
+
+use super::ConsensusState;
+
+/// Processing priority of a block that is not in the graph yet, the lowest being processed first and pruned last
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct BlockPriority {
+    /// true if one of the known parents of the block is an active block out of the blockclique
+    off_blockclique: bool,
+    /// number of slots between the current slot and the slot of the block, if the block is in the future
+    slot_distance: u64,
+}
+
+/// Blocks waiting to be processed, ordered by priority, then by (slot, hash)
+#[derive(Default)]
+pub(crate) struct ProcessingQueue {
+    queue: BTreeSet<(BlockPriority, Slot, BlockId)>,
+    /// queue entry of each block, to replace it when the block is queued again
+    entries: PreHashMap<BlockId, (BlockPriority, Slot)>,
+}
+
+impl ProcessingQueue {
+    /// Queue a block, replacing its previous entry if it was already queued
+    pub(crate) fn push(&mut self, priority: BlockPriority, slot: Slot, block_id: BlockId) {
+        if let Some((old_priority, old_slot)) = self.entries.insert(block_id, (priority, slot)) {
+            self.queue.remove(&(old_priority, old_slot, block_id));
+        }
+        self.queue.insert((priority, slot, block_id));
+    }
+
+    /// Remove the block to process first from the queue
+    pub(crate) fn pop(&mut self) -> Option<(Slot, BlockId)> {
+        let (_priority, slot, block_id) = self.queue.pop_first()?;
+        self.entries.remove(&block_id);
+        Some((slot, block_id))
+    }
+
+    /// Remove all the blocks from the queue
+    pub(crate) fn take_all(&mut self) -> Vec<(Slot, BlockId)> {
+        self.entries.clear();
+        std::mem::take(&mut self.queue)
+            .into_iter()
+            .map(|(_priority, slot, block_id)| (slot, block_id))
+            .collect()
+    }
+}
+
+impl ConsensusState {
+    /// Fingerprint of the current blockclique, to know when the block priorities have to be computed again
+    pub(crate) fn blockclique_priority_fingerprint(&self) -> Option<Hash> {
+        self.max_cliques
+            .iter()
+            .find(|clique| clique.is_blockclique)
+            .map(|clique| blockclique_fingerprint(&clique.block_ids))
+    }
+
+    /// Compute the processing priority of a block that is not active yet.
+    /// During catch-up, this makes the node extend the blockclique first instead of processing far away forks.
+    ///
+    /// # Arguments:
+    /// * `block_id`: the id of the block
+    /// * `current_slot`: the current slot
+    pub(crate) fn block_priority(
+        &self,
+        block_id: &BlockId,
+        current_slot: Option<Slot>,
+    ) -> BlockPriority {
+        let header_or_block = match self.blocks_state.get(block_id) {
+            Some(BlockStatus::Incoming(header_or_block))
+            | Some(BlockStatus::WaitingForSlot(header_or_block))
+            | Some(BlockStatus::WaitingForDependencies {
+                header_or_block, ..
+            }) => header_or_block,
+            _ => {
+                return BlockPriority {
+                    off_blockclique: false,
+                    slot_distance: 0,
+                }
+            }
+        };
+        let slot = header_or_block.get_slot();
+        let parents = match header_or_block {
+            HeaderOrBlock::Header(header) => header.content.parents.clone(),
+            HeaderOrBlock::Block { id, storage, .. } => storage
+                .read_blocks()
+                .get(id)
+                .map(|block| block.content.header.content.parents.clone())
+                .unwrap_or_default(),
+        };
+        let blockclique = self.max_cliques.iter().find(|clique| clique.is_blockclique);
+        let off_blockclique =
+            parents
+                .iter()
+                .any(|parent_id| match self.blocks_state.get(parent_id) {
+                    Some(BlockStatus::Active { a_block, .. }) => {
+                        !a_block.is_final
+                            && blockclique
+                                .is_some_and(|clique| !clique.block_ids.contains(parent_id))
+                    }
+                    _ => false,
+                });
+        // blocks of past slots keep being processed in slot order, so that parents come before their children
+        let slot_distance = match current_slot {
+            Some(current_slot) if slot > current_slot => slot
+                .slots_since(&current_slot, self.config.thread_count)
+                .unwrap_or(u64::MAX),
+            _ => 0,
+        };
+        BlockPriority {
+            off_blockclique,
+            slot_distance,
+        }
+    }
+}
@@ -6,6 +6,7 @@ use std::{
 use massa_consensus_exports::{
     block_status::{BlockStatus, DiscardReason, HeaderOrBlock, StorageOrBlock},
     error::ConsensusError,
+    FinalizedBlock,
 };
 use massa_execution_exports::ExecutionBlockMetadata;
 use massa_logging::massa_trace;
@@ -14,18 +15,20 @@ use massa_models::{
     address::Address,
     block_header::SecuredHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{blockclique_fingerprint, BlockcliqueReorg, Clique},
     prehash::{PreHashMap, PreHashSet},
     slot::Slot,
     timeslots,
 };
+use massa_pool_exports::BlockUsage;
 use massa_signature::PublicKey;
 use massa_storage::Storage;
 use massa_time::MassaTime;
-use tracing::log::{debug, info};
+use tracing::log::{debug, info, trace};
 
 use crate::state::{
     clique_computation::compute_max_cliques,
+    priority::ProcessingQueue,
     verifications::{BlockCheckOutcome, HeaderCheckOutcome},
 };
 
@@ -60,15 +63,48 @@ impl ConsensusState {
     /// Success or error if an error happened during the processing of items
     pub fn rec_process(
         &mut self,
-        mut to_ack: BTreeSet<(Slot, BlockId)>,
+        to_ack: BTreeSet<(Slot, BlockId)>,
         current_slot: Option<Slot>,
     ) -> Result<(), ConsensusError> {
-        // order processing by (slot, hash)
-        while let Some((_slot, hash)) = to_ack.pop_first() {
+        // order processing by priority (blocks extending the blockclique and close to the current slot first), then by (slot, hash)
+        let mut queue = ProcessingQueue::default();
+        for (slot, hash) in to_ack {
+            queue.push(self.block_priority(&hash, current_slot), slot, hash);
+        }
+        let mut blockclique = self.blockclique_priority_fingerprint();
+        while let Some((_slot, hash)) = queue.pop() {
             // When a slot and a block ID is processed through the `process` function, it is possible that it causes others blocks
             // to need processing as well. In this case the `process` function will return them and they will be added to
-            // the `to_ack` vector to be processed in the future.
-            to_ack.extend(self.process(hash, current_slot)?)
+            // the queue to be processed in the future.
+            let reprocess = self.process(hash, current_slot)?;
+
+            // the priorities depend on the blockclique, which can only change when a block joins the graph:
+            // compute the priorities of the queued blocks again if it changed
+            if matches!(
+                self.blocks_state.get(&hash),
+                Some(BlockStatus::Active { .. })
+            ) {
+                let new_blockclique = self.blockclique_priority_fingerprint();
+                if new_blockclique != blockclique {
+                    blockclique = new_blockclique;
+                    for (slot, queued_hash) in queue.take_all() {
+                        queue.push(
+                            self.block_priority(&queued_hash, current_slot),
+                            slot,
+                            queued_hash,
+                        );
+                    }
+                }
+            }
+
+            // the status of the re-queued blocks changed: compute their priority again
+            for (slot, reprocess_hash) in reprocess {
+                queue.push(
+                    self.block_priority(&reprocess_hash, current_slot),
+                    slot,
+                    reprocess_hash,
+                );
+            }
         }
         Ok(())
     }
@@ -624,6 +660,7 @@ impl ConsensusState {
 
         // Get new blockclique block list with slots.
         let mut blockclique_changed = false;
+        let mut promoted_blocks = Vec::new();
         let new_blockclique: PreHashMap<BlockId, Slot> = self
             .get_blockclique()
             .iter()
@@ -645,6 +682,7 @@ impl ConsensusState {
                         _ => panic!("blockclique block not found in active blocks and/or its operations are missing"),
                     };
                     new_blocks_metadata.insert(*b_id, ExecutionBlockMetadata { same_thread_parent_creator: a_block.same_thread_parent_creator, storage: Some(storage.clone()) });
+                    promoted_blocks.push((a_block.slot, *b_id));
                     (*b_id, a_block.slot)
                 }
             })
@@ -654,6 +692,13 @@ impl ConsensusState {
             // If `prev_blockclique` is not empty here, it means that it contained elements that are not in the new blockclique anymore.
             // In that case, we mark the blockclique as having changed.
             blockclique_changed = true;
+            if self.config.broadcast_enabled {
+                self.broadcast_blockclique_reorg(
+                    &finalized_blocks,
+                    &new_blockclique,
+                    promoted_blocks,
+                );
+            }
         }
         // Overwrite previous blockclique.
         // Should still be done even if unchanged because elements were removed from it above.
@@ -678,6 +723,70 @@ impl ConsensusState {
             );
     }
 
+    /// Broadcast a reorg if non-final blocks of the previous blockclique left it.
+    ///
+    /// Must be called before overwriting `prev_blockclique`, which only holds the blocks
+    /// of the previous blockclique that are not in `new_blockclique` anymore.
+    fn broadcast_blockclique_reorg(
+        &self,
+        finalized_blocks: &HashMap<Slot, BlockId>,
+        new_blockclique: &PreHashMap<BlockId, Slot>,
+        mut promoted_blocks: Vec<(Slot, BlockId)>,
+    ) {
+        // final blocks leave the blockclique without being demoted
+        let finalized_ids: PreHashSet<BlockId> = finalized_blocks.values().copied().collect();
+        let mut demoted_blocks: Vec<(Slot, BlockId)> = self
+            .prev_blockclique
+            .iter()
+            .filter(|(b_id, _slot)| !finalized_ids.contains(b_id))
+            .map(|(b_id, slot)| (*slot, *b_id))
+            .collect();
+        if demoted_blocks.is_empty() {
+            return;
+        }
+        demoted_blocks.sort_unstable();
+        promoted_blocks.sort_unstable();
+
+        let promoted_ids: PreHashSet<BlockId> =
+            promoted_blocks.iter().map(|(_slot, b_id)| *b_id).collect();
+        let old_blockclique_fingerprint = blockclique_fingerprint(
+            new_blockclique
+                .keys()
+                .filter(|b_id| !promoted_ids.contains(b_id))
+                .chain(self.prev_blockclique.keys()),
+        );
+        let mut affected_slots: Vec<Slot> = demoted_blocks
+            .iter()
+            .chain(promoted_blocks.iter())
+            .map(|(slot, _b_id)| *slot)
+            .collect();
+        affected_slots.sort_unstable();
+        affected_slots.dedup();
+
+        let reorg = BlockcliqueReorg {
+            old_blockclique_fingerprint,
+            new_blockclique_fingerprint: blockclique_fingerprint(new_blockclique.keys()),
+            demoted_blocks,
+            promoted_blocks,
+            affected_slots,
+        };
+        debug!(
+            "blockclique reorg: {} blocks demoted",
+            reorg.demoted_blocks.len()
+        );
+        if let Err(err) = self
+            .channels
+            .broadcasts
+            .blockclique_reorg_sender
+            .send(reorg)
+        {
+            trace!(
+                "error, failed to broadcast blockclique reorg due to: {}",
+                err
+            );
+        }
+    }
+
     /// call me if the block database changed
     /// Processing of final blocks, pruning.
     ///
@@ -685,15 +794,15 @@ impl ConsensusState {
     /// 2. Notify of attack attempts
     /// 3. get new final blocks
     /// 4. get blockclique
-    /// 5. notify Execution
+    /// 5. notify Execution and Pool (block usage) and broadcast the new final blocks
     /// 6. Process new final blocks
     /// 7. Notify pool of new final ops
     /// 8. Notify PoS of final blocks
     /// 9. notify protocol of block wish list
     /// 10. note new latest final periods (prune graph if changed)
-    /// 11. add stale blocks to stats
+    /// 11. add stale and discarded blocks to stats
     pub fn block_db_changed(&mut self) -> Result<(), ConsensusError> {
-        let final_block_slots = {
+        let (final_block_slots, mut finalized_broadcasts, final_block_usage) = {
             massa_trace!("consensus.consensus_worker.block_db_changed", {});
 
             // Propagate new blocks
@@ -721,11 +830,58 @@ impl ConsensusState {
             let finalized_blocks = mem::take(&mut self.new_final_blocks);
             let mut final_block_slots = HashMap::with_capacity(finalized_blocks.len());
             let mut final_block_stats = VecDeque::with_capacity(finalized_blocks.len());
+            let mut finalized_broadcasts = Vec::new();
+            let mut final_block_usage = Vec::with_capacity(finalized_blocks.len());
             for b_id in finalized_blocks {
-                if let Some(BlockStatus::Active { a_block, .. }) = self.blocks_state.get(&b_id) {
+                if let Some(BlockStatus::Active {
+                    a_block,
+                    storage_or_block,
+                }) = self.blocks_state.get(&b_id)
+                {
                     // add to final blocks to notify execution
                     final_block_slots.insert(a_block.slot, b_id);
 
+                    // measure the resources used by the operations of the block, for the pool fee floor
+                    if let StorageOrBlock::Storage(storage) = storage_or_block {
+                        if let Some(block) = storage.read_blocks().get(&b_id) {
+                            let ops = storage.read_operations();
+                            let mut usage = BlockUsage {
+                                slot: a_block.slot,
+                                operation_count: 0,
+                                gas: 0,
+                                size: 0,
+                            };
+                            for op in block
+                                .content
+                                .operations
+                                .iter()
+                                .filter_map(|op_id| ops.get(op_id))
+                            {
+                                usage.operation_count = usage.operation_count.saturating_add(1);
+                                usage.gas = usage.gas.saturating_add(op.get_gas_usage());
+                                usage.size = usage.size.saturating_add(op.serialized_size() as u64);
+                            }
+                            final_block_usage.push(usage);
+                        }
+                    }
+
+                    // add to final blocks to broadcast
+                    if self.config.broadcast_enabled {
+                        let operation_ids = match storage_or_block {
+                            StorageOrBlock::Storage(storage) => storage
+                                .read_blocks()
+                                .get(&b_id)
+                                .map(|block| block.content.operations.clone())
+                                .unwrap_or_default(),
+                            StorageOrBlock::Block(block) => block.content.operations.clone(),
+                        };
+                        finalized_broadcasts.push(FinalizedBlock {
+                            slot: a_block.slot,
+                            block_id: b_id,
+                            operation_ids,
+                        });
+                    }
+
                     // add to stats
                     let block_is_from_protocol = self
                         .protocol_blocks
@@ -743,15 +899,47 @@ impl ConsensusState {
             // add stale blocks to stats
             let new_stale_block_ids_creators_slots = mem::take(&mut self.new_stale_blocks);
             let timestamp = MassaTime::now()?;
-            for (_b_id, (_b_creator, _b_slot)) in new_stale_block_ids_creators_slots.into_iter() {
+            for (b_id, (b_creator, b_slot)) in new_stale_block_ids_creators_slots.into_iter() {
+                debug!(
+                    "block {} created by {} at slot {} became stale",
+                    b_id, b_creator, b_slot
+                );
                 self.stale_block_stats.push_back(timestamp);
             }
-            final_block_slots
+
+            // add discarded blocks to stats
+            self.note_new_discards();
+            (final_block_slots, finalized_broadcasts, final_block_usage)
         };
 
         // notify execution
         self.notify_execution(final_block_slots);
 
+        // notify pool of the resources used by the new final blocks
+        if !final_block_usage.is_empty() {
+            self.channels
+                .pool_controller
+                .notify_final_block_usage(final_block_usage);
+        }
+
+        // broadcast the new final blocks in slot order
+        finalized_broadcasts.sort_unstable_by_key(|finalized| finalized.slot);
+        for finalized in finalized_broadcasts {
+            let block_id = finalized.block_id;
+            if let Err(err) = self
+                .channels
+                .broadcasts
+                .finalized_block_sender
+                .send(finalized)
+            {
+                trace!(
+                    "error, failed to broadcast finalized block with id {} due to: {}",
+                    block_id,
+                    err
+                );
+            }
+        }
+
         // notify protocol of block wishlist
         let new_wishlist = self.get_block_wishlist()?;
         let new_blocks: PreHashMap<BlockId, Option<SecuredHeader>> = new_wishlist
@@ -10,56 +10,75 @@ use massa_models::{
     block_id::BlockId,
     prehash::{PreHashMap, PreHashSet},
     slot::Slot,
+    timeslots::get_latest_block_slot_at_timestamp,
 };
+use massa_time::MassaTime;
+use std::cmp::Reverse;
 use tracing::debug;
 
 use super::ConsensusState;
 
 impl ConsensusState {
     /// prune active blocks and return final blocks, return discarded final blocks
+    ///
+    /// Only the active blocks that crossed a retention boundary are visited:
+    /// the blocks older than `force_keep_final_periods_without_ops` are removed unless required,
+    /// and the operations of the blocks older than `force_keep_final_periods` are dropped once.
     fn prune_active(&mut self) -> Result<PreHashMap<BlockId, ActiveBlock>, ConsensusError> {
         // list required active blocks
-        let mut retain_active: PreHashSet<BlockId> = self.list_required_active_blocks(None)?;
+        let retain_active: PreHashSet<BlockId> = self.list_required_active_blocks(None)?;
 
-        // retain extra history according to the config
-        // this is useful to avoid desync on temporary connection loss
-        for a_block in self.blocks_state.active_blocks().clone().iter() {
-            if let Some(BlockStatus::Active {
-                a_block: active_block,
-                storage_or_block,
-            }) = self.blocks_state.get_mut(a_block)
+        let mut to_remove: Vec<BlockId> = Vec::new();
+        for thread in 0..self.config.thread_count {
+            let (_b_id, latest_final_period) = self.latest_final_blocks_periods[thread as usize];
+            let keep_without_ops_from = latest_final_period
+                .saturating_sub(self.config.force_keep_final_periods_without_ops);
+            let keep_with_ops_from =
+                latest_final_period.saturating_sub(self.config.force_keep_final_periods);
+
+            // retain extra history according to the config, remove the older blocks that are not required
+            // this is useful to avoid desync on temporary connection loss
+            for (_period, block_id) in self
+                .blocks_state
+                .active_blocks_in_periods(thread, 0..keep_without_ops_from)
             {
-                let (_b_id, latest_final_period) =
-                    self.latest_final_blocks_periods[active_block.slot.thread as usize];
+                self.active_index_without_ops.remove(block_id);
+                if !retain_active.contains(block_id) {
+                    to_remove.push(*block_id);
+                }
+            }
 
-                if active_block.slot.period
-                    >= latest_final_period
-                        .saturating_sub(self.config.force_keep_final_periods_without_ops)
+            // drop the operations of the blocks that crossed `force_keep_final_periods` since the last pruning
+            let strip_from = std::cmp::max(
+                keep_without_ops_from,
+                self.ops_pruned_periods[thread as usize],
+            );
+            let to_strip: Vec<BlockId> = self
+                .blocks_state
+                .active_blocks_in_periods(thread, strip_from..keep_with_ops_from)
+                .map(|(_period, block_id)| *block_id)
+                .collect();
+            for block_id in to_strip {
+                if self.active_index_without_ops.contains(&block_id) {
+                    continue;
+                }
+                if let Some(BlockStatus::Active {
+                    a_block: active_block,
+                    storage_or_block,
+                }) = self.blocks_state.get_mut(&block_id)
                 {
-                    retain_active.insert(*a_block);
-                    if active_block.slot.period
-                        < latest_final_period.saturating_sub(self.config.force_keep_final_periods)
-                        && !self.active_index_without_ops.contains(a_block)
-                    {
-                        storage_or_block.strip_to_block(a_block);
-                        self.active_index_without_ops.insert(*a_block);
-                        // reset the list of descendants
-                        active_block.descendants = Default::default();
-                    }
-                } else {
-                    self.active_index_without_ops.remove(a_block);
+                    storage_or_block.strip_to_block(&block_id);
+                    self.active_index_without_ops.insert(block_id);
+                    // reset the list of descendants
+                    active_block.descendants = Default::default();
                 }
             }
+            self.ops_pruned_periods[thread as usize] =
+                std::cmp::max(self.ops_pruned_periods[thread as usize], keep_with_ops_from);
         }
 
         // remove unused final active blocks
         let mut discarded_finals: PreHashMap<BlockId, ActiveBlock> = PreHashMap::default();
-        let to_remove: Vec<BlockId> = self
-            .blocks_state
-            .active_blocks()
-            .difference(&retain_active)
-            .copied()
-            .collect();
         for discard_active_h in to_remove {
             let sequence_number = self.blocks_state.sequence_counter();
             self.blocks_state.transition_map(&discard_active_h, |block_status, block_statuses| {
@@ -163,6 +182,12 @@ impl ConsensusState {
     }
 
     fn prune_waiting_for_dependencies(&mut self) -> Result<(), ConsensusError> {
+        let current_slot = get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            MassaTime::now()?,
+        )?;
         let mut to_discard: PreHashMap<BlockId, Option<DiscardReason>> = PreHashMap::default();
         let mut to_keep: PreHashMap<BlockId, (u64, Slot)> = PreHashMap::default();
 
@@ -212,6 +237,7 @@ impl ConsensusState {
         }
 
         // discard in chain and because of limited size
+        let mut removal_order = None;
         while !to_keep.is_empty() {
             // mark entries as to_discard and remove them from to_keep
             for (hash, _old_order) in to_keep.clone().into_iter() {
@@ -249,23 +275,28 @@ impl ConsensusState {
                 }
             }
 
-            // remove worst excess element
+            // remove worst excess element: the lowest priority one, then the oldest one
             if to_keep.len() > self.config.max_dependency_blocks {
-                let remove_elt = to_keep
-                    .iter()
-                    .filter_map(|(hash, _old_order)| {
-                        if let Some(BlockStatus::WaitingForDependencies {
-                            header_or_block,
-                            sequence_number,
-                            ..
-                        }) = self.blocks_state.get(hash)
-                        {
-                            return Some((sequence_number, header_or_block.get_slot(), *hash));
-                        }
-                        None
-                    })
-                    .min();
-                if let Some((_seq_num, _slot, hash)) = remove_elt {
+                // the priorities do not change while pruning: sort the kept blocks once, the worst one last
+                let removal_order = removal_order.get_or_insert_with(|| {
+                    let mut order: Vec<_> = to_keep
+                        .iter()
+                        .map(|(hash, (sequence_number, slot))| {
+                            (
+                                Reverse(self.block_priority(hash, current_slot)),
+                                *sequence_number,
+                                *slot,
+                                *hash,
+                            )
+                        })
+                        .collect();
+                    order.sort_unstable_by(|a, b| b.cmp(a));
+                    order
+                });
+                // skip the blocks discarded in chain in the meantime
+                let remove_elt = std::iter::from_fn(|| removal_order.pop())
+                    .find(|(_priority, _seq_num, _slot, hash)| to_keep.contains_key(hash));
+                if let Some((_priority, _seq_num, _slot, hash)) = remove_elt {
                     to_keep.remove(&hash);
                     to_discard.insert(hash, None);
                     continue;
@@ -1,8 +1,14 @@
 use super::ConsensusState;
-use massa_consensus_exports::error::ConsensusError;
-use massa_models::stats::ConsensusStats;
+use massa_consensus_exports::{block_status::DiscardReasonKind, error::ConsensusError};
+use massa_models::stats::{CliqueStats, ConsensusStats, DiscardedBlockCounts};
 use massa_time::MassaTime;
-use std::cmp::max;
+use std::{
+    cmp::{max, Reverse},
+    collections::BTreeMap,
+};
+
+/// Number of cycles for which discarded block counts are kept
+const DISCARD_STATS_CYCLE_COUNT: usize = 5;
 
 #[cfg(not(feature = "sandbox"))]
 use tracing::log::warn;
@@ -29,21 +35,91 @@ impl ConsensusState {
             .filter(|t| **t >= timespan_start && **t < timespan_end)
             .count() as u64;
         let clique_count = self.get_clique_count() as u64;
+        let max_clique_count = self
+            .clique_count_stats
+            .iter()
+            .filter(|(t, _)| *t >= timespan_start && *t < timespan_end)
+            .map(|(_, count)| *count as u64)
+            .fold(clique_count, max);
+        let mut cliques: Vec<CliqueStats> = self
+            .max_cliques
+            .iter()
+            .map(|clique| CliqueStats {
+                block_count: clique.block_ids.len() as u64,
+                fitness: clique.fitness,
+                is_blockclique: clique.is_blockclique,
+            })
+            .collect();
+        cliques.sort_unstable_by_key(|clique| Reverse(clique.fitness));
+        let fork_depths = self
+            .best_parents
+            .iter()
+            .zip(self.latest_final_blocks_periods.iter())
+            .map(|((_, head_period), (_, final_period))| head_period.saturating_sub(*final_period))
+            .collect();
+        let mut discarded_blocks_per_cycle = BTreeMap::new();
+        let mut discarded_blocks_per_creator = BTreeMap::new();
+        for (cycle, creators) in &self.discarded_block_stats {
+            let cycle_counts: &mut DiscardedBlockCounts =
+                discarded_blocks_per_cycle.entry(*cycle).or_default();
+            for (creator, counts) in creators {
+                add_counts(cycle_counts, counts);
+                add_counts(
+                    discarded_blocks_per_creator.entry(*creator).or_default(),
+                    counts,
+                );
+            }
+        }
         Ok(ConsensusStats {
             final_block_count,
             stale_block_count,
             clique_count,
+            max_clique_count,
+            cliques,
+            fork_depths,
             start_timespan: timespan_start,
             end_timespan: timespan_end,
+            discarded_blocks_per_cycle,
+            discarded_blocks_per_creator,
         })
     }
 
+    /// Count the blocks discarded since the last call, per cycle and per creator
+    pub(crate) fn note_new_discards(&mut self) {
+        for (slot, creator, reason) in self.blocks_state.take_new_discards() {
+            let counts = self
+                .discarded_block_stats
+                .entry(slot.get_cycle(self.config.periods_per_cycle))
+                .or_default()
+                .entry(creator)
+                .or_default();
+            let label = match reason {
+                DiscardReasonKind::Stale => {
+                    counts.stale_count += 1;
+                    "stale"
+                }
+                DiscardReasonKind::Invalid => {
+                    counts.invalid_count += 1;
+                    "invalid"
+                }
+                DiscardReasonKind::Final => {
+                    counts.final_count += 1;
+                    "final"
+                }
+            };
+            self.massa_metrics.inc_consensus_discarded_blocks(label);
+        }
+    }
+
     /// Must be called each tick to update stats. Will detect if a desynchronization happened
     pub fn stats_tick(&mut self) -> Result<(), ConsensusError> {
         #[cfg(not(feature = "sandbox"))]
         {
             self.check_desync()?;
         }
+        // note the clique count, to know how much the network was split over the stats time span
+        self.clique_count_stats
+            .push_back((MassaTime::now()?, self.get_clique_count()));
         // prune stats
         self.prune_stats()?;
         Ok(())
@@ -105,6 +181,23 @@ impl ConsensusState {
                 break;
             }
         }
+        while let Some((t, _)) = self.clique_count_stats.front() {
+            if t < &start_time {
+                self.clique_count_stats.pop_front();
+            } else {
+                break;
+            }
+        }
+        while self.discarded_block_stats.len() > DISCARD_STATS_CYCLE_COUNT {
+            self.discarded_block_stats.pop_first();
+        }
         Ok(())
     }
 }
+
+/// Add the discarded block counts of `other` to `counts`
+fn add_counts(counts: &mut DiscardedBlockCounts, other: &DiscardedBlockCounts) {
+    counts.stale_count += other.stale_count;
+    counts.invalid_count += other.invalid_count;
+    counts.final_count += other.final_count;
+}
@@ -1,5 +1,6 @@
 mod tools;
 
+pub mod replay_scenarios;
 pub mod scenarios;
 pub mod three_four_threads_scenarios;
 pub mod two_threads_scenarios;
@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::{replay_blocks, tests::tools::create_block, ReplayOutcome};
+use massa_channel::MassaChannel;
+use massa_consensus_exports::{ConsensusBroadcasts, ConsensusChannels, ConsensusConfig};
+use massa_execution_exports::{ExecutionController, MockExecutionController};
+use massa_metrics::MassaMetrics;
+use massa_models::{
+    address::Address, block::SecureShareBlock, block_id::BlockId, config::THREAD_COUNT, slot::Slot,
+};
+use massa_pool_exports::{MockPoolController, PoolController};
+use massa_pos_exports::{MockSelectorController, SelectorController};
+use massa_protocol_exports::{MockProtocolController, ProtocolController};
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+use massa_time::MassaTime;
+
+fn replay_execution_controller() -> Box<dyn ExecutionController> {
+    let mut execution_controller = MockExecutionController::new();
+    execution_controller
+        .expect_update_blockclique_status()
+        .returning(|_, _, _| {});
+    execution_controller
+        .expect_clone_box()
+        .returning(replay_execution_controller);
+    Box::new(execution_controller)
+}
+
+fn replay_pool_controller() -> Box<dyn PoolController> {
+    let mut pool_controller = MockPoolController::new();
+    pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    pool_controller
+        .expect_notify_final_block_usage()
+        .returning(|_| {});
+    pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    pool_controller
+        .expect_clone_box()
+        .returning(replay_pool_controller);
+    Box::new(pool_controller)
+}
+
+fn replay_protocol_controller() -> Box<dyn ProtocolController> {
+    let mut protocol_controller = MockProtocolController::new();
+    protocol_controller
+        .expect_integrated_block()
+        .returning(|_, _| Ok(()));
+    protocol_controller
+        .expect_send_wishlist_delta()
+        .returning(|_, _| Ok(()));
+    protocol_controller
+        .expect_notify_block_attack()
+        .returning(|_| Ok(()));
+    protocol_controller
+        .expect_clone_box()
+        .returning(replay_protocol_controller);
+    Box::new(protocol_controller)
+}
+
+fn replay_selector_controller(producer: Address) -> Box<dyn SelectorController> {
+    let mut selector_controller = MockSelectorController::new();
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(producer));
+    selector_controller
+        .expect_clone_box()
+        .returning(move || replay_selector_controller(producer));
+    Box::new(selector_controller)
+}
+
+/// Replay the given blocks with channels ignoring every notification
+fn replay(cfg: &ConsensusConfig, blocks: &[SecureShareBlock]) -> ReplayOutcome {
+    let producer = Address::from_public_key(&cfg.genesis_key.get_public_key());
+    let (consensus_event_sender, _) = MassaChannel::new(String::from("consensus_event"), Some(10));
+    let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
+    let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
+    let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
+    let (finalized_block_sender, _finalized_block_receiver) = tokio::sync::broadcast::channel(10);
+    let (blockclique_reorg_sender, _blockclique_reorg_receiver) =
+        tokio::sync::broadcast::channel(10);
+    let channels = ConsensusChannels {
+        broadcasts: ConsensusBroadcasts {
+            block_sender,
+            block_header_sender,
+            filled_block_sender,
+            finalized_block_sender,
+            blockclique_reorg_sender,
+        },
+        controller_event_tx: consensus_event_sender,
+        execution_controller: replay_execution_controller(),
+        protocol_controller: replay_protocol_controller(),
+        pool_controller: replay_pool_controller(),
+        selector_controller: replay_selector_controller(producer),
+    };
+
+    let mut storage = Storage::create_root();
+    for block in blocks {
+        storage.store_block(block.clone());
+    }
+    replay_blocks(
+        cfg.clone(),
+        channels,
+        None,
+        storage,
+        blocks.iter().map(|block| block.id).collect(),
+        MassaMetrics::new(
+            false,
+            "0.0.0.0:9898".parse().unwrap(),
+            THREAD_COUNT,
+            Duration::from_secs(1),
+        )
+        .0,
+    )
+    .expect("replay failed")
+}
+
+/// Replaying the same blocks twice, given in different orders, gives the same final blocks.
+#[test]
+fn test_replay_is_deterministic() {
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let cfg = ConsensusConfig {
+        t0: MassaTime::from_millis(1000),
+        thread_count: 2,
+        genesis_timestamp: MassaTime::now().unwrap(),
+        delta_f0: 3,
+        force_keep_final_periods: 50,
+        force_keep_final_periods_without_ops: 128,
+        genesis_key: staking_key.clone(),
+        ..ConsensusConfig::default()
+    };
+
+    // without blocks, only the genesis blocks are final
+    let genesis_outcome = replay(&cfg, &[]);
+    let genesis_hashes: Vec<BlockId> = genesis_outcome.final_blocks.values().copied().collect();
+    assert_eq!(genesis_hashes.len(), 2);
+
+    // build a chain of blocks on both threads
+    let mut blocks = Vec::new();
+    let mut parents = genesis_hashes;
+    for period in 1..=8 {
+        let t0_block = create_block(Slot::new(period, 0), parents.clone(), &staking_key);
+        let t1_block = create_block(
+            Slot::new(period, 1),
+            vec![t0_block.id, parents[1]],
+            &staking_key,
+        );
+        parents = vec![t0_block.id, t1_block.id];
+        blocks.push(t0_block);
+        blocks.push(t1_block);
+    }
+
+    let outcome = replay(&cfg, &blocks);
+    assert!(outcome.final_blocks.len() > genesis_outcome.final_blocks.len());
+    assert!(!outcome.blockclique.is_empty());
+
+    let mut reversed_blocks = blocks.clone();
+    reversed_blocks.reverse();
+    let reversed_outcome = replay(&cfg, &reversed_blocks);
+    assert_eq!(outcome, reversed_outcome);
+
+    // the final blocks of a replay are checked against the expected ones
+    outcome
+        .check_final_blocks(&reversed_outcome.final_blocks)
+        .expect("final blocks should match");
+    let mut diverging_final_blocks = BTreeMap::new();
+    diverging_final_blocks.insert(Slot::new(1, 0), blocks[1].id);
+    assert!(outcome.check_final_blocks(&diverging_final_blocks).is_err());
+}
@@ -53,6 +53,9 @@ fn test_unsorted_block() {
     pool_controller_2
         .expect_notify_final_cs_periods()
         .returning(|_| {});
+    pool_controller_2
+        .expect_notify_final_block_usage()
+        .returning(|_| {});
     //TODO: To be deleted when channels will be well used instead of clones
     pool_controller
         .expect_clone_box()
@@ -170,6 +173,9 @@ fn test_parallel_incompatibility() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
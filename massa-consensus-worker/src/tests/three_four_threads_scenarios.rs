@@ -41,6 +41,9 @@ fn test_fts_latest_blocks_as_parents() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -254,6 +257,9 @@ fn test_fts_multiple_max_cliques_1() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -586,6 +592,9 @@ fn test_fts_multiple_max_cliques_2() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -73,6 +73,9 @@ pub fn consensus_test<F>(
     let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
     let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
     let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
+    let (finalized_block_sender, _finalized_block_receiver) = tokio::sync::broadcast::channel(10);
+    let (blockclique_reorg_sender, _blockclique_reorg_receiver) =
+        tokio::sync::broadcast::channel(10);
     let (consensus_controller, mut consensus_manager) = start_consensus_worker(
         cfg.clone(),
         ConsensusChannels {
@@ -80,6 +83,8 @@ pub fn consensus_test<F>(
                 block_sender,
                 block_header_sender,
                 filled_block_sender,
+                finalized_block_sender,
+                blockclique_reorg_sender,
             },
             controller_event_tx: consensus_event_sender,
             execution_controller,
@@ -41,6 +41,9 @@ fn test_tts_latest_blocks_as_parents() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -227,6 +230,9 @@ fn test_tts_latest_period_blocks_as_parents() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -394,6 +400,9 @@ fn test_tts_mixed_blocks_as_parents() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -585,6 +594,9 @@ fn test_tts_p2_depends_on_p0_1() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -741,6 +753,9 @@ fn test_tts_p2_depends_on_p0_2() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -883,6 +898,9 @@ fn test_tts_p3_depends_on_p0() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -990,6 +1008,9 @@ fn test_tts_multiple_blocks_depend_on_p0_no_incomp() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -1179,6 +1200,9 @@ fn test_tts_multiple_blocks_depend_on_p0_parallel_incomp() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -1309,6 +1333,9 @@ fn test_tts_parent_registered_later() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
@@ -1455,6 +1482,9 @@ fn test_tts_incompatible_parents() {
             .expect_notify_final_cs_periods()
             .returning(|_| {});
         pool_controller
+            .expect_notify_final_block_usage()
+            .returning(|_| {});
+        pool_controller
     });
     pool_controller
         .expect_add_denunciation_precursor()
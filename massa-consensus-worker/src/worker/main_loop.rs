@@ -1,6 +1,8 @@
 use std::time::Instant;
 
-use massa_consensus_exports::{error::ConsensusError, events::ConsensusEvent};
+use massa_consensus_exports::{
+    error::ConsensusError, events::ConsensusEvent, state_cache::save_state_cache,
+};
 use massa_models::{
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
@@ -169,5 +171,30 @@ impl ConsensusWorker {
                 }
             };
         }
+        self.save_state_cache();
+    }
+
+    /// Saves the final blocks to the state cache file on shutdown, if it is configured
+    fn save_state_cache(&self) {
+        let Some(path) = &self.config.state_cache_path else {
+            return;
+        };
+        let graph = match self.shared_state.read().export_final_blocks() {
+            Ok(graph) => graph,
+            Err(err) => {
+                warn!(
+                    "Could not export the final blocks to the state cache: {}",
+                    err
+                );
+                return;
+            }
+        };
+        match save_state_cache(path, &graph) {
+            Ok(()) => info!(
+                "Saved {} final blocks to the consensus state cache",
+                graph.final_blocks.len()
+            ),
+            Err(err) => warn!("Could not save the consensus state cache: {}", err),
+        }
     }
 }
@@ -59,14 +59,52 @@ pub fn start_consensus_worker(
     massa_metrics: MassaMetrics,
 ) -> (Box<dyn ConsensusController>, Box<dyn ConsensusManager>) {
     let (tx, rx) = MassaChannel::new("consensus_command".to_string(), Some(CHANNEL_SIZE));
-    // desync detection timespan
     let bootstrap_part_size = config.bootstrap_part_size;
+    let shared_state = Arc::new(RwLock::new(new_consensus_state(
+        &config,
+        channels.clone(),
+        storage.clone(),
+        massa_metrics,
+    )));
+
+    let shared_state_cloned = shared_state.clone();
+    let mut consensus_worker =
+        ConsensusWorker::new(config.clone(), rx, shared_state_cloned, init_graph, storage).unwrap();
+
+    let consensus_thread = thread::Builder::new()
+        .name("consensus worker".into())
+        .spawn(move || consensus_worker.run())
+        .expect("Can't spawn consensus thread.");
+
+    let manager = ConsensusManagerImpl {
+        consensus_thread: Some((tx.clone(), consensus_thread)),
+    };
+
+    let controller = ConsensusControllerImpl::new(
+        tx,
+        channels,
+        shared_state,
+        bootstrap_part_size,
+        config.broadcast_enabled,
+    );
+
+    (Box::new(controller), Box::new(manager))
+}
+
+/// Create the state of consensus, holding only the empty graph
+pub(crate) fn new_consensus_state(
+    config: &ConsensusConfig,
+    channels: ConsensusChannels,
+    storage: Storage,
+    massa_metrics: MassaMetrics,
+) -> ConsensusState {
+    // desync detection timespan
     let stats_desync_detection_timespan =
         config.t0.checked_mul(config.periods_per_cycle * 2).unwrap();
-    let shared_state = Arc::new(RwLock::new(ConsensusState {
-        storage: storage.clone(),
+    ConsensusState {
+        storage,
         config: config.clone(),
-        channels: channels.clone(),
+        channels,
         max_cliques: vec![Clique {
             block_ids: PreHashSet::<BlockId>::default(),
             fitness: 0,
@@ -78,6 +116,7 @@ pub fn start_consensus_worker(
         new_final_blocks: Default::default(),
         new_stale_blocks: Default::default(),
         active_index_without_ops: Default::default(),
+        ops_pruned_periods: vec![0; config.thread_count as usize],
         save_final_periods: Default::default(),
         latest_final_blocks_periods: Default::default(),
         best_parents: Default::default(),
@@ -85,6 +124,8 @@ pub fn start_consensus_worker(
         gi_head: Default::default(),
         final_block_stats: Default::default(),
         stale_block_stats: Default::default(),
+        clique_count_stats: Default::default(),
+        discarded_block_stats: Default::default(),
         protocol_blocks: Default::default(),
         wishlist: Default::default(),
         launch_time: MassaTime::now().unwrap(),
@@ -96,28 +137,5 @@ pub fn start_consensus_worker(
         prev_blockclique: Default::default(),
         nonfinal_active_blocks_per_slot: Default::default(),
         massa_metrics,
-    }));
-
-    let shared_state_cloned = shared_state.clone();
-    let mut consensus_worker =
-        ConsensusWorker::new(config.clone(), rx, shared_state_cloned, init_graph, storage).unwrap();
-
-    let consensus_thread = thread::Builder::new()
-        .name("consensus worker".into())
-        .spawn(move || consensus_worker.run())
-        .expect("Can't spawn consensus thread.");
-
-    let manager = ConsensusManagerImpl {
-        consensus_thread: Some((tx.clone(), consensus_thread)),
-    };
-
-    let controller = ConsensusControllerImpl::new(
-        tx,
-        channels,
-        shared_state,
-        bootstrap_part_size,
-        config.broadcast_enabled,
-    );
-
-    (Box::new(controller), Box::new(manager))
+    }
 }
@@ -2,6 +2,32 @@
 pub const METADATA_CF: &str = "metadata";
 pub const STATE_CF: &str = "state";
 pub const VERSIONING_CF: &str = "versioning";
+pub const HISTORY_CF: &str = "history";
+pub const PRODUCTION_STATS_CF: &str = "production_stats";
+pub const EVENTS_CF: &str = "events";
+pub const TRANSFERS_CF: &str = "transfers";
+pub const POOL_CF: &str = "pool";
+pub const EXECUTION_STATS_CF: &str = "execution_stats";
+/// Column families local to the node: neither part of the state hash nor streamed to bootstrap clients
+pub const LOCAL_CFS: &[&str] = &[
+    PRODUCTION_STATS_CF,
+    EVENTS_CF,
+    TRANSFERS_CF,
+    POOL_CF,
+    EXECUTION_STATS_CF,
+];
+/// All the column families of a `MassaDB`
+pub const ALL_CFS: &[&str] = &[
+    STATE_CF,
+    METADATA_CF,
+    VERSIONING_CF,
+    HISTORY_CF,
+    PRODUCTION_STATS_CF,
+    EVENTS_CF,
+    TRANSFERS_CF,
+    POOL_CF,
+    EXECUTION_STATS_CF,
+];
 
 // Hash
 pub const STATE_HASH_BYTES_LEN: usize = 512;
@@ -13,6 +39,10 @@ pub const CHANGE_ID_KEY: &[u8; 1] = b"c";
 pub const CHANGE_ID_DESER_ERROR: &str = "critical: change_id deserialization failed";
 pub const CHANGE_ID_SER_ERROR: &str = "critical: change_id serialization failed";
 
+// Archive
+pub const ARCHIVE_HORIZON_KEY: &[u8; 1] = b"a";
+pub const ARCHIVE_DESER_ERROR: &str = "critical: archived change deserialization failed";
+
 // Errors
 pub const CF_ERROR: &str = "critical: rocksdb column family operation failed";
 pub const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
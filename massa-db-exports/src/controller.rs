@@ -1,7 +1,11 @@
-use crate::{DBBatch, Key, MassaDBError, StreamBatch, Value};
+use crate::{
+    DBBatch, DBPreviousValues, Key, MassaDBError, MassaDBIntegrityReport, MassaDBMetrics,
+    MassaDBTransaction, StreamBatch, Value,
+};
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{error::ModelsError, slot::Slot, streaming_step::StreamingStep};
 use parking_lot::RwLock;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::{fmt::Debug, sync::Arc};
 
@@ -12,6 +16,13 @@ pub trait MassaDBController: Send + Sync + Debug {
     /// Creates a new hard copy of the DB, for the given slot
     fn backup_db(&self, slot: Slot) -> PathBuf;
 
+    /// Lists the backups of the DB, sorted by slot
+    fn list_backups(&self) -> Vec<(Slot, PathBuf)>;
+
+    /// Replaces the content of the DB with the backup made at the given slot.
+    /// The backups are kept, and the change history is cleared.
+    fn restore_from_backup(&mut self, slot: Slot) -> Result<(), MassaDBError>;
+
     /// Get the current change_id attached to the database.
     fn get_change_id(&self) -> Result<Slot, ModelsError>;
 
@@ -21,6 +32,24 @@ pub trait MassaDBController: Send + Sync + Debug {
     /// Writes the batch to the DB
     fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>);
 
+    /// Writes the batch to the DB, given the values of some of its keys before the write.
+    /// The previous values are needed to update the state hash: providing them saves a read per key.
+    fn write_batch_with_previous_values(
+        &mut self,
+        batch: DBBatch,
+        versioning_batch: DBBatch,
+        previous_values: &DBPreviousValues,
+        change_id: Option<Slot>,
+    );
+
+    /// Writes the changes staged in a transaction to STATE_CF and VERSIONING_CF, atomically.
+    /// Nothing is written if it fails, e.g. if `change_id` is before the current one.
+    fn commit_transaction(
+        &mut self,
+        transaction: MassaDBTransaction,
+        change_id: Option<Slot>,
+    ) -> Result<(), MassaDBError>;
+
     /// Utility function to put / update a key & value in the batch
     fn put_or_update_entry_value(&self, batch: &mut DBBatch, key: Vec<u8>, value: &[u8]);
 
@@ -46,19 +75,83 @@ pub trait MassaDBController: Send + Sync + Debug {
         mode: MassaIteratorMode,
     ) -> Box<dyn Iterator<Item = (Key, Value)> + '_>;
 
-    /// Exposes RocksDB's "prefix_iterator_cf" function
+    /// Exposes RocksDB's "prefix_iterator_cf" function.
+    /// No prefix extractor is configured: the iteration goes on after the keys starting with `prefix`,
+    /// callers must stop at the first key not starting with it, or use `range_iterator_cf`.
     fn prefix_iterator_cf(
         &self,
         handle_cf: &str,
         prefix: &[u8],
     ) -> Box<dyn Iterator<Item = (Key, Value)> + '_>;
 
+    /// Iterates over the keys from `start` (included) to `end` (excluded, unbounded if `None`),
+    /// in the given direction. The bounds are enforced by RocksDB, so no key outside of them is read.
+    fn range_iterator_cf(
+        &self,
+        handle_cf: &str,
+        start: &[u8],
+        end: Option<&[u8]>,
+        direction: MassaDirection,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_>;
+
     /// Get the current extended state hash of the database
     fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES>;
 
+    /// Recomputes the state hash from the entries of the state column family and compares it
+    /// to the stored one. Entries for which `is_key_value_valid` returns false are reported.
+    /// Writes are blocked while the whole column family is read.
+    fn verify_integrity(
+        &self,
+        is_key_value_valid: &dyn Fn(&[u8], &[u8]) -> bool,
+    ) -> Result<MassaDBIntegrityReport, MassaDBError>;
+
     /// Flushes the underlying db.
     fn flush(&self) -> Result<(), MassaDBError>;
 
+    /// Makes a secondary instance apply the latest changes of its primary instance.
+    /// Fails on a primary instance.
+    fn catch_up_with_primary(&self) -> Result<(), MassaDBError>;
+
+    /// Writes a batch to a column family local to this node, that is neither part of the state hash
+    /// nor streamed to bootstrap clients (PRODUCTION_STATS_CF, EVENTS_CF, TRANSFERS_CF, POOL_CF
+    /// and EXECUTION_STATS_CF).
+    fn write_local_batch(&self, handle_cf: &str, batch: DBBatch) -> Result<(), MassaDBError>;
+
+    /// Starts compacting the whole column family `handle_cf` in the background, and returns.
+    /// Fails if the column family does not exist or if a manual compaction is already running.
+    fn trigger_compaction(&self, handle_cf: &str) -> Result<(), MassaDBError>;
+
+    /// Get the size on disk of each column family, in bytes
+    fn get_cf_sizes(&self) -> BTreeMap<String, u64>;
+
+    /// Get the space available on the disk holding the database, in bytes, if it can be determined
+    fn get_available_disk_space(&self) -> Option<u64>;
+
+    /// Get a snapshot of the RocksDB and change history statistics
+    fn get_metrics(&self) -> MassaDBMetrics;
+
+    /// Get the value of a key of the state column family at the end of the given slot.
+    /// Fails if the archival mode is disabled or if the slot is not archived.
+    fn get_at_slot(&self, key: &[u8], slot: Slot) -> Result<Option<Value>, MassaDBError>;
+
+    /// Get the entries of the state column family whose key starts with `prefix`,
+    /// as they were at the end of the given slot, sorted by key.
+    /// Fails if the archival mode is disabled or if the slot is not archived.
+    fn iterate_at_slot(
+        &self,
+        prefix: &[u8],
+        slot: Slot,
+    ) -> Result<Box<dyn Iterator<Item = (Key, Value)> + '_>, MassaDBError>;
+
+    /// Get the oldest slot whose state can be queried with `get_at_slot` and `iterate_at_slot`,
+    /// or `None` if the archival mode is disabled.
+    fn get_archive_horizon(&self) -> Option<Slot>;
+
+    /// Get the oldest slot from which changes can still be streamed to a bootstrap client,
+    /// or `None` if the change history is empty.
+    /// Clients that last synchronized before it have to restart their bootstrap from scratch.
+    fn get_change_history_horizon(&self) -> Option<Slot>;
+
     /// Write a stream_batch of database entries received from a bootstrap server
     fn write_batch_bootstrap_client(
         &mut self,
@@ -66,6 +159,13 @@ pub trait MassaDBController: Send + Sync + Debug {
         stream_changes_versioning: StreamBatch<Slot>,
     ) -> Result<(StreamingStep<Key>, StreamingStep<Key>), MassaDBError>;
 
+    /// Write a stream_batch of a stripe of the state received from a bootstrap server.
+    /// The change_id of the DB is not set: the stripes are streamed in parallel at different slots.
+    fn write_stripe_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<Slot>,
+    ) -> Result<StreamingStep<Key>, MassaDBError>;
+
     /// Used for bootstrap servers (get a new batch of data from STATE_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -75,6 +175,18 @@ pub trait MassaDBController: Send + Sync + Debug {
         last_change_id: Option<Slot>,
     ) -> Result<StreamBatch<Slot>, MassaDBError>;
 
+    /// Used for bootstrap servers streaming a stripe of the state (get a new batch of data from STATE_CF,
+    /// restricted to the keys from `range_start` included to `range_end` excluded, to stream to the client)
+    ///
+    /// Returns a StreamBatch<Slot>
+    fn get_stripe_batch_to_stream(
+        &self,
+        range_start: &[u8],
+        range_end: Option<&[u8]>,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError>;
+
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -8,6 +8,13 @@ pub type Value = Vec<u8>;
 /// Here, a DBBatch is a map from Key to Some(Value) for a new or updated value, or None for a deletion
 pub type DBBatch = BTreeMap<Key, Option<Value>>;
 
+/// Values of keys of a DBBatch before it is written, when the caller already knows them.
+///
+/// Here, a key maps to Some(Value) if it holds a value, or None if it is absent from the database.
+/// Writing a batch needs the previous value of each key to update the state hash:
+/// the keys missing from this map are read from the database.
+pub type DBPreviousValues = BTreeMap<Key, Option<Value>>;
+
 /// A Batch of elements from the database, used by a bootstrap server.
 #[derive(Debug, Clone)]
 pub struct StreamBatch<ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug> {
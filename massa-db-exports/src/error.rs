@@ -17,4 +17,12 @@ pub enum MassaDBError {
     RocksDBError(String),
     /// hash error: {0}
     HashError(String),
+    /// backup error: {0}
+    BackupError(String),
+    /// transaction error: {0}
+    TransactionError(String),
+    /// archive error: {0}
+    ArchiveError(String),
+    /// streaming error: {0}
+    StreamingError(String),
 }
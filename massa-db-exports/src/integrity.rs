@@ -0,0 +1,30 @@
+use crate::Key;
+use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
+use massa_models::slot::Slot;
+
+/// Maximum number of invalid keys listed in a `MassaDBIntegrityReport`
+pub const MAX_REPORTED_INVALID_KEYS: usize = 100;
+
+/// Result of the verification of the state column family against its stored hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MassaDBIntegrityReport {
+    /// change_id of the verified state, if one is stored
+    pub change_id: Option<Slot>,
+    /// number of entries of the state column family
+    pub key_count: u64,
+    /// state hash stored in the metadata column family
+    pub stored_hash: HashXof<HASH_XOF_SIZE_BYTES>,
+    /// state hash recomputed from the entries of the state column family
+    pub computed_hash: HashXof<HASH_XOF_SIZE_BYTES>,
+    /// number of entries rejected by the key/value validator
+    pub invalid_key_count: u64,
+    /// first entries rejected by the key/value validator, at most `MAX_REPORTED_INVALID_KEYS`
+    pub invalid_keys: Vec<Key>,
+}
+
+impl MassaDBIntegrityReport {
+    /// Returns true if the recomputed hash matches the stored one and every entry is valid
+    pub fn is_valid(&self) -> bool {
+        self.stored_hash == self.computed_hash && self.invalid_key_count == 0
+    }
+}
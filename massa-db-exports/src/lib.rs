@@ -2,10 +2,19 @@ mod constants;
 mod controller;
 mod db_batch;
 mod error;
+mod integrity;
+mod metrics;
 mod settings;
+mod transaction;
 
 pub use constants::*;
 pub use controller::*;
 pub use db_batch::*;
 pub use error::*;
+pub use integrity::*;
+pub use metrics::*;
 pub use settings::*;
+pub use transaction::*;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod test_exports;
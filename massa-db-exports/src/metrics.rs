@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Snapshot of the RocksDB and change history statistics of a `MassaDB`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MassaDBMetrics {
+    /// total size of the SST files of each column family, in bytes
+    pub cf_sst_sizes: BTreeMap<String, u64>,
+    /// estimated number of bytes that compactions need to rewrite, over all column families
+    pub pending_compaction_bytes: u64,
+    /// number of compactions currently running
+    pub running_compactions: u64,
+    /// number of batches written since the database was opened
+    pub write_count: u64,
+    /// time spent writing batches since the database was opened
+    pub write_time: Duration,
+    /// number of point reads (`get_cf`, and each key of `multi_get_cf`) since the database was opened
+    pub read_count: u64,
+    /// time spent in point reads since the database was opened
+    pub read_time: Duration,
+    /// number of change_ids in the change history
+    pub change_history_length: usize,
+    /// size in bytes of the keys and values of the change history
+    pub change_history_bytes: usize,
+    /// number of change_ids in the versioning change history
+    pub change_history_versioning_length: usize,
+    /// size in bytes of the keys and values of the versioning change history
+    pub change_history_versioning_bytes: usize,
+}
@@ -1,4 +1,7 @@
+use massa_models::config::MAX_BACKUPS_TO_KEEP;
+use serde::Deserialize;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Config structure for a `MassaDBRaw`
 #[derive(Debug, Clone)]
@@ -7,8 +10,117 @@ pub struct MassaDBConfig {
     pub path: PathBuf,
     /// Change history to keep (indexed by ChangeID)
     pub max_history_length: usize,
+    /// Maximum size in bytes of the keys and values kept in the change history, unbounded if `None`.
+    /// The oldest changes are evicted first, but the latest change is always kept.
+    pub max_history_bytes: Option<usize>,
     /// max_new_elements for bootstrap
     pub max_new_elements: usize,
+    /// Maximum size in bytes of the keys and values of a bootstrap stream batch, unbounded if `None`.
+    /// The updates are sent a change_id at a time, then the new elements, at least one of each.
+    pub max_stream_batch_bytes: Option<usize>,
     /// Thread count for slot serialization
     pub thread_count: u8,
+    /// RocksDB tuning options
+    pub tuning: MassaDBTuning,
+    /// Retention policy of the backups
+    pub backup_retention: MassaDBBackupRetention,
+    /// Archival of the state changes of each slot
+    pub archival: MassaDBArchival,
+}
+
+/// Archival mode: the changes made to the state column family at each slot are kept in the
+/// history column family, to query the state as it was at the end of a past slot
+#[derive(Debug, Clone, Default)]
+pub struct MassaDBArchival {
+    /// Whether the state changes are archived
+    pub enabled: bool,
+    /// Number of periods of state changes to keep, the oldest ones are removed first.
+    /// Unbounded if `None`.
+    pub max_periods: Option<u64>,
+}
+
+/// Retention policy of the backups created by `backup_db`, applied each time a backup is created
+#[derive(Debug, Clone)]
+pub struct MassaDBBackupRetention {
+    /// Maximum number of backups to keep, the oldest ones are removed first. Unbounded if `None`.
+    pub max_backups: Option<usize>,
+    /// Maximum age of the backups to keep. Unbounded if `None`.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for MassaDBBackupRetention {
+    fn default() -> Self {
+        MassaDBBackupRetention {
+            max_backups: MAX_BACKUPS_TO_KEEP,
+            max_age: None,
+        }
+    }
+}
+
+/// RocksDB tuning options of a `MassaDBRaw`. Options left to `None` keep the RocksDB defaults.
+#[derive(Debug, Clone, Default)]
+pub struct MassaDBTuning {
+    /// Size in bytes of the LRU block cache shared by all column families
+    pub block_cache_size: Option<usize>,
+    /// Size in bytes of the memtable of each column family
+    pub write_buffer_size: Option<usize>,
+    /// Bits per key of the bloom filters of the column families, disabled if `None`
+    pub bloom_filter_bits_per_key: Option<f64>,
+    /// Maximum number of files kept open by RocksDB, `-1` for no limit
+    pub max_open_files: Option<i32>,
+    /// Compression of the state column family, also used for the history column family
+    /// that stores previous state values
+    pub state_cf_compression: Option<MassaDBCompression>,
+    /// Compression of the metadata column family
+    pub metadata_cf_compression: Option<MassaDBCompression>,
+    /// Compression of the versioning column family
+    pub versioning_cf_compression: Option<MassaDBCompression>,
+    /// Compression of the column families local to the node (production stats, events,
+    /// transfers, pool and execution stats)
+    pub local_cf_compression: Option<MassaDBCompression>,
+    /// Durability policy of the writes. If `None`, the write-ahead log is written at each batch
+    /// but only synced to disk by the OS: no write is lost on a process crash, some can be on a power loss.
+    pub wal_sync: Option<MassaDBWalSync>,
+    /// Maximum rate in bytes per second of the flush and compaction writes, to avoid compaction
+    /// bursts stalling the other disk accesses. Unbounded if `None`.
+    pub compaction_rate_limit: Option<i64>,
+    /// Maximum number of concurrent background flush and compaction jobs
+    pub max_background_jobs: Option<i32>,
+}
+
+/// Durability policy of the writes of a `MassaDBRaw`, trading durability for write throughput
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MassaDBWalSync {
+    /// the write-ahead log is synced to disk at each batch: no write is lost on a crash
+    EveryBatch,
+    /// the write-ahead log is synced to disk at this interval, in a background thread:
+    /// the writes of the last interval can be lost on a power loss
+    Periodic(Duration),
+    /// no write-ahead log: the writes that were not flushed yet from the memtables (see
+    /// `write_buffer_size`) are lost on a crash. The column families are always flushed together,
+    /// so that the database recovers to the consistent state of a previous change_id.
+    Disabled,
+}
+
+/// Refresh policy of a read-only secondary instance of a `MassaDBRaw`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MassaDBCatchUpPolicy {
+    /// catch up with the primary instance only when `catch_up_with_primary` is called
+    Manual,
+    /// catch up with the primary instance periodically, in a background thread
+    Periodic(Duration),
+}
+
+/// Compression algorithm of a column family
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MassaDBCompression {
+    /// no compression
+    None,
+    /// Snappy compression
+    Snappy,
+    /// LZ4 compression
+    Lz4,
+    /// Zstandard compression
+    Zstd,
 }
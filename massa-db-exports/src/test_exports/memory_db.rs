@@ -0,0 +1,1127 @@
+//! In-memory `MassaDBController`, for tests.
+//!
+//! The column families are `BTreeMap`s, which iterate in the same byte order as RocksDB.
+//! The change_id, the XOF state hash, the change history and the bootstrap streaming follow
+//! the RocksDB implementation of `massa_db_worker`, so the state hash of a `MemoryMassaDB` is
+//! the same as the one of a `MassaDB` after the same writes.
+//!
+//! Backups are kept in memory: the paths returned by `backup_db` do not exist on disk.
+
+use crate::{
+    DBBatch, DBPreviousValues, Key, MassaDBConfig, MassaDBController, MassaDBError,
+    MassaDBIntegrityReport, MassaDBMetrics, MassaDBTransaction, MassaDirection, MassaIteratorMode,
+    StreamBatch, Value, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY, CHANGE_ID_SER_ERROR,
+    CRUD_ERROR, EVENTS_CF, EXECUTION_STATS_CF, LOCAL_CFS, MAX_REPORTED_INVALID_KEYS, METADATA_CF,
+    POOL_CF, PRODUCTION_STATS_CF, STATE_CF, STATE_HASH_ERROR, STATE_HASH_INITIAL_BYTES,
+    STATE_HASH_KEY, TRANSFERS_CF, VERSIONING_CF,
+};
+use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
+use massa_models::{
+    error::ModelsError,
+    slot::{Slot, SlotDeserializer, SlotSerializer},
+    streaming_step::StreamingStep,
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use parking_lot::{Mutex, RwLock};
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Included, Unbounded};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Content of the column families of a `MemoryMassaDB`
+#[derive(Debug, Clone, Default)]
+struct MemoryColumns {
+    state: BTreeMap<Key, Value>,
+    metadata: BTreeMap<Key, Value>,
+    versioning: BTreeMap<Key, Value>,
+    production_stats: BTreeMap<Key, Value>,
+    events: BTreeMap<Key, Value>,
+    transfers: BTreeMap<Key, Value>,
+    pool: BTreeMap<Key, Value>,
+    execution_stats: BTreeMap<Key, Value>,
+}
+
+impl MemoryColumns {
+    fn cf(&self, handle_cf: &str) -> &BTreeMap<Key, Value> {
+        match handle_cf {
+            STATE_CF => &self.state,
+            METADATA_CF => &self.metadata,
+            VERSIONING_CF => &self.versioning,
+            PRODUCTION_STATS_CF => &self.production_stats,
+            EVENTS_CF => &self.events,
+            TRANSFERS_CF => &self.transfers,
+            POOL_CF => &self.pool,
+            EXECUTION_STATS_CF => &self.execution_stats,
+            _ => panic!("{}", CF_ERROR),
+        }
+    }
+
+    fn cf_mut(&mut self, handle_cf: &str) -> &mut BTreeMap<Key, Value> {
+        match handle_cf {
+            STATE_CF => &mut self.state,
+            METADATA_CF => &mut self.metadata,
+            VERSIONING_CF => &mut self.versioning,
+            PRODUCTION_STATS_CF => &mut self.production_stats,
+            EVENTS_CF => &mut self.events,
+            TRANSFERS_CF => &mut self.transfers,
+            POOL_CF => &mut self.pool,
+            EXECUTION_STATS_CF => &mut self.execution_stats,
+            _ => panic!("{}", CF_ERROR),
+        }
+    }
+}
+
+/// `MassaDBController` keeping its data in memory
+pub struct MemoryMassaDB {
+    /// configuration, the path is only used to name the backups
+    config: MassaDBConfig,
+    /// column families, behind a lock as the change_id can be set through a shared reference
+    columns: RwLock<MemoryColumns>,
+    /// latest changes made to STATE_CF, by change_id
+    change_history: BTreeMap<Slot, BTreeMap<Key, Option<Value>>>,
+    /// latest changes made to VERSIONING_CF, by change_id
+    change_history_versioning: BTreeMap<Slot, BTreeMap<Key, Option<Value>>>,
+    /// size in bytes of the keys and values retained in change_history
+    change_history_bytes: usize,
+    /// size in bytes of the keys and values retained in change_history_versioning
+    change_history_versioning_bytes: usize,
+    /// backups, by slot, with their creation time
+    backups: Mutex<BTreeMap<Slot, (Instant, MemoryColumns)>>,
+    /// number of writes
+    write_count: AtomicU64,
+    /// number of point reads
+    read_count: AtomicU64,
+    change_id_serializer: SlotSerializer,
+    change_id_deserializer: SlotDeserializer,
+}
+
+impl std::fmt::Debug for MemoryMassaDB {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryMassaDB")
+            .field("config", &self.config)
+            .field("columns", &self.columns)
+            .field("change_history", &self.change_history)
+            .finish()
+    }
+}
+
+impl MemoryMassaDB {
+    /// Returns a new empty `MemoryMassaDB`, attached to slot (0, 0)
+    pub fn new(config: MassaDBConfig) -> Self {
+        let change_id_deserializer = SlotDeserializer::new(
+            (Included(u64::MIN), Included(u64::MAX)),
+            (Included(0), Excluded(config.thread_count)),
+        );
+        let db = MemoryMassaDB {
+            config,
+            columns: RwLock::new(MemoryColumns::default()),
+            change_history: BTreeMap::new(),
+            change_history_versioning: BTreeMap::new(),
+            change_history_bytes: 0,
+            change_history_versioning_bytes: 0,
+            backups: Mutex::new(BTreeMap::new()),
+            write_count: AtomicU64::new(0),
+            read_count: AtomicU64::new(0),
+            change_id_serializer: SlotSerializer::new(),
+            change_id_deserializer,
+        };
+        db.set_initial_change_id(Slot::new(0, 0));
+        db
+    }
+
+    /// Applies changes to STATE_CF and VERSIONING_CF, like `RawMassaDB::write_changes`
+    fn write_changes(
+        &mut self,
+        changes: BTreeMap<Key, Option<Value>>,
+        versioning_changes: BTreeMap<Key, Option<Value>>,
+        previous_values: &DBPreviousValues,
+        change_id: Option<Slot>,
+        reset_history: bool,
+    ) -> Result<(), MassaDBError> {
+        self.check_change_id(change_id)?;
+
+        let mut current_xor_hash = self.get_xof_db_hash();
+        {
+            let mut columns = self.columns.write();
+            for (key, value) in changes.iter() {
+                let prev_value = match previous_values.get(key) {
+                    Some(prev_value) => prev_value.clone(),
+                    None => columns.state.get(key).cloned(),
+                };
+                if let Some(prev_value) = prev_value {
+                    current_xor_hash ^=
+                        HashXof::compute_from_tuple(&[key.as_slice(), prev_value.as_slice()]);
+                }
+                if let Some(value) = value {
+                    current_xor_hash ^=
+                        HashXof::compute_from_tuple(&[key.as_slice(), value.as_slice()]);
+                }
+            }
+            apply_changes(&mut columns.state, &changes);
+            apply_changes(&mut columns.versioning, &versioning_changes);
+        }
+        self.write_metadata(change_id, current_xor_hash);
+
+        self.record_changes_in_history(changes, versioning_changes, reset_history);
+        Ok(())
+    }
+
+    /// Deletes all the keys starting with `prefix` in the `handle_str` column family
+    fn delete_prefix_keys(
+        &mut self,
+        prefix: &[u8],
+        handle_str: &str,
+        change_id: Option<Slot>,
+    ) -> Result<(), MassaDBError> {
+        self.check_change_id(change_id)?;
+
+        let mut current_xor_hash = self.get_xof_db_hash();
+        let mut deleted = BTreeMap::new();
+        {
+            let mut columns = self.columns.write();
+            let cf = columns.cf_mut(handle_str);
+            let keys: Vec<Key> = cf
+                .range::<[u8], _>((Included(prefix), Unbounded))
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in keys {
+                let value = cf.remove(&key).expect(CRUD_ERROR);
+                // only the state column family is part of the hash
+                if handle_str == STATE_CF {
+                    current_xor_hash ^=
+                        HashXof::compute_from_tuple(&[key.as_slice(), value.as_slice()]);
+                }
+                deleted.insert(key, None);
+            }
+        }
+        self.write_metadata(change_id, current_xor_hash);
+
+        match handle_str {
+            STATE_CF => self.record_changes_in_history(deleted, BTreeMap::new(), false),
+            VERSIONING_CF => self.record_changes_in_history(BTreeMap::new(), deleted, false),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Fails if `change_id` is before the current one
+    fn check_change_id(&self, change_id: Option<Slot>) -> Result<(), MassaDBError> {
+        match change_id {
+            Some(change_id) if change_id < self.get_change_id().expect(CHANGE_ID_DESER_ERROR) => {
+                Err(MassaDBError::InvalidChangeID(String::from(
+                    "change_id should monotonically increase after every write",
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the change_id, if any, and the state hash in METADATA_CF
+    fn write_metadata(&self, change_id: Option<Slot>, state_hash: HashXof<HASH_XOF_SIZE_BYTES>) {
+        let mut columns = self.columns.write();
+        if let Some(change_id) = change_id {
+            columns
+                .metadata
+                .insert(CHANGE_ID_KEY.to_vec(), self.serialize_change_id(&change_id));
+        }
+        columns
+            .metadata
+            .insert(STATE_HASH_KEY.to_vec(), state_hash.0.to_vec());
+        self.write_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn serialize_change_id(&self, change_id: &Slot) -> Vec<u8> {
+        let mut change_id_bytes = Vec::new();
+        self.change_id_serializer
+            .serialize(change_id, &mut change_id_bytes)
+            .expect(CHANGE_ID_SER_ERROR);
+        change_id_bytes
+    }
+
+    /// Records the changes written at the current change_id in the change history, then prunes it
+    fn record_changes_in_history(
+        &mut self,
+        changes: BTreeMap<Key, Option<Value>>,
+        versioning_changes: BTreeMap<Key, Option<Value>>,
+        reset_history: bool,
+    ) {
+        let change_id = self.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+
+        self.change_history_bytes = insert_in_history(
+            &mut self.change_history,
+            self.change_history_bytes,
+            change_id,
+            changes,
+        );
+        self.change_history_versioning_bytes = insert_in_history(
+            &mut self.change_history_versioning,
+            self.change_history_versioning_bytes,
+            change_id,
+            versioning_changes,
+        );
+
+        if reset_history {
+            self.change_history.clear();
+            self.change_history_bytes = 0;
+        }
+
+        self.change_history_bytes = prune_history(
+            &mut self.change_history,
+            self.change_history_bytes,
+            self.config.max_history_length,
+            self.config.max_history_bytes,
+        );
+        self.change_history_versioning_bytes = prune_history(
+            &mut self.change_history_versioning,
+            self.change_history_versioning_bytes,
+            self.config.max_history_length,
+            self.config.max_history_bytes,
+        );
+    }
+
+    /// Gets a batch of `handle_cf` entries and of changes to stream to a bootstrap client,
+    /// like `RawMassaDB::get_batch_to_stream`, restricted to the keys of `key_range`
+    fn batch_to_stream(
+        &self,
+        handle_cf: &str,
+        change_history: &BTreeMap<Slot, BTreeMap<Key, Option<Value>>>,
+        key_range: (&[u8], Option<&[u8]>),
+        last_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError> {
+        let current_change_id = self.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+        let max_bytes = self.config.max_stream_batch_bytes.unwrap_or(usize::MAX);
+        let mut batch_change_id = current_change_id;
+        let mut batch_bytes = 0usize;
+        let (range_start, range_end) = key_range;
+        let in_range = |key: &[u8]| range_end.map_or(true, |end| key < end);
+        let bound_key_for_changes = match last_step {
+            StreamingStep::Ongoing(max_key) => Included(max_key.clone()),
+            _ => Unbounded,
+        };
+
+        let updates_on_previous_elements = match (last_step, last_change_id) {
+            (StreamingStep::Started, _) => BTreeMap::new(),
+            (_, Some(last_change_id)) => match last_change_id.cmp(&current_change_id) {
+                std::cmp::Ordering::Greater => {
+                    return Err(MassaDBError::TimeError(String::from(
+                        "we don't have this change yet on this node (it's in the future for us)",
+                    )));
+                }
+                std::cmp::Ordering::Equal => BTreeMap::new(),
+                std::cmp::Ordering::Less => {
+                    let mut cursor = change_history.range((Included(&last_change_id), Unbounded));
+                    if cursor.next().is_none() {
+                        return Err(MassaDBError::TimeError(String::from(
+                            "all our changes are strictly after last_change_id, we can't be sure we did not miss any",
+                        )));
+                    }
+                    let mut updates = BTreeMap::new();
+                    if let Some((cursor_change_id, _)) = cursor.next() {
+                        let mut last_sent_change_id = None;
+                        for (change_id, changes) in
+                            change_history.range((Included(cursor_change_id), Unbounded))
+                        {
+                            let changes: Vec<_> = changes
+                                .range((
+                                    Included(range_start.to_vec()),
+                                    bound_key_for_changes.clone(),
+                                ))
+                                .filter(|(k, _)| in_range(k))
+                                .collect();
+                            let changes_bytes: usize =
+                                changes.iter().map(|(k, v)| entry_size(k, v)).sum();
+                            if let Some(last_sent_change_id) = last_sent_change_id {
+                                if batch_bytes.saturating_add(changes_bytes) > max_bytes {
+                                    batch_change_id = last_sent_change_id;
+                                    break;
+                                }
+                            }
+                            batch_bytes = batch_bytes.saturating_add(changes_bytes);
+                            updates
+                                .extend(changes.into_iter().map(|(k, v)| (k.clone(), v.clone())));
+                            last_sent_change_id = Some(*change_id);
+                        }
+                    }
+                    updates
+                }
+            },
+            _ => {
+                return Err(MassaDBError::TimeError(String::from(
+                    "State streaming was ongoing or finished, but no last_change_id was provided",
+                )));
+            }
+        };
+
+        let mut new_elements = BTreeMap::new();
+        if !last_step.finished() {
+            let start = match last_step {
+                StreamingStep::Ongoing(max_key) => Excluded(max_key.clone()),
+                _ => Included(range_start.to_vec()),
+            };
+            let columns = self.columns.read();
+            for (key, value) in columns.cf(handle_cf).range((start, Unbounded)) {
+                if !in_range(key) {
+                    break;
+                }
+                let element_bytes = key.len() + value.len();
+                if new_elements.len() >= self.config.max_new_elements
+                    || (!new_elements.is_empty()
+                        && batch_bytes.saturating_add(element_bytes) > max_bytes)
+                {
+                    break;
+                }
+                batch_bytes = batch_bytes.saturating_add(element_bytes);
+                new_elements.insert(key.clone(), value.clone());
+            }
+        }
+
+        Ok(StreamBatch {
+            new_elements,
+            updates_on_previous_elements,
+            change_id: batch_change_id,
+        })
+    }
+}
+
+impl MassaDBController for MemoryMassaDB {
+    /// Keeps a copy of the column families, for the given slot
+    fn backup_db(&self, slot: Slot) -> PathBuf {
+        let mut backups = self.backups.lock();
+        if let Some(max_age) = self.config.backup_retention.max_age {
+            backups.retain(|_, (created, _)| created.elapsed() <= max_age);
+        }
+        if let Some(max_backups) = self.config.backup_retention.max_backups {
+            while backups.len() >= max_backups {
+                if backups.pop_first().is_none() {
+                    break;
+                }
+            }
+        }
+        backups.insert(slot, (Instant::now(), self.columns.read().clone()));
+        backup_path(&self.config.path, slot)
+    }
+
+    /// Lists the backups of the DB, sorted by slot
+    fn list_backups(&self) -> Vec<(Slot, PathBuf)> {
+        self.backups
+            .lock()
+            .keys()
+            .map(|slot| (*slot, backup_path(&self.config.path, *slot)))
+            .collect()
+    }
+
+    /// Replaces the content of the DB with the backup made at the given slot
+    fn restore_from_backup(&mut self, slot: Slot) -> Result<(), MassaDBError> {
+        let columns = match self.backups.lock().get(&slot) {
+            Some((_, columns)) => columns.clone(),
+            None => {
+                return Err(MassaDBError::BackupError(format!(
+                    "no backup found for slot {}",
+                    slot
+                )))
+            }
+        };
+        *self.columns.write() = columns;
+        self.change_history.clear();
+        self.change_history_versioning.clear();
+        self.change_history_bytes = 0;
+        self.change_history_versioning_bytes = 0;
+        Ok(())
+    }
+
+    /// Get the current change_id attached to the database.
+    fn get_change_id(&self) -> Result<Slot, ModelsError> {
+        let columns = self.columns.read();
+        let Some(change_id_bytes) = columns.metadata.get(CHANGE_ID_KEY.as_slice()) else {
+            return Err(ModelsError::BufferError(String::from(
+                "Could not recover change_id in database",
+            )));
+        };
+        let (_rest, change_id) = self
+            .change_id_deserializer
+            .deserialize::<DeserializeError>(change_id_bytes)
+            .expect(CHANGE_ID_DESER_ERROR);
+        Ok(change_id)
+    }
+
+    /// Set the initial change_id
+    fn set_initial_change_id(&self, change_id: Slot) {
+        let change_id_bytes = self.serialize_change_id(&change_id);
+        self.columns
+            .write()
+            .metadata
+            .insert(CHANGE_ID_KEY.to_vec(), change_id_bytes);
+        self.write_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Writes the batch to the DB
+    fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>) {
+        self.write_changes(
+            batch,
+            versioning_batch,
+            &DBPreviousValues::new(),
+            change_id,
+            false,
+        )
+        .expect(CRUD_ERROR);
+    }
+
+    /// Writes the batch to the DB, using the provided previous values instead of reading them
+    fn write_batch_with_previous_values(
+        &mut self,
+        batch: DBBatch,
+        versioning_batch: DBBatch,
+        previous_values: &DBPreviousValues,
+        change_id: Option<Slot>,
+    ) {
+        self.write_changes(batch, versioning_batch, previous_values, change_id, false)
+            .expect(CRUD_ERROR);
+    }
+
+    /// Writes the changes staged in a transaction, atomically
+    fn commit_transaction(
+        &mut self,
+        transaction: MassaDBTransaction,
+        change_id: Option<Slot>,
+    ) -> Result<(), MassaDBError> {
+        let (changes, versioning_changes, previous_values) = transaction.into_changes();
+        self.write_changes(
+            changes,
+            versioning_changes,
+            &previous_values,
+            change_id,
+            false,
+        )
+    }
+
+    /// Utility function to put / update a key & value in the batch
+    fn put_or_update_entry_value(&self, batch: &mut DBBatch, key: Vec<u8>, value: &[u8]) {
+        batch.insert(key, Some(value.to_vec()));
+    }
+
+    /// Utility function to delete a key & value in the batch
+    fn delete_key(&self, batch: &mut DBBatch, key: Vec<u8>) {
+        batch.insert(key, None);
+    }
+
+    /// Utility function to delete all keys in a prefix
+    fn delete_prefix(&mut self, prefix: &str, handle_str: &str, change_id: Option<Slot>) {
+        self.delete_prefix_keys(prefix.as_bytes(), handle_str, change_id)
+            .expect(CRUD_ERROR);
+    }
+
+    /// Reset the database, and attach it to the given slot.
+    fn reset(&mut self, slot: Slot) {
+        self.set_initial_change_id(slot);
+        self.change_history.clear();
+        self.change_history_bytes = 0;
+    }
+
+    fn get_cf(&self, handle_cf: &str, key: Key) -> Result<Option<Value>, MassaDBError> {
+        self.read_count.fetch_add(1, Ordering::Relaxed);
+        Ok(self.columns.read().cf(handle_cf).get(&key).cloned())
+    }
+
+    fn multi_get_cf(&self, query: Vec<(&str, Key)>) -> Vec<Result<Option<Value>, MassaDBError>> {
+        self.read_count
+            .fetch_add(query.len() as u64, Ordering::Relaxed);
+        let columns = self.columns.read();
+        query
+            .into_iter()
+            .map(|(handle_cf, key)| Ok(columns.cf(handle_cf).get(&key).cloned()))
+            .collect()
+    }
+
+    /// Iterates over a snapshot of the column family, taken when the iterator is created
+    fn iterator_cf(
+        &self,
+        handle_cf: &str,
+        mode: MassaIteratorMode,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        let columns = self.columns.read();
+        let cf = columns.cf(handle_cf);
+        let entries: Vec<(Key, Value)> = match mode {
+            MassaIteratorMode::Start => cf.iter().map(clone_entry).collect(),
+            MassaIteratorMode::End => cf.iter().rev().map(clone_entry).collect(),
+            MassaIteratorMode::From(key, MassaDirection::Forward) => cf
+                .range::<[u8], _>((Included(key), Unbounded))
+                .map(clone_entry)
+                .collect(),
+            MassaIteratorMode::From(key, MassaDirection::Reverse) => cf
+                .range::<[u8], _>((Unbounded, Included(key)))
+                .rev()
+                .map(clone_entry)
+                .collect(),
+        };
+        Box::new(entries.into_iter())
+    }
+
+    /// Iterates from `prefix` to the end of the column family, like RocksDB without a prefix
+    /// extractor: callers stop at the first key not starting with `prefix`.
+    fn prefix_iterator_cf(
+        &self,
+        handle_cf: &str,
+        prefix: &[u8],
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        self.iterator_cf(
+            handle_cf,
+            MassaIteratorMode::From(prefix, MassaDirection::Forward),
+        )
+    }
+
+    fn range_iterator_cf(
+        &self,
+        handle_cf: &str,
+        start: &[u8],
+        end: Option<&[u8]>,
+        direction: MassaDirection,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        // an empty range makes `BTreeMap::range` panic
+        if end.map_or(false, |end| end <= start) {
+            return Box::new(std::iter::empty());
+        }
+        let columns = self.columns.read();
+        let range = columns
+            .cf(handle_cf)
+            .range::<[u8], _>((Included(start), end.map_or(Unbounded, Excluded)));
+        let entries: Vec<(Key, Value)> = match direction {
+            MassaDirection::Forward => range.map(clone_entry).collect(),
+            MassaDirection::Reverse => range.rev().map(clone_entry).collect(),
+        };
+        Box::new(entries.into_iter())
+    }
+
+    /// Get the current extended state hash of the database
+    fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES> {
+        self.columns
+            .read()
+            .metadata
+            .get(STATE_HASH_KEY.as_slice())
+            .map(|state_hash_bytes| {
+                HashXof(
+                    state_hash_bytes
+                        .as_slice()
+                        .try_into()
+                        .expect(STATE_HASH_ERROR),
+                )
+            })
+            .unwrap_or(HashXof(*STATE_HASH_INITIAL_BYTES))
+    }
+
+    /// Recomputes the state hash and compares it to the stored one
+    fn verify_integrity(
+        &self,
+        is_key_value_valid: &dyn Fn(&[u8], &[u8]) -> bool,
+    ) -> Result<MassaDBIntegrityReport, MassaDBError> {
+        let mut computed_hash = HashXof(*STATE_HASH_INITIAL_BYTES);
+        let mut invalid_key_count = 0u64;
+        let mut invalid_keys = Vec::new();
+        let columns = self.columns.read();
+        for (key, value) in columns.state.iter() {
+            computed_hash ^= HashXof::compute_from_tuple(&[key.as_slice(), value.as_slice()]);
+            if !is_key_value_valid(key, value) {
+                invalid_key_count += 1;
+                if invalid_keys.len() < MAX_REPORTED_INVALID_KEYS {
+                    invalid_keys.push(key.clone());
+                }
+            }
+        }
+        drop(columns);
+
+        Ok(MassaDBIntegrityReport {
+            change_id: self.get_change_id().ok(),
+            key_count: self.columns.read().state.len() as u64,
+            stored_hash: self.get_xof_db_hash(),
+            computed_hash,
+            invalid_key_count,
+            invalid_keys,
+        })
+    }
+
+    /// Nothing to flush
+    fn flush(&self) -> Result<(), MassaDBError> {
+        Ok(())
+    }
+
+    /// A `MemoryMassaDB` is never a secondary instance
+    fn catch_up_with_primary(&self) -> Result<(), MassaDBError> {
+        Err(MassaDBError::RocksDBError(String::from(
+            "an in-memory database is not a secondary instance",
+        )))
+    }
+
+    /// Writes a batch to a column family local to this node, without updating the state hash
+    fn write_local_batch(&self, handle_cf: &str, batch: DBBatch) -> Result<(), MassaDBError> {
+        if !LOCAL_CFS.contains(&handle_cf) {
+            return Err(MassaDBError::RocksDBError(format!(
+                "column family {} is not local to the node",
+                handle_cf
+            )));
+        }
+        self.write_count.fetch_add(1, Ordering::Relaxed);
+        apply_changes(self.columns.write().cf_mut(handle_cf), &batch);
+        Ok(())
+    }
+
+    /// A `MemoryMassaDB` has nothing to compact
+    fn trigger_compaction(&self, handle_cf: &str) -> Result<(), MassaDBError> {
+        match handle_cf {
+            STATE_CF | METADATA_CF | VERSIONING_CF => Ok(()),
+            cf if LOCAL_CFS.contains(&cf) => Ok(()),
+            _ => Err(MassaDBError::RocksDBError(format!(
+                "unknown column family: {}",
+                handle_cf
+            ))),
+        }
+    }
+
+    /// Get the size of the keys and values of each column family, in bytes
+    fn get_cf_sizes(&self) -> BTreeMap<String, u64> {
+        let columns = self.columns.read();
+        [STATE_CF, METADATA_CF, VERSIONING_CF]
+            .iter()
+            .map(|cf| {
+                let size = columns
+                    .cf(cf)
+                    .iter()
+                    .map(|(k, v)| (k.len() + v.len()) as u64)
+                    .sum::<u64>();
+                (cf.to_string(), size)
+            })
+            .collect()
+    }
+
+    /// Nothing is stored on disk
+    fn get_available_disk_space(&self) -> Option<u64> {
+        None
+    }
+
+    /// Get the operation counts and the change history statistics.
+    /// There are no SST files nor compactions.
+    fn get_metrics(&self) -> MassaDBMetrics {
+        MassaDBMetrics {
+            cf_sst_sizes: [STATE_CF, METADATA_CF, VERSIONING_CF]
+                .iter()
+                .map(|cf| (cf.to_string(), 0))
+                .collect(),
+            write_count: self.write_count.load(Ordering::Relaxed),
+            read_count: self.read_count.load(Ordering::Relaxed),
+            change_history_length: self.change_history.len(),
+            change_history_bytes: self.change_history_bytes,
+            change_history_versioning_length: self.change_history_versioning.len(),
+            change_history_versioning_bytes: self.change_history_versioning_bytes,
+            ..Default::default()
+        }
+    }
+
+    /// The archival mode is not supported
+    fn get_at_slot(&self, _key: &[u8], _slot: Slot) -> Result<Option<Value>, MassaDBError> {
+        Err(archival_unsupported())
+    }
+
+    /// The archival mode is not supported
+    fn iterate_at_slot(
+        &self,
+        _prefix: &[u8],
+        _slot: Slot,
+    ) -> Result<Box<dyn Iterator<Item = (Key, Value)> + '_>, MassaDBError> {
+        Err(archival_unsupported())
+    }
+
+    /// The archival mode is not supported
+    fn get_archive_horizon(&self) -> Option<Slot> {
+        None
+    }
+
+    /// Get the oldest slot from which changes can still be streamed to a bootstrap client
+    fn get_change_history_horizon(&self) -> Option<Slot> {
+        let state_horizon = self.change_history.first_key_value()?.0;
+        let versioning_horizon = self.change_history_versioning.first_key_value()?.0;
+        Some(*state_horizon.max(versioning_horizon))
+    }
+
+    /// Write a stream_batch of database entries received from a bootstrap server
+    fn write_batch_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<Slot>,
+        stream_changes_versioning: StreamBatch<Slot>,
+    ) -> Result<(StreamingStep<Key>, StreamingStep<Key>), MassaDBError> {
+        let new_cursor = match stream_changes.new_elements.last_key_value() {
+            Some((k, _)) => StreamingStep::Ongoing(k.clone()),
+            None => StreamingStep::Finished(None),
+        };
+        let new_cursor_versioning = match stream_changes_versioning.new_elements.last_key_value() {
+            Some((k, _)) => StreamingStep::Ongoing(k.clone()),
+            None => StreamingStep::Finished(None),
+        };
+
+        let mut changes = stream_changes.updates_on_previous_elements;
+        changes.extend(
+            stream_changes
+                .new_elements
+                .into_iter()
+                .map(|(k, v)| (k, Some(v))),
+        );
+        let mut versioning_changes = stream_changes_versioning.updates_on_previous_elements;
+        versioning_changes.extend(
+            stream_changes_versioning
+                .new_elements
+                .into_iter()
+                .map(|(k, v)| (k, Some(v))),
+        );
+
+        self.write_changes(
+            changes,
+            versioning_changes,
+            &DBPreviousValues::new(),
+            Some(stream_changes.change_id),
+            true,
+        )?;
+
+        Ok((new_cursor, new_cursor_versioning))
+    }
+
+    /// Write a stream_batch of a stripe of the state received from a bootstrap server
+    fn write_stripe_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<Slot>,
+    ) -> Result<StreamingStep<Key>, MassaDBError> {
+        let new_cursor = match stream_changes.new_elements.last_key_value() {
+            Some((k, _)) => StreamingStep::Ongoing(k.clone()),
+            None => StreamingStep::Finished(None),
+        };
+
+        let mut changes = stream_changes.updates_on_previous_elements;
+        changes.extend(
+            stream_changes
+                .new_elements
+                .into_iter()
+                .map(|(k, v)| (k, Some(v))),
+        );
+
+        self.write_changes(
+            changes,
+            BTreeMap::new(),
+            &DBPreviousValues::new(),
+            None,
+            true,
+        )?;
+
+        Ok(new_cursor)
+    }
+
+    /// Used for bootstrap servers (get a new batch of data from STATE_CF to stream to the client)
+    fn get_batch_to_stream(
+        &self,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError> {
+        self.batch_to_stream(
+            STATE_CF,
+            &self.change_history,
+            (&[], None),
+            last_state_step,
+            last_change_id,
+        )
+    }
+
+    /// Used for bootstrap servers streaming a stripe of the state
+    fn get_stripe_batch_to_stream(
+        &self,
+        range_start: &[u8],
+        range_end: Option<&[u8]>,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError> {
+        if let StreamingStep::Ongoing(max_key) = last_state_step {
+            if max_key.as_slice() < range_start
+                || range_end.map_or(false, |end| max_key.as_slice() >= end)
+            {
+                return Err(MassaDBError::StreamingError(String::from(
+                    "the stripe cursor is out of the stripe range",
+                )));
+            }
+        }
+        self.batch_to_stream(
+            STATE_CF,
+            &self.change_history,
+            (range_start, range_end),
+            last_state_step,
+            last_change_id,
+        )
+    }
+
+    /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
+    fn get_versioning_batch_to_stream(
+        &self,
+        last_versioning_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError> {
+        self.batch_to_stream(
+            VERSIONING_CF,
+            &self.change_history_versioning,
+            (&[], None),
+            last_versioning_step,
+            last_change_id,
+        )
+    }
+}
+
+fn archival_unsupported() -> MassaDBError {
+    MassaDBError::ArchiveError(String::from(
+        "the archival mode is not supported by the in-memory database",
+    ))
+}
+
+/// Path that a backup made at `slot` would have on disk
+fn backup_path(db_path: &std::path::Path, slot: Slot) -> PathBuf {
+    db_path.join(format!("backup_{}_{}", slot.period, slot.thread))
+}
+
+fn clone_entry((key, value): (&Key, &Value)) -> (Key, Value) {
+    (key.clone(), value.clone())
+}
+
+/// Applies the puts (`Some`) and deletions (`None`) of `changes` to a column family
+fn apply_changes(cf: &mut BTreeMap<Key, Value>, changes: &BTreeMap<Key, Option<Value>>) {
+    for (key, value) in changes {
+        match value {
+            Some(value) => {
+                cf.insert(key.clone(), value.clone());
+            }
+            None => {
+                cf.remove(key);
+            }
+        }
+    }
+}
+
+/// Size in bytes of the keys and values of a set of changes
+fn changes_size(changes: &BTreeMap<Key, Option<Value>>) -> usize {
+    changes
+        .iter()
+        .map(|(key, value)| entry_size(key, value))
+        .sum()
+}
+
+/// Size in bytes of a key and of its value, if any
+fn entry_size(key: &[u8], value: &Option<Value>) -> usize {
+    key.len() + value.as_ref().map_or(0, |value| value.len())
+}
+
+/// Merges changes in the entry of `change_id`, returning the new size of the history
+fn insert_in_history(
+    history: &mut BTreeMap<Slot, BTreeMap<Key, Option<Value>>>,
+    history_bytes: usize,
+    change_id: Slot,
+    changes: BTreeMap<Key, Option<Value>>,
+) -> usize {
+    let entry = history.entry(change_id).or_default();
+    let previous_size = changes_size(entry);
+    entry.extend(changes);
+    history_bytes - previous_size + changes_size(entry)
+}
+
+/// Removes the oldest entries of a history, always keeping the latest one.
+/// Returns the new size of the history in bytes.
+fn prune_history(
+    history: &mut BTreeMap<Slot, BTreeMap<Key, Option<Value>>>,
+    mut history_bytes: usize,
+    max_length: usize,
+    max_bytes: Option<usize>,
+) -> usize {
+    while history.len() > max_length
+        || (history.len() > 1 && max_bytes.map_or(false, |max_bytes| history_bytes > max_bytes))
+    {
+        match history.pop_first() {
+            Some((_, changes)) => history_bytes -= changes_size(&changes),
+            None => break,
+        }
+    }
+    history_bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MassaDBBackupRetention, ShareableMassaDBController};
+    use std::sync::Arc;
+
+    fn test_db() -> MemoryMassaDB {
+        MemoryMassaDB::new(MassaDBConfig {
+            path: PathBuf::from("memory_db"),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 2,
+            max_stream_batch_bytes: None,
+            thread_count: 32,
+            tuning: Default::default(),
+            backup_retention: MassaDBBackupRetention {
+                max_backups: Some(2),
+                max_age: None,
+            },
+            archival: Default::default(),
+        })
+    }
+
+    #[test]
+    fn test_memory_db_hash_and_change_id() {
+        let mut db = test_db();
+        assert_eq!(db.get_change_id().unwrap(), Slot::new(0, 0));
+        assert_eq!(db.get_xof_db_hash(), HashXof(*STATE_HASH_INITIAL_BYTES));
+
+        db.write_batch(
+            DBBatch::from([(vec![1], Some(vec![10])), (vec![2], Some(vec![20]))]),
+            DBBatch::from([(vec![3], Some(vec![30]))]),
+            Some(Slot::new(1, 0)),
+        );
+        db.write_batch(
+            DBBatch::from([(vec![2], None)]),
+            DBBatch::new(),
+            Some(Slot::new(2, 0)),
+        );
+        assert_eq!(db.get_change_id().unwrap(), Slot::new(2, 0));
+        // only STATE_CF is hashed
+        assert_eq!(
+            db.get_xof_db_hash(),
+            HashXof(*STATE_HASH_INITIAL_BYTES)
+                ^ HashXof::compute_from_tuple(&[[1u8].as_slice(), [10u8].as_slice()])
+        );
+        assert!(db.verify_integrity(&|_, _| true).unwrap().is_valid());
+        assert_eq!(db.get_cf(VERSIONING_CF, vec![3]).unwrap(), Some(vec![30]));
+
+        db.delete_prefix("\u{1}", STATE_CF, Some(Slot::new(3, 0)));
+        assert_eq!(db.get_xof_db_hash(), HashXof(*STATE_HASH_INITIAL_BYTES));
+        assert_eq!(db.get_change_history_horizon(), Some(Slot::new(1, 0)));
+    }
+
+    #[test]
+    fn test_memory_db_iterators() {
+        let mut db = test_db();
+        db.write_batch(
+            DBBatch::from([
+                (b"a1".to_vec(), Some(vec![1])),
+                (b"b1".to_vec(), Some(vec![2])),
+                (b"b2".to_vec(), Some(vec![3])),
+                (b"c1".to_vec(), Some(vec![4])),
+            ]),
+            DBBatch::new(),
+            Some(Slot::new(1, 0)),
+        );
+        fn keys(iter: impl Iterator<Item = (Key, Value)>) -> Vec<Key> {
+            iter.map(|(k, _)| k).collect()
+        }
+        assert_eq!(
+            keys(db.iterator_cf(STATE_CF, MassaIteratorMode::End)),
+            vec![
+                b"c1".to_vec(),
+                b"b2".to_vec(),
+                b"b1".to_vec(),
+                b"a1".to_vec()
+            ]
+        );
+        assert_eq!(
+            keys(db.iterator_cf(
+                STATE_CF,
+                MassaIteratorMode::From(b"b2", MassaDirection::Reverse)
+            )),
+            vec![b"b2".to_vec(), b"b1".to_vec(), b"a1".to_vec()]
+        );
+        assert_eq!(
+            keys(
+                db.prefix_iterator_cf(STATE_CF, b"b")
+                    .take_while(|(k, _)| k.starts_with(b"b"))
+            ),
+            vec![b"b1".to_vec(), b"b2".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_memory_db_stream() {
+        let mut db = test_db();
+        db.write_batch(
+            DBBatch::from([
+                (vec![1], Some(vec![10])),
+                (vec![2], Some(vec![20])),
+                (vec![3], Some(vec![30])),
+            ]),
+            DBBatch::new(),
+            Some(Slot::new(1, 0)),
+        );
+
+        let db: ShareableMassaDBController = Arc::new(RwLock::new(Box::new(db)));
+        let client: ShareableMassaDBController = Arc::new(RwLock::new(Box::new(test_db())));
+
+        // first batch: max_new_elements entries
+        let batch = db
+            .read()
+            .get_batch_to_stream(&StreamingStep::Started, None)
+            .unwrap();
+        assert_eq!(batch.new_elements.len(), 2);
+        let versioning_batch = db
+            .read()
+            .get_versioning_batch_to_stream(&StreamingStep::Started, None)
+            .unwrap();
+        let (cursor, _) = client
+            .write()
+            .write_batch_bootstrap_client(batch, versioning_batch)
+            .unwrap();
+        assert_eq!(cursor, StreamingStep::Ongoing(vec![2]));
+
+        // a streamed key changes
+        db.write().write_batch(
+            DBBatch::from([(vec![1], Some(vec![11]))]),
+            DBBatch::new(),
+            Some(Slot::new(2, 0)),
+        );
+
+        let batch = db
+            .read()
+            .get_batch_to_stream(&cursor, Some(Slot::new(1, 0)))
+            .unwrap();
+        assert_eq!(batch.new_elements, BTreeMap::from([(vec![3], vec![30])]));
+        assert_eq!(
+            batch.updates_on_previous_elements,
+            BTreeMap::from([(vec![1], Some(vec![11]))])
+        );
+        let versioning_batch = db
+            .read()
+            .get_versioning_batch_to_stream(&StreamingStep::Finished(None), Some(Slot::new(1, 0)))
+            .unwrap();
+        client
+            .write()
+            .write_batch_bootstrap_client(batch, versioning_batch)
+            .unwrap();
+
+        assert_eq!(client.read().get_xof_db_hash(), db.read().get_xof_db_hash());
+        assert_eq!(
+            client.read().get_change_id().unwrap(),
+            db.read().get_change_id().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_memory_db_backups() {
+        let mut db = test_db();
+        for period in 1..=3 {
+            db.write_batch(
+                DBBatch::from([(vec![1], Some(vec![period as u8]))]),
+                DBBatch::new(),
+                Some(Slot::new(period, 0)),
+            );
+            db.backup_db(Slot::new(period, 0));
+        }
+        // at most 2 backups are kept
+        let backups: Vec<Slot> = db.list_backups().into_iter().map(|(s, _)| s).collect();
+        assert_eq!(backups, vec![Slot::new(2, 0), Slot::new(3, 0)]);
+
+        db.restore_from_backup(Slot::new(2, 0)).unwrap();
+        assert_eq!(db.get_change_id().unwrap(), Slot::new(2, 0));
+        assert_eq!(db.get_cf(STATE_CF, vec![1]).unwrap(), Some(vec![2]));
+        assert!(db.get_change_history_horizon().is_none());
+        assert!(db.restore_from_backup(Slot::new(1, 0)).is_err());
+    }
+}
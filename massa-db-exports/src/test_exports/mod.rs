@@ -0,0 +1,14 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module exposes useful tooling for testing.
+//! It is only compiled and exported by the crate if the "testing" feature is enabled.
+//!
+//! # Architecture
+//!
+//! ## `memory_db.rs`
+//! Provides `MemoryMassaDB`, a `MassaDBController` keeping its column families in memory,
+//! so that tests do not need a temporary directory nor a RocksDB instance.
+
+mod memory_db;
+
+pub use memory_db::*;
@@ -0,0 +1,131 @@
+use crate::{
+    DBBatch, DBPreviousValues, Key, MassaDBController, MassaDBError, Value, CF_ERROR, STATE_CF,
+    VERSIONING_CF,
+};
+
+/// Changes staged across STATE_CF and VERSIONING_CF, written atomically by
+/// `MassaDBController::commit_transaction`.
+///
+/// Nothing is written to the database before the commit: aborting a transaction only drops it.
+/// Savepoints allow undoing the changes staged since a given point, e.g. those of a failed step.
+#[derive(Debug, Clone, Default)]
+pub struct MassaDBTransaction {
+    /// staged changes to STATE_CF
+    state_changes: DBBatch,
+    /// staged changes to VERSIONING_CF
+    versioning_changes: DBBatch,
+    /// known values of STATE_CF keys before the transaction, saving reads on commit
+    previous_values: DBPreviousValues,
+    /// staged changes at each savepoint, the latest savepoint last
+    savepoints: Vec<(DBBatch, DBBatch)>,
+}
+
+impl MassaDBTransaction {
+    /// Creates an empty transaction
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn changes(&self, handle_cf: &str) -> &DBBatch {
+        match handle_cf {
+            STATE_CF => &self.state_changes,
+            VERSIONING_CF => &self.versioning_changes,
+            _ => panic!("{}", CF_ERROR),
+        }
+    }
+
+    fn changes_mut(&mut self, handle_cf: &str) -> &mut DBBatch {
+        match handle_cf {
+            STATE_CF => &mut self.state_changes,
+            VERSIONING_CF => &mut self.versioning_changes,
+            _ => panic!("{}", CF_ERROR),
+        }
+    }
+
+    /// Stages a put of `key` in the `handle_cf` column family.
+    /// Only STATE_CF and VERSIONING_CF can be written.
+    pub fn put(&mut self, handle_cf: &str, key: Key, value: Value) {
+        self.changes_mut(handle_cf).insert(key, Some(value));
+    }
+
+    /// Stages a deletion of `key` in the `handle_cf` column family.
+    /// Only STATE_CF and VERSIONING_CF can be written.
+    pub fn delete(&mut self, handle_cf: &str, key: Key) {
+        self.changes_mut(handle_cf).insert(key, None);
+    }
+
+    /// Stages all the changes of `batch` in the `handle_cf` column family
+    pub fn extend(&mut self, handle_cf: &str, batch: DBBatch) {
+        self.changes_mut(handle_cf).extend(batch);
+    }
+
+    /// Provides the value of a STATE_CF key before the transaction, `None` if it is absent.
+    /// The previous values are needed to update the state hash on commit:
+    /// the missing ones are read from the database.
+    pub fn set_previous_value(&mut self, key: Key, value: Option<Value>) {
+        self.previous_values.insert(key, value);
+    }
+
+    /// Returns the change staged for `key` in the `handle_cf` column family, if any:
+    /// `Some(None)` for a staged deletion.
+    pub fn get_staged(&self, handle_cf: &str, key: &[u8]) -> Option<&Option<Value>> {
+        self.changes(handle_cf).get(key)
+    }
+
+    /// Reads `key` in the `handle_cf` column family as it will be after the commit:
+    /// the staged change if any, the value in `db` otherwise.
+    pub fn get_cf(
+        &self,
+        db: &dyn MassaDBController,
+        handle_cf: &str,
+        key: Key,
+    ) -> Result<Option<Value>, MassaDBError> {
+        match self.get_staged(handle_cf, &key) {
+            Some(value) => Ok(value.clone()),
+            None => db.get_cf(handle_cf, key),
+        }
+    }
+
+    /// Returns true if no change is staged
+    pub fn is_empty(&self) -> bool {
+        self.state_changes.is_empty() && self.versioning_changes.is_empty()
+    }
+
+    /// Marks the current staged changes, to go back to them with `rollback_to_savepoint`
+    pub fn set_savepoint(&mut self) {
+        self.savepoints
+            .push((self.state_changes.clone(), self.versioning_changes.clone()));
+    }
+
+    /// Undoes the changes staged since the latest savepoint, and removes it.
+    /// Fails if there is no savepoint.
+    pub fn rollback_to_savepoint(&mut self) -> Result<(), MassaDBError> {
+        let (state_changes, versioning_changes) = self.savepoints.pop().ok_or_else(|| {
+            MassaDBError::TransactionError(String::from("no savepoint to roll back to"))
+        })?;
+        self.state_changes = state_changes;
+        self.versioning_changes = versioning_changes;
+        Ok(())
+    }
+
+    /// Removes the latest savepoint, keeping the changes staged since then.
+    /// Fails if there is no savepoint.
+    pub fn release_savepoint(&mut self) -> Result<(), MassaDBError> {
+        self.savepoints
+            .pop()
+            .map(|_| ())
+            .ok_or_else(|| MassaDBError::TransactionError(String::from("no savepoint to release")))
+    }
+
+    /// Drops the transaction without writing anything
+    pub fn abort(self) {}
+
+    /// Returns the staged changes to STATE_CF and VERSIONING_CF, and the known previous values
+    pub fn into_changes(self) -> (DBBatch, DBBatch, DBPreviousValues) {
+        (
+            self.state_changes,
+            self.versioning_changes,
+            self.previous_values,
+        )
+    }
+}
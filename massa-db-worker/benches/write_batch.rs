@@ -0,0 +1,73 @@
+#[cfg(feature = "benchmarking")]
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+#[cfg(feature = "benchmarking")]
+fn criterion_benchmark(c: &mut Criterion) {
+    use massa_db_exports::{DBBatch, DBPreviousValues, MassaDBConfig, MassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_models::slot::Slot;
+    use tempfile::TempDir;
+
+    const KEY_COUNT: u32 = 10_000;
+
+    /// Opens a database holding `KEY_COUNT` keys, and returns it with a batch overwriting
+    /// all of them and the previous values of the overwritten keys
+    fn prepare_bench_function() -> (TempDir, MassaDB, DBBatch, DBPreviousValues) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: 32,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        });
+        let initial_batch: DBBatch = (0..KEY_COUNT)
+            .map(|i| (i.to_be_bytes().to_vec(), Some(vec![0; 64])))
+            .collect();
+        db.write_batch(initial_batch.clone(), DBBatch::new(), Some(Slot::new(1, 0)));
+        let batch = (0..KEY_COUNT)
+            .map(|i| (i.to_be_bytes().to_vec(), Some(vec![1; 64])))
+            .collect();
+        (temp_dir, db, batch, initial_batch)
+    }
+
+    c.bench_function("write batch reading previous values", |b| {
+        b.iter_batched(
+            prepare_bench_function,
+            |(_temp_dir, mut db, batch, _)| {
+                db.write_batch(black_box(batch), DBBatch::new(), Some(Slot::new(2, 0)))
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    c.bench_function("write batch with known previous values", |b| {
+        b.iter_batched(
+            prepare_bench_function,
+            |(_temp_dir, mut db, batch, previous_values)| {
+                db.write_batch_with_previous_values(
+                    black_box(batch),
+                    DBBatch::new(),
+                    &previous_values,
+                    Some(Slot::new(2, 0)),
+                )
+            },
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+#[cfg(feature = "benchmarking")]
+criterion_group!(benches, criterion_benchmark);
+
+#[cfg(feature = "benchmarking")]
+criterion_main!(benches);
+
+#[cfg(not(feature = "benchmarking"))]
+fn main() {
+    println!("Please use the `--features benchmarking` flag to run this benchmark.");
+}
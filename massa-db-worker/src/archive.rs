@@ -0,0 +1,305 @@
+//! Archival mode of a `MassaDB`: the changes made to STATE_CF at each change_id are kept in
+//! HISTORY_CF, keyed by (change_id, key), with the value of the key before and after the change.
+//!
+//! The value of a key at the end of a past change_id is the value it had before its first change
+//! made after that change_id, or its current value if it was not changed since.
+//! The archive horizon is the oldest change_id after which every change is archived.
+
+use crate::massa_db::end_prefix;
+use crate::RawMassaDB;
+use massa_db_exports::{
+    Key, MassaDBError, Value, ARCHIVE_DESER_ERROR, ARCHIVE_HORIZON_KEY, CF_ERROR,
+    CHANGE_ID_DESER_ERROR, CHANGE_ID_SER_ERROR, CRUD_ERROR, HISTORY_CF, METADATA_CF, STATE_CF,
+};
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use rocksdb::{Direction, IteratorMode, WriteBatch};
+use std::collections::BTreeMap;
+
+/// Change of a key archived at a change_id: its values before and after the change
+pub(crate) type ArchivedChange = (Option<Value>, Option<Value>);
+
+/// ChangeIDs that can index the archived state changes
+pub trait ArchiveChangeID: Sized {
+    /// Size of the binary representation of a ChangeID in the archive
+    const ARCHIVE_KEY_SIZE: usize;
+
+    /// Fixed-size binary representation of the ChangeID, sorted like the ChangeIDs
+    fn to_archive_key(&self) -> Vec<u8>;
+
+    /// Newest ChangeID that can be forgotten when the latest one is `self` and
+    /// `max_periods` periods of changes are kept, if any
+    fn archive_pruning_bound(&self, max_periods: u64) -> Option<Self>;
+}
+
+impl ArchiveChangeID for Slot {
+    const ARCHIVE_KEY_SIZE: usize = SLOT_KEY_SIZE;
+
+    fn to_archive_key(&self) -> Vec<u8> {
+        self.to_bytes_key().to_vec()
+    }
+
+    fn archive_pruning_bound(&self, max_periods: u64) -> Option<Self> {
+        self.period
+            .checked_sub(max_periods)
+            .map(|period| Slot::new(period, 0))
+    }
+}
+
+/// Serializes an archived change: a flag byte telling which values are present,
+/// the length of the previous value as a big-endian u64, then the values.
+fn encode_archived_change(prev_value: Option<&[u8]>, new_value: Option<&[u8]>) -> Vec<u8> {
+    let prev = prev_value.unwrap_or_default();
+    let new = new_value.unwrap_or_default();
+    let flags = u8::from(prev_value.is_some()) | (u8::from(new_value.is_some()) << 1);
+    let mut bytes = Vec::with_capacity(9 + prev.len() + new.len());
+    bytes.push(flags);
+    bytes.extend((prev.len() as u64).to_be_bytes());
+    bytes.extend(prev);
+    bytes.extend(new);
+    bytes
+}
+
+/// Deserializes an archived change written by `encode_archived_change`
+fn decode_archived_change(bytes: &[u8]) -> ArchivedChange {
+    let (flags, rest) = bytes.split_first().expect(ARCHIVE_DESER_ERROR);
+    let (prev_len, rest) = rest.split_at(8);
+    let prev_len = u64::from_be_bytes(prev_len.try_into().expect(ARCHIVE_DESER_ERROR)) as usize;
+    let (prev, new) = rest.split_at(prev_len);
+    (
+        (flags & 1 != 0).then(|| prev.to_vec()),
+        (flags & 2 != 0).then(|| new.to_vec()),
+    )
+}
+
+impl<ChangeID, ChangeIDSerializer, ChangeIDDeserializer>
+    RawMassaDB<ChangeID, ChangeIDSerializer, ChangeIDDeserializer>
+where
+    ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug + ArchiveChangeID,
+    ChangeIDSerializer: Serializer<ChangeID>,
+    ChangeIDDeserializer: Deserializer<ChangeID>,
+{
+    /// Get the archive horizon, if the archival mode is or was enabled
+    pub fn read_archive_horizon(&self) -> Option<ChangeID> {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let horizon_bytes = self
+            .db
+            .get_pinned_cf(handle_metadata, ARCHIVE_HORIZON_KEY)
+            .expect(CRUD_ERROR)?;
+        let (_rest, horizon) = self
+            .change_id_deserializer
+            .deserialize::<DeserializeError>(&horizon_bytes)
+            .expect(CHANGE_ID_DESER_ERROR);
+        Some(horizon)
+    }
+
+    /// Set the archive horizon in the batch
+    fn set_archive_horizon_to_batch(&self, batch: &mut WriteBatch, horizon: &ChangeID) {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let mut horizon_bytes = Vec::new();
+        self.change_id_serializer
+            .serialize(horizon, &mut horizon_bytes)
+            .expect(CHANGE_ID_SER_ERROR);
+        batch.put_cf(handle_metadata, ARCHIVE_HORIZON_KEY, &horizon_bytes);
+    }
+
+    /// Adds to the batch the removal of all the changes archived up to `bound` included
+    fn delete_archive_up_to_batch(&self, batch: &mut WriteBatch, bound: Option<&ChangeID>) {
+        let handle_history = self.db.cf_handle(HISTORY_CF).expect(CF_ERROR);
+        let end = match bound {
+            Some(bound) => end_prefix(&bound.to_archive_key()),
+            None => None,
+        }
+        .unwrap_or_else(|| vec![u8::MAX; ChangeID::ARCHIVE_KEY_SIZE + 1]);
+        batch.delete_range_cf(handle_history, Vec::<u8>::new(), end);
+    }
+
+    /// Starts archiving from the current change_id if the archival mode was just enabled,
+    /// or removes the archive if it was disabled. To be called when the database is opened.
+    pub(crate) fn init_archive(&self) -> Result<(), MassaDBError> {
+        let mut batch = WriteBatch::default();
+        match (
+            self.config.archival.enabled,
+            self.read_archive_horizon().is_some(),
+        ) {
+            (true, false) => {
+                let change_id = self.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+                self.set_archive_horizon_to_batch(&mut batch, &change_id);
+            }
+            // the changes made while the archival mode was disabled are missing
+            (false, true) => {
+                let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+                batch.delete_cf(handle_metadata, ARCHIVE_HORIZON_KEY);
+                self.delete_archive_up_to_batch(&mut batch, None);
+            }
+            _ => return Ok(()),
+        }
+        self.write_to_db(batch)
+            .map_err(|e| MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e)))
+    }
+
+    /// Adds to the batch the archival of changes made at `change_id`, then the pruning of the
+    /// archive according to the configuration.
+    pub(crate) fn archive_changes_to_batch(
+        &self,
+        batch: &mut WriteBatch,
+        change_id: &ChangeID,
+        changes: BTreeMap<Key, ArchivedChange>,
+    ) {
+        let handle_history = self.db.cf_handle(HISTORY_CF).expect(CF_ERROR);
+        let change_id_key = change_id.to_archive_key();
+        for (key, (prev_value, new_value)) in changes {
+            let history_key = [change_id_key.as_slice(), &key].concat();
+            // a key written several times at the same change_id keeps its first previous value
+            let prev_value = match self
+                .db
+                .get_pinned_cf(handle_history, &history_key)
+                .expect(CRUD_ERROR)
+            {
+                Some(archived) => decode_archived_change(&archived).0,
+                None => prev_value,
+            };
+            batch.put_cf(
+                handle_history,
+                history_key,
+                encode_archived_change(prev_value.as_deref(), new_value.as_deref()),
+            );
+        }
+
+        let Some(bound) = self
+            .config
+            .archival
+            .max_periods
+            .and_then(|max_periods| change_id.archive_pruning_bound(max_periods))
+        else {
+            return;
+        };
+        if self
+            .read_archive_horizon()
+            .map_or(true, |horizon| bound > horizon)
+        {
+            self.delete_archive_up_to_batch(batch, Some(&bound));
+            self.set_archive_horizon_to_batch(batch, &bound);
+        }
+    }
+
+    /// Adds to the batch the removal of the archive, which now starts at `change_id`.
+    /// Used when the state is replaced instead of changed, e.g. by a reset or a bootstrap.
+    pub(crate) fn restart_archive_to_batch(&self, batch: &mut WriteBatch, change_id: &ChangeID) {
+        self.delete_archive_up_to_batch(batch, None);
+        self.set_archive_horizon_to_batch(batch, change_id);
+    }
+
+    /// Fails if the state at the end of `change_id` can't be read from the archive
+    fn check_archived(&self, change_id: &ChangeID) -> Result<(), MassaDBError> {
+        if !self.config.archival.enabled {
+            return Err(MassaDBError::ArchiveError(String::from(
+                "the archival mode is disabled",
+            )));
+        }
+        let horizon = self.read_archive_horizon().ok_or_else(|| {
+            MassaDBError::ArchiveError(String::from("the archive has not been started"))
+        })?;
+        if *change_id < horizon {
+            return Err(MassaDBError::ArchiveError(format!(
+                "{:?} is before the archive horizon {:?}",
+                change_id, horizon
+            )));
+        }
+        if *change_id > self.get_change_id().expect(CHANGE_ID_DESER_ERROR) {
+            return Err(MassaDBError::ArchiveError(format!(
+                "{:?} is after the current change_id",
+                change_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get the value of a key of STATE_CF at the end of the given change_id
+    pub fn get_at_change_id(
+        &self,
+        key: &[u8],
+        change_id: &ChangeID,
+    ) -> Result<Option<Value>, MassaDBError> {
+        self.check_archived(change_id)?;
+
+        let handle_history = self.db.cf_handle(HISTORY_CF).expect(CF_ERROR);
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let snapshot = self.db.snapshot();
+        if let Some(start) = end_prefix(&change_id.to_archive_key()) {
+            for (history_key, archived) in snapshot
+                .iterator_cf(
+                    handle_history,
+                    IteratorMode::From(&start, Direction::Forward),
+                )
+                .flatten()
+            {
+                if &history_key[ChangeID::ARCHIVE_KEY_SIZE..] == key {
+                    return Ok(decode_archived_change(&archived).0);
+                }
+            }
+        }
+        snapshot
+            .get_cf(handle_state, key)
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
+    }
+
+    /// Get the entries of STATE_CF whose key starts with `prefix` at the end of the given change_id
+    pub fn get_prefix_at_change_id(
+        &self,
+        prefix: &[u8],
+        change_id: &ChangeID,
+    ) -> Result<BTreeMap<Key, Value>, MassaDBError> {
+        self.check_archived(change_id)?;
+
+        let handle_history = self.db.cf_handle(HISTORY_CF).expect(CF_ERROR);
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let snapshot = self.db.snapshot();
+        let mut entries: BTreeMap<Key, Value> = snapshot
+            .iterator_cf(handle_state, IteratorMode::From(prefix, Direction::Forward))
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.to_vec(), value.to_vec()))
+            .collect();
+
+        // only the first change of each key after change_id matters
+        let mut restored = std::collections::BTreeSet::new();
+        if let Some(start) = end_prefix(&change_id.to_archive_key()) {
+            for (history_key, archived) in snapshot
+                .iterator_cf(
+                    handle_history,
+                    IteratorMode::From(&start, Direction::Forward),
+                )
+                .flatten()
+            {
+                let key = &history_key[ChangeID::ARCHIVE_KEY_SIZE..];
+                if !key.starts_with(prefix) || !restored.insert(key.to_vec()) {
+                    continue;
+                }
+                match decode_archived_change(&archived).0 {
+                    Some(prev_value) => entries.insert(key.to_vec(), prev_value),
+                    None => entries.remove(key),
+                };
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_archived_change_encoding() {
+        for change in [
+            (None, None),
+            (Some(vec![]), None),
+            (None, Some(vec![1, 2])),
+            (Some(vec![3]), Some(vec![4, 5, 6])),
+        ] {
+            let bytes = encode_archived_change(change.0.as_deref(), change.1.as_deref());
+            assert_eq!(decode_archived_change(&bytes), change);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Manual compaction of the column families of a `MassaDB`, run in a background thread
+
+use massa_db_exports::{MassaDBError, CF_ERROR};
+use rocksdb::{CompactOptions, DB};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+
+/// Tracks the manual compaction running in the background, if any
+#[derive(Default)]
+pub(crate) struct ManualCompaction {
+    running: Arc<AtomicBool>,
+}
+
+impl ManualCompaction {
+    /// Starts compacting the whole column family `cf` of `db` in a background thread.
+    /// Fails if the column family does not exist or if a manual compaction is already running.
+    pub(crate) fn trigger(&self, db: Arc<DB>, cf: &str) -> Result<(), MassaDBError> {
+        if db.cf_handle(cf).is_none() {
+            return Err(MassaDBError::RocksDBError(format!(
+                "unknown column family: {}",
+                cf
+            )));
+        }
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err(MassaDBError::RocksDBError(String::from(
+                "a manual compaction is already running",
+            )));
+        }
+
+        let running = self.running.clone();
+        let cf = cf.to_string();
+        std::thread::Builder::new()
+            .name("db_manual_compaction".to_string())
+            .spawn(move || {
+                info!("manual compaction of the {} column family started", cf);
+                let start = Instant::now();
+                let mut opts = CompactOptions::default();
+                // let the automatic compactions run meanwhile
+                opts.set_exclusive_manual_compaction(false);
+                db.compact_range_cf_opt(
+                    db.cf_handle(&cf).expect(CF_ERROR),
+                    None::<&[u8]>,
+                    None::<&[u8]>,
+                    &opts,
+                );
+                info!(
+                    "manual compaction of the {} column family done in {:?}",
+                    cf,
+                    start.elapsed()
+                );
+                // release the DB before reporting the end of the compaction, so that it can be restored
+                drop(db);
+                running.store(false, Ordering::SeqCst);
+            })
+            .map_err(|e| {
+                self.running.store(false, Ordering::SeqCst);
+                MassaDBError::RocksDBError(format!("failed to spawn the compaction thread: {}", e))
+            })?;
+        Ok(())
+    }
+
+    /// Whether a manual compaction is running
+    pub(crate) fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
@@ -12,10 +12,11 @@
 //! RocksDB stores keys and values, which are arbitrarily-sized byte streams (aka vec<u8> or &[u8]).
 //! It supports both point lookups and range scans.
 //!
-//! For MassaDB, we use 3 rocksdb column:
+//! For MassaDB, we use 4 rocksdb column:
 //! * state: all data for (async pool, executed ops/de, ledger ...) and used to compute the db hash
 //! * versioning: partial MIP store data see Versioning doc section: "MipStore and Final state hash"
 //! * metadata: final state hash + slot
+//! * history: changes made to 'state' at each slot, only written in archival mode (see archive.rs)
 //!
 //! Note that data is stored with a prefix (see constants.rs in massa-db-exports).
 //! For instance, a ledger update, will be stored (in column: 'state') as:
@@ -59,7 +60,39 @@
 //!    the updates (by querying only the cache)
 //! 3- Even after this process is finished (and as other things like consensus data are streamed),
 //!    we can send the updates
+//!
+//! # Secondary instances
+//!
+//! Heavy read queries (iterators, prefix scans) can be served from a read-only RocksDB secondary
+//! instance (see `MassaDB::new_secondary`), which has its own lock and does not contend with the
+//! writes made by execution on the primary instance. A secondary instance only sees the writes made
+//! after its opening once it caught up with the primary: either on demand or periodically
+//! (see `start_catch_up`).
+//!
+//! # Durability
+//!
+//! By default, each batch is written to the RocksDB write-ahead log (WAL) but the WAL is only synced
+//! to disk by the OS. `MassaDBTuning::wal_sync` lets operators sync it at each batch, periodically,
+//! or disable it, trading durability for throughput. In all cases a crashed database recovers to the
+//! consistent state of a change_id: the state, its hash and the change_id are written in the same batch.
+//! See the crash-recovery tests in `tests/crash_recovery.rs`.
+//!
+//! # Compaction
+//!
+//! RocksDB compacts its files in the background. Heavy slot writes can cause compaction bursts
+//! stalling the other disk accesses: `MassaDBTuning::compaction_rate_limit` and
+//! `MassaDBTuning::max_background_jobs` pace them. Operators can also compact a whole column family
+//! on demand, e.g. during low-traffic periods (see `trigger_compaction`).
 
+mod archive;
+mod compaction;
 mod massa_db;
+mod secondary;
+mod wal;
+
+#[cfg(test)]
+mod tests;
 
+pub use crate::archive::*;
 pub use crate::massa_db::*;
+pub use crate::secondary::*;
@@ -1,29 +1,37 @@
+use crate::compaction::ManualCompaction;
+use crate::wal::{start_wal_sync, wal_write_opts, WalSyncHandle};
+use crate::ArchiveChangeID;
 use massa_db_exports::{
-    DBBatch, Key, MassaDBConfig, MassaDBController, MassaDBError, MassaDirection,
-    MassaIteratorMode, StreamBatch, Value, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY,
-    CHANGE_ID_SER_ERROR, CRUD_ERROR, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR,
+    DBBatch, DBPreviousValues, Key, MassaDBCompression, MassaDBConfig, MassaDBController,
+    MassaDBError, MassaDBIntegrityReport, MassaDBMetrics, MassaDBTransaction, MassaDBWalSync,
+    MassaDirection, MassaIteratorMode, StreamBatch, Value, ALL_CFS, CF_ERROR,
+    CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY, CHANGE_ID_SER_ERROR, CRUD_ERROR, HISTORY_CF, LOCAL_CFS,
+    MAX_REPORTED_INVALID_KEYS, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR,
     STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
 };
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{
-    config::MAX_BACKUPS_TO_KEEP,
+    datastore::get_prefix_bounds,
     error::ModelsError,
     slot::{Slot, SlotDeserializer, SlotSerializer},
     streaming_step::StreamingStep,
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
-use parking_lot::Mutex;
 use rocksdb::{
-    checkpoint::Checkpoint, ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch,
-    DB,
+    checkpoint::Checkpoint, BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType,
+    Direction, IteratorMode, Options, ReadOptions, WriteBatch, WriteOptions, DB,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{
+    borrow::Cow,
     collections::BTreeMap,
     format,
     ops::Bound::{self, Excluded, Included, Unbounded},
+    sync::atomic::{AtomicU64, Ordering},
     sync::Arc,
+    time::{Duration, Instant},
 };
+use tracing::warn;
 
 /// Wrapped RocksDB database
 ///
@@ -49,12 +57,50 @@ pub struct RawMassaDB<
     pub change_history: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
     /// same as change_history but for versioning
     pub change_history_versioning: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
+    /// Size in bytes of the keys and values retained in change_history
+    pub change_history_bytes: usize,
+    /// Size in bytes of the keys and values retained in change_history_versioning
+    pub change_history_versioning_bytes: usize,
     /// A serializer for the ChangeID type
     pub change_id_serializer: ChangeIDSerializer,
     /// A deserializer for the ChangeID type
     pub change_id_deserializer: ChangeIDDeserializer,
-    /// The current RocksDB batch of the database, in a Mutex to share it
-    pub current_batch: Arc<Mutex<WriteBatch>>,
+    /// Count and duration of the batch writes
+    write_stats: OperationStats,
+    /// Count and duration of the point reads
+    read_stats: OperationStats,
+    /// Options of the batch writes, according to the WAL sync policy
+    write_opts: WriteOptions,
+    /// Thread syncing the WAL, if the WAL sync policy is periodic
+    wal_sync: WalSyncHandle,
+    /// Manual compaction running in the background, if any
+    manual_compaction: ManualCompaction,
+}
+
+/// Number of operations of a kind and total time spent in them
+#[derive(Debug, Default)]
+struct OperationStats {
+    count: AtomicU64,
+    micros: AtomicU64,
+}
+
+impl OperationStats {
+    /// Records `count` operations that took `elapsed` overall
+    fn record(&self, count: u64, elapsed: Duration) {
+        self.count.fetch_add(count, Ordering::Relaxed);
+        self.micros.fetch_add(
+            elapsed.as_micros().try_into().unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Returns the number of operations and the total time spent in them
+    fn get(&self) -> (u64, Duration) {
+        (
+            self.count.load(Ordering::Relaxed),
+            Duration::from_micros(self.micros.load(Ordering::Relaxed)),
+        )
+    }
 }
 
 impl<ChangeID, ChangeIDSerializer, ChangeIDDeserializer> std::fmt::Debug
@@ -76,7 +122,7 @@ where
 impl<ChangeID, ChangeIDSerializer, ChangeIDDeserializer>
     RawMassaDB<ChangeID, ChangeIDSerializer, ChangeIDDeserializer>
 where
-    ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug,
+    ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug + ArchiveChangeID,
     ChangeIDSerializer: Serializer<ChangeID>,
     ChangeIDDeserializer: Deserializer<ChangeID>,
 {
@@ -88,108 +134,42 @@ where
         last_state_step: &StreamingStep<Vec<u8>>,
         last_change_id: Option<ChangeID>,
     ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
-        let bound_key_for_changes = match &last_state_step {
-            StreamingStep::Ongoing(max_key) => Included(max_key.clone()),
-            _ => Unbounded,
-        };
-
-        // Updates == "everything that changed since the last change_id streamed, up to a certain key".
-        // This definition also applies to keys that were not in the DB beforehand.
-        let updates_on_previous_elements = match (&last_state_step, last_change_id) {
-            (StreamingStep::Started, _) => {
-                // Stream No changes, new elements from start
-                BTreeMap::new()
-            }
-            (_, Some(last_change_id)) => {
-                // Stream the changes depending on the previously computed bound
-
-                match last_change_id.cmp(&self.get_change_id().expect(CHANGE_ID_DESER_ERROR)) {
-                    std::cmp::Ordering::Greater => {
-                        return Err(MassaDBError::TimeError(String::from(
-                            "we don't have this change yet on this node (it's in the future for us)",
-                        )));
-                    }
-                    std::cmp::Ordering::Equal => {
-                        BTreeMap::new() // no new updates
-                    }
-                    std::cmp::Ordering::Less => {
-                        // We should send all the new updates since last_change_id
-
-                        let mut cursor = self
-                            .change_history
-                            .range((Bound::Included(&last_change_id), Bound::Unbounded));
-
-                        if cursor.next().is_none() {
-                            return Err(MassaDBError::TimeError(String::from(
-                                "all our changes are strictly after last_change_id, we can't be sure we did not miss any",
-                            )));
-                        }
+        self.get_cf_batch_to_stream(
+            STATE_CF,
+            &self.change_history,
+            (&[], None),
+            last_state_step,
+            last_change_id,
+        )
+    }
 
-                        match cursor.next() {
-                            Some((cursor_change_id, _)) => {
-                                // We have to send all the updates since cursor_change_id
-                                // TODO_PR: check if / how we want to limit the number of updates we send. It may be needed but tricky to implement.
-                                let mut updates: BTreeMap<Vec<u8>, Option<Vec<u8>>> =
-                                    BTreeMap::new();
-                                let iter = self
-                                    .change_history
-                                    .range((Bound::Included(cursor_change_id), Bound::Unbounded));
-                                for (_change_id, changes) in iter {
-                                    updates.extend(
-                                        changes
-                                            .range((
-                                                Bound::<Vec<u8>>::Unbounded,
-                                                bound_key_for_changes.clone(),
-                                            ))
-                                            .map(|(k, v)| (k.clone(), v.clone())),
-                                    );
-                                }
-                                updates
-                            }
-                            None => BTreeMap::new(), // no new updates
-                        }
-                    }
-                }
-            }
-            _ => {
-                // last_change_id is None, but StreamingStep is either Ongoing or Finished
-                return Err(MassaDBError::TimeError(String::from(
-                    "State streaming was ongoing or finished, but no last_change_id was provided",
+    /// Used for bootstrap servers streaming a stripe of the state (get a new batch of data from STATE_CF,
+    /// restricted to the keys from `range_start` included to `range_end` excluded, to stream to the client)
+    ///
+    /// Returns a StreamBatch<ChangeID>
+    pub fn get_stripe_batch_to_stream(
+        &self,
+        range_start: &[u8],
+        range_end: Option<&[u8]>,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<ChangeID>,
+    ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
+        if let StreamingStep::Ongoing(max_key) = last_state_step {
+            if max_key.as_slice() < range_start
+                || range_end.map_or(false, |end| max_key.as_slice() >= end)
+            {
+                return Err(MassaDBError::StreamingError(String::from(
+                    "the stripe cursor is out of the stripe range",
                 )));
             }
-        };
-
-        let mut new_elements = BTreeMap::new();
-
-        if !last_state_step.finished() {
-            let handle = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
-
-            // Creates an iterator from the next element after the last if defined, otherwise initialize it at the first key.
-            let db_iterator = match &last_state_step {
-                StreamingStep::Ongoing(max_key) => {
-                    let mut iter = self
-                        .db
-                        .iterator_cf(handle, IteratorMode::From(max_key, Direction::Forward));
-                    iter.next();
-                    iter
-                }
-                _ => self.db.iterator_cf(handle, IteratorMode::Start),
-            };
-
-            for (serialized_key, serialized_value) in db_iterator.flatten() {
-                if new_elements.len() < self.config.max_new_elements {
-                    new_elements.insert(serialized_key.to_vec(), serialized_value.to_vec());
-                } else {
-                    break;
-                }
-            }
         }
-
-        Ok(StreamBatch {
-            new_elements,
-            updates_on_previous_elements,
-            change_id: self.get_change_id().expect(CHANGE_ID_DESER_ERROR),
-        })
+        self.get_cf_batch_to_stream(
+            STATE_CF,
+            &self.change_history,
+            (range_start, range_end),
+            last_state_step,
+            last_change_id,
+        )
     }
 
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
@@ -200,12 +180,46 @@ where
         last_versioning_step: &StreamingStep<Vec<u8>>,
         last_change_id: Option<ChangeID>,
     ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
-        let bound_key_for_changes = match &last_versioning_step {
+        self.get_cf_batch_to_stream(
+            VERSIONING_CF,
+            &self.change_history_versioning,
+            (&[], None),
+            last_versioning_step,
+            last_change_id,
+        )
+    }
+
+    /// Gets a new batch of data from the `handle_cf` column family, whose changes are kept in
+    /// `change_history`, to stream to a bootstrap client.
+    ///
+    /// The keys and values of the batch are limited to `max_stream_batch_bytes` bytes, the updates first.
+    /// If the updates since `last_change_id` don't fit, only those up to an older change_id are sent
+    /// and the change_id of the batch is that one: the client asks for the next ones with it.
+    /// At least one new element is sent while the column family has not been fully streamed,
+    /// so that the stream always progresses.
+    /// Only the keys of `key_range` (start included, end excluded or unbounded if `None`) are streamed.
+    fn get_cf_batch_to_stream(
+        &self,
+        handle_cf: &str,
+        change_history: &BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
+        key_range: (&[u8], Option<&[u8]>),
+        last_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<ChangeID>,
+    ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
+        let current_change_id = self.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+        let max_bytes = self.config.max_stream_batch_bytes.unwrap_or(usize::MAX);
+        let mut batch_change_id = current_change_id.clone();
+        let mut batch_bytes = 0usize;
+        let (range_start, range_end) = key_range;
+
+        let bound_key_for_changes = match &last_step {
             StreamingStep::Ongoing(max_key) => Included(max_key.clone()),
             _ => Unbounded,
         };
 
-        let updates_on_previous_elements = match (&last_versioning_step, last_change_id) {
+        // Updates == "everything that changed since the last change_id streamed, up to a certain key".
+        // This definition also applies to keys that were not in the DB beforehand.
+        let updates_on_previous_elements = match (&last_step, last_change_id) {
             (StreamingStep::Started, _) => {
                 // Stream No changes, new elements from start
                 BTreeMap::new()
@@ -213,7 +227,7 @@ where
             (_, Some(last_change_id)) => {
                 // Stream the changes depending on the previously computed bound
 
-                match last_change_id.cmp(&self.get_change_id().expect(CHANGE_ID_DESER_ERROR)) {
+                match last_change_id.cmp(&current_change_id) {
                     std::cmp::Ordering::Greater => {
                         return Err(MassaDBError::TimeError(String::from(
                             "we don't have this change yet on this node (it's in the future for us)",
@@ -225,9 +239,8 @@ where
                     std::cmp::Ordering::Less => {
                         // We should send all the new updates since last_change_id
 
-                        let mut cursor = self
-                            .change_history_versioning
-                            .range((Bound::Included(&last_change_id), Unbounded));
+                        let mut cursor =
+                            change_history.range((Bound::Included(&last_change_id), Unbounded));
 
                         if cursor.next().is_none() {
                             return Err(MassaDBError::TimeError(String::from(
@@ -237,22 +250,37 @@ where
 
                         match cursor.next() {
                             Some((cursor_change_id, _)) => {
-                                // We have to send all the updates since cursor_change_id
-                                // TODO_PR: check if / how we want to limit the number of updates we send. It may be needed but tricky to implement.
+                                // We have to send all the updates since cursor_change_id,
+                                // a change_id at a time while they fit in the batch
                                 let mut updates: BTreeMap<Vec<u8>, Option<Vec<u8>>> =
                                     BTreeMap::new();
-                                let iter = self
-                                    .change_history_versioning
+                                let mut last_sent_change_id = None;
+                                let iter = change_history
                                     .range((Bound::Included(cursor_change_id), Bound::Unbounded));
-                                for (_change_id, changes) in iter {
+                                for (change_id, changes) in iter {
+                                    let changes: Vec<_> = changes
+                                        .range((
+                                            Bound::Included(range_start.to_vec()),
+                                            bound_key_for_changes.clone(),
+                                        ))
+                                        .filter(|(k, _)| {
+                                            range_end.map_or(true, |end| k.as_slice() < end)
+                                        })
+                                        .collect();
+                                    let changes_bytes: usize =
+                                        changes.iter().map(|(k, v)| entry_size(k, v)).sum();
+                                    if let Some(last_sent_change_id) = &last_sent_change_id {
+                                        if batch_bytes.saturating_add(changes_bytes) > max_bytes {
+                                            // the next change_ids are sent in the next batches
+                                            batch_change_id = ChangeID::clone(last_sent_change_id);
+                                            break;
+                                        }
+                                    }
+                                    batch_bytes = batch_bytes.saturating_add(changes_bytes);
                                     updates.extend(
-                                        changes
-                                            .range((
-                                                Bound::<Vec<u8>>::Unbounded,
-                                                bound_key_for_changes.clone(),
-                                            ))
-                                            .map(|(k, v)| (k.clone(), v.clone())),
+                                        changes.into_iter().map(|(k, v)| (k.clone(), v.clone())),
                                     );
+                                    last_sent_change_id = Some(change_id.clone());
                                 }
                                 updates
                             }
@@ -271,11 +299,11 @@ where
 
         let mut new_elements = BTreeMap::new();
 
-        if !last_versioning_step.finished() {
-            let handle = self.db.cf_handle(VERSIONING_CF).expect(CF_ERROR);
+        if !last_step.finished() {
+            let handle = self.db.cf_handle(handle_cf).expect(CF_ERROR);
 
             // Creates an iterator from the next element after the last if defined, otherwise initialize it at the first key.
-            let db_iterator = match &last_versioning_step {
+            let db_iterator = match &last_step {
                 StreamingStep::Ongoing(max_key) => {
                     let mut iter = self
                         .db
@@ -283,22 +311,31 @@ where
                     iter.next();
                     iter
                 }
-                _ => self.db.iterator_cf(handle, IteratorMode::Start),
+                _ => self
+                    .db
+                    .iterator_cf(handle, IteratorMode::From(range_start, Direction::Forward)),
             };
 
             for (serialized_key, serialized_value) in db_iterator.flatten() {
-                if new_elements.len() < self.config.max_new_elements {
-                    new_elements.insert(serialized_key.to_vec(), serialized_value.to_vec());
-                } else {
+                if range_end.map_or(false, |end| serialized_key.as_ref() >= end) {
+                    break;
+                }
+                let element_bytes = serialized_key.len() + serialized_value.len();
+                if new_elements.len() >= self.config.max_new_elements
+                    || (!new_elements.is_empty()
+                        && batch_bytes.saturating_add(element_bytes) > max_bytes)
+                {
                     break;
                 }
+                batch_bytes = batch_bytes.saturating_add(element_bytes);
+                new_elements.insert(serialized_key.to_vec(), serialized_value.to_vec());
             }
         }
 
         Ok(StreamBatch {
             new_elements,
             updates_on_previous_elements,
-            change_id: self.get_change_id().expect(CHANGE_ID_DESER_ERROR),
+            change_id: batch_change_id,
         })
     }
 
@@ -306,10 +343,13 @@ where
     /// - Bootstrap clients, to write on disk a new received Stream (reset_history: true)
     /// - Normal operations, to write changes associated to a given change_id (reset_history: false)
     ///
+    /// `previous_values` holds the values of the changed keys before the write, when the caller knows them.
+    /// The other previous values are read from the database to update the state hash.
     pub fn write_changes(
         &mut self,
         changes: BTreeMap<Key, Option<Value>>,
         versioning_changes: BTreeMap<Key, Option<Value>>,
+        previous_values: &DBPreviousValues,
         change_id: Option<ChangeID>,
         reset_history: bool,
     ) -> Result<(), MassaDBError> {
@@ -327,29 +367,41 @@ where
 
         let mut current_xor_hash = self.get_xof_db_hash();
 
-        *self.current_batch.lock() = WriteBatch::default();
+        let mut batch = WriteBatch::default();
+        // a bootstrap replaces the state instead of changing it: nothing to archive
+        let archive = self.config.archival.enabled && !reset_history;
+        let mut archived_changes = BTreeMap::new();
 
         for (key, value) in changes.iter() {
             if let Some(value) = value {
-                self.current_batch.lock().put_cf(handle_state, key, value);
+                batch.put_cf(handle_state, key, value);
+            } else {
+                batch.delete_cf(handle_state, key);
+            }
 
-                // Compute the XOR in all cases
-                if let Ok(Some(prev_value)) = self.db.get_cf(handle_state, key) {
-                    let prev_hash =
-                        HashXof::compute_from_tuple(&[key.as_slice(), prev_value.as_slice()]);
-                    current_xor_hash ^= prev_hash;
-                };
+            // Compute the XOR in all cases, reading the previous value only if the caller did not provide it
+            let prev_value = match previous_values.get(key) {
+                Some(prev_value) => prev_value.as_deref().map(Cow::Borrowed),
+                None => self
+                    .db
+                    .get_cf(handle_state, key)
+                    .ok()
+                    .flatten()
+                    .map(Cow::Owned),
+            };
+            if archive {
+                archived_changes.insert(
+                    key.clone(),
+                    (prev_value.as_deref().map(<[u8]>::to_vec), value.clone()),
+                );
+            }
+            if let Some(prev_value) = prev_value {
+                let prev_hash = HashXof::compute_from_tuple(&[key.as_slice(), prev_value.as_ref()]);
+                current_xor_hash ^= prev_hash;
+            }
+            if let Some(value) = value {
                 let new_hash = HashXof::compute_from_tuple(&[key.as_slice(), value.as_slice()]);
                 current_xor_hash ^= new_hash;
-            } else {
-                self.current_batch.lock().delete_cf(handle_state, key);
-
-                // Compute the XOR in all cases
-                if let Ok(Some(prev_value)) = self.db.get_cf(handle_state, key) {
-                    let prev_hash =
-                        HashXof::compute_from_tuple(&[key.as_slice(), prev_value.as_slice()]);
-                    current_xor_hash ^= prev_hash;
-                };
             }
         }
 
@@ -357,72 +409,167 @@ where
         // e.g everything that is not in 'Active' state (so hashes remain compatibles)
         for (key, value) in versioning_changes.iter() {
             if let Some(value) = value {
-                self.current_batch
-                    .lock()
-                    .put_cf(handle_versioning, key, value);
+                batch.put_cf(handle_versioning, key, value);
+            } else {
+                batch.delete_cf(handle_versioning, key);
+            }
+        }
+
+        if self.config.archival.enabled {
+            let archive_change_id = change_id
+                .clone()
+                .unwrap_or_else(|| self.get_change_id().expect(CHANGE_ID_DESER_ERROR));
+            if archive {
+                self.archive_changes_to_batch(&mut batch, &archive_change_id, archived_changes);
             } else {
-                self.current_batch.lock().delete_cf(handle_versioning, key);
+                self.restart_archive_to_batch(&mut batch, &archive_change_id);
             }
         }
 
         if let Some(change_id) = change_id {
-            self.set_change_id_to_batch(change_id);
+            self.set_change_id_to_batch(&mut batch, change_id);
         }
 
         // Update the hash entry
-        self.current_batch
-            .lock()
-            .put_cf(handle_metadata, STATE_HASH_KEY, current_xor_hash.0);
+        batch.put_cf(handle_metadata, STATE_HASH_KEY, current_xor_hash.0);
 
-        {
-            let mut current_batch_guard = self.current_batch.lock();
-            let batch = WriteBatch::from_data(current_batch_guard.data());
-            current_batch_guard.clear();
+        self.write_to_db(batch)
+            .map_err(|e| MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e)))?;
 
-            self.db.write(batch).map_err(|e| {
-                MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e))
-            })?;
+        self.record_changes_in_history(changes, versioning_changes, reset_history);
+
+        Ok(())
+    }
+
+    /// Deletes all the keys starting with `prefix` in the `handle_str` column family,
+    /// using a single range deletion instead of a delete per key.
+    ///
+    /// The deleted entries are still read once, to update the state hash and the change history.
+    pub fn delete_prefix_range(
+        &mut self,
+        prefix: &[u8],
+        handle_str: &str,
+        change_id: Option<ChangeID>,
+    ) -> Result<(), MassaDBError> {
+        if let Some(change_id) = change_id.clone() {
+            if change_id < self.get_change_id().expect(CHANGE_ID_DESER_ERROR) {
+                return Err(MassaDBError::InvalidChangeID(String::from(
+                    "change_id should monotonically increase after every write",
+                )));
+            }
         }
 
-        match self
-            .change_history
-            .entry(self.get_change_id().expect(CHANGE_ID_DESER_ERROR))
+        let handle = self.db.cf_handle(handle_str).expect(CF_ERROR);
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+
+        let mut current_xor_hash = self.get_xof_db_hash();
+        let mut deleted = BTreeMap::new();
+        let archive = self.config.archival.enabled && handle_str == STATE_CF;
+        let mut archived_changes = BTreeMap::new();
+        for (key, value) in self
+            .db
+            .iterator_cf(handle, IteratorMode::From(prefix, Direction::Forward))
+            .flatten()
         {
-            std::collections::btree_map::Entry::Vacant(entry) => {
-                entry.insert(changes);
+            if !key.starts_with(prefix) {
+                break;
             }
-            std::collections::btree_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().extend(changes);
+            // only the state column family is part of the hash
+            if handle_str == STATE_CF {
+                current_xor_hash ^= HashXof::compute_from_tuple(&[key.as_ref(), value.as_ref()]);
             }
+            if archive {
+                archived_changes.insert(key.to_vec(), (Some(value.to_vec()), None));
+            }
+            deleted.insert(key.to_vec(), None);
         }
 
-        match self
-            .change_history_versioning
-            .entry(self.get_change_id().expect(CHANGE_ID_DESER_ERROR))
-        {
-            std::collections::btree_map::Entry::Vacant(entry) => {
-                entry.insert(versioning_changes);
-            }
-            std::collections::btree_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().extend(versioning_changes);
+        let mut batch = WriteBatch::default();
+        match end_prefix(prefix) {
+            Some(end) => batch.delete_range_cf(handle, prefix, end),
+            // no upper bound: the keys of the prefix are deleted one by one
+            None => {
+                for key in deleted.keys() {
+                    batch.delete_cf(handle, key);
+                }
             }
         }
 
-        if reset_history {
-            self.change_history.clear();
+        if archive {
+            let archive_change_id = change_id
+                .clone()
+                .unwrap_or_else(|| self.get_change_id().expect(CHANGE_ID_DESER_ERROR));
+            self.archive_changes_to_batch(&mut batch, &archive_change_id, archived_changes);
         }
 
-        while self.change_history.len() > self.config.max_history_length {
-            self.change_history.pop_first();
+        if let Some(change_id) = change_id {
+            self.set_change_id_to_batch(&mut batch, change_id);
         }
 
-        while self.change_history_versioning.len() > self.config.max_history_length {
-            self.change_history_versioning.pop_first();
+        batch.put_cf(handle_metadata, STATE_HASH_KEY, current_xor_hash.0);
+
+        self.write_to_db(batch)
+            .map_err(|e| MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e)))?;
+
+        match handle_str {
+            STATE_CF => self.record_changes_in_history(deleted, BTreeMap::new(), false),
+            VERSIONING_CF => self.record_changes_in_history(BTreeMap::new(), deleted, false),
+            _ => {}
         }
 
         Ok(())
     }
 
+    /// Records the changes written at the current change_id in the change history,
+    /// then prunes the history to `max_history_length` entries and `max_history_bytes` bytes
+    fn record_changes_in_history(
+        &mut self,
+        changes: BTreeMap<Key, Option<Value>>,
+        versioning_changes: BTreeMap<Key, Option<Value>>,
+        reset_history: bool,
+    ) {
+        let change_id = self.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+
+        self.change_history_bytes = insert_in_history(
+            &mut self.change_history,
+            self.change_history_bytes,
+            change_id.clone(),
+            changes,
+        );
+        self.change_history_versioning_bytes = insert_in_history(
+            &mut self.change_history_versioning,
+            self.change_history_versioning_bytes,
+            change_id,
+            versioning_changes,
+        );
+
+        if reset_history {
+            self.change_history.clear();
+            self.change_history_bytes = 0;
+        }
+
+        self.change_history_bytes = prune_history(
+            &mut self.change_history,
+            self.change_history_bytes,
+            self.config.max_history_length,
+            self.config.max_history_bytes,
+        );
+        self.change_history_versioning_bytes = prune_history(
+            &mut self.change_history_versioning,
+            self.change_history_versioning_bytes,
+            self.config.max_history_length,
+            self.config.max_history_bytes,
+        );
+    }
+
+    /// Returns the oldest change_id from which changes can still be streamed to a bootstrap client,
+    /// or `None` if the change history is empty.
+    pub fn get_change_history_horizon(&self) -> Option<ChangeID> {
+        let state_horizon = self.change_history.first_key_value()?.0;
+        let versioning_horizon = self.change_history_versioning.first_key_value()?.0;
+        Some(state_horizon.max(versioning_horizon).clone())
+    }
+
     /// Get the current change_id attached to the database.
     pub fn get_change_id(&self) -> Result<ChangeID, ModelsError> {
         let db = &self.db;
@@ -444,22 +591,21 @@ where
 
     /// Set the initial change_id. This function should only be called at startup/reset, as it does not batch this set with other changes.
     pub fn set_initial_change_id(&self, change_id: ChangeID) {
-        self.current_batch.lock().clear();
-
-        self.set_change_id_to_batch(change_id);
-
-        let batch;
-        {
-            let mut current_batch_guard = self.current_batch.lock();
-            batch = WriteBatch::from_data(current_batch_guard.data());
-            current_batch_guard.clear();
+        let mut batch = WriteBatch::default();
+        self.set_change_id_to_batch(&mut batch, change_id);
+        self.write_to_db(batch).expect(CRUD_ERROR);
+    }
 
-            self.db.write(batch).expect(CRUD_ERROR);
-        }
+    /// Writes a batch to RocksDB, recording the time it took
+    pub(crate) fn write_to_db(&self, batch: WriteBatch) -> Result<(), rocksdb::Error> {
+        let start = Instant::now();
+        let res = self.db.write_opt(batch, &self.write_opts);
+        self.write_stats.record(1, start.elapsed());
+        res
     }
 
     /// Set the current change_id in the batch
-    pub fn set_change_id_to_batch(&self, change_id: ChangeID) {
+    pub fn set_change_id_to_batch(&self, batch: &mut WriteBatch, change_id: ChangeID) {
         let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
 
         let mut change_id_bytes = Vec::new();
@@ -467,9 +613,7 @@ where
             .serialize(&change_id, &mut change_id_bytes)
             .expect(CHANGE_ID_SER_ERROR);
 
-        self.current_batch
-            .lock()
-            .put_cf(handle_metadata, CHANGE_ID_KEY, &change_id_bytes);
+        batch.put_cf(handle_metadata, CHANGE_ID_KEY, &change_id_bytes);
     }
 
     /// Write a stream_batch of database entries received from a bootstrap server
@@ -512,6 +656,7 @@ where
         self.write_changes(
             changes,
             versioning_changes,
+            &DBPreviousValues::new(),
             Some(stream_changes.change_id),
             true,
         )?;
@@ -519,6 +664,119 @@ where
         Ok((new_cursor, new_cursor_versioning))
     }
 
+    /// Write a stream_batch of a stripe of the state received from a bootstrap server.
+    ///
+    /// The stripes are streamed in parallel at different slots: contrary to `write_batch_bootstrap_client`,
+    /// the change_id of the DB is not set, the client catches up on the changes of all the stripes afterwards.
+    pub fn write_stripe_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<ChangeID>,
+    ) -> Result<StreamingStep<Key>, MassaDBError> {
+        let new_cursor: StreamingStep<Vec<u8>> = match stream_changes.new_elements.last_key_value()
+        {
+            Some((k, _)) => StreamingStep::Ongoing(k.clone()),
+            None => StreamingStep::Finished(None),
+        };
+
+        let mut changes = BTreeMap::new();
+        changes.extend(stream_changes.updates_on_previous_elements);
+        changes.extend(
+            stream_changes
+                .new_elements
+                .into_iter()
+                .map(|(k, v)| (k, Some(v))),
+        );
+
+        self.write_changes(
+            changes,
+            BTreeMap::new(),
+            &DBPreviousValues::new(),
+            None,
+            true,
+        )?;
+
+        Ok(new_cursor)
+    }
+
+    /// Get the size on disk of each column family (SST files and memtables), in bytes
+    pub fn get_cf_sizes(&self) -> BTreeMap<String, u64> {
+        ALL_CFS
+            .iter()
+            .map(|cf| {
+                let handle = self.db.cf_handle(cf).expect(CF_ERROR);
+                let size = [
+                    "rocksdb.total-sst-files-size",
+                    "rocksdb.size-all-mem-tables",
+                ]
+                .iter()
+                .filter_map(|property| {
+                    self.db
+                        .property_int_value_cf(handle, *property)
+                        .ok()
+                        .flatten()
+                })
+                .fold(0u64, |acc, size| acc.saturating_add(size));
+                (cf.to_string(), size)
+            })
+            .collect()
+    }
+
+    /// Get a snapshot of the RocksDB and change history statistics
+    pub fn get_metrics(&self) -> MassaDBMetrics {
+        let mut metrics = MassaDBMetrics::default();
+        for cf in ALL_CFS {
+            let handle = self.db.cf_handle(cf).expect(CF_ERROR);
+            let property = |name: &str| {
+                self.db
+                    .property_int_value_cf(handle, name)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
+            };
+            metrics
+                .cf_sst_sizes
+                .insert(cf.to_string(), property("rocksdb.total-sst-files-size"));
+            metrics.pending_compaction_bytes = metrics
+                .pending_compaction_bytes
+                .saturating_add(property("rocksdb.estimate-pending-compaction-bytes"));
+        }
+        // compactions are run by the background threads shared by all the column families
+        metrics.running_compactions = self
+            .db
+            .property_int_value("rocksdb.num-running-compactions")
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        (metrics.write_count, metrics.write_time) = self.write_stats.get();
+        (metrics.read_count, metrics.read_time) = self.read_stats.get();
+        metrics.change_history_length = self.change_history.len();
+        metrics.change_history_bytes = self.change_history_bytes;
+        metrics.change_history_versioning_length = self.change_history_versioning.len();
+        metrics.change_history_versioning_bytes = self.change_history_versioning_bytes;
+        metrics
+    }
+
+    /// Get the space available to unprivileged users on the disk holding the database, in bytes
+    #[cfg(unix)]
+    pub fn get_available_disk_space(&self) -> Option<u64> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = CString::new(self.config.path.as_os_str().as_bytes()).ok()?;
+        // SAFETY: `path` is a valid NUL-terminated string and `stat` is only read if the call succeeds
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        Some(u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize)))
+    }
+
+    /// Get the space available on the disk holding the database: not supported on this platform
+    #[cfg(not(unix))]
+    pub fn get_available_disk_space(&self) -> Option<u64> {
+        None
+    }
+
     /// Get the current XOF state hash of the database
     pub fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES> {
         self.get_xof_db_hash_opt()
@@ -551,84 +809,397 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         db_opts
     }
 
-    /// Returns a new `MassaDB` instance given a config and RocksDB options
-    fn new_with_options(config: MassaDBConfig, db_opts: Options) -> Result<Self, rocksdb::Error> {
-        let db = DB::open_cf_descriptors(
+    /// Returns the options of a column family, tuned according to the config
+    fn cf_opts(
+        config: &MassaDBConfig,
+        block_cache: Option<&Cache>,
+        compression: Option<MassaDBCompression>,
+    ) -> Options {
+        let tuning = &config.tuning;
+        let mut cf_opts = Options::default();
+        if let Some(write_buffer_size) = tuning.write_buffer_size {
+            cf_opts.set_write_buffer_size(write_buffer_size);
+        }
+        if let Some(compression) = compression {
+            cf_opts.set_compression_type(match compression {
+                MassaDBCompression::None => DBCompressionType::None,
+                MassaDBCompression::Snappy => DBCompressionType::Snappy,
+                MassaDBCompression::Lz4 => DBCompressionType::Lz4,
+                MassaDBCompression::Zstd => DBCompressionType::Zstd,
+            });
+        }
+        if block_cache.is_some() || tuning.bloom_filter_bits_per_key.is_some() {
+            let mut table_opts = BlockBasedOptions::default();
+            if let Some(block_cache) = block_cache {
+                table_opts.set_block_cache(block_cache);
+            }
+            if let Some(bits_per_key) = tuning.bloom_filter_bits_per_key {
+                table_opts.set_bloom_filter(bits_per_key, false);
+            }
+            cf_opts.set_block_based_table_factory(&table_opts);
+        }
+        cf_opts
+    }
+
+    /// Opens the RocksDB instance and its column families, tuned according to the config
+    fn open_db(config: &MassaDBConfig, mut db_opts: Options) -> Result<DB, rocksdb::Error> {
+        let tuning = &config.tuning;
+        if let Some(max_open_files) = tuning.max_open_files {
+            db_opts.set_max_open_files(max_open_files);
+        }
+        if let Some(rate_limit) = tuning.compaction_rate_limit {
+            // refill every 100ms, with the default fairness
+            db_opts.set_ratelimiter(rate_limit, 100_000, 10);
+        }
+        if let Some(max_background_jobs) = tuning.max_background_jobs {
+            db_opts.set_max_background_jobs(max_background_jobs);
+        }
+        if tuning.wal_sync == Some(MassaDBWalSync::Disabled) {
+            // without WAL, the memtables of the column families must be flushed together
+            // for the state, its hash and the change_id to be recovered consistently
+            db_opts.set_atomic_flush(true);
+        }
+        // the block cache is shared by all the column families
+        let block_cache = tuning.block_cache_size.map(Cache::new_lru_cache);
+
+        DB::open_cf_descriptors(
             &db_opts,
             &config.path,
-            vec![
-                ColumnFamilyDescriptor::new(STATE_CF, Options::default()),
-                ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
-                ColumnFamilyDescriptor::new(VERSIONING_CF, Options::default()),
-            ],
-        )?;
+            ALL_CFS.iter().map(|cf| {
+                // the history stores previous state values, so it is compressed like the state
+                let compression = match *cf {
+                    STATE_CF | HISTORY_CF => tuning.state_cf_compression,
+                    METADATA_CF => tuning.metadata_cf_compression,
+                    VERSIONING_CF => tuning.versioning_cf_compression,
+                    _ => tuning.local_cf_compression,
+                };
+                ColumnFamilyDescriptor::new(
+                    *cf,
+                    Self::cf_opts(config, block_cache.as_ref(), compression),
+                )
+            }),
+        )
+    }
 
-        let db = Arc::new(db);
-        let current_batch = Arc::new(Mutex::new(WriteBatch::default()));
+    /// Opens a read-only secondary instance of the `MassaDB` stored at `config.path`,
+    /// keeping its own logs in `secondary_path`.
+    ///
+    /// A secondary instance can be read while the primary instance is written,
+    /// without sharing its lock, and sees the writes made after its opening once it has
+    /// caught up with the primary (see `catch_up_with_primary`). It must not be written.
+    pub fn new_secondary(
+        config: MassaDBConfig,
+        secondary_path: PathBuf,
+    ) -> Result<Self, MassaDBError> {
+        let mut db_opts = Options::default();
+        // required by secondary instances
+        db_opts.set_max_open_files(-1);
+        let db = DB::open_cf_as_secondary(&db_opts, &config.path, secondary_path, ALL_CFS)
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?;
 
         let change_id_deserializer = SlotDeserializer::new(
             (Included(u64::MIN), Included(u64::MAX)),
             (Included(0), Excluded(config.thread_count)),
         );
 
-        let massa_db = Self {
-            db,
+        Ok(Self {
+            db: Arc::new(db),
             config,
             change_history: BTreeMap::new(),
             change_history_versioning: BTreeMap::new(),
+            change_history_bytes: 0,
+            change_history_versioning_bytes: 0,
             change_id_serializer: SlotSerializer::new(),
             change_id_deserializer,
-            current_batch,
-        };
+            write_stats: OperationStats::default(),
+            read_stats: OperationStats::default(),
+            write_opts: WriteOptions::default(),
+            wal_sync: WalSyncHandle::default(),
+            manual_compaction: ManualCompaction::default(),
+        })
+    }
 
-        if massa_db.get_change_id().is_err() {
-            massa_db.set_initial_change_id(Slot {
-                period: 0,
-                thread: 0,
-            });
+    /// Recomputes the state hash from the entries of STATE_CF and compares it to the stored one.
+    /// The entries and the stored hash are read from the same snapshot.
+    pub fn verify_integrity(
+        &self,
+        is_key_value_valid: &dyn Fn(&[u8], &[u8]) -> bool,
+    ) -> Result<MassaDBIntegrityReport, MassaDBError> {
+        let snapshot = self.db.snapshot();
+        let metadata_handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let state_handle = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+
+        let stored_hash = match snapshot
+            .get_cf(metadata_handle, STATE_HASH_KEY)
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?
+        {
+            Some(bytes) => HashXof(bytes.as_slice().try_into().map_err(|_| {
+                MassaDBError::HashError(format!(
+                    "stored state hash has an invalid length: {}",
+                    bytes.len()
+                ))
+            })?),
+            None => HashXof(*STATE_HASH_INITIAL_BYTES),
+        };
+        let change_id = snapshot
+            .get_cf(metadata_handle, CHANGE_ID_KEY)
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))?
+            .map(|bytes| {
+                self.change_id_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .map(|(_rest, change_id)| change_id)
+                    .map_err(|e| MassaDBError::InvalidChangeID(format!("{:?}", e)))
+            })
+            .transpose()?;
+
+        let mut computed_hash = HashXof(*STATE_HASH_INITIAL_BYTES);
+        let mut key_count = 0u64;
+        let mut invalid_key_count = 0u64;
+        let mut invalid_keys = Vec::new();
+        for item in snapshot.iterator_cf(state_handle, IteratorMode::Start) {
+            // an error here means that a block of the column family could not be read
+            let (key, value) = item.map_err(|e| {
+                MassaDBError::RocksDBError(format!(
+                    "could not read the entry following {} valid entries: {:?}",
+                    key_count, e
+                ))
+            })?;
+            computed_hash ^= HashXof::compute_from_tuple(&[key.as_ref(), value.as_ref()]);
+            key_count += 1;
+            if !is_key_value_valid(&key, &value) {
+                invalid_key_count += 1;
+                if invalid_keys.len() < MAX_REPORTED_INVALID_KEYS {
+                    invalid_keys.push(key.to_vec());
+                }
+            }
         }
 
-        Ok(massa_db)
+        Ok(MassaDBIntegrityReport {
+            change_id,
+            key_count,
+            stored_hash,
+            computed_hash,
+            invalid_key_count,
+            invalid_keys,
+        })
     }
-}
 
-impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
+    /// Makes a secondary instance apply the latest changes of its primary instance
+    pub fn catch_up_with_primary(&self) -> Result<(), MassaDBError> {
+        self.db
+            .try_catch_up_with_primary()
+            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
+    }
+
+    /// Writes a batch to a column family local to this node: the state hash, the change history
+    /// and the archive are not updated.
+    pub fn write_local_batch(&self, handle_cf: &str, batch: DBBatch) -> Result<(), MassaDBError> {
+        if !LOCAL_CFS.contains(&handle_cf) {
+            return Err(MassaDBError::RocksDBError(format!(
+                "column family {} is not local to the node",
+                handle_cf
+            )));
+        }
+        let handle = self.db.cf_handle(handle_cf).expect(CF_ERROR);
+        let mut write_batch = WriteBatch::default();
+        for (key, value) in batch.iter() {
+            if let Some(value) = value {
+                write_batch.put_cf(handle, key, value);
+            } else {
+                write_batch.delete_cf(handle, key);
+            }
+        }
+        self.write_to_db(write_batch)
+            .map_err(|e| MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e)))
+    }
+
+    /// Starts compacting the whole column family `handle_cf` in a background thread.
+    /// Only one manual compaction can run at a time, and backups can't be restored meanwhile.
+    pub fn trigger_compaction(&self, handle_cf: &str) -> Result<(), MassaDBError> {
+        self.manual_compaction.trigger(self.db.clone(), handle_cf)
+    }
+
+    /// Whether a manual compaction triggered by `trigger_compaction` is running
+    pub fn is_manual_compaction_running(&self) -> bool {
+        self.manual_compaction.is_running()
+    }
+
+    /// Returns a new `MassaDB` instance given a config and RocksDB options
+    fn new_with_options(config: MassaDBConfig, db_opts: Options) -> Result<Self, rocksdb::Error> {
+        let db = Self::open_db(&config, db_opts)?;
+
+        let db = Arc::new(db);
+        let write_opts = wal_write_opts(config.tuning.wal_sync);
+        let wal_sync = start_wal_sync(db.clone(), config.tuning.wal_sync);
+
+        let change_id_deserializer = SlotDeserializer::new(
+            (Included(u64::MIN), Included(u64::MAX)),
+            (Included(0), Excluded(config.thread_count)),
+        );
+
+        let massa_db = Self {
+            db,
+            config,
+            change_history: BTreeMap::new(),
+            change_history_versioning: BTreeMap::new(),
+            change_history_bytes: 0,
+            change_history_versioning_bytes: 0,
+            change_id_serializer: SlotSerializer::new(),
+            change_id_deserializer,
+            write_stats: OperationStats::default(),
+            read_stats: OperationStats::default(),
+            write_opts,
+            wal_sync,
+            manual_compaction: ManualCompaction::default(),
+        };
+
+        if massa_db.get_change_id().is_err() {
+            massa_db.set_initial_change_id(Slot {
+                period: 0,
+                thread: 0,
+            });
+        }
+        massa_db.init_archive().expect(CRUD_ERROR);
+
+        Ok(massa_db)
+    }
+}
+
+impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
+    /// Lists the backups stored in the DB directory, sorted by slot
+    pub fn list_backups(&self) -> Vec<(Slot, PathBuf)> {
+        list_backups_in(self.db.path())
+    }
+
+    /// Replaces the content of the DB with the backup made at the given slot.
+    ///
+    /// The backup is copied next to the DB directory, along with the other backups,
+    /// then the DB is closed and the two directories are swapped with renames,
+    /// so that an interrupted restore never leaves a partially written DB in place.
+    /// If the restored DB can't be opened, the previous DB is put back in place and reopened.
+    /// Fails while a manual compaction is running, as it keeps the DB open.
+    pub fn restore_from_backup(&mut self, slot: Slot) -> Result<(), MassaDBError> {
+        if self.manual_compaction.is_running() {
+            return Err(MassaDBError::BackupError(String::from(
+                "a manual compaction is running",
+            )));
+        }
+        let backup_path = self
+            .list_backups()
+            .into_iter()
+            .find_map(|(backup_slot, path)| (backup_slot == slot).then_some(path))
+            .ok_or_else(|| MassaDBError::BackupError(format!("no backup at slot {}", slot)))?;
+
+        let db_path = self.config.path.clone();
+        let staging_path = path_with_suffix(&db_path, "restore");
+        let old_path = path_with_suffix(&db_path, "old");
+        let placeholder_path = path_with_suffix(&db_path, "placeholder");
+        for path in [&staging_path, &old_path, &placeholder_path] {
+            if path.exists() {
+                std::fs::remove_dir_all(path).map_err(backup_error)?;
+            }
+        }
+
+        // Stage a copy of the backup
+        if let Err(e) = copy_dir_files(&backup_path, &staging_path) {
+            let _ = std::fs::remove_dir_all(&staging_path);
+            return Err(backup_error(e));
+        }
+
+        // Close the DB: it is swapped with an empty one while the directories are renamed
+        let placeholder = DB::open_default(&placeholder_path)
+            .map_err(|e| MassaDBError::RocksDBError(e.to_string()))?;
+        self.wal_sync.stop();
+        let db = std::mem::replace(&mut self.db, Arc::new(placeholder));
+        let db = match Arc::try_unwrap(db) {
+            Ok(db) => db,
+            Err(db) => {
+                self.wal_sync = start_wal_sync(db.clone(), self.config.tuning.wal_sync);
+                self.db = db;
+                let _ = std::fs::remove_dir_all(&staging_path);
+                let _ = std::fs::remove_dir_all(&placeholder_path);
+                return Err(MassaDBError::BackupError(String::from(
+                    "the DB is still in use",
+                )));
+            }
+        };
+        drop(db);
+
+        let restored = swap_db_dirs(&db_path, &staging_path, &old_path)
+            .map_err(backup_error)
+            .and_then(|_| {
+                Self::open_db(&self.config, Self::default_db_opts())
+                    .map_err(|e| MassaDBError::RocksDBError(e.to_string()))
+            });
+        let db = match restored {
+            Ok(db) => db,
+            Err(e) => {
+                // Put the previous DB back in place
+                if let Err(rollback_error) = unswap_db_dirs(&db_path, &staging_path, &old_path) {
+                    return Err(MassaDBError::BackupError(format!(
+                        "{}, and the previous DB could not be put back in place: {}",
+                        e, rollback_error
+                    )));
+                }
+                let db = Self::open_db(&self.config, Self::default_db_opts())
+                    .map_err(|e| MassaDBError::RocksDBError(e.to_string()))?;
+                self.db = Arc::new(db);
+                self.wal_sync = start_wal_sync(self.db.clone(), self.config.tuning.wal_sync);
+                let _ = std::fs::remove_dir_all(&placeholder_path);
+                return Err(e);
+            }
+        };
+        self.db = Arc::new(db);
+        self.wal_sync = start_wal_sync(self.db.clone(), self.config.tuning.wal_sync);
+        self.change_history.clear();
+        self.change_history_versioning.clear();
+        self.change_history_bytes = 0;
+        self.change_history_versioning_bytes = 0;
+
+        std::fs::remove_dir_all(&old_path).map_err(backup_error)?;
+        std::fs::remove_dir_all(&placeholder_path).map_err(backup_error)?;
+        Ok(())
+    }
+}
+
+impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
     /// Creates a new hard copy of the DB, for the given slot
     fn backup_db(&self, slot: Slot) -> PathBuf {
         let db = &self.db;
         let subpath = format!("backup_{}_{}", slot.period, slot.thread);
 
-        if let Some(max_backups) = MAX_BACKUPS_TO_KEEP {
-            let previous_backups_paths = std::fs::read_dir(db.path())
-                .expect("Cannot walk db path")
-                .map(|res| res.map(|e| e.path()))
-                .collect::<Result<Vec<_>, std::io::Error>>()
-                .expect("Cannot walk db path");
-
-            let mut previous_backups = BTreeMap::new();
-
-            for backup_path in previous_backups_paths.iter() {
-                let Some(path_str) = backup_path.file_name().and_then(|f| f.to_str()) else {
-                    continue;
-                };
-                let vec = path_str.split('_').collect::<Vec<&str>>();
-                if vec.len() == 3 && vec[0] == "backup" {
-                    let Ok(period) = vec[1].parse::<u64>() else {
-                        continue;
-                    };
-                    let Ok(thread) = vec[2].parse::<u8>() else {
-                        continue;
-                    };
-                    let backup_slot = Slot::new(period, thread);
-                    previous_backups.insert(backup_slot, backup_path);
+        let mut previous_backups: BTreeMap<Slot, PathBuf> =
+            self.list_backups().into_iter().collect();
+
+        // Remove the backups that are too old
+        if let Some(max_age) = self.config.backup_retention.max_age {
+            previous_backups.retain(|_, backup_path| {
+                let expired = std::fs::metadata(&*backup_path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .map_or(false, |age| age > max_age);
+                if expired {
+                    if let Err(e) = std::fs::remove_dir_all(&*backup_path) {
+                        warn!("cannot remove expired backup {:?}: {}", backup_path, e);
+                    }
                 }
-            }
+                !expired
+            });
+        }
 
-            // Remove the oldest backups if we have too many
+        // Remove the oldest backups if we have too many
+        if let Some(max_backups) = self.config.backup_retention.max_backups {
             while previous_backups.len() >= max_backups {
                 if let Some((_, oldest_backup_path)) = previous_backups.pop_first() {
-                    std::fs::remove_dir_all(oldest_backup_path)
-                        .expect("Cannot remove oldest backup");
+                    if let Err(e) = std::fs::remove_dir_all(&oldest_backup_path) {
+                        warn!(
+                            "cannot remove oldest backup {:?}: {}",
+                            oldest_backup_path, e
+                        );
+                    }
+                } else {
+                    break;
                 }
             }
         }
@@ -643,12 +1214,56 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         backup_path
     }
 
+    /// Lists the backups of the DB, sorted by slot
+    fn list_backups(&self) -> Vec<(Slot, PathBuf)> {
+        self.list_backups()
+    }
+
+    /// Replaces the content of the DB with the backup made at the given slot
+    fn restore_from_backup(&mut self, slot: Slot) -> Result<(), MassaDBError> {
+        self.restore_from_backup(slot)
+    }
+
     /// Writes the batch to the DB
     fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>) {
-        self.write_changes(batch, versioning_batch, change_id, false)
+        self.write_changes(
+            batch,
+            versioning_batch,
+            &DBPreviousValues::new(),
+            change_id,
+            false,
+        )
+        .expect(CRUD_ERROR);
+    }
+
+    /// Writes the batch to the DB, using the provided previous values instead of reading them
+    fn write_batch_with_previous_values(
+        &mut self,
+        batch: DBBatch,
+        versioning_batch: DBBatch,
+        previous_values: &DBPreviousValues,
+        change_id: Option<Slot>,
+    ) {
+        self.write_changes(batch, versioning_batch, previous_values, change_id, false)
             .expect(CRUD_ERROR);
     }
 
+    /// Writes the changes staged in a transaction, atomically
+    fn commit_transaction(
+        &mut self,
+        transaction: MassaDBTransaction,
+        change_id: Option<Slot>,
+    ) -> Result<(), MassaDBError> {
+        let (changes, versioning_changes, previous_values) = transaction.into_changes();
+        self.write_changes(
+            changes,
+            versioning_changes,
+            &previous_values,
+            change_id,
+            false,
+        )
+    }
+
     /// Utility function to put / update a key & value in the batch
     fn put_or_update_entry_value(&self, batch: &mut DBBatch, key: Vec<u8>, value: &[u8]) {
         batch.insert(key, Some(value.to_vec()));
@@ -661,41 +1276,30 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
 
     /// Utility function to delete all keys in a prefix
     fn delete_prefix(&mut self, prefix: &str, handle_str: &str, change_id: Option<Slot>) {
-        let db = &self.db;
-
-        let handle = db.cf_handle(handle_str).expect(CF_ERROR);
-        let mut batch = DBBatch::new();
-        for (serialized_key, _) in db.prefix_iterator_cf(handle, prefix).flatten() {
-            if !serialized_key.starts_with(prefix.as_bytes()) {
-                break;
-            }
-
-            self.delete_key(&mut batch, serialized_key.to_vec());
-        }
-
-        match handle_str {
-            STATE_CF => {
-                self.write_batch(batch, DBBatch::new(), change_id);
-            }
-            VERSIONING_CF => {
-                self.write_batch(DBBatch::new(), batch, change_id);
-            }
-            _ => {}
-        }
+        self.delete_prefix_range(prefix.as_bytes(), handle_str, change_id)
+            .expect(CRUD_ERROR);
     }
 
     /// Reset the database, and attach it to the given slot.
     fn reset(&mut self, slot: Slot) {
         self.set_initial_change_id(slot);
         self.change_history.clear();
+        self.change_history_bytes = 0;
+        if self.config.archival.enabled {
+            let mut batch = WriteBatch::default();
+            self.restart_archive_to_batch(&mut batch, &slot);
+            self.write_to_db(batch).expect(CRUD_ERROR);
+        }
     }
 
     fn get_cf(&self, handle_cf: &str, key: Key) -> Result<Option<Value>, MassaDBError> {
         let db = &self.db;
         let handle = db.cf_handle(handle_cf).expect(CF_ERROR);
 
-        db.get_cf(handle, key)
-            .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
+        let start = Instant::now();
+        let res = db.get_cf(handle, key);
+        self.read_stats.record(1, start.elapsed());
+        res.map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
     }
 
     /// Exposes RocksDB's "multi_get_cf" function
@@ -707,8 +1311,11 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             .map(|(handle_cf, key)| (db.cf_handle(handle_cf).expect(CF_ERROR), key))
             .collect::<Vec<_>>();
 
-        db.multi_get_cf(rocks_db_query)
-            .into_iter()
+        let count = rocks_db_query.len() as u64;
+        let start = Instant::now();
+        let res = db.multi_get_cf(rocks_db_query);
+        self.read_stats.record(count, start.elapsed());
+        res.into_iter()
             .map(|res| res.map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e))))
             .collect()
     }
@@ -756,6 +1363,34 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         )
     }
 
+    fn range_iterator_cf(
+        &self,
+        handle_cf: &str,
+        start: &[u8],
+        end: Option<&[u8]>,
+        direction: MassaDirection,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        let db = &self.db;
+        let handle = db.cf_handle(handle_cf).expect(CF_ERROR);
+
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_iterate_lower_bound(start);
+        if let Some(end) = end {
+            read_opts.set_iterate_upper_bound(end);
+        }
+        // the iterator starts at the first or last key within the bounds
+        let rocksdb_mode = match direction {
+            MassaDirection::Forward => IteratorMode::Start,
+            MassaDirection::Reverse => IteratorMode::End,
+        };
+
+        Box::new(
+            db.iterator_cf_opt(handle, read_opts, rocksdb_mode)
+                .flatten()
+                .map(|(k, v)| (k.to_vec(), v.to_vec())),
+        )
+    }
+
     /// Get the current extended state hash of the database
     fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES> {
         self.get_xof_db_hash()
@@ -771,6 +1406,14 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.set_initial_change_id(change_id)
     }
 
+    /// Recomputes the state hash and compares it to the stored one
+    fn verify_integrity(
+        &self,
+        is_key_value_valid: &dyn Fn(&[u8], &[u8]) -> bool,
+    ) -> Result<MassaDBIntegrityReport, MassaDBError> {
+        self.verify_integrity(is_key_value_valid)
+    }
+
     /// Flushes the underlying db.
     fn flush(&self) -> Result<(), MassaDBError> {
         self.db
@@ -778,6 +1421,62 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
     }
 
+    /// Makes a secondary instance apply the latest changes of its primary instance
+    fn catch_up_with_primary(&self) -> Result<(), MassaDBError> {
+        self.catch_up_with_primary()
+    }
+
+    /// Writes a batch to a column family local to this node
+    fn write_local_batch(&self, handle_cf: &str, batch: DBBatch) -> Result<(), MassaDBError> {
+        self.write_local_batch(handle_cf, batch)
+    }
+
+    /// Starts compacting a whole column family in a background thread
+    fn trigger_compaction(&self, handle_cf: &str) -> Result<(), MassaDBError> {
+        self.trigger_compaction(handle_cf)
+    }
+
+    /// Get the size on disk of each column family, in bytes
+    fn get_cf_sizes(&self) -> BTreeMap<String, u64> {
+        self.get_cf_sizes()
+    }
+
+    /// Get the space available on the disk holding the database, in bytes, if it can be determined
+    fn get_available_disk_space(&self) -> Option<u64> {
+        self.get_available_disk_space()
+    }
+
+    /// Get a snapshot of the RocksDB and change history statistics
+    fn get_metrics(&self) -> MassaDBMetrics {
+        self.get_metrics()
+    }
+
+    fn get_at_slot(&self, key: &[u8], slot: Slot) -> Result<Option<Value>, MassaDBError> {
+        self.get_at_change_id(key, &slot)
+    }
+
+    fn iterate_at_slot(
+        &self,
+        prefix: &[u8],
+        slot: Slot,
+    ) -> Result<Box<dyn Iterator<Item = (Key, Value)> + '_>, MassaDBError> {
+        Ok(Box::new(
+            self.get_prefix_at_change_id(prefix, &slot)?.into_iter(),
+        ))
+    }
+
+    fn get_archive_horizon(&self) -> Option<Slot> {
+        if !self.config.archival.enabled {
+            return None;
+        }
+        self.read_archive_horizon()
+    }
+
+    /// Get the oldest slot from which changes can still be streamed to a bootstrap client
+    fn get_change_history_horizon(&self) -> Option<Slot> {
+        self.get_change_history_horizon()
+    }
+
     /// Write a stream_batch of database entries received from a bootstrap server
     fn write_batch_bootstrap_client(
         &mut self,
@@ -787,6 +1486,14 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.write_batch_bootstrap_client(stream_changes, stream_changes_versioning)
     }
 
+    /// Write a stream_batch of a stripe of the state received from a bootstrap server
+    fn write_stripe_bootstrap_client(
+        &mut self,
+        stream_changes: StreamBatch<Slot>,
+    ) -> Result<StreamingStep<Key>, MassaDBError> {
+        self.write_stripe_bootstrap_client(stream_changes)
+    }
+
     /// Used for bootstrap servers (get a new batch of data from STATE_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -798,6 +1505,20 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.get_batch_to_stream(last_state_step, last_change_id)
     }
 
+    /// Used for bootstrap servers streaming a stripe of the state
+    /// (get a new batch of data from STATE_CF, restricted to a key range, to stream to the client)
+    ///
+    /// Returns a StreamBatch<Slot>
+    fn get_stripe_batch_to_stream(
+        &self,
+        range_start: &[u8],
+        range_end: Option<&[u8]>,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError> {
+        self.get_stripe_batch_to_stream(range_start, range_end, last_state_step, last_change_id)
+    }
+
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -810,12 +1531,147 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
     }
 }
 
+/// Lists the backups stored in a DB directory, sorted by slot
+fn list_backups_in(db_path: &Path) -> Vec<(Slot, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(db_path) else {
+        return Vec::new();
+    };
+    let mut backups = BTreeMap::new();
+    for backup_path in entries.flatten().map(|entry| entry.path()) {
+        let Some(path_str) = backup_path.file_name().and_then(|f| f.to_str()) else {
+            continue;
+        };
+        let vec = path_str.split('_').collect::<Vec<&str>>();
+        if vec.len() == 3 && vec[0] == "backup" {
+            let Ok(period) = vec[1].parse::<u64>() else {
+                continue;
+            };
+            let Ok(thread) = vec[2].parse::<u8>() else {
+                continue;
+            };
+            backups.insert(Slot::new(period, thread), backup_path);
+        }
+    }
+    backups.into_iter().collect()
+}
+
+/// Returns the sibling of `path` named after it with `_{suffix}` appended
+fn path_with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push("_");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Converts an IO error of a backup operation
+fn backup_error(e: std::io::Error) -> MassaDBError {
+    MassaDBError::BackupError(e.to_string())
+}
+
+/// Copies the files of the `from` directory into the new `to` directory
+fn copy_dir_files(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        std::fs::copy(entry.path(), to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Moves the DB at `db_path` to `old_path` and the staged DB at `staging_path` to `db_path`,
+/// along with the backups of the DB
+fn swap_db_dirs(db_path: &Path, staging_path: &Path, old_path: &Path) -> std::io::Result<()> {
+    for (_, path) in list_backups_in(db_path) {
+        if let Some(name) = path.file_name() {
+            std::fs::rename(&path, staging_path.join(name))?;
+        }
+    }
+    std::fs::rename(db_path, old_path)?;
+    std::fs::rename(staging_path, db_path)
+}
+
+/// Undoes `swap_db_dirs`, whichever step it stopped at, and removes the staged DB
+fn unswap_db_dirs(db_path: &Path, staging_path: &Path, old_path: &Path) -> std::io::Result<()> {
+    if old_path.exists() {
+        if db_path.exists() {
+            std::fs::rename(db_path, staging_path)?;
+        }
+        std::fs::rename(old_path, db_path)?;
+    }
+    for (_, path) in list_backups_in(staging_path) {
+        if let Some(name) = path.file_name() {
+            std::fs::rename(&path, db_path.join(name))?;
+        }
+    }
+    std::fs::remove_dir_all(staging_path)
+}
+
+/// Size in bytes of a key and of its value, if any
+fn entry_size(key: &[u8], value: &Option<Value>) -> usize {
+    key.len() + value.as_ref().map_or(0, |value| value.len())
+}
+
+/// Size in bytes of the keys and values of a set of changes
+fn changes_size(changes: &BTreeMap<Key, Option<Value>>) -> usize {
+    changes
+        .iter()
+        .map(|(key, value)| entry_size(key, value))
+        .sum()
+}
+
+/// Adds changes to the entry of `change_id` in a change history of `history_bytes` bytes.
+/// Returns the new size of the history in bytes.
+fn insert_in_history<ChangeID: Ord>(
+    history: &mut BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
+    history_bytes: usize,
+    change_id: ChangeID,
+    changes: BTreeMap<Key, Option<Value>>,
+) -> usize {
+    let entry = history.entry(change_id).or_default();
+    let previous_size = changes_size(entry);
+    entry.extend(changes);
+    history_bytes - previous_size + changes_size(entry)
+}
+
+/// Removes the oldest entries of a change history of `history_bytes` bytes until it holds
+/// at most `max_length` entries and, if set, `max_bytes` bytes.
+/// The latest entry is always kept so that the latest changes can be streamed.
+/// Returns the new size of the history in bytes.
+fn prune_history<ChangeID: Ord>(
+    history: &mut BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
+    mut history_bytes: usize,
+    max_length: usize,
+    max_bytes: Option<usize>,
+) -> usize {
+    while history.len() > max_length
+        || (history.len() > 1 && max_bytes.map_or(false, |max_bytes| history_bytes > max_bytes))
+    {
+        match history.pop_first() {
+            Some((_, changes)) => history_bytes -= changes_size(&changes),
+            None => break,
+        }
+    }
+    history_bytes
+}
+
+/// For a given start prefix (inclusive), returns the end key (exclusive) of the keys having it.
+/// Returns `None` if there is no such bound, e.g. for a prefix made of `255` bytes only.
+pub(crate) fn end_prefix(prefix: &[u8]) -> Option<Vec<u8>> {
+    match get_prefix_bounds(prefix).1 {
+        Bound::Excluded(end) => Some(end),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::BTreeMap;
 
     use assert_matches::assert_matches;
+    use massa_db_exports::MassaDBArchival;
     use massa_db_exports::MassaDBError::TimeError;
+    use massa_db_exports::MassaDBTuning;
+    use massa_models::config::MAX_BACKUPS_TO_KEEP;
     use parking_lot::RwLock;
     use tempfile::tempdir;
 
@@ -852,113 +1708,646 @@ mod test {
     }
 
     #[test]
-    fn test_init() {
-        // Test we cannot double init a Db object
+    fn test_init() {
+        // Test we cannot double init a Db object
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        // println!("temp_dir_db: {:?}", temp_dir_db);
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db_opts = MassaDB::default_db_opts();
+        // Additional checks (only for testing)
+        db_opts.set_paranoid_checks(true);
+
+        let _db = MassaDB::new_with_options(db_config.clone(), db_opts.clone()).unwrap();
+
+        // Check not allowed to init a second instance
+        let db2 = MassaDB::new_with_options(db_config, db_opts.clone());
+        assert!(db2.is_err());
+        assert!(db2
+            .err()
+            .unwrap()
+            .into_string()
+            .contains("IO error: lock hold by current process"));
+    }
+
+    #[test]
+    fn test_tuning() {
+        // Open a db with every tuning option set, then write and read it back
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: MassaDBTuning {
+                block_cache_size: Some(8 * 1024 * 1024),
+                write_buffer_size: Some(4 * 1024 * 1024),
+                bloom_filter_bits_per_key: Some(10.0),
+                max_open_files: Some(64),
+                state_cf_compression: Some(MassaDBCompression::Lz4),
+                metadata_cf_compression: Some(MassaDBCompression::None),
+                versioning_cf_compression: Some(MassaDBCompression::Snappy),
+                local_cf_compression: Some(MassaDBCompression::Zstd),
+                wal_sync: Some(MassaDBWalSync::Periodic(Duration::from_millis(10))),
+                compaction_rate_limit: Some(16 * 1024 * 1024),
+                max_background_jobs: Some(2),
+            },
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+
+        let mut batch = DBBatch::new();
+        batch.insert(vec![1, 2, 3], Some(vec![4, 5, 6]));
+        let mut versioning_batch = DBBatch::new();
+        versioning_batch.insert(vec![10, 20, 30], Some(vec![40, 50, 60]));
+        db.write()
+            .write_batch(batch.clone(), versioning_batch.clone(), None);
+
+        assert_eq!(dump_column_opt(db.clone(), STATE_CF), batch);
+        assert_eq!(dump_column_opt(db.clone(), VERSIONING_CF), versioning_batch);
+    }
+
+    #[test]
+    fn test_manual_compaction() {
+        // Compact a column family in the background, then read it back
+        // and check that the DB can be restored once the compaction is done
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().join("db"),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(db_config);
+
+        let mut batch = DBBatch::new();
+        for i in 0..100u8 {
+            batch.insert(vec![i], Some(vec![i; 100]));
+        }
+        db.write_batch(batch.clone(), DBBatch::new(), Some(Slot::new(1, 0)));
+        db.flush().unwrap();
+        db.backup_db(Slot::new(1, 0));
+
+        assert!(db.trigger_compaction("unknown").is_err());
+        db.trigger_compaction(STATE_CF).unwrap();
+        let start = Instant::now();
+        while db.is_manual_compaction_running() {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        db.restore_from_backup(Slot::new(1, 0)).unwrap();
+
+        let db = Arc::new(RwLock::new(
+            Box::new(db) as Box<(dyn MassaDBController + 'static)>
+        ));
+        assert_eq!(dump_column_opt(db, STATE_CF), batch);
+    }
+
+    #[test]
+    fn test_basics_1() {
+        // 1- Init a db + check initial hash
+        // 2- Add some data
+        // 3- Read it
+        // 4- Remove data
+
+        // Init
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        // println!("temp_dir_db: {:?}", temp_dir_db);
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db_opts = MassaDB::default_db_opts();
+        // Additional checks (only for testing)
+        db_opts.set_paranoid_checks(true);
+
+        let _db = MassaDB::new_with_options(db_config, db_opts.clone()).unwrap();
+        let db = Arc::new(RwLock::new(
+            Box::new(_db) as Box<(dyn MassaDBController + 'static)>
+        ));
+
+        assert_eq!(
+            Hash::compute_from(db.read().get_xof_db_hash().to_bytes()),
+            initial_hash()
+        );
+
+        // Checks up
+        let cfs = rocksdb::DB::list_cf(&db_opts, temp_dir_db.path()).unwrap_or(vec![]);
+        let cf_exists_1 = cfs.iter().find(|cf| cf == &"state").is_some();
+        let cf_exists_2 = cfs.iter().find(|cf| cf == &"metadata").is_some();
+        let cf_exists_3 = cfs.iter().find(|cf| cf == &"versioning").is_some();
+        println!("cfs: {:?}", cfs);
+        assert_eq!(cf_exists_1, true);
+        assert_eq!(cf_exists_2, true);
+        assert_eq!(cf_exists_3, true);
+        for col in cfs {
+            if col == "metadata" {
+                continue;
+            }
+            assert!(dump_column(db.clone(), col.as_str()).is_empty());
+        }
+
+        // Add data
+
+        let mut batch = DBBatch::new();
+        let b_key1 = vec![1, 2, 3];
+        let b_value1 = vec![4, 5, 6];
+        batch.insert(b_key1.clone(), Some(b_value1.clone()));
+        let mut versioning_batch = BTreeMap::default();
+        let vb_key1 = vec![10, 20, 30];
+        let vb_value1 = vec![40, 50, 60];
+        versioning_batch.insert(vb_key1.clone(), Some(vb_value1.clone()));
+
+        let mut guard = db.write();
+        guard.write_batch(batch.clone(), versioning_batch.clone(), None);
+        drop(guard);
+
+        // assert_eq!(dump_column_opt(db_.clone(), "state"), batch);
+        assert_eq!(dump_column_opt(db.clone(), "state"), batch);
+        // assert_eq!(dump_column_opt(db_.clone(), "versioning"), versioning_batch);
+        assert_eq!(dump_column_opt(db.clone(), "versioning"), versioning_batch);
+
+        // Remove data
+
+        let mut batch_2 = DBBatch::new();
+        batch_2.insert(b_key1.clone(), None);
+        let mut versioning_batch_2 = BTreeMap::default();
+        versioning_batch_2.insert(vb_key1.clone(), None);
+        let mut guard = db.write();
+        guard.write_batch(batch_2.clone(), versioning_batch_2.clone(), None);
+        drop(guard);
+
+        // assert!(dump_column(db_.clone(), "state").is_empty());
+        assert!(dump_column(db.clone(), "state").is_empty());
+        // assert!(dump_column(db_.clone(), "versioning").is_empty());
+        assert!(dump_column(db.clone(), "versioning").is_empty());
+    }
+
+    #[test]
+    fn test_delete_prefix_range() {
+        // 1- Fill a db with keys under several prefixes
+        // 2- Delete some prefixes with range deletions
+        // 3- Check the hash equals the hash of a db only holding the remaining keys
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let temp_dir_expected = tempdir().expect("Unable to create a temp folder");
+        let config = |path: &std::path::Path| MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(config(temp_dir_db.path()));
+        let mut expected_db = MassaDB::new(config(temp_dir_expected.path()));
+
+        let mut batch = DBBatch::new();
+        let mut expected_batch = DBBatch::new();
+        for i in 0..10u8 {
+            batch.insert([b"a/".as_slice(), &[i]].concat(), Some(vec![i]));
+            batch.insert([b"b/".as_slice(), &[i]].concat(), Some(vec![i]));
+            batch.insert(vec![255, 255, i], Some(vec![i]));
+            let kept = ([b"c/".as_slice(), &[i]].concat(), Some(vec![i]));
+            batch.insert(kept.0.clone(), kept.1.clone());
+            expected_batch.insert(kept.0, kept.1);
+        }
+        // key right after the "b/" prefix, must not be deleted
+        batch.insert(b"b0".to_vec(), Some(vec![0]));
+        expected_batch.insert(b"b0".to_vec(), Some(vec![0]));
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 0)));
+        expected_db.write_batch(
+            expected_batch.clone(),
+            DBBatch::new(),
+            Some(Slot::new(1, 0)),
+        );
+
+        db.delete_prefix("a/", STATE_CF, Some(Slot::new(2, 0)));
+        db.delete_prefix("b/", STATE_CF, None);
+        // prefix without upper bound
+        db.delete_prefix_range(&[255, 255], STATE_CF, None).unwrap();
+
+        assert_eq!(db.get_xof_db_hash(), expected_db.get_xof_db_hash());
+        let db = Arc::new(RwLock::new(
+            Box::new(db) as Box<(dyn MassaDBController + 'static)>
+        ));
+        assert_eq!(dump_column_opt(db.clone(), STATE_CF), expected_batch);
+
+        // the deletions are recorded in the change history for bootstrap streaming
+        let guard = db.read();
+        let changes =
+            guard.get_batch_to_stream(&StreamingStep::Finished(None), Some(Slot::new(1, 0)));
+        assert_matches!(changes, Ok(batch) if batch.updates_on_previous_elements.len() == 30);
+    }
+
+    #[test]
+    fn test_memory_db_parity() {
+        // 1- Apply the same writes and deletions to a MassaDB and to a MemoryMassaDB
+        // 2- Check they have the same hash, change_id, content and batches to stream
+
+        use massa_db_exports::test_exports::MemoryMassaDB;
+        use massa_db_exports::ShareableMassaDBController;
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let config = |path: &std::path::Path| MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 4,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let db: ShareableMassaDBController = Arc::new(RwLock::new(Box::new(MassaDB::new(config(
+            temp_dir_db.path(),
+        )))));
+        let memory_db: ShareableMassaDBController = Arc::new(RwLock::new(Box::new(
+            MemoryMassaDB::new(config(temp_dir_db.path())),
+        )));
+
+        for db in [db.clone(), memory_db.clone()] {
+            let mut guard = db.write();
+            let mut batch = DBBatch::new();
+            let mut versioning_batch = DBBatch::new();
+            for i in 0..10u8 {
+                batch.insert([b"a/".as_slice(), &[i]].concat(), Some(vec![i]));
+                batch.insert([b"b/".as_slice(), &[i]].concat(), Some(vec![i]));
+                versioning_batch.insert(vec![i], Some(vec![i]));
+            }
+            guard.write_batch(batch, versioning_batch, Some(Slot::new(1, 0)));
+
+            let mut batch = DBBatch::new();
+            batch.insert(b"a/\x01".to_vec(), Some(vec![42]));
+            batch.insert(b"a/\x02".to_vec(), None);
+            guard.write_batch(batch, DBBatch::new(), Some(Slot::new(2, 0)));
+            guard.delete_prefix("b/", STATE_CF, Some(Slot::new(3, 0)));
+        }
+
+        assert_eq!(
+            db.read().get_xof_db_hash(),
+            memory_db.read().get_xof_db_hash()
+        );
+        assert_eq!(
+            db.read().get_change_id().unwrap(),
+            memory_db.read().get_change_id().unwrap()
+        );
+        for column in [STATE_CF, METADATA_CF, VERSIONING_CF] {
+            assert_eq!(
+                dump_column(db.clone(), column),
+                dump_column(memory_db.clone(), column)
+            );
+        }
+
+        let step = StreamingStep::Ongoing(b"a/\x05".to_vec());
+        let batch = db
+            .read()
+            .get_batch_to_stream(&step, Some(Slot::new(1, 0)))
+            .unwrap();
+        let memory_batch = memory_db
+            .read()
+            .get_batch_to_stream(&step, Some(Slot::new(1, 0)))
+            .unwrap();
+        assert_eq!(batch.new_elements, memory_batch.new_elements);
+        assert_eq!(
+            batch.updates_on_previous_elements,
+            memory_batch.updates_on_previous_elements
+        );
+        assert_eq!(batch.change_id, memory_batch.change_id);
+    }
+
+    #[test]
+    fn test_range_iterator() {
+        // Iterate over bounded and unbounded ranges, in both directions, on a MassaDB and on a MemoryMassaDB
+
+        use massa_db_exports::test_exports::MemoryMassaDB;
+        use massa_db_exports::ShareableMassaDBController;
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let db: ShareableMassaDBController =
+            Arc::new(RwLock::new(Box::new(MassaDB::new(config.clone()))));
+        let memory_db: ShareableMassaDBController =
+            Arc::new(RwLock::new(Box::new(MemoryMassaDB::new(config))));
+
+        for db in [db, memory_db] {
+            let mut batch = DBBatch::new();
+            for key in [&b"a"[..], b"a/1", b"a/2", b"a0", b"b"] {
+                batch.insert(key.to_vec(), Some(key.to_vec()));
+            }
+            db.write().write_batch(batch, DBBatch::new(), None);
+
+            let keys = |start: &[u8], end: Option<&[u8]>, direction| {
+                db.read()
+                    .range_iterator_cf(STATE_CF, start, end, direction)
+                    .map(|(key, _)| key)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                keys(b"a/", Some(&b"a0"[..]), MassaDirection::Forward),
+                vec![b"a/1".to_vec(), b"a/2".to_vec()]
+            );
+            assert_eq!(
+                keys(b"a/", Some(&b"a0"[..]), MassaDirection::Reverse),
+                vec![b"a/2".to_vec(), b"a/1".to_vec()]
+            );
+            assert_eq!(
+                keys(b"a0", None, MassaDirection::Forward),
+                vec![b"a0".to_vec(), b"b".to_vec()]
+            );
+            assert_eq!(
+                keys(b"a0", None, MassaDirection::Reverse),
+                vec![b"b".to_vec(), b"a0".to_vec()]
+            );
+            assert!(keys(b"b", Some(&b"a"[..]), MassaDirection::Forward).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_transaction() {
+        // 1- Stage changes across STATE_CF and VERSIONING_CF, roll back part of them
+        // 2- Check nothing is written before the commit, and an aborted transaction writes nothing
+        // 3- Check the commit writes the remaining changes like write_batch does
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let temp_dir_expected = tempdir().expect("Unable to create a temp folder");
+        let config = |path: &std::path::Path| MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(config(temp_dir_db.path()));
+        let mut expected_db = MassaDB::new(config(temp_dir_expected.path()));
+
+        let mut transaction = MassaDBTransaction::new();
+        transaction.put(STATE_CF, vec![1], vec![10]);
+        transaction.put(VERSIONING_CF, vec![2], vec![20]);
+        transaction.set_savepoint();
+        transaction.put(STATE_CF, vec![3], vec![30]);
+        transaction.delete(STATE_CF, vec![1]);
+        assert_eq!(transaction.get_cf(&db, STATE_CF, vec![1]).unwrap(), None);
+        transaction.rollback_to_savepoint().unwrap();
+        assert!(transaction.rollback_to_savepoint().is_err());
+        assert_eq!(
+            transaction.get_cf(&db, STATE_CF, vec![1]).unwrap(),
+            Some(vec![10])
+        );
+        assert_eq!(transaction.get_staged(STATE_CF, &[3]), None);
+
+        // nothing is written before the commit
+        assert_eq!(db.get_cf(STATE_CF, vec![1]).unwrap(), None);
+        assert_eq!(db.get_xof_db_hash(), HashXof(*STATE_HASH_INITIAL_BYTES));
+        transaction.clone().abort();
+        assert_eq!(db.get_cf(VERSIONING_CF, vec![2]).unwrap(), None);
+
+        db.commit_transaction(transaction, Some(Slot::new(1, 0)))
+            .unwrap();
+        expected_db.write_batch(
+            DBBatch::from([(vec![1], Some(vec![10]))]),
+            DBBatch::from([(vec![2], Some(vec![20]))]),
+            Some(Slot::new(1, 0)),
+        );
+        assert_eq!(db.get_xof_db_hash(), expected_db.get_xof_db_hash());
+        assert_eq!(db.get_change_id().unwrap(), Slot::new(1, 0));
+        assert_eq!(db.get_cf(VERSIONING_CF, vec![2]).unwrap(), Some(vec![20]));
+
+        // a transaction going back in time is not written
+        let mut transaction = MassaDBTransaction::new();
+        transaction.put(STATE_CF, vec![4], vec![40]);
+        assert_matches!(
+            db.commit_transaction(transaction, Some(Slot::new(0, 0))),
+            Err(MassaDBError::InvalidChangeID(_))
+        );
+        assert_eq!(db.get_cf(STATE_CF, vec![4]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_archival() {
+        // 1- Write and delete keys at several slots in archival mode, keeping 2 periods
+        // 2- Check the state at past slots, and that the oldest slots are pruned
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: MassaDBArchival {
+                enabled: true,
+                max_periods: Some(2),
+            },
+        };
+        let mut db = MassaDB::new(db_config);
+        assert_eq!(db.get_archive_horizon(), Some(Slot::new(0, 0)));
+
+        db.write_batch(
+            DBBatch::from([
+                (b"a/1".to_vec(), Some(vec![1])),
+                (b"a/2".to_vec(), Some(vec![2])),
+                (b"b/1".to_vec(), Some(vec![3])),
+            ]),
+            DBBatch::new(),
+            Some(Slot::new(1, 0)),
+        );
+        db.write_batch(
+            DBBatch::from([(b"a/1".to_vec(), Some(vec![10]))]),
+            DBBatch::new(),
+            Some(Slot::new(1, 1)),
+        );
+        // written twice at the same slot
+        db.write_batch(
+            DBBatch::from([(b"a/1".to_vec(), Some(vec![11]))]),
+            DBBatch::new(),
+            None,
+        );
+        db.delete_prefix("a/", STATE_CF, Some(Slot::new(2, 0)));
+
+        let a_1 = |slot| db.get_at_slot(b"a/1", slot).unwrap();
+        assert_eq!(a_1(Slot::new(0, 0)), None);
+        assert_eq!(a_1(Slot::new(1, 0)), Some(vec![1]));
+        assert_eq!(a_1(Slot::new(1, 1)), Some(vec![11]));
+        assert_eq!(a_1(Slot::new(2, 0)), None);
+        assert_matches!(
+            db.get_at_slot(b"a/1", Slot::new(3, 0)),
+            Err(MassaDBError::ArchiveError(_))
+        );
+        assert_eq!(
+            db.iterate_at_slot(b"a/", Slot::new(1, 0))
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![(b"a/1".to_vec(), vec![1]), (b"a/2".to_vec(), vec![2])]
+        );
+        assert_eq!(
+            db.iterate_at_slot(b"a/", Slot::new(2, 0)).unwrap().count(),
+            0
+        );
+
+        // the changes up to period 1 are removed at period 3
+        db.write_batch(
+            DBBatch::from([(b"b/1".to_vec(), Some(vec![4]))]),
+            DBBatch::new(),
+            Some(Slot::new(3, 0)),
+        );
+        assert_eq!(db.get_archive_horizon(), Some(Slot::new(1, 0)));
+        assert_matches!(
+            db.get_at_slot(b"a/1", Slot::new(0, 0)),
+            Err(MassaDBError::ArchiveError(_))
+        );
+        assert_eq!(
+            db.get_at_slot(b"b/1", Slot::new(2, 0)).unwrap(),
+            Some(vec![3])
+        );
+        assert_eq!(
+            db.get_at_slot(b"b/1", Slot::new(3, 0)).unwrap(),
+            Some(vec![4])
+        );
+    }
+
+    #[test]
+    fn test_history_max_bytes() {
+        // 1- Write changes of 10 bytes at several slots, with a budget of 25 bytes
+        // 2- Check the oldest changes are evicted and the horizon moves forward
+        // 3- Check a single change larger than the budget is kept
 
         let temp_dir_db = tempdir().expect("Unable to create a temp folder");
-        // println!("temp_dir_db: {:?}", temp_dir_db);
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: Some(25),
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
-        let mut db_opts = MassaDB::default_db_opts();
-        // Additional checks (only for testing)
-        db_opts.set_paranoid_checks(true);
+        let mut db = MassaDB::new(db_config);
+        assert_eq!(db.get_change_history_horizon(), None);
 
-        let _db = MassaDB::new_with_options(db_config.clone(), db_opts.clone()).unwrap();
+        for period in 1..=3u8 {
+            let mut batch = DBBatch::new();
+            batch.insert(vec![period; 5], Some(vec![period; 5]));
+            db.write_batch(batch, DBBatch::new(), Some(Slot::new(period as u64, 0)));
+        }
+        assert_eq!(db.change_history.len(), 2);
+        assert_eq!(db.change_history_bytes, 20);
+        assert_eq!(db.get_change_history_horizon(), Some(Slot::new(2, 0)));
+
+        // the first slot can't be streamed anymore
+        assert_matches!(
+            db.get_batch_to_stream(&StreamingStep::Finished(None), Some(Slot::new(2, 0))),
+            Ok(batch) if batch.updates_on_previous_elements.len() == 1
+        );
 
-        // Check not allowed to init a second instance
-        let db2 = MassaDB::new_with_options(db_config, db_opts.clone());
-        assert!(db2.is_err());
-        assert!(db2
-            .err()
-            .unwrap()
-            .into_string()
-            .contains("IO error: lock hold by current process"));
+        let mut batch = DBBatch::new();
+        batch.insert(vec![4; 20], Some(vec![4; 20]));
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(4, 0)));
+        assert_eq!(db.change_history.len(), 1);
+        assert_eq!(db.change_history_bytes, 40);
+        assert_eq!(db.get_change_history_horizon(), Some(Slot::new(4, 0)));
     }
 
     #[test]
-    fn test_basics_1() {
-        // 1- Init a db + check initial hash
-        // 2- Add some data
-        // 3- Read it
-        // 4- Remove data
-
-        // Init
-        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
-        // println!("temp_dir_db: {:?}", temp_dir_db);
-        let db_config = MassaDBConfig {
-            path: temp_dir_db.path().to_path_buf(),
+    fn test_write_with_previous_values() {
+        // Check that writing a batch with the previous values provided by the caller
+        // gives the same hash as reading them from the db
+
+        let temp_dir_read = tempdir().expect("Unable to create a temp folder");
+        let temp_dir_provided = tempdir().expect("Unable to create a temp folder");
+        let config = |path: &std::path::Path| MassaDBConfig {
+            path: path.to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
-        let mut db_opts = MassaDB::default_db_opts();
-        // Additional checks (only for testing)
-        db_opts.set_paranoid_checks(true);
-
-        let _db = MassaDB::new_with_options(db_config, db_opts.clone()).unwrap();
-        let db = Arc::new(RwLock::new(
-            Box::new(_db) as Box<(dyn MassaDBController + 'static)>
-        ));
-
-        assert_eq!(
-            Hash::compute_from(db.read().get_xof_db_hash().to_bytes()),
-            initial_hash()
-        );
-
-        // Checks up
-        let cfs = rocksdb::DB::list_cf(&db_opts, temp_dir_db.path()).unwrap_or(vec![]);
-        let cf_exists_1 = cfs.iter().find(|cf| cf == &"state").is_some();
-        let cf_exists_2 = cfs.iter().find(|cf| cf == &"metadata").is_some();
-        let cf_exists_3 = cfs.iter().find(|cf| cf == &"versioning").is_some();
-        println!("cfs: {:?}", cfs);
-        assert_eq!(cf_exists_1, true);
-        assert_eq!(cf_exists_2, true);
-        assert_eq!(cf_exists_3, true);
-        for col in cfs {
-            if col == "metadata" {
-                continue;
-            }
-            assert!(dump_column(db.clone(), col.as_str()).is_empty());
-        }
+        let mut db_read = MassaDB::new(config(temp_dir_read.path()));
+        let mut db_provided = MassaDB::new(config(temp_dir_provided.path()));
 
-        // Add data
+        let mut initial_batch = DBBatch::new();
+        initial_batch.insert(vec![1], Some(vec![10]));
+        initial_batch.insert(vec![2], Some(vec![20]));
+        initial_batch.insert(vec![3], Some(vec![30]));
+        db_read.write_batch(initial_batch.clone(), DBBatch::new(), Some(Slot::new(1, 0)));
+        db_provided.write_batch(initial_batch, DBBatch::new(), Some(Slot::new(1, 0)));
 
         let mut batch = DBBatch::new();
-        let b_key1 = vec![1, 2, 3];
-        let b_value1 = vec![4, 5, 6];
-        batch.insert(b_key1.clone(), Some(b_value1.clone()));
-        let mut versioning_batch = BTreeMap::default();
-        let vb_key1 = vec![10, 20, 30];
-        let vb_value1 = vec![40, 50, 60];
-        versioning_batch.insert(vb_key1.clone(), Some(vb_value1.clone()));
-
-        let mut guard = db.write();
-        guard.write_batch(batch.clone(), versioning_batch.clone(), None);
-        drop(guard);
-
-        // assert_eq!(dump_column_opt(db_.clone(), "state"), batch);
-        assert_eq!(dump_column_opt(db.clone(), "state"), batch);
-        // assert_eq!(dump_column_opt(db_.clone(), "versioning"), versioning_batch);
-        assert_eq!(dump_column_opt(db.clone(), "versioning"), versioning_batch);
-
-        // Remove data
-
-        let mut batch_2 = DBBatch::new();
-        batch_2.insert(b_key1.clone(), None);
-        let mut versioning_batch_2 = BTreeMap::default();
-        versioning_batch_2.insert(vb_key1.clone(), None);
-        let mut guard = db.write();
-        guard.write_batch(batch_2.clone(), versioning_batch_2.clone(), None);
-        drop(guard);
+        batch.insert(vec![1], Some(vec![11]));
+        batch.insert(vec![2], None);
+        batch.insert(vec![3], Some(vec![31]));
+        batch.insert(vec![4], Some(vec![41]));
+        // the previous value of key 3 is not provided: it is read from the db
+        let mut previous_values = DBPreviousValues::new();
+        previous_values.insert(vec![1], Some(vec![10]));
+        previous_values.insert(vec![2], Some(vec![20]));
+        previous_values.insert(vec![4], None);
+
+        db_read.write_batch(batch.clone(), DBBatch::new(), Some(Slot::new(2, 0)));
+        db_provided.write_batch_with_previous_values(
+            batch,
+            DBBatch::new(),
+            &previous_values,
+            Some(Slot::new(2, 0)),
+        );
 
-        // assert!(dump_column(db_.clone(), "state").is_empty());
-        assert!(dump_column(db.clone(), "state").is_empty());
-        // assert!(dump_column(db_.clone(), "versioning").is_empty());
-        assert!(dump_column(db.clone(), "versioning").is_empty());
+        assert_eq!(db_read.get_xof_db_hash(), db_provided.get_xof_db_hash());
     }
 
     #[test]
@@ -974,8 +2363,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let mut db_opts = MassaDB::default_db_opts();
         // Additional checks (only for testing)
@@ -1057,8 +2451,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let mut db_opts = MassaDB::default_db_opts();
         // Additional checks (only for testing)
@@ -1104,8 +2503,13 @@ mod test {
             let db_backup_1_config = MassaDBConfig {
                 path: backup_1,
                 max_history_length: 100,
+                max_history_bytes: None,
                 max_new_elements: 100,
+                max_stream_batch_bytes: None,
                 thread_count: THREAD_COUNT,
+                tuning: Default::default(),
+                backup_retention: Default::default(),
+                archival: Default::default(),
             };
             let mut db_backup_1_opts = MassaDB::default_db_opts();
             db_backup_1_opts.create_if_missing(false);
@@ -1126,8 +2530,13 @@ mod test {
             let db_backup_2_config = MassaDBConfig {
                 path: backup_2,
                 max_history_length: 100,
+                max_history_bytes: None,
                 max_new_elements: 100,
+                max_stream_batch_bytes: None,
                 thread_count: THREAD_COUNT,
+                tuning: Default::default(),
+                backup_retention: Default::default(),
+                archival: Default::default(),
             };
             let mut db_backup_2_opts = MassaDB::default_db_opts();
             db_backup_2_opts.create_if_missing(false);
@@ -1157,8 +2566,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let mut db_opts = MassaDB::default_db_opts();
         // Additional checks (only for testing)
@@ -1202,8 +2616,13 @@ mod test {
             let db_backup_config = MassaDBConfig {
                 path: backup_path.clone(),
                 max_history_length: 100,
+                max_history_bytes: None,
                 max_new_elements: 100,
+                max_stream_batch_bytes: None,
                 thread_count: THREAD_COUNT,
+                tuning: Default::default(),
+                backup_retention: Default::default(),
+                archival: Default::default(),
             };
             // let db_backup_2_opts = MassaDB::default_db_opts();
 
@@ -1236,6 +2655,278 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_backup_restore() {
+        // 1- Init a db, add data and backup at slot 1 and 2
+        // 2- Add data at slot 3
+        // 3- Restore the backup of slot 1 + checks
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_path = temp_dir_db.path().join("db");
+        let db_config = MassaDBConfig {
+            path: db_path.clone(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(db_config);
+
+        let batch_1 = DBBatch::from([(vec![1], Some(vec![10]))]);
+        db.write_batch(batch_1.clone(), DBBatch::new(), Some(Slot::new(1, 0)));
+        let hash_1 = db.get_xof_db_hash();
+        db.backup_db(Slot::new(1, 0));
+        db.write_batch(
+            DBBatch::from([(vec![2], Some(vec![20]))]),
+            DBBatch::new(),
+            Some(Slot::new(2, 0)),
+        );
+        db.backup_db(Slot::new(2, 0));
+        db.write_batch(
+            DBBatch::from([(vec![3], Some(vec![30]))]),
+            DBBatch::new(),
+            Some(Slot::new(3, 0)),
+        );
+
+        assert_eq!(
+            db.list_backups()
+                .into_iter()
+                .map(|(slot, _)| slot)
+                .collect::<Vec<_>>(),
+            vec![Slot::new(1, 0), Slot::new(2, 0)]
+        );
+        assert_matches!(
+            db.restore_from_backup(Slot::new(5, 0)),
+            Err(MassaDBError::BackupError(_))
+        );
+
+        db.restore_from_backup(Slot::new(1, 0)).unwrap();
+
+        assert_eq!(db.get_change_id().unwrap(), Slot::new(1, 0));
+        assert_eq!(db.get_xof_db_hash(), hash_1);
+        assert!(db.change_history.is_empty());
+        assert_eq!(db.list_backups().len(), 2);
+        let db = Arc::new(RwLock::new(
+            Box::new(db) as Box<(dyn MassaDBController + 'static)>
+        ));
+        assert_eq!(dump_column_opt(db.clone(), STATE_CF), batch_1);
+
+        // the restored db is writable
+        db.write().write_batch(
+            DBBatch::from([(vec![4], Some(vec![40]))]),
+            DBBatch::new(),
+            Some(Slot::new(2, 0)),
+        );
+        assert_eq!(db.read().get_change_id().unwrap(), Slot::new(2, 0));
+        assert_eq!(
+            std::fs::read_dir(temp_dir_db.path()).unwrap().count(),
+            1,
+            "the staging directories are removed"
+        );
+    }
+
+    #[test]
+    fn test_backup_restore_rollback() {
+        // 1- Init a db, add data and backup at slot 1
+        // 2- Corrupt the backup and add data at slot 2
+        // 3- Restore the backup of slot 1: it fails and the db is left as it was
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().join("db"),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(db_config);
+
+        db.write_batch(
+            DBBatch::from([(vec![1], Some(vec![10]))]),
+            DBBatch::new(),
+            Some(Slot::new(1, 0)),
+        );
+        let backup_path = db.backup_db(Slot::new(1, 0));
+        std::fs::write(backup_path.join("CURRENT"), b"corrupted").unwrap();
+        db.write_batch(
+            DBBatch::from([(vec![2], Some(vec![20]))]),
+            DBBatch::new(),
+            Some(Slot::new(2, 0)),
+        );
+        let hash_2 = db.get_xof_db_hash();
+
+        assert!(db.restore_from_backup(Slot::new(1, 0)).is_err());
+
+        assert_eq!(db.get_change_id().unwrap(), Slot::new(2, 0));
+        assert_eq!(db.get_xof_db_hash(), hash_2);
+        assert_eq!(db.list_backups().len(), 1);
+        assert_eq!(
+            std::fs::read_dir(temp_dir_db.path()).unwrap().count(),
+            1,
+            "the staging directories are removed"
+        );
+
+        // the db is still writable
+        db.write_batch(
+            DBBatch::from([(vec![3], Some(vec![30]))]),
+            DBBatch::new(),
+            Some(Slot::new(3, 0)),
+        );
+        assert_eq!(db.get_change_id().unwrap(), Slot::new(3, 0));
+    }
+
+    #[test]
+    fn test_secondary_instance() {
+        // 1- Init a primary db + add data
+        // 2- Open a secondary instance and read the data
+        // 3- Add data to the primary, check it is visible after a catch up
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let temp_dir_secondary = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(db_config.clone());
+        let batch_1 = DBBatch::from([(vec![1], Some(vec![10]))]);
+        db.write_batch(batch_1.clone(), DBBatch::new(), Some(Slot::new(1, 0)));
+
+        let secondary =
+            MassaDB::new_secondary(db_config, temp_dir_secondary.path().to_path_buf()).unwrap();
+        let secondary = Arc::new(RwLock::new(
+            Box::new(secondary) as Box<(dyn MassaDBController + 'static)>
+        ));
+        assert_eq!(dump_column_opt(secondary.clone(), STATE_CF), batch_1);
+        assert_eq!(secondary.read().get_xof_db_hash(), db.get_xof_db_hash());
+
+        db.write_batch(
+            DBBatch::from([(vec![2], Some(vec![20]))]),
+            DBBatch::new(),
+            Some(Slot::new(2, 0)),
+        );
+        assert_eq!(secondary.read().get_change_id().unwrap(), Slot::new(1, 0));
+
+        secondary.read().catch_up_with_primary().unwrap();
+        assert_eq!(secondary.read().get_change_id().unwrap(), Slot::new(2, 0));
+        assert_eq!(secondary.read().get_xof_db_hash(), db.get_xof_db_hash());
+        assert_eq!(
+            dump_column(secondary.clone(), STATE_CF).len(),
+            2,
+            "the secondary instance sees the new data"
+        );
+
+        // the primary instance can't catch up
+        assert!(db.catch_up_with_primary().is_err());
+    }
+
+    #[test]
+    fn test_verify_integrity() {
+        // 1- Init a db + add data, check it is valid
+        // 2- Reject a key with the validator
+        // 3- Write to STATE_CF without updating the hash, check the mismatch is reported
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(db_config);
+        db.write_batch(
+            DBBatch::from([(vec![1], Some(vec![10])), (vec![2], Some(vec![20]))]),
+            DBBatch::new(),
+            Some(Slot::new(1, 0)),
+        );
+
+        let report = db.verify_integrity(&|_, _| true).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.change_id, Some(Slot::new(1, 0)));
+        assert_eq!(report.key_count, 2);
+        assert_eq!(report.computed_hash, db.get_xof_db_hash());
+
+        let report = db.verify_integrity(&|key, _| key != [2]).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.stored_hash, report.computed_hash);
+        assert_eq!(report.invalid_key_count, 1);
+        assert_eq!(report.invalid_keys, vec![vec![2]]);
+
+        let handle = db.db.cf_handle(STATE_CF).unwrap();
+        db.db.put_cf(handle, [3], [30]).unwrap();
+        let report = db.verify_integrity(&|_, _| true).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.key_count, 3);
+        assert_eq!(report.stored_hash, db.get_xof_db_hash());
+        assert_ne!(report.stored_hash, report.computed_hash);
+    }
+
+    #[test]
+    fn test_metrics() {
+        // 1- Init a db, write two batches and read a key
+        // 2- Check the operations and the change history are accounted for
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(db_config);
+        let initial_metrics = db.get_metrics();
+
+        db.write_batch(
+            DBBatch::from([(vec![1], Some(vec![10]))]),
+            DBBatch::new(),
+            Some(Slot::new(1, 0)),
+        );
+        db.write_batch(
+            DBBatch::from([(vec![2], Some(vec![20]))]),
+            DBBatch::from([(vec![3], Some(vec![30]))]),
+            Some(Slot::new(2, 0)),
+        );
+        assert_eq!(
+            MassaDBController::get_cf(&db, STATE_CF, vec![1]).unwrap(),
+            Some(vec![10])
+        );
+        let _ = db.multi_get_cf(vec![(STATE_CF, vec![1]), (STATE_CF, vec![2])]);
+
+        let metrics = db.get_metrics();
+        assert_eq!(metrics.write_count, initial_metrics.write_count + 2);
+        assert_eq!(metrics.read_count, initial_metrics.read_count + 3);
+        assert_eq!(metrics.change_history_length, 2);
+        assert_eq!(metrics.change_history_bytes, db.change_history_bytes);
+        assert_eq!(metrics.change_history_versioning_length, 2);
+        let mut all_cfs = ALL_CFS.to_vec();
+        all_cfs.sort_unstable();
+        assert_eq!(metrics.cf_sst_sizes.keys().collect::<Vec<_>>(), all_cfs);
+    }
+
     #[test]
     fn test_db_stream() {
         // Init db + add data
@@ -1250,8 +2941,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let mut db_opts = MassaDB::default_db_opts();
         // Additional checks (only for testing)
@@ -1342,8 +3038,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let mut db_opts = MassaDB::default_db_opts();
         // Additional checks (only for testing)
@@ -1430,8 +3131,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let mut db_opts = MassaDB::default_db_opts();
         // Additional checks (only for testing)
@@ -1514,8 +3220,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let mut db_opts = MassaDB::default_db_opts();
         // Additional checks (only for testing)
@@ -1608,4 +3319,195 @@ mod test {
         assert!(stream_batch.new_elements.is_empty());
         assert!(stream_batch.updates_on_previous_elements.is_empty());
     }
+
+    #[test]
+    fn test_db_stream_max_bytes() {
+        // Init db + add data
+        // Stream new elements limited by size
+        // Update some values at several slots
+        // Stream updates limited by size, continuing from the last slot sent
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: Some(10),
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut db = MassaDB::new(db_config);
+
+        // 3 entries of 5 bytes each (at slot 1)
+        let slot_1 = Slot::new(1, 0);
+        let batch = DBBatch::from([
+            (vec![1], Some(vec![1; 4])),
+            (vec![2], Some(vec![2; 4])),
+            (vec![3], Some(vec![3; 4])),
+        ]);
+        db.write_batch(batch, DBBatch::new(), Some(slot_1));
+
+        // Only 2 entries fit in a batch
+        let stream_batch = db
+            .get_batch_to_stream(&StreamingStep::Started, None)
+            .unwrap();
+        assert_eq!(
+            stream_batch.new_elements,
+            BTreeMap::from([(vec![1], vec![1; 4]), (vec![2], vec![2; 4])])
+        );
+        assert_eq!(stream_batch.change_id, slot_1);
+
+        // Updates of 5, 5 and 10 bytes (at slots 2, 3 and 4)
+        let slot_2 = Slot::new(2, 0);
+        let slot_3 = Slot::new(3, 0);
+        let slot_4 = Slot::new(4, 0);
+        db.write_batch(
+            DBBatch::from([(vec![1], Some(vec![9; 4]))]),
+            DBBatch::new(),
+            Some(slot_2),
+        );
+        db.write_batch(
+            DBBatch::from([(vec![2], Some(vec![8; 4]))]),
+            DBBatch::new(),
+            Some(slot_3),
+        );
+        db.write_batch(
+            DBBatch::from([(vec![1], Some(vec![7; 9]))]),
+            DBBatch::new(),
+            Some(slot_4),
+        );
+
+        // The updates of slots 2 and 3 fill the batch, but one new element is still sent
+        let stream_batch = db
+            .get_batch_to_stream(&StreamingStep::Ongoing(vec![2]), Some(slot_1))
+            .unwrap();
+        assert_eq!(
+            stream_batch.updates_on_previous_elements,
+            BTreeMap::from([(vec![1], Some(vec![9; 4])), (vec![2], Some(vec![8; 4]))])
+        );
+        assert_eq!(
+            stream_batch.new_elements,
+            BTreeMap::from([(vec![3], vec![3; 4])])
+        );
+        assert_eq!(stream_batch.change_id, slot_3);
+
+        // The next batch continues from slot 3
+        let stream_batch = db
+            .get_batch_to_stream(&StreamingStep::Finished(None), Some(slot_3))
+            .unwrap();
+        assert_eq!(
+            stream_batch.updates_on_previous_elements,
+            BTreeMap::from([(vec![1], Some(vec![7; 9]))])
+        );
+        assert!(stream_batch.new_elements.is_empty());
+        assert_eq!(stream_batch.change_id, slot_4);
+    }
+
+    #[test]
+    fn test_db_stream_stripes() {
+        // Init a server db + add data
+        // Stream two stripes of it, with their updates
+        // Merge them in a client db and compare the state hashes
+
+        let temp_dir_server = tempdir().expect("Unable to create a temp folder");
+        let temp_dir_client = tempdir().expect("Unable to create a temp folder");
+        let db_config = |path: &Path| MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 100,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let mut server_db = MassaDB::new(db_config(temp_dir_server.path()));
+        let mut client_db = MassaDB::new(db_config(temp_dir_client.path()));
+
+        let slot_1 = Slot::new(1, 0);
+        let batch = DBBatch::from([
+            (vec![1], Some(vec![1])),
+            (vec![2], Some(vec![2])),
+            (vec![3], Some(vec![3])),
+            (vec![4], Some(vec![4])),
+        ]);
+        server_db.write_batch(batch, DBBatch::new(), Some(slot_1));
+
+        // Each stripe only streams its own keys
+        let split_key = vec![3];
+        let stripe_1 = server_db
+            .get_stripe_batch_to_stream(&[], Some(&split_key), &StreamingStep::Started, None)
+            .unwrap();
+        assert_eq!(
+            stripe_1.new_elements,
+            BTreeMap::from([(vec![1], vec![1]), (vec![2], vec![2])])
+        );
+        let stripe_2 = server_db
+            .get_stripe_batch_to_stream(&split_key, None, &StreamingStep::Started, None)
+            .unwrap();
+        assert_eq!(
+            stripe_2.new_elements,
+            BTreeMap::from([(vec![3], vec![3]), (vec![4], vec![4])])
+        );
+        assert_eq!(
+            client_db.write_stripe_bootstrap_client(stripe_1).unwrap(),
+            StreamingStep::Ongoing(vec![2])
+        );
+        assert_eq!(
+            client_db.write_stripe_bootstrap_client(stripe_2).unwrap(),
+            StreamingStep::Ongoing(vec![4])
+        );
+
+        // The updates are restricted to the keys of the stripe
+        let slot_2 = Slot::new(2, 0);
+        let batch = DBBatch::from([(vec![2], Some(vec![22])), (vec![4], None)]);
+        server_db.write_batch(batch, DBBatch::new(), Some(slot_2));
+        let stripe_1 = server_db
+            .get_stripe_batch_to_stream(
+                &[],
+                Some(&split_key),
+                &StreamingStep::Finished(None),
+                Some(slot_1),
+            )
+            .unwrap();
+        assert_eq!(
+            stripe_1.updates_on_previous_elements,
+            BTreeMap::from([(vec![2], Some(vec![22]))])
+        );
+        let stripe_2 = server_db
+            .get_stripe_batch_to_stream(
+                &split_key,
+                None,
+                &StreamingStep::Ongoing(vec![4]),
+                Some(slot_1),
+            )
+            .unwrap();
+        assert_eq!(
+            stripe_2.updates_on_previous_elements,
+            BTreeMap::from([(vec![4], None)])
+        );
+        assert!(stripe_2.new_elements.is_empty());
+        client_db.write_stripe_bootstrap_client(stripe_1).unwrap();
+        assert_eq!(
+            client_db.write_stripe_bootstrap_client(stripe_2).unwrap(),
+            StreamingStep::Finished(None)
+        );
+
+        // The merged state matches the one of the server, the change_id is left untouched
+        assert_eq!(client_db.get_xof_db_hash(), server_db.get_xof_db_hash());
+        assert_ne!(client_db.get_change_id().unwrap(), slot_2);
+
+        // A cursor out of the stripe is refused
+        let stream_batch = server_db.get_stripe_batch_to_stream(
+            &[],
+            Some(&split_key),
+            &StreamingStep::Ongoing(vec![4]),
+            Some(slot_2),
+        );
+        assert_matches!(stream_batch, Err(MassaDBError::StreamingError(..)));
+    }
 }
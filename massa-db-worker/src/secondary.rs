@@ -0,0 +1,67 @@
+//! Refresh of the read-only secondary instances of a `MassaDB`
+
+use massa_db_exports::{MassaDBCatchUpPolicy, ShareableMassaDBController};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use tracing::warn;
+
+/// Handle of the thread refreshing a secondary instance
+#[derive(Default)]
+pub struct MassaDBCatchUpHandle {
+    stopper: Option<Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl MassaDBCatchUpHandle {
+    /// Stops refreshing the secondary instance
+    pub fn stop(&mut self) {
+        if let Some(stopper) = self.stopper.take() {
+            // the thread may have already stopped
+            let _ = stopper.send(());
+            if let Some(join_handle) = self.join_handle.take() {
+                if join_handle.join().is_err() {
+                    warn!("failed to join the secondary db catch up thread");
+                }
+            }
+        }
+    }
+}
+
+impl Drop for MassaDBCatchUpHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Keeps a secondary instance up to date with its primary instance according to `policy`.
+///
+/// With `MassaDBCatchUpPolicy::Manual`, nothing is started and the caller is expected to call
+/// `catch_up_with_primary` itself, e.g. before serving a query that needs fresh data.
+pub fn start_catch_up(
+    secondary_db: ShareableMassaDBController,
+    policy: MassaDBCatchUpPolicy,
+) -> MassaDBCatchUpHandle {
+    let MassaDBCatchUpPolicy::Periodic(interval) = policy else {
+        return MassaDBCatchUpHandle::default();
+    };
+
+    let (stopper, stop_receiver) = channel();
+    let join_handle = std::thread::Builder::new()
+        .name("secondary_db_catch_up".to_string())
+        .spawn(move || loop {
+            match stop_receiver.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(e) = secondary_db.read().catch_up_with_primary() {
+                        warn!("secondary db failed to catch up with primary: {}", e);
+                    }
+                }
+                _ => break,
+            }
+        })
+        .expect("failed to spawn the secondary db catch up thread");
+
+    MassaDBCatchUpHandle {
+        stopper: Some(stopper),
+        join_handle: Some(join_handle),
+    }
+}
@@ -0,0 +1,157 @@
+//! Crash-recovery harness: a child process (this test binary, running `crash_recovery_writer`)
+//! writes batches to a `MassaDB` until it is killed mid-write, then the database is reopened
+//! and must be in the consistent state of the change_id it recovered.
+
+use crate::MassaDB;
+use massa_db_exports::{
+    DBBatch, Key, MassaDBConfig, MassaDBController, MassaDBTuning, MassaDBWalSync,
+    MassaIteratorMode, Value, STATE_CF,
+};
+use massa_models::{config::THREAD_COUNT, slot::Slot};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tempfile::tempdir;
+
+/// Path of the database written by the child process
+const DB_PATH_ENV: &str = "MASSA_DB_CRASH_RECOVERY_PATH";
+/// WAL sync policy of the child process, see `wal_sync_policy`
+const WAL_SYNC_ENV: &str = "MASSA_DB_CRASH_RECOVERY_WAL_SYNC";
+/// Line printed by the child process once it has written `READY_AFTER_WRITES` batches
+const WRITER_READY: &str = "crash recovery writer ready";
+const READY_AFTER_WRITES: u64 = 200;
+/// Number of distinct keys written, so that keys get overwritten and deleted
+const KEY_COUNT: u64 = 64;
+
+fn wal_sync_policy(name: &str) -> Option<MassaDBWalSync> {
+    match name {
+        "os" => None,
+        "every_batch" => Some(MassaDBWalSync::EveryBatch),
+        "periodic" => Some(MassaDBWalSync::Periodic(Duration::from_millis(10))),
+        "disabled" => Some(MassaDBWalSync::Disabled),
+        _ => panic!("unknown WAL sync policy: {}", name),
+    }
+}
+
+fn db_config(path: PathBuf, wal_sync: Option<MassaDBWalSync>) -> MassaDBConfig {
+    MassaDBConfig {
+        path,
+        max_history_length: 100,
+        max_history_bytes: None,
+        max_new_elements: 100,
+        max_stream_batch_bytes: None,
+        thread_count: THREAD_COUNT,
+        tuning: MassaDBTuning {
+            // small memtables, so that they get flushed while writing
+            write_buffer_size: Some(256 * 1024),
+            wal_sync,
+            ..Default::default()
+        },
+        backup_retention: Default::default(),
+        archival: Default::default(),
+    }
+}
+
+/// Changes written at `period`
+fn changes_at(period: u64) -> DBBatch {
+    let mut batch = DBBatch::new();
+    let value = period.to_be_bytes().repeat(128);
+    batch.insert((period % KEY_COUNT).to_be_bytes().to_vec(), Some(value));
+    if period % 3 == 0 {
+        let deleted_key = (period + KEY_COUNT / 2) % KEY_COUNT;
+        batch.insert(deleted_key.to_be_bytes().to_vec(), None);
+    }
+    batch
+}
+
+/// Not a test by itself: writes batches until killed when run by `crash_and_recover`
+#[test]
+fn crash_recovery_writer() {
+    let (Ok(path), Ok(wal_sync)) = (std::env::var(DB_PATH_ENV), std::env::var(WAL_SYNC_ENV)) else {
+        return;
+    };
+    let mut db = MassaDB::new(db_config(path.into(), wal_sync_policy(&wal_sync)));
+    for period in 1.. {
+        db.write_batch(
+            changes_at(period),
+            DBBatch::new(),
+            Some(Slot::new(period, 0)),
+        );
+        if period == READY_AFTER_WRITES {
+            println!("{}", WRITER_READY);
+        }
+    }
+}
+
+/// Kills a child process writing to a database with the given WAL sync policy,
+/// then checks that the database recovered the consistent state of a change_id
+fn crash_and_recover(wal_sync: &str) {
+    let temp_dir = tempdir().expect("Unable to create a temp folder");
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "tests::crash_recovery::crash_recovery_writer",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(DB_PATH_ENV, temp_dir.path())
+        .env(WAL_SYNC_ENV, wal_sync)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn the writer process");
+    let ready = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .any(|line| line.is_ok_and(|line| line == WRITER_READY));
+    assert!(ready, "the writer process exited before being ready");
+    // let it write some more, so that it is killed mid-write
+    std::thread::sleep(Duration::from_millis(100));
+    child.kill().expect("failed to kill the writer process");
+    child.wait().unwrap();
+
+    let db = MassaDB::new(db_config(
+        temp_dir.path().to_path_buf(),
+        wal_sync_policy(wal_sync),
+    ));
+    let report = db.verify_integrity(&|_, _| true).unwrap();
+    assert!(report.is_valid(), "state hash mismatch after recovery");
+    let recovered_period = report.change_id.unwrap().period;
+    if wal_sync != "disabled" {
+        // the acknowledged writes survive a process crash as long as the WAL is written
+        assert!(recovered_period >= READY_AFTER_WRITES);
+    }
+
+    // the state must be the one written up to the recovered change_id
+    let mut expected_state: BTreeMap<Key, Value> = BTreeMap::new();
+    for period in 1..=recovered_period {
+        for (key, value) in changes_at(period) {
+            match value {
+                Some(value) => expected_state.insert(key, value),
+                None => expected_state.remove(&key),
+            };
+        }
+    }
+    let state: BTreeMap<Key, Value> = db.iterator_cf(STATE_CF, MassaIteratorMode::Start).collect();
+    assert_eq!(state, expected_state);
+}
+
+#[test]
+fn test_crash_recovery_os_wal_sync() {
+    crash_and_recover("os");
+}
+
+#[test]
+fn test_crash_recovery_every_batch_wal_sync() {
+    crash_and_recover("every_batch");
+}
+
+#[test]
+fn test_crash_recovery_periodic_wal_sync() {
+    crash_and_recover("periodic");
+}
+
+#[test]
+fn test_crash_recovery_wal_disabled() {
+    crash_and_recover("disabled");
+}
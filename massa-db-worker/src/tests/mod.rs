@@ -0,0 +1 @@
+mod crash_recovery;
@@ -0,0 +1,76 @@
+//! Durability of the writes of a `MassaDB`: write-ahead log options and background syncing
+
+use massa_db_exports::MassaDBWalSync;
+use rocksdb::{WriteOptions, DB};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tracing::warn;
+
+/// Returns the options of the batch writes for the given durability policy
+pub(crate) fn wal_write_opts(policy: Option<MassaDBWalSync>) -> WriteOptions {
+    let mut write_opts = WriteOptions::default();
+    match policy {
+        Some(MassaDBWalSync::EveryBatch) => write_opts.set_sync(true),
+        Some(MassaDBWalSync::Disabled) => write_opts.disable_wal(true),
+        Some(MassaDBWalSync::Periodic(_)) | None => {}
+    }
+    write_opts
+}
+
+/// Handle of the thread periodically syncing the write-ahead log
+#[derive(Default)]
+pub(crate) struct WalSyncHandle {
+    stopper: Option<Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl WalSyncHandle {
+    /// Stops syncing the write-ahead log, after a last sync
+    pub(crate) fn stop(&mut self) {
+        if let Some(stopper) = self.stopper.take() {
+            // the thread may have already stopped
+            let _ = stopper.send(());
+            if let Some(join_handle) = self.join_handle.take() {
+                if join_handle.join().is_err() {
+                    warn!("failed to join the db WAL sync thread");
+                }
+            }
+        }
+    }
+}
+
+impl Drop for WalSyncHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Syncs the write-ahead log of `db` to disk periodically if required by `policy`
+pub(crate) fn start_wal_sync(db: Arc<DB>, policy: Option<MassaDBWalSync>) -> WalSyncHandle {
+    let Some(MassaDBWalSync::Periodic(interval)) = policy else {
+        return WalSyncHandle::default();
+    };
+
+    let (stopper, stop_receiver) = channel();
+    let join_handle = std::thread::Builder::new()
+        .name("db_wal_sync".to_string())
+        .spawn(move || loop {
+            let stop = !matches!(
+                stop_receiver.recv_timeout(interval),
+                Err(RecvTimeoutError::Timeout)
+            );
+            if let Err(e) = db.flush_wal(true) {
+                warn!("failed to sync the db WAL: {}", e);
+            }
+            if stop {
+                break;
+            }
+        })
+        .expect("failed to spawn the db WAL sync thread");
+
+    WalSyncHandle {
+        stopper: Some(stopper),
+        join_handle: Some(join_handle),
+    }
+}
@@ -246,8 +246,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config.clone())) as Box<(dyn MassaDBController + 'static)>
@@ -29,6 +29,17 @@ macro_rules! op_id_key {
     };
 }
 
+/// Execution of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpExecutionInfo {
+    /// true if the execution succeeded, false if it failed
+    pub success: bool,
+    /// slot at which the operation was executed.
+    /// None if unknown: the execution slot is not part of the state,
+    /// so it is only known for the operations executed since the node started.
+    pub slot: Option<Slot>,
+}
+
 /// A structure to list and prune previously executed operations
 #[derive(Clone)]
 pub struct ExecutedOps {
@@ -40,6 +51,8 @@ pub struct ExecutedOps {
     pub sorted_ops: BTreeMap<Slot, PreHashSet<OperationId>>,
     /// execution status of operations (true: success, false: fail)
     pub op_exec_status: HashMap<OperationId, bool>,
+    /// execution slot of the operations executed since the node started
+    pub op_exec_slot: HashMap<OperationId, Slot>,
     operation_id_deserializer: OperationIdDeserializer,
     operation_id_serializer: OperationIdSerializer,
     bool_deserializer: BoolDeserializer,
@@ -60,6 +73,7 @@ impl ExecutedOps {
             db,
             sorted_ops: BTreeMap::new(),
             op_exec_status: HashMap::new(),
+            op_exec_slot: HashMap::new(),
             operation_id_deserializer: OperationIdDeserializer::new(),
             operation_id_serializer: OperationIdSerializer::new(),
             bool_deserializer: BoolDeserializer::new(),
@@ -79,10 +93,27 @@ impl ExecutedOps {
             .collect()
     }
 
+    /// Get the execution status and slot of a set of operations.
+    /// Returns a list where each element is None if no execution was found for that op.
+    pub fn get_ops_exec_info(&self, batch: &[OperationId]) -> Vec<Option<OpExecutionInfo>> {
+        batch
+            .iter()
+            .map(|op_id| {
+                self.op_exec_status
+                    .get(op_id)
+                    .map(|success| OpExecutionInfo {
+                        success: *success,
+                        slot: self.op_exec_slot.get(op_id).copied(),
+                    })
+            })
+            .collect()
+    }
+
     /// Recomputes the local caches after bootstrap or loading the state from disk
     pub fn recompute_sorted_ops_and_op_exec_status(&mut self) {
         self.sorted_ops.clear();
         self.op_exec_status.clear();
+        self.op_exec_slot.clear();
 
         let db = self.db.read();
 
@@ -132,7 +163,8 @@ impl ExecutedOps {
         self.recompute_sorted_ops_and_op_exec_status();
     }
 
-    /// Apply speculative operations changes to the final executed operations state
+    /// Apply speculative operations changes to the final executed operations state.
+    /// The operations of `changes` were executed at `slot`.
     pub fn apply_changes_to_batch(
         &mut self,
         changes: ExecutedOpsChanges,
@@ -143,9 +175,9 @@ impl ExecutedOps {
             self.put_entry(id, value, batch);
         }
 
-        for (op_id, (op_exec_success, slot)) in changes {
+        for (op_id, (op_exec_success, expiry_slot)) in changes {
             self.sorted_ops
-                .entry(slot)
+                .entry(expiry_slot)
                 .and_modify(|ids| {
                     ids.insert(op_id);
                 })
@@ -155,6 +187,7 @@ impl ExecutedOps {
                     new
                 });
             self.op_exec_status.insert(op_id, op_exec_success);
+            self.op_exec_slot.insert(op_id, slot);
         }
 
         self.prune_to_batch(slot, batch);
@@ -190,6 +223,7 @@ impl ExecutedOps {
         for (_, ids) in removed {
             for op_id in ids {
                 self.op_exec_status.remove(&op_id);
+                self.op_exec_slot.remove(&op_id);
                 self.delete_entry(&op_id, batch);
             }
         }
@@ -298,8 +332,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config.clone())) as Box<(dyn MassaDBController + 'static)>
@@ -324,6 +363,16 @@ mod test {
         assert_eq!(exec_ops.sorted_ops.len(), 1);
         assert!(!exec_ops.contains(&op_id_1));
         assert!(exec_ops.contains(&op_id_2));
+        assert_eq!(
+            exec_ops.get_ops_exec_info(&[op_id_1, op_id_2]),
+            vec![
+                None,
+                Some(OpExecutionInfo {
+                    success: true,
+                    slot: Some(slot_2)
+                })
+            ]
+        );
 
         let sorted_ops_1 = exec_ops.sorted_ops.clone();
         drop(db);
@@ -337,6 +386,14 @@ mod test {
         let mut exec_ops2 = ExecutedOps::new(config.clone(), db2.clone());
         exec_ops2.recompute_sorted_ops_and_op_exec_status();
         assert_eq!(exec_ops2.sorted_ops, sorted_ops_1);
+        // the execution slot is not stored
+        assert_eq!(
+            exec_ops2.get_ops_exec_info(&[op_id_2]),
+            vec![Some(OpExecutionInfo {
+                success: true,
+                slot: None
+            })]
+        );
 
         // Reset cache
         exec_ops2.reset();
@@ -356,14 +413,24 @@ mod test {
         let db_a_config = MassaDBConfig {
             path: tempdir_a.path().to_path_buf(),
             max_history_length: 10,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db_c_config = MassaDBConfig {
             path: tempdir_c.path().to_path_buf(),
             max_history_length: 10,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
 
         let db_a = Arc::new(RwLock::new(
@@ -1,10 +1,13 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use crate::types::SlotExecutionOutput;
+use massa_pos_exports::CycleCompletion;
 
 /// channels used by the execution worker
 #[derive(Clone)]
 pub struct ExecutionChannels {
     /// Broadcast channel for new slot execution outputs
     pub slot_execution_output_sender: tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    /// Broadcast channel for completed cycles
+    pub cycle_completion_sender: tokio::sync::broadcast::Sender<CycleCompletion>,
 }
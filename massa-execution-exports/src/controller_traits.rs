@@ -3,20 +3,28 @@
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
 use crate::types::{
-    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
+    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, GasEstimation,
+    ReadOnlyExecutionRequest, SlotTransfers,
 };
-use crate::ExecutionError;
+use crate::{
+    AbiCallStats, BytecodeValidation, ExecutionCacheSizes, ExecutionError, OperationFailure,
+};
+use crate::{AsyncMessageFilter, AsyncPoolMessage};
 use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use massa_executed_ops::OpExecutionInfo;
+use massa_ledger_exports::LedgerChanges;
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
+use massa_models::execution::{EventFilter, TransferFilter};
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
-use massa_models::stats::ExecutionStats;
+use massa_models::stats::{DiskUsageForecast, ExecutionStats, ThroughputBucket};
+use massa_pos_exports::StakerDistribution;
+use massa_time::MassaTime;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
@@ -47,6 +55,23 @@ pub trait ExecutionController: Send + Sync {
     /// * operation id
     fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent>;
 
+    /// Get the latest slot of which final events were pruned, because of their age or of the store capacity.
+    /// Final events at or before this slot may be missing.
+    fn get_final_events_pruned_until(&self) -> Option<Slot>;
+
+    /// Get the coin transfers that happened at each executed slot between `start` and `end` (inclusive).
+    /// Final transfers are read from the on-disk store, within `final_transfers_retention_periods`,
+    /// followed by the candidate ones. At most `max_transfers_per_query` transfers are returned.
+    fn get_slot_transfers(&self, start: Slot, end: Slot) -> Vec<SlotTransfers>;
+
+    /// Get the coin transfers spending or crediting an address, grouped by slot, oldest first.
+    /// Final transfers are read from the on-disk index, followed by the candidate ones.
+    fn get_address_transfers(&self, filter: TransferFilter) -> Vec<SlotTransfers>;
+
+    /// Get the messages of the asynchronous pool matching a filter.
+    /// Unless final messages are requested, the candidate changes are applied on top of the final pool.
+    fn get_async_messages(&self, filter: AsyncMessageFilter) -> Vec<AsyncPoolMessage>;
+
     /// Get the final and active values of balance.
     ///
     /// # Return value
@@ -65,6 +90,34 @@ pub trait ExecutionController: Send + Sync {
     /// Otherwise, the status is a boolean indicating whether the execution was successful (true) or if there was an error (false.)
     fn get_ops_exec_status(&self, batch: &[OperationId]) -> Vec<(Option<bool>, Option<bool>)>;
 
+    /// Get the execution status and slot of a batch of operations.
+    ///
+    ///  Return value: vector of
+    ///  `(Option<speculative_execution>, Option<final_execution>)`
+    ///  If an Option is None it means that the op execution was not found.
+    ///  Note that old op executions are forgotten, and that the slot of final executions
+    ///  is only known for operations executed since the node started.
+    #[allow(clippy::type_complexity)]
+    fn get_ops_exec_info(
+        &self,
+        batch: &[OperationId],
+    ) -> Vec<(Option<OpExecutionInfo>, Option<OpExecutionInfo>)>;
+
+    /// Get the ledger changes caused by each operation of a batch, fees included.
+    /// Changes are only available for candidate executions
+    /// and for the last `max_final_operation_changes_slots` finalized slots.
+    fn get_ops_ledger_changes(&self, batch: &[OperationId]) -> Vec<Option<LedgerChanges>>;
+
+    /// Get the failure reason of each operation of a batch.
+    /// `None` is returned for the operations that succeeded or that were not executed
+    /// in the candidate slots or the last `max_final_operation_changes_slots` finalized slots.
+    fn get_ops_failures(&self, batch: &[OperationId]) -> Vec<Option<OperationFailure>>;
+
+    /// Get the call count and time of each host ABI function over the last `slot_count` executed slots,
+    /// most time-consuming functions first.
+    /// Empty if the profiling is disabled (`abi_profile_slots` set to 0).
+    fn get_abi_call_stats(&self, slot_count: usize) -> Vec<AbiCallStats>;
+
     /// Get a copy of a single datastore entry with its final and active values
     ///
     /// # Return value
@@ -81,6 +134,10 @@ pub trait ExecutionController: Send + Sync {
     /// By default it returns an empty map.
     fn get_cycle_active_rolls(&self, cycle: u64) -> BTreeMap<Address, u64>;
 
+    /// Returns the roll distribution statistics of every cycle in the final PoS history, oldest first.
+    /// `top_n` is the number of biggest stakers whose rolls are summed up.
+    fn get_staker_distribution_history(&self, top_n: usize) -> Vec<StakerDistribution>;
+
     /// Execute read-only SC function call without causing modifications to the consensus state
     ///
     /// # arguments
@@ -94,6 +151,49 @@ pub trait ExecutionController: Send + Sync {
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError>;
 
+    /// Execute a batch of read-only requests against the same state, in a single round trip to the execution worker.
+    /// Consecutive requests reading the same snapshot (same `is_final` and `historical_slot`) reuse the same speculative context.
+    ///
+    /// # arguments
+    /// * `reqs`: the read-only requests to execute, in order
+    ///
+    /// # returns
+    /// The output (or error) of each request in the order of the requests,
+    /// or an error if the batch could not be submitted to the execution worker
+    /// or if the total `max_gas` of its requests exceeds `max_read_only_gas`.
+    #[allow(clippy::type_complexity)]
+    fn execute_readonly_batch(
+        &self,
+        reqs: Vec<ReadOnlyExecutionRequest>,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError>;
+
+    /// Estimate the `max_gas` to give to an operation doing the same as a read-only request
+    ///
+    /// # arguments
+    /// * `req`: the read-only request to simulate, its `max_gas` being the upper bound of the search
+    ///
+    /// # returns
+    /// The gas estimation, or an error if the execution fails even with the full `max_gas` of the request.
+    fn estimate_gas(&self, req: ReadOnlyExecutionRequest) -> Result<GasEstimation, ExecutionError>;
+
+    /// Get the current sizes of the caches of the execution worker
+    fn get_cache_sizes(&self) -> ExecutionCacheSizes;
+
+    /// Resize the caches of the execution worker while it is running, evicting the entries in excess
+    ///
+    /// # arguments
+    /// * `sizes`: the new sizes of the caches
+    ///
+    /// # returns
+    /// An error if the new sizes are invalid, in which case no cache is resized
+    fn set_cache_sizes(&self, sizes: ExecutionCacheSizes) -> Result<(), ExecutionError>;
+
+    /// Check a bytecode against the limits of the execution engine without executing it
+    ///
+    /// # returns
+    /// The characteristics of the module and the issues preventing its execution, if any
+    fn validate_bytecode(&self, bytecode: &[u8]) -> BytecodeValidation;
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// (speculative, final)
     fn get_denunciation_execution_status(
@@ -107,6 +207,18 @@ pub trait ExecutionController: Send + Sync {
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats;
 
+    /// Get the number of final executed operations over the last `window`, split in buckets of `resolution`.
+    /// The window is capped to the configured history duration and the resolution can't be lower than one second.
+    fn get_throughput_history(
+        &self,
+        window: MassaTime,
+        resolution: MassaTime,
+    ) -> Vec<ThroughputBucket>;
+
+    /// Get the latest disk usage measurement of the final state and the forecast of when the disk will be full.
+    /// Returns None if no measurement was made yet.
+    fn get_disk_usage_forecast(&self) -> Option<DiskUsageForecast>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ExecutionController>`.
     fn clone_box(&self) -> Box<dyn ExecutionController>;
@@ -2,6 +2,7 @@
 
 //! this file defines all possible execution error categories
 
+use crate::OperationFailureCode;
 use displaydoc::Display;
 use massa_module_cache::error::CacheError;
 use massa_sc_runtime::VMError;
@@ -30,6 +31,9 @@ pub enum ExecutionError {
     /// `RollSell` error: {0}
     RollSellError(String),
 
+    /// `RollTransfer` error: {0}
+    RollTransferError(String),
+
     /// Slash roll or deferred credits  error: {0}
     SlashError(String),
 
@@ -48,6 +52,9 @@ pub enum ExecutionError {
     /// Given gas is above the threshold: {0}
     TooMuchGas(String),
 
+    /// Historical slot error: {0}
+    HistoricalSlotError(String),
+
     /// Include operation error: {0}
     IncludeOperationError(String),
 
@@ -69,9 +76,34 @@ pub enum ExecutionError {
     FactoryError(#[from] FactoryError),
 }
 
+impl ExecutionError {
+    /// Returns the category of the error, as reported in the failure reason of operations
+    pub fn failure_code(&self) -> OperationFailureCode {
+        match self {
+            // the VM and the compilation cost checks report gas exhaustion through their message
+            ExecutionError::VMError { error, .. }
+                if error.to_string().to_lowercase().contains("not enough gas") =>
+            {
+                OperationFailureCode::OutOfGas
+            }
+            ExecutionError::RuntimeError(msg) if msg.to_lowercase().contains("not enough gas") => {
+                OperationFailureCode::OutOfGas
+            }
+            ExecutionError::VMError { .. } => OperationFailureCode::ScriptFailure,
+            ExecutionError::RollBuyError(_)
+            | ExecutionError::RollSellError(_)
+            | ExecutionError::RollTransferError(_)
+            | ExecutionError::TransactionError(_) => OperationFailureCode::OperationRejected,
+            _ => OperationFailureCode::RuntimeError,
+        }
+    }
+}
+
 /// Execution query errors
 #[derive(Clone, Display, Error, Debug)]
 pub enum ExecutionQueryError {
     /// Not found: {0}
     NotFound(String),
+    /// Historical slot error: {0}
+    HistoricalSlotError(String),
 }
@@ -59,14 +59,19 @@ pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::{ExecutionError, ExecutionQueryError};
 pub use event_store::EventStore;
+pub use massa_executed_ops::OpExecutionInfo;
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
-    ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionOutput,
+    AbiCallStats, AbiTrace, AbiTraceCall, AsyncMessageFilter, AsyncPoolMessage, BytecodeDiagnostic,
+    BytecodeDiagnosticSeverity, BytecodeImport, BytecodeValidation, ExecutedBlockInfo,
+    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionCacheSizes, ExecutionOutput,
     ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
     ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
+    ExecutionQueryStakerInfo, ExecutionStackElement, GasEstimation, OperationFailure,
+    OperationFailureCode, ReadOnlyCallRequest, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, ReadOnlyStateOverride, SlotExecutionOutput, SlotTransfers,
+    TransferInfo, TransferOrigin,
 };
 
 #[cfg(any(feature = "testing", feature = "gas_calibration"))]
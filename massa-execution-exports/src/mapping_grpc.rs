@@ -9,6 +9,7 @@ use crate::{
 };
 use grpc_api::execution_query_request_item as exec;
 use massa_models::address::Address;
+use massa_models::datastore::DatastoreKeysFilter;
 use massa_models::error::ModelsError;
 use massa_models::execution::EventFilter;
 use massa_models::mapping_grpc::to_denunciation_index;
@@ -50,14 +51,15 @@ pub fn to_querystate_filter(
                 ))
             }
             exec::RequestItem::AddressDatastoreKeysCandidate(value) => {
-                Ok(ExecutionQueryRequestItem::AddressBytecodeCandidate(
-                    Address::from_str(&value.address)?,
-                ))
+                Ok(ExecutionQueryRequestItem::AddressDatastoreKeysCandidate {
+                    addr: Address::from_str(&value.address)?,
+                    filter: DatastoreKeysFilter::from_prefix(&value.prefix),
+                })
             }
             exec::RequestItem::AddressDatastoreKeysFinal(value) => {
                 Ok(ExecutionQueryRequestItem::AddressDatastoreKeysFinal {
                     addr: Address::from_str(&value.address)?,
-                    prefix: value.prefix,
+                    filter: DatastoreKeysFilter::from_prefix(&value.prefix),
                 })
             }
             exec::RequestItem::AddressDatastoreValueCandidate(value) => {
@@ -349,6 +351,11 @@ impl From<ExecutionQueryError> for grpc_model::Error {
                 code: 404,
                 message: error,
             },
+            ExecutionQueryError::HistoricalSlotError(error) => grpc_model::Error {
+                //TODO to be defined
+                code: 400,
+                message: error,
+            },
         }
     }
 }
@@ -24,8 +24,18 @@ pub struct StorageCostsConstants {
 pub struct ExecutionConfig {
     /// read-only execution request queue length
     pub readonly_queue_length: usize,
-    /// maximum number of SC output events kept in cache
+    /// maximum number of SC output events returned by a single query
     pub max_final_events: usize,
+    /// number of periods for which final SC output events are kept on disk
+    pub final_events_retention_periods: u64,
+    /// maximum number of final SC output events kept on disk, the oldest ones being pruned first
+    pub final_events_capacity: u64,
+    /// number of periods for which the final coin transfers are kept and indexed by address on disk (0 disables the store)
+    pub final_transfers_retention_periods: u64,
+    /// maximum number of coin transfers returned by a single query of the transfers of an address
+    pub max_transfers_per_query: usize,
+    /// number of final slots for which the ledger changes and failure reasons of each operation are kept in cache
+    pub max_final_operation_changes_slots: usize,
     /// maximum available gas for asynchronous messages execution
     pub max_async_gas: u64,
     /// maximum gas per block
@@ -50,8 +60,21 @@ pub struct ExecutionConfig {
     pub periods_per_cycle: u64,
     /// duration of the statistics time window
     pub stats_time_window_duration: MassaTime,
+    /// duration of the final operations throughput history
+    pub stats_history_duration: MassaTime,
+    /// interval between two measurements of the final state disk usage
+    pub disk_usage_sample_interval: MassaTime,
+    /// time window over which the final state disk growth rate is averaged
+    pub disk_usage_forecast_window: MassaTime,
+    /// an alert is raised when the disk is forecast to be full within this duration
+    pub disk_usage_alert_threshold: MassaTime,
+    /// optional HTTP URL to which the disk usage alerts are posted as JSON
+    pub disk_usage_alert_webhook: Option<String>,
     /// Max miss ratio for auto roll sell
     pub max_miss_ratio: Ratio<u64>,
+    /// Number of previous cycles that must also be above `max_miss_ratio` for the auto roll sell,
+    /// once the roll sale grace period network version is active
+    pub roll_sale_grace_cycles: u64,
     /// Max function length in call sc
     pub max_function_length: u16,
     /// Max parameter length in call sc
@@ -66,6 +89,8 @@ pub struct ExecutionConfig {
     pub storage_costs_constants: StorageCostsConstants,
     /// Max gas for read only executions
     pub max_read_only_gas: u64,
+    /// Safety margin added to gas estimations, in percent of the required gas
+    pub gas_estimation_margin_percent: u64,
     /// Gas costs
     pub gas_costs: GasCosts,
     /// last start period, used to attach to the correct execution slot if the network has restarted
@@ -86,6 +111,15 @@ pub struct ExecutionConfig {
     pub broadcast_enabled: bool,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// completed cycles channel capacity
+    pub broadcast_cycle_completion_channel_capacity: usize,
+    /// whether the ledger changes of each operation are included in the slot execution outputs broadcast
+    pub broadcast_operation_changes: bool,
+    /// maximum time the execution waits for the slowest subscriber when the slot execution outputs channel is full,
+    /// before dropping the oldest output (0 drops it right away)
+    pub broadcast_backpressure_timeout: MassaTime,
+    /// number of executed slots for which the host ABI call counters are kept (0 disables the profiling)
+    pub abi_profile_slots: usize,
     /// max size of event data, in bytes
     pub max_event_size: usize,
 }
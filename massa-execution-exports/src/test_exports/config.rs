@@ -22,6 +22,11 @@ impl Default for ExecutionConfig {
         Self {
             readonly_queue_length: 100,
             max_final_events: 1000,
+            final_events_retention_periods: 1000,
+            final_events_capacity: 100_000,
+            final_transfers_retention_periods: 1000,
+            max_transfers_per_query: 1000,
+            max_final_operation_changes_slots: 1000,
             max_async_gas: MAX_ASYNC_GAS,
             thread_count: THREAD_COUNT,
             roll_price: ROLL_PRICE,
@@ -35,12 +40,19 @@ impl Default for ExecutionConfig {
             genesis_timestamp: MassaTime::now().expect("Impossible to reset the timestamp in test"),
             t0: MassaTime::from_millis(64),
             stats_time_window_duration: MassaTime::from_millis(30000),
+            stats_history_duration: MassaTime::from_millis(3600000),
+            disk_usage_sample_interval: MassaTime::from_millis(1000),
+            disk_usage_forecast_window: MassaTime::from_millis(60000),
+            disk_usage_alert_threshold: MassaTime::from_millis(604800000),
+            disk_usage_alert_webhook: None,
             max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
+            roll_sale_grace_cycles: 1,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
             max_bytecode_size: MAX_BYTECODE_LENGTH,
             max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
             storage_costs_constants,
             max_read_only_gas: 100_000_000,
+            gas_estimation_margin_percent: 10,
             gas_costs: GasCosts::new(
                 concat!(
                     env!("CARGO_MANIFEST_DIR"),
@@ -63,6 +75,10 @@ impl Default for ExecutionConfig {
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             broadcast_enabled: true,
             broadcast_slot_execution_output_channel_capacity: 5000,
+            broadcast_operation_changes: true,
+            broadcast_backpressure_timeout: MassaTime::from_millis(0),
+            abi_profile_slots: 10,
+            broadcast_cycle_completion_channel_capacity: 100,
             max_event_size: 50_000,
             max_function_length: 1000,
             max_parameter_length: 1000,
@@ -4,22 +4,25 @@
 
 use crate::error::ExecutionQueryError;
 use crate::event_store::EventStore;
+use massa_async_pool::{AsyncMessage, AsyncMessageId};
 use massa_final_state::StateChanges;
 use massa_hash::Hash;
+use massa_ledger_exports::LedgerChanges;
 use massa_models::block_id::BlockId;
 use massa_models::bytecode::Bytecode;
-use massa_models::datastore::Datastore;
+use massa_models::datastore::{Datastore, DatastoreKeysFilter};
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
-use massa_models::prehash::PreHashSet;
+use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::{
     address::Address, address::ExecutionAddressCycleInfo, amount::Amount, slot::Slot,
 };
 use massa_pos_exports::ProductionStats;
 use massa_storage::Storage;
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
 /// Metadata needed to execute the block
 #[derive(Clone, Debug)]
@@ -66,15 +69,15 @@ pub enum ExecutionQueryRequestItem {
     AddressDatastoreKeysCandidate {
         /// Address for which to query the datastore
         addr: Address,
-        /// Filter only entries whose key starts with a prefix
-        prefix: Vec<u8>,
+        /// Selection of the keys: prefix, key range and page
+        filter: DatastoreKeysFilter,
     },
     /// gets the datastore keys (final) of an address, returns ExecutionQueryResponseItem::KeyList(keys) or an error if the address is not found
     AddressDatastoreKeysFinal {
         /// Address for which to query the datastore
         addr: Address,
-        /// Filter only entries whose key starts with a prefix
-        prefix: Vec<u8>,
+        /// Selection of the keys: prefix, key range and page
+        filter: DatastoreKeysFilter,
     },
     /// gets a datastore value (candidate) for an address, returns ExecutionQueryResponseItem::DatastoreValue(keys) or an error if the address or key is not found
     AddressDatastoreValueCandidate {
@@ -90,6 +93,22 @@ pub enum ExecutionQueryRequestItem {
         /// Key of the entry
         key: Vec<u8>,
     },
+    /// gets the balance of an address at the end of a past final slot, returns ExecutionQueryResponseItem::Amount(balance) or an error if the address is not found or the slot is not archived
+    AddressBalanceHistorical {
+        /// Address for which to query the balance
+        addr: Address,
+        /// Past final slot at the end of which the balance is read
+        slot: Slot,
+    },
+    /// gets a datastore value for an address at the end of a past final slot, returns ExecutionQueryResponseItem::DatastoreValue(value) or an error if the address or key is not found or the slot is not archived
+    AddressDatastoreValueHistorical {
+        /// Address for which to query the datastore
+        addr: Address,
+        /// Key of the entry
+        key: Vec<u8>,
+        /// Past final slot at the end of which the entry is read
+        slot: Slot,
+    },
 
     /// gets the execution status (candidate) for an operation, returns ExecutionQueryResponseItem::ExecutionStatus(status)
     OpExecutionStatusCandidate(OperationId),
@@ -235,6 +254,150 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// coin transfers that happened during the execution step
+    pub transfers: Vec<TransferInfo>,
+    /// ledger changes caused by each operation executed during the execution step, fees included
+    pub operation_changes: PreHashMap<OperationId, LedgerChanges>,
+    /// reason of the failure of each operation that failed during the execution step
+    pub operation_failures: PreHashMap<OperationId, OperationFailure>,
+}
+
+/// Category of the error that made an operation execution fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationFailureCode {
+    /// the execution ran out of gas
+    OutOfGas,
+    /// the smart contract code failed (assertion failure, abort, invalid ABI call...)
+    ScriptFailure,
+    /// the coin or roll operation could not be applied (insufficient balance or rolls...)
+    OperationRejected,
+    /// any other execution error
+    RuntimeError,
+}
+
+/// Structured reason of an operation execution failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationFailure {
+    /// category of the error
+    pub code: OperationFailureCode,
+    /// full error message
+    pub message: String,
+    /// address of the smart contract being executed when the error happened, if any
+    pub address: Option<Address>,
+    /// gas remaining when the error happened, if known
+    pub remaining_gas: Option<u64>,
+}
+
+/// Filter used when listing the messages of the asynchronous pool
+#[derive(Debug, Clone, Default)]
+pub struct AsyncMessageFilter {
+    /// optional sender address
+    pub sender: Option<Address>,
+    /// optional destination address
+    pub destination: Option<Address>,
+    /// optional emission slot lower bound (included)
+    pub emission_start: Option<Slot>,
+    /// optional emission slot upper bound (excluded)
+    pub emission_end: Option<Slot>,
+    /// optional slot that must be within the validity window of the message
+    pub valid_at: Option<Slot>,
+    /// optional message status
+    ///
+    /// Some(true) means messages of the final pool, without the candidate changes
+    /// Some(false) means messages emitted or updated in candidate slots
+    /// None means the final pool with the candidate changes applied
+    pub is_final: Option<bool>,
+}
+
+impl AsyncMessageFilter {
+    /// Returns true if the emission slot and the validity window of a message match the filter
+    pub fn matches_slots(&self, emission_slot: Slot, validity: (Slot, Slot)) -> bool {
+        if let Some(start) = self.emission_start {
+            if emission_slot < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.emission_end {
+            if emission_slot >= end {
+                return false;
+            }
+        }
+        if let Some(slot) = self.valid_at {
+            // the validity end is not included in the validity window
+            if slot < validity.0 || slot >= validity.1 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns true if the message matches the filter
+    pub fn matches(&self, message: &AsyncMessage) -> bool {
+        if let Some(sender) = self.sender {
+            if message.sender != sender {
+                return false;
+            }
+        }
+        if let Some(destination) = self.destination {
+            if message.destination != destination {
+                return false;
+            }
+        }
+        self.matches_slots(
+            message.emission_slot,
+            (message.validity_start, message.validity_end),
+        )
+    }
+}
+
+/// A message of the asynchronous pool
+#[derive(Debug, Clone)]
+pub struct AsyncPoolMessage {
+    /// message id
+    pub id: AsyncMessageId,
+    /// message
+    pub message: AsyncMessage,
+    /// whether the message is in the final pool and unchanged by the candidate slots
+    pub is_final: bool,
+}
+
+/// Cause of a native coin transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferOrigin {
+    /// execution of an operation (including the fee debit)
+    Operation(OperationId),
+    /// execution or cancellation of an asynchronous message
+    AsyncMessage,
+    /// block production and endorsement rewards
+    BlockReward,
+    /// deferred credits (roll sales for example)
+    DeferredCredit,
+    /// any other cause (read-only executions for example)
+    Other,
+}
+
+/// A native coin transfer that happened during the execution of a slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferInfo {
+    /// spending address (None for coin creation)
+    pub from: Option<Address>,
+    /// credited address (None for coin destruction)
+    pub to: Option<Address>,
+    /// amount of coins transferred
+    pub amount: Amount,
+    /// cause of the transfer
+    pub origin: TransferOrigin,
+}
+
+/// Coin transfers that happened at a given slot
+#[derive(Debug, Clone)]
+pub struct SlotTransfers {
+    /// slot
+    pub slot: Slot,
+    /// whether the slot execution is final
+    pub is_final: bool,
+    /// transfers, in execution order
+    pub transfers: Vec<TransferInfo>,
 }
 
 /// structure describing the output of a read only execution
@@ -246,6 +409,165 @@ pub struct ReadOnlyExecutionOutput {
     pub gas_cost: u64,
     /// Returned value from the module call
     pub call_result: Vec<u8>,
+    /// ABI calls made by the execution, in order, if tracing was requested
+    pub trace: Option<Vec<AbiTrace>>,
+}
+
+/// Profiling counters of a host ABI function over the last executed slots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiCallStats {
+    /// name of the host ABI function
+    pub function: String,
+    /// number of calls
+    pub call_count: u64,
+    /// total wall time spent in the calls
+    pub total_time: Duration,
+}
+
+/// ABI call recorded during a traced read-only execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiTrace {
+    /// depth of the call stack when the call happened
+    pub depth: usize,
+    /// recorded call
+    pub call: AbiTraceCall,
+}
+
+/// Kind of ABI call recorded during a traced read-only execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiTraceCall {
+    /// call to another smart contract: the following records until the matching `CallEnd` happen inside it
+    CallStart {
+        /// called address
+        target: Address,
+        /// coins transferred to the called address
+        coins: Amount,
+    },
+    /// end of the innermost call
+    CallEnd,
+    /// coin transfer, including the fee and coins paid before the execution
+    Transfer {
+        /// spending address (None for coin creation)
+        from: Option<Address>,
+        /// credited address (None for coin destruction)
+        to: Option<Address>,
+        /// amount of coins transferred
+        amount: Amount,
+    },
+    /// datastore read
+    DatastoreRead {
+        /// address owning the datastore
+        address: Address,
+        /// key of the entry
+        key: Vec<u8>,
+        /// value read, None if the entry does not exist
+        value: Option<Vec<u8>>,
+    },
+    /// datastore write, creating the entry if it does not exist
+    DatastoreWrite {
+        /// address owning the datastore
+        address: Address,
+        /// key of the entry
+        key: Vec<u8>,
+        /// new value of the entry
+        value: Vec<u8>,
+    },
+    /// datastore append
+    DatastoreAppend {
+        /// address owning the datastore
+        address: Address,
+        /// key of the entry
+        key: Vec<u8>,
+        /// appended value
+        value: Vec<u8>,
+    },
+    /// datastore entry deletion
+    DatastoreDelete {
+        /// address owning the datastore
+        address: Address,
+        /// key of the entry
+        key: Vec<u8>,
+    },
+    /// event emission
+    Event {
+        /// data of the event
+        data: String,
+    },
+}
+
+/// Gas needed by a read-only execution request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasEstimation {
+    /// gas consumed by the execution when run with the full `max_gas` of the request
+    pub gas_cost: u64,
+    /// smallest `max_gas` found for which the execution succeeds
+    pub required_gas: u64,
+    /// `required_gas` increased by the safety margin, to be used as the `max_gas` of the operation
+    pub estimated_max_gas: u64,
+}
+
+/// Sizes of the caches of the execution worker, that can be changed while the node is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionCacheSizes {
+    /// maximum number of compiled modules kept in RAM
+    pub module_lru_cache_size: u32,
+    /// maximum number of compiled modules kept on disk
+    pub module_hd_cache_size: usize,
+    /// number of final slots for which the ledger changes and failure reasons of each operation are kept in RAM
+    pub max_final_operation_changes_slots: usize,
+}
+
+/// Severity of an issue found while validating a bytecode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BytecodeDiagnosticSeverity {
+    /// the bytecode cannot be executed by the node
+    Error,
+    /// the bytecode can be executed but may behave unexpectedly
+    Warning,
+}
+
+/// Issue found while validating a bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytecodeDiagnostic {
+    /// severity of the issue
+    pub severity: BytecodeDiagnosticSeverity,
+    /// description of the issue
+    pub message: String,
+}
+
+/// Function imported by a bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytecodeImport {
+    /// module from which the function is imported
+    pub module: String,
+    /// name of the imported function
+    pub name: String,
+}
+
+/// Result of the static validation of a bytecode against the execution engine limits
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BytecodeValidation {
+    /// size of the bytecode in bytes
+    pub bytecode_size: u64,
+    /// functions imported by the module
+    pub imports: Vec<BytecodeImport>,
+    /// names of the functions exported by the module
+    pub exports: Vec<String>,
+    /// number of functions defined by the module
+    pub function_count: u64,
+    /// initial and maximum number of 64KiB pages of the memory, if any is declared or imported
+    pub memory_pages: Option<(u64, Option<u64>)>,
+    /// issues found in the bytecode
+    pub diagnostics: Vec<BytecodeDiagnostic>,
+}
+
+impl BytecodeValidation {
+    /// Returns true if no error was found in the bytecode
+    pub fn is_valid(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .all(|d| d.severity != BytecodeDiagnosticSeverity::Error)
+    }
 }
 
 /// structure describing different types of read-only execution request
@@ -265,6 +587,25 @@ pub struct ReadOnlyExecutionRequest {
     ///
     /// Whether to start execution from final or active state
     pub is_final: bool,
+    /// Past final slot at the end of which the ledger is read, instead of the final or active one.
+    /// Requires the archival mode, other parts of the state are read as usual.
+    pub historical_slot: Option<Slot>,
+    /// Temporary state of some addresses, applied on top of the start state for this execution only
+    pub state_overrides: BTreeMap<Address, ReadOnlyStateOverride>,
+    /// Whether to record the ABI calls made by the execution (see `ReadOnlyExecutionOutput::trace`)
+    pub trace: bool,
+}
+
+/// Temporary state of an address applied for a single read-only execution.
+/// The address is created if it does not exist.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadOnlyStateOverride {
+    /// balance replacing the current one
+    pub balance: Option<Amount>,
+    /// bytecode replacing the current one
+    pub bytecode: Option<Bytecode>,
+    /// datastore entries to set (`Some`) or delete (`None`)
+    pub datastore: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
 }
 
 /// structure describing different possible targets of a read-only execution request
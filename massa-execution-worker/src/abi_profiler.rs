@@ -0,0 +1,117 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module accumulates profiling counters of the host ABI functions called during slot executions.
+//!
+//! Each call is timed by `InterfaceImpl` and recorded for the slot being executed.
+//! The counters of the last executed slots are kept in memory so that core developers
+//! can see which host functions dominate the execution time.
+//! The profiler has its own lock: it never waits for the execution context lock.
+
+use massa_execution_exports::AbiCallStats;
+use massa_models::slot::Slot;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// call count and total wall time of each host ABI function
+type AbiCounters = BTreeMap<&'static str, (u64, Duration)>;
+
+/// Profiler of the host ABI calls, shared between the execution state and the interface
+pub type SharedAbiProfiler = Arc<Mutex<AbiProfiler>>;
+
+/// Profiling counters of the host ABI functions
+pub struct AbiProfiler {
+    /// number of executed slots for which the counters are kept
+    max_slots: usize,
+    /// counters of the slot being executed
+    current: AbiCounters,
+    /// counters of the last executed slots, oldest at the front
+    history: VecDeque<(Slot, AbiCounters)>,
+}
+
+impl AbiProfiler {
+    /// Creates a profiler keeping the counters of the last `max_slots` executed slots
+    pub fn new(max_slots: usize) -> Self {
+        AbiProfiler {
+            max_slots,
+            current: Default::default(),
+            history: Default::default(),
+        }
+    }
+
+    /// Records a call to a host ABI function
+    pub fn record(&mut self, function: &'static str, time: Duration) {
+        let (count, total_time) = self.current.entry(function).or_default();
+        *count = count.saturating_add(1);
+        *total_time = total_time.saturating_add(time);
+    }
+
+    /// Discards the calls recorded outside of a slot execution (read-only executions for example)
+    pub fn start_slot(&mut self) {
+        self.current.clear();
+    }
+
+    /// Moves the counters of the executed slot to the history
+    pub fn settle_slot(&mut self, slot: Slot) {
+        if self.max_slots == 0 {
+            return;
+        }
+        self.history
+            .push_back((slot, std::mem::take(&mut self.current)));
+        while self.history.len() > self.max_slots {
+            self.history.pop_front();
+        }
+    }
+
+    /// Aggregates the counters of the last `slot_count` executed slots, most time-consuming functions first
+    pub fn get_call_stats(&self, slot_count: usize) -> Vec<AbiCallStats> {
+        let mut aggregated = AbiCounters::new();
+        for (_slot, counters) in self.history.iter().rev().take(slot_count) {
+            for (function, (count, time)) in counters {
+                let (total_count, total_time) = aggregated.entry(function).or_default();
+                *total_count = total_count.saturating_add(*count);
+                *total_time = total_time.saturating_add(*time);
+            }
+        }
+        let mut stats: Vec<AbiCallStats> = aggregated
+            .into_iter()
+            .map(|(function, (call_count, total_time))| AbiCallStats {
+                function: function.to_string(),
+                call_count,
+                total_time,
+            })
+            .collect();
+        stats.sort_by(|a, b| b.total_time.cmp(&a.total_time));
+        stats
+    }
+}
+
+/// Measures the wall time of a host ABI call, recorded in the profiler when dropped
+pub struct AbiCallTimer<'a> {
+    /// profiler recording the call
+    profiler: &'a SharedAbiProfiler,
+    /// name of the called function
+    function: &'static str,
+    /// instant at which the call started
+    start: Instant,
+}
+
+impl<'a> AbiCallTimer<'a> {
+    /// Starts measuring a call to `function`
+    pub fn new(profiler: &'a SharedAbiProfiler, function: &'static str) -> Self {
+        AbiCallTimer {
+            profiler,
+            function,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for AbiCallTimer<'_> {
+    fn drop(&mut self) {
+        self.profiler
+            .lock()
+            .record(self.function, self.start.elapsed());
+    }
+}
@@ -1,4 +1,5 @@
 use massa_async_pool::{AsyncMessage, AsyncMessageId, AsyncMessageUpdate};
+use massa_executed_ops::OpExecutionInfo;
 use massa_execution_exports::ExecutionOutput;
 use massa_ledger_exports::{
     Applicable, LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
@@ -325,6 +326,15 @@ impl ActiveHistory {
     /// Returns a list where each element is None if no execution was found for that op,
     /// or a boolean indicating whether the execution was successful (true) or had an error (false).
     pub fn get_ops_exec_status(&self, batch: &[OperationId]) -> Vec<Option<bool>> {
+        self.get_ops_exec_info(batch)
+            .into_iter()
+            .map(|info| info.map(|info| info.success))
+            .collect()
+    }
+
+    /// Get the execution statuses and slots of a set of operations.
+    /// Returns a list where each element is None if no execution was found for that op.
+    pub fn get_ops_exec_info(&self, batch: &[OperationId]) -> Vec<Option<OpExecutionInfo>> {
         let mut to_find: PreHashSet<OperationId> = batch.iter().copied().collect();
         let mut found = PreHashMap::with_capacity(to_find.len());
         for hist_item in self.0.iter().rev() {
@@ -332,7 +342,13 @@ impl ActiveHistory {
                 if let Some((success, _expiry_slot)) =
                     hist_item.state_changes.executed_ops_changes.get(op_id)
                 {
-                    found.insert(*op_id, *success);
+                    found.insert(
+                        *op_id,
+                        OpExecutionInfo {
+                            success: *success,
+                            slot: Some(hist_item.slot),
+                        },
+                    );
                     false
                 } else {
                     true
@@ -0,0 +1,416 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module statically inspects a bytecode before it is compiled by the execution engine.
+//!
+//! Only the sections needed to check the module against the engine limits are decoded
+//! (types, imports, functions, memories, globals, exports and the locals of the code section),
+//! the instructions themselves are checked by the engine when compiling the module.
+
+use massa_execution_exports::{
+    BytecodeDiagnostic, BytecodeDiagnosticSeverity, BytecodeImport, BytecodeValidation,
+};
+
+/// magic number and version at the start of every WebAssembly module
+const WASM_HEADER: [u8; 8] = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+
+/// prefix byte of the modules using the wasmv1 ABI
+const WASMV1_PREFIX: u8 = 0x01;
+
+/// modules from which the execution engine provides imported functions
+const ALLOWED_IMPORT_MODULES: [&str; 2] = ["massa", "env"];
+
+/// maximum number of 64KiB pages of a 32-bit memory
+const MAX_MEMORY_PAGES: u64 = 65536;
+
+/// value types of the floating-point numbers
+const FLOAT_VALUE_TYPES: [u8; 2] = [0x7D, 0x7C];
+
+/// Reads the sections of a WebAssembly module
+struct WasmReader<'a> {
+    /// bytes of the module
+    bytes: &'a [u8],
+    /// position of the next byte to read
+    pos: usize,
+}
+
+impl<'a> WasmReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        WasmReader { bytes, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| format!("unexpected end of module at offset {}", self.pos))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("unexpected end of module at offset {}", self.pos))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned LEB128 integer
+    fn u64(&mut self) -> Result<u64, String> {
+        let mut result: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift >= 64 {
+                return Err(format!("integer too large at offset {}", self.pos));
+            }
+            result |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Skips a signed LEB128 integer
+    fn skip_signed(&mut self) -> Result<(), String> {
+        while self.byte()? & 0x80 != 0 {}
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let len = self.u64()? as usize;
+        let offset = self.pos;
+        String::from_utf8(self.bytes(len)?.to_vec())
+            .map_err(|_| format!("invalid UTF-8 name at offset {}", offset))
+    }
+
+    /// Reads memory limits, returns the initial and maximum pages and the flags
+    fn limits(&mut self) -> Result<(u64, Option<u64>, u8), String> {
+        let flags = self.byte()?;
+        let initial = self.u64()?;
+        let maximum = if flags & 0x01 != 0 {
+            Some(self.u64()?)
+        } else {
+            None
+        };
+        Ok((initial, maximum, flags))
+    }
+
+    /// Skips a constant expression, as found in global initializers
+    fn skip_const_expr(&mut self) -> Result<(), String> {
+        loop {
+            match self.byte()? {
+                0x0B => return Ok(()),
+                0x41 | 0x42 => self.skip_signed()?,
+                0x43 => {
+                    self.bytes(4)?;
+                }
+                0x44 => {
+                    self.bytes(8)?;
+                }
+                0x23 | 0xD2 => {
+                    self.u64()?;
+                }
+                0xD0 => {
+                    self.byte()?;
+                }
+                opcode => {
+                    return Err(format!(
+                        "unsupported opcode 0x{:02X} in constant expression at offset {}",
+                        opcode,
+                        self.pos - 1
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Statically inspects a bytecode, without compiling it
+///
+/// # arguments
+/// * `bytecode`: the bytecode to inspect, with its ABI prefix if any
+/// * `max_bytecode_size`: maximum size of a bytecode accepted by the node
+pub fn inspect_bytecode(bytecode: &[u8], max_bytecode_size: u64) -> BytecodeValidation {
+    let mut validation = BytecodeValidation {
+        bytecode_size: bytecode.len() as u64,
+        ..Default::default()
+    };
+    if validation.bytecode_size > max_bytecode_size {
+        error(
+            &mut validation,
+            format!(
+                "bytecode size {} exceeds the maximum of {} bytes",
+                validation.bytecode_size, max_bytecode_size
+            ),
+        );
+        return validation;
+    }
+    let module = match bytecode.split_first() {
+        Some((&WASMV1_PREFIX, module)) => module,
+        _ => bytecode,
+    };
+    if !module.starts_with(&WASM_HEADER) {
+        error(
+            &mut validation,
+            "bytecode is not a WebAssembly module of version 1".to_string(),
+        );
+        return validation;
+    }
+    if let Err(err) = inspect_sections(&module[WASM_HEADER.len()..], &mut validation) {
+        error(&mut validation, format!("malformed module: {}", err));
+    }
+    validation
+}
+
+/// Adds an error to the diagnostics
+fn error(validation: &mut BytecodeValidation, message: String) {
+    validation.diagnostics.push(BytecodeDiagnostic {
+        severity: BytecodeDiagnosticSeverity::Error,
+        message,
+    });
+}
+
+/// Adds a warning to the diagnostics
+fn warning(validation: &mut BytecodeValidation, message: String) {
+    validation.diagnostics.push(BytecodeDiagnostic {
+        severity: BytecodeDiagnosticSeverity::Warning,
+        message,
+    });
+}
+
+/// Checks memory limits and records them
+fn check_memory(
+    validation: &mut BytecodeValidation,
+    (initial, maximum, flags): (u64, Option<u64>, u8),
+) {
+    if flags & 0x02 != 0 {
+        error(validation, "shared memories are not supported".to_string());
+    }
+    if flags & 0x04 != 0 {
+        error(validation, "64-bit memories are not supported".to_string());
+    }
+    if initial > MAX_MEMORY_PAGES || maximum.map_or(false, |max| max > MAX_MEMORY_PAGES) {
+        error(
+            validation,
+            format!("memory exceeds the limit of {} pages", MAX_MEMORY_PAGES),
+        );
+    }
+    if maximum.map_or(false, |max| max < initial) {
+        error(
+            validation,
+            "memory maximum is below its initial size".to_string(),
+        );
+    }
+    if validation.memory_pages.is_some() {
+        error(
+            validation,
+            "multiple memories are not supported".to_string(),
+        );
+    }
+    validation.memory_pages = Some((initial, maximum));
+}
+
+/// Decodes the sections of a module following its header
+fn inspect_sections(bytes: &[u8], validation: &mut BytecodeValidation) -> Result<(), String> {
+    let mut reader = WasmReader::new(bytes);
+    let mut float_types = false;
+    let mut float_globals = false;
+    let mut float_locals = false;
+    while !reader.is_empty() {
+        let id = reader.byte()?;
+        let size = reader.u64()? as usize;
+        let mut section = WasmReader::new(reader.bytes(size)?);
+        match id {
+            // type section
+            1 => {
+                for _ in 0..section.u64()? {
+                    if section.byte()? != 0x60 {
+                        return Err("invalid function type".to_string());
+                    }
+                    for _ in 0..2 {
+                        let len = section.u64()? as usize;
+                        float_types |= section
+                            .bytes(len)?
+                            .iter()
+                            .any(|t| FLOAT_VALUE_TYPES.contains(t));
+                    }
+                }
+            }
+            // import section
+            2 => {
+                for _ in 0..section.u64()? {
+                    let module = section.name()?;
+                    let name = section.name()?;
+                    match section.byte()? {
+                        0x00 => {
+                            section.u64()?;
+                            if !ALLOWED_IMPORT_MODULES.contains(&module.as_str()) {
+                                error(
+                                    validation,
+                                    format!(
+                                        "function {}.{} is not provided by the execution engine",
+                                        module, name
+                                    ),
+                                );
+                            }
+                            validation.imports.push(BytecodeImport { module, name });
+                        }
+                        0x01 => {
+                            section.byte()?;
+                            section.limits()?;
+                            warning(validation, format!("table {}.{} is imported", module, name));
+                        }
+                        0x02 => {
+                            let limits = section.limits()?;
+                            check_memory(validation, limits);
+                        }
+                        0x03 => {
+                            float_globals |= FLOAT_VALUE_TYPES.contains(&section.byte()?);
+                            section.byte()?;
+                            warning(
+                                validation,
+                                format!("global {}.{} is imported", module, name),
+                            );
+                        }
+                        kind => return Err(format!("invalid import kind 0x{:02X}", kind)),
+                    }
+                }
+            }
+            // function section
+            3 => validation.function_count = section.u64()?,
+            // memory section
+            5 => {
+                for _ in 0..section.u64()? {
+                    let limits = section.limits()?;
+                    check_memory(validation, limits);
+                }
+            }
+            // global section
+            6 => {
+                for _ in 0..section.u64()? {
+                    float_globals |= FLOAT_VALUE_TYPES.contains(&section.byte()?);
+                    section.byte()?;
+                    section.skip_const_expr()?;
+                }
+            }
+            // export section
+            7 => {
+                for _ in 0..section.u64()? {
+                    let name = section.name()?;
+                    let kind = section.byte()?;
+                    section.u64()?;
+                    if kind == 0x00 {
+                        validation.exports.push(name);
+                    }
+                }
+            }
+            // code section
+            10 => {
+                for _ in 0..section.u64()? {
+                    let body_size = section.u64()? as usize;
+                    let mut body = WasmReader::new(section.bytes(body_size)?);
+                    for _ in 0..body.u64()? {
+                        body.u64()?;
+                        float_locals |= FLOAT_VALUE_TYPES.contains(&body.byte()?);
+                    }
+                }
+            }
+            // other sections are not checked
+            id if id <= 12 => {}
+            id => return Err(format!("unknown section id {}", id)),
+        }
+    }
+    if float_types || float_globals || float_locals {
+        warning(
+            validation,
+            "module uses floating-point values, which are not deterministic across platforms"
+                .to_string(),
+        );
+    }
+    if !validation.exports.iter().any(|name| name == "main") {
+        warning(
+            validation,
+            "module does not export a main function and cannot be run by an ExecuteSC operation"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_bytecode() {
+        // (module
+        //   (import "massa" "assembly_script_print" (func (param i32)))
+        //   (import "other" "f" (func (param f64)))
+        //   (memory 1 2)
+        //   (func (export "main")))
+        let mut bytecode = WASM_HEADER.to_vec();
+        bytecode.extend([1, 9, 2, 0x60, 1, 0x7F, 0, 0x60, 1, 0x7C, 0]);
+        let mut imports = vec![2, 0, 2];
+        imports.push(5);
+        imports.extend(b"massa");
+        imports.push(21);
+        imports.extend(b"assembly_script_print");
+        imports.extend([0, 0]);
+        imports.push(5);
+        imports.extend(b"other");
+        imports.push(1);
+        imports.extend(b"f");
+        imports.extend([0, 1]);
+        imports[1] = (imports.len() - 2) as u8;
+        bytecode.extend(imports);
+        bytecode.extend([3, 2, 1, 0]);
+        bytecode.extend([5, 4, 1, 1, 1, 2]);
+        bytecode.extend([7, 8, 1, 4]);
+        bytecode.extend(b"main");
+        bytecode.extend([0, 2]);
+        bytecode.extend([10, 4, 1, 2, 0, 0x0B]);
+
+        let validation = inspect_bytecode(&bytecode, 10_000);
+        assert_eq!(validation.bytecode_size, bytecode.len() as u64);
+        assert_eq!(validation.function_count, 1);
+        assert_eq!(validation.imports.len(), 2);
+        assert_eq!(validation.imports[0].name, "assembly_script_print");
+        assert_eq!(validation.exports, vec!["main".to_string()]);
+        assert_eq!(validation.memory_pages, Some((1, Some(2))));
+        assert!(!validation.is_valid());
+        let errors: Vec<_> = validation
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == BytecodeDiagnosticSeverity::Error)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("other.f"));
+        assert!(validation
+            .diagnostics
+            .iter()
+            .any(|d| d.message.contains("floating-point")));
+
+        // the wasmv1 prefix is skipped
+        let mut prefixed = vec![WASMV1_PREFIX];
+        prefixed.extend(&bytecode);
+        assert_eq!(
+            inspect_bytecode(&prefixed, 10_000).imports,
+            validation.imports
+        );
+
+        // size limit and header
+        assert!(!inspect_bytecode(&bytecode, 10).is_valid());
+        assert!(!inspect_bytecode(b"not wasm", 10_000).is_valid());
+        // truncated module
+        assert!(!inspect_bytecode(&bytecode[..bytecode.len() - 3], 10_000).is_valid());
+    }
+}
@@ -17,8 +17,9 @@ use massa_async_pool::{AsyncMessage, AsyncPoolChanges};
 use massa_async_pool::{AsyncMessageId, AsyncMessageInfo};
 use massa_executed_ops::{ExecutedDenunciationsChanges, ExecutedOpsChanges};
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionConfig, ExecutionError, ExecutionOutput,
-    ExecutionStackElement,
+    AbiTrace, AbiTraceCall, EventStore, ExecutedBlockInfo, ExecutionConfig, ExecutionError,
+    ExecutionOutput, ExecutionStackElement, OperationFailure, ReadOnlyStateOverride, TransferInfo,
+    TransferOrigin,
 };
 use massa_final_state::{FinalState, StateChanges};
 use massa_hash::Hash;
@@ -34,13 +35,14 @@ use massa_models::{
     block_id::BlockId,
     operation::OperationId,
     output_event::{EventExecutionContext, SCOutputEvent},
+    prehash::PreHashMap,
     slot::Slot,
 };
 use massa_module_cache::controller::ModuleCache;
 use massa_pos_exports::PoSChanges;
 use massa_serialization::Serializer;
 use massa_versioning::address_factory::{AddressArgs, AddressFactory};
-use massa_versioning::versioning::MipStore;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_versioning::versioning_factory::{FactoryStrategy, VersioningFactory};
 use parking_lot::RwLock;
 use rand::SeedableRng;
@@ -51,6 +53,7 @@ use tracing::{debug, warn};
 
 /// A snapshot taken from an `ExecutionContext` and that represents its current state.
 /// The `ExecutionContext` state can then be restored later from this snapshot.
+#[derive(Clone)]
 pub struct ExecutionContextSnapshot {
     /// speculative ledger changes caused so far in the context
     pub ledger_changes: LedgerChanges,
@@ -85,6 +88,9 @@ pub struct ExecutionContextSnapshot {
     /// generated events during this execution, with multiple indexes
     pub events: EventStore,
 
+    /// number of coin transfers recorded so far during this execution
+    pub transfer_count: usize,
+
     /// Unsafe random state
     pub unsafe_rng: Xoshiro256PlusPlus,
 }
@@ -158,6 +164,21 @@ pub struct ExecutionContext {
     /// Unsafe random state (can be predicted and manipulated)
     pub unsafe_rng: Xoshiro256PlusPlus,
 
+    /// coin transfers recorded during this execution
+    pub transfers: Vec<TransferInfo>,
+
+    /// cause attached to the coin transfers recorded from now on
+    pub transfer_origin: TransferOrigin,
+
+    /// ABI calls recorded during this execution, if tracing is enabled
+    pub trace: Option<Vec<AbiTrace>>,
+
+    /// ledger changes caused by each operation executed so far, fees included
+    pub operation_changes: PreHashMap<OperationId, LedgerChanges>,
+
+    /// reason of the failure of each operation that failed so far
+    pub operation_failures: PreHashMap<OperationId, OperationFailure>,
+
     /// Creator address. The bytecode of this address can't be modified
     pub creator_address: Option<Address>,
 
@@ -229,6 +250,11 @@ impl ExecutionContext {
             read_only: Default::default(),
             events: Default::default(),
             unsafe_rng: init_prng(&execution_trail_hash),
+            transfers: Default::default(),
+            transfer_origin: TransferOrigin::Other,
+            trace: Default::default(),
+            operation_changes: Default::default(),
+            operation_failures: Default::default(),
             creator_address: Default::default(),
             origin_operation_id: Default::default(),
             module_cache,
@@ -254,6 +280,7 @@ impl ExecutionContext {
             created_message_index: self.created_message_index,
             stack: self.stack.clone(),
             events: self.events.clone(),
+            transfer_count: self.transfers.len(),
             unsafe_rng: self.unsafe_rng.clone(),
         }
     }
@@ -282,6 +309,7 @@ impl ExecutionContext {
         self.created_message_index = snapshot.created_message_index;
         self.stack = snapshot.stack;
         self.unsafe_rng = snapshot.unsafe_rng;
+        self.transfers.truncate(snapshot.transfer_count);
 
         // For events, set snapshot delta to error events.
         // Start iterating from snapshot events length because we are dealing with a VecDeque.
@@ -297,6 +325,35 @@ impl ExecutionContext {
         ));
     }
 
+    /// Resets a read-only context to a snapshot taken before its first execution,
+    /// so that it can be reused for another read-only execution reading the same state.
+    /// Unlike `reset_to_snapshot`, the outputs of the previous execution are dropped instead of being marked as errors.
+    ///
+    /// # Arguments
+    /// * `snapshot`: a snapshot taken right after the creation of the read-only context
+    pub fn reset_readonly_to_snapshot(&mut self, snapshot: ExecutionContextSnapshot) {
+        self.speculative_ledger
+            .reset_to_snapshot(snapshot.ledger_changes);
+        self.speculative_async_pool
+            .reset_to_snapshot((snapshot.async_pool_changes, snapshot.message_infos));
+        self.speculative_roll_state
+            .reset_to_snapshot(snapshot.pos_changes);
+        self.speculative_executed_ops
+            .reset_to_snapshot(snapshot.executed_ops);
+        self.speculative_executed_denunciations
+            .reset_to_snapshot(snapshot.executed_denunciations);
+        self.created_addr_index = snapshot.created_addr_index;
+        self.created_event_index = snapshot.created_event_index;
+        self.created_message_index = snapshot.created_message_index;
+        self.stack = snapshot.stack;
+        self.unsafe_rng = snapshot.unsafe_rng;
+        self.events = snapshot.events;
+        self.transfers.truncate(snapshot.transfer_count);
+        self.trace = None;
+        self.operation_changes.clear();
+        self.operation_failures.clear();
+    }
+
     /// Create a new `ExecutionContext` for read-only execution
     /// This should be used before performing a read-only execution.
     ///
@@ -538,8 +595,16 @@ impl ExecutionContext {
     }
 
     /// gets the data from a datastore entry of an address if it exists in the speculative ledger, or returns None
-    pub fn get_data_entry(&self, address: &Address, key: &[u8]) -> Option<Vec<u8>> {
-        self.speculative_ledger.get_data_entry(address, key)
+    pub fn get_data_entry(&mut self, address: &Address, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.speculative_ledger.get_data_entry(address, key);
+        if self.trace.is_some() {
+            self.record_trace(AbiTraceCall::DatastoreRead {
+                address: *address,
+                key: key.to_vec(),
+                value: value.clone(),
+            });
+        }
+        value
     }
 
     /// checks if a datastore entry exists in the speculative ledger
@@ -547,6 +612,36 @@ impl ExecutionContext {
         self.speculative_ledger.has_data_entry(address, key)
     }
 
+    /// Applies temporary address states on top of the speculative ledger of a read-only execution.
+    /// The overrides are part of the ledger changes of the execution output.
+    pub fn apply_state_overrides(
+        &mut self,
+        overrides: BTreeMap<Address, ReadOnlyStateOverride>,
+    ) -> Result<(), ExecutionError> {
+        if !self.read_only {
+            return Err(ExecutionError::RuntimeError(
+                "state overrides can only be applied to read-only executions".to_string(),
+            ));
+        }
+        for (addr, state_override) in overrides {
+            self.speculative_ledger
+                .apply_state_override(&addr, state_override)?;
+        }
+        Ok(())
+    }
+
+    /// Makes a read-only execution read the ledger as it was at the end of a past final slot.
+    /// The slot must be archived.
+    pub fn set_historical_slot(&mut self, slot: Slot) -> Result<(), ExecutionError> {
+        if !self.read_only {
+            return Err(ExecutionError::RuntimeError(
+                "historical slots can only be used by read-only executions".to_string(),
+            ));
+        }
+        self.speculative_ledger.set_historical_slot(slot);
+        Ok(())
+    }
+
     /// gets the effective balance of an address
     pub fn get_balance(&self, address: &Address) -> Option<Amount> {
         self.speculative_ledger.get_balance(address)
@@ -575,8 +670,18 @@ impl ExecutionContext {
         }
 
         // set data entry
+        let traced_entry = self.trace.is_some().then(|| (key.clone(), data.clone()));
         self.speculative_ledger
-            .set_data_entry(&self.get_current_address()?, address, key, data)
+            .set_data_entry(&self.get_current_address()?, address, key, data)?;
+
+        if let Some((key, value)) = traced_entry {
+            self.record_trace(AbiTraceCall::DatastoreWrite {
+                address: *address,
+                key,
+                value,
+            });
+        }
+        Ok(())
     }
 
     /// Appends data to a datastore entry for an address in the speculative ledger.
@@ -601,6 +706,8 @@ impl ExecutionContext {
             )));
         }
 
+        let traced_entry = self.trace.is_some().then(|| (key.clone(), data.clone()));
+
         // get current data entry
         let mut res_data = self
             .speculative_ledger
@@ -616,8 +723,21 @@ impl ExecutionContext {
         res_data.extend(data);
 
         // set data entry
-        self.speculative_ledger
-            .set_data_entry(&self.get_current_address()?, address, key, res_data)
+        self.speculative_ledger.set_data_entry(
+            &self.get_current_address()?,
+            address,
+            key,
+            res_data,
+        )?;
+
+        if let Some((key, value)) = traced_entry {
+            self.record_trace(AbiTraceCall::DatastoreAppend {
+                address: *address,
+                key,
+                value,
+            });
+        }
+        Ok(())
     }
 
     /// Deletes a datastore entry for an address.
@@ -641,7 +761,15 @@ impl ExecutionContext {
 
         // delete entry
         self.speculative_ledger
-            .delete_data_entry(&self.get_current_address()?, address, key)
+            .delete_data_entry(&self.get_current_address()?, address, key)?;
+
+        if self.trace.is_some() {
+            self.record_trace(AbiTraceCall::DatastoreDelete {
+                address: *address,
+                key: key.to_vec(),
+            });
+        }
+        Ok(())
     }
 
     /// Transfers coins from one address to another.
@@ -685,7 +813,21 @@ impl ExecutionContext {
 
         // do the transfer
         self.speculative_ledger
-            .transfer_coins(from_addr, to_addr, amount)
+            .transfer_coins(from_addr, to_addr, amount)?;
+
+        // record the transfer
+        self.transfers.push(TransferInfo {
+            from: from_addr,
+            to: to_addr,
+            amount,
+            origin: self.transfer_origin,
+        });
+        self.record_trace(AbiTraceCall::Transfer {
+            from: from_addr,
+            to: to_addr,
+            amount,
+        });
+        Ok(())
     }
 
     /// Add a new asynchronous message to speculative pool
@@ -701,6 +843,7 @@ impl ExecutionContext {
     /// # Arguments
     /// * `msg`: the asynchronous message to cancel
     pub fn cancel_async_message(&mut self, msg: &AsyncMessage) {
+        self.transfer_origin = TransferOrigin::AsyncMessage;
         if let Err(e) = self.transfer_coins(None, Some(msg.sender), msg.coins, false) {
             debug!(
                 "async message cancel: reimbursement of {} failed: {}",
@@ -740,6 +883,28 @@ impl ExecutionContext {
         )
     }
 
+    /// Try to transfer `roll_count` rolls from the sender address to the recipient address.
+    ///
+    /// # Arguments
+    /// * `sender_addr`: address giving the rolls
+    /// * `recipient_addr`: address receiving the rolls
+    /// * `roll_count`: number of rolls to transfer
+    pub fn try_transfer_rolls(
+        &mut self,
+        sender_addr: &Address,
+        recipient_addr: &Address,
+        roll_count: u64,
+    ) -> Result<(), ExecutionError> {
+        self.speculative_roll_state.try_transfer_rolls(
+            sender_addr,
+            recipient_addr,
+            self.slot,
+            roll_count,
+            self.config.periods_per_cycle,
+            self.config.denunciation_expire_periods,
+        )
+    }
+
     /// Try to slash `roll_count` rolls from the denounced address. If not enough rolls,
     /// slash the available amount and return the result
     ///
@@ -824,6 +989,7 @@ impl ExecutionContext {
     /// # Arguments
     /// * `slot`: associated slot of the deferred credits to be executed
     pub fn execute_deferred_credits(&mut self, slot: &Slot) {
+        self.transfer_origin = TransferOrigin::DeferredCredit;
         for (_slot, map) in self
             .speculative_roll_state
             .take_unexecuted_deferred_credits(slot)
@@ -878,12 +1044,31 @@ impl ExecutionContext {
             .slot
             .is_last_of_cycle(self.config.periods_per_cycle, self.config.thread_count)
         {
+            // the grace period only applies once its network version is active
+            let slot_ts = get_block_slot_timestamp(
+                self.config.thread_count,
+                self.config.t0,
+                self.config.genesis_timestamp,
+                self.slot,
+            )
+            .expect("could not compute current slot timestamp");
+            let grace_cycles = if self
+                .address_factory
+                .mip_store
+                .get_latest_component_version_at(&MipComponent::RollSaleGracePeriod, slot_ts)
+                == 0
+            {
+                0
+            } else {
+                self.config.roll_sale_grace_cycles
+            };
             self.speculative_roll_state.settle_production_stats(
                 &slot,
                 self.config.periods_per_cycle,
                 self.config.thread_count,
                 self.config.roll_price,
                 self.config.max_miss_ratio,
+                grace_cycles,
             );
         }
 
@@ -903,6 +1088,9 @@ impl ExecutionContext {
             block_info,
             state_changes,
             events: std::mem::take(&mut self.events),
+            transfers: std::mem::take(&mut self.transfers),
+            operation_changes: std::mem::take(&mut self.operation_changes),
+            operation_failures: std::mem::take(&mut self.operation_failures),
         }
     }
 
@@ -970,10 +1158,24 @@ impl ExecutionContext {
         // Increment the event counter fot this slot
         self.created_event_index += 1;
 
+        if self.trace.is_some() {
+            self.record_trace(AbiTraceCall::Event {
+                data: event.data.clone(),
+            });
+        }
+
         // Add the event to the context store
         self.events.push(event);
     }
 
+    /// Records an ABI call at the current depth of the call stack, if tracing is enabled
+    pub fn record_trace(&mut self, call: AbiTraceCall) {
+        let depth = self.stack.len();
+        if let Some(trace) = &mut self.trace {
+            trace.push(AbiTrace { depth, call });
+        }
+    }
+
     /// Check if an operation was previously executed (to prevent reuse)
     pub fn is_op_executed(&self, op_id: &OperationId) -> bool {
         self.speculative_executed_ops.is_op_executed(op_id)
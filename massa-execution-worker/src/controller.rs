@@ -4,21 +4,27 @@
 //! See `massa-execution-exports/controller_traits.rs` for functional details.
 
 use crate::execution::ExecutionState;
-use crate::request_queue::{RequestQueue, RequestWithResponseSender};
+use crate::request_queue::{ReadOnlyRequestQueue, RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
+use massa_executed_ops::OpExecutionInfo;
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
+    AbiCallStats, AsyncMessageFilter, AsyncPoolMessage, BytecodeValidation, ExecutionAddressInfo,
+    ExecutionBlockMetadata, ExecutionCacheSizes, ExecutionConfig, ExecutionController,
     ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
     ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionQueryResponseItem, GasEstimation, OperationFailure, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, SlotTransfers,
 };
+use massa_ledger_exports::LedgerChanges;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
+use massa_models::execution::{EventFilter, TransferFilter};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
-use massa_models::stats::ExecutionStats;
+use massa_models::stats::{DiskUsageForecast, ExecutionStats, ThroughputBucket};
 use massa_models::{address::Address, amount::Amount, operation::OperationId};
 use massa_models::{block_id::BlockId, slot::Slot};
+use massa_pos_exports::StakerDistribution;
+use massa_time::MassaTime;
 use parking_lot::{Condvar, Mutex, RwLock};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
@@ -35,8 +41,8 @@ pub(crate) struct ExecutionInputData {
     pub new_blockclique: Option<HashMap<Slot, BlockId>>,
     /// storage instances for previously unprocessed blocks
     pub block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata>,
-    /// queue for read-only execution requests and response MPSCs to send back their outputs
-    pub readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// queue for batches of read-only execution requests and response MPSCs to send back their outputs
+    pub readonly_requests: ReadOnlyRequestQueue,
 }
 
 impl Display for ExecutionInputData {
@@ -96,6 +102,8 @@ pub struct ExecutionControllerImpl {
     pub(crate) input_data: Arc<(Condvar, Mutex<ExecutionInputData>)>,
     /// current execution state (see execution.rs for details)
     pub(crate) execution_state: Arc<RwLock<ExecutionState>>,
+    /// execution config
+    pub(crate) config: ExecutionConfig,
 }
 
 impl ExecutionController for ExecutionControllerImpl {
@@ -188,17 +196,17 @@ impl ExecutionController for ExecutionControllerImpl {
                         None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
                     }
                 }
-                ExecutionQueryRequestItem::AddressDatastoreKeysCandidate { addr, prefix } => {
+                ExecutionQueryRequestItem::AddressDatastoreKeysCandidate { addr, filter } => {
                     let (_final_v, speculative_v) =
-                        execution_lock.get_final_and_candidate_datastore_keys(&addr, &prefix);
+                        execution_lock.get_final_and_candidate_datastore_keys(&addr, &filter);
                     match speculative_v {
                         Some(keys) => Ok(ExecutionQueryResponseItem::KeyList(keys)),
                         None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
                     }
                 }
-                ExecutionQueryRequestItem::AddressDatastoreKeysFinal { addr, prefix } => {
+                ExecutionQueryRequestItem::AddressDatastoreKeysFinal { addr, filter } => {
                     let (final_v, _speculative_v) =
-                        execution_lock.get_final_and_candidate_datastore_keys(&addr, &prefix);
+                        execution_lock.get_final_and_candidate_datastore_keys(&addr, &filter);
                     match final_v {
                         Some(keys) => Ok(ExecutionQueryResponseItem::KeyList(keys)),
                         None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
@@ -226,6 +234,26 @@ impl ExecutionController for ExecutionControllerImpl {
                         ))),
                     }
                 }
+                ExecutionQueryRequestItem::AddressBalanceHistorical { addr, slot } => {
+                    match execution_lock.get_historical_balance(&addr, slot) {
+                        Ok(Some(balance)) => Ok(ExecutionQueryResponseItem::Amount(balance)),
+                        Ok(None) => Err(ExecutionQueryError::NotFound(format!(
+                            "Account {} at slot {}",
+                            addr, slot
+                        ))),
+                        Err(err) => Err(ExecutionQueryError::HistoricalSlotError(err.to_string())),
+                    }
+                }
+                ExecutionQueryRequestItem::AddressDatastoreValueHistorical { addr, key, slot } => {
+                    match execution_lock.get_historical_data_entry(&addr, &key, slot) {
+                        Ok(Some(value)) => Ok(ExecutionQueryResponseItem::DatastoreValue(value)),
+                        Ok(None) => Err(ExecutionQueryError::NotFound(format!(
+                            "Account {} datastore entry {:?} at slot {}",
+                            addr, key, slot
+                        ))),
+                        Err(err) => Err(ExecutionQueryError::HistoricalSlotError(err.to_string())),
+                    }
+                }
                 ExecutionQueryRequestItem::OpExecutionStatusCandidate(id) => {
                     let (speculative_v, _final_v) = execution_lock
                         .get_ops_exec_status(&[id])
@@ -342,6 +370,26 @@ impl ExecutionController for ExecutionControllerImpl {
             .get_filtered_sc_output_event(filter)
     }
 
+    /// Get the latest slot of which final events were pruned
+    fn get_final_events_pruned_until(&self) -> Option<Slot> {
+        self.execution_state.read().get_final_events_pruned_until()
+    }
+
+    /// Get the coin transfers that happened at each executed slot between `start` and `end` (inclusive)
+    fn get_slot_transfers(&self, start: Slot, end: Slot) -> Vec<SlotTransfers> {
+        self.execution_state.read().get_slot_transfers(start, end)
+    }
+
+    /// Get the coin transfers spending or crediting an address, grouped by slot
+    fn get_address_transfers(&self, filter: TransferFilter) -> Vec<SlotTransfers> {
+        self.execution_state.read().get_address_transfers(filter)
+    }
+
+    /// Get the messages of the asynchronous pool matching a filter
+    fn get_async_messages(&self, filter: AsyncMessageFilter) -> Vec<AsyncPoolMessage> {
+        self.execution_state.read().get_async_messages(&filter)
+    }
+
     /// Get the final and candidate values of balance.
     ///
     /// # Return value
@@ -379,12 +427,46 @@ impl ExecutionController for ExecutionControllerImpl {
         self.execution_state.read().get_cycle_active_rolls(cycle)
     }
 
+    /// Return the roll distribution statistics of the cycles in the final PoS history
+    fn get_staker_distribution_history(&self, top_n: usize) -> Vec<StakerDistribution> {
+        self.execution_state
+            .read()
+            .get_staker_distribution_history(top_n)
+    }
+
     /// Executes a read-only request
     /// Read-only requests do not modify consensus state
     fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        self.execute_readonly_batch(vec![req])?
+            .pop()
+            .unwrap_or_else(|| {
+                Err(ExecutionError::ChannelError(
+                    "readonly execution returned no output".into(),
+                ))
+            })
+    }
+
+    /// Executes a batch of read-only requests against the same state
+    /// Read-only requests do not modify consensus state
+    fn execute_readonly_batch(
+        &self,
+        reqs: Vec<ReadOnlyExecutionRequest>,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError> {
+        // the execution state stays write-locked for the whole batch, holding back slot executions:
+        // bound the batch by the gas of a single read-only request
+        let batch_gas = reqs
+            .iter()
+            .fold(0u64, |total, req| total.saturating_add(req.max_gas));
+        if batch_gas > self.config.max_read_only_gas {
+            return Err(ExecutionError::TooMuchGas(format!(
+                "total max_gas {} of the read-only batch exceeds the limit {}",
+                batch_gas, self.config.max_read_only_gas
+            )));
+        }
+
         let resp_rx = {
             let mut input_data = self.input_data.1.lock();
 
@@ -395,13 +477,13 @@ impl ExecutionController for ExecutionControllerImpl {
                 ));
             }
 
-            // prepare the channel to send back the result of the read-only execution
+            // prepare the channel to send back the result of the read-only executions
             let (resp_tx, resp_rx) = MassaChannel::new("read_only_request".to_string(), None);
 
-            // append the request to the queue of input read-only requests
+            // append the batch to the queue of input read-only requests
             input_data
                 .readonly_requests
-                .push(RequestWithResponseSender::new(req, resp_tx));
+                .push(RequestWithResponseSender::new(reqs, resp_tx));
 
             // wake up the execution main loop
             self.input_data.0.notify_one();
@@ -409,7 +491,7 @@ impl ExecutionController for ExecutionControllerImpl {
             resp_rx
         };
 
-        // Wait for the result of the execution
+        // Wait for the result of the executions
         match resp_rx.recv() {
             Ok(result) => result,
             Err(err) => Err(ExecutionError::ChannelError(format!(
@@ -419,6 +501,59 @@ impl ExecutionController for ExecutionControllerImpl {
         }
     }
 
+    /// Estimates the gas needed by a read-only request by running it several times
+    fn estimate_gas(&self, req: ReadOnlyExecutionRequest) -> Result<GasEstimation, ExecutionError> {
+        let run = |max_gas: u64| {
+            let mut probe = req.clone();
+            probe.max_gas = max_gas;
+            self.execute_readonly_request(probe)
+        };
+
+        // the execution has to succeed with the full gas budget of the request
+        let gas_cost = run(req.max_gas)?.gas_cost;
+
+        // the consumed gas is usually enough, but more can be required,
+        // for example when a part of the remaining gas is forwarded to a nested call
+        let required_gas = if run(gas_cost).is_ok() {
+            gas_cost
+        } else {
+            // binary search between a failing and a succeeding budget,
+            // stopping at a precision of 0.1% which is far below the safety margin
+            let (mut failing, mut succeeding) = (gas_cost, req.max_gas);
+            while succeeding - failing > std::cmp::max(1, succeeding / 1000) {
+                let middle = failing + (succeeding - failing) / 2;
+                if run(middle).is_ok() {
+                    succeeding = middle;
+                } else {
+                    failing = middle;
+                }
+            }
+            succeeding
+        };
+
+        let margin = required_gas.saturating_mul(self.config.gas_estimation_margin_percent) / 100;
+        Ok(GasEstimation {
+            gas_cost,
+            required_gas,
+            estimated_max_gas: required_gas.saturating_add(margin),
+        })
+    }
+
+    /// Gets the current sizes of the execution caches
+    fn get_cache_sizes(&self) -> ExecutionCacheSizes {
+        self.execution_state.read().get_cache_sizes()
+    }
+
+    /// Resizes the execution caches while the worker is running
+    fn set_cache_sizes(&self, sizes: ExecutionCacheSizes) -> Result<(), ExecutionError> {
+        self.execution_state.write().set_cache_sizes(sizes)
+    }
+
+    /// Validates a bytecode against the limits of the execution engine without executing it
+    fn validate_bytecode(&self, bytecode: &[u8]) -> BytecodeValidation {
+        self.execution_state.read().validate_bytecode(bytecode)
+    }
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// Returns a tuple of booleans: `(speculative_execution_status, final_execution_status)`
     fn get_denunciation_execution_status(
@@ -436,7 +571,7 @@ impl ExecutionController for ExecutionControllerImpl {
         let exec_state = self.execution_state.read();
         for addr in addresses {
             let (final_datastore_keys, candidate_datastore_keys) =
-                exec_state.get_final_and_candidate_datastore_keys(addr, &[]);
+                exec_state.get_final_and_candidate_datastore_keys(addr, &Default::default());
             let (final_balance, candidate_balance) =
                 exec_state.get_final_and_candidate_balance(addr);
             let (final_roll_count, candidate_roll_count) =
@@ -460,6 +595,22 @@ impl ExecutionController for ExecutionControllerImpl {
         self.execution_state.read().get_stats()
     }
 
+    /// Get the number of final executed operations over the last `window`, split in buckets of `resolution`
+    fn get_throughput_history(
+        &self,
+        window: MassaTime,
+        resolution: MassaTime,
+    ) -> Vec<ThroughputBucket> {
+        self.execution_state
+            .read()
+            .get_throughput_history(window, resolution)
+    }
+
+    /// Get the latest final state disk usage forecast
+    fn get_disk_usage_forecast(&self) -> Option<DiskUsageForecast> {
+        self.execution_state.read().get_disk_usage_forecast()
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn ExecutionController>`,
     /// see `massa-execution-exports/controller_traits.rs`
@@ -471,6 +622,29 @@ impl ExecutionController for ExecutionControllerImpl {
     fn get_ops_exec_status(&self, batch: &[OperationId]) -> Vec<(Option<bool>, Option<bool>)> {
         self.execution_state.read().get_ops_exec_status(batch)
     }
+
+    /// See trait definition
+    fn get_ops_exec_info(
+        &self,
+        batch: &[OperationId],
+    ) -> Vec<(Option<OpExecutionInfo>, Option<OpExecutionInfo>)> {
+        self.execution_state.read().get_ops_exec_info(batch)
+    }
+
+    /// See trait definition
+    fn get_ops_ledger_changes(&self, batch: &[OperationId]) -> Vec<Option<LedgerChanges>> {
+        self.execution_state.read().get_ops_ledger_changes(batch)
+    }
+
+    /// See trait definition
+    fn get_ops_failures(&self, batch: &[OperationId]) -> Vec<Option<OperationFailure>> {
+        self.execution_state.read().get_ops_failures(batch)
+    }
+
+    /// See trait definition
+    fn get_abi_call_stats(&self, slot_count: usize) -> Vec<AbiCallStats> {
+        self.execution_state.read().get_abi_call_stats(slot_count)
+    }
 }
 
 /// Execution manager
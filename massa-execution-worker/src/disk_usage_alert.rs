@@ -0,0 +1,173 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Posts the final state disk usage alerts to a webhook.
+//!
+//! The HTTP requests are sent from a dedicated thread so that a slow or unreachable
+//! webhook never delays the execution of slots.
+
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::time::Duration;
+
+use hyper::{header::CONTENT_TYPE, Body, Client, Method, Request};
+use massa_models::stats::DiskUsageForecast;
+use massa_time::MassaTime;
+use serde_json::json;
+use tracing::warn;
+
+/// number of alerts waiting to be posted before new ones are dropped
+const ALERT_QUEUE_LENGTH: usize = 8;
+
+/// maximum duration of a webhook request
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Raises an alert when the disk holding the final state is forecast to be full soon
+pub(crate) struct DiskUsageAlerter {
+    /// the disk is forecast to be full within this duration
+    threshold: MassaTime,
+    /// queue of the alerts to post to the webhook, if one is configured
+    webhook_sender: Option<SyncSender<String>>,
+    /// whether the last forecast was below the threshold, so that an alert is posted once per crossing
+    alerting: bool,
+}
+
+impl DiskUsageAlerter {
+    /// Creates the alerter, and the thread posting to `webhook_url` if one is given
+    pub fn new(threshold: MassaTime, webhook_url: Option<String>) -> Self {
+        let webhook_sender = webhook_url.map(|url| {
+            let (sender, receiver) = sync_channel::<String>(ALERT_QUEUE_LENGTH);
+            std::thread::Builder::new()
+                .name("disk-usage-alert".into())
+                .spawn(move || {
+                    let rt = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .expect("error on build tokio runtime for disk usage alerts");
+                    let client = Client::new();
+                    // stops when the alerter is dropped
+                    while let Ok(payload) = receiver.recv() {
+                        let request = Request::builder()
+                            .method(Method::POST)
+                            .uri(url.as_str())
+                            .header(CONTENT_TYPE, "application/json")
+                            .body(Body::from(payload));
+                        let request = match request {
+                            Ok(request) => request,
+                            Err(err) => {
+                                warn!("invalid disk usage alert webhook {}: {}", url, err);
+                                continue;
+                            }
+                        };
+                        let response = rt.block_on(async {
+                            tokio::time::timeout(WEBHOOK_TIMEOUT, client.request(request)).await
+                        });
+                        match response {
+                            Ok(Ok(response)) if response.status().is_success() => {}
+                            Ok(Ok(response)) => warn!(
+                                "disk usage alert webhook {} answered {}",
+                                url,
+                                response.status()
+                            ),
+                            Ok(Err(err)) => {
+                                warn!("could not post the disk usage alert to {}: {}", url, err)
+                            }
+                            Err(_) => warn!("disk usage alert webhook {} timed out", url),
+                        }
+                    }
+                })
+                .expect("failed to spawn thread : disk-usage-alert");
+            sender
+        });
+        DiskUsageAlerter {
+            threshold,
+            webhook_sender,
+            alerting: false,
+        }
+    }
+
+    /// Warns if the disk is forecast to be full within the threshold,
+    /// and posts an alert to the webhook when the forecast crosses the threshold.
+    pub fn check(&mut self, forecast: &DiskUsageForecast) {
+        let time_to_full = match forecast.time_to_full {
+            Some(time_to_full) if time_to_full < self.threshold => time_to_full,
+            _ => {
+                self.alerting = false;
+                return;
+            }
+        };
+        let message = format!(
+            "the disk holding the final state is forecast to be full in {} (growth rate: {:.0} bytes/s, available: {} bytes)",
+            time_to_full
+                .format_duration()
+                .unwrap_or_else(|_| format!("{} ms", time_to_full)),
+            forecast.growth_rate,
+            forecast.available_space.unwrap_or_default()
+        );
+        warn!("{}", message);
+
+        if std::mem::replace(&mut self.alerting, true) {
+            return;
+        }
+        if let Some(sender) = &self.webhook_sender {
+            // the `text` field is understood by the usual chat incoming webhooks
+            let payload = json!({
+                "text": message,
+                "forecast": forecast,
+            })
+            .to_string();
+            if let Err(TrySendError::Full(_)) = sender.try_send(payload) {
+                warn!("disk usage alert dropped: too many alerts waiting for the webhook");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiskUsageAlerter;
+    use massa_models::stats::DiskUsageForecast;
+    use massa_time::MassaTime;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    #[test]
+    fn test_disk_usage_alert_webhook() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        let mut alerter = DiskUsageAlerter::new(MassaTime::from_millis(1000), Some(url));
+        let mut forecast = DiskUsageForecast {
+            timestamp: MassaTime::from_millis(0),
+            column_family_sizes: Default::default(),
+            growth_rate: 10.0,
+            available_space: Some(5),
+            time_to_full: Some(MassaTime::from_millis(500)),
+        };
+
+        // the first forecast below the threshold is posted, the next ones are not
+        alerter.check(&forecast);
+        alerter.check(&forecast);
+        let (mut stream, _) = listener.accept().unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        // the headers and the body may be received separately
+        let mut request = String::new();
+        let mut buffer = [0u8; 4096];
+        while !request.contains("forecast to be full") {
+            let len = stream.read(&mut buffer).unwrap();
+            assert!(len > 0, "the alert was not posted");
+            request.push_str(&String::from_utf8_lossy(&buffer[..len]));
+        }
+        assert!(request.starts_with("POST /alerts"));
+        assert!(request.contains("forecast to be full"));
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+        assert!(alerter.alerting);
+
+        // the alert is raised again once the forecast went back above the threshold
+        forecast.time_to_full = None;
+        alerter.check(&forecast);
+        assert!(!alerter.alerting);
+    }
+}
@@ -8,28 +8,38 @@
 //! * the VM is called for execution within this context
 //! * the output of the execution is extracted from the context
 
+use crate::abi_profiler::{AbiProfiler, SharedAbiProfiler};
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::bytecode_validation::inspect_bytecode;
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
+use crate::disk_usage_alert::DiskUsageAlerter;
+use crate::final_event_store::FinalEventStore;
+use crate::final_transfer_store::FinalTransferStore;
 use crate::interface_impl::InterfaceImpl;
-use crate::stats::ExecutionStatsCounter;
-use massa_async_pool::AsyncMessage;
+use crate::stats::{DiskUsageTracker, ExecutionStatsCounter};
+use massa_async_pool::{AsyncMessage, AsyncMessageId};
+use massa_db_exports::{STATE_CF, VERSIONING_CF};
+use massa_executed_ops::OpExecutionInfo;
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
-    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    AbiCallStats, AsyncMessageFilter, AsyncPoolMessage, BytecodeDiagnostic,
+    BytecodeDiagnosticSeverity, BytecodeValidation, ExecutedBlockInfo, ExecutionBlockMetadata,
+    ExecutionCacheSizes, ExecutionChannels, ExecutionConfig, ExecutionError, ExecutionOutput,
+    ExecutionQueryCycleInfos, ExecutionQueryStakerInfo, ExecutionStackElement, OperationFailure,
+    OperationFailureCode, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, SlotExecutionOutput, SlotTransfers, TransferInfo, TransferOrigin,
 };
 use massa_final_state::FinalState;
-use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
+use massa_ledger_exports::{Applicable, LedgerChanges, SetOrDelete, SetUpdateOrDelete};
 use massa_metrics::MassaMetrics;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::bytecode::Bytecode;
-use massa_models::datastore::get_prefix_bounds;
+use massa_models::config::VERSION;
+use massa_models::datastore::DatastoreKeysFilter;
 use massa_models::denunciation::{Denunciation, DenunciationIndex};
-use massa_models::execution::EventFilter;
+use massa_models::execution::{EventFilter, TransferFilter};
 use massa_models::output_event::SCOutputEvent;
-use massa_models::prehash::PreHashSet;
-use massa_models::stats::ExecutionStats;
+use massa_models::prehash::{PreHashMap, PreHashSet};
+use massa_models::stats::{DiskUsageForecast, ExecutionStats, ThroughputBucket};
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{
     address::Address,
@@ -39,15 +49,25 @@ use massa_models::{
 use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
-use massa_pos_exports::SelectorController;
+use massa_pos_exports::{SelectorController, StakerDistribution};
 use massa_sc_runtime::{Interface, Response, VMError};
-use massa_versioning::versioning::MipStore;
+use massa_time::MassaTime;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_wallet::Wallet;
 use parking_lot::{Mutex, RwLock};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::ops::Bound;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, trace, warn};
 
+/// Interval at which a full slot execution outputs channel is checked while waiting for its subscribers
+const BROADCAST_BACKPRESSURE_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Read-only context reused by the consecutive requests of a batch reading the same snapshot:
+/// the `(is_final, historical_slot)` snapshot read, and the state of the context before its first execution
+type SharedReadOnlyContext = ((bool, Option<Slot>), ExecutionContextSnapshot);
+
 /// Used to acquire a lock on the execution context
 macro_rules! context_guard {
     ($self:ident) => {
@@ -70,16 +90,28 @@ pub(crate) struct ExecutionState {
     pub active_cursor: Slot,
     // a cursor pointing to the highest executed final slot
     pub final_cursor: Slot,
-    // store containing execution events that became final
-    final_events: EventStore,
+    // store containing execution events that became final, persisted on disk
+    final_events: FinalEventStore,
+    // final coin transfers of each slot, indexed by address, persisted on disk
+    final_transfer_store: FinalTransferStore,
+    // ledger changes caused by each operation of the last final slots, oldest at the front
+    final_operation_changes: VecDeque<(Slot, PreHashMap<OperationId, LedgerChanges>)>,
+    // failure reasons of the operations of the last final slots, oldest at the front
+    final_operation_failures: VecDeque<(Slot, PreHashMap<OperationId, OperationFailure>)>,
     // final state with atomic R/W access
     final_state: Arc<RwLock<FinalState>>,
     // execution context (see documentation in context.rs)
     execution_context: Arc<Mutex<ExecutionContext>>,
     // execution interface allowing the VM runtime to access the Massa context
     execution_interface: Box<dyn Interface>,
+    // profiling counters of the host ABI calls, shared with the execution interface
+    abi_profiler: SharedAbiProfiler,
     // execution statistics
     stats_counter: ExecutionStatsCounter,
+    // final state disk usage tracker
+    disk_usage_tracker: DiskUsageTracker,
+    // alerts raised when the disk is forecast to be full soon
+    disk_usage_alerter: DiskUsageAlerter,
     // cache of pre compiled sc modules
     module_cache: Arc<RwLock<ModuleCache>>,
     // MipStore (Versioning)
@@ -116,10 +148,27 @@ impl ExecutionState {
         // This should be among the latest final slots.
         let last_final_slot;
         let execution_trail_hash;
+        let final_events;
+        let final_transfer_store;
+        let stats_counter;
         {
             let final_state_read = final_state.read();
             last_final_slot = final_state_read.get_slot();
             execution_trail_hash = final_state_read.get_execution_trail_hash();
+            final_events = FinalEventStore::new(
+                final_state_read.db.clone(),
+                config.final_events_retention_periods,
+                config.final_events_capacity,
+            );
+            final_transfer_store = FinalTransferStore::new(
+                final_state_read.db.clone(),
+                config.final_transfers_retention_periods,
+            );
+            stats_counter = ExecutionStatsCounter::new(
+                config.stats_time_window_duration,
+                config.stats_history_duration,
+                final_state_read.db.clone(),
+            );
         }
 
         // Create default active history
@@ -134,6 +183,7 @@ impl ExecutionState {
             hd_cache_size: config.hd_cache_size,
             snip_amount: config.snip_amount,
             max_module_length: config.max_bytecode_size,
+            engine_version: VERSION.to_string(),
         })));
 
         // Create an empty placeholder execution context, with shared atomic access
@@ -146,10 +196,14 @@ impl ExecutionState {
             execution_trail_hash,
         )));
 
+        // Create the profiler of the host ABI calls
+        let abi_profiler = Arc::new(Mutex::new(AbiProfiler::new(config.abi_profile_slots)));
+
         // Instantiate the interface providing ABI access to the VM, share the execution context with it
         let execution_interface = Box::new(InterfaceImpl::new(
             config.clone(),
             execution_context.clone(),
+            abi_profiler.clone(),
         ));
 
         // build the execution state
@@ -157,14 +211,28 @@ impl ExecutionState {
             final_state,
             execution_context,
             execution_interface,
+            abi_profiler,
             // empty execution output history: it is not recovered through bootstrap
             active_history,
-            // empty final event store: it is not recovered through bootstrap
-            final_events: Default::default(),
+            // final event store: kept across restarts, but not recovered through bootstrap
+            final_events,
+            final_transfer_store,
+            // empty final operation histories: they are not recovered through bootstrap
+            final_operation_changes: Default::default(),
+            final_operation_failures: Default::default(),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
-            stats_counter: ExecutionStatsCounter::new(config.stats_time_window_duration),
+            // throughput history: kept across restarts, but not recovered through bootstrap
+            stats_counter,
+            disk_usage_tracker: DiskUsageTracker::new(
+                config.disk_usage_sample_interval,
+                config.disk_usage_forecast_window,
+            ),
+            disk_usage_alerter: DiskUsageAlerter::new(
+                config.disk_usage_alert_threshold,
+                config.disk_usage_alert_webhook.clone(),
+            ),
             module_cache,
             config,
             mip_store,
@@ -186,6 +254,78 @@ impl ExecutionState {
             .get_stats(self.active_cursor, self.final_cursor)
     }
 
+    /// Get the final operations throughput history
+    pub fn get_throughput_history(
+        &self,
+        window: MassaTime,
+        resolution: MassaTime,
+    ) -> Vec<ThroughputBucket> {
+        self.stats_counter
+            .get_throughput_history(window, resolution)
+    }
+
+    /// Get the latest final state disk usage forecast
+    pub fn get_disk_usage_forecast(&self) -> Option<DiskUsageForecast> {
+        self.disk_usage_tracker.get_forecast()
+    }
+
+    /// Measures the final state disk usage if a measurement is due,
+    /// and raises an alert if the disk is forecast to be full soon.
+    fn update_disk_usage(&mut self) {
+        let current_time = MassaTime::now().expect("could not get current time");
+        if !self.disk_usage_tracker.is_sample_due(current_time) {
+            return;
+        }
+        let (column_family_sizes, available_space) = {
+            let final_state = self.final_state.read();
+            let db = final_state.db.read();
+            (db.get_cf_sizes(), db.get_available_disk_space())
+        };
+        let forecast = self.disk_usage_tracker.register_sample(
+            current_time,
+            column_family_sizes,
+            available_space,
+        );
+        self.massa_metrics.set_final_state_disk_usage(
+            forecast.total_size(),
+            forecast
+                .time_to_full
+                .map(|time_to_full| time_to_full.to_duration().as_secs()),
+        );
+        self.disk_usage_alerter.check(&forecast);
+    }
+
+    /// Exports the statistics of the final state database to the prometheus metrics
+    fn update_db_metrics(&self) {
+        if !self.massa_metrics.is_enabled() {
+            return;
+        }
+        let metrics = self.final_state.read().db.read().get_metrics();
+        for (cf, size) in &metrics.cf_sst_sizes {
+            self.massa_metrics.set_db_sst_files_size(cf, *size);
+        }
+        self.massa_metrics.set_db_compactions(
+            metrics.pending_compaction_bytes,
+            metrics.running_compactions,
+        );
+        self.massa_metrics.set_db_operations(
+            metrics.write_count,
+            metrics.write_time.as_secs_f64(),
+            metrics.read_count,
+            metrics.read_time.as_secs_f64(),
+        );
+        self.massa_metrics.set_db_change_history(
+            STATE_CF,
+            metrics.change_history_length,
+            metrics.change_history_bytes,
+        );
+        self.massa_metrics.set_db_change_history(
+            VERSIONING_CF,
+            metrics.change_history_versioning_length,
+            metrics.change_history_versioning_bytes,
+        );
+    }
+
     /// Applies the output of an execution to the final execution state.
     /// The newly applied final output should be from the slot just after the last executed final slot
     ///
@@ -229,8 +369,26 @@ impl ExecutionState {
 
         // append generated events to the final event store
         exec_out.events.finalize();
-        self.final_events.extend(exec_out.events);
-        self.final_events.prune(self.config.max_final_events);
+        let truncated_events = self
+            .final_events
+            .push_slot_events(exec_out.slot, &exec_out.events);
+        if truncated_events > 0 {
+            self.massa_metrics
+                .inc_final_events_truncated_by(truncated_events);
+        }
+
+        // keep the coin transfers of the slot, and index them by address
+        self.final_transfer_store
+            .push_slot_transfers(exec_out.slot, &exec_out.transfers);
+
+        // keep the ledger changes of the operations of the slot
+        self.final_operation_changes
+            .push_back((exec_out.slot, exec_out.operation_changes));
+
+        // keep the failure reasons of the operations of the slot
+        self.final_operation_failures
+            .push_back((exec_out.slot, exec_out.operation_failures));
+        self.trim_final_caches();
 
         // update the prometheus metrics
         self.massa_metrics
@@ -250,6 +408,9 @@ impl ExecutionState {
             self.final_state.read().async_pool.message_info_cache.len(),
         );
 
+        self.update_disk_usage();
+        self.update_db_metrics();
+
         self.massa_metrics.inc_executed_final_slot();
         if exec_out.block_info.is_some() {
             self.massa_metrics.inc_executed_final_slot_with_block();
@@ -257,21 +418,69 @@ impl ExecutionState {
 
         // Broadcast a final slot execution output to active channel subscribers.
         if self.config.broadcast_enabled {
-            let slot_exec_out = SlotExecutionOutput::FinalizedSlot(exec_out_2);
-            if let Err(err) = self
-                .channels
-                .slot_execution_output_sender
-                .send(slot_exec_out)
-            {
-                trace!(
-                    "error, failed to broadcast final execution output for slot {} due to: {}",
-                    exec_out.slot,
-                    err
-                );
+            let mut exec_out_2 = exec_out_2;
+            if !self.config.broadcast_operation_changes {
+                exec_out_2.operation_changes.clear();
+            }
+            self.broadcast_slot_execution_output(SlotExecutionOutput::FinalizedSlot(exec_out_2));
+        }
+
+        // Broadcast the completion of a cycle to active channel subscribers.
+        if self.config.broadcast_enabled
+            && exec_out
+                .slot
+                .is_last_of_cycle(self.config.periods_per_cycle, self.config.thread_count)
+        {
+            let cycle = exec_out.slot.get_cycle(self.config.periods_per_cycle);
+            let cycle_completion = self
+                .final_state
+                .read()
+                .pos_state
+                .get_cycle_completion(cycle);
+            if let Some(cycle_completion) = cycle_completion {
+                if let Err(err) = self.channels.cycle_completion_sender.send(cycle_completion) {
+                    trace!(
+                        "error, failed to broadcast the completion of cycle {} due to: {}",
+                        cycle,
+                        err
+                    );
+                }
             }
         }
     }
 
+    /// Broadcasts a slot execution output to the active channel subscribers.
+    ///
+    /// When the channel is full, waits up to `broadcast_backpressure_timeout` for the slowest subscriber
+    /// to catch up, after which the oldest output is dropped for the subscribers that did not receive it.
+    fn broadcast_slot_execution_output(&self, output: SlotExecutionOutput) {
+        let sender = &self.channels.slot_execution_output_sender;
+        // the broadcast channel rounds its capacity up to the next power of two
+        let capacity = self
+            .config
+            .broadcast_slot_execution_output_channel_capacity
+            .next_power_of_two();
+        let is_full = || sender.receiver_count() > 0 && sender.len() >= capacity;
+        let deadline = Instant::now() + self.config.broadcast_backpressure_timeout.to_duration();
+        while is_full() && Instant::now() < deadline {
+            std::thread::sleep(BROADCAST_BACKPRESSURE_POLL_INTERVAL);
+        }
+        if is_full() {
+            self.massa_metrics.inc_slot_execution_outputs_dropped();
+        }
+        let slot = match &output {
+            SlotExecutionOutput::ExecutedSlot(exec_out)
+            | SlotExecutionOutput::FinalizedSlot(exec_out) => exec_out.slot,
+        };
+        if let Err(err) = sender.send(output) {
+            trace!(
+                "error, failed to broadcast execution output for slot {} due to: {}",
+                slot,
+                err
+            );
+        }
+    }
+
     /// Applies an execution output to the active (non-final) state
     /// The newly active final output should be from the slot just after the last executed active slot
     ///
@@ -330,6 +539,9 @@ impl ExecutionState {
                 .saturating_sub(operation.get_max_spending(self.config.roll_price)),
         );
 
+        // attach the coin transfers from now on to the operation
+        context.transfer_origin = TransferOrigin::Operation(operation_id);
+
         // debit the fee from the operation sender
         if let Err(err) =
             context.transfer_coins(Some(sender_addr), None, operation.content.fee, false)
@@ -408,6 +620,9 @@ impl ExecutionState {
         // Add fee from operation.
         let new_block_credits = block_credits.saturating_add(operation.content.fee);
 
+        // keep the ledger changes preceding the operation to extract the ones it causes
+        let ledger_changes_before = context_guard!(self).speculative_ledger.get_snapshot();
+
         let context_snapshot = self.prepare_operation_for_execution(operation, sender_addr)?;
 
         // update block gas
@@ -430,6 +645,9 @@ impl ExecutionState {
             OperationType::RollSell { .. } => {
                 self.execute_roll_sell_op(&operation.content.op, sender_addr)
             }
+            OperationType::RollTransfer { .. } => {
+                self.execute_roll_transfer_op(&operation.content.op, sender_addr)
+            }
             OperationType::Transaction { .. } => {
                 self.execute_transaction_op(&operation.content.op, sender_addr)
             }
@@ -464,6 +682,20 @@ impl ExecutionState {
                     );
                 }
                 Err(err) => {
+                    // keep a structured failure reason before the call stack is reset:
+                    // after a VM error, the top of the stack is the smart contract that failed
+                    let code = err.failure_code();
+                    let failure = OperationFailure {
+                        code,
+                        message: err.to_string(),
+                        address: if matches!(err, ExecutionError::VMError { .. }) {
+                            context.stack.last().map(|element| element.address)
+                        } else {
+                            None
+                        },
+                        remaining_gas: (code == OperationFailureCode::OutOfGas).then_some(0),
+                    };
+
                     // an error occurred: emit error event and reset context to snapshot
                     let err = ExecutionError::RuntimeError(format!(
                         "runtime error when executing operation {}: {}",
@@ -471,6 +703,7 @@ impl ExecutionState {
                     ));
                     debug!("{}", &err);
                     context.reset_to_snapshot(context_snapshot, err);
+                    context.operation_failures.insert(operation_id, failure);
 
                     // Insert op AFTER the context has been restored (otherwise it would be overwritten)
                     context.insert_executed_op(
@@ -480,6 +713,15 @@ impl ExecutionState {
                     )
                 }
             }
+
+            // record the ledger changes caused by the operation, fees included
+            let operation_changes = context
+                .speculative_ledger
+                .get_snapshot()
+                .diff_since(&ledger_changes_before);
+            context
+                .operation_changes
+                .insert(operation_id, operation_changes);
         }
 
         Ok(())
@@ -666,6 +908,67 @@ impl ExecutionState {
         Ok(())
     }
 
+    /// Execute an operation of type `RollTransfer`
+    /// Will panic if called with another operation type
+    ///
+    /// # Arguments
+    /// * `operation`: the `WrappedOperation` to process, must be an `RollTransfer`
+    /// * `sender_addr`: address of the sender
+    pub fn execute_roll_transfer_op(
+        &self,
+        operation: &OperationType,
+        sender_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // process roll transfer operations only
+        let (recipient_addr, roll_count) = match operation {
+            OperationType::RollTransfer {
+                recipient_address,
+                roll_count,
+            } => (recipient_address, roll_count),
+            _ => panic!("unexpected operation type"),
+        };
+
+        // acquire write access to the context
+        let mut context = context_guard!(self);
+
+        // Set call stack
+        // This needs to be defined before anything can fail, so that the emitted event contains the right stack
+        context.stack = vec![ExecutionStackElement {
+            address: sender_addr,
+            coins: Amount::default(),
+            owned_addresses: vec![sender_addr],
+            operation_datastore: None,
+        }];
+
+        // roll transfers are only valid once their network version is active
+        let slot_ts = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            context.slot,
+        )
+        .expect("Cannot get timestamp from slot");
+        if self
+            .mip_store
+            .get_latest_component_version_at(&MipComponent::RollTransfer, slot_ts)
+            == 0
+        {
+            return Err(ExecutionError::RollTransferError(format!(
+                "{} tried to transfer rolls before roll transfers are activated",
+                sender_addr
+            )));
+        }
+
+        // try to transfer the rolls
+        if let Err(err) = context.try_transfer_rolls(&sender_addr, recipient_addr, *roll_count) {
+            return Err(ExecutionError::RollTransferError(format!(
+                "{} failed to transfer {} rolls to {}: {}",
+                sender_addr, roll_count, recipient_addr, err
+            )));
+        }
+        Ok(())
+    }
+
     /// Execute an operation of type `RollBuy`
     /// Will panic if called with another operation type
     ///
@@ -949,6 +1252,7 @@ impl ExecutionState {
         let bytecode = {
             let mut context = context_guard!(self);
             context_snapshot = context.get_snapshot();
+            context.transfer_origin = TransferOrigin::AsyncMessage;
             context.max_gas = message.max_gas;
             context.creator_address = None;
             context.creator_min_balance = None;
@@ -1079,6 +1383,9 @@ impl ExecutionState {
         // Apply the created execution context for slot execution
         *context_guard!(self) = execution_context;
 
+        // Only profile the host ABI calls made while executing the slot
+        self.abi_profiler.lock().start_slot();
+
         // Try executing asynchronous messages.
         // Effects are cancelled on failure and the sender is reimbursed.
         for (opt_bytecode, message) in messages {
@@ -1185,6 +1492,7 @@ impl ExecutionState {
             context.update_production_stats(&block_creator_addr, *slot, Some(*block_id));
 
             // Credit endorsement producers and endorsed block producers
+            context.transfer_origin = TransferOrigin::BlockReward;
             let mut remaining_credit = block_credits;
             let block_credit_part = block_credits
                 .checked_div_u64(3 * (1 + (self.config.endorsement_count)))
@@ -1246,21 +1554,17 @@ impl ExecutionState {
 
         // Finish slot
         let exec_out = context_guard!(self).settle_slot(block_info);
+        self.abi_profiler.lock().settle_slot(*slot);
 
         // Broadcast a slot execution output to active channel subscribers.
         if self.config.broadcast_enabled {
-            let slot_exec_out = SlotExecutionOutput::ExecutedSlot(exec_out.clone());
-            if let Err(err) = self
-                .channels
-                .slot_execution_output_sender
-                .send(slot_exec_out)
-            {
-                trace!(
-                    "error, failed to broadcast execution output for slot {} due to: {}",
-                    exec_out.slot.clone(),
-                    err
-                );
+            let mut broadcast_exec_out = exec_out.clone();
+            if !self.config.broadcast_operation_changes {
+                broadcast_exec_out.operation_changes.clear();
             }
+            self.broadcast_slot_execution_output(SlotExecutionOutput::ExecutedSlot(
+                broadcast_exec_out,
+            ));
         }
 
         // Return the execution output
@@ -1383,19 +1687,58 @@ impl ExecutionState {
         &self,
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
-        // TODO ensure that speculative things are reset after every execution ends (incl. on error and readonly)
-        // otherwise, on prod stats accumulation etc... from the API we might be counting the remainder of this speculative execution
+        self.execute_readonly_batch(vec![req])
+            .pop()
+            .expect("a read-only batch returns one output per request")
+    }
 
-        // check if read only request max gas is above the threshold
-        if req.max_gas > self.config.max_read_only_gas {
-            return Err(ExecutionError::TooMuchGas(format!(
-                "execution gas for read-only call is {} which is above the maximum allowed {}",
-                req.max_gas, self.config.max_read_only_gas
-            )));
+    /// Runs a batch of read-only execution requests one after the other.
+    /// No slot is executed in the middle of a batch, so all its requests read the same state.
+    /// Consecutive requests reading the same snapshot (same `is_final` and `historical_slot`)
+    /// reuse the same speculative context, which is reset between executions.
+    ///
+    /// # Arguments
+    /// * `reqs`: the read-only execution requests of the batch
+    ///
+    /// # Returns
+    /// The output of each request, in the order of the requests
+    pub(crate) fn execute_readonly_batch(
+        &self,
+        reqs: Vec<ReadOnlyExecutionRequest>,
+    ) -> Vec<Result<ReadOnlyExecutionOutput, ExecutionError>> {
+        let mut shared_context = None;
+        reqs.into_iter()
+            .map(|req| self.execute_readonly_request_in(req, &mut shared_context))
+            .collect()
+    }
+
+    /// Prepares the execution context for a read-only request of a batch,
+    /// reusing the context of the previous request if it reads the same snapshot.
+    ///
+    /// # Arguments
+    /// * `req`: the read-only execution request
+    /// * `shared_context`: the context reused since the previous request of the batch, if any
+    fn prepare_readonly_context(
+        &self,
+        req: &ReadOnlyExecutionRequest,
+        shared_context: &mut Option<SharedReadOnlyContext>,
+    ) -> Result<(), ExecutionError> {
+        let snapshot_key = (req.is_final, req.historical_slot);
+        if let Some((key, initial_state)) = shared_context.as_ref() {
+            if *key == snapshot_key {
+                context_guard!(self).reset_readonly_to_snapshot(initial_state.clone());
+                return Ok(());
+            }
         }
 
-        // set the execution slot to be the one after the latest executed active or final slot
-        let slot = if req.is_final {
+        // set the execution slot to be the one after the historical slot,
+        // or after the latest executed active or final slot
+        let slot = if let Some(historical_slot) = req.historical_slot {
+            self.check_historical_slot(historical_slot)?;
+            historical_slot
+                .get_next_slot(self.config.thread_count)
+                .expect("slot overflow in readonly execution from historical slot")
+        } else if req.is_final {
             self.final_cursor
                 .get_next_slot(self.config.thread_count)
                 .expect("slot overflow in readonly execution from final slot")
@@ -1406,32 +1749,69 @@ impl ExecutionState {
         };
 
         // create a readonly execution context
-        let execution_context = ExecutionContext::readonly(
+        let mut execution_context = ExecutionContext::readonly(
             self.config.clone(),
             slot,
             req.max_gas,
-            req.call_stack,
+            Vec::new(),
             self.final_state.clone(),
             self.active_history.clone(),
             self.module_cache.clone(),
             self.mip_store.clone(),
         );
+        if let Some(historical_slot) = req.historical_slot {
+            execution_context.set_historical_slot(historical_slot)?;
+        }
+        *shared_context = Some((snapshot_key, execution_context.get_snapshot()));
+        *context_guard!(self) = execution_context;
+        Ok(())
+    }
 
-        // run the interpreter according to the target type
-        let exec_response = match req.target {
-            ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
-                {
-                    let mut context = context_guard!(self);
-                    *context = execution_context;
+    /// Runs a read-only execution request of a batch (see `execute_readonly_batch`)
+    fn execute_readonly_request_in(
+        &self,
+        req: ReadOnlyExecutionRequest,
+        shared_context: &mut Option<SharedReadOnlyContext>,
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        // TODO ensure that speculative things are reset after every execution ends (incl. on error and readonly)
+        // otherwise, on prod stats accumulation etc... from the API we might be counting the remainder of this speculative execution
 
-                    let call_stack_addr = context.get_call_stack();
+        // check if read only request max gas is above the threshold
+        if req.max_gas > self.config.max_read_only_gas {
+            return Err(ExecutionError::TooMuchGas(format!(
+                "execution gas for read-only call is {} which is above the maximum allowed {}",
+                req.max_gas, self.config.max_read_only_gas
+            )));
+        }
 
-                    // transfer fee
-                    if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.get(0)) {
-                        context.transfer_coins(Some(*addr), None, fee, false)?;
-                    }
+        self.prepare_readonly_context(&req, shared_context)?;
+        {
+            let mut context = context_guard!(self);
+            context.max_gas = req.max_gas;
+            context.stack = req.call_stack;
+            context.apply_state_overrides(req.state_overrides)?;
+            context.trace = req.trace.then(Vec::new);
+
+            let call_stack_addr = context.get_call_stack();
+
+            // transfer fee
+            if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.get(0)) {
+                context.transfer_coins(Some(*addr), None, fee, false)?;
+            }
+
+            // transfer coins
+            if let ReadOnlyExecutionTarget::FunctionCall { .. } = req.target {
+                if let (Some(coins), Some(from), Some(to)) =
+                    (req.coins, call_stack_addr.get(0), call_stack_addr.get(1))
+                {
+                    context.transfer_coins(Some(*from), Some(*to), coins, false)?;
                 }
+            }
+        }
 
+        // run the interpreter according to the target type
+        let exec_response = match req.target {
+            ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
                 // load the tmp module
                 let module = self
                     .module_cache
@@ -1455,30 +1835,11 @@ impl ExecutionState {
                 parameter,
             } => {
                 // get the bytecode, default to an empty vector
-                let bytecode = execution_context
+                let bytecode = context_guard!(self)
                     .get_bytecode(&target_addr)
                     .unwrap_or_default()
                     .0;
 
-                {
-                    let mut context = context_guard!(self);
-                    *context = execution_context;
-
-                    let call_stack_addr = context.get_call_stack();
-
-                    // transfer fee
-                    if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.get(0)) {
-                        context.transfer_coins(Some(*addr), None, fee, false)?;
-                    }
-
-                    // transfer coins
-                    if let (Some(coins), Some(from), Some(to)) =
-                        (req.coins, call_stack_addr.get(0), call_stack_addr.get(1))
-                    {
-                        context.transfer_coins(Some(*from), Some(*to), coins, false)?;
-                    }
-                }
-
                 // load and execute the compiled module
                 // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
                 let module = self
@@ -1510,14 +1871,68 @@ impl ExecutionState {
         };
 
         // return the execution output
-        let execution_output = context_guard!(self).settle_slot(None);
+        let (execution_output, trace) = {
+            let mut context = context_guard!(self);
+            let trace = context.trace.take();
+            (context.settle_slot(None), trace)
+        };
         Ok(ReadOnlyExecutionOutput {
             out: execution_output,
             gas_cost: req.max_gas.saturating_sub(exec_response.remaining_gas),
             call_result: exec_response.ret,
+            trace,
         })
     }
 
+    /// Checks that the ledger can be read at the end of a past final slot
+    pub fn check_historical_slot(&self, slot: Slot) -> Result<(), ExecutionError> {
+        if slot > self.final_cursor {
+            return Err(ExecutionError::HistoricalSlotError(format!(
+                "slot {} is not final yet",
+                slot
+            )));
+        }
+        match self.final_state.read().db.read().get_archive_horizon() {
+            None => Err(ExecutionError::HistoricalSlotError(
+                "the archival mode is disabled".to_string(),
+            )),
+            Some(horizon) if slot < horizon => Err(ExecutionError::HistoricalSlotError(format!(
+                "slot {} is older than the oldest archived slot {}",
+                slot, horizon
+            ))),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Gets the balance of an address at the end of a past final slot, from the archive
+    pub fn get_historical_balance(
+        &self,
+        address: &Address,
+        slot: Slot,
+    ) -> Result<Option<Amount>, ExecutionError> {
+        self.check_historical_slot(slot)?;
+        self.final_state
+            .read()
+            .ledger
+            .get_balance_at_slot(address, slot)
+            .map_err(|err| ExecutionError::HistoricalSlotError(err.to_string()))
+    }
+
+    /// Gets a datastore value of an address at the end of a past final slot, from the archive
+    pub fn get_historical_data_entry(
+        &self,
+        address: &Address,
+        key: &[u8],
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, ExecutionError> {
+        self.check_historical_slot(slot)?;
+        self.final_state
+            .read()
+            .ledger
+            .get_data_entry_at_slot(address, key, slot)
+            .map_err(|err| ExecutionError::HistoricalSlotError(err.to_string()))
+    }
+
     /// Gets a balance both at the latest final and candidate executed slots
     pub fn get_final_and_candidate_balance(
         &self,
@@ -1582,29 +1997,55 @@ impl ExecutionState {
         )
     }
 
-    /// Get every final and active datastore key of the given address
+    /// Get the final and active datastore keys of the given address selected by a filter
     #[allow(clippy::type_complexity)]
     pub fn get_final_and_candidate_datastore_keys(
         &self,
         addr: &Address,
-        prefix: &[u8],
+        filter: &DatastoreKeysFilter,
     ) -> (Option<BTreeSet<Vec<u8>>>, Option<BTreeSet<Vec<u8>>>) {
+        let active_history = self.active_history.read();
+        // when no key can be selected, only the existence of the address is checked
+        let (lower, upper, limit) = match filter.key_bounds() {
+            Some((lower, upper)) => (lower, upper, filter.limit),
+            None => (Vec::new(), Bound::Unbounded, Some(0)),
+        };
+        let range_ref = (Bound::Included(&lower), upper.as_ref());
+
+        // each key deleted by the candidate slots can shorten the final page by one:
+        // fetch as many extra final keys so that the candidate page is still complete
+        let deleted_key_count = active_history
+            .0
+            .iter()
+            .filter_map(
+                |output| match output.state_changes.ledger_changes.get(addr) {
+                    Some(SetUpdateOrDelete::Update(entry_updates)) => Some(
+                        entry_updates
+                            .datastore
+                            .range::<Vec<u8>, _>(range_ref)
+                            .filter(|(_k, ds_update)| matches!(ds_update, SetOrDelete::Delete))
+                            .count(),
+                    ),
+                    _ => None,
+                },
+            )
+            .sum::<usize>();
+        let final_filter = DatastoreKeysFilter {
+            limit: limit.map(|limit| limit.saturating_add(deleted_key_count)),
+            ..filter.clone()
+        };
+
         // here, get the final keys from the final ledger, and make a copy of it for the candidate list
-        // let final_keys = final_state.read().ledger.get_datastore_keys(addr);
         let final_keys = self
             .final_state
             .read()
             .ledger
-            .get_datastore_keys(addr, prefix);
+            .get_datastore_keys_filtered(addr, &final_filter);
 
         let mut candidate_keys = final_keys.clone();
 
-        // compute prefix range
-        let prefix_range = get_prefix_bounds(prefix);
-        let range_ref = (prefix_range.0.as_ref(), prefix_range.1.as_ref());
-
         // traverse the history from oldest to newest, applying additions and deletions
-        for output in &self.active_history.read().0 {
+        for output in &active_history.0 {
             match output.state_changes.ledger_changes.get(addr) {
                 // address absent from the changes
                 None => (),
@@ -1640,7 +2081,12 @@ impl ExecutionState {
             }
         }
 
-        (final_keys, candidate_keys)
+        // only keep the first keys of each list
+        let truncate = |keys: Option<BTreeSet<Vec<u8>>>| match limit {
+            Some(limit) => keys.map(|keys| keys.into_iter().take(limit).collect()),
+            None => keys,
+        };
+        (truncate(final_keys), truncate(candidate_keys))
     }
 
     pub fn get_address_cycle_infos(&self, address: &Address) -> Vec<ExecutionAddressCycleInfo> {
@@ -1658,6 +2104,20 @@ impl ExecutionState {
             .get_all_active_rolls(cycle)
     }
 
+    /// Returns the roll distribution statistics of every cycle in the final PoS history, oldest first.
+    pub fn get_staker_distribution_history(&self, top_n: usize) -> Vec<StakerDistribution> {
+        self.final_state
+            .read()
+            .pos_state
+            .get_staker_distribution_history(top_n)
+    }
+
+    /// Gets the latest slot of which final events were pruned from the disk store.
+    /// Final events at or before this slot may be missing from the query results.
+    pub fn get_final_events_pruned_until(&self) -> Option<Slot> {
+        self.final_events.pruned_until()
+    }
+
     /// Gets execution events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -1665,33 +2125,307 @@ impl ExecutionState {
     /// * original caller address
     /// * operation id
     /// * event state (final, candidate or both)
+    ///
+    /// Final events come first, followed by the candidate ones.
+    /// The result is then paginated by the offset and limit of the filter,
+    /// and never holds more than `max_final_events` events.
     pub fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent> {
-        match filter.is_final {
-            Some(true) => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .collect(),
-            Some(false) => self
-                .active_history
-                .read()
+        let offset = filter.offset.unwrap_or(0);
+        let limit = filter
+            .limit
+            .unwrap_or(usize::MAX)
+            .min(self.config.max_final_events);
+        // final events past `offset + limit` can't be returned, so they are not read from disk
+        let max_final_count = offset.saturating_add(limit);
+        let final_events = || {
+            self.final_events
+                .get_filtered_sc_output_events(&filter, max_final_count)
+        };
+        let active_history = self.active_history.read();
+        let candidate_events = || {
+            active_history
                 .0
                 .iter()
                 .flat_map(|item| item.events.get_filtered_sc_output_events(&filter))
-                .collect(),
-            None => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
+        };
+        let events: Box<dyn Iterator<Item = SCOutputEvent> + '_> = match filter.is_final {
+            Some(true) => Box::new(final_events().into_iter()),
+            Some(false) => Box::new(candidate_events()),
+            None => Box::new(final_events().into_iter().chain(candidate_events())),
+        };
+        events.skip(offset).take(limit).collect()
+    }
+
+    /// Gets the coin transfers that happened at each executed slot between `start` and `end` (inclusive).
+    /// Final slots come first, read from the on-disk store, followed by the active ones.
+    /// The result never holds more than `max_transfers_per_query` transfers.
+    pub fn get_slot_transfers(&self, start: Slot, end: Slot) -> Vec<SlotTransfers> {
+        let limit = self.config.max_transfers_per_query;
+        let final_transfers = self
+            .final_transfer_store
+            .get_slot_transfers(start, end, limit)
+            .into_iter()
+            .map(|(slot, transfer)| (slot, true, transfer));
+        let active_history = self.active_history.read();
+        let candidate_transfers = active_history
+            .0
+            .iter()
+            .filter(|output| output.slot >= start && output.slot <= end)
+            .flat_map(|output| {
+                output
+                    .transfers
+                    .iter()
+                    .map(|transfer| (output.slot, false, transfer.clone()))
+            });
+
+        // group the transfers by slot
+        let mut res: Vec<SlotTransfers> = Vec::new();
+        for (slot, is_final, transfer) in final_transfers.chain(candidate_transfers).take(limit) {
+            match res.last_mut() {
+                Some(last) if last.slot == slot && last.is_final == is_final => {
+                    last.transfers.push(transfer)
+                }
+                _ => res.push(SlotTransfers {
+                    slot,
+                    is_final,
+                    transfers: vec![transfer],
+                }),
+            }
+        }
+        res
+    }
+
+    /// Gets the coin transfers spending or crediting an address, with their slot, oldest first.
+    /// Final transfers come first, read from the on-disk index, followed by the candidate ones.
+    /// The result is paginated by the offset and limit of the filter,
+    /// and never holds more than `max_transfers_per_query` transfers.
+    pub fn get_address_transfers(&self, filter: TransferFilter) -> Vec<SlotTransfers> {
+        let offset = filter.offset.unwrap_or(0);
+        let limit = filter
+            .limit
+            .unwrap_or(usize::MAX)
+            .min(self.config.max_transfers_per_query);
+
+        let mut transfers: Vec<(Slot, bool, TransferInfo)> = Vec::new();
+        if filter.is_final != Some(false) {
+            transfers.extend(
+                self.final_transfer_store
+                    .get_address_transfers(&filter, offset.saturating_add(limit))
+                    .into_iter()
+                    .map(|(slot, transfer)| (slot, true, transfer)),
+            );
+        }
+        if filter.is_final != Some(true) {
+            let involves_address = |transfer: &TransferInfo| {
+                transfer.from == Some(filter.address) || transfer.to == Some(filter.address)
+            };
+            transfers.extend(
+                self.active_history
+                    .read()
+                    .0
+                    .iter()
+                    .filter(|output| filter.contains_slot(&output.slot))
+                    .flat_map(|output| {
+                        output
+                            .transfers
+                            .iter()
+                            .filter(|transfer| involves_address(transfer))
+                            .map(|transfer| (output.slot, false, transfer.clone()))
+                    }),
+            );
+        }
+
+        // group the selected transfers by slot
+        let mut res: Vec<SlotTransfers> = Vec::new();
+        for (slot, is_final, transfer) in transfers.into_iter().skip(offset).take(limit) {
+            match res.last_mut() {
+                Some(last) if last.slot == slot && last.is_final == is_final => {
+                    last.transfers.push(transfer)
+                }
+                _ => res.push(SlotTransfers {
+                    slot,
+                    is_final,
+                    transfers: vec![transfer],
+                }),
+            }
+        }
+        res
+    }
+
+    /// Gets the ledger changes caused by each operation of a batch,
+    /// looking first at the candidate executions then at the last final slots.
+    /// `None` is returned for the operations that were not executed in these slots.
+    pub fn get_ops_ledger_changes(&self, batch: &[OperationId]) -> Vec<Option<LedgerChanges>> {
+        let active_history = self.active_history.read();
+        batch
+            .iter()
+            .map(|op_id| {
+                active_history
+                    .0
+                    .iter()
+                    .rev()
+                    .map(|output| &output.operation_changes)
+                    .chain(
+                        self.final_operation_changes
+                            .iter()
+                            .rev()
+                            .map(|(_, operation_changes)| operation_changes),
+                    )
+                    .find_map(|operation_changes| operation_changes.get(op_id).cloned())
+            })
+            .collect()
+    }
+
+    /// Gets the failure reason of each operation of a batch,
+    /// looking first at the candidate executions then at the last final slots.
+    /// `None` is returned for the operations that did not fail in these slots.
+    pub fn get_ops_failures(&self, batch: &[OperationId]) -> Vec<Option<OperationFailure>> {
+        let active_history = self.active_history.read();
+        batch
+            .iter()
+            .map(|op_id| {
+                active_history
+                    .0
+                    .iter()
+                    .rev()
+                    .map(|output| &output.operation_failures)
+                    .chain(
+                        self.final_operation_failures
+                            .iter()
+                            .rev()
+                            .map(|(_, operation_failures)| operation_failures),
+                    )
+                    .find_map(|operation_failures| operation_failures.get(op_id).cloned())
+            })
+            .collect()
+    }
+
+    /// Checks a bytecode against the limits of the execution engine without executing it.
+    /// The module is compiled only if the static inspection found no error.
+    pub fn validate_bytecode(&self, bytecode: &[u8]) -> BytecodeValidation {
+        let mut validation = inspect_bytecode(bytecode, self.config.max_bytecode_size);
+        if validation.is_valid() {
+            if let Err(err) = self
+                .module_cache
+                .read()
+                .load_tmp_module(bytecode, self.config.max_read_only_gas)
+            {
+                validation.diagnostics.push(BytecodeDiagnostic {
+                    severity: BytecodeDiagnosticSeverity::Error,
+                    message: format!("module rejected by the execution engine: {}", err),
+                });
+            }
+        }
+        validation
+    }
+
+    /// Gets the current sizes of the execution caches
+    pub fn get_cache_sizes(&self) -> ExecutionCacheSizes {
+        let module_cache = self.module_cache.read();
+        ExecutionCacheSizes {
+            module_lru_cache_size: module_cache.lru_cache_size(),
+            module_hd_cache_size: module_cache.hd_cache_size(),
+            max_final_operation_changes_slots: self.config.max_final_operation_changes_slots,
+        }
+    }
+
+    /// Resizes the execution caches, dropping the entries in excess right away
+    pub fn set_cache_sizes(&mut self, sizes: ExecutionCacheSizes) -> Result<(), ExecutionError> {
+        self.module_cache
+            .write()
+            .resize(sizes.module_lru_cache_size, sizes.module_hd_cache_size)?;
+
+        self.config.max_final_operation_changes_slots = sizes.max_final_operation_changes_slots;
+        self.trim_final_caches();
+        Ok(())
+    }
+
+    /// Drops the oldest slots of the in-memory caches of final slot outputs that exceed their configured size
+    fn trim_final_caches(&mut self) {
+        while self.final_operation_changes.len() > self.config.max_final_operation_changes_slots {
+            self.final_operation_changes.pop_front();
+        }
+        while self.final_operation_failures.len() > self.config.max_final_operation_changes_slots {
+            self.final_operation_failures.pop_front();
+        }
+    }
+
+    /// Gets the call count and time of each host ABI function over the last `slot_count` executed slots
+    pub fn get_abi_call_stats(&self, slot_count: usize) -> Vec<AbiCallStats> {
+        self.abi_profiler.lock().get_call_stats(slot_count)
+    }
+
+    /// Gets the messages of the asynchronous pool matching a filter.
+    /// The final pool is read as is. Otherwise the changes of the candidate slots are applied
+    /// on top of it, so that messages updated or deleted by a candidate slot are reported as such.
+    pub fn get_async_messages(&self, filter: &AsyncMessageFilter) -> Vec<AsyncPoolMessage> {
+        let active_history = self.active_history.read();
+
+        // messages touched by the candidate slots
+        let candidate_ids: BTreeSet<AsyncMessageId> = if filter.is_final == Some(true) {
+            BTreeSet::new()
+        } else {
+            active_history
+                .0
+                .iter()
+                .flat_map(|output| output.state_changes.async_pool_changes.0.keys())
+                .copied()
+                .collect()
+        };
+
+        let mut messages: BTreeMap<AsyncMessageId, (AsyncMessage, bool)> = {
+            let final_state = self.final_state.read();
+            let async_pool = &final_state.async_pool;
+            // pre-filter on the message info cache to avoid fetching unneeded messages from disk,
+            // but keep the ones a candidate slot may update
+            let message_ids: Vec<&AsyncMessageId> = async_pool
+                .message_info_cache
+                .iter()
+                .filter(|(id, info)| {
+                    candidate_ids.contains(id)
+                        || filter.matches_slots(id.1, (info.validity_start, info.validity_end))
+                })
+                .map(|(id, _)| id)
+                .collect();
+            async_pool
+                .fetch_messages(message_ids)
                 .into_iter()
-                .chain(
-                    self.active_history
-                        .read()
-                        .0
-                        .iter()
-                        .flat_map(|item| item.events.get_filtered_sc_output_events(&filter)),
-                )
-                .collect(),
+                .filter_map(|(id, message)| message.map(|message| (*id, (message, true))))
+                .collect()
+        };
+
+        if filter.is_final != Some(true) {
+            for output in active_history.0.iter() {
+                for (id, change) in output.state_changes.async_pool_changes.0.iter() {
+                    match change {
+                        SetUpdateOrDelete::Set(message) => {
+                            messages.insert(*id, (message.clone(), false));
+                        }
+                        SetUpdateOrDelete::Update(update) => {
+                            if let Some((message, is_final)) = messages.get_mut(id) {
+                                message.apply(update.clone());
+                                *is_final = false;
+                            }
+                        }
+                        SetUpdateOrDelete::Delete => {
+                            messages.remove(id);
+                        }
+                    }
+                }
+            }
         }
+
+        messages
+            .into_iter()
+            .filter(|(_, (message, is_final))| {
+                filter.is_final.map_or(true, |f| f == *is_final) && filter.matches(message)
+            })
+            .map(|(id, (message, is_final))| AsyncPoolMessage {
+                id,
+                message,
+                is_final,
+            })
+            .collect()
     }
 
     /// Check if a denunciation has been executed given a `DenunciationIndex`
@@ -1835,12 +2569,33 @@ impl ExecutionState {
     ///  Note that old op executions are forgotten.
     /// Otherwise, the status is a boolean indicating whether the execution was successful (true) or if there was an error (false.)
     pub fn get_ops_exec_status(&self, batch: &[OperationId]) -> Vec<(Option<bool>, Option<bool>)> {
-        let speculative_exec = self.active_history.read().get_ops_exec_status(batch);
+        self.get_ops_exec_info(batch)
+            .into_iter()
+            .map(|(speculative_v, final_v)| {
+                (
+                    speculative_v.map(|info| info.success),
+                    final_v.map(|info| info.success),
+                )
+            })
+            .collect()
+    }
+
+    /// Get the execution status and slot of a batch of operations.
+    ///
+    ///  Return value: vector of
+    ///  `(Option<speculative_execution>, Option<final_execution>)`
+    ///  If an Option is None it means that the op execution was not found.
+    ///  Note that old op executions are forgotten.
+    pub fn get_ops_exec_info(
+        &self,
+        batch: &[OperationId],
+    ) -> Vec<(Option<OpExecutionInfo>, Option<OpExecutionInfo>)> {
+        let speculative_exec = self.active_history.read().get_ops_exec_info(batch);
         let final_exec = self
             .final_state
             .read()
             .executed_ops
-            .get_ops_exec_status(batch);
+            .get_ops_exec_info(batch);
         speculative_exec
             .into_iter()
             .zip(final_exec)
@@ -0,0 +1,269 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module stores the final execution events on disk, in the `EVENTS_CF` column family of the database.
+//!
+//! Events are indexed by slot, by (emitter address, slot) and by (original operation id, slot),
+//! so that the usual filters do not require scanning every stored event.
+//! Events older than a configurable number of periods are pruned as new slots become final,
+//! as well as the oldest events when the store exceeds its configurable capacity.
+//! The latest slot of which events were pruned is kept so that clients can know
+//! whether the events of a slot range are complete.
+//! The column family is local to the node: it is neither hashed nor bootstrapped.
+
+use massa_db_exports::{
+    DBBatch, MassaDBController, MassaDirection, ShareableMassaDBController, CRUD_ERROR, EVENTS_CF,
+};
+use massa_execution_exports::EventStore;
+use massa_models::datastore::get_prefix_bounds;
+use massa_models::execution::EventFilter;
+use massa_models::operation::{OperationId, OperationIdSerializer};
+use massa_models::output_event::SCOutputEvent;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::Serializer;
+use std::ops::Bound;
+use tracing::warn;
+
+/// Prefix of the events, keyed by (slot, index in slot)
+const EVENT_IDENT: u8 = 0u8;
+/// Prefix of the emitter address index, keyed by (address, slot, index in slot)
+const EMITTER_IDENT: u8 = 1u8;
+/// Prefix of the original operation index, keyed by (operation id, slot, index in slot)
+const OPERATION_IDENT: u8 = 2u8;
+/// Prefix of the metadata of the store
+const METADATA_IDENT: u8 = 3u8;
+
+/// Key of the number of stored events
+const COUNT_KEY: [u8; 2] = [METADATA_IDENT, 0];
+/// Key of the latest slot of which events were pruned
+const PRUNED_UNTIL_KEY: [u8; 2] = [METADATA_IDENT, 1];
+
+/// Size of the (slot, index in slot) suffix shared by all the keys
+const EVENT_ID_SIZE: usize = SLOT_KEY_SIZE + 8;
+
+/// Store of the final execution events, persisted on disk
+pub(crate) struct FinalEventStore {
+    /// database holding the events
+    db: ShareableMassaDBController,
+    /// number of periods for which events are kept
+    retention_periods: u64,
+    /// maximum number of stored events, the oldest ones being pruned first
+    capacity: u64,
+    /// number of stored events
+    count: u64,
+    /// latest slot of which events were pruned, older events are missing
+    pruned_until: Option<Slot>,
+}
+
+impl FinalEventStore {
+    /// Creates a store writing to the `EVENTS_CF` column family of `db`,
+    /// keeping at most `capacity` events for `retention_periods` periods
+    pub fn new(db: ShareableMassaDBController, retention_periods: u64, capacity: u64) -> Self {
+        let (count, pruned_until) = {
+            let db = db.read();
+            let count = match db.get_cf(EVENTS_CF, COUNT_KEY.to_vec()).expect(CRUD_ERROR) {
+                Some(count) => u64::from_be_bytes(
+                    count
+                        .try_into()
+                        .expect("critical: invalid stored event count"),
+                ),
+                // stores written before the count was persisted
+                None => events_iterator(&**db).count() as u64,
+            };
+            let pruned_until = db
+                .get_cf(EVENTS_CF, PRUNED_UNTIL_KEY.to_vec())
+                .expect(CRUD_ERROR)
+                .map(|slot| {
+                    Slot::from_bytes_key(
+                        &slot
+                            .try_into()
+                            .expect("critical: invalid stored event pruning slot"),
+                    )
+                });
+            (count, pruned_until)
+        };
+        FinalEventStore {
+            db,
+            retention_periods,
+            capacity,
+            count,
+            pruned_until,
+        }
+    }
+
+    /// Latest slot of which events were pruned, either by age or because the store was full.
+    /// Final events at or before this slot may be missing.
+    pub fn pruned_until(&self) -> Option<Slot> {
+        self.pruned_until
+    }
+
+    /// Stores the final events of a slot, and prunes the events that are older than the retention horizon
+    /// or exceed the capacity of the store.
+    ///
+    /// Returns the number of events pruned because the store was full.
+    pub fn push_slot_events(&mut self, slot: Slot, events: &EventStore) -> u64 {
+        if self.retention_periods == 0 || self.capacity == 0 {
+            return 0;
+        }
+
+        // if the slot alone exceeds the capacity, only its last events are kept
+        let skipped = events.0.len().saturating_sub(self.capacity as usize);
+        if skipped > 0 {
+            self.pruned_until = Some(slot);
+        }
+        let mut truncated = skipped as u64;
+
+        let mut batch = DBBatch::new();
+        let mut new_count = self.count;
+        for (index, event) in events.0.iter().enumerate().skip(skipped) {
+            let event_id = event_id(slot, index as u64);
+            match serde_json::to_vec(event) {
+                Ok(serialized_event) => {
+                    for index_key in index_keys(event, &event_id) {
+                        batch.insert(index_key, Some(Vec::new()));
+                    }
+                    batch.insert(
+                        [&[EVENT_IDENT][..], &event_id[..]].concat(),
+                        Some(serialized_event),
+                    );
+                    new_count += 1;
+                }
+                Err(err) => warn!("could not serialize an event of slot {}: {}", slot, err),
+            }
+        }
+
+        // prune, oldest first, the events that left the retention horizon or exceed the capacity,
+        // with their index entries
+        let min_slot = Slot::new(slot.period.saturating_sub(self.retention_periods - 1), 0);
+        let db = self.db.read();
+        for (serialized_key, serialized_event) in events_iterator(&**db) {
+            let event_slot = Slot::from_bytes_key(
+                &serialized_key[1..1 + SLOT_KEY_SIZE]
+                    .try_into()
+                    .expect("critical: invalid stored event key"),
+            );
+            let expired = event_slot < min_slot;
+            if !expired && new_count <= self.capacity {
+                break;
+            }
+            if !expired {
+                truncated += 1;
+            }
+            if let Ok(event) = serde_json::from_slice::<SCOutputEvent>(&serialized_event) {
+                for index_key in index_keys(&event, &serialized_key[1..]) {
+                    batch.insert(index_key, None);
+                }
+            }
+            batch.insert(serialized_key, None);
+            new_count = new_count.saturating_sub(1);
+            self.pruned_until = std::cmp::max(self.pruned_until, Some(event_slot));
+        }
+
+        batch.insert(COUNT_KEY.to_vec(), Some(new_count.to_be_bytes().to_vec()));
+        if let Some(pruned_until) = self.pruned_until {
+            batch.insert(
+                PRUNED_UNTIL_KEY.to_vec(),
+                Some(pruned_until.to_bytes_key().to_vec()),
+            );
+        }
+        match db.write_local_batch(EVENTS_CF, batch) {
+            Ok(()) => self.count = new_count,
+            Err(err) => warn!("could not store the events of slot {}: {}", slot, err),
+        }
+        truncated
+    }
+
+    /// Get at most `max_count` of the stored events matching the filter, oldest first.
+    ///
+    /// The operation id and emitter address indexes are used when the filter sets them,
+    /// otherwise only the events of the filtered slot range are read.
+    pub fn get_filtered_sc_output_events(
+        &self,
+        filter: &EventFilter,
+        max_count: usize,
+    ) -> Vec<SCOutputEvent> {
+        let db = self.db.read();
+
+        let index_prefix = if let Some(operation_id) = filter.original_operation_id {
+            Some(operation_prefix(&operation_id))
+        } else {
+            filter
+                .emitter_address
+                .map(|address| [&[EMITTER_IDENT][..], &address.to_prefixed_bytes()[..]].concat())
+        };
+        let prefix = index_prefix.clone().unwrap_or_else(|| vec![EVENT_IDENT]);
+
+        let start = match filter.start {
+            Some(start) => [&prefix[..], &start.to_bytes_key()[..]].concat(),
+            None => prefix.clone(),
+        };
+        let end = match filter.end {
+            Some(end) => Some([&prefix[..], &end.to_bytes_key()[..]].concat()),
+            None => match get_prefix_bounds(&prefix).1 {
+                Bound::Excluded(end) => Some(end),
+                _ => None,
+            },
+        };
+
+        db.range_iterator_cf(EVENTS_CF, &start, end.as_deref(), MassaDirection::Forward)
+            .filter_map(|(serialized_key, serialized_event)| {
+                let serialized_event = if index_prefix.is_some() {
+                    let event_id = &serialized_key[serialized_key.len() - EVENT_ID_SIZE..];
+                    db.get_cf(EVENTS_CF, [&[EVENT_IDENT][..], event_id].concat())
+                        .expect(CRUD_ERROR)?
+                } else {
+                    serialized_event
+                };
+                serde_json::from_slice::<SCOutputEvent>(&serialized_event)
+                    .map_err(|err| warn!("could not deserialize a stored event: {}", err))
+                    .ok()
+            })
+            .filter(|event| filter.matches(event))
+            .take(max_count)
+            .collect()
+    }
+}
+
+/// Iterates over the stored events, oldest first
+fn events_iterator<'a>(
+    db: &'a dyn MassaDBController,
+) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+    db.range_iterator_cf(
+        EVENTS_CF,
+        &[EVENT_IDENT],
+        Some(&[EVENT_IDENT + 1]),
+        MassaDirection::Forward,
+    )
+}
+
+/// Serializes the (slot, index in slot) identifier of an event, ordered as the events
+fn event_id(slot: Slot, index: u64) -> Vec<u8> {
+    [&slot.to_bytes_key()[..], &index.to_be_bytes()[..]].concat()
+}
+
+/// Prefix of the index entries of the events created by an operation
+fn operation_prefix(operation_id: &OperationId) -> Vec<u8> {
+    let mut prefix = vec![OPERATION_IDENT];
+    OperationIdSerializer::new()
+        .serialize(operation_id, &mut prefix)
+        .expect("operation id serialization never fails");
+    prefix
+}
+
+/// Keys of the index entries of an event
+fn index_keys(event: &SCOutputEvent, event_id: &[u8]) -> Vec<Vec<u8>> {
+    let mut keys = Vec::new();
+    if let Some(emitter_address) = event.context.call_stack.front() {
+        keys.push(
+            [
+                &[EMITTER_IDENT][..],
+                &emitter_address.to_prefixed_bytes()[..],
+                event_id,
+            ]
+            .concat(),
+        );
+    }
+    if let Some(operation_id) = event.context.origin_operation_id {
+        keys.push([&operation_prefix(&operation_id)[..], event_id].concat());
+    }
+    keys
+}
@@ -0,0 +1,248 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module stores the final native coin transfers on disk, in the `TRANSFERS_CF` column family of the database.
+//!
+//! Transfers are keyed by (slot, index in slot), and indexed by (address, slot, index in slot)
+//! for both the spending and the credited addresses, so that the transfers of an address
+//! can be paginated without replaying the slots.
+//! Transfers older than a configurable number of periods are pruned as new slots become final.
+//! The column family is local to the node: it is neither hashed nor bootstrapped.
+
+use massa_db_exports::{
+    DBBatch, MassaDirection, ShareableMassaDBController, CRUD_ERROR, TRANSFERS_CF,
+};
+use massa_execution_exports::{TransferInfo, TransferOrigin};
+use massa_models::address::{Address, AddressDeserializer, AddressSerializer};
+use massa_models::amount::Amount;
+use massa_models::datastore::get_prefix_bounds;
+use massa_models::execution::TransferFilter;
+use massa_models::operation::{OperationIdDeserializer, OperationIdSerializer};
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use std::ops::Bound;
+use tracing::warn;
+
+/// Prefix of the transfers, keyed by (slot, index in slot)
+const TRANSFER_IDENT: u8 = 0u8;
+/// Prefix of the address index, keyed by (address, slot, index in slot)
+const ADDRESS_IDENT: u8 = 1u8;
+
+/// Size of the (slot, index in slot) suffix shared by all the keys
+const TRANSFER_ID_SIZE: usize = SLOT_KEY_SIZE + 8;
+
+/// Store of the final native coin transfers, persisted on disk
+pub(crate) struct FinalTransferStore {
+    /// database holding the transfers
+    db: ShareableMassaDBController,
+    /// number of periods for which transfers are kept
+    retention_periods: u64,
+}
+
+impl FinalTransferStore {
+    /// Creates a store writing to the `TRANSFERS_CF` column family of `db`,
+    /// keeping transfers for `retention_periods` periods
+    pub fn new(db: ShareableMassaDBController, retention_periods: u64) -> Self {
+        FinalTransferStore {
+            db,
+            retention_periods,
+        }
+    }
+
+    /// Stores the final transfers of a slot, and prunes the transfers that are older than the retention horizon
+    pub fn push_slot_transfers(&self, slot: Slot, transfers: &[TransferInfo]) {
+        if self.retention_periods == 0 {
+            return;
+        }
+
+        let mut batch = DBBatch::new();
+        for (index, transfer) in transfers.iter().enumerate() {
+            let transfer_id = transfer_id(slot, index as u64);
+            for index_key in index_keys(transfer, &transfer_id) {
+                batch.insert(index_key, Some(Vec::new()));
+            }
+            batch.insert(
+                [&[TRANSFER_IDENT][..], &transfer_id[..]].concat(),
+                Some(serialize_transfer(transfer)),
+            );
+        }
+
+        // prune the transfers that left the retention horizon, with their index entries
+        let min_slot = Slot::new(slot.period.saturating_sub(self.retention_periods - 1), 0);
+        let db = self.db.read();
+        for (serialized_key, serialized_transfer) in db.range_iterator_cf(
+            TRANSFERS_CF,
+            &[TRANSFER_IDENT],
+            Some(&[&[TRANSFER_IDENT][..], &min_slot.to_bytes_key()[..]].concat()),
+            MassaDirection::Forward,
+        ) {
+            if let Some(transfer) = deserialize_transfer(&serialized_transfer) {
+                for index_key in index_keys(&transfer, &serialized_key[1..]) {
+                    batch.insert(index_key, None);
+                }
+            }
+            batch.insert(serialized_key, None);
+        }
+
+        if let Err(err) = db.write_local_batch(TRANSFERS_CF, batch) {
+            warn!("could not store the transfers of slot {}: {}", slot, err);
+        }
+    }
+
+    /// Get at most `max_count` of the stored transfers that happened between `start` and `end` (inclusive),
+    /// oldest first
+    pub fn get_slot_transfers(
+        &self,
+        start: Slot,
+        end: Slot,
+        max_count: usize,
+    ) -> Vec<(Slot, TransferInfo)> {
+        let db = self.db.read();
+        let upper_bound =
+            match get_prefix_bounds(&[&[TRANSFER_IDENT][..], &end.to_bytes_key()[..]].concat()).1 {
+                Bound::Excluded(end) => Some(end),
+                _ => None,
+            };
+        db.range_iterator_cf(
+            TRANSFERS_CF,
+            &[&[TRANSFER_IDENT][..], &start.to_bytes_key()[..]].concat(),
+            upper_bound.as_deref(),
+            MassaDirection::Forward,
+        )
+        .take(max_count)
+        .filter_map(|(serialized_key, serialized_transfer)| {
+            let slot = Slot::from_bytes_key(
+                serialized_key[1..1 + SLOT_KEY_SIZE]
+                    .try_into()
+                    .expect("critical: invalid stored transfer key"),
+            );
+            deserialize_transfer(&serialized_transfer).map(|transfer| (slot, transfer))
+        })
+        .collect()
+    }
+
+    /// Get at most `max_count` of the stored transfers spending or crediting the address of the filter
+    /// in its slot range, oldest first. The other criteria of the filter are not applied.
+    pub fn get_address_transfers(
+        &self,
+        filter: &TransferFilter,
+        max_count: usize,
+    ) -> Vec<(Slot, TransferInfo)> {
+        let db = self.db.read();
+        let prefix = address_prefix(&filter.address);
+        let start = match filter.start {
+            Some(start) => [&prefix[..], &start.to_bytes_key()[..]].concat(),
+            None => prefix.clone(),
+        };
+        let end = match filter.end {
+            Some(end) => Some([&prefix[..], &end.to_bytes_key()[..]].concat()),
+            None => match get_prefix_bounds(&prefix).1 {
+                Bound::Excluded(end) => Some(end),
+                _ => None,
+            },
+        };
+
+        db.range_iterator_cf(
+            TRANSFERS_CF,
+            &start,
+            end.as_deref(),
+            MassaDirection::Forward,
+        )
+        .take(max_count)
+        .filter_map(|(serialized_key, _)| {
+            let transfer_id = &serialized_key[serialized_key.len() - TRANSFER_ID_SIZE..];
+            let slot = Slot::from_bytes_key(
+                transfer_id[..SLOT_KEY_SIZE]
+                    .try_into()
+                    .expect("critical: invalid stored transfer key"),
+            );
+            let serialized_transfer = db
+                .get_cf(TRANSFERS_CF, [&[TRANSFER_IDENT][..], transfer_id].concat())
+                .expect(CRUD_ERROR)?;
+            deserialize_transfer(&serialized_transfer).map(|transfer| (slot, transfer))
+        })
+        .collect()
+    }
+}
+
+/// Serializes the (slot, index in slot) identifier of a transfer, ordered as the transfers
+fn transfer_id(slot: Slot, index: u64) -> Vec<u8> {
+    [&slot.to_bytes_key()[..], &index.to_be_bytes()[..]].concat()
+}
+
+/// Prefix of the index entries of the transfers of an address
+fn address_prefix(address: &Address) -> Vec<u8> {
+    [&[ADDRESS_IDENT][..], &address.to_prefixed_bytes()[..]].concat()
+}
+
+/// Keys of the index entries of a transfer, one for each distinct address involved
+fn index_keys(transfer: &TransferInfo, transfer_id: &[u8]) -> Vec<Vec<u8>> {
+    let mut addresses: Vec<Address> = transfer.from.into_iter().chain(transfer.to).collect();
+    addresses.dedup();
+    addresses
+        .iter()
+        .map(|address| [&address_prefix(address)[..], transfer_id].concat())
+        .collect()
+}
+
+/// Serializes a transfer as: flags of the present addresses, addresses, raw amount, origin
+fn serialize_transfer(transfer: &TransferInfo) -> Vec<u8> {
+    let flags = u8::from(transfer.from.is_some()) | (u8::from(transfer.to.is_some()) << 1);
+    let mut buffer = vec![flags];
+    let address_serializer = AddressSerializer::new();
+    for address in transfer.from.iter().chain(transfer.to.iter()) {
+        address_serializer
+            .serialize(address, &mut buffer)
+            .expect("address serialization never fails");
+    }
+    buffer.extend(transfer.amount.to_raw().to_be_bytes());
+    match &transfer.origin {
+        TransferOrigin::Operation(operation_id) => {
+            buffer.push(0);
+            OperationIdSerializer::new()
+                .serialize(operation_id, &mut buffer)
+                .expect("operation id serialization never fails");
+        }
+        TransferOrigin::AsyncMessage => buffer.push(1),
+        TransferOrigin::BlockReward => buffer.push(2),
+        TransferOrigin::DeferredCredit => buffer.push(3),
+        TransferOrigin::Other => buffer.push(4),
+    }
+    buffer
+}
+
+/// Deserializes a transfer serialized by `serialize_transfer`
+fn deserialize_transfer(buffer: &[u8]) -> Option<TransferInfo> {
+    let address_deserializer = AddressDeserializer::new();
+    let (&flags, mut rest) = buffer.split_first()?;
+    let mut read_address = |present: bool| -> Option<Option<Address>> {
+        if !present {
+            return Some(None);
+        }
+        let (remaining, address) = address_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .ok()?;
+        rest = remaining;
+        Some(Some(address))
+    };
+    let from = read_address(flags & 1 != 0)?;
+    let to = read_address(flags & 2 != 0)?;
+    let amount = Amount::from_raw(u64::from_be_bytes(rest.get(..8)?.try_into().ok()?));
+    let origin = match rest.get(8)? {
+        0 => {
+            let (_, operation_id) = OperationIdDeserializer::new()
+                .deserialize::<DeserializeError>(&rest[9..])
+                .ok()?;
+            TransferOrigin::Operation(operation_id)
+        }
+        1 => TransferOrigin::AsyncMessage,
+        2 => TransferOrigin::BlockReward,
+        3 => TransferOrigin::DeferredCredit,
+        _ => TransferOrigin::Other,
+    };
+    Some(TransferInfo {
+        from,
+        to,
+        amount,
+        origin,
+    })
+}
@@ -5,11 +5,12 @@
 //! for example to interact with the ledger.
 //! See the definition of Interface in the massa-sc-runtime crate for functional details.
 
+use crate::abi_profiler::{AbiCallTimer, SharedAbiProfiler};
 use crate::context::ExecutionContext;
 use anyhow::{anyhow, bail, Result};
 use massa_async_pool::{AsyncMessage, AsyncMessageTrigger};
-use massa_execution_exports::ExecutionConfig;
 use massa_execution_exports::ExecutionStackElement;
+use massa_execution_exports::{AbiTraceCall, ExecutionConfig};
 use massa_models::bytecode::Bytecode;
 use massa_models::config::MAX_DATASTORE_KEY_LENGTH;
 use massa_models::datastore::get_prefix_bounds;
@@ -56,6 +57,14 @@ macro_rules! context_guard {
     };
 }
 
+/// helper for timing a host ABI call until the end of the calling function, if the profiling is enabled
+macro_rules! abi_profile {
+    ($self:ident, $function:expr) => {
+        let _abi_call_timer = ($self.config.abi_profile_slots > 0)
+            .then(|| AbiCallTimer::new(&$self.abi_profiler, $function));
+    };
+}
+
 /// an implementation of the Interface trait (see massa-sc-runtime crate)
 #[derive(Clone)]
 pub struct InterfaceImpl {
@@ -63,6 +72,8 @@ pub struct InterfaceImpl {
     config: ExecutionConfig,
     /// thread-safe shared access to the execution context (see context.rs)
     context: Arc<Mutex<ExecutionContext>>,
+    /// profiling counters of the host ABI calls (see abi_profiler.rs)
+    abi_profiler: SharedAbiProfiler,
 }
 
 impl InterfaceImpl {
@@ -71,8 +82,17 @@ impl InterfaceImpl {
     /// # Arguments
     /// * `config`: execution configuration
     /// * `context`: thread-safe shared access to the current execution context (see context.rs)
-    pub fn new(config: ExecutionConfig, context: Arc<Mutex<ExecutionContext>>) -> InterfaceImpl {
-        InterfaceImpl { config, context }
+    /// * `abi_profiler`: profiler recording the host ABI calls, shared with the execution state
+    pub fn new(
+        config: ExecutionConfig,
+        context: Arc<Mutex<ExecutionContext>>,
+        abi_profiler: SharedAbiProfiler,
+    ) -> InterfaceImpl {
+        InterfaceImpl {
+            config,
+            context,
+            abi_profiler,
+        }
     }
 
     #[cfg(any(
@@ -85,8 +105,9 @@ impl InterfaceImpl {
         sender_addr: Address,
         operation_datastore: Option<Datastore>,
     ) -> InterfaceImpl {
+        use crate::abi_profiler::AbiProfiler;
         use massa_ledger_exports::{LedgerEntry, SetUpdateOrDelete};
-        use massa_models::config::MIP_STORE_STATS_BLOCK_CONSIDERED;
+        use massa_models::config::{MIP_STORE_STATS_BLOCK_CONSIDERED, VERSION};
         use massa_module_cache::{config::ModuleCacheConfig, controller::ModuleCache};
         use massa_versioning::versioning::{MipStatsConfig, MipStore};
         use parking_lot::RwLock;
@@ -101,6 +122,7 @@ impl InterfaceImpl {
             hd_cache_size: config.hd_cache_size,
             snip_amount: config.snip_amount,
             max_module_length: config.max_bytecode_size,
+            engine_version: VERSION.to_string(),
         })));
 
         // create an empty default store
@@ -133,7 +155,8 @@ impl InterfaceImpl {
             }),
         );
         let context = Arc::new(Mutex::new(execution_context));
-        InterfaceImpl::new(config, context)
+        let abi_profiler = Arc::new(Mutex::new(AbiProfiler::new(config.abi_profile_slots)));
+        InterfaceImpl::new(config, context, abi_profiler)
     }
 }
 
@@ -189,6 +212,7 @@ fn get_address_from_opt_or_context(
 impl Interface for InterfaceImpl {
     /// prints a message in the node logs at log level 3 (debug)
     fn print(&self, message: &str) -> Result<()> {
+        abi_profile!(self, "print");
         if cfg!(test) {
             println!("SC print: {}", message);
         } else {
@@ -209,6 +233,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The target bytecode or an error
     fn init_call(&self, address: &str, raw_coins: u64) -> Result<Vec<u8>> {
+        abi_profile!(self, "init_call");
         // get target address
         let to_address = Address::from_str(address)?;
 
@@ -234,6 +259,10 @@ impl Interface for InterfaceImpl {
 
         // transfer coins from caller to target address
         let coins = Amount::from_raw(raw_coins);
+        context.record_trace(AbiTraceCall::CallStart {
+            target: to_address,
+            coins,
+        });
         // note: rights are not checked here we checked that to_address is an SC address above
         // and we know that the sender is at the top of the call stack
         if let Err(err) = context.transfer_coins(Some(from_address), Some(to_address), coins, false)
@@ -262,11 +291,13 @@ impl Interface for InterfaceImpl {
     /// Called to finish the call process after a bytecode calls a function from another one.
     /// This function just pops away the top element of the call stack.
     fn finish_call(&self) -> Result<()> {
+        abi_profile!(self, "finish_call");
         let mut context = context_guard!(self);
 
         if context.stack.pop().is_none() {
             bail!("call stack out of bounds")
         }
+        context.record_trace(AbiTraceCall::CallEnd);
 
         Ok(())
     }
@@ -276,6 +307,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// A `massa-sc-runtime` compiled module
     fn get_module(&self, bytecode: &[u8], limit: u64) -> Result<RuntimeModule> {
+        abi_profile!(self, "get_module");
         let context = context_guard!(self);
         let module = context.module_cache.write().load_module(bytecode, limit)?;
         Ok(module)
@@ -289,6 +321,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_balance_wasmv1`
     fn get_balance(&self) -> Result<u64> {
+        abi_profile!(self, "get_balance");
         let context = context_guard!(self);
         let address = context.get_current_address()?;
         Ok(context.get_balance(&address).unwrap_or_default().to_raw())
@@ -305,6 +338,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_balance_wasmv1`
     fn get_balance_for(&self, address: &str) -> Result<u64> {
+        abi_profile!(self, "get_balance_for");
         let address = massa_models::address::Address::from_str(address)?;
         Ok(context_guard!(self)
             .get_balance(&address)
@@ -321,6 +355,7 @@ impl Interface for InterfaceImpl {
     /// The raw representation (no decimal factor) of the balance of the address,
     /// or zero if the address is not found in the ledger.
     fn get_balance_wasmv1(&self, address: Option<String>) -> Result<NativeAmount> {
+        abi_profile!(self, "get_balance_wasmv1");
         let context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -339,6 +374,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The string representation of the newly created address
     fn create_module(&self, bytecode: &[u8]) -> Result<String> {
+        abi_profile!(self, "create_module");
         match context_guard!(self).create_new_sc_address(Bytecode(bytecode.to_vec())) {
             Ok(addr) => Ok(addr.to_string()),
             Err(err) => bail!("couldn't create new SC address: {}", err),
@@ -352,6 +388,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_keys_wasmv1`
     fn get_keys(&self, prefix_opt: Option<&[u8]>) -> Result<BTreeSet<Vec<u8>>> {
+        abi_profile!(self, "get_keys");
         let context = context_guard!(self);
         let addr = context.get_current_address()?;
         match context.get_keys(&addr, prefix_opt.unwrap_or_default()) {
@@ -367,6 +404,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_keys_wasmv1`
     fn get_keys_for(&self, address: &str, prefix_opt: Option<&[u8]>) -> Result<BTreeSet<Vec<u8>>> {
+        abi_profile!(self, "get_keys_for");
         let addr = &Address::from_str(address)?;
         let context = context_guard!(self);
         match context.get_keys(addr, prefix_opt.unwrap_or_default()) {
@@ -384,6 +422,7 @@ impl Interface for InterfaceImpl {
         prefix: &[u8],
         address: Option<String>,
     ) -> Result<BTreeSet<Vec<u8>>> {
+        abi_profile!(self, "get_ds_keys_wasmv1");
         let context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -403,7 +442,8 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data(&self, key: &[u8]) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        abi_profile!(self, "raw_get_data");
+        let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
         match context.get_data_entry(&addr, key) {
             Some(value) => Ok(value),
@@ -422,8 +462,9 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data_for(&self, address: &str, key: &[u8]) -> Result<Vec<u8>> {
+        abi_profile!(self, "raw_get_data_for");
         let addr = &massa_models::address::Address::from_str(address)?;
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
         match context.get_data_entry(addr, key) {
             Some(value) => Ok(value),
             _ => bail!("data entry not found"),
@@ -439,7 +480,8 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The datastore value matching the provided key, if found, otherwise an error.
     fn get_ds_value_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        abi_profile!(self, "get_ds_value_wasmv1");
+        let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
         match context.get_data_entry(&address, key) {
@@ -459,6 +501,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_set_data_wasmv1`
     fn raw_set_data(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_set_data");
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
         context.set_data_entry(&addr, key.to_vec(), value.to_vec())?;
@@ -476,6 +519,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_set_data_wasmv1`
     fn raw_set_data_for(&self, address: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_set_data_for");
         let addr = massa_models::address::Address::from_str(address)?;
         let mut context = context_guard!(self);
         context.set_data_entry(&addr, key.to_vec(), value.to_vec())?;
@@ -483,6 +527,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn set_ds_value_wasmv1(&self, key: &[u8], value: &[u8], address: Option<String>) -> Result<()> {
+        abi_profile!(self, "set_ds_value_wasmv1");
         let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -499,6 +544,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_append_data_wasmv1`
     fn raw_append_data(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_append_data");
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
         context.append_data_entry(&addr, key.to_vec(), value.to_vec())?;
@@ -515,6 +561,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_append_data_wasmv1`
     fn raw_append_data_for(&self, address: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_append_data_for");
         let addr = massa_models::address::Address::from_str(address)?;
         context_guard!(self).append_data_entry(&addr, key.to_vec(), value.to_vec())?;
         Ok(())
@@ -533,6 +580,7 @@ impl Interface for InterfaceImpl {
         value: &[u8],
         address: Option<String>,
     ) -> Result<()> {
+        abi_profile!(self, "append_ds_value_wasmv1");
         let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -548,6 +596,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_delete_data_wasmv1`
     fn raw_delete_data(&self, key: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_delete_data");
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
         context.delete_data_entry(&addr, key)?;
@@ -563,6 +612,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_delete_data_wasmv1`
     fn raw_delete_data_for(&self, address: &str, key: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_delete_data_for");
         let addr = &massa_models::address::Address::from_str(address)?;
         context_guard!(self).delete_data_entry(addr, key)?;
         Ok(())
@@ -575,6 +625,7 @@ impl Interface for InterfaceImpl {
     /// * address: string representation of the address
     /// * key: string key of the datastore entry to delete
     fn delete_ds_entry_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<()> {
+        abi_profile!(self, "delete_ds_entry_wasmv1");
         let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -592,6 +643,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `has_data_wasmv1`
     fn has_data(&self, key: &[u8]) -> Result<bool> {
+        abi_profile!(self, "has_data");
         let context = context_guard!(self);
         let addr = context.get_current_address()?;
         Ok(context.has_data_entry(&addr, key))
@@ -608,6 +660,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `has_data_wasmv1`
     fn has_data_for(&self, address: &str, key: &[u8]) -> Result<bool> {
+        abi_profile!(self, "has_data_for");
         let addr = massa_models::address::Address::from_str(address)?;
         let context = context_guard!(self);
         Ok(context.has_data_entry(&addr, key))
@@ -622,6 +675,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// true if the address exists and has the entry matching the provided key in its datastore, otherwise false
     fn ds_entry_exists_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<bool> {
+        abi_profile!(self, "ds_entry_exists_wasmv1");
         let context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -633,6 +687,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// true if the caller has write access
     fn caller_has_write_access(&self) -> Result<bool> {
+        abi_profile!(self, "caller_has_write_access");
         let context = context_guard!(self);
         let mut call_stack_iter = context.stack.iter().rev();
         let caller_owned_addresses = if let Some(last) = call_stack_iter.next() {
@@ -652,6 +707,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_bytecode_wasmv1`
     fn raw_get_bytecode(&self) -> Result<Vec<u8>> {
+        abi_profile!(self, "raw_get_bytecode");
         let context = context_guard!(self);
         let address = context.get_current_address()?;
         match context.get_bytecode(&address) {
@@ -664,6 +720,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_bytecode_wasmv1`
     fn raw_get_bytecode_for(&self, address: &str) -> Result<Vec<u8>> {
+        abi_profile!(self, "raw_get_bytecode_for");
         let context = context_guard!(self);
         let address = Address::from_str(address)?;
         match context.get_bytecode(&address) {
@@ -674,6 +731,7 @@ impl Interface for InterfaceImpl {
 
     /// Returns bytecode of the target address, or the current address if not provided
     fn get_bytecode_wasmv1(&self, address: Option<String>) -> Result<Vec<u8>> {
+        abi_profile!(self, "get_bytecode_wasmv1");
         let context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -691,6 +749,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_op_keys_wasmv1`
     fn get_op_keys(&self, prefix_opt: Option<&[u8]>) -> Result<Vec<Vec<u8>>> {
+        abi_profile!(self, "get_op_keys");
         let prefix: &[u8] = prefix_opt.unwrap_or_default();
 
         // compute prefix range
@@ -716,6 +775,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// A list of keys (keys are byte arrays) that match the given prefix
     fn get_op_keys_wasmv1(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+        abi_profile!(self, "get_op_keys_wasmv1");
         let prefix_range = get_prefix_bounds(prefix);
         let range_ref = (prefix_range.0.as_ref(), prefix_range.1.as_ref());
 
@@ -741,6 +801,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// true if the entry is matching the provided key in its operation datastore, otherwise false
     fn op_entry_exists(&self, key: &[u8]) -> Result<bool> {
+        abi_profile!(self, "op_entry_exists");
         let context = context_guard!(self);
         let stack = context.stack.last().ok_or_else(|| anyhow!("No stack"))?;
         let datastore = stack
@@ -760,6 +821,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The operation datastore value matching the provided key, if found, otherwise an error.
     fn get_op_data(&self, key: &[u8]) -> Result<Vec<u8>> {
+        abi_profile!(self, "get_op_data");
         let context = context_guard!(self);
         let stack = context.stack.last().ok_or_else(|| anyhow!("No stack"))?;
         let datastore = stack
@@ -781,6 +843,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The hash in bytes format
     fn hash(&self, data: &[u8]) -> Result<[u8; 32]> {
+        abi_profile!(self, "hash");
         Ok(massa_hash::Hash::compute_from(data).into_bytes())
     }
 
@@ -792,12 +855,14 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The string representation of the resulting address
     fn address_from_public_key(&self, public_key: &str) -> Result<String> {
+        abi_profile!(self, "address_from_public_key");
         let public_key = massa_signature::PublicKey::from_str(public_key)?;
         let addr = massa_models::address::Address::from_public_key(&public_key);
         Ok(addr.to_string())
     }
 
     fn validate_address(&self, address: &str) -> Result<bool> {
+        abi_profile!(self, "validate_address");
         Ok(massa_models::address::Address::from_str(address).is_ok())
     }
 
@@ -811,6 +876,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// true if the signature verification succeeded, false otherwise
     fn signature_verify(&self, data: &[u8], signature: &str, public_key: &str) -> Result<bool> {
+        abi_profile!(self, "signature_verify");
         let signature = match massa_signature::Signature::from_bs58_check(signature) {
             Ok(sig) => sig,
             Err(_) => return Ok(false),
@@ -837,6 +903,7 @@ impl Interface for InterfaceImpl {
         message_: &[u8],
         public_key_: &[u8],
     ) -> Result<bool> {
+        abi_profile!(self, "evm_signature_verify");
         // check the signature length
         if signature_.len() != 65 {
             return Err(anyhow!("invalid signature length in evm_signature_verify"));
@@ -869,12 +936,14 @@ impl Interface for InterfaceImpl {
 
     /// Keccak256 hash function
     fn hash_keccak256(&self, bytes: &[u8]) -> Result<[u8; 32]> {
+        abi_profile!(self, "hash_keccak256");
         Ok(sha3::Keccak256::digest(bytes).into())
     }
 
     /// Get an EVM address from a raw secp256k1 public key (64 bytes).
     /// Address is the last 20 bytes of the hash of the public key.
     fn evm_get_address_from_pubkey(&self, public_key_: &[u8]) -> Result<Vec<u8>> {
+        abi_profile!(self, "evm_get_address_from_pubkey");
         // parse the public key
         let public_key = libsecp256k1::PublicKey::parse_slice(
             public_key_,
@@ -893,6 +962,7 @@ impl Interface for InterfaceImpl {
 
     /// Get a raw secp256k1 public key from an EVM signature and the signed hash.
     fn evm_get_pubkey_from_signature(&self, hash_: &[u8], signature_: &[u8]) -> Result<Vec<u8>> {
+        abi_profile!(self, "evm_get_pubkey_from_signature");
         // check the signature length
         if signature_.len() != 65 {
             return Err(anyhow!(
@@ -918,6 +988,7 @@ impl Interface for InterfaceImpl {
 
     // Return true if the address is a User address, false if it is an SC address.
     fn is_address_eoa(&self, address_: &str) -> Result<bool> {
+        abi_profile!(self, "is_address_eoa");
         let address = Address::from_str(address_)?;
         Ok(matches!(address, Address::User(..)))
     }
@@ -930,6 +1001,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `transfer_coins_wasmv1`
     fn transfer_coins(&self, to_address: &str, raw_amount: u64) -> Result<()> {
+        abi_profile!(self, "transfer_coins");
         let to_address = Address::from_str(to_address)?;
         let amount = Amount::from_raw(raw_amount);
         let mut context = context_guard!(self);
@@ -952,6 +1024,7 @@ impl Interface for InterfaceImpl {
         to_address: &str,
         raw_amount: u64,
     ) -> Result<()> {
+        abi_profile!(self, "transfer_coins_for");
         let from_address = Address::from_str(from_address)?;
         let to_address = Address::from_str(to_address)?;
         let amount = Amount::from_raw(raw_amount);
@@ -972,6 +1045,7 @@ impl Interface for InterfaceImpl {
         raw_amount: NativeAmount,
         from_address: Option<String>,
     ) -> Result<()> {
+        abi_profile!(self, "transfer_coins_wasmv1");
         let to_address = Address::from_str(&to_address)?;
         let amount = amount_from_native_amount(&raw_amount)?;
 
@@ -993,6 +1067,7 @@ impl Interface for InterfaceImpl {
     /// A vector with the string representation of each owned address.
     /// Note that the ordering of this vector is deterministic and conserved.
     fn get_owned_addresses(&self) -> Result<Vec<String>> {
+        abi_profile!(self, "get_owned_addresses");
         Ok(context_guard!(self)
             .get_current_owned_addresses()?
             .into_iter()
@@ -1005,6 +1080,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// A vector with the string representation of each call stack address.
     fn get_call_stack(&self) -> Result<Vec<String>> {
+        abi_profile!(self, "get_call_stack");
         Ok(context_guard!(self)
             .get_call_stack()
             .into_iter()
@@ -1020,6 +1096,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_call_coins_wasmv1`
     fn get_call_coins(&self) -> Result<u64> {
+        abi_profile!(self, "get_call_coins");
         Ok(context_guard!(self).get_current_call_coins()?.to_raw())
     }
 
@@ -1029,6 +1106,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The amount of coins
     fn get_call_coins_wasmv1(&self) -> Result<NativeAmount> {
+        abi_profile!(self, "get_call_coins_wasmv1");
         let amount = context_guard!(self).get_current_call_coins()?;
         Ok(amount_to_native_amount(&amount))
     }
@@ -1040,6 +1118,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_current_slot`
     fn generate_event(&self, data: String) -> Result<()> {
+        abi_profile!(self, "generate_event");
         if data.len() > self.config.max_event_size {
             bail!("Event data size is too large");
         };
@@ -1055,6 +1134,7 @@ impl Interface for InterfaceImpl {
     /// # Arguments:
     /// data: the bytes_array data that is the payload of the event
     fn generate_event_wasmv1(&self, data: Vec<u8>) -> Result<()> {
+        abi_profile!(self, "generate_event_wasmv1");
         if data.len() > self.config.max_event_size {
             bail!("Event data size is too large");
         };
@@ -1070,6 +1150,7 @@ impl Interface for InterfaceImpl {
     /// Returns the current time (millisecond UNIX timestamp)
     /// Note that in order to ensure determinism, this is actually the time of the context slot.
     fn get_time(&self) -> Result<u64> {
+        abi_profile!(self, "get_time");
         let slot = context_guard!(self).slot;
         let ts = get_block_slot_timestamp(
             self.config.thread_count,
@@ -1088,6 +1169,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `unsafe_random_wasmv1`
     fn unsafe_random(&self) -> Result<i64> {
+        abi_profile!(self, "unsafe_random");
         let distr = rand::distributions::Uniform::new_inclusive(i64::MIN, i64::MAX);
         Ok(context_guard!(self).unsafe_rng.sample(distr))
     }
@@ -1100,6 +1182,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `unsafe_random_wasmv1`
     fn unsafe_random_f64(&self) -> Result<f64> {
+        abi_profile!(self, "unsafe_random_f64");
         let distr = rand::distributions::Uniform::new(0f64, 1f64);
         Ok(context_guard!(self).unsafe_rng.sample(distr))
     }
@@ -1110,6 +1193,7 @@ impl Interface for InterfaceImpl {
     /// This random number generator is unsafe:
     /// it can be both predicted and manipulated before the execution
     fn unsafe_random_wasmv1(&self, num_bytes: u64) -> Result<Vec<u8>> {
+        abi_profile!(self, "unsafe_random_wasmv1");
         let mut arr = vec![0u8; num_bytes as usize];
         context_guard!(self).unsafe_rng.try_fill_bytes(&mut arr)?;
         Ok(arr)
@@ -1138,6 +1222,7 @@ impl Interface for InterfaceImpl {
         data: &[u8],
         filter: Option<(&str, Option<&[u8]>)>,
     ) -> Result<()> {
+        abi_profile!(self, "send_message");
         if validity_start.1 >= self.config.thread_count {
             bail!("validity start thread exceeds the configuration thread count")
         }
@@ -1201,6 +1286,7 @@ impl Interface for InterfaceImpl {
 
     // Returns the operation id that originated the current execution if there is one
     fn get_origin_operation_id(&self) -> Result<Option<String>> {
+        abi_profile!(self, "get_origin_operation_id");
         let operation_id = context_guard!(self)
             .origin_operation_id
             .map(|op_id| op_id.to_string());
@@ -1211,6 +1297,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_current_slot`
     fn get_current_period(&self) -> Result<u64> {
+        abi_profile!(self, "get_current_period");
         let slot = context_guard!(self).slot;
         Ok(slot.period)
     }
@@ -1219,12 +1306,14 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `get_current_slot`
     fn get_current_thread(&self) -> Result<u8> {
+        abi_profile!(self, "get_current_thread");
         let slot = context_guard!(self).slot;
         Ok(slot.thread)
     }
 
     /// Returns the current execution slot
     fn get_current_slot(&self) -> Result<massa_proto_rs::massa::model::v1::Slot> {
+        abi_profile!(self, "get_current_slot");
         let slot_models = context_guard!(self).slot;
         Ok(slot_models.into())
     }
@@ -1233,6 +1322,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_set_bytecode_wasmv1`
     fn raw_set_bytecode(&self, bytecode: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_set_bytecode");
         let mut execution_context = context_guard!(self);
         let address = execution_context.get_current_address()?;
         match execution_context.set_bytecode(&address, Bytecode(bytecode.to_vec())) {
@@ -1246,6 +1336,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_set_bytecode_wasmv1`
     fn raw_set_bytecode_for(&self, address: &str, bytecode: &[u8]) -> Result<()> {
+        abi_profile!(self, "raw_set_bytecode_for");
         let address: Address = massa_models::address::Address::from_str(address)?;
         let mut execution_context = context_guard!(self);
         match execution_context.set_bytecode(&address, Bytecode(bytecode.to_vec())) {
@@ -1257,6 +1348,7 @@ impl Interface for InterfaceImpl {
     /// Sets the bytecode of an arbitrary address, or the current address if not provided.
     /// Fails if the address does not exist, is an user address, or if the context doesn't have write access rights on it.
     fn set_bytecode_wasmv1(&self, bytecode: &[u8], address: Option<String>) -> Result<()> {
+        abi_profile!(self, "set_bytecode_wasmv1");
         let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
@@ -1274,6 +1366,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The byte array of the resulting hash
     fn hash_sha256(&self, bytes: &[u8]) -> Result<[u8; 32]> {
+        abi_profile!(self, "hash_sha256");
         let mut hasher = Sha256::new();
         hasher.update(bytes);
         let hash = hasher.finalize().into();
@@ -1288,11 +1381,13 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The byte array of the resulting hash
     fn hash_blake3(&self, bytes: &[u8]) -> Result<[u8; 32]> {
+        abi_profile!(self, "hash_blake3");
         Ok(blake3::hash(bytes).into())
     }
 
     #[allow(unused_variables)]
     fn init_call_wasmv1(&self, address: &str, raw_coins: NativeAmount) -> Result<Vec<u8>> {
+        abi_profile!(self, "init_call_wasmv1");
         // get target address
         let to_address = Address::from_str(address)?;
 
@@ -1318,6 +1413,10 @@ impl Interface for InterfaceImpl {
 
         // transfer coins from caller to target address
         let coins = amount_from_native_amount(&raw_coins)?;
+        context.record_trace(AbiTraceCall::CallStart {
+            target: to_address,
+            coins,
+        });
         // note: rights are not checked here we checked that to_address is an SC address above
         // and we know that the sender is at the top of the call stack
         if let Err(err) = context.transfer_coins(Some(from_address), Some(to_address), coins, false)
@@ -1345,12 +1444,14 @@ impl Interface for InterfaceImpl {
 
     /// Returns a NativeAmount from a string
     fn native_amount_from_str_wasmv1(&self, amount: &str) -> Result<NativeAmount> {
+        abi_profile!(self, "native_amount_from_str_wasmv1");
         let amount = Amount::from_str(amount).map_err(|err| anyhow!(format!("{}", err)))?;
         Ok(amount_to_native_amount(&amount))
     }
 
     /// Returns a string from a NativeAmount
     fn native_amount_to_string_wasmv1(&self, amount: &NativeAmount) -> Result<String> {
+        abi_profile!(self, "native_amount_to_string_wasmv1");
         let amount = amount_from_native_amount(amount)
             .map_err(|err| anyhow!(format!("Couldn't convert native amount to Amount: {}", err)))?;
         Ok(amount.to_string())
@@ -1358,6 +1459,7 @@ impl Interface for InterfaceImpl {
 
     /// Checks if the given native amount is valid
     fn check_native_amount_wasmv1(&self, amount: &NativeAmount) -> Result<bool> {
+        abi_profile!(self, "check_native_amount_wasmv1");
         Ok(amount_from_native_amount(amount).is_ok())
     }
 
@@ -1367,6 +1469,7 @@ impl Interface for InterfaceImpl {
         amount1: &NativeAmount,
         amount2: &NativeAmount,
     ) -> Result<NativeAmount> {
+        abi_profile!(self, "add_native_amount_wasmv1");
         let amount1 = amount_from_native_amount(amount1)?;
         let amount2 = amount_from_native_amount(amount2)?;
         let sum = amount1.saturating_add(amount2);
@@ -1379,6 +1482,7 @@ impl Interface for InterfaceImpl {
         amount1: &NativeAmount,
         amount2: &NativeAmount,
     ) -> Result<NativeAmount> {
+        abi_profile!(self, "sub_native_amount_wasmv1");
         let amount1 = amount_from_native_amount(amount1)?;
         let amount2 = amount_from_native_amount(amount2)?;
         let sub = amount1.saturating_sub(amount2);
@@ -1391,6 +1495,7 @@ impl Interface for InterfaceImpl {
         amount: &NativeAmount,
         factor: u64,
     ) -> Result<NativeAmount> {
+        abi_profile!(self, "scalar_mul_native_amount_wasmv1");
         let amount = amount_from_native_amount(amount)?;
         let mul = amount.saturating_mul_u64(factor);
         Ok(amount_to_native_amount(&mul))
@@ -1402,6 +1507,7 @@ impl Interface for InterfaceImpl {
         dividend: &NativeAmount,
         divisor: u64,
     ) -> Result<(NativeAmount, NativeAmount)> {
+        abi_profile!(self, "scalar_div_rem_native_amount_wasmv1");
         let dividend = amount_from_native_amount(dividend)?;
 
         let quotient = dividend
@@ -1424,6 +1530,7 @@ impl Interface for InterfaceImpl {
         dividend: &NativeAmount,
         divisor: &NativeAmount,
     ) -> Result<(u64, NativeAmount)> {
+        abi_profile!(self, "div_rem_native_amount_wasmv1");
         let dividend = amount_from_native_amount(dividend)?;
         let divisor = amount_from_native_amount(divisor)?;
 
@@ -1440,6 +1547,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn base58_check_to_bytes_wasmv1(&self, s: &str) -> Result<Vec<u8>> {
+        abi_profile!(self, "base58_check_to_bytes_wasmv1");
         bs58::decode(s)
             .with_check(None)
             .into_vec()
@@ -1447,22 +1555,27 @@ impl Interface for InterfaceImpl {
     }
 
     fn bytes_to_base58_check_wasmv1(&self, data: &[u8]) -> String {
+        abi_profile!(self, "bytes_to_base58_check_wasmv1");
         bs58::encode(data).with_check().into_string()
     }
 
     fn check_address_wasmv1(&self, to_check: &str) -> Result<bool> {
+        abi_profile!(self, "check_address_wasmv1");
         Ok(Address::from_str(to_check).is_ok())
     }
 
     fn check_pubkey_wasmv1(&self, to_check: &str) -> Result<bool> {
+        abi_profile!(self, "check_pubkey_wasmv1");
         Ok(PublicKey::from_str(to_check).is_ok())
     }
 
     fn check_signature_wasmv1(&self, to_check: &str) -> Result<bool> {
+        abi_profile!(self, "check_signature_wasmv1");
         Ok(Signature::from_str(to_check).is_ok())
     }
 
     fn get_address_category_wasmv1(&self, to_check: &str) -> Result<AddressCategory> {
+        abi_profile!(self, "get_address_category_wasmv1");
         let addr = Address::from_str(to_check)?;
         match addr {
             Address::User(_) => Ok(AddressCategory::ScAddress),
@@ -1473,6 +1586,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn get_address_version_wasmv1(&self, address: &str) -> Result<u64> {
+        abi_profile!(self, "get_address_version_wasmv1");
         let address = Address::from_str(address)?;
         match address {
             Address::User(UserAddress::UserAddressV0(_)) => Ok(0),
@@ -1485,6 +1599,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn get_pubkey_version_wasmv1(&self, pubkey: &str) -> Result<u64> {
+        abi_profile!(self, "get_pubkey_version_wasmv1");
         let pubkey = PublicKey::from_str(pubkey)?;
         match pubkey {
             PublicKey::PublicKeyV0(_) => Ok(0),
@@ -1494,6 +1609,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn get_signature_version_wasmv1(&self, signature: &str) -> Result<u64> {
+        abi_profile!(self, "get_signature_version_wasmv1");
         let signature = Signature::from_str(signature)?;
         match signature {
             Signature::SignatureV0(_) => Ok(0),
@@ -1507,6 +1623,7 @@ impl Interface for InterfaceImpl {
         time1: &NativeTime,
         time2: &NativeTime,
     ) -> Result<NativeTime> {
+        abi_profile!(self, "checked_add_native_time_wasmv1");
         let time1 = massa_time_from_native_time(time1)?;
         let time2 = massa_time_from_native_time(time2)?;
         let sum = time1.checked_add(time2)?;
@@ -1518,6 +1635,7 @@ impl Interface for InterfaceImpl {
         time1: &NativeTime,
         time2: &NativeTime,
     ) -> Result<NativeTime> {
+        abi_profile!(self, "checked_sub_native_time_wasmv1");
         let time1 = massa_time_from_native_time(time1)?;
         let time2 = massa_time_from_native_time(time2)?;
         let sub = time1.checked_sub(time2)?;
@@ -1525,6 +1643,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn checked_mul_native_time_wasmv1(&self, time: &NativeTime, factor: u64) -> Result<NativeTime> {
+        abi_profile!(self, "checked_mul_native_time_wasmv1");
         let time1 = massa_time_from_native_time(time)?;
         let mul = time1.checked_mul(factor)?;
         Ok(massa_time_to_native_time(&mul))
@@ -1535,6 +1654,7 @@ impl Interface for InterfaceImpl {
         dividend: &NativeTime,
         divisor: u64,
     ) -> Result<(NativeTime, NativeTime)> {
+        abi_profile!(self, "checked_scalar_div_native_time_wasmv1");
         let dividend = massa_time_from_native_time(dividend)?;
 
         let quotient = dividend
@@ -1555,6 +1675,7 @@ impl Interface for InterfaceImpl {
         dividend: &NativeTime,
         divisor: &NativeTime,
     ) -> Result<(u64, NativeTime)> {
+        abi_profile!(self, "checked_div_native_time_wasmv1");
         let dividend = massa_time_from_native_time(dividend)?;
         let divisor = massa_time_from_native_time(divisor)?;
 
@@ -1571,6 +1692,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn compare_address_wasmv1(&self, left: &str, right: &str) -> Result<ComparisonResult> {
+        abi_profile!(self, "compare_address_wasmv1");
         let left = Address::from_str(left)?;
         let right = Address::from_str(right)?;
 
@@ -1588,6 +1710,7 @@ impl Interface for InterfaceImpl {
         left: &NativeAmount,
         right: &NativeAmount,
     ) -> Result<ComparisonResult> {
+        abi_profile!(self, "compare_native_amount_wasmv1");
         let left = amount_from_native_amount(left)?;
         let right = amount_from_native_amount(right)?;
 
@@ -1605,6 +1728,7 @@ impl Interface for InterfaceImpl {
         left: &NativeTime,
         right: &NativeTime,
     ) -> Result<ComparisonResult> {
+        abi_profile!(self, "compare_native_time_wasmv1");
         let left = massa_time_from_native_time(left)?;
         let right = massa_time_from_native_time(right)?;
 
@@ -1618,6 +1742,7 @@ impl Interface for InterfaceImpl {
     }
 
     fn compare_pub_key_wasmv1(&self, left: &str, right: &str) -> Result<ComparisonResult> {
+        abi_profile!(self, "compare_pub_key_wasmv1");
         let left = PublicKey::from_str(left)?;
         let right = PublicKey::from_str(right)?;
 
@@ -62,6 +62,18 @@
 //! It also serves as an access point to the current execution state and speculative ledger
 //! as defined in `speculative_ledger.rs`.
 //!
+//! ## `abi_profiler.rs`
+//! Counts the calls and time spent in each host ABI function over the last executed slots.
+//!
+//! ## `bytecode_validation.rs`
+//! Statically checks a bytecode against the limits of the execution engine before its compilation.
+//!
+//! ## `final_event_store.rs`
+//! Stores the final execution events on disk, indexed by slot, emitter address and operation id.
+//!
+//! ## `final_transfer_store.rs`
+//! Indexes the final native coin transfers on disk by address and slot.
+//!
 //! ## `speculative_ledger.rs`
 //! A speculative (non-final) ledger that supports canceling already-executed operations
 //! in the case of some blockclique changes.
@@ -79,10 +91,15 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+mod abi_profiler;
 mod active_history;
+mod bytecode_validation;
 mod context;
 mod controller;
+mod disk_usage_alert;
 mod execution;
+mod final_event_store;
+mod final_transfer_store;
 mod interface_impl;
 mod request_queue;
 mod slot_sequencer;
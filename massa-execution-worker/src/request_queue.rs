@@ -3,7 +3,7 @@
 //! This file defines a generic finite-size execution request queue with an MPSC-based result sender.
 
 use massa_channel::sender::MassaSender;
-use massa_execution_exports::ExecutionError;
+use massa_execution_exports::{ExecutionError, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest};
 use std::collections::VecDeque;
 
 /// Represents an execution request T coupled with an MPSC sender for a result of type R
@@ -58,6 +58,13 @@ pub(crate) struct RequestQueue<T, R> {
     queue: VecDeque<RequestWithResponseSender<T, R>>,
 }
 
+/// Queue of batches of read-only execution requests,
+/// each batch being answered with the outputs of its requests in the same order
+pub(crate) type ReadOnlyRequestQueue = RequestQueue<
+    Vec<ReadOnlyExecutionRequest>,
+    Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>,
+>;
+
 impl<T, R> RequestQueue<T, R> {
     /// Create a new request queue
     ///
@@ -6,18 +6,20 @@
 //! but keeps track of the changes that were applied to it since its creation.
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
-use massa_execution_exports::ExecutionError;
 use massa_execution_exports::StorageCostsConstants;
+use massa_execution_exports::{ExecutionError, ReadOnlyStateOverride};
 use massa_final_state::FinalState;
-use massa_ledger_exports::{Applicable, LedgerChanges, SetOrDelete, SetUpdateOrDelete};
+use massa_ledger_exports::{
+    Applicable, LedgerChanges, LedgerController, LedgerError, SetOrDelete, SetUpdateOrDelete,
+};
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::get_prefix_bounds;
-use massa_models::{address::Address, amount::Amount};
+use massa_models::{address::Address, amount::Amount, slot::Slot};
 use parking_lot::RwLock;
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use std::sync::Arc;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// The `SpeculativeLedger` contains an thread-safe shared reference to the final ledger (read-only),
 /// a list of existing changes that happened o the ledger since its finality,
@@ -58,6 +60,9 @@ pub(crate) struct SpeculativeLedger {
 
     /// storage cost constants
     storage_costs_constants: StorageCostsConstants,
+
+    /// past final slot whose archived ledger replaces the final ledger and the active history, if any
+    historical_slot: Option<Slot>,
 }
 
 impl SpeculativeLedger {
@@ -82,9 +87,29 @@ impl SpeculativeLedger {
             max_datastore_value_size,
             max_bytecode_size,
             storage_costs_constants,
+            historical_slot: None,
         }
     }
 
+    /// Makes the ledger start from its state at the end of a past final slot, read from the archive.
+    /// The slot must be archived.
+    pub fn set_historical_slot(&mut self, slot: Slot) {
+        self.historical_slot = Some(slot);
+    }
+
+    /// Reads the archived final ledger.
+    /// The historical slot is checked to be archived before the execution starts,
+    /// so a failing read is only logged and seen as missing data.
+    fn read_historical<T: Default>(
+        &self,
+        read: impl FnOnce(&dyn LedgerController) -> Result<T, LedgerError>,
+    ) -> T {
+        read(self.final_state.read().ledger.as_ref()).unwrap_or_else(|err| {
+            warn!("could not read the archived ledger: {}", err);
+            T::default()
+        })
+    }
+
     /// Returns the changes caused to the `SpeculativeLedger` since its creation,
     /// and resets their local value to nothing.
     pub fn take(&mut self) -> LedgerChanges {
@@ -111,6 +136,9 @@ impl SpeculativeLedger {
     pub fn get_balance(&self, addr: &Address) -> Option<Amount> {
         // try to read from added changes > history > final_state
         self.added_changes.get_balance_or_else(addr, || {
+            if let Some(slot) = self.historical_slot {
+                return self.read_historical(|ledger| ledger.get_balance_at_slot(addr, slot));
+            }
             match self.active_history.read().fetch_balance(addr) {
                 HistorySearchResult::Present(par_balance) => Some(par_balance),
                 HistorySearchResult::NoInfo => self.final_state.read().ledger.get_balance(addr),
@@ -129,6 +157,9 @@ impl SpeculativeLedger {
     pub fn get_bytecode(&self, addr: &Address) -> Option<Bytecode> {
         // try to read from added changes > history > final_state
         self.added_changes.get_bytecode_or_else(addr, || {
+            if let Some(slot) = self.historical_slot {
+                return self.read_historical(|ledger| ledger.get_bytecode_at_slot(addr, slot));
+            }
             match self.active_history.read().fetch_bytecode(addr) {
                 HistorySearchResult::Present(bytecode) => Some(bytecode),
                 HistorySearchResult::NoInfo => self.final_state.read().ledger.get_bytecode(addr),
@@ -216,6 +247,9 @@ impl SpeculativeLedger {
     pub fn entry_exists(&self, addr: &Address) -> bool {
         // try to read from added changes > history > final_state
         self.added_changes.entry_exists_or_else(addr, || {
+            if let Some(slot) = self.historical_slot {
+                return self.read_historical(|ledger| ledger.entry_exists_at_slot(addr, slot));
+            }
             match self.active_history.read().fetch_balance(addr) {
                 HistorySearchResult::Present(_balance) => true,
                 HistorySearchResult::NoInfo => self.final_state.read().ledger.entry_exists(addr),
@@ -365,17 +399,24 @@ impl SpeculativeLedger {
         let range_ref = (prefix_range.0.as_ref(), prefix_range.1.as_ref());
 
         // init keys with final state
-        let mut candidate_keys: Option<BTreeSet<Vec<u8>>> = self
-            .final_state
-            .read()
-            .ledger
-            .get_datastore_keys(addr, prefix);
+        let mut candidate_keys: Option<BTreeSet<Vec<u8>>> = match self.historical_slot {
+            Some(slot) => {
+                self.read_historical(|ledger| ledger.get_datastore_keys_at_slot(addr, prefix, slot))
+            }
+            None => self
+                .final_state
+                .read()
+                .ledger
+                .get_datastore_keys(addr, prefix),
+        };
 
         // here, traverse the history from oldest to newest with added_changes at the end, applying additions and deletions
+        // (the active history is ignored when reading from a historical slot, being more recent)
         let active_history = self.active_history.read();
         let changes_iterator = active_history
             .0
             .iter()
+            .filter(|_| self.historical_slot.is_none())
             .map(|item| &item.state_changes.ledger_changes)
             .chain(std::iter::once(&self.added_changes));
         for ledger_changes in changes_iterator {
@@ -428,6 +469,10 @@ impl SpeculativeLedger {
     pub fn get_data_entry(&self, addr: &Address, key: &[u8]) -> Option<Vec<u8>> {
         // try to read from added changes > history > final_state
         self.added_changes.get_data_entry_or_else(addr, key, || {
+            if let Some(slot) = self.historical_slot {
+                return self
+                    .read_historical(|ledger| ledger.get_data_entry_at_slot(addr, key, slot));
+            }
             match self
                 .active_history
                 .read()
@@ -453,6 +498,11 @@ impl SpeculativeLedger {
     pub fn has_data_entry(&self, addr: &Address, key: &[u8]) -> bool {
         // try to read from added changes > history > final_state
         self.added_changes.has_data_entry_or_else(addr, key, || {
+            if let Some(slot) = self.historical_slot {
+                return self
+                    .read_historical(|ledger| ledger.get_data_entry_at_slot(addr, key, slot))
+                    .is_some();
+            }
             match self
                 .active_history
                 .read()
@@ -655,4 +705,66 @@ impl SpeculativeLedger {
 
         Ok(())
     }
+
+    /// Applies the temporary state of an address for a read-only execution.
+    /// No storage cost is charged and the address is created if it does not exist.
+    ///
+    /// # Arguments
+    /// * `addr`: target address
+    /// * `state_override`: balance, bytecode and datastore entries to apply
+    pub fn apply_state_override(
+        &mut self,
+        addr: &Address,
+        state_override: ReadOnlyStateOverride,
+    ) -> Result<(), ExecutionError> {
+        // check the override before touching the ledger
+        if let Some(bytecode) = &state_override.bytecode {
+            if bytecode.0.len() > self.max_bytecode_size as usize {
+                return Err(ExecutionError::RuntimeError(format!(
+                    "overridden bytecode of address {} is {} bytes long, but it must be in [0..={}]",
+                    addr,
+                    bytecode.0.len(),
+                    self.max_bytecode_size
+                )));
+            }
+        }
+        for (key, value) in &state_override.datastore {
+            if key.is_empty() || key.len() > self.max_datastore_key_length as usize {
+                return Err(ExecutionError::RuntimeError(format!(
+                    "overridden datastore key length of address {} is {}, but it must be in [0..={}]",
+                    addr,
+                    key.len(),
+                    self.max_datastore_key_length
+                )));
+            }
+            if let Some(value) = value {
+                if value.len() > self.max_datastore_value_size as usize {
+                    return Err(ExecutionError::RuntimeError(format!(
+                        "overridden datastore value length of address {} is {}, but it must be in [0..={}]",
+                        addr,
+                        value.len(),
+                        self.max_datastore_value_size
+                    )));
+                }
+            }
+        }
+
+        if !self.entry_exists(addr) {
+            self.added_changes.create_address(addr);
+        }
+        if let Some(balance) = state_override.balance {
+            self.added_changes.set_balance(*addr, balance);
+        }
+        if let Some(bytecode) = state_override.bytecode {
+            self.added_changes.set_bytecode(*addr, bytecode);
+        }
+        for (key, value) in state_override.datastore {
+            match value {
+                Some(value) => self.added_changes.set_data_entry(*addr, key, value),
+                None => self.added_changes.delete_data_entry(*addr, key),
+            }
+        }
+
+        Ok(())
+    }
 }
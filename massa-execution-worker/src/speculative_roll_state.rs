@@ -5,12 +5,16 @@ use massa_execution_exports::ExecutionError;
 use massa_final_state::FinalState;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::{
-    address::Address, amount::Amount, block_id::BlockId, prehash::PreHashMap, slot::Slot,
+    address::Address,
+    amount::Amount,
+    block_id::BlockId,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
 };
 use massa_pos_exports::{DeferredCredits, PoSChanges, ProductionStats};
 use num::rational::Ratio;
 use parking_lot::RwLock;
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
@@ -149,6 +153,109 @@ impl SpeculativeRollState {
         Ok(())
     }
 
+    /// Try to transfer `roll_count` rolls from the sender address to the recipient address.
+    ///
+    /// # Arguments
+    /// * `sender_addr`: address giving the rolls
+    /// * `recipient_addr`: address receiving the rolls
+    /// * `slot`: slot at which the transfer is executed
+    /// * `roll_count`: number of rolls to transfer
+    ///
+    /// Rolls that may still be slashed for an offense are locked: the sender can only
+    /// transfer the rolls it owns on top of its highest active roll count over the
+    /// cycles a denunciation can still target.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_transfer_rolls(
+        &mut self,
+        sender_addr: &Address,
+        recipient_addr: &Address,
+        slot: Slot,
+        roll_count: u64,
+        periods_per_cycle: u64,
+        denunciation_expire_periods: u64,
+    ) -> Result<(), ExecutionError> {
+        if sender_addr == recipient_addr {
+            return Err(ExecutionError::RollTransferError(format!(
+                "{} tried to transfer rolls to itself",
+                sender_addr
+            )));
+        }
+
+        // fetch the roll counts from: current changes > active history > final state
+        let sender_count = self.get_rolls(sender_addr);
+        let recipient_count = self.get_rolls(recipient_addr);
+
+        // verify that the sender has enough rolls to transfer
+        if sender_count < roll_count {
+            return Err(ExecutionError::RollTransferError(format!(
+                "{} tried to transfer {} rolls but only has {}",
+                sender_addr, roll_count, sender_count
+            )));
+        }
+
+        // verify that the transferred rolls are not at stake
+        let locked_count = self
+            .get_locked_rolls(
+                sender_addr,
+                slot,
+                periods_per_cycle,
+                denunciation_expire_periods,
+            )
+            .unwrap_or(sender_count);
+        if sender_count.saturating_sub(locked_count) < roll_count {
+            return Err(ExecutionError::RollTransferError(format!(
+                "{} tried to transfer {} rolls but {} of its {} rolls are locked until they can no longer be slashed",
+                sender_addr, roll_count, locked_count, sender_count
+            )));
+        }
+
+        // move the rolls, roll changes being absolute values
+        self.added_changes
+            .roll_changes
+            .insert(*sender_addr, sender_count.saturating_sub(roll_count));
+        self.added_changes
+            .roll_changes
+            .insert(*recipient_addr, recipient_count.saturating_add(roll_count));
+
+        Ok(())
+    }
+
+    /// Get the number of rolls of an address that can still be slashed at the given slot,
+    /// that is its highest active roll count over the cycles a denunciation can target
+    /// (from the oldest non-expired period up to the last drawn cycle).
+    ///
+    /// Returns `None` if one of the lookback cycles is not complete in the final state.
+    fn get_locked_rolls(
+        &self,
+        addr: &Address,
+        slot: Slot,
+        periods_per_cycle: u64,
+        denunciation_expire_periods: u64,
+    ) -> Option<u64> {
+        let first_cycle = Slot::new(
+            slot.period.saturating_sub(denunciation_expire_periods),
+            slot.thread,
+        )
+        .get_cycle(periods_per_cycle);
+        let last_cycle = slot.get_cycle(periods_per_cycle).saturating_add(2);
+
+        let final_state = self.final_state.read();
+        let mut locked_count = 0;
+        for cycle in first_cycle..=last_cycle {
+            if let Some(lookback_cycle) = cycle.checked_sub(3) {
+                if final_state.pos_state.is_cycle_complete(lookback_cycle) != Some(true) {
+                    return None;
+                }
+            }
+            let active_count = final_state
+                .pos_state
+                .get_address_active_rolls(addr, cycle)
+                .unwrap_or_default();
+            locked_count = max(locked_count, active_count);
+        }
+        Some(locked_count)
+    }
+
     /// Try to slash `roll_count` rolls from the given address. If not enough roll, slash
     /// the available amount and return the value.
     ///
@@ -242,6 +349,7 @@ impl SpeculativeRollState {
     ///
     /// # Arguments:
     /// `slot`: the final slot of the cycle to compute
+    /// `grace_cycles`: number of previous cycles that must also be unsatisfying for the rolls to be sold
     pub fn settle_production_stats(
         &mut self,
         slot: &Slot,
@@ -249,6 +357,7 @@ impl SpeculativeRollState {
         thread_count: u8,
         roll_price: Amount,
         max_miss_ratio: Ratio<u64>,
+        grace_cycles: u64,
     ) {
         let cycle = slot.get_cycle(periods_per_cycle);
 
@@ -269,15 +378,41 @@ impl SpeculativeRollState {
         )
         .expect("unexpected slot overflow in settle_production_stats");
 
+        let mut unsatisfying: PreHashSet<Address> = production_stats
+            .into_iter()
+            .filter(|(_, stats)| !stats.is_satisfying(&max_miss_ratio))
+            .map(|(addr, _)| addr)
+            .collect();
+
+        // spare the addresses that were satisfying (or not staking yet) in one of the grace cycles
+        for past_cycle in (1..=grace_cycles).filter_map(|offset| cycle.checked_sub(offset)) {
+            if unsatisfying.is_empty() {
+                break;
+            }
+            let (past_stats, _) = self.get_production_stats_at_cycle(
+                past_cycle,
+                periods_per_cycle,
+                thread_count,
+                slot,
+            );
+            unsatisfying.retain(|addr| {
+                past_stats
+                    .get(addr)
+                    .is_some_and(|stats| !stats.is_satisfying(&max_miss_ratio))
+            });
+        }
+        if grace_cycles > cycle {
+            // the history is too short for the addresses to have been unsatisfying long enough
+            unsatisfying.clear();
+        }
+
         let mut target_credits = PreHashMap::default();
-        for (addr, stats) in production_stats {
-            if !stats.is_satisfying(&max_miss_ratio) {
-                let owned_count = self.get_rolls(&addr);
-                if owned_count != 0 {
-                    if let Some(amount) = roll_price.checked_mul_u64(owned_count) {
-                        target_credits.insert(addr, amount);
-                        self.added_changes.roll_changes.insert(addr, 0);
-                    }
+        for addr in unsatisfying {
+            let owned_count = self.get_rolls(&addr);
+            if owned_count != 0 {
+                if let Some(amount) = roll_price.checked_mul_u64(owned_count) {
+                    target_credits.insert(addr, amount);
+                    self.added_changes.roll_changes.insert(addr, 0);
                 }
             }
         }
@@ -1,9 +1,11 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_db_exports::{DBBatch, MassaDirection, ShareableMassaDBController, EXECUTION_STATS_CF};
 use massa_models::slot::Slot;
-use massa_models::stats::ExecutionStats;
+use massa_models::stats::{DiskUsageForecast, ExecutionStats, ThroughputBucket};
 use massa_time::MassaTime;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
+use tracing::warn;
 
 /// Execution statistics counter
 pub struct ExecutionStatsCounter {
@@ -15,17 +17,47 @@ pub struct ExecutionStatsCounter {
     final_executed_ops: VecDeque<(usize, MassaTime)>,
     /// final denunciations executed in the time window (count, instant)
     final_executed_denunciations: VecDeque<(usize, MassaTime)>,
+    /// duration of the throughput history
+    history_duration: MassaTime,
+    /// final operations executed in the throughput history, aggregated per second (second start, count)
+    final_executed_ops_history: VecDeque<(MassaTime, usize)>,
+    /// database in which the throughput history is persisted
+    db: ShareableMassaDBController,
 }
 
+/// granularity of the throughput history
+const HISTORY_RESOLUTION: MassaTime = MassaTime::from_millis(1000);
+
+/// Prefix of the throughput history in the `EXECUTION_STATS_CF` column family, keyed by second start
+const THROUGHPUT_HISTORY_IDENT: u8 = 0u8;
+
 impl ExecutionStatsCounter {
-    /// create a new `ExecutionStatsCounter`
-    pub fn new(time_window_duration: MassaTime) -> Self {
-        ExecutionStatsCounter {
+    /// create a new `ExecutionStatsCounter`, loading the throughput history persisted in `db`
+    pub fn new(
+        time_window_duration: MassaTime,
+        history_duration: MassaTime,
+        db: ShareableMassaDBController,
+    ) -> Self {
+        let final_executed_ops_history = db
+            .read()
+            .prefix_iterator_cf(EXECUTION_STATS_CF, &[THROUGHPUT_HISTORY_IDENT])
+            .filter_map(|(key, value)| {
+                let second_start = u64::from_be_bytes(key.get(1..)?.try_into().ok()?);
+                let count = u64::from_be_bytes(value.as_slice().try_into().ok()?);
+                Some((MassaTime::from_millis(second_start), count as usize))
+            })
+            .collect();
+        let mut counter = ExecutionStatsCounter {
             time_window_duration,
             final_blocks: Default::default(),
             final_executed_ops: Default::default(),
             final_executed_denunciations: Default::default(),
-        }
+            history_duration,
+            final_executed_ops_history,
+            db,
+        };
+        counter.refresh(MassaTime::now().expect("could not get current time"));
+        counter
     }
 
     /// refresh the counters and delete old records
@@ -49,6 +81,16 @@ impl ExecutionStatsCounter {
                 break;
             }
         }
+
+        // prune throughput history
+        let history_start_time = current_time.saturating_sub(self.history_duration);
+        while let Some((t, _)) = self.final_executed_ops_history.front() {
+            if t.saturating_add(HISTORY_RESOLUTION) <= history_start_time {
+                self.final_executed_ops_history.pop_front();
+            } else {
+                break;
+            }
+        }
     }
 
     /// register final blocks
@@ -62,7 +104,49 @@ impl ExecutionStatsCounter {
     pub fn register_final_executed_operations(&mut self, count: usize) {
         let current_time = MassaTime::now().expect("could not get current time");
         self.final_executed_ops.push_back((count, current_time));
+        let second_start = current_time.saturating_sub(
+            current_time
+                .checked_rem_time(HISTORY_RESOLUTION)
+                .expect("history resolution is not zero"),
+        );
+        match self.final_executed_ops_history.back_mut() {
+            Some((t, cnt)) if *t == second_start => *cnt = cnt.saturating_add(count),
+            _ => self
+                .final_executed_ops_history
+                .push_back((second_start, count)),
+        }
         self.refresh(current_time);
+        self.persist_throughput_history(second_start);
+    }
+
+    /// writes the throughput history bucket starting at `second_start`,
+    /// and deletes the persisted buckets that left the history duration
+    fn persist_throughput_history(&self, second_start: MassaTime) {
+        let mut batch = DBBatch::new();
+        if let Some((_, cnt)) = self.final_executed_ops_history.back() {
+            batch.insert(
+                throughput_history_key(second_start),
+                Some((*cnt as u64).to_be_bytes().to_vec()),
+            );
+        }
+
+        let first_kept = self
+            .final_executed_ops_history
+            .front()
+            .map_or(second_start, |(t, _)| *t);
+        let db = self.db.read();
+        for (key, _) in db.range_iterator_cf(
+            EXECUTION_STATS_CF,
+            &[THROUGHPUT_HISTORY_IDENT],
+            Some(&throughput_history_key(first_kept)),
+            MassaDirection::Forward,
+        ) {
+            batch.insert(key, None);
+        }
+
+        if let Err(err) = db.write_local_batch(EXECUTION_STATS_CF, batch) {
+            warn!("could not store the throughput history: {}", err);
+        }
     }
 
     /// register final executed denunciations
@@ -94,4 +178,143 @@ impl ExecutionStatsCounter {
             final_cursor,
         }
     }
+
+    /// get the number of final executed operations over the last `window`, split in buckets of `resolution`.
+    /// The window is capped to the history duration and the resolution can't be lower than one second.
+    /// Buckets are returned from oldest to newest, the last one ending now.
+    pub fn get_throughput_history(
+        &self,
+        window: MassaTime,
+        resolution: MassaTime,
+    ) -> Vec<ThroughputBucket> {
+        let current_time = MassaTime::now().expect("could not get current time");
+        let window = std::cmp::min(window, self.history_duration);
+        let resolution = std::cmp::max(resolution, HISTORY_RESOLUTION);
+        let bucket_count = window
+            .checked_div_time(resolution)
+            .expect("resolution is not zero");
+        let start_time = current_time.saturating_sub(resolution.saturating_mul(bucket_count));
+
+        let mut buckets: Vec<ThroughputBucket> = (0..bucket_count)
+            .map(|i| {
+                let start = start_time.saturating_add(resolution.saturating_mul(i));
+                ThroughputBucket {
+                    start,
+                    end: start.saturating_add(resolution),
+                    final_executed_operations_count: 0,
+                }
+            })
+            .collect();
+        for (t, cnt) in self.final_executed_ops_history.iter() {
+            if t < &start_time || t >= &current_time {
+                continue;
+            }
+            let index = t
+                .saturating_sub(start_time)
+                .checked_div_time(resolution)
+                .expect("resolution is not zero") as usize;
+            if let Some(bucket) = buckets.get_mut(index) {
+                bucket.final_executed_operations_count =
+                    bucket.final_executed_operations_count.saturating_add(*cnt);
+            }
+        }
+        buckets
+    }
+}
+
+/// Key of the persisted throughput history bucket starting at `second_start`
+fn throughput_history_key(second_start: MassaTime) -> Vec<u8> {
+    [
+        &[THROUGHPUT_HISTORY_IDENT][..],
+        &second_start.to_millis().to_be_bytes()[..],
+    ]
+    .concat()
+}
+
+/// Final state disk usage tracker, forecasting when the disk will be full
+/// from the average growth rate of the database over a time window
+pub struct DiskUsageTracker {
+    /// minimal duration between two measurements
+    sample_interval: MassaTime,
+    /// duration over which the growth rate is averaged
+    forecast_window: MassaTime,
+    /// total database size measurements in the forecast window (instant, size in bytes)
+    samples: VecDeque<(MassaTime, u64)>,
+    /// forecast computed from the latest measurement
+    forecast: Option<DiskUsageForecast>,
+}
+
+impl DiskUsageTracker {
+    /// create a new `DiskUsageTracker`
+    pub fn new(sample_interval: MassaTime, forecast_window: MassaTime) -> Self {
+        DiskUsageTracker {
+            sample_interval,
+            forecast_window,
+            samples: Default::default(),
+            forecast: None,
+        }
+    }
+
+    /// returns true if a new measurement is due at `current_time`
+    pub fn is_sample_due(&self, current_time: MassaTime) -> bool {
+        match self.samples.back() {
+            Some((t, _)) => current_time.saturating_sub(*t) >= self.sample_interval,
+            None => true,
+        }
+    }
+
+    /// register a measurement of the database and update the forecast
+    pub fn register_sample(
+        &mut self,
+        current_time: MassaTime,
+        column_family_sizes: BTreeMap<String, u64>,
+        available_space: Option<u64>,
+    ) -> &DiskUsageForecast {
+        let total_size = column_family_sizes
+            .values()
+            .fold(0u64, |acc, size| acc.saturating_add(*size));
+        self.samples.push_back((current_time, total_size));
+
+        // prune the measurements that are out of the window, keeping at least the latest one
+        let window_start = current_time.saturating_sub(self.forecast_window);
+        while self.samples.len() > 1 {
+            match self.samples.front() {
+                Some((t, _)) if *t < window_start => {
+                    self.samples.pop_front();
+                }
+                _ => break,
+            }
+        }
+
+        let growth_rate = match (self.samples.front(), self.samples.back()) {
+            (Some((t_start, size_start)), Some((t_end, size_end))) => {
+                let elapsed = t_end.saturating_sub(*t_start).to_duration().as_secs_f64();
+                if elapsed > 0.0 {
+                    (*size_end as f64 - *size_start as f64) / elapsed
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+        let time_to_full = match available_space {
+            Some(available_space) if growth_rate > 0.0 => Some(MassaTime::from_millis(
+                (available_space as f64 / growth_rate * 1000.0) as u64,
+            )),
+            _ => None,
+        };
+
+        self.forecast.insert(DiskUsageForecast {
+            timestamp: current_time,
+            column_family_sizes,
+            growth_rate,
+            available_space,
+            time_to_full,
+        })
+    }
+
+    /// get the latest forecast, none if no measurement was made yet
+    pub fn get_forecast(&self) -> Option<DiskUsageForecast> {
+        self.forecast.clone()
+    }
 }
@@ -82,8 +82,13 @@ pub fn get_sample_state(
     let db_config = MassaDBConfig {
         path: tempdir.path().to_path_buf(),
         max_history_length: 10,
+        max_history_bytes: None,
         max_new_elements: 100,
+        max_stream_batch_bytes: None,
         thread_count: THREAD_COUNT,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -108,6 +113,7 @@ pub fn get_sample_state(
         max_denunciations_per_block_header: 0,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        snapshot_interval_cycles: 0,
     };
     let (_, selector_controller) = start_selector_worker(SelectorConfig::default())
         .expect("could not start selector controller");
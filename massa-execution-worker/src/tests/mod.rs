@@ -11,9 +11,21 @@ mod mock;
 #[cfg(all(not(feature = "gas_calibration"), not(feature = "benchmarking")))]
 mod scenarios_mandatories;
 
+#[cfg(all(not(feature = "gas_calibration"), not(feature = "benchmarking")))]
+mod tests_abi_profiler;
+
 #[cfg(all(not(feature = "gas_calibration"), not(feature = "benchmarking")))]
 mod tests_active_history;
 
+#[cfg(all(not(feature = "gas_calibration"), not(feature = "benchmarking")))]
+mod tests_execution_stats;
+
+#[cfg(all(not(feature = "gas_calibration"), not(feature = "benchmarking")))]
+mod tests_final_event_store;
+
+#[cfg(all(not(feature = "gas_calibration"), not(feature = "benchmarking")))]
+mod tests_final_transfer_store;
+
 mod interface;
 
 #[cfg(any(
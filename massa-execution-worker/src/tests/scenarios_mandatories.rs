@@ -2,19 +2,23 @@
 #[cfg(test)]
 mod tests {
     use crate::active_history::ActiveHistory;
+    use crate::context::ExecutionContext;
     use crate::speculative_async_pool::SpeculativeAsyncPool;
     use crate::start_execution_worker;
     use crate::tests::mock::{create_block, get_random_address_full, get_sample_state};
     use massa_async_pool::AsyncMessage;
     use massa_db_exports::DBBatch;
     use massa_execution_exports::{
-        ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-        ExecutionError, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+        AbiTraceCall, AsyncMessageFilter, ExecutionBlockMetadata, ExecutionChannels,
+        ExecutionConfig, ExecutionController, ExecutionError, ExecutionStackElement,
+        OperationFailureCode, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+        ReadOnlyStateOverride, TransferInfo, TransferOrigin,
     };
     use massa_hash::Hash;
     use massa_metrics::MassaMetrics;
     use massa_models::config::{
         LEDGER_ENTRY_BASE_COST, LEDGER_ENTRY_DATASTORE_BASE_SIZE, MIP_STORE_STATS_BLOCK_CONSIDERED,
+        VERSION, VERSIONING_THRESHOLD_TRANSITION_ACCEPTED,
     };
     use massa_models::prehash::PreHashMap;
     use massa_models::test_exports::gen_endorsements_for_denunciation;
@@ -31,10 +35,15 @@ mod tests {
         operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
         secure_share::SecureShareContent,
     };
+    use massa_module_cache::config::ModuleCacheConfig;
+    use massa_module_cache::controller::ModuleCache;
+    use massa_pos_exports::{PoSChanges, ProductionStats};
     use massa_signature::KeyPair;
     use massa_storage::Storage;
     use massa_time::MassaTime;
-    use massa_versioning::versioning::{MipStatsConfig, MipStore};
+    use massa_versioning::versioning::{
+        Advance, MipComponent, MipInfo, MipState, MipStatsConfig, MipStore,
+    };
     use massa_wallet::test_exports::create_test_wallet;
     use num::rational::Ratio;
     use parking_lot::RwLock;
@@ -64,6 +73,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
@@ -100,6 +110,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
@@ -150,6 +161,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -182,12 +194,16 @@ mod tests {
                 is_final: true,
                 coins: None,
                 fee: None,
+                historical_slot: None,
+                state_overrides: Default::default(),
+                trace: false,
             })
             .expect("readonly execution failed");
 
         assert_eq!(res.out.slot, Slot::new(1, 0));
         assert!(res.gas_cost > 0);
         assert_eq!(res.out.events.take().len(), 1, "wrong number of events");
+        assert!(res.trace.is_none());
 
         let res = controller
             .execute_readonly_request(ReadOnlyExecutionRequest {
@@ -199,10 +215,87 @@ mod tests {
                 is_final: false,
                 coins: None,
                 fee: None,
+                historical_slot: None,
+                state_overrides: Default::default(),
+                trace: true,
             })
             .expect("readonly execution failed");
 
         assert!(res.out.slot.period > 8);
+        let trace = res.trace.expect("the trace was not recorded");
+        assert!(trace
+            .iter()
+            .any(|item| matches!(item.call, AbiTraceCall::Event { .. })));
+
+        // the fee can only be paid by an unknown caller if its balance is overridden
+        let (caller, _) = get_random_address_full();
+        let request = |state_overrides| ReadOnlyExecutionRequest {
+            max_gas: 1_000_000,
+            call_stack: vec![ExecutionStackElement {
+                address: caller,
+                coins: Amount::zero(),
+                owned_addresses: vec![caller],
+                operation_datastore: None,
+            }],
+            target: ReadOnlyExecutionTarget::BytecodeExecution(
+                include_bytes!("./wasm/event_test.wasm").to_vec(),
+            ),
+            is_final: true,
+            coins: None,
+            fee: Some(Amount::from_str("5").unwrap()),
+            historical_slot: None,
+            state_overrides,
+            trace: false,
+        };
+        assert!(controller
+            .execute_readonly_request(request(Default::default()))
+            .is_err());
+        let state_override = ReadOnlyStateOverride {
+            balance: Some(Amount::from_str("10").unwrap()),
+            datastore: [(b"key".to_vec(), Some(b"value".to_vec()))].into(),
+            ..Default::default()
+        };
+        let mut res = controller
+            .execute_readonly_request(request([(caller, state_override.clone())].into()))
+            .expect("readonly execution with state overrides failed");
+        assert_eq!(res.out.events.take().len(), 1, "wrong number of events");
+
+        // the estimated gas is enough to run the same execution again
+        let estimation = controller
+            .estimate_gas(request([(caller, state_override.clone())].into()))
+            .expect("gas estimation failed");
+        assert_eq!(estimation.gas_cost, res.gas_cost);
+        assert!(estimation.required_gas >= estimation.gas_cost);
+        assert!(estimation.estimated_max_gas > estimation.required_gas);
+        let mut estimated_request = request([(caller, state_override.clone())].into());
+        estimated_request.max_gas = estimation.estimated_max_gas;
+        controller
+            .execute_readonly_request(estimated_request)
+            .expect("readonly execution with the estimated gas failed");
+
+        // the requests of a batch read the same state, and the overrides of one request do not leak to the next
+        let outputs = controller
+            .execute_readonly_batch(vec![
+                request([(caller, state_override.clone())].into()),
+                request(Default::default()),
+                request([(caller, state_override)].into()),
+            ])
+            .expect("readonly batch failed");
+        assert_eq!(outputs.len(), 3);
+        assert!(outputs[1].is_err());
+        let first = outputs[0].as_ref().expect("first batched execution failed");
+        let last = outputs[2].as_ref().expect("last batched execution failed");
+        assert_eq!(first.out.slot, last.out.slot);
+        assert_eq!(first.gas_cost, last.gas_cost);
+        assert_eq!(last.out.events.0.len(), 1, "wrong number of events");
+
+        // a batch is bounded by the gas of a single read-only request
+        let mut heavy_request = request(Default::default());
+        heavy_request.max_gas = exec_cfg.max_read_only_gas;
+        assert!(matches!(
+            controller.execute_readonly_batch(vec![heavy_request.clone(), heavy_request]),
+            Err(ExecutionError::TooMuchGas(_))
+        ));
 
         manager.stop();
     }
@@ -276,6 +369,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -460,6 +554,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -629,6 +724,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // init the storage
@@ -754,6 +850,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -867,6 +964,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -998,6 +1096,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -1123,6 +1222,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -1237,6 +1337,18 @@ mod tests {
         // match the events
         assert!(events.len() == 3, "3 events were expected");
 
+        // the message with the wrong trigger is waiting in the final pool
+        let messages = controller.get_async_messages(AsyncMessageFilter {
+            is_final: Some(true),
+            ..Default::default()
+        });
+        assert!(!messages.is_empty(), "a pending message was expected");
+        let messages = controller.get_async_messages(AsyncMessageFilter {
+            sender: Some(get_random_address()),
+            ..Default::default()
+        });
+        assert!(messages.is_empty(), "no message was expected");
+
         // keypair associated to thread 2
         let keypair = KeyPair::from_str(TEST_SK_3).unwrap();
         // load bytecode
@@ -1307,6 +1419,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -1346,6 +1459,7 @@ mod tests {
             &sender_keypair,
         )
         .unwrap();
+        let operation_id = operation.id;
         // create the block containing the transaction operation
         storage.store_operations(vec![operation.clone()]);
         let block = create_block(
@@ -1387,6 +1501,25 @@ mod tests {
                 // Storage cost base
                 .saturating_sub(LEDGER_ENTRY_BASE_COST)
         );
+        // check that the transfer was recorded for the slot
+        let slot_transfers = controller.get_slot_transfers(Slot::new(1, 0), Slot::new(1, 0));
+        assert_eq!(slot_transfers.len(), 1);
+        assert!(slot_transfers[0].is_final);
+        assert!(slot_transfers[0].transfers.contains(&TransferInfo {
+            from: Some(Address::from_public_key(&sender_keypair.get_public_key())),
+            to: Some(recipient_address),
+            amount: Amount::from_str("100").unwrap(),
+            origin: TransferOrigin::Operation(operation_id),
+        }));
+        // check that the ledger changes of the operation were recorded
+        let op_ledger_changes = controller.get_ops_ledger_changes(&[operation_id]);
+        let op_ledger_changes = op_ledger_changes[0]
+            .as_ref()
+            .expect("missing operation ledger changes");
+        assert!(op_ledger_changes
+            .0
+            .contains_key(&Address::from_public_key(&sender_keypair.get_public_key())));
+        assert!(op_ledger_changes.0.contains_key(&recipient_address));
         // stop the execution controller
         manager.stop();
     }
@@ -1417,6 +1550,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -1490,6 +1624,225 @@ mod tests {
         manager.stop();
     }
 
+    #[test]
+    #[serial]
+    pub fn roll_transfer_before_activation() {
+        // setup the period duration
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the MIP store without any roll transfer MIP
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        // init the storage
+        let mut storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+        // generate the sender keypair and the recipient address
+        let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+        let recipient_address = get_random_address();
+        // create the operation
+        let operation = Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::RollTransfer {
+                    recipient_address,
+                    roll_count: 10,
+                },
+            },
+            OperationSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+        // create the block containing the roll transfer operation
+        storage.store_operations(vec![operation.clone()]);
+        let block = create_block(
+            KeyPair::generate(0).unwrap(),
+            vec![operation],
+            vec![],
+            Slot::new(1, 0),
+        )
+        .unwrap();
+        // store the block in storage
+        storage.store_block(block.clone());
+        // set our block as a final block so the transfer is processed
+        let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+        finalized_blocks.insert(block.content.header.content.slot, block.id);
+        let mut block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata> = Default::default();
+        block_metadata.insert(
+            block.id,
+            ExecutionBlockMetadata {
+                same_thread_parent_creator: Some(get_random_address()),
+                storage: Some(storage),
+            },
+        );
+        controller.update_blockclique_status(
+            finalized_blocks,
+            Default::default(),
+            block_metadata.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(100));
+        // check that the transfer was refused and no roll moved
+        let events = controller.get_filtered_sc_output_event(EventFilter {
+            start: Some(Slot::new(1, 0)),
+            end: Some(Slot::new(1, 1)),
+            ..Default::default()
+        });
+        assert!(events
+            .iter()
+            .any(|event| event.data.contains("before roll transfers are activated")));
+        let sample_read = sample_state.read();
+        assert_eq!(sample_read.pos_state.get_rolls_for(&address), 100);
+        assert_eq!(sample_read.pos_state.get_rolls_for(&recipient_address), 0);
+        // stop the execution controller
+        manager.stop();
+    }
+
+    /// Settle the last slot of cycle 1 for a staker that produced its block in cycle 0
+    /// but missed its block in cycle 1, and return the roll count it is set to, if any
+    fn settle_staker_unsatisfying_for_one_cycle(mip_store: MipStore) -> Option<u64> {
+        let exec_cfg = ExecutionConfig {
+            periods_per_cycle: 10,
+            ..ExecutionConfig::default()
+        };
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+        let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+
+        // complete cycle 0 with a satisfying staker, then start cycle 1 in the final state
+        {
+            let mut final_state = sample_state.write();
+            let mut batch = DBBatch::new();
+            let mut changes = PoSChanges::default();
+            changes
+                .seed_bits
+                .resize(10 * exec_cfg.thread_count as usize, false);
+            changes.production_stats.insert(
+                address,
+                ProductionStats {
+                    block_success_count: 1,
+                    block_failure_count: 0,
+                },
+            );
+            final_state
+                .pos_state
+                .apply_changes_to_batch(changes, Slot::new(9, 31), false, &mut batch)
+                .unwrap();
+            final_state
+                .pos_state
+                .apply_changes_to_batch(PoSChanges::default(), Slot::new(10, 0), false, &mut batch)
+                .unwrap();
+            final_state
+                .db
+                .write()
+                .write_batch(batch, Default::default(), None);
+        }
+
+        // miss the block of the staker at the last slot of cycle 1 and settle the cycle
+        let module_cache = Arc::new(RwLock::new(ModuleCache::new(ModuleCacheConfig {
+            hd_cache_path: exec_cfg.hd_cache_path.clone(),
+            gas_costs: exec_cfg.gas_costs.clone(),
+            compilation_gas: exec_cfg.max_gas_per_block,
+            lru_cache_size: exec_cfg.lru_cache_size,
+            hd_cache_size: exec_cfg.hd_cache_size,
+            snip_amount: exec_cfg.snip_amount,
+            max_module_length: exec_cfg.max_bytecode_size,
+            engine_version: VERSION.to_string(),
+        })));
+        let slot = Slot::new(19, 31);
+        let mut context = ExecutionContext::active_slot(
+            exec_cfg,
+            slot,
+            None,
+            sample_state,
+            Arc::new(RwLock::new(ActiveHistory::default())),
+            module_cache,
+            mip_store,
+        );
+        context.update_production_stats(&address, slot, None);
+        let output = context.settle_slot(None);
+        output
+            .state_changes
+            .pos_changes
+            .roll_changes
+            .get(&address)
+            .copied()
+    }
+
+    #[test]
+    #[serial]
+    fn roll_sale_grace_period_activation() {
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+
+        // before activation, a single unsatisfying cycle sells the rolls
+        let mip_store = MipStore::try_from(([], mip_stats_config.clone())).unwrap();
+        assert_eq!(settle_staker_unsatisfying_for_one_cycle(mip_store), Some(0));
+
+        // once the grace period is active, the staker keeps its rolls
+        let mip_info = MipInfo {
+            name: "MIP-RollSaleGracePeriod".to_string(),
+            version: 1,
+            components: BTreeMap::from([(MipComponent::RollSaleGracePeriod, 1)]),
+            start: MassaTime::from_millis(2),
+            timeout: MassaTime::from_millis(10),
+            activation_delay: MassaTime::from_millis(2),
+        };
+        let mut mip_state = MipState::new(MassaTime::from_millis(1));
+        let mut advance = Advance {
+            start_timestamp: mip_info.start,
+            timeout: mip_info.timeout,
+            activation_delay: mip_info.activation_delay,
+            threshold: Ratio::new_raw(0, 1),
+            now: MassaTime::from_millis(3),
+        };
+        mip_state.on_advance(&advance);
+        advance.threshold = VERSIONING_THRESHOLD_TRANSITION_ACCEPTED;
+        advance.now = MassaTime::from_millis(4);
+        mip_state.on_advance(&advance);
+        advance.now = MassaTime::from_millis(7);
+        mip_state.on_advance(&advance);
+        let mip_store = MipStore::try_from(([(mip_info, mip_state)], mip_stats_config)).unwrap();
+        assert_eq!(settle_staker_unsatisfying_for_one_cycle(mip_store), None);
+    }
+
     #[test]
     #[serial]
     pub fn roll_sell() {
@@ -1527,6 +1880,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -1743,6 +2097,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -1916,6 +2271,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -2096,6 +2452,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -2124,6 +2481,7 @@ mod tests {
         // create the block containing the erroneous smart contract execution operation
         let operation =
             create_execute_sc_operation(&keypair, bytecode, BTreeMap::default()).unwrap();
+        let operation_id = operation.id;
         storage.store_operations(vec![operation.clone()]);
         let block = create_block(
             KeyPair::generate(0).unwrap(),
@@ -2165,6 +2523,17 @@ mod tests {
             .data
             .contains("runtime error when executing operation"));
         assert!(events[1].data.contains("address parsing error"));
+
+        // check the structured failure reason of the operation
+        let failure = controller.get_ops_failures(&[operation_id])[0]
+            .clone()
+            .expect("the failure reason of the operation was expected");
+        assert_eq!(failure.code, OperationFailureCode::ScriptFailure);
+        assert_eq!(
+            failure.address,
+            Some(Address::from_public_key(&keypair.get_public_key()))
+        );
+        assert!(failure.message.contains("address parsing error"));
         // stop the execution controller
         manager.stop();
     }
@@ -2196,6 +2565,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -2293,6 +2663,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -2390,6 +2761,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -2555,6 +2927,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         let (mut manager, controller) = start_execution_worker(
@@ -2682,6 +3055,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -2842,6 +3216,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
@@ -2949,6 +3324,7 @@ mod tests {
 
         let channels = ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: broadcast::channel(100).0,
         };
 
         // start the execution worker
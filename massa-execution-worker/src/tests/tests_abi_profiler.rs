@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use crate::abi_profiler::{AbiCallTimer, AbiProfiler};
+    use massa_models::slot::Slot;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_abi_profiler_aggregates_last_slots() {
+        let mut profiler = AbiProfiler::new(2);
+
+        // calls made outside of a slot execution are discarded
+        profiler.record("get_balance", Duration::from_millis(100));
+        profiler.start_slot();
+        profiler.record("get_balance", Duration::from_micros(10));
+        profiler.record("raw_get_data", Duration::from_micros(30));
+        profiler.settle_slot(Slot::new(1, 0));
+
+        profiler.start_slot();
+        profiler.record("get_balance", Duration::from_micros(40));
+        profiler.settle_slot(Slot::new(1, 1));
+
+        let stats = profiler.get_call_stats(2);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].function, "get_balance");
+        assert_eq!(stats[0].call_count, 2);
+        assert_eq!(stats[0].total_time, Duration::from_micros(50));
+        assert_eq!(stats[1].function, "raw_get_data");
+        assert_eq!(stats[1].call_count, 1);
+
+        let stats = profiler.get_call_stats(1);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].call_count, 1);
+
+        // only the last `max_slots` slots are kept
+        profiler.start_slot();
+        profiler.settle_slot(Slot::new(1, 2));
+        let stats = profiler.get_call_stats(10);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].total_time, Duration::from_micros(40));
+    }
+
+    #[test]
+    fn test_abi_call_timer_records_on_drop() {
+        let profiler = Arc::new(Mutex::new(AbiProfiler::new(1)));
+        {
+            let _timer = AbiCallTimer::new(&profiler, "print");
+        }
+        profiler.lock().settle_slot(Slot::new(1, 0));
+        let stats = profiler.lock().get_call_stats(1);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].function, "print");
+        assert_eq!(stats[0].call_count, 1);
+    }
+}
@@ -58,6 +58,9 @@ mod tests {
                 execution_trail_hash_change: Default::default(),
             },
             events: Default::default(),
+            transfers: Default::default(),
+            operation_changes: Default::default(),
+            operation_failures: Default::default(),
         };
 
         let active_history = ActiveHistory {
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use crate::stats::ExecutionStatsCounter;
+    use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_models::config::THREAD_COUNT;
+    use massa_time::MassaTime;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn get_db() -> (ShareableMassaDBController, TempDir) {
+        let tempdir = TempDir::new().expect("cannot create temp directory");
+        let db_config = MassaDBConfig {
+            path: tempdir.path().to_path_buf(),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        (db, tempdir)
+    }
+
+    #[test]
+    fn test_throughput_history_persistence() {
+        let (db, _tempdir) = get_db();
+        let time_window = MassaTime::from_millis(10_000);
+        let history_duration = MassaTime::from_millis(60_000);
+
+        let mut counter = ExecutionStatsCounter::new(time_window, history_duration, db.clone());
+        counter.register_final_executed_operations(3);
+        counter.register_final_executed_operations(4);
+        let count = |counter: &ExecutionStatsCounter| -> usize {
+            counter
+                .get_throughput_history(history_duration, history_duration)
+                .iter()
+                .map(|bucket| bucket.final_executed_operations_count)
+                .sum()
+        };
+        assert_eq!(count(&counter), 7);
+        drop(counter);
+
+        // the history is reloaded from the database after a restart
+        let mut counter = ExecutionStatsCounter::new(time_window, history_duration, db);
+        assert_eq!(count(&counter), 7);
+        counter.register_final_executed_operations(1);
+        assert_eq!(count(&counter), 8);
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::final_event_store::FinalEventStore;
+    use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_execution_exports::EventStore;
+    use massa_hash::Hash;
+    use massa_models::address::{Address, UserAddress, UserAddressV0};
+    use massa_models::config::THREAD_COUNT;
+    use massa_models::execution::EventFilter;
+    use massa_models::operation::OperationId;
+    use massa_models::output_event::{EventExecutionContext, SCOutputEvent};
+    use massa_models::slot::Slot;
+    use parking_lot::RwLock;
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn get_db() -> (ShareableMassaDBController, TempDir) {
+        let tempdir = TempDir::new().expect("cannot create temp directory");
+        let db_config = MassaDBConfig {
+            path: tempdir.path().to_path_buf(),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        (db, tempdir)
+    }
+
+    fn get_event(slot: Slot, emitter: Address, operation_id: Option<OperationId>) -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot: 0,
+                call_stack: VecDeque::from(vec![emitter]),
+                origin_operation_id: operation_id,
+                is_final: true,
+                is_error: false,
+            },
+            data: format!("{}", slot),
+        }
+    }
+
+    #[test]
+    fn test_final_event_store_filters_and_retention() {
+        let (db, _tempdir) = get_db();
+        let mut store = FinalEventStore::new(db, 3, 100);
+
+        let addr1 = Address::User(UserAddress::UserAddressV0(UserAddressV0(
+            Hash::compute_from("AU1".as_bytes()),
+        )));
+        let addr2 = Address::User(UserAddress::UserAddressV0(UserAddressV0(
+            Hash::compute_from("AU2".as_bytes()),
+        )));
+        let op_id = OperationId::new(Hash::compute_from("op".as_bytes()));
+
+        for period in 1..=4 {
+            let slot = Slot::new(period, 0);
+            let mut events = EventStore::default();
+            events.push(get_event(slot, addr1, None));
+            events.push(get_event(slot, addr2, Some(op_id)));
+            store.push_slot_events(slot, &events);
+        }
+
+        // the events of period 1 left the retention horizon
+        let events = store.get_filtered_sc_output_events(&EventFilter::default(), usize::MAX);
+        assert_eq!(events.len(), 6);
+        assert_eq!(store.pruned_until(), Some(Slot::new(1, 0)));
+        assert_eq!(events[0].context.slot, Slot::new(2, 0));
+        assert_eq!(events[5].context.slot, Slot::new(4, 0));
+
+        // emitter index, restricted to a slot range
+        let events = store.get_filtered_sc_output_events(
+            &EventFilter {
+                emitter_address: Some(addr1),
+                start: Some(Slot::new(3, 0)),
+                ..Default::default()
+            },
+            usize::MAX,
+        );
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|event| event.context.call_stack.front() == Some(&addr1)));
+
+        // operation index, with the other criteria still applied
+        let events = store.get_filtered_sc_output_events(
+            &EventFilter {
+                original_operation_id: Some(op_id),
+                emitter_address: Some(addr1),
+                ..Default::default()
+            },
+            usize::MAX,
+        );
+        assert!(events.is_empty());
+        let events = store.get_filtered_sc_output_events(
+            &EventFilter {
+                original_operation_id: Some(op_id),
+                ..Default::default()
+            },
+            2,
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].context.slot, Slot::new(2, 0));
+        assert_eq!(events[1].context.slot, Slot::new(3, 0));
+    }
+
+    #[test]
+    fn test_final_event_store_capacity() {
+        let (db, _tempdir) = get_db();
+        let mut store = FinalEventStore::new(db.clone(), 1000, 5);
+        let addr = Address::User(UserAddress::UserAddressV0(UserAddressV0(
+            Hash::compute_from("AU1".as_bytes()),
+        )));
+
+        let mut truncated = 0;
+        for period in 1..=3 {
+            let slot = Slot::new(period, 0);
+            let mut events = EventStore::default();
+            events.push(get_event(slot, addr, None));
+            events.push(get_event(slot, addr, None));
+            truncated += store.push_slot_events(slot, &events);
+        }
+
+        // the oldest event was pruned to respect the capacity
+        assert_eq!(truncated, 1);
+        assert_eq!(store.pruned_until(), Some(Slot::new(1, 0)));
+        let events = store.get_filtered_sc_output_events(
+            &EventFilter {
+                emitter_address: Some(addr),
+                ..Default::default()
+            },
+            usize::MAX,
+        );
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0].context.slot, Slot::new(1, 0));
+        assert_eq!(events[1].context.slot, Slot::new(2, 0));
+
+        // the count and the pruning slot are restored from the disk
+        let mut store = FinalEventStore::new(db, 1000, 5);
+        assert_eq!(store.pruned_until(), Some(Slot::new(1, 0)));
+        let slot = Slot::new(4, 0);
+        let mut events = EventStore::default();
+        events.push(get_event(slot, addr, None));
+        assert_eq!(store.push_slot_events(slot, &events), 1);
+        let events = store.get_filtered_sc_output_events(&EventFilter::default(), usize::MAX);
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0].context.slot, Slot::new(2, 0));
+    }
+}
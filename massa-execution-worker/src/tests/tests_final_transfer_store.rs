@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use crate::final_transfer_store::FinalTransferStore;
+    use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_execution_exports::{TransferInfo, TransferOrigin};
+    use massa_hash::Hash;
+    use massa_models::address::{Address, UserAddress, UserAddressV0};
+    use massa_models::amount::Amount;
+    use massa_models::config::THREAD_COUNT;
+    use massa_models::execution::TransferFilter;
+    use massa_models::operation::OperationId;
+    use massa_models::slot::Slot;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn get_db() -> (ShareableMassaDBController, TempDir) {
+        let tempdir = TempDir::new().expect("cannot create temp directory");
+        let db_config = MassaDBConfig {
+            path: tempdir.path().to_path_buf(),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        (db, tempdir)
+    }
+
+    fn get_address(seed: &str) -> Address {
+        Address::User(UserAddress::UserAddressV0(UserAddressV0(
+            Hash::compute_from(seed.as_bytes()),
+        )))
+    }
+
+    #[test]
+    fn test_final_transfer_store_index_and_retention() {
+        let (db, _tempdir) = get_db();
+        let store = FinalTransferStore::new(db, 3);
+
+        let addr1 = get_address("AU1");
+        let addr2 = get_address("AU2");
+        let op_id = OperationId::new(Hash::compute_from("op".as_bytes()));
+
+        for period in 1..=4 {
+            let transfers = vec![
+                TransferInfo {
+                    from: Some(addr1),
+                    to: Some(addr2),
+                    amount: Amount::from_raw(period),
+                    origin: TransferOrigin::Operation(op_id),
+                },
+                TransferInfo {
+                    from: None,
+                    to: Some(addr1),
+                    amount: Amount::from_raw(100),
+                    origin: TransferOrigin::BlockReward,
+                },
+            ];
+            store.push_slot_transfers(Slot::new(period, 0), &transfers);
+        }
+
+        // the transfers of period 1 left the retention horizon
+        let filter = TransferFilter {
+            address: addr1,
+            start: None,
+            end: None,
+            is_final: None,
+            offset: None,
+            limit: None,
+        };
+        let transfers = store.get_address_transfers(&filter, usize::MAX);
+        assert_eq!(transfers.len(), 6);
+        assert_eq!(transfers[0].0, Slot::new(2, 0));
+        assert_eq!(
+            transfers[0].1,
+            TransferInfo {
+                from: Some(addr1),
+                to: Some(addr2),
+                amount: Amount::from_raw(2),
+                origin: TransferOrigin::Operation(op_id),
+            }
+        );
+        assert_eq!(transfers[1].1.origin, TransferOrigin::BlockReward);
+        assert_eq!(transfers[5].0, Slot::new(4, 0));
+
+        // the block rewards of addr1 are not indexed for addr2
+        let transfers = store.get_address_transfers(
+            &TransferFilter {
+                address: addr2,
+                start: Some(Slot::new(3, 0)),
+                ..filter.clone()
+            },
+            usize::MAX,
+        );
+        assert_eq!(transfers.len(), 2);
+        assert!(transfers
+            .iter()
+            .all(|(_, transfer)| transfer.to == Some(addr2)));
+
+        // slot range and maximum count
+        let transfers = store.get_address_transfers(
+            &TransferFilter {
+                end: Some(Slot::new(4, 0)),
+                ..filter
+            },
+            3,
+        );
+        assert_eq!(transfers.len(), 3);
+        assert_eq!(transfers[2].0, Slot::new(3, 0));
+    }
+}
@@ -7,11 +7,11 @@
 
 use crate::controller::{ExecutionControllerImpl, ExecutionInputData, ExecutionManagerImpl};
 use crate::execution::ExecutionState;
-use crate::request_queue::RequestQueue;
+use crate::request_queue::{ReadOnlyRequestQueue, RequestQueue};
 use crate::slot_sequencer::SlotSequencer;
 use massa_execution_exports::{
     ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionError, ExecutionManager,
 };
 use massa_final_state::FinalState;
 use massa_metrics::MassaMetrics;
@@ -34,8 +34,8 @@ pub(crate) struct ExecutionThread {
     slot_sequencer: SlotSequencer,
     // Execution state (see execution.rs) to which execution requests are sent
     execution_state: Arc<RwLock<ExecutionState>>,
-    /// queue for read-only requests and response MPSCs to send back their outputs
-    readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// queue for batches of read-only requests and response MPSCs to send back their outputs
+    readonly_requests: ReadOnlyRequestQueue,
     /// Selector controller
     selector: Box<dyn SelectorController>,
 }
@@ -77,26 +77,23 @@ impl ExecutionThread {
 
     /// Append incoming read-only requests to the relevant queue,
     /// Cancel those that are in excess if there are too many.
-    fn update_readonly_requests(
-        &mut self,
-        new_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
-    ) {
+    fn update_readonly_requests(&mut self, new_requests: ReadOnlyRequestQueue) {
         // Append incoming readonly requests to our readonly request queue
         // Excess requests are cancelled
         self.readonly_requests.extend(new_requests);
     }
 
-    /// Executes a read-only request from the queue, if any.
-    /// The result of the execution is sent asynchronously through the response channel provided with the request.
+    /// Executes a batch of read-only requests from the queue, if any.
+    /// The results of the executions are sent asynchronously through the response channel provided with the batch.
     ///
     /// # Returns
-    /// true if a request was executed, false otherwise
-    fn execute_one_readonly_request(&mut self) -> bool {
+    /// true if a batch was executed, false otherwise
+    fn execute_one_readonly_batch(&mut self) -> bool {
         if let Some(req_resp) = self.readonly_requests.pop() {
-            let (req, resp_tx) = req_resp.into_request_sender_pair();
+            let (reqs, resp_tx) = req_resp.into_request_sender_pair();
 
-            // Acquire write access to the execution state (for cache updates) and execute the read-only request
-            let outcome = self.execution_state.write().execute_readonly_request(req);
+            // Acquire write access to the execution state (for cache updates) and execute the read-only requests
+            let outcome = Ok(self.execution_state.write().execute_readonly_batch(reqs));
 
             // Send the execution output through resp_tx.
             // Ignore errors because they just mean that the request emitter dropped the received
@@ -216,8 +213,8 @@ impl ExecutionThread {
                 continue;
             }
 
-            // low priority: execute a read-only request (note that the queue is of finite length), if there is one ready.
-            self.execute_one_readonly_request();
+            // low priority: execute a batch of read-only requests (note that the queue is of finite length), if there is one ready.
+            self.execute_one_readonly_batch();
         }
 
         // We are quitting the loop.
@@ -275,6 +272,7 @@ pub fn start_execution_worker(
     let controller = ExecutionControllerImpl {
         input_data: input_data.clone(),
         execution_state: execution_state.clone(),
+        config: config.clone(),
     };
 
     // launch the execution thread
@@ -43,4 +43,6 @@ pub struct FinalStateConfig {
     pub t0: MassaTime,
     /// TODO
     pub genesis_timestamp: MassaTime,
+    /// number of cycles between two state snapshots, disabled if 0
+    pub snapshot_interval_cycles: u64,
 }
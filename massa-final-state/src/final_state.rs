@@ -5,29 +5,29 @@
 //! the output of a given final slot (the latest executed final slot),
 //! and need to be bootstrapped by nodes joining the network.
 
-use crate::{config::FinalStateConfig, error::FinalStateError, state_changes::StateChanges};
+use crate::{
+    config::FinalStateConfig, error::FinalStateError, snapshot::StateSnapshotManager,
+    state_changes::StateChanges,
+};
 
 use anyhow::{anyhow, Result as AnyResult};
 use massa_async_pool::AsyncPool;
 use massa_db_exports::EXECUTION_TRAIL_HASH_PREFIX;
 use massa_db_exports::{
-    DBBatch, MassaIteratorMode, ShareableMassaDBController, ASYNC_POOL_PREFIX,
-    CYCLE_HISTORY_PREFIX, DEFERRED_CREDITS_PREFIX, EXECUTED_DENUNCIATIONS_PREFIX,
-    EXECUTED_OPS_PREFIX, LEDGER_PREFIX, MIP_STORE_PREFIX, STATE_CF,
+    DBBatch, DBPreviousValues, MassaDBError, MassaDBIntegrityReport, MassaIteratorMode,
+    ShareableMassaDBController, ASYNC_POOL_PREFIX, CYCLE_HISTORY_PREFIX, DEFERRED_CREDITS_PREFIX,
+    EXECUTED_DENUNCIATIONS_PREFIX, EXECUTED_OPS_PREFIX, LEDGER_PREFIX, MIP_STORE_PREFIX, STATE_CF,
 };
 use massa_executed_ops::ExecutedDenunciations;
 use massa_executed_ops::ExecutedOps;
 use massa_ledger_exports::LedgerController;
 use massa_ledger_exports::SetOrKeep;
 use massa_models::slot::Slot;
+use massa_models::timeslots::get_block_slot_timestamp;
 use massa_pos_exports::{PoSFinalState, SelectorController};
 use massa_versioning::versioning::MipStore;
 use tracing::{debug, info, warn};
 
-#[cfg(feature = "bootstrap_server")]
-use massa_models::config::PERIODS_BETWEEN_BACKUPS;
-use massa_models::timeslots::get_block_slot_timestamp;
-
 /// Represents a final state `(ledger, async pool, executed_ops, executed_de and the state of the PoS)`
 pub struct FinalState {
     /// execution state configuration
@@ -56,6 +56,8 @@ pub struct FinalState {
     pub last_slot_before_downtime: Option<Slot>,
     /// the rocksdb instance used to write every final_state struct on disk
     pub db: ShareableMassaDBController,
+    /// periodic snapshots of the state, served for snapshot sync
+    pub snapshots: StateSnapshotManager,
 }
 
 impl FinalState {
@@ -106,6 +108,8 @@ impl FinalState {
         let executed_denunciations =
             ExecutedDenunciations::new(config.executed_denunciations_config.clone(), db.clone());
 
+        let snapshots = StateSnapshotManager::new(&config);
+
         let mut final_state = FinalState {
             ledger,
             async_pool,
@@ -117,6 +121,7 @@ impl FinalState {
             last_start_period: 0,
             last_slot_before_downtime: None,
             db,
+            snapshots,
         };
 
         if reset_final_state {
@@ -599,6 +604,8 @@ impl FinalState {
 
         let mut db_batch = DBBatch::new();
         let mut db_versioning_batch = DBBatch::new();
+        // values of the changed keys already read while building the batch, to avoid reading them again
+        let mut db_previous_values = DBPreviousValues::new();
 
         // apply the state changes to the batch
 
@@ -609,8 +616,11 @@ impl FinalState {
 
         // do not panic above, it might just mean that the lookback cycle is not available
         // bootstrap again instead
-        self.ledger
-            .apply_changes_to_batch(changes.ledger_changes, &mut db_batch);
+        self.ledger.apply_changes_to_batch(
+            changes.ledger_changes,
+            &mut db_batch,
+            &mut db_previous_values,
+        );
         self.executed_ops
             .apply_changes_to_batch(changes.executed_ops_changes, slot, &mut db_batch);
 
@@ -648,36 +658,32 @@ impl FinalState {
             );
         }
 
-        self.db
-            .write()
-            .write_batch(db_batch, db_versioning_batch, Some(slot));
+        self.db.write().write_batch_with_previous_values(
+            db_batch,
+            db_versioning_batch,
+            &db_previous_values,
+            Some(slot),
+        );
 
         let final_state_hash = self.db.read().get_xof_db_hash();
 
         // compute the final state hash
         info!("final_state hash at slot {}: {}", slot, final_state_hash);
 
-        // Backup DB if needed
+        // Snapshot the state if needed
         #[cfg(feature = "bootstrap_server")]
-        if slot.period % PERIODS_BETWEEN_BACKUPS == 0 && slot.period != 0 && slot.thread == 0 {
-            let state_slot = self.db.read().get_change_id();
-            match state_slot {
-                Ok(slot) => {
-                    info!(
-                        "Backuping db for slot {}, state slot: {}, state hash: {}",
-                        slot, slot, final_state_hash
-                    );
-                }
-                Err(e) => {
-                    info!("{}", e);
-                    info!(
-                        "Backuping db for unknown state slot, state hash: {}",
-                        final_state_hash
-                    );
-                }
+        if self.snapshots.is_snapshot_slot(slot) {
+            match self
+                .snapshots
+                .take_snapshot(&self.db, slot, final_state_hash)
+            {
+                Ok(snapshot) => info!(
+                    "state snapshot at slot {} written to {}",
+                    slot,
+                    snapshot.path.display()
+                ),
+                Err(e) => warn!("could not snapshot the state at slot {}: {}", slot, e),
             }
-
-            self.db.read().backup_db(slot);
         }
 
         // feed final_state_hash to the last cycle
@@ -821,6 +827,39 @@ impl FinalState {
         Ok(())
     }
 
+    /// Recomputes the state hash of the DB, compares it to the stored one and reports the
+    /// key/values that are rejected by their sub-state.
+    pub fn verify_db_integrity(&self) -> Result<MassaDBIntegrityReport, MassaDBError> {
+        self.db
+            .read()
+            .verify_integrity(&|key, value| self.is_state_key_value_valid(key, value))
+    }
+
+    /// Checks a key/value of STATE_CF with the sub-state owning its prefix
+    fn is_state_key_value_valid(&self, key: &[u8], value: &[u8]) -> bool {
+        if key.starts_with(CYCLE_HISTORY_PREFIX.as_bytes()) {
+            self.pos_state.is_cycle_history_key_value_valid(key, value)
+        } else if key.starts_with(DEFERRED_CREDITS_PREFIX.as_bytes()) {
+            self.pos_state
+                .is_deferred_credits_key_value_valid(key, value)
+        } else if key.starts_with(ASYNC_POOL_PREFIX.as_bytes()) {
+            self.async_pool.is_key_value_valid(key, value)
+        } else if key.starts_with(EXECUTED_OPS_PREFIX.as_bytes()) {
+            self.executed_ops.is_key_value_valid(key, value)
+        } else if key.starts_with(EXECUTED_DENUNCIATIONS_PREFIX.as_bytes()) {
+            self.executed_denunciations.is_key_value_valid(key, value)
+        } else if key.starts_with(LEDGER_PREFIX.as_bytes()) {
+            self.ledger.is_key_value_valid(key, value)
+        } else if key.starts_with(MIP_STORE_PREFIX.as_bytes()) {
+            // TODO: check MIP_STORE_PREFIX
+            true
+        } else if key.starts_with(EXECUTION_TRAIL_HASH_PREFIX.as_bytes()) {
+            massa_hash::Hash::try_from(value).is_ok()
+        } else {
+            false
+        }
+    }
+
     /// Initialize the execution trail hash to zero.
     pub fn init_execution_trail_hash_to_batch(&mut self, batch: &mut DBBatch) {
         batch.insert(
@@ -858,7 +897,7 @@ mod test {
         MAX_DEFERRED_CREDITS_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         MAX_DENUNCIATION_CHANGES_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_PARAMETERS_SIZE,
         MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, MIP_STORE_STATS_BLOCK_CONSIDERED,
-        PERIODS_PER_CYCLE, POS_SAVED_CYCLES, T0, THREAD_COUNT,
+        PERIODS_PER_CYCLE, POS_PRODUCTION_STATS_HISTORY_CYCLES, POS_SAVED_CYCLES, T0, THREAD_COUNT,
     };
     use massa_models::prehash::PreHashMap;
     use massa_pos_exports::MockSelectorController;
@@ -896,6 +935,7 @@ mod test {
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
             initial_deferred_credits_path: Some(
                 massa_node_base.join("base_config/deferred_credits.json"),
             ),
@@ -927,6 +967,7 @@ mod test {
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             t0: T0,
             genesis_timestamp,
+            snapshot_interval_cycles: 0,
         };
 
         return (final_state_config, ledger_config);
@@ -941,8 +982,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir_db.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -28,6 +28,10 @@
 //! ## `bootstrap.rs`
 //! Provides serializable structures and tools for bootstrapping the final state.
 //!
+//! ## `snapshot.rs`
+//! Takes periodic snapshots of the final state and records their slot and state hash in a manifest,
+//! so that they can be served for a snapshot sync instead of a full re-bootstrap.
+//!
 //! ## Test exports
 //!
 //! When the crate feature `testing` is enabled, tooling useful for testing purposes is exported.
@@ -79,8 +83,9 @@
 //!
 //! By default, the network restarts from the state associated with the last final slot before the shutdown.
 //! However, we may sometimes want to recover from an earlier state (e.g. if an attacker stole 50% of all Massa, we want to restart with the state before the attack.
-//! We use RocksDB checkpoint system to save the state at regular interval (see the `PERIODS_BETWEEN_BACKUPS` constant in `massa > massa-models > src > config > constants.rs`)
+//! We use RocksDB checkpoint system to save the state at regular interval (see the `snapshot_interval_cycles` setting of the ledger in `config.toml`)
 //! Backups for `Slot {period, thread}` are stored in `massa > massa-node > storage > ledger > rocks_db_backup > backup_[period]_[thread]`
+//! The slot and state hash of each backup are recorded in the `snapshot_manifest.json` file next to them.
 //! Backups are hard links of the rocks_db, so the overhead of storing them should be minimal.
 //! To recover from a backup, simply replace the contents of the rocks_db folder by the contents of the target backup folder.
 
@@ -91,12 +96,14 @@ mod config;
 mod error;
 mod final_state;
 mod mapping_grpc;
+mod snapshot;
 mod state_changes;
 
 pub use config::FinalStateConfig;
 pub use error::FinalStateError;
 pub use final_state::FinalState;
 use num as _;
+pub use snapshot::{StateSnapshotInfo, StateSnapshotManager, SNAPSHOT_MANIFEST_FILE};
 pub use state_changes::{StateChanges, StateChangesDeserializer, StateChangesSerializer};
 
 #[cfg(test)]
@@ -0,0 +1,227 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines the manager of the periodic snapshots of the final state.
+//!
+//! Snapshots are backups of the state database (see `MassaDBController::backup_db`),
+//! taken every `snapshot_interval_cycles` cycles. The slot and the state hash of each snapshot
+//! are recorded in a JSON manifest stored next to the backups, so that a desynced node
+//! or the bootstrap server can find a verified state without a full re-bootstrap.
+
+use crate::{config::FinalStateConfig, error::FinalStateError};
+use massa_db_exports::ShareableMassaDBController;
+use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
+use massa_models::slot::Slot;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Name of the snapshot manifest file, in the state database directory
+pub const SNAPSHOT_MANIFEST_FILE: &str = "snapshot_manifest.json";
+
+/// Description of a snapshot of the final state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSnapshotInfo {
+    /// slot the state was snapshotted at
+    pub slot: Slot,
+    /// hash of the final state at `slot`
+    pub state_hash: String,
+    /// directory of the database backup
+    pub path: PathBuf,
+}
+
+/// Content of the snapshot manifest, sorted by slot
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StateSnapshotManifest {
+    snapshots: Vec<StateSnapshotInfo>,
+}
+
+/// Takes the periodic snapshots of the final state and keeps their manifest up to date
+pub struct StateSnapshotManager {
+    /// number of cycles between two snapshots, disabled if 0
+    interval_cycles: u64,
+    /// periods per cycle
+    periods_per_cycle: u64,
+    /// path of the manifest file
+    manifest_path: PathBuf,
+    /// snapshots recorded in the manifest, sorted by slot
+    snapshots: Vec<StateSnapshotInfo>,
+}
+
+impl StateSnapshotManager {
+    /// Creates the manager, loading the manifest of the snapshots taken by a previous run if any
+    pub fn new(config: &FinalStateConfig) -> Self {
+        let manifest_path = config
+            .ledger_config
+            .disk_ledger_path
+            .join(SNAPSHOT_MANIFEST_FILE);
+        let snapshots = match load_manifest(&manifest_path) {
+            Ok(manifest) => manifest.snapshots,
+            Err(e) => {
+                warn!("ignoring the state snapshot manifest: {}", e);
+                Vec::new()
+            }
+        };
+        StateSnapshotManager {
+            interval_cycles: config.snapshot_interval_cycles,
+            periods_per_cycle: config.periods_per_cycle,
+            manifest_path,
+            snapshots,
+        }
+    }
+
+    /// Whether a snapshot must be taken once the given slot is final:
+    /// the first slot of every `snapshot_interval_cycles`-th cycle, except the genesis one
+    pub fn is_snapshot_slot(&self, slot: Slot) -> bool {
+        self.interval_cycles != 0
+            && slot.thread == 0
+            && slot.period != 0
+            && slot.period % (self.interval_cycles * self.periods_per_cycle) == 0
+    }
+
+    /// Backups the state database at `slot` and records it in the manifest.
+    ///
+    /// The retention policy of the database backups applies, and the snapshots
+    /// whose backup was removed are pruned from the manifest.
+    pub fn take_snapshot(
+        &mut self,
+        db: &ShareableMassaDBController,
+        slot: Slot,
+        state_hash: HashXof<HASH_XOF_SIZE_BYTES>,
+    ) -> Result<StateSnapshotInfo, FinalStateError> {
+        let (path, backups) = {
+            let db = db.read();
+            let path = db.backup_db(slot);
+            (path, db.list_backups())
+        };
+        let snapshot = StateSnapshotInfo {
+            slot,
+            state_hash: state_hash.to_string(),
+            path,
+        };
+
+        // forget the snapshots removed by the retention policy, or replaced by this one
+        self.snapshots.retain(|s| {
+            s.slot != slot
+                && backups.iter().any(|(backup_slot, backup_path)| {
+                    *backup_slot == s.slot && *backup_path == s.path
+                })
+        });
+        self.snapshots.push(snapshot.clone());
+        self.snapshots.sort_unstable_by_key(|s| s.slot);
+
+        self.write_manifest()?;
+        Ok(snapshot)
+    }
+
+    /// Lists the recorded snapshots, sorted by slot
+    pub fn list_snapshots(&self) -> &[StateSnapshotInfo] {
+        &self.snapshots
+    }
+
+    /// Returns the most recent snapshot, to be served for a snapshot sync
+    pub fn latest_snapshot(&self) -> Option<&StateSnapshotInfo> {
+        self.snapshots.last()
+    }
+
+    /// Writes the manifest to a temporary file, then renames it so that it is never partially written
+    fn write_manifest(&self) -> Result<(), FinalStateError> {
+        let manifest = StateSnapshotManifest {
+            snapshots: self.snapshots.clone(),
+        };
+        let content = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| FinalStateError::SnapshotError(e.to_string()))?;
+        let tmp_path = self.manifest_path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)
+            .and_then(|_| std::fs::rename(&tmp_path, &self.manifest_path))
+            .map_err(|e| {
+                FinalStateError::SnapshotError(format!(
+                    "could not write {}: {}",
+                    self.manifest_path.display(),
+                    e
+                ))
+            })
+    }
+}
+
+fn load_manifest(path: &Path) -> Result<StateSnapshotManifest, FinalStateError> {
+    if !path.exists() {
+        return Ok(StateSnapshotManifest::default());
+    }
+    let content = std::fs::read(path).map_err(|e| {
+        FinalStateError::SnapshotError(format!("could not read {}: {}", path.display(), e))
+    })?;
+    serde_json::from_slice(&content).map_err(|e| {
+        FinalStateError::SnapshotError(format!("could not parse {}: {}", path.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_db_exports::{MassaDBBackupRetention, MassaDBConfig, MassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_ledger_exports::LedgerConfig;
+    use massa_models::config::THREAD_COUNT;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_manifest_and_retention() {
+        let temp_dir = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: MassaDBBackupRetention {
+                max_backups: Some(2),
+                max_age: None,
+            },
+            archival: Default::default(),
+        };
+        let db: ShareableMassaDBController = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
+        ));
+
+        let default_config = FinalStateConfig::default();
+        let config = FinalStateConfig {
+            ledger_config: LedgerConfig {
+                disk_ledger_path: temp_dir.path().to_path_buf(),
+                ..default_config.ledger_config.clone()
+            },
+            periods_per_cycle: 4,
+            snapshot_interval_cycles: 2,
+            ..default_config
+        };
+
+        let mut manager = StateSnapshotManager::new(&config);
+        assert!(manager.latest_snapshot().is_none());
+        assert!(!manager.is_snapshot_slot(Slot::new(0, 0)));
+        assert!(!manager.is_snapshot_slot(Slot::new(4, 0)));
+        assert!(!manager.is_snapshot_slot(Slot::new(8, 1)));
+        assert!(manager.is_snapshot_slot(Slot::new(8, 0)));
+
+        let state_hash = db.read().get_xof_db_hash();
+        for period in [8, 16, 24] {
+            manager
+                .take_snapshot(&db, Slot::new(period, 0), state_hash)
+                .expect("snapshot failed");
+        }
+
+        // the oldest backup was removed by the retention policy, and pruned from the manifest
+        let slots: Vec<Slot> = manager.list_snapshots().iter().map(|s| s.slot).collect();
+        assert_eq!(slots, vec![Slot::new(16, 0), Slot::new(24, 0)]);
+        assert_eq!(
+            manager.latest_snapshot().map(|s| s.state_hash.clone()),
+            Some(state_hash.to_string())
+        );
+
+        // the manifest is loaded back on restart
+        let reloaded = StateSnapshotManager::new(&config);
+        assert_eq!(reloaded.list_snapshots(), manager.list_snapshots());
+    }
+}
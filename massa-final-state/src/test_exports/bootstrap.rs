@@ -10,7 +10,7 @@ use massa_ledger_exports::LedgerController;
 use massa_pos_exports::PoSFinalState;
 use massa_versioning::versioning::MipStore;
 
-use crate::{FinalState, FinalStateConfig};
+use crate::{FinalState, FinalStateConfig, StateSnapshotManager};
 
 /// Create a `FinalState` from pre-set values
 pub fn create_final_state(
@@ -24,6 +24,7 @@ pub fn create_final_state(
     db: ShareableMassaDBController,
 ) -> FinalState {
     FinalState {
+        snapshots: StateSnapshotManager::new(&config),
         config,
         ledger,
         async_pool,
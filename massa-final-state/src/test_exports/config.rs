@@ -6,7 +6,7 @@ use std::path::PathBuf;
 
 use num::rational::Ratio;
 
-use crate::{FinalState, FinalStateConfig};
+use crate::{FinalState, FinalStateConfig, StateSnapshotManager};
 use massa_async_pool::{AsyncPool, AsyncPoolConfig};
 use massa_db_exports::ShareableMassaDBController;
 use massa_executed_ops::{
@@ -18,7 +18,7 @@ use massa_models::config::{
     DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP,
     KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_DEFERRED_CREDITS_LENGTH,
     MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_DENUNCIATION_CHANGES_LENGTH,
-    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, T0,
+    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, POS_PRODUCTION_STATS_HISTORY_CYCLES, T0,
 };
 use massa_models::config::{PERIODS_PER_CYCLE, POS_SAVED_CYCLES, THREAD_COUNT};
 use massa_pos_exports::{PoSConfig, PoSFinalState};
@@ -32,6 +32,7 @@ impl FinalState {
         db: ShareableMassaDBController,
     ) -> Self {
         FinalState {
+            snapshots: StateSnapshotManager::new(&config),
             ledger: Box::new(FinalLedger::new(config.ledger_config.clone(), db.clone())),
             async_pool: AsyncPool::new(config.async_pool_config.clone(), db.clone()),
             pos_state,
@@ -79,6 +80,7 @@ impl Default for FinalStateConfig {
                 max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
                 max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
                 max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+                production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
                 initial_deferred_credits_path: None,
             },
             final_history_length: 10,
@@ -91,6 +93,7 @@ impl Default for FinalStateConfig {
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             t0: T0,
             genesis_timestamp: *GENESIS_TIMESTAMP,
+            snapshot_interval_cycles: 0,
         }
     }
 }
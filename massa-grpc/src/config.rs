@@ -17,6 +17,14 @@ pub struct GrpcConfig {
     pub accept_http1: bool,
     /// whether to enable CORS. Works only if `accept_http1` is true
     pub enable_cors: bool,
+    /// whether to also serve the API over grpc-web (HTTP/1.1) on `grpc_web_bind`, for browsers
+    pub enable_grpc_web: bool,
+    /// bind for the grpc-web server
+    pub grpc_web_bind: SocketAddr,
+    /// origins allowed to make cross-origin grpc-web requests. Any origin is allowed if empty
+    pub grpc_web_allowed_origins: Vec<String>,
+    /// how long browsers may cache the grpc-web CORS preflight responses
+    pub grpc_web_cors_max_age: Duration,
     /// whether to enable gRPC health service
     pub enable_health: bool,
     /// whether to enable gRPC reflection
@@ -59,6 +67,10 @@ pub struct GrpcConfig {
     pub http2_keepalive_interval: Option<Duration>,
     /// sets a timeout for receiving an acknowledgement of the keepalive ping. Default is 20 seconds
     pub http2_keepalive_timeout: Option<Duration>,
+    /// closes bidirectional streams on which the client sent no message for this duration. Default is no timeout (`None`)
+    pub stream_idle_timeout: Option<Duration>,
+    /// closes bidirectional streams open for longer than this duration. Default is no limit (`None`)
+    pub max_stream_lifetime: Option<Duration>,
     /// sets whether to use an adaptive flow control. Defaults to false
     pub http2_adaptive_window: Option<bool>,
     /// sets the maximum frame size to use for HTTP2. If not set, will default from underlying transport
@@ -0,0 +1,177 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Execution status of the operations of filled blocks.
+//!
+//! Filled blocks are broadcast when they are received, before their slot is executed.
+//! [`FilledBlockExecutionJoiner`] holds them until the execution output of their slot is
+//! broadcast, then attaches the outcome of each of their operations, so that block explorers
+//! do not have to correlate the `NewFilledBlocks` and `NewSlotExecutionOutputs` streams by
+//! operation id themselves.
+//!
+//! Blocks that are not executed in time (e.g. blocks that end up stale) are released without
+//! outcomes once `timeout` is elapsed, or when more than `max_pending` blocks are waiting.
+//!
+//! `NewFilledBlocks` uses the joiner when the client sets the [`EXECUTION_STATUS_METADATA_KEY`]
+//! request metadata to `true`: each block is then sent once its slot was executed, so that the
+//! matching `NewSlotExecutionOutputs` message was already broadcast. The outcomes themselves
+//! are not sent yet, as `NewFilledBlocksResponse` has no field for them in the public API
+//! protobuf definitions.
+
+use massa_execution_exports::ExecutionOutput;
+use massa_models::block::FilledBlock;
+use massa_models::block_id::BlockId;
+use massa_models::operation::OperationId;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Request metadata key used by clients to ask for the execution status of the operations
+pub const EXECUTION_STATUS_METADATA_KEY: &str = "x-massa-include-execution-status";
+
+/// Outcome of the execution of an operation of a filled block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationExecutionOutcome {
+    /// id of the operation
+    pub operation_id: OperationId,
+    /// true if the operation was executed successfully
+    pub success: bool,
+    /// maximum gas of the operation, taken from the block gas budget.
+    /// The gas actually consumed by an operation is not tracked by the execution.
+    pub max_gas: u64,
+    /// number of events emitted by the operation
+    pub events_count: u64,
+}
+
+/// Filled block, with the outcome of its operations if its slot was executed
+#[derive(Debug, Clone)]
+pub struct FilledBlockWithExecutionStatus {
+    /// filled block
+    pub filled_block: FilledBlock,
+    /// outcome of the executed operations of the block, in block order.
+    /// `None` if the block was released before being executed.
+    /// Operations that were not executed (e.g. skipped because of an invalid fee) have no outcome.
+    pub operations_outcome: Option<Vec<OperationExecutionOutcome>>,
+}
+
+/// Holds filled blocks until the execution output of their slot is known
+pub struct FilledBlockExecutionJoiner {
+    /// blocks waiting for their execution output, by arrival time
+    pending: HashMap<BlockId, (Instant, FilledBlock)>,
+    /// arrival order of the pending blocks
+    order: VecDeque<BlockId>,
+    /// maximum number of pending blocks
+    max_pending: usize,
+    /// maximum time a block waits for its execution output
+    timeout: Duration,
+}
+
+impl FilledBlockExecutionJoiner {
+    /// Creates a joiner holding at most `max_pending` blocks during at most `timeout`
+    pub fn new(max_pending: usize, timeout: Duration) -> Self {
+        FilledBlockExecutionJoiner {
+            pending: HashMap::new(),
+            order: VecDeque::new(),
+            max_pending,
+            timeout,
+        }
+    }
+
+    /// Adds a filled block waiting for its execution output.
+    /// Returns the blocks released without outcome to make room for it.
+    pub fn push_block(&mut self, filled_block: FilledBlock) -> Vec<FilledBlockWithExecutionStatus> {
+        let block_id = filled_block.header.id;
+        if self
+            .pending
+            .insert(block_id, (Instant::now(), filled_block))
+            .is_none()
+        {
+            self.order.push_back(block_id);
+        }
+
+        let mut released = Vec::new();
+        while self.pending.len() > self.max_pending {
+            match self.pop_oldest() {
+                Some(filled_block) => released.push(FilledBlockWithExecutionStatus {
+                    filled_block,
+                    operations_outcome: None,
+                }),
+                None => break,
+            }
+        }
+        released
+    }
+
+    /// Joins an execution output with the block of its slot, if that block is pending
+    pub fn push_execution_output(
+        &mut self,
+        output: &ExecutionOutput,
+    ) -> Option<FilledBlockWithExecutionStatus> {
+        let block_id = output.block_info.as_ref()?.block_id;
+        let (_, filled_block) = self.pending.remove(&block_id)?;
+        self.order.retain(|id| id != &block_id);
+
+        let operations_outcome = filled_block
+            .operations
+            .iter()
+            .filter_map(|(operation_id, operation)| {
+                let (success, _) = output
+                    .state_changes
+                    .executed_ops_changes
+                    .get(operation_id)?;
+                Some(OperationExecutionOutcome {
+                    operation_id: *operation_id,
+                    success: *success,
+                    max_gas: operation
+                        .as_ref()
+                        .map(|op| op.get_gas_usage())
+                        .unwrap_or_default(),
+                    events_count: output
+                        .events
+                        .0
+                        .iter()
+                        .filter(|event| event.context.origin_operation_id == Some(*operation_id))
+                        .count() as u64,
+                })
+            })
+            .collect();
+
+        Some(FilledBlockWithExecutionStatus {
+            filled_block,
+            operations_outcome: Some(operations_outcome),
+        })
+    }
+
+    /// Releases, without outcome, the blocks that waited for more than `timeout` at `now`
+    pub fn take_expired(&mut self, now: Instant) -> Vec<FilledBlockWithExecutionStatus> {
+        let mut released = Vec::new();
+        while let Some(block_id) = self.order.front() {
+            match self.pending.get(block_id) {
+                Some((arrival, _)) if now.saturating_duration_since(*arrival) < self.timeout => {
+                    break
+                }
+                _ => {}
+            }
+            if let Some(filled_block) = self.pop_oldest() {
+                released.push(FilledBlockWithExecutionStatus {
+                    filled_block,
+                    operations_outcome: None,
+                });
+            }
+        }
+        released
+    }
+
+    /// Number of blocks waiting for their execution output
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Removes the oldest pending block
+    fn pop_oldest(&mut self) -> Option<FilledBlock> {
+        while let Some(block_id) = self.order.pop_front() {
+            if let Some((_, filled_block)) = self.pending.remove(&block_id) {
+                return Some(filled_block);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Filter expressions of the streaming methods.
+//!
+//! The filters of a request are ANDed: a message is sent only if it matches every filter.
+//! Each filter holds a list of values and matches if any of them does (OR).
+//! For instance, `[OperationTypes([CallSC]), Addresses([X])]` selects the `CallSC` operations
+//! created by `X`, `[Addresses([X, Y])]` selects the operations created by `X` or `Y`,
+//! and `[Addresses([X]), Addresses([Y])]` selects nothing as an operation has a single creator.
+//! A request without filters selects everything.
+//!
+//! This is a breaking change: repeated filters of the same type used to be merged (ORed)
+//! and are now intersected (ANDed). Clients that split a list of values over several filters
+//! of the same type must send them in a single filter instead.
+//!
+//! The `max_*_per_request` limits of the configuration apply to the total number of values
+//! of a type over all the filters of a request.
+
+use crate::error::GrpcError;
+
+/// Conjunction (AND) of groups of predicates, each group being a disjunction (OR)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FilterExpression<P> {
+    /// groups of predicates, with the name of the filter type they come from
+    groups: Vec<(&'static str, Vec<P>)>,
+}
+
+impl<P> Default for FilterExpression<P> {
+    fn default() -> Self {
+        FilterExpression { groups: Vec::new() }
+    }
+}
+
+impl<P> FilterExpression<P> {
+    /// Checks that adding `count` values of the filter type `name` keeps the total number of values
+    /// of that type in the request within `max_values`.
+    /// Called before parsing the values, so that oversized requests are rejected early.
+    pub(crate) fn check_value_count(
+        &self,
+        name: &'static str,
+        count: usize,
+        max_values: u32,
+    ) -> Result<(), GrpcError> {
+        let total = self
+            .groups
+            .iter()
+            .filter(|(group_name, _)| *group_name == name)
+            .fold(count, |acc, (_, group)| acc.saturating_add(group.len()));
+        if total > max_values as usize {
+            return Err(GrpcError::InvalidArgument(format!(
+                "too many {} received. Only a maximum of {} {} are accepted per request",
+                name, max_values, name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Adds a group of predicates of the filter type `name`, at least one of which must match.
+    /// An empty group is rejected as it could never match.
+    pub(crate) fn and_any_of(
+        &mut self,
+        predicates: Vec<P>,
+        name: &'static str,
+    ) -> Result<(), GrpcError> {
+        if predicates.is_empty() {
+            return Err(GrpcError::InvalidArgument(format!(
+                "empty {} filter: at least one value is required",
+                name
+            )));
+        }
+        self.groups.push((name, predicates));
+        Ok(())
+    }
+
+    /// Returns true if every group has a predicate for which `matches_predicate` returns true
+    pub(crate) fn matches<F>(&self, mut matches_predicate: F) -> bool
+    where
+        F: FnMut(&P) -> bool,
+    {
+        self.groups
+            .iter()
+            .all(|(_, group)| group.iter().any(&mut matches_predicate))
+    }
+}
@@ -8,8 +8,11 @@
 //! ## **Structure**
 //!
 //! * `api.rs`: implements gRPC service methods without streams.
+//! * `filter.rs`: combines the filters of the streaming methods (ANDed filters of ORed values).
 //! * `handler.rs`: defines the logic for handling incoming gRPC requests.
+//! * `metrics.rs`: records per-method Prometheus metrics of the gRPC services.
 //! * `server`: initializes the gRPC service and serve It.
+//! * `schema.rs`: versions the slot execution output wire schema and downgrades outputs for older clients.
 //! * `stream/`: contains the gRPC streaming methods implementations files.
 
 #![warn(missing_docs)]
@@ -27,12 +30,20 @@ use tonic_web as _;
 pub mod config;
 /// models error
 pub mod error;
+/// execution status of the operations of filled blocks
+pub mod execution_status;
+/// filter expressions of the streaming methods
+pub(crate) mod filter;
 /// gRPC API implementation
 pub mod handler;
+/// per-method gRPC metrics
+pub mod metrics;
 /// business code for node management methods
 pub mod private;
 /// business code for non stream methods
 pub mod public;
+/// slot execution output schema versioning
+pub mod schema;
 /// gRPC service initialization and serve
 pub mod server;
 /// business code for stream methods
@@ -0,0 +1,181 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Per-method Prometheus metrics of the gRPC services.
+//!
+//! [`GrpcMetricsService`] wraps a gRPC service and records, for each RPC method:
+//! * the number of requests and the time spent by the handler before responding
+//! * the number of responses per status code
+//! * the number of open calls, which is how long-lived server streams show up
+//!
+//! Calls to unknown methods are all recorded under the `unknown` method to bound the
+//! number of label values a client can create.
+
+use futures_util::future::BoxFuture;
+use hyper::body::{Bytes, HttpBody, SizeHint};
+use hyper::header::HeaderMap;
+use hyper::service::Service;
+use hyper::{Body, Request, Response};
+use massa_metrics::MassaMetrics;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tonic::body::BoxBody;
+use tonic::transport::NamedService;
+use tonic::{Code, Status};
+
+/// method label used for calls to methods the service does not implement
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// code label used for calls that ended without a status (e.g. cancelled by the client)
+const CANCELLED_CODE: &str = "Cancelled";
+
+/// gRPC service wrapper recording per-method metrics
+#[derive(Clone)]
+pub struct GrpcMetricsService<S> {
+    inner: S,
+    metrics: MassaMetrics,
+}
+
+impl<S> GrpcMetricsService<S> {
+    /// Wraps `inner`, recording its metrics in `metrics`
+    pub fn new(inner: S, metrics: MassaMetrics) -> Self {
+        GrpcMetricsService { inner, metrics }
+    }
+}
+
+impl<S: NamedService> NamedService for GrpcMetricsService<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S> Service<Request<Body>> for GrpcMetricsService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        // the router only forwards `/{service}/{method}` paths to this service
+        let method = req
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let metrics = self.metrics.clone();
+        let start = Instant::now();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+            let service = S::NAME;
+            let code = status_code(response.headers());
+            let method = if code == Some(Code::Unimplemented) {
+                UNKNOWN_METHOD.to_string()
+            } else {
+                method
+            };
+            metrics.inc_grpc_requests(service, &method, start.elapsed().as_secs_f64());
+
+            // trailers-only response: the call is already over
+            if let Some(code) = code {
+                metrics.inc_grpc_responses(service, &method, &format!("{:?}", code));
+                return Ok(response);
+            }
+
+            metrics.inc_grpc_active_streams(service, &method);
+            let guard = CallGuard {
+                metrics,
+                service,
+                method,
+                code: None,
+            };
+            Ok(response.map(|body| tonic::body::boxed(MetricsBody { inner: body, guard })))
+        })
+    }
+}
+
+/// Reads the gRPC status code from response headers or trailers
+fn status_code(headers: &HeaderMap) -> Option<Code> {
+    headers
+        .get("grpc-status")
+        .map(|value| Code::from_bytes(value.as_bytes()))
+}
+
+/// Records the end of a call when the response body is dropped
+struct CallGuard {
+    metrics: MassaMetrics,
+    service: &'static str,
+    method: String,
+    code: Option<Code>,
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        let code = self
+            .code
+            .map(|code| format!("{:?}", code))
+            .unwrap_or_else(|| CANCELLED_CODE.to_string());
+        self.metrics
+            .inc_grpc_responses(self.service, &self.method, &code);
+        self.metrics
+            .dec_grpc_active_streams(self.service, &self.method);
+    }
+}
+
+/// Response body reading the final status of the call from its trailers
+struct MetricsBody {
+    inner: BoxBody,
+    guard: CallGuard,
+}
+
+impl HttpBody for MetricsBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_data(cx);
+        if let Poll::Ready(Some(Err(status))) = &poll {
+            this.guard.code = Some(status.code());
+        }
+        poll
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_trailers(cx);
+        match &poll {
+            Poll::Ready(Ok(Some(trailers))) => {
+                if let Some(code) = status_code(trailers) {
+                    this.guard.code = Some(code);
+                }
+            }
+            Poll::Ready(Err(status)) => this.guard.code = Some(status.code()),
+            _ => {}
+        }
+        poll
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
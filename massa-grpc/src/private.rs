@@ -284,6 +284,7 @@ pub(crate) fn get_node_status(
     let node_ip = grpc
         .protocol_config
         .routable_ip
+        .or(grpc.protocol_config.routable_ipv6.map(IpAddr::V6))
         .map(|ip| ip.to_string())
         .unwrap_or_default();
 
@@ -129,6 +129,10 @@ pub(crate) fn execute_read_only_call(
                     .map_err(|_| GrpcError::InvalidArgument("invalid amount".to_string()))
             })
             .transpose()?,
+        // historical slots, state overrides and traces can't be set until `ReadOnlyExecutionCall` carries them in massa-proto-rs
+        historical_slot: None,
+        state_overrides: Default::default(),
+        trace: false,
     };
 
     let output = grpc
@@ -0,0 +1,90 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Versioning of the slot execution output wire schema.
+//!
+//! Clients announce the schema version they were built against with the
+//! `x-massa-schema-version` request metadata. Outputs are always converted with the latest
+//! schema, then downgraded here so that indexers built against an older schema keep
+//! receiving the data they expect for at least one release cycle after fields are added.
+//! Clients that do not announce a version receive the latest schema.
+//!
+//! When a field is added to `SlotExecutionOutput` or its state changes, add a variant to
+//! [`SlotExecutionOutputSchemaVersion`], update `CURRENT` and strip the field for older
+//! versions in [`downgrade_slot_execution_output`]. Drop the oldest variant once it is no
+//! longer supported.
+
+use crate::error::GrpcError;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use tonic::metadata::MetadataMap;
+
+/// Request metadata key used by clients to announce the schema version they understand
+pub const SCHEMA_VERSION_METADATA_KEY: &str = "x-massa-schema-version";
+
+/// Versions of the slot execution output wire schema, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SlotExecutionOutputSchemaVersion {
+    /// state changes without the execution trail hash change
+    V0 = 0,
+    /// adds `StateChanges.execution_trail_hash_change`
+    V1 = 1,
+}
+
+impl SlotExecutionOutputSchemaVersion {
+    /// Latest schema version, produced by the conversion layer
+    pub const CURRENT: SlotExecutionOutputSchemaVersion = SlotExecutionOutputSchemaVersion::V1;
+    /// Oldest schema version that can still be served
+    pub const MIN_SUPPORTED: SlotExecutionOutputSchemaVersion =
+        SlotExecutionOutputSchemaVersion::V0;
+
+    /// Reads the schema version announced in the request metadata, defaulting to the latest one
+    pub fn from_metadata(metadata: &MetadataMap) -> Result<Self, GrpcError> {
+        let Some(value) = metadata.get(SCHEMA_VERSION_METADATA_KEY) else {
+            return Ok(Self::CURRENT);
+        };
+        let version = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().parse::<u32>().ok())
+            .ok_or_else(|| {
+                GrpcError::InvalidArgument(format!(
+                    "invalid {} metadata: expected an integer",
+                    SCHEMA_VERSION_METADATA_KEY
+                ))
+            })?;
+        Self::try_from(version)
+    }
+}
+
+impl TryFrom<u32> for SlotExecutionOutputSchemaVersion {
+    type Error = GrpcError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SlotExecutionOutputSchemaVersion::V0),
+            1 => Ok(SlotExecutionOutputSchemaVersion::V1),
+            _ => Err(GrpcError::InvalidArgument(format!(
+                "unsupported slot execution output schema version {}: supported versions are {} to {}",
+                value,
+                SlotExecutionOutputSchemaVersion::MIN_SUPPORTED as u32,
+                SlotExecutionOutputSchemaVersion::CURRENT as u32
+            ))),
+        }
+    }
+}
+
+/// Converts a slot execution output built with the latest schema to the given schema version
+pub fn downgrade_slot_execution_output(
+    mut output: grpc_model::SlotExecutionOutput,
+    version: SlotExecutionOutputSchemaVersion,
+) -> grpc_model::SlotExecutionOutput {
+    if version < SlotExecutionOutputSchemaVersion::V1 {
+        if let Some(state_changes) = output
+            .execution_output
+            .as_mut()
+            .and_then(|execution_output| execution_output.state_changes.as_mut())
+        {
+            state_changes.execution_trail_hash_change = None;
+        }
+    }
+    output
+}
@@ -11,11 +11,14 @@ use std::sync::{Arc, Condvar, Mutex};
 
 use crate::config::{GrpcConfig, ServiceName};
 use crate::error::GrpcError;
+use crate::metrics::GrpcMetricsService;
 use futures_util::FutureExt;
+use hyper::header::{HeaderName, HeaderValue};
 use hyper::service::Service;
 use hyper::{Body, Method, Request, Response};
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
 use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_metrics::MassaMetrics;
 use massa_pool_exports::{PoolBroadcasts, PoolController};
 use massa_pos_exports::SelectorController;
 use massa_proto_rs::massa::api::v1::{
@@ -35,7 +38,7 @@ use tonic::transport::NamedService;
 use tonic::transport::{Certificate, Identity, ServerTlsConfig};
 use tonic_health::server::HealthReporter;
 use tonic_web::GrpcWebLayer;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tracing::log::{info, warn};
 
 /// gRPC PRIVATE API content
@@ -66,11 +69,14 @@ pub struct MassaPrivateGrpc {
     pub version: massa_models::version::Version,
     /// white/black list of bootstrap
     pub bs_white_black_list: Option<SharedWhiteBlackList<'static>>,
+    /// node metrics, used to record per-method gRPC metrics
+    pub massa_metrics: MassaMetrics,
 }
 
 impl MassaPrivateGrpc {
     /// Start the gRPC PRIVATE API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let massa_metrics = self.massa_metrics.clone();
         let mut service = PrivateServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -87,7 +93,7 @@ impl MassaPrivateGrpc {
             };
         }
 
-        serve(service, config).await
+        serve(GrpcMetricsService::new(service, massa_metrics), config).await
     }
 }
 
@@ -122,11 +128,14 @@ pub struct MassaPublicGrpc {
     pub version: massa_models::version::Version,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// node metrics, used to record per-method gRPC metrics
+    pub massa_metrics: MassaMetrics,
 }
 
 impl MassaPublicGrpc {
     /// Start the gRPC PUBLIC API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let massa_metrics = self.massa_metrics.clone();
         let mut service = PublicServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -143,18 +152,24 @@ impl MassaPublicGrpc {
             };
         }
 
-        serve(service, config).await
+        serve(GrpcMetricsService::new(service, massa_metrics), config).await
     }
 }
 
 /// Used to be able to stop the gRPC API
 pub struct StopHandle {
     stop_cmd_sender: oneshot::Sender<()>,
+    grpc_web_stop_cmd_sender: Option<oneshot::Sender<()>>,
 }
 
 impl StopHandle {
     /// stop the gRPC API gracefully
     pub fn stop(self) {
+        if let Some(grpc_web_stop_cmd_sender) = self.grpc_web_stop_cmd_sender {
+            if let Err(e) = grpc_web_stop_cmd_sender.send(()) {
+                warn!("gRPC-web API thread panicked: {:?}", e);
+            }
+        }
         if let Err(e) = self.stop_cmd_sender.send(()) {
             warn!("gRPC API thread panicked: {:?}", e);
         } else {
@@ -163,6 +178,42 @@ impl StopHandle {
     }
 }
 
+/// headers that grpc-web clients send and that must be allowed by CORS
+const GRPC_WEB_ALLOW_HEADERS: [&str; 4] =
+    ["x-grpc-web", "content-type", "x-user-agent", "grpc-timeout"];
+
+/// headers that grpc-web clients need to read from responses
+const GRPC_WEB_EXPOSE_HEADERS: [&str; 3] =
+    ["grpc-status", "grpc-message", "grpc-status-details-bin"];
+
+// Build the CORS layer of the grpc-web server
+fn grpc_web_cors_layer(config: &GrpcConfig) -> Result<CorsLayer, GrpcError> {
+    let allow_origin = if config.grpc_web_allowed_origins.is_empty() {
+        AllowOrigin::any()
+    } else {
+        let origins = config
+            .grpc_web_allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin).map_err(|e| {
+                    GrpcError::InvalidArgument(format!(
+                        "invalid grpc-web allowed origin {}: {}",
+                        origin, e
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        AllowOrigin::list(origins)
+    };
+
+    Ok(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::POST, Method::OPTIONS])
+        .allow_headers(GRPC_WEB_ALLOW_HEADERS.map(HeaderName::from_static))
+        .expose_headers(GRPC_WEB_EXPOSE_HEADERS.map(HeaderName::from_static))
+        .max_age(config.grpc_web_cors_max_age))
+}
+
 /// Massa service health check implementation
 async fn massa_service_status(mut reporter: HealthReporter) {
     //TODO add a complete health check based on Massa modules health
@@ -261,6 +312,28 @@ where
         None
     };
 
+    // serve the same services over grpc-web on a dedicated bind, so that browsers can reach them without a proxy
+    let grpc_web_stop_cmd_sender = if config.enable_grpc_web {
+        let (grpc_web_shutdown_send, grpc_web_shutdown_recv) = oneshot::channel::<()>();
+        let router_grpc_web = server_builder
+            .clone()
+            .accept_http1(true)
+            .layer(grpc_web_cors_layer(config)?)
+            .layer(GrpcWebLayer::new())
+            .add_optional_service(reflection_service_opt.clone())
+            .add_optional_service(health_service_opt.clone())
+            .add_service(service.clone());
+
+        tokio::spawn(
+            router_grpc_web
+                .serve_with_shutdown(config.grpc_web_bind, grpc_web_shutdown_recv.map(drop)),
+        );
+        info!("gRPC-web enabled on {}", config.grpc_web_bind);
+        Some(grpc_web_shutdown_send)
+    } else {
+        None
+    };
+
     if config.accept_http1 {
         if config.enable_cors {
             let cors = CorsLayer::new()
@@ -304,6 +377,7 @@ where
 
     Ok(StopHandle {
         stop_cmd_sender: shutdown_send,
+        grpc_web_stop_cmd_sender,
     })
 }
 
@@ -1,5 +1,12 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::config::GrpcConfig;
+use futures_util::StreamExt;
+use tokio::sync::mpsc::Sender;
+use tokio::time::{sleep_until, Duration, Instant};
+use tonic::{Status, Streaming};
+use tracing::log::{debug, error};
+
 /// stream new blocks
 pub mod new_blocks;
 /// stream new endorsements
@@ -18,3 +25,66 @@ pub mod send_endorsements;
 pub mod send_operations;
 /// subscribe tx througput
 pub mod tx_throughput;
+
+/// Idle timeout and maximum lifetime of a bidirectional stream
+pub(crate) struct StreamLimits {
+    /// maximum duration without any message from the client
+    idle_timeout: Option<Duration>,
+    /// instant after which the stream is closed
+    lifetime_deadline: Option<Instant>,
+    /// instant of the last message received from the client
+    last_message: Instant,
+}
+
+impl StreamLimits {
+    /// Starts tracking the limits of a new stream
+    pub(crate) fn new(config: &GrpcConfig) -> Self {
+        let now = Instant::now();
+        StreamLimits {
+            idle_timeout: config.stream_idle_timeout,
+            lifetime_deadline: config.max_stream_lifetime.map(|lifetime| now + lifetime),
+            last_message: now,
+        }
+    }
+
+    // Returns the next limit to be exceeded along with the reason to report to the client
+    fn next_deadline(&self) -> Option<(Instant, &'static str)> {
+        let idle_deadline = self
+            .idle_timeout
+            .map(|timeout| (self.last_message + timeout, "stream idle timeout exceeded"));
+        let lifetime_deadline = self
+            .lifetime_deadline
+            .map(|deadline| (deadline, "maximum stream lifetime exceeded"));
+        match (idle_deadline, lifetime_deadline) {
+            (Some(idle), Some(lifetime)) => Some(std::cmp::min_by_key(idle, lifetime, |d| d.0)),
+            (idle, lifetime) => idle.or(lifetime),
+        }
+    }
+
+    /// Waits for the next message of the client.
+    ///
+    /// Returns `None` when the client closed the stream or when a limit is exceeded.
+    /// In the latter case, the stream is closed with a `DeadlineExceeded` status sent through `tx`.
+    pub(crate) async fn next_message<T, R>(
+        &mut self,
+        in_stream: &mut Streaming<T>,
+        tx: &Sender<Result<R, Status>>,
+    ) -> Option<Result<T, Status>> {
+        let Some((deadline, reason)) = self.next_deadline() else {
+            return in_stream.next().await;
+        };
+        tokio::select! {
+            res = in_stream.next() => {
+                self.last_message = Instant::now();
+                res
+            },
+            _ = sleep_until(deadline) => {
+                debug!("closing gRPC stream: {}", reason);
+                if let Err(e) = tx.send(Err(Status::deadline_exceeded(reason))).await {
+                    error!("failed to send back stream closing status: {}", e);
+                }
+                None
+            }
+        }
+    }
+}
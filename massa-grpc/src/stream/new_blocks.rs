@@ -2,15 +2,15 @@
 
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
+use crate::filter::FilterExpression;
 use crate::server::MassaPublicGrpc;
+use crate::stream::StreamLimits;
 use crate::SlotRange;
-use futures_util::StreamExt;
 use massa_models::address::Address;
 use massa_models::block::SecureShareBlock;
 use massa_models::block_id::BlockId;
 use massa_models::slot::Slot;
 use massa_proto_rs::massa::api::v1::{self as grpc_api};
-use std::collections::HashSet;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use std::str::FromStr;
@@ -27,17 +27,20 @@ pub type NewBlocksStreamType = Pin<
     >,
 >;
 
-// Type declaration for NewBlocksFilter
-#[derive(Clone, Debug)]
-struct Filter {
-    // Block ids to filter
-    block_ids: Option<HashSet<BlockId>>,
-    // Addresses to filter
-    addresses: Option<HashSet<Address>>,
-    // Slot range to filter
-    slot_ranges: Option<HashSet<SlotRange>>,
+// Predicate of a NewBlocks filter
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum BlockPredicate {
+    // Block id
+    BlockId(BlockId),
+    // Block creator address
+    Address(Address),
+    // Slot range of the block
+    SlotRange(SlotRange),
 }
 
+// Type declaration for NewBlocksFilter
+type Filter = FilterExpression<BlockPredicate>;
+
 /// Creates a new stream of new produced and received blocks
 pub(crate) async fn new_blocks(
     grpc: &MassaPublicGrpc,
@@ -52,8 +55,10 @@ pub(crate) async fn new_blocks(
     // Clone grpc to be able to use it in the spawned task
     let grpc_config = grpc.grpc_config.clone();
 
+    let mut limits = StreamLimits::new(&grpc_config);
+
     tokio::spawn(async move {
-        if let Some(Ok(request)) = in_stream.next().await {
+        if let Some(Ok(request)) = limits.next_message(&mut in_stream, &tx).await {
             let mut filters = match get_filter(request, &grpc_config) {
                 Ok(filter) => filter,
                 Err(err) => {
@@ -73,7 +78,7 @@ pub(crate) async fn new_blocks(
                         match event {
                             Ok(massa_block) => {
                                 // Check if the block should be sent
-                                if !should_send(&massa_block, &filters, &grpc_config) {
+                                if !should_send(&massa_block, &filters) {
                                     continue;
                                 }
                                 // Send the new block through the channel
@@ -87,7 +92,7 @@ pub(crate) async fn new_blocks(
                             Err(e) => error!("error on receive new block : {}", e)
                         }
                     },
-                    res = in_stream.next() => {
+                    res = limits.next_message(&mut in_stream, &tx) => {
                         match res {
                             Some(res) => {
                                 match res {
@@ -154,110 +159,103 @@ fn get_filter(
         )));
     }
 
-    let mut block_ids_filter: Option<HashSet<BlockId>> = None;
-    let mut addresses_filter: Option<HashSet<Address>> = None;
-    let mut slot_ranges_filter: Option<HashSet<SlotRange>> = None;
+    let mut filters = Filter::default();
+    // intersection of the slot ranges of all the filters
+    let mut start_slot = Slot::new(0, 0); // inclusive
+    let mut end_slot = Slot::new(u64::MAX, grpc_config.thread_count - 1); // exclusive
 
-    // Get params filter from the request.
+    // Each filter of the request is a group of ORed values, groups are ANDed
     for query in request.filters.into_iter() {
         if let Some(filter) = query.filter {
             match filter {
                 grpc_api::new_blocks_filter::Filter::BlockIds(ids) => {
-                    if ids.block_ids.len() as u32 > grpc_config.max_block_ids_per_request {
-                        return Err(GrpcError::InvalidArgument(format!(
-                            "too many block ids received. Only a maximum of {} block ids are accepted per request",
-                            grpc_config.max_block_ids_per_request
-                        )));
-                    }
-
-                    let block_ids = block_ids_filter.get_or_insert_with(HashSet::new);
-                    for block_id in ids.block_ids {
-                        block_ids.insert(BlockId::from_str(&block_id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid block id: {}", block_id))
-                        })?);
-                    }
+                    filters.check_value_count(
+                        "block ids",
+                        ids.block_ids.len(),
+                        grpc_config.max_block_ids_per_request,
+                    )?;
+                    let block_ids = ids
+                        .block_ids
+                        .into_iter()
+                        .map(|block_id| {
+                            BlockId::from_str(&block_id)
+                                .map(BlockPredicate::BlockId)
+                                .map_err(|_| {
+                                    GrpcError::InvalidArgument(format!(
+                                        "invalid block id: {}",
+                                        block_id
+                                    ))
+                                })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    filters.and_any_of(block_ids, "block ids")?;
                 }
                 grpc_api::new_blocks_filter::Filter::Addresses(addrs) => {
-                    if addrs.addresses.len() as u32 > grpc_config.max_addresses_per_request {
-                        return Err(GrpcError::InvalidArgument(format!(
-                            "too many addresses received. Only a maximum of {} addresses are accepted per request",
-                         grpc_config.max_addresses_per_request
-                        )));
-                    }
-
-                    let addresses = addresses_filter.get_or_insert_with(HashSet::new);
-                    for address in addrs.addresses {
-                        addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
-                        })?);
-                    }
+                    filters.check_value_count(
+                        "addresses",
+                        addrs.addresses.len(),
+                        grpc_config.max_addresses_per_request,
+                    )?;
+                    let addresses = addrs
+                        .addresses
+                        .into_iter()
+                        .map(|address| {
+                            Address::from_str(&address)
+                                .map(BlockPredicate::Address)
+                                .map_err(|_| {
+                                    GrpcError::InvalidArgument(format!(
+                                        "invalid address: {}",
+                                        address
+                                    ))
+                                })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    filters.and_any_of(addresses, "addresses")?;
                 }
                 grpc_api::new_blocks_filter::Filter::SlotRange(s_range) => {
-                    let slot_ranges = slot_ranges_filter.get_or_insert_with(HashSet::new);
-                    if slot_ranges.len() as u32 > grpc_config.max_slot_ranges_per_request {
-                        return Err(GrpcError::InvalidArgument(format!(
-                            "too many slot ranges received. Only a maximum of {} slot ranges are accepted per request",
-                         grpc_config.max_slot_ranges_per_request
-                        )));
-                    }
-
-                    let start_slot = s_range.start_slot.map(|s| s.into());
-                    let end_slot = s_range.end_slot.map(|s| s.into());
+                    filters.check_value_count(
+                        "slot ranges",
+                        1,
+                        grpc_config.max_slot_ranges_per_request,
+                    )?;
 
                     let slot_range = SlotRange {
-                        start_slot,
-                        end_slot,
+                        start_slot: s_range.start_slot.map(|s| s.into()),
+                        end_slot: s_range.end_slot.map(|s| s.into()),
                     };
                     slot_range.check()?;
-                    slot_ranges.insert(slot_range);
+
+                    // slot ranges are ANDed: reject the ones that can never match together
+                    if let Some(s_slot) = slot_range.start_slot {
+                        start_slot = start_slot.max(s_slot);
+                    }
+                    if let Some(e_slot) = slot_range.end_slot {
+                        end_slot = end_slot.min(e_slot);
+                    }
+                    if start_slot >= end_slot {
+                        return Err(GrpcError::InvalidArgument(
+                            "the slot ranges of the filters do not overlap".to_string(),
+                        ));
+                    }
+                    filters
+                        .and_any_of(vec![BlockPredicate::SlotRange(slot_range)], "slot ranges")?;
                 }
             }
         }
     }
 
-    Ok(Filter {
-        block_ids: block_ids_filter,
-        addresses: addresses_filter,
-        slot_ranges: slot_ranges_filter,
-    })
+    Ok(filters)
 }
 
 // This function checks if the block should be sent
-fn should_send(
-    signed_block: &SecureShareBlock,
-    filters: &Filter,
-    grpc_config: &GrpcConfig,
-) -> bool {
-    if let Some(block_ids) = &filters.block_ids {
-        if !block_ids.contains(&signed_block.id) {
-            return false;
-        }
-    }
-
-    if let Some(addresses) = &filters.addresses {
-        if !addresses.contains(&signed_block.content_creator_address) {
-            return false;
-        }
-    }
-
-    if let Some(slot_ranges) = &filters.slot_ranges {
-        let mut start_slot = Slot::new(0, 0); // inclusive
-        let mut end_slot = Slot::new(u64::MAX, grpc_config.thread_count - 1); // exclusive
-
-        for slot_range in slot_ranges {
-            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
-            end_slot = end_slot.min(
-                slot_range
-                    .end_slot
-                    .unwrap_or_else(|| Slot::new(u64::MAX, grpc_config.thread_count - 1)),
-            );
+fn should_send(signed_block: &SecureShareBlock, filters: &Filter) -> bool {
+    let slot = signed_block.content.header.content.slot;
+    filters.matches(|predicate| match predicate {
+        BlockPredicate::BlockId(id) => *id == signed_block.id,
+        BlockPredicate::Address(address) => *address == signed_block.content_creator_address,
+        BlockPredicate::SlotRange(slot_range) => {
+            slot_range.start_slot.map_or(true, |start| slot >= start) // inclusive
+                && slot_range.end_slot.map_or(true, |end| slot < end) // exclusive
         }
-        end_slot = end_slot.max(start_slot);
-        let current_slot = signed_block.content.header.content.slot;
-
-        return current_slot >= start_slot // inclusive
-            && current_slot < end_slot; // exclusive
-    }
-
-    true
+    })
 }
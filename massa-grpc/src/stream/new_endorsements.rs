@@ -3,7 +3,7 @@
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
-use futures_util::StreamExt;
+use crate::stream::StreamLimits;
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
 use massa_models::endorsement::{EndorsementId, SecureShareEndorsement};
@@ -50,8 +50,10 @@ pub(crate) async fn new_endorsements(
     // Clone grpc to be able to use it in the spawned task
     let grpc_config = grpc.grpc_config.clone();
 
+    let mut limits = StreamLimits::new(&grpc_config);
+
     tokio::spawn(async move {
-        if let Some(Ok(request)) = in_stream.next().await {
+        if let Some(Ok(request)) = limits.next_message(&mut in_stream, &tx).await {
             let mut filters = match get_filter(request, &grpc_config) {
                 Ok(filter) => filter,
                 Err(err) => {
@@ -87,7 +89,7 @@ pub(crate) async fn new_endorsements(
                         }
                     },
                     // Receive a new message from the in_stream
-                    res = in_stream.next() => {
+                    res = limits.next_message(&mut in_stream, &tx) => {
                         match res {
                             Some(res) => {
                                 match res {
@@ -2,10 +2,13 @@
 
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
+use crate::execution_status::{FilledBlockExecutionJoiner, EXECUTION_STATUS_METADATA_KEY};
 use crate::server::MassaPublicGrpc;
+use crate::stream::StreamLimits;
 use crate::SlotRange;
-use futures_util::StreamExt;
+use massa_execution_exports::SlotExecutionOutput;
 use massa_models::address::Address;
+use massa_models::block::FilledBlock;
 use massa_models::block_header::BlockHeader;
 use massa_models::block_id::BlockId;
 use massa_models::secure_share::SecureShare;
@@ -15,7 +18,9 @@ use std::collections::HashSet;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use std::str::FromStr;
+use std::time::Instant;
 use tokio::select;
+use tokio::sync::mpsc::Sender;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
 
@@ -44,17 +49,38 @@ pub(crate) async fn new_filled_blocks(
     grpc: &MassaPublicGrpc,
     request: Request<Streaming<grpc_api::NewFilledBlocksRequest>>,
 ) -> Result<NewFilledBlocksStreamType, GrpcError> {
+    // Hold each block until its slot is executed if the client asked for the execution status
+    let include_execution_status = request
+        .metadata()
+        .get(EXECUTION_STATUS_METADATA_KEY)
+        .map_or(false, |value| value == "true");
     // Create a channel to handle communication with the client
     let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
     // Subscribe to the new filled blocks channel
     let mut subscriber = grpc.consensus_broadcasts.filled_block_sender.subscribe();
+    // Subscribe to the slot execution outputs, to know when the slot of a block is executed
+    let mut execution_subscriber = grpc
+        .execution_channels
+        .slot_execution_output_sender
+        .subscribe();
     // Clone grpc to be able to use it in the spawned task
     let grpc_config = grpc.grpc_config.clone();
 
+    let mut limits = StreamLimits::new(&grpc_config);
+
+    // a block whose slot is not executed within a period (e.g. a stale block) is sent anyway
+    let period_duration = grpc_config
+        .t0
+        .saturating_mul(grpc_config.thread_count as u64)
+        .to_duration();
+    let mut joiner = include_execution_status
+        .then(|| FilledBlockExecutionJoiner::new(grpc_config.max_channel_size, period_duration));
+    let mut expiry_interval = tokio::time::interval(grpc_config.t0.to_duration());
+
     tokio::spawn(async move {
-        if let Some(Ok(request)) = in_stream.next().await {
+        if let Some(Ok(request)) = limits.next_message(&mut in_stream, &tx).await {
             let mut filters = match get_filter(request, &grpc_config) {
                 Ok(filter) => filter,
                 Err(err) => {
@@ -77,19 +103,53 @@ pub(crate) async fn new_filled_blocks(
                                 if !should_send(&massa_filled_block.header, &filters, &grpc_config) {
                                     continue;
                                 }
-                                // Send the new filled block through the channel
-                                if let Err(e) = tx.send(Ok(grpc_api::NewFilledBlocksResponse {
-                                        filled_block: Some(massa_filled_block.into())
-                                })).await {
-                                    error!("failed to send new filled block : {}", e);
+                                // Wait for the execution of the block slot if requested
+                                let ready = match joiner.as_mut() {
+                                    Some(joiner) => joiner
+                                        .push_block(massa_filled_block)
+                                        .into_iter()
+                                        .map(|released| released.filled_block)
+                                        .collect(),
+                                    None => vec![massa_filled_block],
+                                };
+                                if !send_filled_blocks(&tx, ready).await {
                                     break;
                                 }
                             },
                             Err(e) => error!("error on receive new filled block : {}", e)
                         }
                     },
+                    // Receive a new slot execution output, only used to join the blocks with their execution
+                    event = execution_subscriber.recv(), if joiner.is_some() => {
+                        match event {
+                            Ok(SlotExecutionOutput::ExecutedSlot(output)) => {
+                                let executed = joiner
+                                    .as_mut()
+                                    .and_then(|joiner| joiner.push_execution_output(&output))
+                                    .map(|executed| executed.filled_block);
+                                if !send_filled_blocks(&tx, executed.into_iter().collect()).await {
+                                    break;
+                                }
+                            },
+                            Ok(SlotExecutionOutput::FinalizedSlot(_)) => {},
+                            Err(e) => error!("error on receive new slot execution output : {}", e)
+                        }
+                    },
+                    // Release the blocks that waited too long for their execution
+                    _ = expiry_interval.tick(), if joiner.is_some() => {
+                        let expired = joiner
+                            .as_mut()
+                            .map(|joiner| joiner.take_expired(Instant::now()))
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|released| released.filled_block)
+                            .collect();
+                        if !send_filled_blocks(&tx, expired).await {
+                            break;
+                        }
+                    },
                 // Receive a new message from the in_stream
-                res = in_stream.next() => {
+                res = limits.next_message(&mut in_stream, &tx) => {
                     match res {
                         Some(res) => {
                             match res {
@@ -141,6 +201,25 @@ pub(crate) async fn new_filled_blocks(
     Ok(Box::pin(out_stream) as NewFilledBlocksStreamType)
 }
 
+// Sends filled blocks to the client, returns false if the client is gone
+async fn send_filled_blocks(
+    tx: &Sender<Result<grpc_api::NewFilledBlocksResponse, tonic::Status>>,
+    filled_blocks: Vec<FilledBlock>,
+) -> bool {
+    for filled_block in filled_blocks {
+        if let Err(e) = tx
+            .send(Ok(grpc_api::NewFilledBlocksResponse {
+                filled_block: Some(filled_block.into()),
+            }))
+            .await
+        {
+            error!("failed to send new filled block : {}", e);
+            return false;
+        }
+    }
+    true
+}
+
 // This function returns a filter from the request
 fn get_filter(
     request: grpc_api::NewFilledBlocksRequest,
@@ -2,13 +2,14 @@
 
 use crate::config::GrpcConfig;
 use crate::error::GrpcError;
+use crate::filter::FilterExpression;
 use crate::server::MassaPublicGrpc;
-use futures_util::StreamExt;
+use crate::stream::StreamLimits;
 use massa_models::address::Address;
+use massa_models::mapping_grpc::to_op_type;
 use massa_models::operation::{OperationId, SecureShareOperation};
 use massa_proto_rs::massa::api::v1::{self as grpc_api, NewOperationsRequest};
 use massa_proto_rs::massa::model::v1 as grpc_model;
-use std::collections::HashSet;
 use std::pin::Pin;
 use std::str::FromStr;
 use tokio::select;
@@ -24,17 +25,23 @@ pub type NewOperationsStreamType = Pin<
     >,
 >;
 
-// Type declaration for NewOperationsFilter
-#[derive(Debug)]
-struct Filter {
-    // Operation ids to filter
-    operation_ids: Option<HashSet<OperationId>>,
-    // Addresses to filter
-    addresses: Option<HashSet<Address>>,
-    // Operation types to filter
-    operation_types: Option<HashSet<i32>>,
+// Predicate of a NewOperations filter
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum OperationPredicate {
+    // Operation id
+    OperationId(OperationId),
+    // Operation creator address
+    Address(Address),
+    // Operation type
+    OperationType(i32),
 }
 
+// Type declaration for NewOperationsFilter
+type Filter = FilterExpression<OperationPredicate>;
+
+// Number of operation types of the gRPC OpType enum, Unspecified excluded
+const MAX_OPERATION_TYPES_PER_REQUEST: u32 = 6;
+
 /// Creates a new stream of new produced and received operations
 pub(crate) async fn new_operations(
     grpc: &MassaPublicGrpc,
@@ -51,8 +58,10 @@ pub(crate) async fn new_operations(
 
     let config = grpc.grpc_config.clone();
 
+    let mut limits = StreamLimits::new(&config);
+
     tokio::spawn(async move {
-        if let Some(Ok(request)) = in_stream.next().await {
+        if let Some(Ok(request)) = limits.next_message(&mut in_stream, &tx).await {
             // Spawn a new task for sending new operations
             let mut filters = match get_filter(request, &config) {
                 Ok(filter) => filter,
@@ -87,7 +96,7 @@ pub(crate) async fn new_operations(
                         }
                     },
                     // Receive a new message from the in_stream
-                    res = in_stream.next() => {
+                    res = limits.next_message(&mut in_stream, &tx) => {
                         match res {
                             Some(res) => {
                                 match res {
@@ -140,83 +149,94 @@ fn get_filter(
         )));
     }
 
-    let mut operation_ids_filter: Option<HashSet<OperationId>> = None;
-    let mut addresses_filter: Option<HashSet<Address>> = None;
-    let mut operation_types_filter: Option<HashSet<i32>> = None;
+    let mut filters = Filter::default();
 
-    // Get params filter from the request.
+    // Each filter of the request is a group of ORed values, groups are ANDed
     for query in request.filters.into_iter() {
         if let Some(filter) = query.filter {
             match filter {
                 grpc_api::new_operations_filter::Filter::OperationIds(ids) => {
-                    if ids.operation_ids.len() as u32 > grpc_config.max_operation_ids_per_request {
-                        return Err(GrpcError::InvalidArgument(format!(
-                            "too many operation ids received. Only a maximum of {} operation ids are accepted per request",
-                         grpc_config.max_block_ids_per_request
-                        )));
-                    }
-                    let operation_ids = operation_ids_filter.get_or_insert_with(HashSet::new);
-                    for id in ids.operation_ids {
-                        operation_ids.insert(OperationId::from_str(&id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid operation id: {}", id))
-                        })?);
-                    }
+                    filters.check_value_count(
+                        "operation ids",
+                        ids.operation_ids.len(),
+                        grpc_config.max_operation_ids_per_request,
+                    )?;
+                    let operation_ids = ids
+                        .operation_ids
+                        .into_iter()
+                        .map(|id| {
+                            OperationId::from_str(&id)
+                                .map(OperationPredicate::OperationId)
+                                .map_err(|_| {
+                                    GrpcError::InvalidArgument(format!(
+                                        "invalid operation id: {}",
+                                        id
+                                    ))
+                                })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    filters.and_any_of(operation_ids, "operation ids")?;
                 }
                 grpc_api::new_operations_filter::Filter::Addresses(addrs) => {
-                    if addrs.addresses.len() as u32 > grpc_config.max_addresses_per_request {
-                        return Err(GrpcError::InvalidArgument(format!(
-                            "too many addresses received. Only a maximum of {} addresses are accepted per request",
-                         grpc_config.max_addresses_per_request
-                        )));
-                    }
-                    let addresses = addresses_filter.get_or_insert_with(HashSet::new);
-                    for address in addrs.addresses {
-                        addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
-                        })?);
-                    }
+                    filters.check_value_count(
+                        "addresses",
+                        addrs.addresses.len(),
+                        grpc_config.max_addresses_per_request,
+                    )?;
+                    let addresses = addrs
+                        .addresses
+                        .into_iter()
+                        .map(|address| {
+                            Address::from_str(&address)
+                                .map(OperationPredicate::Address)
+                                .map_err(|_| {
+                                    GrpcError::InvalidArgument(format!(
+                                        "invalid address: {}",
+                                        address
+                                    ))
+                                })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    filters.and_any_of(addresses, "addresses")?;
                 }
                 grpc_api::new_operations_filter::Filter::OperationTypes(ope_types) => {
                     // The length limited to the number of operation types in the enum
-                    if ope_types.op_types.len() as u64 > 6 {
-                        return Err(GrpcError::InvalidArgument(
-                            "too many operation types received. Only a maximum of 6 operation types are accepted per request".to_string()
-                        ));
-                    }
-                    let operation_types = operation_types_filter.get_or_insert_with(HashSet::new);
-                    operation_types.extend(&ope_types.op_types);
+                    filters.check_value_count(
+                        "operation types",
+                        ope_types.op_types.len(),
+                        MAX_OPERATION_TYPES_PER_REQUEST,
+                    )?;
+                    let operation_types = ope_types
+                        .op_types
+                        .into_iter()
+                        .map(|op_type| match grpc_model::OpType::try_from(op_type) {
+                            Ok(grpc_model::OpType::Unspecified) | Err(_) => {
+                                Err(GrpcError::InvalidArgument(format!(
+                                    "invalid operation type: {}",
+                                    op_type
+                                )))
+                            }
+                            Ok(_) => Ok(OperationPredicate::OperationType(op_type)),
+                        })
+                        .collect::<Result<_, _>>()?;
+                    filters.and_any_of(operation_types, "operation types")?;
                 }
             }
         }
     }
 
-    Ok(Filter {
-        operation_ids: operation_ids_filter,
-        addresses: addresses_filter,
-        operation_types: operation_types_filter,
-    })
+    Ok(filters)
 }
 
 // This function checks if the operation should be sent
 fn should_send(signed_operation: &SecureShareOperation, filters: &Filter) -> bool {
-    if let Some(operation_ids) = &filters.operation_ids {
-        if !operation_ids.contains(&signed_operation.id) {
-            return false;
-        }
-    }
-
-    if let Some(addresses) = &filters.addresses {
-        if !addresses.contains(&signed_operation.content_creator_address) {
-            return false;
-        }
-    }
-
-    if let Some(operation_types) = &filters.operation_types {
-        let op_type = grpc_model::OpType::from(signed_operation.content.op.clone()) as i32;
-        if !operation_types.contains(&op_type) {
-            return false;
+    // roll transfers have no gRPC operation type: they never match a type filter
+    let op_type = to_op_type(&signed_operation.content.op).map(|op_type| op_type as i32);
+    filters.matches(|predicate| match predicate {
+        OperationPredicate::OperationId(id) => *id == signed_operation.id,
+        OperationPredicate::Address(address) => {
+            *address == signed_operation.content_creator_address
         }
-    }
-
-    true
+        OperationPredicate::OperationType(predicate_type) => Some(*predicate_type) == op_type,
+    })
 }
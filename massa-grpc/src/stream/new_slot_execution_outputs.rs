@@ -2,9 +2,10 @@
 
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
+use crate::schema::{downgrade_slot_execution_output, SlotExecutionOutputSchemaVersion};
 use crate::server::MassaPublicGrpc;
+use crate::stream::StreamLimits;
 use crate::SlotRange;
-use futures_util::StreamExt;
 use massa_execution_exports::{ExecutionOutput, SlotExecutionOutput};
 use massa_models::slot::Slot;
 use massa_proto_rs::massa::api::v1::{self as grpc_api, NewSlotExecutionOutputsRequest};
@@ -81,6 +82,8 @@ pub(crate) async fn new_slot_execution_outputs(
     grpc: &MassaPublicGrpc,
     request: Request<Streaming<grpc_api::NewSlotExecutionOutputsRequest>>,
 ) -> Result<NewSlotExecutionOutputsStreamType, GrpcError> {
+    // Get the schema version understood by the client
+    let schema_version = SlotExecutionOutputSchemaVersion::from_metadata(request.metadata())?;
     // Create a channel to handle communication with the client
     let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
     // Get the inner stream from the request
@@ -92,8 +95,10 @@ pub(crate) async fn new_slot_execution_outputs(
         .subscribe();
     let grpc_config = grpc.grpc_config.clone();
 
+    let mut limits = StreamLimits::new(&grpc_config);
+
     tokio::spawn(async move {
-        if let Some(Ok(request)) = in_stream.next().await {
+        if let Some(Ok(request)) = limits.next_message(&mut in_stream, &tx).await {
             let mut filters: Filter = match get_filter(request.clone(), &grpc_config) {
                 Ok(filter) => filter,
                 Err(err) => {
@@ -117,7 +122,7 @@ pub(crate) async fn new_slot_execution_outputs(
                                 if let Some(slot_execution_output) = slot_execution_output {
                                     // Send the new slot execution output through the channel
                                     if let Err(e) = tx.send(Ok(grpc_api::NewSlotExecutionOutputsResponse {
-                                            output: Some(slot_execution_output.into())
+                                            output: Some(downgrade_slot_execution_output(slot_execution_output.into(), schema_version))
                                     })).await {
                                         error!("failed to send new slot execution output : {}", e);
                                         break;
@@ -129,7 +134,7 @@ pub(crate) async fn new_slot_execution_outputs(
                         }
                     },
                     // Receive a new message from the in_stream
-                    res = in_stream.next() => {
+                    res = limits.next_message(&mut in_stream, &tx) => {
                         match res {
                             Some(res) => {
                                 match res {
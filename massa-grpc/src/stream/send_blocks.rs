@@ -2,7 +2,7 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
-use futures_util::StreamExt;
+use crate::stream::StreamLimits;
 use massa_models::block::{BlockDeserializer, BlockDeserializerArgs, SecureShareBlock};
 use massa_models::error::ModelsError;
 use massa_models::secure_share::SecureShareDeserializer;
@@ -41,9 +41,11 @@ pub(crate) async fn send_blocks(
     // Extract the incoming stream of block messages
     let mut in_stream = request.into_inner();
 
+    let mut limits = StreamLimits::new(&config);
+
     // Spawn a task that reads incoming messages and processes the block in each message
     tokio::spawn(async move {
-        while let Some(result) = in_stream.next().await {
+        while let Some(result) = limits.next_message(&mut in_stream, &tx).await {
             match result {
                 Ok(req_content) => {
                     if req_content.block.is_empty() {
@@ -2,7 +2,7 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
-use futures_util::StreamExt;
+use crate::stream::StreamLimits;
 use massa_models::endorsement::{EndorsementDeserializer, SecureShareEndorsement};
 use massa_models::secure_share::SecureShareDeserializer;
 use massa_proto_rs::massa::api::v1 as grpc_api;
@@ -39,9 +39,11 @@ pub(crate) async fn send_endorsements(
     // Extract the incoming stream of endorsements messages
     let mut in_stream = request.into_inner();
 
+    let mut limits = StreamLimits::new(&config);
+
     // Spawn a task that reads incoming messages and processes the endorsements in each message
     tokio::spawn(async move {
-        while let Some(result) = in_stream.next().await {
+        while let Some(result) = limits.next_message(&mut in_stream, &tx).await {
             match result {
                 Ok(req_content) => {
                     // If the incoming message has no endorsements, send an error message back to the client
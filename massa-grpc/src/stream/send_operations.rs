@@ -2,7 +2,7 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
-use futures_util::StreamExt;
+use crate::stream::StreamLimits;
 use massa_models::operation::{OperationDeserializer, OperationType, SecureShareOperation};
 use massa_models::secure_share::SecureShareDeserializer;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
@@ -10,6 +10,7 @@ use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_time::MassaTime;
+use massa_versioning::versioning::MipComponent;
 use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::pin::Pin;
@@ -35,15 +36,18 @@ pub(crate) async fn send_operations(
     let protocol_controller = grpc.protocol_controller.clone();
     let config = grpc.grpc_config.clone();
     let storage = grpc.storage.clone_without_refs();
+    let mip_store = grpc.mip_store.clone();
 
     // Create a channel for sending responses to the client
     let (tx, rx) = tokio::sync::mpsc::channel(config.max_channel_size);
     // Extract the incoming stream of operations messages
     let mut in_stream = request.into_inner();
 
+    let mut limits = StreamLimits::new(&config);
+
     // Spawn a task that reads incoming messages and processes the operations in each message
     tokio::spawn(async move {
-        while let Some(result) = in_stream.next().await {
+        while let Some(result) = limits.next_message(&mut in_stream, &tx).await {
             match result {
                 Ok(req_content) => {
                     // If the incoming message has no operations, send an error message back to the client
@@ -110,6 +114,11 @@ pub(crate) async fn send_operations(
                                                         return Err(GrpcError::InvalidArgument("Gas limit of the operation is higher than the block gas limit. Your operation will never be included in a block.".into()));
                                                     }
                                                 },
+                                                OperationType::RollTransfer { .. } => {
+                                                    if mip_store.get_latest_component_version_at(&MipComponent::RollTransfer, now) == 0 {
+                                                        return Err(GrpcError::InvalidArgument("Roll transfers are not active yet. Your operation will never be included in a block.".into()));
+                                                    }
+                                                },
                                                 _ => {}
                                             };
                                             if let Some(slot) = last_slot {
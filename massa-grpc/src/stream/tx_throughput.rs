@@ -1,7 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::{error::GrpcError, server::MassaPublicGrpc};
-use futures_util::StreamExt;
+use crate::{error::GrpcError, server::MassaPublicGrpc, stream::StreamLimits};
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use std::pin::Pin;
 use std::time::Duration;
@@ -37,6 +36,8 @@ pub(crate) async fn transactions_throughput(
     // Extract the incoming stream of operations messages
     let mut in_stream = request.into_inner();
 
+    let mut limits = StreamLimits::new(&grpc.grpc_config);
+
     // Spawn a new Tokio task to handle the stream processing
     tokio::spawn(async move {
         let mut interval = time::interval(Duration::from_secs(DEFAULT_THROUGHPUT_INTERVAL));
@@ -45,7 +46,7 @@ pub(crate) async fn transactions_throughput(
         loop {
             select! {
                 // Receive a new message from the in_stream
-                res = in_stream.next() => {
+                res = limits.next_message(&mut in_stream, &tx) => {
                     match res {
                         Some(Ok(req)) => {
                             // Update the interval timer based on the request (or use the default)
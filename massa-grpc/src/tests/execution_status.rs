@@ -0,0 +1,129 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::execution_status::{FilledBlockExecutionJoiner, OperationExecutionOutcome};
+use massa_execution_exports::{ExecutedBlockInfo, ExecutionOutput};
+use massa_models::{
+    block::FilledBlock,
+    output_event::{EventExecutionContext, SCOutputEvent},
+    slot::Slot,
+};
+use massa_protocol_exports::test_exports::tools::{
+    create_block_with_operations, create_operation_with_expire_period,
+};
+use massa_signature::KeyPair;
+use std::time::{Duration, Instant};
+
+#[test]
+fn join_filled_block_with_execution_output() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let slot = Slot::new(1, 0);
+    let op_ok = create_operation_with_expire_period(&keypair, 10);
+    let op_failed = create_operation_with_expire_period(&keypair, 11);
+    let op_skipped = create_operation_with_expire_period(&keypair, 12);
+    let block = create_block_with_operations(
+        &keypair,
+        slot,
+        vec![op_ok.clone(), op_failed.clone(), op_skipped.clone()],
+    );
+    let filled_block = FilledBlock {
+        header: block.content.header.clone(),
+        operations: vec![
+            (op_ok.id, Some(op_ok.clone())),
+            (op_failed.id, Some(op_failed.clone())),
+            (op_skipped.id, Some(op_skipped.clone())),
+        ],
+    };
+
+    let mut joiner = FilledBlockExecutionJoiner::new(10, Duration::from_secs(60));
+    assert!(joiner.push_block(filled_block).is_empty());
+    assert_eq!(joiner.pending_count(), 1);
+
+    let mut output = ExecutionOutput {
+        slot,
+        block_info: Some(ExecutedBlockInfo {
+            block_id: block.id,
+            current_version: 0,
+            announced_version: None,
+        }),
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+        transfers: Default::default(),
+        operation_changes: Default::default(),
+        operation_failures: Default::default(),
+    };
+    output
+        .state_changes
+        .executed_ops_changes
+        .insert(op_ok.id, (true, slot));
+    output
+        .state_changes
+        .executed_ops_changes
+        .insert(op_failed.id, (false, slot));
+    for _ in 0..2 {
+        output.events.push(SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: Some(block.id),
+                read_only: false,
+                index_in_slot: 0,
+                call_stack: Default::default(),
+                origin_operation_id: Some(op_ok.id),
+                is_final: false,
+                is_error: false,
+            },
+            data: "event".to_string(),
+        });
+    }
+
+    let joined = joiner.push_execution_output(&output).unwrap();
+    assert_eq!(joined.filled_block.header.id, block.id);
+    assert_eq!(
+        joined.operations_outcome.unwrap(),
+        vec![
+            OperationExecutionOutcome {
+                operation_id: op_ok.id,
+                success: true,
+                max_gas: op_ok.get_gas_usage(),
+                events_count: 2,
+            },
+            OperationExecutionOutcome {
+                operation_id: op_failed.id,
+                success: false,
+                max_gas: op_failed.get_gas_usage(),
+                events_count: 0,
+            },
+        ]
+    );
+    assert_eq!(joiner.pending_count(), 0);
+    // the block is only joined once
+    assert!(joiner.push_execution_output(&output).is_none());
+}
+
+#[test]
+fn release_unexecuted_filled_blocks() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let filled_block = |period| {
+        let block = create_block_with_operations(&keypair, Slot::new(period, 0), vec![]);
+        FilledBlock {
+            header: block.content.header,
+            operations: vec![],
+        }
+    };
+    let first = filled_block(1);
+    let second = filled_block(2);
+
+    // too many pending blocks: the oldest one is released without outcome
+    let mut joiner = FilledBlockExecutionJoiner::new(1, Duration::from_secs(60));
+    assert!(joiner.push_block(first.clone()).is_empty());
+    let released = joiner.push_block(second.clone());
+    assert_eq!(released.len(), 1);
+    assert_eq!(released[0].filled_block.header.id, first.header.id);
+    assert!(released[0].operations_outcome.is_none());
+    assert!(joiner.take_expired(Instant::now()).is_empty());
+
+    // timeout elapsed: the pending block is released without outcome
+    let released = joiner.take_expired(Instant::now() + Duration::from_secs(61));
+    assert_eq!(released.len(), 1);
+    assert_eq!(released[0].filled_block.header.id, second.header.id);
+    assert_eq!(joiner.pending_count(), 0);
+}
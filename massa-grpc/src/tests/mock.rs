@@ -5,6 +5,7 @@ use crate::config::{GrpcConfig, ServiceName};
 use crate::server::MassaPublicGrpc;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
 use massa_execution_exports::{ExecutionChannels, MockExecutionController};
+use massa_metrics::MassaMetrics;
 use massa_models::{
     config::{
         ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
@@ -48,6 +49,10 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         enabled: true,
         accept_http1: true,
         enable_cors: true,
+        enable_grpc_web: false,
+        grpc_web_bind: "[::]:0".parse().unwrap(),
+        grpc_web_allowed_origins: vec![],
+        grpc_web_cors_max_age: Default::default(),
         enable_health: true,
         enable_reflection: true,
         enable_tls: false,
@@ -72,6 +77,8 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         tcp_nodelay: false,
         http2_keepalive_interval: None,
         http2_keepalive_timeout: None,
+        stream_idle_timeout: None,
+        max_stream_lifetime: None,
         http2_adaptive_window: None,
         max_frame_size: None,
         thread_count: THREAD_COUNT,
@@ -121,15 +128,19 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
             block_sender: tokio::sync::broadcast::channel(100).0,
             block_header_sender: tokio::sync::broadcast::channel(100).0,
             filled_block_sender: tokio::sync::broadcast::channel(100).0,
+            finalized_block_sender: tokio::sync::broadcast::channel(100).0,
+            blockclique_reorg_sender: tokio::sync::broadcast::channel(100).0,
         },
         consensus_controller: consensus_ctrl,
         execution_controller: execution_ctrl,
         execution_channels: ExecutionChannels {
             slot_execution_output_sender,
+            cycle_completion_sender: tokio::sync::broadcast::channel(100).0,
         },
         pool_broadcasts: PoolBroadcasts {
             endorsement_sender,
             operation_sender,
+            operation_lifecycle_sender: tokio::sync::broadcast::channel(100).0,
         },
         pool_controller: pool_ctrl,
         protocol_controller: protocol_ctrl,
@@ -142,5 +153,12 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         keypair_factory: KeyPairFactory {
             mip_store: mip_store.clone(),
         },
+        massa_metrics: MassaMetrics::new(
+            false,
+            "0.0.0.0:31248".parse().unwrap(),
+            THREAD_COUNT,
+            std::time::Duration::from_secs(5),
+        )
+        .0,
     }
 }
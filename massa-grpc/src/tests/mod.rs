@@ -3,6 +3,9 @@
 #[cfg(test)]
 pub mod mock;
 
+#[cfg(test)]
+mod execution_status;
+
 #[cfg(test)]
 mod public;
 #[cfg(test)]
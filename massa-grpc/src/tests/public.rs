@@ -351,9 +351,13 @@ async fn execute_read_only_call() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: EventStore::default(),
+                    transfers: Default::default(),
+                    operation_changes: Default::default(),
+                    operation_failures: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                trace: None,
             })
         });
 
@@ -1,8 +1,13 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::execution_status::EXECUTION_STATUS_METADATA_KEY;
+use crate::filter::FilterExpression;
+use crate::schema::SCHEMA_VERSION_METADATA_KEY;
 use crate::tests::mock::grpc_public_service;
 use massa_consensus_exports::MockConsensusController;
-use massa_execution_exports::{ExecutionOutput, MockExecutionController, SlotExecutionOutput};
+use massa_execution_exports::{
+    ExecutedBlockInfo, ExecutionOutput, MockExecutionController, SlotExecutionOutput,
+};
 use massa_models::{
     address::Address, block::FilledBlock, secure_share::SecureShareSerializer, slot::Slot,
     stats::ExecutionStats,
@@ -375,6 +380,75 @@ async fn new_operations() {
         keypair.get_public_key().to_string()
     );
 
+    let addresses_filter =
+        |addresses: Vec<String>| massa_proto_rs::massa::api::v1::NewOperationsFilter {
+            filter: Some(
+                massa_proto_rs::massa::api::v1::new_operations_filter::Filter::Addresses(
+                    massa_proto_rs::massa::model::v1::Addresses { addresses },
+                ),
+            ),
+        };
+    let unknown_address = "AU12BTfZ7k1z6PsLEUZeHYNirz6WJ3NdrWto9H4TkVpkV9xE2TJg2".to_string();
+
+    // filters are ANDed: the operation can't be created by both addresses
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![
+                addresses_filter(vec![unknown_address.clone()]),
+                addresses_filter(vec![address.to_string()]),
+            ],
+        })
+        .await
+        .unwrap();
+    op_send_signal.send(()).await.unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(2), resp_stream.next()).await;
+    assert!(result.is_err());
+
+    // the values of a filter are ORed
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![
+                addresses_filter(vec![unknown_address, address.to_string()]),
+                massa_proto_rs::massa::api::v1::NewOperationsFilter {
+                    filter: Some(
+                        massa_proto_rs::massa::api::v1::new_operations_filter::Filter::OperationTypes(
+                            massa_proto_rs::massa::model::v1::OpTypes {
+                                op_types: vec![
+                                    massa_proto_rs::massa::model::v1::OpType::Transaction as i32,
+                                ],
+                            },
+                        ),
+                    ),
+                },
+            ],
+        })
+        .await
+        .unwrap();
+    op_send_signal.send(()).await.unwrap();
+    let received = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        received.signed_operation.unwrap().content_creator_pub_key,
+        keypair.get_public_key().to_string()
+    );
+
+    // a filter without values can never match
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![addresses_filter(vec![])],
+        })
+        .await
+        .unwrap();
+    let status = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
     stop_handle.stop();
 }
 
@@ -1067,6 +1141,9 @@ async fn new_slot_execution_outputs() {
         block_info: None,
         state_changes: massa_final_state::StateChanges::default(),
         events: Default::default(),
+        transfers: Default::default(),
+        operation_changes: Default::default(),
+        operation_failures: Default::default(),
     };
 
     let (tx_request, rx) = tokio::sync::mpsc::channel(10);
@@ -1223,6 +1300,135 @@ async fn new_slot_execution_outputs() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn new_slot_execution_outputs_schema_downgrade() {
+    let addr: SocketAddr = "[::]:4026".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let (slot_tx, _slot_rx) = tokio::sync::broadcast::channel(10);
+
+    public_server
+        .execution_channels
+        .slot_execution_output_sender = slot_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let exec_output = ExecutionOutput {
+        slot: Slot::new(1, 5),
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+        transfers: Default::default(),
+        operation_changes: Default::default(),
+        operation_failures: Default::default(),
+    };
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').into_iter().last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // unsupported schema version
+    let (_tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let mut request = tonic::Request::new(tokio_stream::wrappers::ReceiverStream::new(rx));
+    request
+        .metadata_mut()
+        .insert(SCHEMA_VERSION_METADATA_KEY, "42".parse().unwrap());
+    let result = public_client.new_slot_execution_outputs(request).await;
+    assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    // schema version 0 does not know about the execution trail hash change
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let mut request = tonic::Request::new(tokio_stream::wrappers::ReceiverStream::new(rx));
+    request
+        .metadata_mut()
+        .insert(SCHEMA_VERSION_METADATA_KEY, "0".parse().unwrap());
+    let mut resp_stream = public_client
+        .new_slot_execution_outputs(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    tx_request
+        .send(NewSlotExecutionOutputsRequest { filters: vec![] })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    slot_tx
+        .send(SlotExecutionOutput::ExecutedSlot(exec_output))
+        .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let state_changes = result
+        .output
+        .unwrap()
+        .execution_output
+        .unwrap()
+        .state_changes
+        .unwrap();
+    assert!(state_changes.execution_trail_hash_change.is_none());
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_operations_stream_limits() {
+    let addr: SocketAddr = "[::]:4027".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.stream_idle_timeout = Some(Duration::from_millis(300));
+    public_server.grpc_config.max_stream_lifetime = Some(Duration::from_secs(10));
+    let config = public_server.grpc_config.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').into_iter().last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let mut resp_stream = public_client
+        .new_operations(request_stream)
+        .await
+        .unwrap()
+        .into_inner();
+
+    tx_request
+        .send(NewOperationsRequest { filters: vec![] })
+        .await
+        .unwrap();
+
+    // a message from the client keeps the stream open
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    tx_request
+        .send(NewOperationsRequest { filters: vec![] })
+        .await
+        .unwrap();
+    let result = tokio::time::timeout(Duration::from_millis(200), resp_stream.next()).await;
+    assert!(result.is_err());
+
+    // the stream is closed once the client stays idle for too long
+    let status = tokio::time::timeout(Duration::from_secs(2), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+    assert_eq!(status.message(), "stream idle timeout exceeded");
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn send_operations() {
     let addr: SocketAddr = "[::]:4023".parse().unwrap();
@@ -1577,3 +1783,105 @@ async fn send_blocks() {
 
     stop_handle.stop();
 }
+
+#[test]
+fn filter_value_limits() {
+    let mut filters = FilterExpression::<u32>::default();
+    filters.check_value_count("addresses", 2, 3).unwrap();
+    filters.and_any_of(vec![1, 2], "addresses").unwrap();
+    // the limit applies to the total over all the filters of a type
+    assert!(filters.check_value_count("addresses", 2, 3).is_err());
+    filters.check_value_count("addresses", 1, 3).unwrap();
+    // other filter types have their own limit
+    filters.check_value_count("block ids", 3, 3).unwrap();
+
+    // groups are ANDed, the values of a group are ORed
+    filters.and_any_of(vec![2, 3], "block ids").unwrap();
+    assert!(filters.matches(|value| *value == 2));
+    assert!(!filters.matches(|value| *value == 1));
+    assert!(filters.and_any_of(vec![], "addresses").is_err());
+}
+
+#[tokio::test]
+async fn new_filled_blocks_with_execution_status() {
+    let addr: SocketAddr = "[::]:4028".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let (filled_block_tx, _filled_block_rx) = tokio::sync::broadcast::channel(10);
+    let (slot_tx, _slot_rx) = tokio::sync::broadcast::channel(10);
+
+    public_server.consensus_broadcasts.filled_block_sender = filled_block_tx.clone();
+    public_server
+        .execution_channels
+        .slot_execution_output_sender = slot_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let block = create_block(&keypair);
+    let filled_block = FilledBlock {
+        header: block.content.header.clone(),
+        operations: vec![],
+    };
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').into_iter().last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let mut request = tonic::Request::new(tokio_stream::wrappers::ReceiverStream::new(rx));
+    request
+        .metadata_mut()
+        .insert(EXECUTION_STATUS_METADATA_KEY, "true".parse().unwrap());
+    let mut resp_stream = public_client
+        .new_filled_blocks(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    tx_request
+        .send(NewFilledBlocksRequest { filters: vec![] })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    filled_block_tx.send(filled_block.clone()).unwrap();
+
+    // the block is held until its slot is executed
+    let result = tokio::time::timeout(Duration::from_millis(500), resp_stream.next()).await;
+    assert!(result.is_err());
+
+    let exec_output = ExecutionOutput {
+        slot: block.content.header.content.slot,
+        block_info: Some(ExecutedBlockInfo {
+            block_id: block.id,
+            current_version: 0,
+            announced_version: None,
+        }),
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+        transfers: Default::default(),
+        operation_changes: Default::default(),
+        operation_failures: Default::default(),
+    };
+    slot_tx
+        .send(SlotExecutionOutput::ExecutedSlot(exec_output))
+        .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        result.filled_block.unwrap().header.unwrap().secure_hash,
+        block.id.to_string()
+    );
+
+    stop_handle.stop();
+}
@@ -1,9 +1,10 @@
-use massa_models::{address::Address, amount::Amount, bytecode::Bytecode};
+use massa_models::datastore::DatastoreKeysFilter;
+use massa_models::{address::Address, amount::Amount, bytecode::Bytecode, slot::Slot};
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 
 use crate::{LedgerChanges, LedgerError};
-use massa_db_exports::DBBatch;
+use massa_db_exports::{DBBatch, DBPreviousValues};
 
 pub trait LedgerController: Send + Sync + Debug {
     /// Loads ledger from file
@@ -43,12 +44,67 @@ pub trait LedgerController: Send + Sync + Debug {
     /// A `BTreeSet` of the datastore keys
     fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>>;
 
+    /// Get a page of the keys of the datastore for a given address (see `DatastoreKeysFilter`).
+    ///
+    /// # Returns
+    /// A `BTreeSet` of the selected datastore keys, or None if the ledger entry was not found
+    fn get_datastore_keys_filtered(
+        &self,
+        addr: &Address,
+        filter: &DatastoreKeysFilter,
+    ) -> Option<BTreeSet<Vec<u8>>>;
+
+    /// Gets the balance of a ledger entry at the end of a past final slot.
+    /// Fails if the slot is not archived (see the archival mode of the database).
+    fn get_balance_at_slot(
+        &self,
+        addr: &Address,
+        slot: Slot,
+    ) -> Result<Option<Amount>, LedgerError>;
+
+    /// Gets a copy of the bytecode of a ledger entry at the end of a past final slot.
+    /// Fails if the slot is not archived.
+    fn get_bytecode_at_slot(
+        &self,
+        addr: &Address,
+        slot: Slot,
+    ) -> Result<Option<Bytecode>, LedgerError>;
+
+    /// Checks if a ledger entry existed at the end of a past final slot.
+    /// Fails if the slot is not archived.
+    fn entry_exists_at_slot(&self, addr: &Address, slot: Slot) -> Result<bool, LedgerError>;
+
+    /// Gets a copy of the value of a datastore entry at the end of a past final slot.
+    /// Fails if the slot is not archived.
+    fn get_data_entry_at_slot(
+        &self,
+        addr: &Address,
+        key: &[u8],
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, LedgerError>;
+
+    /// Get the keys of the datastore of an address starting with `prefix` at the end of a past final slot.
+    /// Fails if the slot is not archived.
+    fn get_datastore_keys_at_slot(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        slot: Slot,
+    ) -> Result<Option<BTreeSet<Vec<u8>>>, LedgerError>;
+
     /// Reset the ledger
     ///
     /// USED FOR BOOTSTRAP ONLY
     fn reset(&mut self);
 
-    fn apply_changes_to_batch(&mut self, changes: LedgerChanges, ledger_batch: &mut DBBatch);
+    /// Applies `LedgerChanges` to the batch.
+    /// The values of the changed keys known before the write are added to `previous_values`.
+    fn apply_changes_to_batch(
+        &mut self,
+        changes: LedgerChanges,
+        ledger_batch: &mut DBBatch,
+        previous_values: &mut DBPreviousValues,
+    );
 
     /// Deserializes the key and value, useful after bootstrap
     fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool;
@@ -1,10 +1,15 @@
 use massa_db_exports::LEDGER_PREFIX;
+use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_models::{
-    address::{Address, AddressDeserializer, AddressSerializer},
+    address::{
+        Address, AddressDeserializer, AddressSerializer, SCAddress, SCAddressV0, UserAddress,
+        UserAddressV0,
+    },
     serialization::{VecU8Deserializer, VecU8Serializer},
 };
 use massa_serialization::{
-    Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+    DeserializeError, Deserializer, SerializeError, Serializer, U64VarIntDeserializer,
+    U64VarIntSerializer,
 };
 use nom::error::{ContextError, ParseError};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -153,6 +158,82 @@ pub fn datastore_prefix_from_address(address: &Address, prefix: &[u8]) -> Vec<u8
     res_prefix
 }
 
+/// Splits the ledger keyspace into `count` stripes of about the same number of addresses,
+/// to bootstrap them from several servers in parallel.
+///
+/// Returns the `count - 1` sorted keys delimiting the stripes: the first stripe starts at the
+/// beginning of the keyspace and the last one ends at its end.
+///
+/// ## Example
+/// ```
+/// use massa_ledger_exports::ledger_stripe_split_keys;
+///
+/// let split_keys = ledger_stripe_split_keys(4);
+/// assert_eq!(split_keys.len(), 3);
+/// assert!(split_keys.windows(2).all(|keys| keys[0] < keys[1]));
+/// assert!(ledger_stripe_split_keys(1).is_empty());
+/// ```
+pub fn ledger_stripe_split_keys(count: usize) -> Vec<Vec<u8>> {
+    // user and smart contract addresses are split on the first byte of their hash
+    let positions = 2 * 256;
+    (1..count)
+        .map(|i| {
+            let position = i * positions / count;
+            let mut hash_bytes = [0u8; HASH_SIZE_BYTES];
+            hash_bytes[0] = (position % 256) as u8;
+            let hash = Hash::from_bytes(&hash_bytes);
+            let address = if position < 256 {
+                Address::User(UserAddress::UserAddressV0(UserAddressV0(hash)))
+            } else {
+                Address::SC(SCAddress::SCAddressV0(SCAddressV0(hash)))
+            };
+            let mut key = LEDGER_PREFIX.as_bytes().to_vec();
+            U64VarIntSerializer::new()
+                .serialize(&KEY_VERSION, &mut key)
+                .unwrap();
+            AddressSerializer::new()
+                .serialize(&address, &mut key)
+                .unwrap();
+            key
+        })
+        .collect()
+}
+
+/// Estimates the share of the ledger keyspace sorted before `key`, between 0 and 1,
+/// with the same distribution of the addresses as `ledger_stripe_split_keys`.
+///
+/// Keys sorted before the ledger are at 0, and keys sorted after it at 1.
+///
+/// ## Example
+/// ```
+/// use massa_ledger_exports::{ledger_key_position, ledger_stripe_split_keys};
+///
+/// let split_keys = ledger_stripe_split_keys(4);
+/// assert_eq!(ledger_key_position(&split_keys[1]), 0.5);
+/// assert_eq!(ledger_key_position(b"async_pool/"), 0.0);
+/// assert_eq!(ledger_key_position(b"versioning/"), 1.0);
+/// ```
+pub fn ledger_key_position(key: &[u8]) -> f64 {
+    let Some(rest) = key.strip_prefix(LEDGER_PREFIX.as_bytes()) else {
+        return if key < LEDGER_PREFIX.as_bytes() {
+            0.0
+        } else {
+            1.0
+        };
+    };
+    let address = U64VarIntDeserializer::new(Included(0), Included(u64::MAX))
+        .deserialize::<DeserializeError>(rest)
+        .and_then(|(rest, _version)| AddressDeserializer::new().deserialize(rest));
+    let (sc_offset, hash) = match address {
+        Ok((_, Address::User(UserAddress::UserAddressV0(UserAddressV0(hash))))) => (0.0, hash),
+        Ok((_, Address::SC(SCAddress::SCAddressV0(SCAddressV0(hash))))) => (1.0, hash),
+        Err(_) => return 0.0,
+    };
+    let hash_bytes = hash.to_bytes();
+    let hash_position = f64::from(u16::from_be_bytes([hash_bytes[0], hash_bytes[1]])) / 65536.0;
+    (sc_offset + hash_position) / 2.0
+}
+
 /// Basic key serializer
 #[derive(Default, Clone)]
 pub struct KeySerializer {
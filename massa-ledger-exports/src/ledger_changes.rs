@@ -1025,4 +1025,44 @@ impl LedgerChanges {
             }
         }
     }
+
+    /// Returns the changes made on top of `previous`, which must be an earlier state of these changes.
+    ///
+    /// Entries replaced or deleted are returned as is, while for updated entries
+    /// only the balance, bytecode and datastore keys whose change differs from `previous` are kept.
+    pub fn diff_since(&self, previous: &LedgerChanges) -> LedgerChanges {
+        let mut diff = LedgerChanges::default();
+        for (addr, change) in self.0.iter() {
+            let previous_change = previous.0.get(addr);
+            if previous_change == Some(change) {
+                continue;
+            }
+            let change = match (previous_change, change) {
+                (
+                    Some(SetUpdateOrDelete::Update(previous_update)),
+                    SetUpdateOrDelete::Update(update),
+                ) => SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                    balance: if update.balance != previous_update.balance {
+                        update.balance.clone()
+                    } else {
+                        SetOrKeep::Keep
+                    },
+                    bytecode: if update.bytecode != previous_update.bytecode {
+                        update.bytecode.clone()
+                    } else {
+                        SetOrKeep::Keep
+                    },
+                    datastore: update
+                        .datastore
+                        .iter()
+                        .filter(|(key, value)| previous_update.datastore.get(*key) != Some(*value))
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                }),
+                _ => change.clone(),
+            };
+            diff.0.insert(*addr, change);
+        }
+        diff
+    }
 }
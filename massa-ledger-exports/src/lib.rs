@@ -15,8 +15,9 @@ pub use config::LedgerConfig;
 pub use controller::LedgerController;
 pub use error::LedgerError;
 pub use key::{
-    datastore_prefix_from_address, Key, KeyDeserializer, KeySerializer, KeyType, BALANCE_IDENT,
-    BYTECODE_IDENT, DATASTORE_IDENT, VERSION_IDENT,
+    datastore_prefix_from_address, ledger_key_position, ledger_stripe_split_keys, Key,
+    KeyDeserializer, KeySerializer, KeyType, BALANCE_IDENT, BYTECODE_IDENT, DATASTORE_IDENT,
+    VERSION_IDENT,
 };
 pub use ledger_changes::{
     DatastoreUpdateDeserializer, DatastoreUpdateSerializer, LedgerChanges,
@@ -0,0 +1,351 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Genesis ledger importer.
+//!
+//! Reads the initial ledger from a CSV or JSON file, validates it and builds the
+//! initial ledger entries and roll counts.
+//!
+//! CSV files contain one `address,balance[,rolls]` line per entry. Empty lines,
+//! lines starting with `#` and an optional `address,...` header are ignored.
+//!
+//! JSON files contain an array of objects:
+//! `{ "address": "AU...", "balance": "1000", "rolls": 10, "datastore": [[[1, 2], [3]]] }`
+//! where `rolls` and `datastore` (list of `[key, value]` byte arrays) are optional.
+
+use massa_ledger_exports::{LedgerEntry, LedgerError};
+use massa_models::{address::Address, amount::Amount, datastore::Datastore};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+/// An entry of the genesis ledger input file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GenesisLedgerEntry {
+    /// address of the entry
+    pub address: Address,
+    /// initial balance
+    pub balance: Amount,
+    /// initial roll count
+    #[serde(default)]
+    pub rolls: u64,
+    /// initial datastore entries
+    #[serde(default)]
+    pub datastore: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Format of a genesis ledger input file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenesisLedgerFormat {
+    /// comma-separated `address,balance[,rolls]` lines
+    Csv,
+    /// JSON array of `GenesisLedgerEntry`
+    Json,
+}
+
+impl GenesisLedgerFormat {
+    /// Guesses the format of a file from its extension
+    pub fn from_path(path: &Path) -> Result<Self, LedgerError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Ok(GenesisLedgerFormat::Csv),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(GenesisLedgerFormat::Json),
+            _ => Err(LedgerError::FileError(format!(
+                "unknown genesis ledger format for file {}: expected a .csv or .json extension",
+                path.to_str().unwrap_or("(non-utf8 path)")
+            ))),
+        }
+    }
+}
+
+/// Parses a genesis ledger input.
+/// Malformed CSV lines do not interrupt the parsing: they are returned as errors along with the valid entries.
+pub fn parse_genesis_ledger(
+    content: &str,
+    format: GenesisLedgerFormat,
+) -> Result<(Vec<GenesisLedgerEntry>, Vec<String>), LedgerError> {
+    match format {
+        GenesisLedgerFormat::Json => serde_json::from_str(content)
+            .map(|entries| (entries, Vec::new()))
+            .map_err(|err| {
+                LedgerError::FileError(format!("error parsing genesis ledger JSON: {}", err))
+            }),
+        GenesisLedgerFormat::Csv => {
+            let mut entries = Vec::new();
+            let mut errors = Vec::new();
+            for (index, line) in content.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+                if index == 0 && fields[0].eq_ignore_ascii_case("address") {
+                    continue;
+                }
+                match parse_csv_fields(&fields) {
+                    Ok(entry) => entries.push(entry),
+                    Err(err) => errors.push(format!("line {}: {}", index + 1, err)),
+                }
+            }
+            Ok((entries, errors))
+        }
+    }
+}
+
+fn parse_csv_fields(fields: &[&str]) -> Result<GenesisLedgerEntry, String> {
+    if fields.len() < 2 || fields.len() > 3 {
+        return Err(format!("expected 2 or 3 fields, found {}", fields.len()));
+    }
+    let address =
+        Address::from_str(fields[0]).map_err(|err| format!("invalid address: {}", err))?;
+    let balance = Amount::from_str(fields[1]).map_err(|err| format!("invalid balance: {}", err))?;
+    let rolls = match fields.get(2) {
+        Some(rolls) => rolls
+            .parse::<u64>()
+            .map_err(|err| format!("invalid roll count: {}", err))?,
+        None => 0,
+    };
+    Ok(GenesisLedgerEntry {
+        address,
+        balance,
+        rolls,
+        datastore: Vec::new(),
+    })
+}
+
+/// Reads and parses a genesis ledger input file, guessing its format from its extension
+pub fn read_genesis_ledger_file(
+    path: &Path,
+) -> Result<(Vec<GenesisLedgerEntry>, Vec<String>), LedgerError> {
+    let format = GenesisLedgerFormat::from_path(path)?;
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        LedgerError::FileError(format!(
+            "error loading genesis ledger file {}: {}",
+            path.to_str().unwrap_or("(non-utf8 path)"),
+            err
+        ))
+    })?;
+    parse_genesis_ledger(&content, format)
+}
+
+/// Validation report of a genesis ledger
+#[derive(Debug, Clone, Default)]
+pub struct GenesisLedgerReport {
+    /// number of valid entries
+    pub entry_count: usize,
+    /// sum of the balances of all the entries
+    pub total_balance: Amount,
+    /// sum of the rolls of all the entries
+    pub total_rolls: u64,
+    /// number of datastore entries
+    pub datastore_entry_count: usize,
+    /// errors that prevent the ledger from being used
+    pub errors: Vec<String>,
+    /// suspicious entries that do not prevent the ledger from being used
+    pub warnings: Vec<String>,
+}
+
+impl GenesisLedgerReport {
+    /// Returns true if no error was found
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Display for GenesisLedgerReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Genesis ledger report:")?;
+        writeln!(f, "\tEntries: {}", self.entry_count)?;
+        writeln!(f, "\tTotal balance: {}", self.total_balance)?;
+        writeln!(f, "\tTotal rolls: {}", self.total_rolls)?;
+        writeln!(f, "\tDatastore entries: {}", self.datastore_entry_count)?;
+        for warning in &self.warnings {
+            writeln!(f, "\tWarning: {}", warning)?;
+        }
+        for error in &self.errors {
+            writeln!(f, "\tError: {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validates genesis ledger entries.
+///
+/// # Arguments
+/// * `entries`: parsed entries
+/// * `parse_errors`: errors returned by the parser, reported as is
+/// * `max_key_length`: maximum datastore key length
+/// * `expected_total_balance`: optional checksum of the balances
+/// * `expected_total_rolls`: optional checksum of the roll counts
+pub fn validate_genesis_ledger(
+    entries: &[GenesisLedgerEntry],
+    parse_errors: Vec<String>,
+    max_key_length: u8,
+    expected_total_balance: Option<Amount>,
+    expected_total_rolls: Option<u64>,
+) -> GenesisLedgerReport {
+    let mut report = GenesisLedgerReport {
+        errors: parse_errors,
+        ..Default::default()
+    };
+    let mut seen: HashMap<Address, usize> = HashMap::with_capacity(entries.len());
+
+    for (index, entry) in entries.iter().enumerate() {
+        if let Some(first) = seen.insert(entry.address, index) {
+            report.errors.push(format!(
+                "duplicate address {} (entries {} and {})",
+                entry.address,
+                first + 1,
+                index + 1
+            ));
+            continue;
+        }
+        report.entry_count += 1;
+
+        match report.total_balance.checked_add(entry.balance) {
+            Some(total) => report.total_balance = total,
+            None => report.errors.push(format!(
+                "total balance overflow at address {}",
+                entry.address
+            )),
+        }
+        match report.total_rolls.checked_add(entry.rolls) {
+            Some(total) => report.total_rolls = total,
+            None => report
+                .errors
+                .push(format!("total rolls overflow at address {}", entry.address)),
+        }
+
+        if entry.balance == Amount::zero() && entry.rolls == 0 {
+            report.warnings.push(format!(
+                "address {} has no balance and no rolls",
+                entry.address
+            ));
+        }
+
+        let mut keys = Datastore::new();
+        for (key, value) in &entry.datastore {
+            if key.len() > max_key_length as usize {
+                report.errors.push(format!(
+                    "datastore key of address {} exceeds {} bytes",
+                    entry.address, max_key_length
+                ));
+            }
+            if keys.insert(key.clone(), value.clone()).is_some() {
+                report.errors.push(format!(
+                    "duplicate datastore key {:?} for address {}",
+                    key, entry.address
+                ));
+            }
+        }
+        report.datastore_entry_count += keys.len();
+    }
+
+    if let Some(expected) = expected_total_balance {
+        if expected != report.total_balance {
+            report.errors.push(format!(
+                "total balance {} does not match the expected {}",
+                report.total_balance, expected
+            ));
+        }
+    }
+    if let Some(expected) = expected_total_rolls {
+        if expected != report.total_rolls {
+            report.errors.push(format!(
+                "total rolls {} do not match the expected {}",
+                report.total_rolls, expected
+            ));
+        }
+    }
+
+    report
+}
+
+/// Builds the initial ledger entries and the initial roll counts from validated genesis entries.
+/// Addresses without rolls are not part of the roll counts.
+pub fn build_genesis_ledger(
+    entries: Vec<GenesisLedgerEntry>,
+) -> (HashMap<Address, LedgerEntry>, BTreeMap<Address, u64>) {
+    let mut ledger = HashMap::with_capacity(entries.len());
+    let mut rolls = BTreeMap::new();
+    for entry in entries {
+        if entry.rolls > 0 {
+            rolls.insert(entry.address, entry.rolls);
+        }
+        ledger.insert(
+            entry.address,
+            LedgerEntry {
+                balance: entry.balance,
+                datastore: entry.datastore.into_iter().collect(),
+                ..Default::default()
+            },
+        );
+    }
+    (ledger, rolls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    fn random_address() -> Address {
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key())
+    }
+
+    #[test]
+    fn test_parse_genesis_ledger_csv() {
+        let addr_1 = random_address();
+        let addr_2 = random_address();
+        let content = format!(
+            "address,balance,rolls\n# comment\n{},1000,10\n\n{},12.5\nnot an address,1\n{},abc,1\n",
+            addr_1, addr_2, addr_1
+        );
+        let (entries, errors) = parse_genesis_ledger(&content, GenesisLedgerFormat::Csv).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].address, addr_1);
+        assert_eq!(entries[0].rolls, 10);
+        assert_eq!(entries[1].balance, Amount::from_str("12.5").unwrap());
+        assert_eq!(entries[1].rolls, 0);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("line 6"));
+    }
+
+    #[test]
+    fn test_validate_genesis_ledger() {
+        let addr_1 = random_address();
+        let addr_2 = random_address();
+        let content = format!(
+            r#"[
+                {{ "address": "{}", "balance": "100", "rolls": 2, "datastore": [[[1], [2]]] }},
+                {{ "address": "{}", "balance": "50" }},
+                {{ "address": "{}", "balance": "1" }}
+            ]"#,
+            addr_1, addr_2, addr_1
+        );
+        let (entries, errors) = parse_genesis_ledger(&content, GenesisLedgerFormat::Json).unwrap();
+        assert!(errors.is_empty());
+
+        let report = validate_genesis_ledger(
+            &entries,
+            errors,
+            255,
+            Some(Amount::from_str("150").unwrap()),
+            Some(2),
+        );
+        // the duplicate is reported and not counted in the totals
+        assert_eq!(report.entry_count, 2);
+        assert_eq!(report.total_balance, Amount::from_str("150").unwrap());
+        assert_eq!(report.datastore_entry_count, 1);
+        assert_eq!(report.errors.len(), 1);
+        assert!(!report.is_valid());
+
+        let report = validate_genesis_ledger(&entries[..2], Vec::new(), 255, None, Some(3));
+        assert_eq!(report.errors.len(), 1);
+
+        let (ledger, rolls) = build_genesis_ledger(entries[..2].to_vec());
+        assert_eq!(ledger.len(), 2);
+        assert_eq!(rolls.len(), 1);
+        assert_eq!(ledger[&addr_1].datastore.get(&vec![1u8]), Some(&vec![2u8]));
+    }
+}
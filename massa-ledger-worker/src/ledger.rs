@@ -3,7 +3,7 @@
 //! This file defines the final ledger associating addresses to their balances, bytecode and data.
 
 use crate::ledger_db::{LedgerDB, LedgerSubEntry};
-use massa_db_exports::{DBBatch, ShareableMassaDBController};
+use massa_db_exports::{DBBatch, DBPreviousValues, ShareableMassaDBController};
 use massa_ledger_exports::{
     LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
 };
@@ -11,6 +11,8 @@ use massa_models::{
     address::Address,
     amount::{Amount, AmountDeserializer},
     bytecode::{Bytecode, BytecodeDeserializer},
+    datastore::DatastoreKeysFilter,
+    slot::Slot,
 };
 use massa_serialization::{DeserializeError, Deserializer};
 use std::collections::{BTreeSet, HashMap};
@@ -45,6 +47,11 @@ impl FinalLedger {
             config,
         }
     }
+
+    /// Loads the given entries as the initial ledger, bypassing the initial ledger file
+    pub fn load_initial_entries(&mut self, initial_ledger: HashMap<Address, LedgerEntry>) {
+        self.sorted_ledger.load_initial_ledger(initial_ledger);
+    }
 }
 
 impl LedgerController for FinalLedger {
@@ -142,6 +149,91 @@ impl LedgerController for FinalLedger {
         self.sorted_ledger.get_datastore_keys(addr, prefix)
     }
 
+    /// Get a page of the keys of the datastore for a given address.
+    ///
+    /// # Returns
+    /// A `BTreeSet` of the selected datastore keys
+    fn get_datastore_keys_filtered(
+        &self,
+        addr: &Address,
+        filter: &DatastoreKeysFilter,
+    ) -> Option<BTreeSet<Vec<u8>>> {
+        self.sorted_ledger.get_datastore_keys_filtered(addr, filter)
+    }
+
+    /// Gets the balance of a ledger entry at the end of a past final slot
+    fn get_balance_at_slot(
+        &self,
+        addr: &Address,
+        slot: Slot,
+    ) -> Result<Option<Amount>, LedgerError> {
+        let amount_deserializer =
+            AmountDeserializer::new(Included(Amount::MIN), Included(Amount::MAX));
+        Ok(self
+            .sorted_ledger
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Balance, slot)
+            .map_err(|err| LedgerError::ArchiveError(err.to_string()))?
+            .map(|bytes| {
+                amount_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect("critical: invalid balance format")
+                    .1
+            }))
+    }
+
+    /// Gets a copy of the bytecode of a ledger entry at the end of a past final slot
+    fn get_bytecode_at_slot(
+        &self,
+        addr: &Address,
+        slot: Slot,
+    ) -> Result<Option<Bytecode>, LedgerError> {
+        let bytecode_deserializer =
+            BytecodeDeserializer::new(self.config.max_datastore_value_length);
+        Ok(self
+            .sorted_ledger
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Bytecode, slot)
+            .map_err(|err| LedgerError::ArchiveError(err.to_string()))?
+            .map(|bytes| {
+                bytecode_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect("critical: invalid bytecode format")
+                    .1
+            }))
+    }
+
+    /// Checks if a ledger entry existed at the end of a past final slot
+    fn entry_exists_at_slot(&self, addr: &Address, slot: Slot) -> Result<bool, LedgerError> {
+        Ok(self
+            .sorted_ledger
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Version, slot)
+            .map_err(|err| LedgerError::ArchiveError(err.to_string()))?
+            .is_some())
+    }
+
+    /// Gets a copy of the value of a datastore entry at the end of a past final slot
+    fn get_data_entry_at_slot(
+        &self,
+        addr: &Address,
+        key: &[u8],
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, LedgerError> {
+        self.sorted_ledger
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Datastore(key.to_owned()), slot)
+            .map_err(|err| LedgerError::ArchiveError(err.to_string()))
+    }
+
+    /// Get the keys of the datastore of an address starting with `prefix` at the end of a past final slot
+    fn get_datastore_keys_at_slot(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        slot: Slot,
+    ) -> Result<Option<BTreeSet<Vec<u8>>>, LedgerError> {
+        self.sorted_ledger
+            .get_datastore_keys_at_slot(addr, prefix, slot)
+            .map_err(|err| LedgerError::ArchiveError(err.to_string()))
+    }
+
     /// Reset the disk ledger.
     ///
     /// USED FOR BOOTSTRAP ONLY
@@ -150,9 +242,14 @@ impl LedgerController for FinalLedger {
     }
 
     /// Allows applying `LedgerChanges` to the final ledger
-    fn apply_changes_to_batch(&mut self, changes: LedgerChanges, ledger_batch: &mut DBBatch) {
+    fn apply_changes_to_batch(
+        &mut self,
+        changes: LedgerChanges,
+        ledger_batch: &mut DBBatch,
+        previous_values: &mut DBPreviousValues,
+    ) {
         self.sorted_ledger
-            .apply_changes_to_batch(changes, ledger_batch);
+            .apply_changes_to_batch(changes, ledger_batch, previous_values);
     }
 
     /// Deserializes the key and value, useful after bootstrap
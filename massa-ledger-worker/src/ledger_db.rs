@@ -3,13 +3,13 @@
 //! Module to interact with the disk ledger
 
 use massa_db_exports::{
-    DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController, CRUD_ERROR,
-    KEY_SER_ERROR, LEDGER_PREFIX, STATE_CF,
+    DBBatch, DBPreviousValues, MassaDBError, MassaDirection, ShareableMassaDBController,
+    CHANGE_ID_DESER_ERROR, CRUD_ERROR, KEY_DESER_ERROR, KEY_SER_ERROR, LEDGER_PREFIX, STATE_CF,
 };
 use massa_ledger_exports::*;
 use massa_models::amount::AmountDeserializer;
 use massa_models::bytecode::BytecodeDeserializer;
-use massa_models::datastore::get_prefix_bounds;
+use massa_models::datastore::{get_prefix_bounds, DatastoreKeysFilter};
 use massa_models::{
     address::Address, amount::AmountSerializer, bytecode::BytecodeSerializer, slot::Slot,
 };
@@ -125,7 +125,13 @@ impl LedgerDB {
     /// # Arguments
     /// * changes: ledger changes to be applied
     /// * batch: the batch to apply the changes to
-    pub fn apply_changes_to_batch(&self, changes: LedgerChanges, batch: &mut DBBatch) {
+    /// * previous_values: the known values of the changed keys before the changes are applied
+    pub fn apply_changes_to_batch(
+        &self,
+        changes: LedgerChanges,
+        batch: &mut DBBatch,
+        previous_values: &mut DBPreviousValues,
+    ) {
         // for all incoming changes
         for (addr, change) in changes.0 {
             match change {
@@ -143,7 +149,7 @@ impl LedgerDB {
                 // the incoming change deletes a ledger entry
                 SetUpdateOrDelete::Delete => {
                     // delete the entry, if it exists
-                    self.delete_entry(&addr, batch);
+                    self.delete_entry(&addr, batch, previous_values);
                 }
             }
         }
@@ -167,11 +173,77 @@ impl LedgerDB {
         db.get_cf(STATE_CF, serialized_key).expect(CRUD_ERROR)
     }
 
+    /// Get the value of a sub-entry at the end of a past final slot, from the archive.
+    ///
+    /// # Returns
+    /// An Option of the sub-entry value as bytes, or an error if the slot is not archived
+    pub fn get_sub_entry_at_slot(
+        &self,
+        addr: &Address,
+        ty: LedgerSubEntry,
+        slot: Slot,
+    ) -> Result<Option<Vec<u8>>, MassaDBError> {
+        let key = ty.derive_key(addr);
+        let mut serialized_key = Vec::new();
+        self.key_serializer_db
+            .serialize(&key, &mut serialized_key)
+            .expect(KEY_SER_ERROR);
+        self.db.read().get_at_slot(&serialized_key, slot)
+    }
+
+    /// Get the keys of the datastore of an address at the end of a past final slot, from the archive.
+    ///
+    /// # Returns
+    /// A `BTreeSet` of the datastore keys, `None` if the address did not exist,
+    /// or an error if the slot is not archived
+    pub fn get_datastore_keys_at_slot(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        slot: Slot,
+    ) -> Result<Option<BTreeSet<Vec<u8>>>, MassaDBError> {
+        if self
+            .get_sub_entry_at_slot(addr, LedgerSubEntry::Balance, slot)?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let db = self.db.read();
+        let keys = db
+            .iterate_at_slot(&datastore_prefix_from_address(addr, prefix), slot)?
+            .filter_map(|(key, _)| {
+                let (_rest, key) = self
+                    .key_deserializer_db
+                    .deserialize::<DeserializeError>(&key)
+                    .expect("could not deserialize datastore key from state db");
+                match key.key_type {
+                    KeyType::DATASTORE(datastore_vec) => Some(datastore_vec),
+                    _ => None,
+                }
+            })
+            .collect();
+        Ok(Some(keys))
+    }
+
     /// Get every key of the datastore for a given address.
     ///
     /// # Returns
     /// A `BTreeSet` of the datastore keys
     pub fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>> {
+        self.get_datastore_keys_filtered(addr, &DatastoreKeysFilter::from_prefix(prefix))
+    }
+
+    /// Get a page of the keys of the datastore for a given address.
+    /// The database is only iterated over the selected range, up to the limit of the filter.
+    ///
+    /// # Returns
+    /// A `BTreeSet` of the selected datastore keys, `None` if the address does not exist
+    pub fn get_datastore_keys_filtered(
+        &self,
+        addr: &Address,
+        filter: &DatastoreKeysFilter,
+    ) -> Option<BTreeSet<Vec<u8>>> {
         let db = self.db.read();
 
         // check if address exists, return None if it does not
@@ -184,18 +256,23 @@ impl LedgerDB {
             db.get_cf(STATE_CF, serialized_key).expect(CRUD_ERROR)?;
         }
 
-        // collect keys starting with prefix
-        let start_prefix = datastore_prefix_from_address(addr, prefix);
-        let end_prefix = end_prefix(&start_prefix);
+        let Some((lower, upper)) = filter.key_bounds() else {
+            return Some(BTreeSet::new());
+        };
+
+        // collect the keys within the bounds: the serialized keys are ordered like the datastore keys
+        let start_key = datastore_prefix_from_address(addr, &lower);
+        let end_key = match upper {
+            Bound::Excluded(upper) => Some(datastore_prefix_from_address(addr, &upper)),
+            _ => end_prefix(&datastore_prefix_from_address(addr, &[])),
+        };
         Some(
-            db.iterator_cf(
+            db.range_iterator_cf(
                 STATE_CF,
-                MassaIteratorMode::From(&start_prefix, MassaDirection::Forward),
+                &start_key,
+                end_key.as_deref(),
+                MassaDirection::Forward,
             )
-            .take_while(|(key, _)| match &end_prefix {
-                Some(end) => key < end,
-                None => true,
-            })
             .filter_map(|(key, _)| {
                 let (_rest, key) = self
                     .key_deserializer_db
@@ -206,6 +283,7 @@ impl LedgerDB {
                     _ => None,
                 }
             })
+            .take(filter.limit.unwrap_or(usize::MAX))
             .collect(),
         )
     }
@@ -273,6 +351,69 @@ impl LedgerDB {
 
         true
     }
+
+    /// Calls `f` on every complete ledger entry, in the order of the serialized addresses.
+    /// The database stays locked for reading meanwhile, so that all entries belong to the same slot.
+    ///
+    /// # Returns
+    /// The slot of the ledger, or the first error returned by `f`
+    pub fn for_each_entry<E>(
+        &self,
+        mut f: impl FnMut(Address, LedgerEntry) -> Result<(), E>,
+    ) -> Result<Slot, E> {
+        let db = self.db.read();
+        let slot = db.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+
+        let end_prefix = end_prefix(LEDGER_PREFIX.as_bytes());
+        let mut current: Option<(Address, LedgerEntry)> = None;
+        for (serialized_key, serialized_value) in db.range_iterator_cf(
+            STATE_CF,
+            LEDGER_PREFIX.as_bytes(),
+            end_prefix.as_deref(),
+            MassaDirection::Forward,
+        ) {
+            let (_rest, key) = self
+                .key_deserializer_db
+                .deserialize::<DeserializeError>(&serialized_key)
+                .expect(KEY_DESER_ERROR);
+
+            // sub-entries of an address are contiguous: flush the previous entry on address change
+            if !matches!(&current, Some((address, _)) if *address == key.address) {
+                if let Some((address, entry)) =
+                    current.replace((key.address, LedgerEntry::default()))
+                {
+                    f(address, entry)?;
+                }
+            }
+            let (_, entry) = current.as_mut().expect("missing current ledger entry");
+
+            match key.key_type {
+                KeyType::VERSION => {}
+                KeyType::BALANCE => {
+                    entry.balance = self
+                        .amount_deserializer
+                        .deserialize::<DeserializeError>(&serialized_value)
+                        .expect("critical: invalid balance format")
+                        .1;
+                }
+                KeyType::BYTECODE => {
+                    entry.bytecode = self
+                        .bytecode_deserializer
+                        .deserialize::<DeserializeError>(&serialized_value)
+                        .expect("critical: invalid bytecode format")
+                        .1;
+                }
+                KeyType::DATASTORE(datastore_key) => {
+                    entry.datastore.insert(datastore_key, serialized_value);
+                }
+            }
+        }
+        if let Some((address, entry)) = current {
+            f(address, entry)?;
+        }
+
+        Ok(slot)
+    }
 }
 
 // Private helpers
@@ -432,7 +573,13 @@ impl LedgerDB {
     ///
     /// # Arguments
     /// * batch: the given operation batch to update
-    fn delete_entry(&self, addr: &Address, batch: &mut DBBatch) {
+    /// * previous_values: filled with the deleted datastore entries, read while listing them
+    fn delete_entry(
+        &self,
+        addr: &Address,
+        batch: &mut DBBatch,
+        previous_values: &mut DBPreviousValues,
+    ) {
         let db = self.db.read();
 
         // version
@@ -458,15 +605,16 @@ impl LedgerDB {
 
         // datastore
         let key_prefix = datastore_prefix_from_address(addr, &[]);
+        let end_prefix = end_prefix(&key_prefix);
 
-        for (serialized_key, _) in db
-            .iterator_cf(
-                STATE_CF,
-                MassaIteratorMode::From(&key_prefix, MassaDirection::Forward),
-            )
-            .take_while(|(key, _)| key <= &end_prefix(&key_prefix).unwrap())
-        {
+        for (serialized_key, value) in db.range_iterator_cf(
+            STATE_CF,
+            &key_prefix,
+            end_prefix.as_deref(),
+            MassaDirection::Forward,
+        ) {
             db.delete_key(batch, serialized_key.to_vec());
+            previous_values.insert(serialized_key, Some(value));
         }
     }
 }
@@ -522,12 +670,14 @@ impl LedgerDB {
         let db = self.db.read();
 
         let key_prefix = datastore_prefix_from_address(addr, &[]);
+        let end_prefix = end_prefix(&key_prefix);
 
-        db.iterator_cf(
+        db.range_iterator_cf(
             STATE_CF,
-            MassaIteratorMode::From(&key_prefix, MassaDirection::Forward),
+            &key_prefix,
+            end_prefix.as_deref(),
+            MassaDirection::Forward,
         )
-        .take_while(|(k, _)| k <= &end_prefix(&key_prefix).unwrap())
         .map(|(key, data)| {
             let (_rest, key) = self
                 .key_deserializer_db
@@ -598,8 +748,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 10,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: 32,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
 
         let db = Arc::new(RwLock::new(
@@ -620,6 +775,52 @@ mod tests {
         (ledger_db, data)
     }
 
+    #[test]
+    fn test_datastore_keys_filtered() {
+        let addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let (ledger_db, _data) = init_test_ledger(addr);
+        let get_keys = |filter: DatastoreKeysFilter| {
+            ledger_db
+                .get_datastore_keys_filtered(&addr, &filter)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+
+        // paginate over the whole datastore
+        let first_page = get_keys(DatastoreKeysFilter {
+            limit: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(first_page, vec![b"1".to_vec(), b"2".to_vec()]);
+        let second_page = get_keys(DatastoreKeysFilter {
+            cursor: first_page.last().cloned(),
+            limit: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(second_page, vec![b"3".to_vec()]);
+
+        // key range and prefix
+        let range = get_keys(DatastoreKeysFilter {
+            start_key: Some(b"2".to_vec()),
+            end_key: Some(b"3".to_vec()),
+            ..Default::default()
+        });
+        assert_eq!(range, vec![b"2".to_vec()]);
+        assert!(get_keys(DatastoreKeysFilter {
+            prefix: b"3".to_vec(),
+            end_key: Some(b"3".to_vec()),
+            ..Default::default()
+        })
+        .is_empty());
+
+        // unknown address
+        let other_addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        assert!(ledger_db
+            .get_datastore_keys_filtered(&other_addr, &Default::default())
+            .is_none());
+    }
+
     /// Functional test of `LedgerDB`
     #[test]
     fn test_ledger_db() {
@@ -656,11 +857,15 @@ mod tests {
 
         // delete entry
         let mut batch = DBBatch::new();
-        ledger_db.delete_entry(&addr, &mut batch);
-        ledger_db
-            .db
-            .write()
-            .write_batch(batch, Default::default(), None);
+        let mut previous_values = DBPreviousValues::new();
+        ledger_db.delete_entry(&addr, &mut batch, &mut previous_values);
+        assert_eq!(previous_values.len(), 3);
+        ledger_db.db.write().write_batch_with_previous_values(
+            batch,
+            Default::default(),
+            &previous_values,
+            None,
+        );
 
         // check deleted address and ledger hash
         assert_eq!(
@@ -27,6 +27,14 @@
 //! Represents a list of changes to ledger entries that
 //! can be modified, combined or applied to the final ledger.
 //!
+//! ## `genesis.rs`
+//! Reads, validates and converts genesis ledger input files (CSV or JSON)
+//! into initial ledger entries and roll counts.
+//!
+//! ## `snapshot.rs`
+//! Exports the full ledger to a canonical, hashed snapshot file
+//! and initializes a ledger from such a file.
+//!
 //! ## `bootstrap.rs`
 //! Provides serializable structures and tools for bootstrapping the final ledger.  
 //!
@@ -38,10 +46,19 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+mod genesis;
 mod ledger;
 mod ledger_db;
+mod snapshot;
 
+pub use genesis::{
+    build_genesis_ledger, parse_genesis_ledger, read_genesis_ledger_file, validate_genesis_ledger,
+    GenesisLedgerEntry, GenesisLedgerFormat, GenesisLedgerReport,
+};
 pub use ledger::FinalLedger;
+pub use snapshot::{
+    read_ledger_snapshot, LedgerSnapshotInfo, LEDGER_SNAPSHOT_MAGIC, LEDGER_SNAPSHOT_VERSION,
+};
 
 #[cfg(test)]
 mod tests;
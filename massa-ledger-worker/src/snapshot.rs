@@ -0,0 +1,399 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Ledger snapshots.
+//!
+//! Exports the full final ledger (balances, bytecode and datastores) to a canonical file,
+//! and initializes a ledger from such a file.
+//!
+//! The same ledger always produces the same file, byte for byte:
+//! * header: the `LEDGER_SNAPSHOT_MAGIC` bytes, then the `LEDGER_SNAPSHOT_VERSION` byte
+//! * one record per ledger entry, in increasing order of the serialized addresses:
+//!   the record length as a big-endian `u64`, then the serialized address and `LedgerEntry`
+//! * footer: a zero record length, the slot of the ledger (period as a big-endian `u64`,
+//!   then the thread byte), the entry count as a big-endian `u64`, then the snapshot hash
+//!
+//! The snapshot hash chains the header, every record and the footer:
+//! `h = Hash(header)`, then `h = Hash(h || record)` for each record,
+//! and finally `Hash(h || period || thread || entry count)`.
+
+use crate::FinalLedger;
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_ledger_exports::{
+    LedgerEntry, LedgerEntryDeserializer, LedgerEntrySerializer, LedgerError,
+};
+use massa_models::{
+    address::{Address, AddressDeserializer, AddressSerializer},
+    slot::Slot,
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Magic bytes starting every ledger snapshot file
+pub const LEDGER_SNAPSHOT_MAGIC: &[u8; 8] = b"MASSALDG";
+
+/// Version of the ledger snapshot format
+pub const LEDGER_SNAPSHOT_VERSION: u8 = 0;
+
+/// Number of entries written to the database at once when importing a snapshot
+const IMPORT_BATCH_ENTRY_COUNT: usize = 1000;
+
+/// Summary of a ledger snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerSnapshotInfo {
+    /// slot of the exported ledger
+    pub slot: Slot,
+    /// number of ledger entries
+    pub entry_count: u64,
+    /// hash of the snapshot
+    pub hash: Hash,
+}
+
+impl FinalLedger {
+    /// Exports the full ledger to a new snapshot file at `path`
+    pub fn export_snapshot(&self, path: &Path) -> Result<LedgerSnapshotInfo, LedgerError> {
+        let file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|err| snapshot_error(path, "creating", err))?;
+        let mut writer = BufWriter::new(file);
+        let info = self
+            .write_snapshot(&mut writer)
+            .map_err(|err| snapshot_error(path, "writing", err))?;
+        writer
+            .into_inner()
+            .map_err(|err| snapshot_error(path, "writing", err.into_error()))?
+            .sync_all()
+            .map_err(|err| snapshot_error(path, "writing", err))?;
+        Ok(info)
+    }
+
+    /// Writes the full ledger as a snapshot to `writer`
+    pub fn write_snapshot<W: Write>(&self, writer: &mut W) -> std::io::Result<LedgerSnapshotInfo> {
+        let address_serializer = AddressSerializer::new();
+        let entry_serializer = LedgerEntrySerializer::new();
+
+        let header = snapshot_header();
+        writer.write_all(&header)?;
+        let mut hash = Hash::compute_from(&header);
+
+        let mut entry_count = 0u64;
+        let mut record = Vec::new();
+        let slot = self.sorted_ledger.for_each_entry(|address, entry| {
+            record.clear();
+            address_serializer
+                .serialize(&address, &mut record)
+                .expect("critical: address serialization failed");
+            entry_serializer
+                .serialize(&entry, &mut record)
+                .expect("critical: ledger entry serialization failed");
+            writer.write_all(&(record.len() as u64).to_be_bytes())?;
+            writer.write_all(&record)?;
+            hash = Hash::compute_from_tuple(&[hash.to_bytes(), &record]);
+            entry_count += 1;
+            Ok::<(), std::io::Error>(())
+        })?;
+
+        let footer = snapshot_footer(&slot, entry_count);
+        let hash = Hash::compute_from_tuple(&[hash.to_bytes(), &footer]);
+        writer.write_all(&0u64.to_be_bytes())?;
+        writer.write_all(&footer)?;
+        writer.write_all(hash.to_bytes())?;
+        writer.flush()?;
+
+        Ok(LedgerSnapshotInfo {
+            slot,
+            entry_count,
+            hash,
+        })
+    }
+
+    /// Initializes the ledger from the snapshot file at `path`.
+    /// The whole file is verified before anything is written to the ledger.
+    pub fn import_snapshot(&mut self, path: &Path) -> Result<LedgerSnapshotInfo, LedgerError> {
+        let max_key_length = self.config.max_key_length;
+        let max_value_length = self.config.max_datastore_value_length;
+        let open = || {
+            File::open(path)
+                .map(BufReader::new)
+                .map_err(|err| snapshot_error(path, "opening", err))
+        };
+
+        let info = read_ledger_snapshot(open()?, max_key_length, max_value_length, |_, _| {})?;
+
+        let mut entries = HashMap::with_capacity(IMPORT_BATCH_ENTRY_COUNT);
+        read_ledger_snapshot(
+            open()?,
+            max_key_length,
+            max_value_length,
+            |address, entry| {
+                entries.insert(address, entry);
+                if entries.len() >= IMPORT_BATCH_ENTRY_COUNT {
+                    self.sorted_ledger
+                        .load_initial_ledger(std::mem::take(&mut entries));
+                }
+            },
+        )?;
+        if !entries.is_empty() {
+            self.sorted_ledger.load_initial_ledger(entries);
+        }
+
+        Ok(info)
+    }
+}
+
+/// Reads a ledger snapshot from `reader`, calling `on_entry` on each entry in file order.
+///
+/// The snapshot is fully verified (format, address order, entry count and hash),
+/// but an error may only be detected after some entries were passed to `on_entry`.
+pub fn read_ledger_snapshot<R: Read>(
+    mut reader: R,
+    max_datastore_key_length: u8,
+    max_datastore_value_length: u64,
+    mut on_entry: impl FnMut(Address, LedgerEntry),
+) -> Result<LedgerSnapshotInfo, LedgerError> {
+    let address_deserializer = AddressDeserializer::new();
+    let entry_deserializer = LedgerEntryDeserializer::new(
+        u64::MAX,
+        max_datastore_key_length,
+        max_datastore_value_length,
+    );
+    let io_error = |err: std::io::Error| {
+        LedgerError::FileError(format!("error reading ledger snapshot: {}", err))
+    };
+    let format_error =
+        |msg: &str| LedgerError::FileError(format!("invalid ledger snapshot: {}", msg));
+
+    let mut header = snapshot_header();
+    reader.read_exact(&mut header).map_err(io_error)?;
+    if header[..LEDGER_SNAPSHOT_MAGIC.len()] != LEDGER_SNAPSHOT_MAGIC[..] {
+        return Err(format_error("not a ledger snapshot file"));
+    }
+    if header[LEDGER_SNAPSHOT_MAGIC.len()] != LEDGER_SNAPSHOT_VERSION {
+        return Err(format_error(&format!(
+            "unsupported format version {}",
+            header[LEDGER_SNAPSHOT_MAGIC.len()]
+        )));
+    }
+    let mut hash = Hash::compute_from(&header);
+
+    let mut entry_count = 0u64;
+    let mut previous_address = Vec::new();
+    let mut record = Vec::new();
+    loop {
+        let mut len = [0u8; 8];
+        reader.read_exact(&mut len).map_err(io_error)?;
+        let len = u64::from_be_bytes(len);
+        if len == 0 {
+            break;
+        }
+
+        // do not trust the length for allocations: read at most `len` bytes
+        record.clear();
+        (&mut reader)
+            .take(len)
+            .read_to_end(&mut record)
+            .map_err(io_error)?;
+        if record.len() as u64 != len {
+            return Err(format_error("truncated record"));
+        }
+
+        let (rest, address) = address_deserializer
+            .deserialize::<DeserializeError>(&record)
+            .map_err(|_| format_error("invalid address"))?;
+        let serialized_address = &record[..record.len() - rest.len()];
+        if entry_count > 0 && serialized_address <= &previous_address[..] {
+            return Err(format_error(&format!(
+                "address {} is not in increasing order",
+                address
+            )));
+        }
+        previous_address = serialized_address.to_vec();
+        let (rest, entry) = entry_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .map_err(|_| format_error(&format!("invalid ledger entry for {}", address)))?;
+        if !rest.is_empty() {
+            return Err(format_error(&format!(
+                "trailing bytes in the ledger entry of {}",
+                address
+            )));
+        }
+
+        hash = Hash::compute_from_tuple(&[hash.to_bytes(), &record]);
+        entry_count += 1;
+        on_entry(address, entry);
+    }
+
+    let mut footer = snapshot_footer(&Slot::new(0, 0), 0);
+    reader.read_exact(&mut footer).map_err(io_error)?;
+    let (period, rest) = footer.split_at(8);
+    let (thread, count) = rest.split_at(1);
+    let slot = Slot::new(
+        u64::from_be_bytes(period.try_into().expect("period length")),
+        thread[0],
+    );
+    if u64::from_be_bytes(count.try_into().expect("entry count length")) != entry_count {
+        return Err(format_error("entry count mismatch"));
+    }
+    let hash = Hash::compute_from_tuple(&[hash.to_bytes(), &footer]);
+    let mut expected_hash = [0u8; HASH_SIZE_BYTES];
+    reader.read_exact(&mut expected_hash).map_err(io_error)?;
+    if Hash::from_bytes(&expected_hash) != hash {
+        return Err(format_error("hash mismatch"));
+    }
+    if reader.read(&mut [0u8; 1]).map_err(io_error)? != 0 {
+        return Err(format_error("trailing bytes after the hash"));
+    }
+
+    Ok(LedgerSnapshotInfo {
+        slot,
+        entry_count,
+        hash,
+    })
+}
+
+fn snapshot_header() -> Vec<u8> {
+    let mut header = LEDGER_SNAPSHOT_MAGIC.to_vec();
+    header.push(LEDGER_SNAPSHOT_VERSION);
+    header
+}
+
+fn snapshot_footer(slot: &Slot, entry_count: u64) -> Vec<u8> {
+    let mut footer = slot.period.to_be_bytes().to_vec();
+    footer.push(slot.thread);
+    footer.extend(entry_count.to_be_bytes());
+    footer
+}
+
+fn snapshot_error(path: &Path, action: &str, err: std::io::Error) -> LedgerError {
+    LedgerError::FileError(format!(
+        "error {} ledger snapshot file {}: {}",
+        action,
+        path.to_str().unwrap_or("(non-utf8 path)"),
+        err
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_ledger_exports::{LedgerConfig, LedgerController};
+    use massa_models::amount::Amount;
+    use massa_models::bytecode::Bytecode;
+    use massa_models::config::{MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH};
+    use massa_signature::KeyPair;
+    use parking_lot::RwLock;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn new_ledger(path: &Path) -> FinalLedger {
+        let db_config = MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: 2,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        };
+        let db: ShareableMassaDBController = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
+        ));
+        let config = LedgerConfig {
+            thread_count: 2,
+            initial_ledger_path: "".into(),
+            disk_ledger_path: path.to_path_buf(),
+            max_key_length: MAX_DATASTORE_KEY_LENGTH,
+            max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        };
+        FinalLedger::new(config, db)
+    }
+
+    fn sample_entries() -> HashMap<Address, LedgerEntry> {
+        (0..5u8)
+            .map(|i| {
+                let address =
+                    Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+                let entry = LedgerEntry {
+                    balance: Amount::from_str(&format!("{}", 10 + i)).unwrap(),
+                    bytecode: Bytecode(vec![i; i as usize]),
+                    datastore: (0..i)
+                        .map(|k| (vec![k], vec![i, k]))
+                        .collect::<BTreeMap<_, _>>(),
+                };
+                (address, entry)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ledger_snapshot_round_trip() {
+        let entries = sample_entries();
+        let source_dir = TempDir::new().unwrap();
+        let mut source = new_ledger(source_dir.path());
+        source.load_initial_entries(entries.clone());
+
+        let snapshot_dir = TempDir::new().unwrap();
+        let snapshot_path = snapshot_dir.path().join("ledger.snapshot");
+        let info = source.export_snapshot(&snapshot_path).unwrap();
+        assert_eq!(info.entry_count, entries.len() as u64);
+        assert_eq!(info.slot, Slot::new(0, 1));
+        assert!(source.export_snapshot(&snapshot_path).is_err());
+
+        // exporting twice gives the same bytes
+        let mut bytes = Vec::new();
+        assert_eq!(source.write_snapshot(&mut bytes).unwrap(), info);
+        assert_eq!(std::fs::read(&snapshot_path).unwrap(), bytes);
+
+        let target_dir = TempDir::new().unwrap();
+        let mut target = new_ledger(target_dir.path());
+        assert_eq!(target.import_snapshot(&snapshot_path).unwrap(), info);
+        for (address, entry) in entries.iter() {
+            assert_eq!(target.get_balance(address), Some(entry.balance));
+            assert_eq!(target.get_bytecode(address), Some(entry.bytecode.clone()));
+            assert_eq!(
+                target.sorted_ledger.get_entire_datastore(address),
+                entry.datastore
+            );
+        }
+        let mut reexported = Vec::new();
+        assert_eq!(target.write_snapshot(&mut reexported).unwrap(), info);
+        assert_eq!(reexported, bytes);
+    }
+
+    #[test]
+    fn test_ledger_snapshot_corruption() {
+        let dir = TempDir::new().unwrap();
+        let mut ledger = new_ledger(dir.path());
+        ledger.load_initial_entries(sample_entries());
+        let mut bytes = Vec::new();
+        ledger.write_snapshot(&mut bytes).unwrap();
+
+        let read = |bytes: &[u8]| {
+            read_ledger_snapshot(
+                bytes,
+                MAX_DATASTORE_KEY_LENGTH,
+                MAX_DATASTORE_VALUE_LENGTH,
+                |_, _| {},
+            )
+        };
+        assert!(read(&bytes).is_ok());
+
+        let mut corrupted = bytes.clone();
+        corrupted[20] ^= 1;
+        assert!(read(&corrupted).is_err());
+        assert!(read(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(read(&trailing).is_err());
+    }
+}
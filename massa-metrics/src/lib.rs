@@ -13,7 +13,10 @@ use std::{
 };
 
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntGauge};
+use prometheus::{
+    register_int_gauge, Counter, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec,
+};
 use tokio::sync::oneshot::Sender;
 use tracing::warn;
 
@@ -98,6 +101,10 @@ pub struct MassaMetrics {
     endorsements_pool: IntGauge,
     /// number of elements in the denunciation pool
     denunciations_pool: IntGauge,
+    /// fraction of the endorsements available in the pool for the last blocks produced by the node
+    endorsement_fill_rate: Gauge,
+    /// number of endorsements missing from the last blocks produced by the node
+    endorsements_missed: IntGauge,
 
     // number of autonomous SCs messages in pool
     async_message_pool_size: IntGauge,
@@ -105,18 +112,68 @@ pub struct MassaMetrics {
     // number of autonomous SC messages executed as final
     sc_messages_final: IntCounter,
 
+    /// number of final SC output events pruned from the disk store because it was full
+    final_events_truncated: IntCounter,
+    /// number of slot execution outputs dropped for subscribers lagging behind the broadcast
+    slot_execution_outputs_dropped: IntCounter,
+
+    /// size on disk of the final state database, in bytes
+    final_state_disk_usage: IntGauge,
+    /// estimated time before the disk holding the final state database is full, in seconds (-1 if unknown)
+    final_state_disk_time_to_full: IntGauge,
+
+    /// size of the SST files of the final state database, per column family
+    db_sst_files_size: IntGaugeVec,
+    /// estimated number of bytes that the final state database compactions need to rewrite
+    db_pending_compaction_bytes: IntGauge,
+    /// number of running compactions of the final state database
+    db_running_compactions: IntGauge,
+    /// number of batches written to the final state database
+    db_writes: IntCounter,
+    /// time spent writing batches to the final state database, in seconds
+    db_write_duration: Counter,
+    /// number of point reads of the final state database
+    db_reads: IntCounter,
+    /// time spent in point reads of the final state database, in seconds
+    db_read_duration: Counter,
+    /// number of change_ids in the final state change history, per column family
+    db_change_history_length: IntGaugeVec,
+    /// size of the keys and values of the final state change history, per column family
+    db_change_history_bytes: IntGaugeVec,
+
     /// number of times our node (re-)bootstrapped
     bootstrap_counter: IntCounter,
     /// number of times we successfully bootstrapped someone
     bootstrap_peers_success: IntCounter,
     /// number of times we failed/refused to bootstrap someone
     bootstrap_peers_failed: IntCounter,
+    /// number of bootstrap sessions started by our bootstrap server
+    bootstrap_sessions_started: IntCounter,
+    /// number of bootstrap sessions of our bootstrap server that completed successfully
+    bootstrap_sessions_completed: IntCounter,
+    /// number of bootstrap sessions of our bootstrap server that failed
+    bootstrap_sessions_failed: IntCounter,
+    /// number of running bootstrap sessions of our bootstrap server
+    bootstrap_sessions_active: IntGauge,
+    /// duration of the bootstrap sessions of our bootstrap server, in seconds
+    bootstrap_session_duration: Histogram,
+    /// number of bytes of the final state streamed by our bootstrap server, per column family
+    bootstrap_streamed_bytes: IntCounterVec,
+    /// number of bootstrap attempts refused because the IP bootstrapped too recently
+    bootstrap_cooldown_refusals: IntCounter,
 
     /// number of times we successfully tested someone
     protocol_tester_success: IntCounter,
     /// number of times we failed to test someone
     protocol_tester_failed: IntCounter,
 
+    /// number of operations of retrieved blocks that were already known locally
+    block_ops_reconstructed: IntCounter,
+    /// number of operations of retrieved blocks that had to be fetched from peers
+    block_ops_fetched: IntCounter,
+    /// number of times the operations of a block had to be asked again because a peer did not send them all
+    block_ops_fetch_fallbacks: IntCounter,
+
     /// know peers in protocol
     protocol_known_peers: IntGauge,
     /// banned peers in protocol
@@ -158,6 +215,7 @@ pub struct MassaMetrics {
     consensus_state_incoming_index: IntGauge,
     consensus_state_discarded_index: IntGauge,
     consensus_state_block_statuses: IntGauge,
+    consensus_discarded_blocks: IntCounterVec,
 
     // endorsement cache
     endorsement_cache_checked_endorsements: IntGauge,
@@ -173,6 +231,15 @@ pub struct MassaMetrics {
     // peer bandwidth (bytes sent, bytes received)
     peers_bandwidth: Arc<RwLock<HashMap<String, (IntCounter, IntCounter)>>>,
 
+    /// number of gRPC requests per service and method
+    grpc_requests: IntCounterVec,
+    /// number of gRPC responses per service, method and status code
+    grpc_responses: IntCounterVec,
+    /// time spent by the gRPC handlers before responding, per service and method
+    grpc_request_duration: HistogramVec,
+    /// number of open gRPC calls (including server streams) per service and method
+    grpc_active_streams: IntGaugeVec,
+
     pub tick_delay: Duration,
 }
 
@@ -233,6 +300,21 @@ impl MassaMetrics {
             "number of times we failed to test someone",
         )
         .unwrap();
+        let block_ops_reconstructed = IntCounter::new(
+            "block_ops_reconstructed",
+            "number of operations of retrieved blocks that were already known locally",
+        )
+        .unwrap();
+        let block_ops_fetched = IntCounter::new(
+            "block_ops_fetched",
+            "number of operations of retrieved blocks that had to be fetched from peers",
+        )
+        .unwrap();
+        let block_ops_fetch_fallbacks = IntCounter::new(
+            "block_ops_fetch_fallbacks",
+            "number of times the operations of a block had to be asked again because a peer did not send them all",
+        )
+        .unwrap();
 
         // pool
         let operations_pool = IntGauge::new(
@@ -250,6 +332,17 @@ impl MassaMetrics {
             "number of elements in the denunciation pool",
         )
         .unwrap();
+        let endorsement_fill_rate = Gauge::new(
+            "endorsement_fill_rate",
+            "fraction of the endorsements available in the pool for the last blocks produced by the node",
+        )
+        .unwrap();
+        endorsement_fill_rate.set(1.0);
+        let endorsements_missed = IntGauge::new(
+            "endorsements_missed",
+            "number of endorsements missing from the last blocks produced by the node",
+        )
+        .unwrap();
 
         let async_message_pool_size = IntGauge::new(
             "async_message_pool_size",
@@ -257,12 +350,92 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let final_state_disk_usage = IntGauge::new(
+            "final_state_disk_usage",
+            "size on disk of the final state database, in bytes",
+        )
+        .unwrap();
+
+        let final_state_disk_time_to_full = IntGauge::new(
+            "final_state_disk_time_to_full",
+            "estimated time before the disk holding the final state database is full, in seconds (-1 if unknown)",
+        )
+        .unwrap();
+        final_state_disk_time_to_full.set(-1);
+
+        let db_sst_files_size = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "db_sst_files_size",
+                "size of the SST files of the final state database, in bytes",
+            ),
+            &["cf"],
+        )
+        .unwrap();
+        let db_pending_compaction_bytes = IntGauge::new(
+            "db_pending_compaction_bytes",
+            "estimated number of bytes that the final state database compactions need to rewrite",
+        )
+        .unwrap();
+        let db_running_compactions = IntGauge::new(
+            "db_running_compactions",
+            "number of running compactions of the final state database",
+        )
+        .unwrap();
+        let db_writes = IntCounter::new(
+            "db_writes",
+            "number of batches written to the final state database",
+        )
+        .unwrap();
+        let db_write_duration = Counter::new(
+            "db_write_duration",
+            "time spent writing batches to the final state database, in seconds",
+        )
+        .unwrap();
+        let db_reads = IntCounter::new(
+            "db_reads",
+            "number of point reads of the final state database",
+        )
+        .unwrap();
+        let db_read_duration = Counter::new(
+            "db_read_duration",
+            "time spent in point reads of the final state database, in seconds",
+        )
+        .unwrap();
+        let db_change_history_length = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "db_change_history_length",
+                "number of change_ids in the final state change history",
+            ),
+            &["cf"],
+        )
+        .unwrap();
+        let db_change_history_bytes = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "db_change_history_bytes",
+                "size of the keys and values of the final state change history, in bytes",
+            ),
+            &["cf"],
+        )
+        .unwrap();
+
         let sc_messages_final = IntCounter::new(
             "sc_messages_final",
             "number of autonomous SC messages executed as final",
         )
         .unwrap();
 
+        let final_events_truncated = IntCounter::new(
+            "final_events_truncated",
+            "number of final SC output events pruned from the disk store because it was full",
+        )
+        .unwrap();
+
+        let slot_execution_outputs_dropped = IntCounter::new(
+            "slot_execution_outputs_dropped",
+            "number of slot execution outputs dropped for subscribers lagging behind the broadcast",
+        )
+        .unwrap();
+
         let bootstrap_counter = IntCounter::new(
             "bootstrap_counter",
             "number of times our node (re-)bootstrapped",
@@ -278,6 +451,49 @@ impl MassaMetrics {
             "number of times we failed/refused to bootstrap someone",
         )
         .unwrap();
+        let bootstrap_sessions_started = IntCounter::new(
+            "bootstrap_sessions_started",
+            "number of bootstrap sessions started by our bootstrap server",
+        )
+        .unwrap();
+        let bootstrap_sessions_completed = IntCounter::new(
+            "bootstrap_sessions_completed",
+            "number of bootstrap sessions of our bootstrap server that completed successfully",
+        )
+        .unwrap();
+        let bootstrap_sessions_failed = IntCounter::new(
+            "bootstrap_sessions_failed",
+            "number of bootstrap sessions of our bootstrap server that failed",
+        )
+        .unwrap();
+        let bootstrap_sessions_active = IntGauge::new(
+            "bootstrap_sessions_active",
+            "number of running bootstrap sessions of our bootstrap server",
+        )
+        .unwrap();
+        let bootstrap_session_duration = Histogram::with_opts(
+            prometheus::HistogramOpts::new(
+                "bootstrap_session_duration",
+                "duration of the bootstrap sessions of our bootstrap server, in seconds",
+            )
+            .buckets(vec![
+                1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0,
+            ]),
+        )
+        .unwrap();
+        let bootstrap_streamed_bytes = IntCounterVec::new(
+            prometheus::Opts::new(
+                "bootstrap_streamed_bytes",
+                "number of bytes of the final state streamed by our bootstrap server",
+            ),
+            &["cf"],
+        )
+        .unwrap();
+        let bootstrap_cooldown_refusals = IntCounter::new(
+            "bootstrap_cooldown_refusals",
+            "number of bootstrap attempts refused because the IP bootstrapped too recently",
+        )
+        .unwrap();
 
         let active_history = IntGauge::new(
             "active_history",
@@ -376,6 +592,15 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let consensus_discarded_blocks = IntCounterVec::new(
+            prometheus::Opts::new(
+                "consensus_discarded_blocks",
+                "number of blocks discarded by consensus per reason",
+            ),
+            &["reason"],
+        )
+        .unwrap();
+
         let endorsement_cache_checked_endorsements = IntGauge::new(
             "endorsement_cache_checked_endorsements",
             "endorsement cache checked endorsements size",
@@ -407,6 +632,36 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let grpc_requests = IntCounterVec::new(
+            prometheus::Opts::new("grpc_requests", "number of gRPC requests"),
+            &["service", "method"],
+        )
+        .unwrap();
+        let grpc_responses = IntCounterVec::new(
+            prometheus::Opts::new("grpc_responses", "number of gRPC responses per status code"),
+            &["service", "method", "code"],
+        )
+        .unwrap();
+        let grpc_request_duration = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "grpc_request_duration",
+                "time spent by the gRPC handlers before responding, in seconds",
+            )
+            .buckets(vec![
+                0.001, 0.005, 0.010, 0.025, 0.050, 0.100, 0.250, 0.500, 1.0, 2.5, 5.0, 10.0,
+            ]),
+            &["service", "method"],
+        )
+        .unwrap();
+        let grpc_active_streams = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "grpc_active_streams",
+                "number of open gRPC calls, including server streams",
+            ),
+            &["service", "method"],
+        )
+        .unwrap();
+
         let mut stopper = MetricsStopper::default();
 
         if enabled {
@@ -429,6 +684,7 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(consensus_state_incoming_index.clone()));
                 let _ = prometheus::register(Box::new(consensus_state_discarded_index.clone()));
                 let _ = prometheus::register(Box::new(consensus_state_block_statuses.clone()));
+                let _ = prometheus::register(Box::new(consensus_discarded_blocks.clone()));
                 let _ = prometheus::register(Box::new(
                     operation_cache_checked_operations_prefix.clone(),
                 ));
@@ -448,17 +704,46 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(bootstrap_counter.clone()));
                 let _ = prometheus::register(Box::new(bootstrap_success.clone()));
                 let _ = prometheus::register(Box::new(bootstrap_failed.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_sessions_started.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_sessions_completed.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_sessions_failed.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_sessions_active.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_session_duration.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_streamed_bytes.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_cooldown_refusals.clone()));
                 let _ = prometheus::register(Box::new(process_available_processors.clone()));
                 let _ = prometheus::register(Box::new(operations_pool.clone()));
                 let _ = prometheus::register(Box::new(endorsements_pool.clone()));
                 let _ = prometheus::register(Box::new(denunciations_pool.clone()));
+                let _ = prometheus::register(Box::new(endorsement_fill_rate.clone()));
+                let _ = prometheus::register(Box::new(endorsements_missed.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_success.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_failed.clone()));
+                let _ = prometheus::register(Box::new(block_ops_reconstructed.clone()));
+                let _ = prometheus::register(Box::new(block_ops_fetched.clone()));
+                let _ = prometheus::register(Box::new(block_ops_fetch_fallbacks.clone()));
                 let _ = prometheus::register(Box::new(sc_messages_final.clone()));
+                let _ = prometheus::register(Box::new(final_events_truncated.clone()));
+                let _ = prometheus::register(Box::new(slot_execution_outputs_dropped.clone()));
                 let _ = prometheus::register(Box::new(async_message_pool_size.clone()));
+                let _ = prometheus::register(Box::new(final_state_disk_usage.clone()));
+                let _ = prometheus::register(Box::new(final_state_disk_time_to_full.clone()));
+                let _ = prometheus::register(Box::new(db_sst_files_size.clone()));
+                let _ = prometheus::register(Box::new(db_pending_compaction_bytes.clone()));
+                let _ = prometheus::register(Box::new(db_running_compactions.clone()));
+                let _ = prometheus::register(Box::new(db_writes.clone()));
+                let _ = prometheus::register(Box::new(db_write_duration.clone()));
+                let _ = prometheus::register(Box::new(db_reads.clone()));
+                let _ = prometheus::register(Box::new(db_read_duration.clone()));
+                let _ = prometheus::register(Box::new(db_change_history_length.clone()));
+                let _ = prometheus::register(Box::new(db_change_history_bytes.clone()));
                 let _ = prometheus::register(Box::new(current_time_period.clone()));
                 let _ = prometheus::register(Box::new(current_time_thread.clone()));
                 let _ = prometheus::register(Box::new(block_slot_delay.clone()));
+                let _ = prometheus::register(Box::new(grpc_requests.clone()));
+                let _ = prometheus::register(Box::new(grpc_responses.clone()));
+                let _ = prometheus::register(Box::new(grpc_request_duration.clone()));
+                let _ = prometheus::register(Box::new(grpc_active_streams.clone()));
 
                 stopper = server::bind_metrics(addr);
             }
@@ -477,13 +762,38 @@ impl MassaMetrics {
                 operations_pool,
                 endorsements_pool,
                 denunciations_pool,
+                endorsement_fill_rate,
+                endorsements_missed,
                 async_message_pool_size,
                 sc_messages_final,
+                final_events_truncated,
+                slot_execution_outputs_dropped,
+                final_state_disk_usage,
+                final_state_disk_time_to_full,
+                db_sst_files_size,
+                db_pending_compaction_bytes,
+                db_running_compactions,
+                db_writes,
+                db_write_duration,
+                db_reads,
+                db_read_duration,
+                db_change_history_length,
+                db_change_history_bytes,
                 bootstrap_counter,
                 bootstrap_peers_success: bootstrap_success,
                 bootstrap_peers_failed: bootstrap_failed,
+                bootstrap_sessions_started,
+                bootstrap_sessions_completed,
+                bootstrap_sessions_failed,
+                bootstrap_sessions_active,
+                bootstrap_session_duration,
+                bootstrap_streamed_bytes,
+                bootstrap_cooldown_refusals,
                 protocol_tester_success,
                 protocol_tester_failed,
+                block_ops_reconstructed,
+                block_ops_fetched,
+                block_ops_fetch_fallbacks,
                 protocol_known_peers: know_peers,
                 protocol_banned_peers: banned_peers,
                 executed_final_slot,
@@ -504,6 +814,7 @@ impl MassaMetrics {
                 consensus_state_incoming_index,
                 consensus_state_discarded_index,
                 consensus_state_block_statuses,
+                consensus_discarded_blocks,
                 endorsement_cache_checked_endorsements,
                 endorsement_cache_known_by_peer,
                 // blocks_counter,
@@ -514,6 +825,10 @@ impl MassaMetrics {
                 final_cursor_thread,
                 final_cursor_period,
                 peers_bandwidth: Arc::new(RwLock::new(HashMap::new())),
+                grpc_requests,
+                grpc_responses,
+                grpc_request_duration,
+                grpc_active_streams,
                 tick_delay,
             },
             stopper,
@@ -573,6 +888,12 @@ impl MassaMetrics {
             .set(active_index_without_ops as i64);
     }
 
+    pub fn inc_consensus_discarded_blocks(&self, reason: &str) {
+        self.consensus_discarded_blocks
+            .with_label_values(&[reason])
+            .inc();
+    }
+
     pub fn set_block_cache_metrics(&self, checked_header_size: usize, blocks_known_by_peer: usize) {
         self.block_cache_checked_headers_size
             .set(checked_header_size as i64);
@@ -651,6 +972,39 @@ impl MassaMetrics {
         self.bootstrap_peers_failed.inc();
     }
 
+    /// Account for a new session of our bootstrap server
+    pub fn inc_bootstrap_sessions_started(&self) {
+        self.bootstrap_sessions_started.inc();
+        self.bootstrap_sessions_active.inc();
+    }
+
+    /// Account for a session of our bootstrap server releasing its slot
+    pub fn dec_bootstrap_sessions_active(&self) {
+        self.bootstrap_sessions_active.dec();
+    }
+
+    pub fn inc_bootstrap_sessions_completed(&self, duration: Duration) {
+        self.bootstrap_sessions_completed.inc();
+        self.bootstrap_session_duration
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn inc_bootstrap_sessions_failed(&self, duration: Duration) {
+        self.bootstrap_sessions_failed.inc();
+        self.bootstrap_session_duration
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn inc_bootstrap_streamed_bytes(&self, cf: &str, bytes: u64) {
+        self.bootstrap_streamed_bytes
+            .with_label_values(&[cf])
+            .inc_by(bytes);
+    }
+
+    pub fn inc_bootstrap_cooldown_refusals(&self) {
+        self.bootstrap_cooldown_refusals.inc();
+    }
+
     pub fn set_operations_pool(&self, nb: usize) {
         self.operations_pool.set(nb as i64);
     }
@@ -663,6 +1017,11 @@ impl MassaMetrics {
         self.denunciations_pool.set(nb as i64);
     }
 
+    pub fn set_endorsement_pool_health(&self, fill_rate: f64, missed: u64) {
+        self.endorsement_fill_rate.set(fill_rate);
+        self.endorsements_missed.set(missed as i64);
+    }
+
     pub fn inc_protocol_tester_success(&self) {
         self.protocol_tester_success.inc();
     }
@@ -671,6 +1030,18 @@ impl MassaMetrics {
         self.protocol_tester_failed.inc();
     }
 
+    /// Record how many operations of a retrieved block were found locally and how many were missing
+    pub fn inc_block_reconstruction(&self, found: usize, missing: usize) {
+        self.block_ops_reconstructed.inc_by(found as u64);
+        self.block_ops_fetched.inc_by(missing as u64);
+    }
+
+    /// Record that the operations of a block are asked to another peer,
+    /// the previous one having sent only part of them or not answered in time
+    pub fn inc_block_ops_fetch_fallbacks(&self) {
+        self.block_ops_fetch_fallbacks.inc();
+    }
+
     pub fn set_stakers(&self, nb: usize) {
         self.stakers.set(nb as i64);
     }
@@ -683,10 +1054,62 @@ impl MassaMetrics {
         self.sc_messages_final.inc_by(diff as u64);
     }
 
+    pub fn inc_final_events_truncated_by(&self, diff: u64) {
+        self.final_events_truncated.inc_by(diff);
+    }
+
+    pub fn inc_slot_execution_outputs_dropped(&self) {
+        self.slot_execution_outputs_dropped.inc();
+    }
+
     pub fn set_async_message_pool_size(&self, nb: usize) {
         self.async_message_pool_size.set(nb as i64);
     }
 
+    pub fn set_final_state_disk_usage(&self, size: u64, time_to_full_secs: Option<u64>) {
+        self.final_state_disk_usage.set(size as i64);
+        self.final_state_disk_time_to_full
+            .set(time_to_full_secs.map(|secs| secs as i64).unwrap_or(-1));
+    }
+
+    pub fn set_db_sst_files_size(&self, cf: &str, size: u64) {
+        self.db_sst_files_size
+            .with_label_values(&[cf])
+            .set(size as i64);
+    }
+
+    pub fn set_db_compactions(&self, pending_bytes: u64, running: u64) {
+        self.db_pending_compaction_bytes.set(pending_bytes as i64);
+        self.db_running_compactions.set(running as i64);
+    }
+
+    /// Update the DB operation counters from the totals since the database was opened
+    pub fn set_db_operations(
+        &self,
+        writes: u64,
+        write_duration: f64,
+        reads: u64,
+        read_duration: f64,
+    ) {
+        self.db_writes
+            .inc_by(writes.saturating_sub(self.db_writes.get()));
+        self.db_write_duration
+            .inc_by((write_duration - self.db_write_duration.get()).max(0.0));
+        self.db_reads
+            .inc_by(reads.saturating_sub(self.db_reads.get()));
+        self.db_read_duration
+            .inc_by((read_duration - self.db_read_duration.get()).max(0.0));
+    }
+
+    pub fn set_db_change_history(&self, cf: &str, length: usize, bytes: usize) {
+        self.db_change_history_length
+            .with_label_values(&[cf])
+            .set(length as i64);
+        self.db_change_history_bytes
+            .with_label_values(&[cf])
+            .set(bytes as i64);
+    }
+
     pub fn set_available_processors(&self, nb: usize) {
         self.process_available_processors.set(nb as i64);
     }
@@ -703,6 +1126,35 @@ impl MassaMetrics {
         self.block_slot_delay.observe(delay);
     }
 
+    /// Record a gRPC request, once its handler has responded after `duration` seconds
+    pub fn inc_grpc_requests(&self, service: &str, method: &str, duration: f64) {
+        self.grpc_requests
+            .with_label_values(&[service, method])
+            .inc();
+        self.grpc_request_duration
+            .with_label_values(&[service, method])
+            .observe(duration);
+    }
+
+    /// Record the status code of a finished gRPC call
+    pub fn inc_grpc_responses(&self, service: &str, method: &str, code: &str) {
+        self.grpc_responses
+            .with_label_values(&[service, method, code])
+            .inc();
+    }
+
+    pub fn inc_grpc_active_streams(&self, service: &str, method: &str) {
+        self.grpc_active_streams
+            .with_label_values(&[service, method])
+            .inc();
+    }
+
+    pub fn dec_grpc_active_streams(&self, service: &str, method: &str) {
+        self.grpc_active_streams
+            .with_label_values(&[service, method])
+            .dec();
+    }
+
     /// Update the bandwidth metrics for all peers
     /// HashMap<peer_id, (tx, rx)>
     pub fn update_peers_tx_rx(&self, data: HashMap<String, (u64, u64)>) {
@@ -18,6 +18,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer};
 use crate::prehash::PreHashSet;
+use crate::slot::Slot;
+use massa_hash::Hash;
 use std::ops::Bound::{Excluded, Included};
 
 /// Mutually compatible blocks in the graph
@@ -42,6 +44,32 @@ impl Default for Clique {
     }
 }
 
+/// Change of blockclique that demoted blocks of the previous blockclique
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BlockcliqueReorg {
+    /// fingerprint of the previous blockclique
+    pub old_blockclique_fingerprint: Hash,
+    /// fingerprint of the new blockclique
+    pub new_blockclique_fingerprint: Hash,
+    /// non-final blocks of the previous blockclique that left it, sorted by slot
+    pub demoted_blocks: Vec<(Slot, BlockId)>,
+    /// blocks that joined the new blockclique, sorted by slot
+    pub promoted_blocks: Vec<(Slot, BlockId)>,
+    /// sorted slots of the demoted and promoted blocks
+    pub affected_slots: Vec<Slot>,
+}
+
+/// Fingerprint of a blockclique: hash of its sorted block ids
+pub fn blockclique_fingerprint<'a>(block_ids: impl IntoIterator<Item = &'a BlockId>) -> Hash {
+    let mut block_ids: Vec<&BlockId> = block_ids.into_iter().collect();
+    block_ids.sort_unstable();
+    let bytes: Vec<&[u8]> = block_ids
+        .iter()
+        .map(|block_id| block_id.0.to_bytes().as_slice())
+        .collect();
+    Hash::compute_from_tuple(&bytes)
+}
+
 /// Basic serializer for `Clique`
 #[derive(Default)]
 pub struct CliqueSerializer {
@@ -141,6 +141,8 @@ pub const MAX_BACKUPS_TO_KEEP: Option<usize> = Some(10);
 /// See https://github.com/massalabs/massa/pull/3871
 /// 1 for pruned cycle safety during bootstrap
 pub const POS_SAVED_CYCLES: usize = 7;
+/// Number of past cycles whose production stats are kept after leaving the PoS cycle history
+pub const POS_PRODUCTION_STATS_HISTORY_CYCLES: u64 = 100;
 /// Number of cycle draws saved in the selector cache
 ///
 /// 5 to have a C-2 to C+2 range (6 cycles post-bootstrap give 5 cycle draws)
@@ -212,6 +214,9 @@ pub const MAX_BOOTSTRAP_ASYNC_POOL_CHANGES: u64 = 100_000;
 pub const MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE: u64 = 1_000_000_000;
 /// Max bytes in final states parts
 pub const MAX_BOOTSTRAPPED_NEW_ELEMENTS: u64 = 500;
+/// Max bytes of keys and values in each of the state and versioning parts of a bootstrap message,
+/// leaving room for the other parts of the message
+pub const MAX_BOOTSTRAPPED_STREAM_BATCH_SIZE: u64 = MAX_BOOTSTRAP_MESSAGE_SIZE as u64 / 4;
 /// Max size of the IP list
 pub const IP_LIST_MAX_SIZE: usize = 10000;
 /// Size of the random bytes array used for the bootstrap, safe to import
@@ -8,8 +8,9 @@ use nom::error::{context, ContextError, ParseError};
 use nom::multi::length_count;
 use nom::sequence::tuple;
 use nom::{IResult, Parser};
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::ops::Bound::Included;
+use std::ops::Bound::{self, Excluded, Included};
 
 /// Datastore entry for Ledger & `ExecuteSC` Operation
 /// A Datastore is a Key Value store where
@@ -169,6 +170,69 @@ pub fn get_prefix_bounds(prefix: &[u8]) -> (std::ops::Bound<Vec<u8>>, std::ops::
     )
 }
 
+/// Selection of a page of the datastore keys of an address.
+///
+/// The selected keys start with `prefix`, are within `[start_key, end_key)`
+/// and are strictly greater than `cursor`, in lexicographic order.
+/// At most `limit` of them are returned: to get the next page,
+/// set `cursor` to the last key of the current one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatastoreKeysFilter {
+    /// only select the keys starting with this prefix
+    #[serde(default)]
+    pub prefix: Vec<u8>,
+    /// only select the keys greater than or equal to this key
+    #[serde(default)]
+    pub start_key: Option<Vec<u8>>,
+    /// only select the keys strictly lower than this key
+    #[serde(default)]
+    pub end_key: Option<Vec<u8>>,
+    /// only select the keys strictly greater than this key, usually the last key of the previous page
+    #[serde(default)]
+    pub cursor: Option<Vec<u8>>,
+    /// maximum number of selected keys
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl DatastoreKeysFilter {
+    /// Creates a filter selecting all the keys starting with `prefix`
+    pub fn from_prefix(prefix: &[u8]) -> Self {
+        DatastoreKeysFilter {
+            prefix: prefix.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the lower (included) and upper bounds of the selected keys, regardless of the limit.
+    /// Returns `None` if no key can be selected.
+    pub fn key_bounds(&self) -> Option<(Vec<u8>, Bound<Vec<u8>>)> {
+        let mut lower = self.prefix.clone();
+        if let Some(start_key) = &self.start_key {
+            lower = lower.max(start_key.clone());
+        }
+        if let Some(cursor) = &self.cursor {
+            // the smallest key strictly greater than the cursor
+            let mut after_cursor = cursor.clone();
+            after_cursor.push(0);
+            lower = lower.max(after_cursor);
+        }
+
+        let mut upper = get_prefix_bounds(&self.prefix).1;
+        if let Some(end_key) = &self.end_key {
+            upper = match upper {
+                Excluded(prefix_end) if &prefix_end <= end_key => Excluded(prefix_end),
+                _ => Excluded(end_key.clone()),
+            };
+        }
+
+        match &upper {
+            Excluded(upper_key) if &lower >= upper_key => None,
+            _ => Some((lower, upper)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -233,4 +297,44 @@ mod tests {
             .deserialize::<DeserializeError>(&buffer)
             .unwrap();
     }
+
+    #[test]
+    fn test_datastore_keys_filter_bounds() {
+        // the cursor is excluded, the start key included
+        let filter = DatastoreKeysFilter {
+            prefix: vec![1],
+            start_key: Some(vec![1, 2]),
+            cursor: Some(vec![1, 5]),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.key_bounds(),
+            Some((vec![1, 5, 0], Bound::Excluded(vec![2])))
+        );
+
+        // the end key restricts the prefix range
+        let filter = DatastoreKeysFilter {
+            prefix: vec![1],
+            end_key: Some(vec![1, 3]),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.key_bounds(),
+            Some((vec![1], Bound::Excluded(vec![1, 3])))
+        );
+
+        // no bound at all
+        assert_eq!(
+            DatastoreKeysFilter::default().key_bounds(),
+            Some((vec![], Bound::Unbounded))
+        );
+
+        // empty selection
+        let filter = DatastoreKeysFilter {
+            start_key: Some(vec![3]),
+            end_key: Some(vec![3]),
+            ..Default::default()
+        };
+        assert_eq!(filter.key_bounds(), None);
+    }
 }
@@ -1,6 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::{address::Address, operation::OperationId, slot::Slot};
+use crate::{address::Address, operation::OperationId, output_event::SCOutputEvent, slot::Slot};
 use serde::{Deserialize, Serialize};
 
 /// filter used when retrieving SC output events
@@ -28,4 +28,91 @@ pub struct EventFilter {
     /// Some(false) means events coming from a succeeded sc execution
     /// None means both
     pub is_error: Option<bool>,
+    /// optional number of matching events to skip, oldest first
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// optional maximum number of events to return
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl EventFilter {
+    /// Checks whether an event matches the filter, regardless of the pagination
+    pub fn matches(&self, event: &SCOutputEvent) -> bool {
+        if let Some(start) = self.start {
+            if event.context.slot < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.end {
+            if event.context.slot >= end {
+                return false;
+            }
+        }
+        if let Some(is_final) = self.is_final {
+            if event.context.is_final != is_final {
+                return false;
+            }
+        }
+        if let Some(is_error) = self.is_error {
+            if event.context.is_error != is_error {
+                return false;
+            }
+        }
+        match (self.emitter_address, event.context.call_stack.front()) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (
+            self.original_caller_address,
+            event.context.call_stack.back(),
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (
+            self.original_operation_id,
+            event.context.origin_operation_id,
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        true
+    }
+}
+
+/// filter used when retrieving the native coin transfers of an address
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct TransferFilter {
+    /// address spending or receiving the coins
+    pub address: Address,
+    /// optional start slot (included)
+    #[serde(default)]
+    pub start: Option<Slot>,
+    /// optional end slot (excluded)
+    #[serde(default)]
+    pub end: Option<Slot>,
+    /// optional transfer status
+    ///
+    /// Some(true) means final
+    /// Some(false) means candidate
+    /// None means final _and_ candidate
+    #[serde(default)]
+    pub is_final: Option<bool>,
+    /// optional number of matching transfers to skip, oldest first
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// optional maximum number of transfers to return
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl TransferFilter {
+    /// Checks whether a slot is in the range of the filter
+    pub fn contains_slot(&self, slot: &Slot) -> bool {
+        self.start.map_or(true, |start| *slot >= start) && self.end.map_or(true, |end| *slot < end)
+    }
 }
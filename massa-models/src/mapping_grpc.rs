@@ -191,6 +191,8 @@ impl From<OperationType> for grpc_model::OperationType {
                 grpc_operation_type.r#type =
                     Some(grpc_model::operation_type::Type::RollSell(roll_sell));
             }
+            // no gRPC message exists yet for roll transfers: the type is left unset
+            OperationType::RollTransfer { .. } => {}
             OperationType::ExecuteSC {
                 data,
                 max_gas,
@@ -242,15 +244,16 @@ impl From<Operation> for grpc_model::Operation {
     }
 }
 
-impl From<OperationType> for grpc_model::OpType {
-    fn from(value: OperationType) -> Self {
-        match value {
-            OperationType::Transaction { .. } => grpc_model::OpType::Transaction,
-            OperationType::RollBuy { .. } => grpc_model::OpType::RollBuy,
-            OperationType::RollSell { .. } => grpc_model::OpType::RollSell,
-            OperationType::ExecuteSC { .. } => grpc_model::OpType::ExecuteSc,
-            OperationType::CallSC { .. } => grpc_model::OpType::CallSc,
-        }
+/// Converts an OperationType into its gRPC `grpc_model::OpType`
+/// Returns `None` for roll transfers, which have no gRPC operation type yet
+pub fn to_op_type(value: &OperationType) -> Option<grpc_model::OpType> {
+    match value {
+        OperationType::Transaction { .. } => Some(grpc_model::OpType::Transaction),
+        OperationType::RollBuy { .. } => Some(grpc_model::OpType::RollBuy),
+        OperationType::RollSell { .. } => Some(grpc_model::OpType::RollSell),
+        OperationType::RollTransfer { .. } => None,
+        OperationType::ExecuteSC { .. } => Some(grpc_model::OpType::ExecuteSc),
+        OperationType::CallSC { .. } => Some(grpc_model::OpType::CallSc),
     }
 }
 
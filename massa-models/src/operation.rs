@@ -387,6 +387,50 @@ enum OperationTypeId {
     RollSell = 2,
     ExecuteSC = 3,
     CallSC = 4,
+    RollTransfer = 5,
+}
+
+/// Way a pending operation left the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationLifecycleStatus {
+    /// included in a block, as seen by its execution in a candidate or final slot
+    Included,
+    /// expired before being included in a block
+    Expired,
+    /// evicted by operations paying a higher fee per gas, the pool being full
+    Evicted,
+}
+
+/// Event of the lifecycle of a pending operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationLifecycleEvent {
+    /// ID of the operation
+    pub operation_id: OperationId,
+    /// address of the creator of the operation
+    pub creator_address: Address,
+    /// way the operation left the pool
+    pub status: OperationLifecycleStatus,
+}
+
+/// Selection of the operation lifecycle events to follow
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationLifecycleFilter {
+    /// IDs of the operations to follow
+    #[serde(default)]
+    pub operation_ids: Vec<OperationId>,
+    /// addresses of the senders whose operations are followed
+    #[serde(default)]
+    pub creator_addresses: Vec<Address>,
+}
+
+impl OperationLifecycleFilter {
+    /// Whether an event is selected: an empty filter selects all the events,
+    /// otherwise the operation or its creator must be listed
+    pub fn matches(&self, event: &OperationLifecycleEvent) -> bool {
+        (self.operation_ids.is_empty() && self.creator_addresses.is_empty())
+            || self.operation_ids.contains(&event.operation_id)
+            || self.creator_addresses.contains(&event.creator_address)
+    }
 }
 
 /// the operation as sent in the network
@@ -589,6 +633,13 @@ pub enum OperationType {
         /// roll count
         roll_count: u64,
     },
+    /// the sender gives `roll_count` of its rolls to the recipient, without selling them
+    RollTransfer {
+        /// recipient address
+        recipient_address: Address,
+        /// roll count
+        roll_count: u64,
+    },
     /// Execute a smart contract.
     ExecuteSC {
         /// Smart contract bytecode.
@@ -635,6 +686,14 @@ impl std::fmt::Display for OperationType {
                 writeln!(f, "Sell rolls:")?;
                 writeln!(f, "\t- Roll count:{}", roll_count)?;
             }
+            OperationType::RollTransfer {
+                recipient_address,
+                roll_count,
+            } => {
+                writeln!(f, "Transfer rolls:")?;
+                writeln!(f, "\t- Recipient:{}", recipient_address)?;
+                writeln!(f, "\t- Roll count:{}", roll_count)?;
+            }
             OperationType::ExecuteSC {
                 max_gas,
                 max_coins,
@@ -737,6 +796,16 @@ impl Serializer<OperationType> for OperationTypeSerializer {
                     .serialize(&u32::from(OperationTypeId::RollSell), buffer)?;
                 self.u64_serializer.serialize(roll_count, buffer)?;
             }
+            OperationType::RollTransfer {
+                recipient_address,
+                roll_count,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::RollTransfer), buffer)?;
+                self.address_serializer
+                    .serialize(recipient_address, buffer)?;
+                self.u64_serializer.serialize(roll_count, buffer)?;
+            }
             OperationType::ExecuteSC {
                 data,
                 max_gas,
@@ -898,6 +967,24 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
                 })
                 .map(|roll_count| OperationType::RollSell { roll_count })
                 .parse(input),
+                OperationTypeId::RollTransfer => context(
+                    "Failed RollTransfer deserialization",
+                    tuple((
+                        context("Failed recipient_address deserialization", |input| {
+                            self.address_deserializer.deserialize(input)
+                        }),
+                        context("Failed roll_count deserialization", |input| {
+                            self.rolls_number_deserializer.deserialize(input)
+                        }),
+                    )),
+                )
+                .map(
+                    |(recipient_address, roll_count)| OperationType::RollTransfer {
+                        recipient_address,
+                        roll_count,
+                    },
+                )
+                .parse(input),
                 OperationTypeId::ExecuteSC => context(
                     "Failed ExecuteSC deserialization",
                     tuple((
@@ -978,6 +1065,7 @@ impl SecureShareOperation {
             OperationType::CallSC { max_gas, .. } => *max_gas,
             OperationType::RollBuy { .. } => 0,
             OperationType::RollSell { .. } => 0,
+            OperationType::RollTransfer { .. } => 0,
             OperationType::Transaction { .. } => 0,
         }
     }
@@ -995,6 +1083,7 @@ impl SecureShareOperation {
             }
             OperationType::RollBuy { .. } => {}
             OperationType::RollSell { .. } => {}
+            OperationType::RollTransfer { .. } => {}
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { target_addr, .. } => {
                 res.insert(*target_addr);
@@ -1010,6 +1099,7 @@ impl SecureShareOperation {
             OperationType::Transaction { amount, .. } => *amount,
             OperationType::RollBuy { roll_count } => roll_price.saturating_mul_u64(*roll_count),
             OperationType::RollSell { .. } => Amount::zero(),
+            OperationType::RollTransfer { .. } => Amount::zero(),
             OperationType::ExecuteSC { max_coins, .. } => *max_coins,
             OperationType::CallSC { coins, .. } => *coins,
         };
@@ -1029,6 +1119,12 @@ impl SecureShareOperation {
             OperationType::RollSell { .. } => {
                 res.insert(Address::from_public_key(&self.content_creator_pub_key));
             }
+            OperationType::RollTransfer {
+                recipient_address, ..
+            } => {
+                res.insert(Address::from_public_key(&self.content_creator_pub_key));
+                res.insert(recipient_address);
+            }
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { .. } => {}
         }
@@ -1549,6 +1645,53 @@ mod tests {
         assert_eq!(op.get_validity_range(10), 40..=50);
     }
 
+    #[test]
+    #[serial]
+    fn test_roll_transfer() {
+        let sender_keypair = KeyPair::generate(0).unwrap();
+        let recv_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        let op = OperationType::RollTransfer {
+            recipient_address: recv_address,
+            roll_count: 12,
+        };
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&op, &mut ser_type)
+            .unwrap();
+        let (_, res_type) = OperationTypeDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        )
+        .deserialize::<DeserializeError>(&ser_type)
+        .unwrap();
+        assert_eq!(res_type, op);
+
+        let content = Operation {
+            fee: Amount::from_str("1").unwrap(),
+            op,
+            expire_period: 50,
+        };
+        let op = Operation::new_verifiable(content, OperationSerializer::new(), &sender_keypair)
+            .unwrap();
+
+        assert_eq!(
+            op.get_max_spending(Amount::from_str("100").unwrap()),
+            Amount::from_str("1").unwrap()
+        );
+        let roll_addresses = op.get_roll_involved_addresses().unwrap();
+        assert_eq!(roll_addresses.len(), 2);
+        assert!(roll_addresses.contains(&recv_address));
+        assert!(
+            roll_addresses.contains(&Address::from_public_key(&sender_keypair.get_public_key()))
+        );
+    }
+
     #[test]
     #[serial]
     fn test_executesc() {
@@ -1,8 +1,9 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::slot::Slot;
+use crate::{address::Address, slot::Slot};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Formatter;
 
 /// execution statistics
@@ -51,6 +52,89 @@ impl std::fmt::Display for ExecutionStats {
     }
 }
 
+/// number of final operations executed during a time bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThroughputBucket {
+    /// bucket start (included)
+    pub start: MassaTime,
+    /// bucket end (excluded)
+    pub end: MassaTime,
+    /// number of final executed operations in the bucket
+    pub final_executed_operations_count: usize,
+}
+
+impl ThroughputBucket {
+    /// Number of final executed operations per second over the bucket
+    pub fn operations_per_second(&self) -> f64 {
+        let duration = self.end.saturating_sub(self.start).to_duration();
+        if duration.is_zero() {
+            return 0.0;
+        }
+        self.final_executed_operations_count as f64 / duration.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for ThroughputBucket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} - {}: {} operations ({:.2} op/s)",
+            self.start.format_instant(),
+            self.end.format_instant(),
+            self.final_executed_operations_count,
+            self.operations_per_second()
+        )
+    }
+}
+
+/// Disk usage of the final state database and its projection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskUsageForecast {
+    /// time of the last measurement
+    pub timestamp: MassaTime,
+    /// size on disk of each column family, in bytes
+    pub column_family_sizes: BTreeMap<String, u64>,
+    /// growth rate of the database over the forecast window, in bytes per second (negative if shrinking)
+    pub growth_rate: f64,
+    /// space available on the disk holding the database, in bytes, if known
+    pub available_space: Option<u64>,
+    /// estimated time before the disk is full, none if the database is not growing or the available space is unknown
+    pub time_to_full: Option<MassaTime>,
+}
+
+impl DiskUsageForecast {
+    /// Total size on disk of the database, in bytes
+    pub fn total_size(&self) -> u64 {
+        self.column_family_sizes
+            .values()
+            .fold(0u64, |acc, size| acc.saturating_add(*size))
+    }
+}
+
+impl std::fmt::Display for DiskUsageForecast {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Disk usage:")?;
+        for (cf, size) in &self.column_family_sizes {
+            writeln!(f, "\t{}: {} bytes", cf, size)?;
+        }
+        writeln!(f, "\tTotal: {} bytes", self.total_size())?;
+        writeln!(f, "\tGrowth rate: {:.2} bytes/s", self.growth_rate)?;
+        if let Some(available_space) = self.available_space {
+            writeln!(f, "\tAvailable space: {} bytes", available_space)?;
+        }
+        if let Some(time_to_full) = self.time_to_full {
+            writeln!(
+                f,
+                "\tEstimated time before disk is full: {}",
+                time_to_full
+                    .format_duration()
+                    .unwrap_or_else(|_| format!("{} ms", time_to_full))
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// stats produced by network module
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkStats {
@@ -78,6 +162,48 @@ impl std::fmt::Display for NetworkStats {
     }
 }
 
+/// number of blocks discarded by consensus, per kind of discard reason
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscardedBlockCounts {
+    /// blocks incompatible with a final block
+    pub stale_count: u64,
+    /// invalid blocks
+    pub invalid_count: u64,
+    /// final blocks that are no longer needed
+    pub final_count: u64,
+}
+
+impl std::fmt::Display for DiscardedBlockCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "stale: {}, invalid: {}, final: {}",
+            self.stale_count, self.invalid_count, self.final_count
+        )
+    }
+}
+
+/// size and fitness of one of the maximal cliques of the block graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliqueStats {
+    /// number of blocks in the clique
+    pub block_count: u64,
+    /// sum of the fitness of the blocks of the clique
+    pub fitness: u64,
+    /// whether the clique is the blockclique
+    pub is_blockclique: bool,
+}
+
+impl std::fmt::Display for CliqueStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} blocks, fitness {}", self.block_count, self.fitness)?;
+        if self.is_blockclique {
+            write!(f, " (blockclique)")?;
+        }
+        Ok(())
+    }
+}
+
 /// stats produced by consensus module
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusStats {
@@ -91,6 +217,21 @@ pub struct ConsensusStats {
     pub stale_block_count: u64,
     ///  number of actives cliques
     pub clique_count: u64,
+    /// highest number of actives cliques over the time span
+    #[serde(default)]
+    pub max_clique_count: u64,
+    /// size and fitness of the actives cliques, by decreasing fitness
+    #[serde(default)]
+    pub cliques: Vec<CliqueStats>,
+    /// per thread, number of periods between the last final block and the head of the blockclique
+    #[serde(default)]
+    pub fork_depths: Vec<u64>,
+    /// number of discarded blocks per kind of reason, for each of the last cycles
+    #[serde(default)]
+    pub discarded_blocks_per_cycle: BTreeMap<u64, DiscardedBlockCounts>,
+    /// number of discarded blocks per kind of reason and per creator, over the same cycles
+    #[serde(default)]
+    pub discarded_blocks_per_creator: BTreeMap<Address, DiscardedBlockCounts>,
 }
 
 impl std::fmt::Display for ConsensusStats {
@@ -109,6 +250,16 @@ impl std::fmt::Display for ConsensusStats {
         writeln!(f, "\tFinal block count: {}", self.final_block_count)?;
         writeln!(f, "\tStale block count: {}", self.stale_block_count)?;
         writeln!(f, "\tClique count: {}", self.clique_count)?;
+        writeln!(f, "\tMax clique count: {}", self.max_clique_count)?;
+        for (index, clique) in self.cliques.iter().enumerate() {
+            writeln!(f, "\tClique {}: {}", index, clique)?;
+        }
+        for (thread, depth) in self.fork_depths.iter().enumerate() {
+            writeln!(f, "\tFork depth in thread {}: {}", thread, depth)?;
+        }
+        for (cycle, counts) in &self.discarded_blocks_per_cycle {
+            writeln!(f, "\tDiscarded blocks in cycle {}: {}", cycle, counts)?;
+        }
         Ok(())
     }
 }
@@ -130,3 +281,49 @@ impl std::fmt::Display for PoolStats {
         Ok(())
     }
 }
+
+/// endorsements available in the pool for a block produced by the node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndorsementFill {
+    /// slot of the produced block
+    pub slot: Slot,
+    /// number of endorsements included in the block
+    pub endorsement_count: u32,
+    /// endorsers drawn for the block whose endorsement was not available
+    pub missed_endorsers: Vec<Address>,
+}
+
+/// health of the endorsement pool over the last blocks produced by the node
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndorsementPoolStats {
+    /// number of blocks produced over the window
+    pub produced_block_count: u64,
+    /// fraction of the endorsements of these blocks that were available at production time,
+    /// none if no block was produced
+    pub fill_rate: Option<f64>,
+    /// number of endorsements missing from these blocks
+    pub missed_endorsement_count: u64,
+    /// number of endorsements missed by each endorser, over the window
+    pub missed_endorsements: BTreeMap<Address, u64>,
+    /// endorsements of the last produced blocks, oldest first
+    pub recent_blocks: Vec<EndorsementFill>,
+}
+
+impl std::fmt::Display for EndorsementPoolStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Endorsement pool stats:")?;
+        writeln!(f, "\tProduced block count: {}", self.produced_block_count)?;
+        if let Some(fill_rate) = self.fill_rate {
+            writeln!(f, "\tEndorsement fill rate: {:.2}%", fill_rate * 100.0)?;
+        }
+        writeln!(
+            f,
+            "\tMissed endorsement count: {}",
+            self.missed_endorsement_count
+        )?;
+        for (address, count) in &self.missed_endorsements {
+            writeln!(f, "\tMissed by {}: {}", address, count)?;
+        }
+        Ok(())
+    }
+}
@@ -18,4 +18,7 @@ pub struct ModuleCacheConfig {
     pub snip_amount: usize,
     /// Maximum length of a module
     pub max_module_length: u64,
+    /// Version of the execution engine that compiled the modules.
+    /// The HD cache is cleared when it was filled by another version.
+    pub engine_version: String,
 }
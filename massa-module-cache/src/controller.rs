@@ -34,6 +34,7 @@ impl ModuleCache {
                 cfg.hd_cache_path.clone(),
                 cfg.hd_cache_size,
                 cfg.snip_amount,
+                &cfg.engine_version,
             ),
             cfg,
         }
@@ -58,6 +59,45 @@ impl ModuleCache {
         }
     }
 
+    /// Maximum number of modules kept in the RAM cache
+    pub fn lru_cache_size(&self) -> u32 {
+        self.cfg.lru_cache_size
+    }
+
+    /// Maximum number of modules kept in the HD cache
+    pub fn hd_cache_size(&self) -> usize {
+        self.cfg.hd_cache_size
+    }
+
+    /// Changes the maximum number of modules kept in the RAM and HD caches,
+    /// evicting the modules in excess
+    pub fn resize(&mut self, lru_cache_size: u32, hd_cache_size: usize) -> Result<(), CacheError> {
+        if lru_cache_size == 0 {
+            return Err(CacheError::ResizeError(
+                "the LRU cache must hold at least one module".to_string(),
+            ));
+        }
+        if hd_cache_size < std::cmp::max(self.cfg.snip_amount, 1) {
+            return Err(CacheError::ResizeError(format!(
+                "the HD cache must hold at least as many modules as the snip amount ({})",
+                self.cfg.snip_amount
+            )));
+        }
+        if lru_cache_size != self.cfg.lru_cache_size {
+            self.lru_cache.resize(lru_cache_size);
+            self.cfg.lru_cache_size = lru_cache_size;
+        }
+        if hd_cache_size != self.cfg.hd_cache_size {
+            self.hd_cache.resize(hd_cache_size);
+            self.cfg.hd_cache_size = hd_cache_size;
+        }
+        info!(
+            "module cache resized: {} modules in RAM, {} modules on disk",
+            lru_cache_size, hd_cache_size
+        );
+        Ok(())
+    }
+
     /// Save a new or an already existing module in the cache
     pub fn save_module(&mut self, bytecode: &[u8]) {
         let hash = Hash::compute_from(bytecode);
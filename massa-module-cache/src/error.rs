@@ -9,6 +9,8 @@ pub enum CacheError {
     VMError(String),
     /// Load error: {0}
     LoadError(String),
+    /// Resize error: {0}
+    ResizeError(String),
 }
 
 impl From<anyhow::Error> for CacheError {
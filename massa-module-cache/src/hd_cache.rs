@@ -6,18 +6,21 @@ use massa_hash::Hash;
 use massa_sc_runtime::{GasCosts, RuntimeModule};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use rand::RngCore;
-use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use rocksdb::{Direction, IteratorMode, Options, WriteBatch, DB, DEFAULT_COLUMN_FAMILY_NAME};
 use std::path::PathBuf;
-use tracing::debug;
+use tracing::{debug, info};
 
 const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
 const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const CF_ERROR: &str = "critical: rocksdb column family operation failed";
 const DATA_SER_ERROR: &str = "critical: metadata serialization failed";
 const DATA_DESER_ERROR: &str = "critical: metadata deserialization failed";
 const MOD_SER_ERROR: &str = "critical: module serialization failed";
 const MOD_DESER_ERROR: &str = "critical: module deserialization failed";
 const MODULE_IDENT: u8 = 0u8;
 const DATA_IDENT: u8 = 1u8;
+const ENGINE_VERSION_CF: &str = "engine_version";
+const ENGINE_VERSION_KEY: &[u8] = b"engine_version";
 
 /// Module key formatting macro
 #[macro_export]
@@ -59,8 +62,23 @@ impl HDCache {
     /// * path: where to store the db
     /// * max_entry_count: maximum number of entries we want to keep in the db
     /// * amount_to_remove: how many entries are removed when `entry_count` reaches `max_entry_count`
-    pub fn new(path: PathBuf, max_entry_count: usize, snip_amount: usize) -> Self {
-        let db = DB::open_default(path).expect(OPEN_ERROR);
+    /// * engine_version: version of the engine compiling the modules, the db is cleared if it was filled by another one
+    pub fn new(
+        path: PathBuf,
+        max_entry_count: usize,
+        snip_amount: usize,
+        engine_version: &str,
+    ) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = DB::open_cf(
+            &db_opts,
+            path,
+            [DEFAULT_COLUMN_FAMILY_NAME, ENGINE_VERSION_CF],
+        )
+        .expect(OPEN_ERROR);
+        Self::check_engine_version(&db, engine_version);
         let entry_count = db.iterator(IteratorMode::Start).count();
 
         Self {
@@ -73,6 +91,39 @@ impl HDCache {
         }
     }
 
+    /// Removes every module if the db was filled by another engine version,
+    /// as the serialized modules can only be deserialized by the engine that compiled them
+    fn check_engine_version(db: &DB, engine_version: &str) {
+        let handle = db.cf_handle(ENGINE_VERSION_CF).expect(CF_ERROR);
+        let stored_version = db.get_cf(handle, ENGINE_VERSION_KEY).expect(CRUD_ERROR);
+        if stored_version.as_deref() == Some(engine_version.as_bytes()) {
+            return;
+        }
+
+        let mut batch = WriteBatch::default();
+        for entry in db.iterator(IteratorMode::Start) {
+            let (key, _) = entry.expect(CRUD_ERROR);
+            batch.delete(key);
+        }
+        if !batch.is_empty() {
+            info!(
+                "clearing the HD module cache compiled by a previous engine version ({} keys)",
+                batch.len()
+            );
+        }
+        batch.put_cf(handle, ENGINE_VERSION_KEY, engine_version.as_bytes());
+        db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Change the maximum number of entries,
+    /// snipping entries until the cache fits in the new size
+    pub fn resize(&mut self, max_entry_count: usize) {
+        self.max_entry_count = max_entry_count;
+        while self.entry_count > self.max_entry_count {
+            self.snip();
+        }
+    }
+
     /// Insert a new module in the cache
     pub fn insert(&mut self, hash: Hash, module_info: ModuleInfo) {
         if self.entry_count >= self.max_entry_count {
@@ -239,7 +290,7 @@ mod tests {
 
     fn setup() -> HDCache {
         let tmp_path = TempDir::new().unwrap().path().to_path_buf();
-        HDCache::new(tmp_path, 1000, 10)
+        HDCache::new(tmp_path, 1000, 10, "test")
     }
 
     #[test]
@@ -289,6 +340,54 @@ mod tests {
         dbg!(cache.entry_count);
     }
 
+    #[test]
+    #[serial]
+    fn test_resize() {
+        let mut cache = setup();
+        let module = make_default_module_info();
+
+        for count in 0..cache.max_entry_count {
+            let key = Hash::compute_from(count.to_string().as_bytes());
+            cache.insert(key, module.clone());
+        }
+
+        // shrinking snips the entries in excess
+        cache.resize(500);
+        assert!(cache.entry_count <= 500);
+
+        // growing keeps the entries and allows inserting more of them
+        let entry_count = cache.entry_count;
+        cache.resize(2000);
+        let key = Hash::compute_from(b"after_resize");
+        cache.insert(key, module);
+        assert_eq!(cache.entry_count, entry_count + 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_engine_version_change() {
+        let tmp_dir = TempDir::new().unwrap();
+        let hash = Hash::compute_from(b"test_hash");
+        let limit = 1;
+        let gas_costs = GasCosts::default();
+
+        {
+            let mut cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, 10, "v1");
+            cache.insert(hash, make_default_module_info());
+        }
+
+        // reopening with the same engine version keeps the modules
+        {
+            let cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, 10, "v1");
+            assert!(cache.get(hash, limit, gas_costs.clone()).is_some());
+        }
+
+        // reopening with another engine version clears them
+        let cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, 10, "v2");
+        assert_eq!(cache.entry_count, 0);
+        assert!(cache.get(hash, limit, gas_costs).is_none());
+    }
+
     #[test]
     #[serial]
     fn test_missing_module() {
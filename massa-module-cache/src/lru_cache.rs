@@ -27,6 +27,19 @@ impl LRUCache {
         }
     }
 
+    /// Change the maximum number of modules of the cache,
+    /// evicting the least recently used ones in excess
+    pub fn resize(&mut self, cache_size: u32) {
+        let mut resized =
+            LruMap::with_hasher(ByLength::new(cache_size), BuildHashMapper::default());
+        // move the modules from the least to the most recently used one to keep their usage order
+        while let Some((hash, module_info)) = self.cache.pop_oldest() {
+            resized.insert(hash, module_info);
+        }
+        self.cache = resized;
+        debug!("(LRU resize) length is: {}", self.cache.len());
+    }
+
     /// If the module is contained in the cache:
     /// * retrieve a copy of it
     /// * move it up in the LRU cache
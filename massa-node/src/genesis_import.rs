@@ -0,0 +1,104 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Genesis ledger import mode.
+//!
+//! Builds the initial ledger from a CSV or JSON file, prints a validation report
+//! and the hash of the resulting ledger so that genesis participants can check
+//! they all start from the same state.
+
+use crate::settings::SETTINGS;
+use anyhow::bail;
+use massa_db_exports::{MassaDBConfig, MassaDBController};
+use massa_db_worker::MassaDB;
+use massa_ledger_exports::{LedgerConfig, LedgerEntry};
+use massa_ledger_worker::{
+    build_genesis_ledger, read_genesis_ledger_file, validate_genesis_ledger, FinalLedger,
+};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::config::constants::{
+    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, THREAD_COUNT,
+};
+use massa_models::config::{MAX_BOOTSTRAPPED_NEW_ELEMENTS, MAX_BOOTSTRAPPED_STREAM_BATCH_SIZE};
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Imports a genesis ledger file into `output_dir`.
+///
+/// Writes `initial_ledger.json` (only if the input has no datastore entries, as they can't be represented in JSON),
+/// `initial_rolls.json` and the initial ledger database in `output_dir/ledger`.
+/// Nothing is written if the validation fails.
+pub(crate) fn import_genesis_ledger(
+    input: &Path,
+    output_dir: &Path,
+    expected_total_balance: Option<Amount>,
+    expected_total_rolls: Option<u64>,
+) -> anyhow::Result<()> {
+    let (entries, parse_errors) = read_genesis_ledger_file(input)?;
+    let report = validate_genesis_ledger(
+        &entries,
+        parse_errors,
+        MAX_DATASTORE_KEY_LENGTH,
+        expected_total_balance,
+        expected_total_rolls,
+    );
+    println!("{}", report);
+    if !report.is_valid() {
+        bail!("the genesis ledger is invalid: nothing was written");
+    }
+
+    let db_path = output_dir.join("ledger");
+    if db_path.exists() {
+        bail!(
+            "{} already exists: remove it before importing a genesis ledger",
+            db_path.display()
+        );
+    }
+    std::fs::create_dir_all(output_dir)?;
+
+    let (initial_ledger, initial_rolls) = build_genesis_ledger(entries);
+    if report.datastore_entry_count == 0 {
+        let sorted_ledger: BTreeMap<&Address, &LedgerEntry> = initial_ledger.iter().collect();
+        std::fs::write(
+            output_dir.join("initial_ledger.json"),
+            serde_json::to_string_pretty(&sorted_ledger)?,
+        )?;
+    } else {
+        println!("initial_ledger.json was not written as the input contains datastore entries: use the ledger database instead");
+    }
+    std::fs::write(
+        output_dir.join("initial_rolls.json"),
+        serde_json::to_string_pretty(&initial_rolls)?,
+    )?;
+
+    // build the initial ledger database
+    let db_config = MassaDBConfig {
+        path: db_path,
+        max_history_length: SETTINGS.ledger.final_history_length,
+        max_history_bytes: SETTINGS.ledger.final_history_max_bytes,
+        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
+        max_stream_batch_bytes: Some(MAX_BOOTSTRAPPED_STREAM_BATCH_SIZE as usize),
+        thread_count: THREAD_COUNT,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
+    };
+    let db = Arc::new(RwLock::new(
+        Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+    ));
+    let ledger_config = LedgerConfig {
+        thread_count: THREAD_COUNT,
+        initial_ledger_path: input.to_path_buf(),
+        disk_ledger_path: output_dir.join("ledger"),
+        max_key_length: MAX_DATASTORE_KEY_LENGTH,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+    };
+    let mut ledger = FinalLedger::new(ledger_config, db.clone());
+    ledger.load_initial_entries(initial_ledger);
+
+    println!("Genesis ledger written to {}", output_dir.display());
+    println!("Genesis ledger hash: {}", db.read().get_xof_db_hash());
+    Ok(())
+}
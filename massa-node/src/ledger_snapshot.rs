@@ -0,0 +1,93 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Ledger snapshot export and import modes.
+//!
+//! The export writes the final ledger on disk to a canonical snapshot file and prints its hash,
+//! so that operators can check they hold the same ledger. The node must be stopped meanwhile.
+//! The import verifies such a file and builds an initial ledger database from it.
+
+use crate::settings::SETTINGS;
+use anyhow::bail;
+use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
+use massa_db_worker::MassaDB;
+use massa_ledger_exports::LedgerConfig;
+use massa_ledger_worker::{FinalLedger, LedgerSnapshotInfo};
+use massa_models::config::constants::{
+    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, THREAD_COUNT,
+};
+use massa_models::config::{MAX_BOOTSTRAPPED_NEW_ELEMENTS, MAX_BOOTSTRAPPED_STREAM_BATCH_SIZE};
+use parking_lot::RwLock;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Exports the final ledger of the node database to the snapshot file `output`
+pub(crate) fn export_ledger_snapshot(output: &Path) -> anyhow::Result<()> {
+    let db_path = &SETTINGS.ledger.disk_ledger_path;
+    if !db_path.exists() {
+        bail!("no ledger database found at {}", db_path.display());
+    }
+    let ledger = open_ledger(db_path, &SETTINGS.ledger.initial_ledger_path);
+
+    let info = ledger.export_snapshot(output)?;
+    println!("Ledger snapshot written to {}", output.display());
+    print_snapshot_info(&info);
+    Ok(())
+}
+
+/// Builds the initial ledger database in `output_dir/ledger` from the snapshot file `input`.
+/// Nothing is written if the snapshot is invalid.
+pub(crate) fn import_ledger_snapshot(input: &Path, output_dir: &Path) -> anyhow::Result<()> {
+    let db_path = output_dir.join("ledger");
+    if db_path.exists() {
+        bail!(
+            "{} already exists: remove it before importing a ledger snapshot",
+            db_path.display()
+        );
+    }
+    std::fs::create_dir_all(output_dir)?;
+
+    let mut ledger = open_ledger(&db_path, input);
+    let info = match ledger.import_snapshot(input) {
+        Ok(info) => info,
+        Err(err) => {
+            // do not leave an empty ledger database behind
+            drop(ledger);
+            let _ = std::fs::remove_dir_all(&db_path);
+            return Err(err.into());
+        }
+    };
+    println!("Ledger snapshot imported to {}", db_path.display());
+    print_snapshot_info(&info);
+    Ok(())
+}
+
+fn open_ledger(db_path: &Path, initial_ledger_path: &Path) -> FinalLedger {
+    let db_config = MassaDBConfig {
+        path: db_path.to_path_buf(),
+        max_history_length: SETTINGS.ledger.final_history_length,
+        max_history_bytes: SETTINGS.ledger.final_history_max_bytes,
+        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
+        max_stream_batch_bytes: Some(MAX_BOOTSTRAPPED_STREAM_BATCH_SIZE as usize),
+        thread_count: THREAD_COUNT,
+        tuning: Default::default(),
+        backup_retention: Default::default(),
+        archival: Default::default(),
+    };
+    let db: ShareableMassaDBController = Arc::new(RwLock::new(
+        Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+    ));
+    let ledger_config = LedgerConfig {
+        thread_count: THREAD_COUNT,
+        initial_ledger_path: initial_ledger_path.to_path_buf(),
+        disk_ledger_path: db_path.to_path_buf(),
+        max_key_length: MAX_DATASTORE_KEY_LENGTH,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+    };
+    FinalLedger::new(ledger_config, db)
+}
+
+fn print_snapshot_info(info: &LedgerSnapshotInfo) {
+    println!("Ledger snapshot slot: {}", info.slot);
+    println!("Ledger snapshot entry count: {}", info.entry_count);
+    println!("Ledger snapshot hash: {}", info.hash);
+}
@@ -15,11 +15,13 @@ use crossbeam_channel::TryRecvError;
 use dialoguer::Password;
 use massa_api::{ApiServer, ApiV2, Private, Public, RpcServer, StopHandle, API};
 use massa_api_exports::config::APIConfig;
+use massa_api_exports::node::DbIntegrityReport;
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::BootstrapError;
 use massa_bootstrap::{
-    get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
-    DefaultConnector,
+    export_snapshot_archive, get_state, resync_versioning, start_bootstrap_server, BootstrapConfig,
+    BootstrapManager, BootstrapPhase, BootstrapProgress, BootstrapTcpListener, DefaultConnector,
+    BOOTSTRAP_CURSOR_FILE,
 };
 use massa_channel::receiver::MassaReceiver;
 use massa_channel::MassaChannel;
@@ -28,7 +30,10 @@ use massa_consensus_exports::{
     ConsensusBroadcasts, ConsensusChannels, ConsensusConfig, ConsensusManager,
 };
 use massa_consensus_worker::start_consensus_worker;
-use massa_db_exports::{MassaDBConfig, MassaDBController};
+use massa_db_exports::{
+    MassaDBArchival, MassaDBBackupRetention, MassaDBConfig, MassaDBController, MassaDBTuning,
+    MassaDBWalSync,
+};
 use massa_db_worker::MassaDB;
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_execution_exports::{
@@ -45,6 +50,7 @@ use massa_ledger_worker::FinalLedger;
 use massa_logging::massa_trace;
 use massa_metrics::{MassaMetrics, MetricsStopper};
 use massa_models::address::Address;
+use massa_models::amount::Amount;
 use massa_models::config::constants::{
     BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE, CONSENSUS_BOOTSTRAP_PART_SIZE,
     DELTA_F0, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, END_TIMESTAMP, GENESIS_KEY,
@@ -68,20 +74,21 @@ use massa_models::config::constants::{
     MAX_SIZE_CHANNEL_NETWORK_TO_ENDORSEMENT_HANDLER, MAX_SIZE_CHANNEL_NETWORK_TO_OPERATION_HANDLER,
     MAX_SIZE_CHANNEL_NETWORK_TO_PEER_HANDLER, MIP_STORE_STATS_BLOCK_CONSIDERED,
     OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, POS_MISS_RATE_DEACTIVATION_THRESHOLD,
-    POS_SAVED_CYCLES, PROTOCOL_CONTROLLER_CHANNEL_SIZE, PROTOCOL_EVENT_CHANNEL_SIZE,
-    ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, ROLL_PRICE, SELECTOR_DRAW_CACHE_SIZE, T0, THREAD_COUNT,
-    VERSION,
+    POS_PRODUCTION_STATS_HISTORY_CYCLES, POS_SAVED_CYCLES, PROTOCOL_CONTROLLER_CHANNEL_SIZE,
+    PROTOCOL_EVENT_CHANNEL_SIZE, ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, ROLL_PRICE,
+    SELECTOR_DRAW_CACHE_SIZE, T0, THREAD_COUNT, VERSION,
 };
 use massa_models::config::{
-    KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_BOOTSTRAPPED_NEW_ELEMENTS, MAX_EVENT_DATA_SIZE,
-    MAX_MESSAGE_SIZE, POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE,
-    POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE, POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
+    KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_BOOTSTRAPPED_NEW_ELEMENTS,
+    MAX_BOOTSTRAPPED_STREAM_BATCH_SIZE, MAX_EVENT_DATA_SIZE, MAX_MESSAGE_SIZE,
+    POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE, POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE,
+    POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
 };
 use massa_models::slot::Slot;
 use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolManager};
 use massa_pool_worker::start_pool_controller;
 use massa_pos_exports::{PoSConfig, SelectorConfig, SelectorManager};
-use massa_pos_worker::start_selector_worker;
+use massa_pos_worker::{load_initial_rolls, start_selector_worker};
 use massa_protocol_exports::{ProtocolConfig, ProtocolManager, TransportType};
 use massa_protocol_worker::{create_protocol_controller, start_protocol_controller};
 use massa_signature::KeyPair;
@@ -93,12 +100,12 @@ use massa_versioning::versioning::{MipStatsConfig, MipStore};
 use massa_wallet::Wallet;
 use num::rational::Ratio;
 use parking_lot::RwLock;
-use settings::GrpcSettings;
+use settings::{DBWalSyncSettings, GrpcSettings};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{path::Path, process, sync::Arc};
 
 use survey::MassaSurveyStopper;
@@ -106,11 +113,18 @@ use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::filter::{filter_fn, LevelFilter};
 
+mod genesis_import;
+mod ledger_snapshot;
 #[cfg(feature = "op_spammer")]
 mod operation_injector;
 mod settings;
 mod survey;
 
+/// Capacity of the channel of the bootstrap progress, a full channel drops the progress
+const BOOTSTRAP_PROGRESS_CHANNEL_SIZE: usize = 1000;
+/// Minimum interval between two logs of the bootstrap progress within a phase
+const BOOTSTRAP_PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
 async fn launch(
     args: &Args,
     node_wallet: Arc<RwLock<Wallet>>,
@@ -207,6 +221,18 @@ async fn launch(
         max_function_params_length: MAX_PARAMETERS_SIZE as u64,
         max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
     };
+    // report suspicious initial rolls early: a bad distribution only shows up as missed blocks
+    // (loading errors are reported when the PoS state is created)
+    if let Ok((_, report)) = load_initial_rolls(&SETTINGS.selector.initial_rolls_path, THREAD_COUNT)
+    {
+        if !report.is_valid() {
+            warn!(
+                "initial rolls file {}: {}",
+                SETTINGS.selector.initial_rolls_path.display(),
+                report
+            );
+        }
+    }
     let pos_config = PoSConfig {
         periods_per_cycle: PERIODS_PER_CYCLE,
         thread_count: THREAD_COUNT,
@@ -214,6 +240,7 @@ async fn launch(
         max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
         max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
         max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+        production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
         initial_deferred_credits_path: SETTINGS.ledger.initial_deferred_credits_path.clone(),
     };
     let executed_ops_config = ExecutedOpsConfig {
@@ -242,6 +269,7 @@ async fn launch(
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        snapshot_interval_cycles: SETTINGS.ledger.snapshot_interval_cycles,
     };
 
     // Start massa metrics
@@ -252,10 +280,20 @@ async fn launch(
         SETTINGS.metrics.tick_delay.to_duration(),
     );
 
+    // Keep the disk ledger holding a partial bootstrap, to resume it
+    let bootstrap_cursor_path = SETTINGS.ledger.disk_ledger_path.join(BOOTSTRAP_CURSOR_FILE);
+    let resume_bootstrap = bootstrap_cursor_path.exists();
+
     // Remove current disk ledger if there is one and we don't want to restart from snapshot
     // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
-    if args.keep_ledger || args.restart_from_snapshot_at_period.is_some() {
+    if args.keep_ledger
+        || args.verify_db
+        || args.export_bootstrap_snapshot.is_some()
+        || args.restart_from_snapshot_at_period.is_some()
+    {
         info!("Loading old ledger for next episode");
+    } else if resume_bootstrap {
+        info!("Loading the partially bootstrapped ledger to resume the bootstrap");
     } else {
         if SETTINGS.ledger.disk_ledger_path.exists() {
             std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
@@ -270,8 +308,37 @@ async fn launch(
     let db_config = MassaDBConfig {
         path: SETTINGS.ledger.disk_ledger_path.clone(),
         max_history_length: SETTINGS.ledger.final_history_length,
+        max_history_bytes: SETTINGS.ledger.final_history_max_bytes,
         max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
+        max_stream_batch_bytes: Some(MAX_BOOTSTRAPPED_STREAM_BATCH_SIZE as usize),
         thread_count: THREAD_COUNT,
+        tuning: MassaDBTuning {
+            block_cache_size: SETTINGS.ledger.db_block_cache_size,
+            write_buffer_size: SETTINGS.ledger.db_write_buffer_size,
+            bloom_filter_bits_per_key: SETTINGS.ledger.db_bloom_filter_bits_per_key,
+            max_open_files: SETTINGS.ledger.db_max_open_files,
+            state_cf_compression: SETTINGS.ledger.db_state_cf_compression,
+            metadata_cf_compression: SETTINGS.ledger.db_metadata_cf_compression,
+            versioning_cf_compression: SETTINGS.ledger.db_versioning_cf_compression,
+            local_cf_compression: SETTINGS.ledger.db_local_cf_compression,
+            wal_sync: SETTINGS.ledger.db_wal_sync.map(|wal_sync| match wal_sync {
+                DBWalSyncSettings::EveryBatch => MassaDBWalSync::EveryBatch,
+                DBWalSyncSettings::Periodic(interval) => {
+                    MassaDBWalSync::Periodic(interval.to_duration())
+                }
+                DBWalSyncSettings::Disabled => MassaDBWalSync::Disabled,
+            }),
+            compaction_rate_limit: SETTINGS.ledger.db_compaction_rate_limit,
+            max_background_jobs: SETTINGS.ledger.db_max_background_jobs,
+        },
+        backup_retention: MassaDBBackupRetention {
+            max_backups: SETTINGS.ledger.db_max_backups,
+            max_age: SETTINGS.ledger.db_max_backup_age.map(|t| t.to_duration()),
+        },
+        archival: MassaDBArchival {
+            enabled: SETTINGS.ledger.db_archival,
+            max_periods: SETTINGS.ledger.db_archive_max_periods,
+        },
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -301,6 +368,53 @@ async fn launch(
     };
     // Ratio::new_raw(*SETTINGS.versioning.warn_announced_version_ratio, 100),
 
+    // Verify the final state on disk, without resetting it, then exit
+    if args.verify_db {
+        let mip_store = MipStore::try_from((get_mip_list(), mip_stats_config))
+            .expect("mip store creation failed");
+        let final_state = FinalState::new(
+            db.clone(),
+            final_state_config,
+            Box::new(ledger),
+            selector_controller.clone(),
+            mip_store,
+            false,
+        )
+        .expect("could not init final state");
+        let report = final_state
+            .verify_db_integrity()
+            .expect("could not verify the final state database");
+        let is_valid = report.is_valid();
+        println!("{}", DbIntegrityReport::from(report));
+        process::exit(if is_valid { 0 } else { 1 });
+    }
+
+    // Export the final state on disk to a bootstrap snapshot archive signed with the node key, then exit
+    if let Some(output) = &args.export_bootstrap_snapshot {
+        let mip_store = MipStore::try_from((get_mip_list(), mip_stats_config))
+            .expect("mip store creation failed");
+        let final_state = FinalState::new(
+            db.clone(),
+            final_state_config,
+            Box::new(ledger),
+            selector_controller.clone(),
+            mip_store,
+            false,
+        )
+        .expect("could not init final state");
+        let keypair_file = std::fs::read_to_string(&SETTINGS.protocol.keypair_file)
+            .expect("could not load node key file");
+        let keypair =
+            serde_json::from_str::<KeyPair>(&keypair_file).expect("could not parse node key file");
+        let info = export_snapshot_archive(&final_state, &keypair, output)
+            .expect("could not export the bootstrap snapshot archive");
+        println!(
+            "Exported the final state at slot {} with hash {}, signed by {}",
+            info.slot, info.state_hash, info.node_id
+        );
+        process::exit(0);
+    }
+
     // Create final state, either from a snapshot, or from scratch
     let final_state = Arc::new(parking_lot::RwLock::new(
         match args.restart_from_snapshot_at_period {
@@ -341,7 +455,7 @@ async fn launch(
                     Box::new(ledger),
                     selector_controller.clone(),
                     mip_store,
-                    true,
+                    !resume_bootstrap,
                 )
                 .expect("could not init final state")
             }
@@ -363,15 +477,41 @@ async fn launch(
         read_error_timeout: SETTINGS.bootstrap.read_error_timeout,
         write_error_timeout: SETTINGS.bootstrap.write_error_timeout,
         retry_delay: SETTINGS.bootstrap.retry_delay,
+        max_retry_delay: SETTINGS.bootstrap.max_retry_delay,
+        retry_jitter: SETTINGS.bootstrap.retry_jitter,
+        max_bootstrap_duration: SETTINGS.bootstrap.max_bootstrap_duration,
         max_ping: SETTINGS.bootstrap.max_ping,
         max_clock_delta: SETTINGS.bootstrap.max_clock_delta,
         cache_duration: SETTINGS.bootstrap.cache_duration,
         keep_ledger: args.keep_ledger,
+        bootstrap_cursor_path: Some(bootstrap_cursor_path),
+        bootstrap_snapshot_path: args.bootstrap_snapshot.clone(),
+        consensus_state_cache_path: SETTINGS.consensus.state_cache_path.clone(),
+        trusted_checkpoint: SETTINGS.bootstrap.trusted_checkpoint,
+        parallel_bootstrap_servers: SETTINGS.bootstrap.parallel_bootstrap_servers,
+        compression_level: SETTINGS.bootstrap.compression_level,
         max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
         max_simultaneous_bootstraps: SETTINGS.bootstrap.max_simultaneous_bootstraps,
         per_ip_min_interval: SETTINGS.bootstrap.per_ip_min_interval,
         ip_list_max_size: SETTINGS.bootstrap.ip_list_max_size,
         rate_limit: SETTINGS.bootstrap.rate_limit,
+        global_rate_limit: SETTINGS.bootstrap.global_rate_limit,
+        per_ip_rate_limit: SETTINGS.bootstrap.per_ip_rate_limit,
+        max_session_duration: SETTINGS.bootstrap.max_session_duration,
+        audit_log_path: SETTINGS.bootstrap.audit_log_path.clone(),
+        audit_log_max_size: SETTINGS.bootstrap.audit_log_max_size,
+        tls_mode: SETTINGS.bootstrap.tls_mode,
+        tls_certificate_path: SETTINGS.bootstrap.tls_certificate_path.clone(),
+        tls_private_key_path: SETTINGS.bootstrap.tls_private_key_path.clone(),
+        connect_with_tls: SETTINGS.bootstrap.connect_with_tls,
+        accept_quic: SETTINGS.bootstrap.accept_quic,
+        connect_with_quic: SETTINGS.bootstrap.connect_with_quic,
+        challenge_mode: SETTINGS.bootstrap.challenge_mode,
+        challenge_difficulty: SETTINGS.bootstrap.challenge_difficulty,
+        challenge_tokens: SETTINGS.bootstrap.challenge_tokens.clone(),
+        challenge_token: SETTINGS.bootstrap.challenge_token.clone(),
+        challenge_timeout: SETTINGS.bootstrap.challenge_timeout,
+        max_simultaneous_challenges: SETTINGS.bootstrap.max_simultaneous_challenges,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -403,6 +543,13 @@ async fn launch(
         max_denunciation_changes_length: MAX_DENUNCIATION_CHANGES_LENGTH,
     };
 
+    let (bootstrap_progress_sender, bootstrap_progress_receiver) =
+        crossbeam_channel::bounded(BOOTSTRAP_PROGRESS_CHANNEL_SIZE);
+    let bootstrap_progress_logger = std::thread::Builder::new()
+        .name("bootstrap-progress".into())
+        .spawn(move || log_bootstrap_progress(bootstrap_progress_receiver))
+        .expect("failed to spawn thread : bootstrap-progress");
+
     let bootstrap_state = match get_state(
         &bootstrap_config,
         final_state.clone(),
@@ -413,6 +560,7 @@ async fn launch(
         args.restart_from_snapshot_at_period,
         sig_int_toggled.clone(),
         massa_metrics.clone(),
+        Some(bootstrap_progress_sender),
     ) {
         Ok(vals) => vals,
         Err(BootstrapError::Interupted(msg)) => {
@@ -421,6 +569,8 @@ async fn launch(
         }
         Err(err) => panic!("critical error detected in the bootstrap process: {}", err),
     };
+    // the progress channel is closed once the bootstrap is over
+    let _ = bootstrap_progress_logger.join();
 
     if !final_state.read().is_db_valid() {
         // TODO: Bootstrap again instead of panicking
@@ -446,17 +596,33 @@ async fn launch(
             .get_prev_slot(THREAD_COUNT)
             .unwrap();
 
-        final_state
-            .read()
-            .mip_store
-            .is_consistent_with_shutdown_period(
-                last_shutdown_start,
-                last_shutdown_end,
-                THREAD_COUNT,
-                T0,
-                *GENESIS_TIMESTAMP,
+        let check_mip_store = || {
+            final_state
+                .read()
+                .mip_store
+                .is_consistent_with_shutdown_period(
+                    last_shutdown_start,
+                    last_shutdown_end,
+                    THREAD_COUNT,
+                    T0,
+                    *GENESIS_TIMESTAMP,
+                )
+        };
+        // our state is still valid: only the versioning is downloaded again
+        if let Err(err) = check_mip_store() {
+            warn!(
+                "Mip store is not consistent with shutdown period ({}): re-syncing the versioning",
+                err
+            );
+            resync_versioning(
+                &bootstrap_config,
+                final_state.clone(),
+                DefaultConnector,
+                *VERSION,
             )
-            .expect("Mip store is not consistent with shutdown period")
+            .expect("could not re-sync the versioning");
+            check_mip_store().expect("Mip store is not consistent with shutdown period");
+        }
     }
 
     // Storage costs constants
@@ -471,6 +637,11 @@ async fn launch(
     // launch execution module
     let execution_config = ExecutionConfig {
         max_final_events: SETTINGS.execution.max_final_events,
+        final_events_retention_periods: SETTINGS.execution.final_events_retention_periods,
+        final_events_capacity: SETTINGS.execution.final_events_capacity,
+        final_transfers_retention_periods: SETTINGS.execution.final_transfers_retention_periods,
+        max_transfers_per_query: SETTINGS.execution.max_transfers_per_query,
+        max_final_operation_changes_slots: SETTINGS.execution.max_final_operation_changes_slots,
         readonly_queue_length: SETTINGS.execution.readonly_queue_length,
         cursor_delay: SETTINGS.execution.cursor_delay,
         max_async_gas: MAX_ASYNC_GAS,
@@ -484,12 +655,19 @@ async fn launch(
         operation_validity_period: OPERATION_VALIDITY_PERIODS,
         periods_per_cycle: PERIODS_PER_CYCLE,
         stats_time_window_duration: SETTINGS.execution.stats_time_window_duration,
+        stats_history_duration: SETTINGS.execution.stats_history_duration,
+        disk_usage_sample_interval: SETTINGS.execution.disk_usage_sample_interval,
+        disk_usage_forecast_window: SETTINGS.execution.disk_usage_forecast_window,
+        disk_usage_alert_threshold: SETTINGS.execution.disk_usage_alert_threshold,
+        disk_usage_alert_webhook: SETTINGS.execution.disk_usage_alert_webhook.clone(),
         max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
+        roll_sale_grace_cycles: SETTINGS.execution.roll_sale_grace_cycles,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         max_bytecode_size: MAX_BYTECODE_LENGTH,
         max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
         storage_costs_constants,
         max_read_only_gas: SETTINGS.execution.max_read_only_gas,
+        gas_estimation_margin_percent: SETTINGS.execution.gas_estimation_margin_percent,
         gas_costs: GasCosts::new(
             SETTINGS.execution.abi_gas_costs_file.clone(),
             SETTINGS.execution.wasm_gas_costs_file.clone(),
@@ -506,6 +684,12 @@ async fn launch(
         broadcast_slot_execution_output_channel_capacity: SETTINGS
             .execution
             .broadcast_slot_execution_output_channel_capacity,
+        broadcast_cycle_completion_channel_capacity: SETTINGS
+            .execution
+            .broadcast_cycle_completion_channel_capacity,
+        broadcast_operation_changes: SETTINGS.execution.broadcast_operation_changes,
+        broadcast_backpressure_timeout: SETTINGS.execution.broadcast_backpressure_timeout,
+        abi_profile_slots: SETTINGS.execution.abi_profile_slots,
         max_event_size: MAX_EVENT_DATA_SIZE,
         max_function_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameter_length: MAX_PARAMETERS_SIZE,
@@ -516,6 +700,10 @@ async fn launch(
             execution_config.broadcast_slot_execution_output_channel_capacity,
         )
         .0,
+        cycle_completion_sender: broadcast::channel(
+            execution_config.broadcast_cycle_completion_channel_capacity,
+        )
+        .0,
     };
 
     let (execution_manager, execution_controller) = start_execution_worker(
@@ -539,9 +727,17 @@ async fn launch(
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         max_operation_pool_size: SETTINGS.pool.max_operation_pool_size,
         max_operation_pool_excess_items: SETTINGS.pool.max_operation_pool_excess_items,
+        max_operations_per_address: SETTINGS.pool.max_operations_per_address,
+        spam_score_retention_percent: SETTINGS.pool.spam_score_retention_percent,
+        minimal_fees: SETTINGS.pool.minimal_fees,
+        dynamic_fee_floor: SETTINGS.pool.dynamic_fee_floor,
+        fee_floor_target_block_fullness_percent: SETTINGS
+            .pool
+            .fee_floor_target_block_fullness_percent,
         operation_pool_refresh_interval: SETTINGS.pool.operation_pool_refresh_interval,
         operation_max_future_start_delay: SETTINGS.pool.operation_max_future_start_delay,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_endorsements_pool_size_per_thread,
+        endorsement_health_block_count: SETTINGS.pool.endorsement_health_block_count,
         operations_channel_size: POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
         endorsements_channel_size: POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE,
         denunciations_channel_size: POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE,
@@ -554,8 +750,14 @@ async fn launch(
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        denunciation_pool_expire_periods: SETTINGS.pool.denunciation_pool_expire_periods,
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         last_start_period: final_state.read().last_start_period,
+        admission_check_structure: SETTINGS.pool.admission_check_structure,
+        admission_check_signature: SETTINGS.pool.admission_check_signature,
+        admission_check_expiry: SETTINGS.pool.admission_check_expiry,
+        admission_check_balance: SETTINGS.pool.admission_check_balance,
+        admission_check_policies: SETTINGS.pool.admission_check_policies,
     };
 
     let pool_channels = PoolChannels {
@@ -566,6 +768,10 @@ async fn launch(
             .0,
             operation_sender: broadcast::channel(pool_config.broadcast_operations_channel_capacity)
                 .0,
+            operation_lifecycle_sender: broadcast::channel(
+                pool_config.broadcast_operations_channel_capacity,
+            )
+            .0,
         },
         selector: selector_controller.clone(),
         execution_controller: execution_controller.clone(),
@@ -573,9 +779,10 @@ async fn launch(
 
     let (pool_manager, pool_controller) = start_pool_controller(
         pool_config,
-        &shared_storage,
+        &shared_storage.clone_without_refs().with_owner("pool"),
         pool_channels.clone(),
         node_wallet.clone(),
+        SETTINGS.pool.persist_pending_items.then(|| db.clone()),
     );
 
     // launch protocol controller
@@ -655,7 +862,10 @@ async fn launch(
         read_write_limit_bytes_per_second: SETTINGS.protocol.read_write_limit_bytes_per_second
             as u128,
         try_connection_timer: SETTINGS.protocol.try_connection_timer,
-        unban_everyone_timer: SETTINGS.protocol.unban_everyone_timer,
+        peer_ban_duration: SETTINGS.protocol.peer_ban_duration,
+        peer_store_file: SETTINGS.protocol.peer_store_file.clone(),
+        peer_store_interval: SETTINGS.protocol.peer_store_interval,
+        peer_score_retention_percent: SETTINGS.protocol.peer_score_retention_percent,
         max_in_connections: SETTINGS.protocol.max_in_connections,
         timeout_connection: SETTINGS.protocol.timeout_connection,
         message_timeout: SETTINGS.protocol.message_timeout,
@@ -664,6 +874,7 @@ async fn launch(
             .protocol
             .routable_ip
             .or(SETTINGS.network.routable_ip),
+        routable_ipv6: SETTINGS.protocol.routable_ipv6,
         debug: false,
         peers_categories: SETTINGS.protocol.peers_categories.clone(),
         default_category_info: SETTINGS.protocol.default_category_info,
@@ -689,7 +900,15 @@ async fn launch(
         operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         periods_per_cycle: PERIODS_PER_CYCLE,
         stats_timespan: SETTINGS.consensus.stats_timespan,
-        force_keep_final_periods: SETTINGS.consensus.force_keep_final_periods,
+        force_keep_final_periods: SETTINGS.consensus.force_keep_final_duration.map_or(
+            SETTINGS.consensus.force_keep_final_periods,
+            |duration| {
+                // each thread finalizes one period per t0, round up to cover the whole time span
+                let periods =
+                    duration.to_millis().saturating_add(T0.to_millis() - 1) / T0.to_millis();
+                std::cmp::max(SETTINGS.consensus.force_keep_final_periods, periods)
+            },
+        ),
         endorsement_count: ENDORSEMENT_COUNT,
         block_db_prune_interval: SETTINGS.consensus.block_db_prune_interval,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
@@ -703,10 +922,17 @@ async fn launch(
         broadcast_filled_blocks_channel_capacity: SETTINGS
             .consensus
             .broadcast_filled_blocks_channel_capacity,
+        broadcast_finalized_blocks_channel_capacity: SETTINGS
+            .consensus
+            .broadcast_finalized_blocks_channel_capacity,
+        broadcast_blockclique_reorgs_channel_capacity: SETTINGS
+            .consensus
+            .broadcast_blockclique_reorgs_channel_capacity,
         last_start_period: final_state.read().last_start_period,
         force_keep_final_periods_without_ops: SETTINGS
             .consensus
             .force_keep_final_periods_without_ops,
+        state_cache_path: SETTINGS.consensus.state_cache_path.clone(),
     };
 
     let (consensus_event_sender, consensus_event_receiver) =
@@ -727,6 +953,14 @@ async fn launch(
                 consensus_config.broadcast_filled_blocks_channel_capacity,
             )
             .0,
+            finalized_block_sender: broadcast::channel(
+                consensus_config.broadcast_finalized_blocks_channel_capacity,
+            )
+            .0,
+            blockclique_reorg_sender: broadcast::channel(
+                consensus_config.broadcast_blockclique_reorgs_channel_capacity,
+            )
+            .0,
         },
     };
 
@@ -734,7 +968,7 @@ async fn launch(
         consensus_config,
         consensus_channels.clone(),
         bootstrap_state.graph,
-        shared_storage.clone(),
+        shared_storage.clone().with_owner("consensus"),
         massa_metrics.clone(),
     );
 
@@ -744,7 +978,7 @@ async fn launch(
         consensus_controller.clone(),
         bootstrap_state.peers,
         pool_controller.clone(),
-        shared_storage.clone(),
+        shared_storage.clone().with_owner("protocol"),
         protocol_channels,
         mip_store.clone(),
         massa_metrics.clone(),
@@ -772,7 +1006,7 @@ async fn launch(
         consensus: consensus_controller.clone(),
         pool: pool_controller.clone(),
         protocol: protocol_controller.clone(),
-        storage: shared_storage.clone(),
+        storage: shared_storage.clone().with_owner("factory"),
     };
     let factory_manager = start_factory(
         factory_config,
@@ -782,13 +1016,21 @@ async fn launch(
     );
 
     let bootstrap_manager = bootstrap_config.listen_addr.map(|addr| {
-        let (listener_stopper, listener) =
+        let (listener_stopper, mut listener) =
             BootstrapTcpListener::create(&addr).unwrap_or_else(|_| {
                 panic!(
                     "{}",
                     format!("Could not bind to address: {}", addr).as_str()
                 )
             });
+        if bootstrap_config.accept_quic {
+            listener.listen_quic(&addr).unwrap_or_else(|e| {
+                panic!(
+                    "Could not bind the QUIC listener to address {}: {}",
+                    addr, e
+                )
+            });
+        }
 
         start_bootstrap_server(
             listener,
@@ -813,6 +1055,7 @@ async fn launch(
         openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
+        bootstrap_audit_log_path: SETTINGS.bootstrap.audit_log_path.clone(),
         max_request_body_size: SETTINGS.api.max_request_body_size,
         max_response_body_size: SETTINGS.api.max_response_body_size,
         max_connections: SETTINGS.api.max_connections,
@@ -879,7 +1122,7 @@ async fn launch(
             pool_controller: pool_controller.clone(),
             protocol_controller: protocol_controller.clone(),
             selector_controller: selector_controller.clone(),
-            storage: shared_storage.clone(),
+            storage: shared_storage.clone().with_owner("grpc"),
             grpc_config: grpc_public_config.clone(),
             protocol_config: protocol_config.clone(),
             node_id,
@@ -887,6 +1130,7 @@ async fn launch(
             keypair_factory: KeyPairFactory {
                 mip_store: mip_store.clone(),
             },
+            massa_metrics: massa_metrics.clone(),
         };
 
         // Spawn gRPC PUBLIC API
@@ -927,6 +1171,7 @@ async fn launch(
             stop_cv: sig_int_toggled.clone(),
             node_wallet: node_wallet.clone(),
             bs_white_black_list,
+            massa_metrics: massa_metrics.clone(),
         };
 
         // Spawn gRPC PRIVATE API
@@ -947,7 +1192,7 @@ async fn launch(
     #[cfg(feature = "op_spammer")]
     start_operation_injector(
         *GENESIS_TIMESTAMP,
-        shared_storage.clone_without_refs(),
+        shared_storage.clone_without_refs().with_owner("op_spammer"),
         node_wallet.read().clone(),
         pool_controller.clone(),
         protocol_controller.clone(),
@@ -961,6 +1206,9 @@ async fn launch(
         api_config.clone(),
         sig_int_toggled,
         node_wallet,
+        final_state.clone(),
+        consensus_controller.clone(),
+        pool_controller.clone(),
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -982,7 +1230,7 @@ async fn launch(
         protocol_config.clone(),
         *VERSION,
         node_id,
-        shared_storage.clone(),
+        shared_storage.clone().with_owner("api"),
         mip_store.clone(),
     );
     let api_public_handle = api_public
@@ -1056,6 +1304,23 @@ async fn launch(
     )
 }
 
+/// Logs the progress of the bootstrap when its phase changes, and at most once per `BOOTSTRAP_PROGRESS_LOG_INTERVAL` otherwise
+fn log_bootstrap_progress(receiver: crossbeam_channel::Receiver<BootstrapProgress>) {
+    let mut last_log: Option<(BootstrapPhase, Instant)> = None;
+    for progress in receiver {
+        let should_log = match last_log {
+            Some((phase, at)) => {
+                phase != progress.phase || at.elapsed() >= BOOTSTRAP_PROGRESS_LOG_INTERVAL
+            }
+            None => true,
+        };
+        if should_log {
+            info!("Bootstrap progress: {}", progress);
+            last_log = Some((progress.phase, Instant::now()));
+        }
+    }
+}
+
 // Get the configuration of the gRPC server
 fn configure_grpc(
     name: ServiceName,
@@ -1068,6 +1333,10 @@ fn configure_grpc(
         enabled: settings.enabled,
         accept_http1: settings.accept_http1,
         enable_cors: settings.enable_cors,
+        enable_grpc_web: settings.enable_grpc_web,
+        grpc_web_bind: settings.grpc_web_bind,
+        grpc_web_allowed_origins: settings.grpc_web_allowed_origins.clone(),
+        grpc_web_cors_max_age: settings.grpc_web_cors_max_age.to_duration(),
         enable_health: settings.enable_health,
         enable_reflection: settings.enable_reflection,
         enable_tls: settings.enable_tls,
@@ -1089,6 +1358,8 @@ fn configure_grpc(
         tcp_nodelay: settings.tcp_nodelay,
         http2_keepalive_interval: settings.http2_keepalive_interval.map(|t| t.to_duration()),
         http2_keepalive_timeout: settings.http2_keepalive_timeout.map(|t| t.to_duration()),
+        stream_idle_timeout: settings.stream_idle_timeout.map(|t| t.to_duration()),
+        max_stream_lifetime: settings.max_stream_lifetime.map(|t| t.to_duration()),
         http2_adaptive_window: settings.http2_adaptive_window,
         max_frame_size: settings.max_frame_size,
         thread_count: THREAD_COUNT,
@@ -1234,10 +1505,47 @@ struct Args {
     #[arg(short = 'p', long = "pwd")]
     password: Option<String>,
 
+    /// Verify the final state database against its stored hash, print the report, then exit
+    #[arg(long = "verify-db")]
+    verify_db: bool,
+
     /// restart_from_snapshot_at_period
     #[arg(long = "restart-from-snapshot-at-period")]
     restart_from_snapshot_at_period: Option<u64>,
 
+    /// Import a genesis ledger from a CSV or JSON file, print its validation report and hash, then exit
+    #[arg(long = "import-genesis-ledger")]
+    import_genesis_ledger: Option<PathBuf>,
+
+    /// Output directory of the genesis ledger import
+    #[arg(long = "genesis-output-dir", default_value = "genesis")]
+    genesis_output_dir: PathBuf,
+
+    /// Expected total balance of the imported genesis ledger
+    #[arg(long = "genesis-total-balance")]
+    genesis_total_balance: Option<Amount>,
+
+    /// Expected total roll count of the imported genesis ledger
+    #[arg(long = "genesis-total-rolls")]
+    genesis_total_rolls: Option<u64>,
+
+    /// Export the final ledger on disk to a snapshot file, print its hash, then exit
+    #[arg(long = "export-ledger-snapshot")]
+    export_ledger_snapshot: Option<PathBuf>,
+
+    /// Build the initial ledger database from a snapshot file into the genesis output directory, print its hash, then exit
+    #[arg(long = "import-ledger-snapshot")]
+    import_ledger_snapshot: Option<PathBuf>,
+
+    /// Export the final state on disk to a bootstrap snapshot archive signed with the node key, then exit
+    #[arg(long = "export-bootstrap-snapshot")]
+    export_bootstrap_snapshot: Option<PathBuf>,
+
+    /// Bootstrap the state from a snapshot archive exported by a node of the bootstrap list,
+    /// then only stream the changes since its slot
+    #[arg(long = "bootstrap-snapshot")]
+    bootstrap_snapshot: Option<PathBuf>,
+
     #[cfg(feature = "op_spammer")]
     /// number of operations
     #[arg(
@@ -1287,6 +1595,21 @@ fn load_wallet(password: Option<String>, path: &Path) -> anyhow::Result<Arc<RwLo
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    if let Some(input) = &args.import_genesis_ledger {
+        return genesis_import::import_genesis_ledger(
+            input,
+            &args.genesis_output_dir,
+            args.genesis_total_balance,
+            args.genesis_total_rolls,
+        );
+    }
+    if let Some(output) = &args.export_ledger_snapshot {
+        return ledger_snapshot::export_ledger_snapshot(output);
+    }
+    if let Some(input) = &args.import_ledger_snapshot {
+        return ledger_snapshot::import_ledger_snapshot(input, &args.genesis_output_dir);
+    }
+
     let tokio_rt = tokio::runtime::Builder::new_multi_thread()
         .thread_name_fn(|| {
             static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
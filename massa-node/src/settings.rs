@@ -3,12 +3,13 @@
 //! Build here the default node settings from the configuration file toml
 use std::{collections::HashMap, path::PathBuf};
 
-use massa_bootstrap::IpType;
-use massa_models::{config::build_massa_settings, node::NodeId};
+use massa_bootstrap::{BootstrapChallengeMode, BootstrapTlsMode, IpType, TrustedCheckpoint};
+use massa_db_exports::MassaDBCompression;
+use massa_models::{amount::Amount, config::build_massa_settings, node::NodeId};
 use massa_protocol_exports::PeerCategoryInfo;
 use massa_time::MassaTime;
 use serde::Deserialize;
-use std::net::{IpAddr, SocketAddr};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 
 lazy_static::lazy_static! {
     pub static ref SETTINGS: Settings = build_massa_settings("massa-node", "MASSA_NODE");
@@ -22,10 +23,22 @@ pub struct LoggingSettings {
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExecutionSettings {
     pub max_final_events: usize,
+    pub final_events_retention_periods: u64,
+    pub final_events_capacity: u64,
+    pub final_transfers_retention_periods: u64,
+    pub max_transfers_per_query: usize,
+    pub max_final_operation_changes_slots: usize,
     pub readonly_queue_length: usize,
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
+    pub stats_history_duration: MassaTime,
+    pub disk_usage_sample_interval: MassaTime,
+    pub disk_usage_forecast_window: MassaTime,
+    pub disk_usage_alert_threshold: MassaTime,
+    pub disk_usage_alert_webhook: Option<String>,
+    pub roll_sale_grace_cycles: u64,
     pub max_read_only_gas: u64,
+    pub gas_estimation_margin_percent: u64,
     pub abi_gas_costs_file: PathBuf,
     pub wasm_gas_costs_file: PathBuf,
     pub hd_cache_path: PathBuf,
@@ -34,6 +47,14 @@ pub struct ExecutionSettings {
     pub snip_amount: usize,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// completed cycles channel capacity
+    pub broadcast_cycle_completion_channel_capacity: usize,
+    /// whether the ledger changes of each operation are included in the slot execution outputs broadcast
+    pub broadcast_operation_changes: bool,
+    /// maximum time the execution waits for the slowest subscriber of a full slot execution outputs channel
+    pub broadcast_backpressure_timeout: MassaTime,
+    /// number of executed slots for which the host ABI call counters are kept (0 disables the profiling)
+    pub abi_profile_slots: usize,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -46,7 +67,33 @@ pub struct LedgerSettings {
     pub initial_ledger_path: PathBuf,
     pub disk_ledger_path: PathBuf,
     pub final_history_length: usize,
+    pub final_history_max_bytes: Option<usize>,
     pub initial_deferred_credits_path: Option<PathBuf>,
+    pub db_block_cache_size: Option<usize>,
+    pub db_write_buffer_size: Option<usize>,
+    pub db_bloom_filter_bits_per_key: Option<f64>,
+    pub db_max_open_files: Option<i32>,
+    pub db_state_cf_compression: Option<MassaDBCompression>,
+    pub db_metadata_cf_compression: Option<MassaDBCompression>,
+    pub db_versioning_cf_compression: Option<MassaDBCompression>,
+    pub db_local_cf_compression: Option<MassaDBCompression>,
+    pub db_max_backups: Option<usize>,
+    pub db_max_backup_age: Option<MassaTime>,
+    pub db_archival: bool,
+    pub db_archive_max_periods: Option<u64>,
+    pub db_wal_sync: Option<DBWalSyncSettings>,
+    pub db_compaction_rate_limit: Option<i64>,
+    pub db_max_background_jobs: Option<i32>,
+    pub snapshot_interval_cycles: u64,
+}
+
+/// Durability policy of the ledger db writes, see `MassaDBWalSync`
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DBWalSyncSettings {
+    EveryBatch,
+    Periodic(MassaTime),
+    Disabled,
 }
 
 /// Bootstrap configuration.
@@ -63,15 +110,61 @@ pub struct BootstrapSettings {
     pub read_error_timeout: MassaTime,
     pub write_error_timeout: MassaTime,
     pub retry_delay: MassaTime,
+    /// max delay between two bootstrap attempts
+    pub max_retry_delay: MassaTime,
+    /// max share of the retry delay randomly cut from it, between 0 and 1
+    pub retry_jitter: f64,
+    /// time after which the node stops retrying to bootstrap, unlimited if 0
+    pub max_bootstrap_duration: MassaTime,
     pub max_ping: MassaTime,
     pub max_clock_delta: MassaTime,
     pub cache_duration: MassaTime,
     pub max_simultaneous_bootstraps: u32,
+    /// number of servers the state is bootstrapped from in parallel
+    pub parallel_bootstrap_servers: usize,
+    /// zstd level of the compression of bootstrap server messages, 0 to disable it
+    pub compression_level: i32,
     pub per_ip_min_interval: MassaTime,
     pub ip_list_max_size: usize,
     pub rate_limit: u64,
+    /// bandwidth of the server shared by all its bootstrap sessions, unlimited if 0
+    pub global_rate_limit: u64,
+    /// bandwidth of the server shared by the bootstrap sessions of an IP, unlimited if 0
+    pub per_ip_rate_limit: u64,
+    /// time after which the server stops sending data to a bootstrap session
+    pub max_session_duration: MassaTime,
+    /// JSON lines file the server records its bootstrap sessions in, if any
+    pub audit_log_path: Option<PathBuf>,
+    /// size in bytes after which the audit log file is rotated
+    pub audit_log_max_size: u64,
+    /// whether the server accepts or requires TLS connections
+    pub tls_mode: BootstrapTlsMode,
+    /// PEM file of the TLS certificate chain of the server, a self-signed one is generated if unset
+    pub tls_certificate_path: Option<PathBuf>,
+    /// PEM file of the private key of the TLS certificate of the server
+    pub tls_private_key_path: Option<PathBuf>,
+    /// whether the client connects to the bootstrap servers with TLS
+    pub connect_with_tls: bool,
+    /// whether the server also accepts QUIC connections on the UDP port of its address (experimental)
+    pub accept_quic: bool,
+    /// whether the client connects to the bootstrap servers with QUIC (experimental)
+    pub connect_with_quic: bool,
+    /// challenge the server sends to the clients after the handshake
+    pub challenge_mode: BootstrapChallengeMode,
+    /// leading zero bits of the proofs of work asked by the server
+    pub challenge_difficulty: u8,
+    /// tokens accepted by the server in `Token` challenge mode
+    pub challenge_tokens: Vec<String>,
+    /// token the client answers the challenges of the servers with
+    pub challenge_token: Option<String>,
+    /// time the server waits for the answer of a client to its challenge
+    pub challenge_timeout: MassaTime,
+    /// max simultaneous clients answering the challenge, which do not count toward `max_simultaneous_bootstraps` yet
+    pub max_simultaneous_challenges: u32,
     /// Allocated time with which to manage the bootstrap process
     pub bootstrap_timeout: MassaTime,
+    /// state the bootstrapped state is verified against, if any
+    pub trusted_checkpoint: Option<TrustedCheckpoint>,
 }
 
 /// Factory settings
@@ -90,14 +183,40 @@ pub struct FactorySettings {
 pub struct PoolSettings {
     pub max_operation_pool_size: usize,
     pub max_operation_pool_excess_items: usize,
+    /// max number of pending operations per sender address
+    pub max_operations_per_address: usize,
+    /// percentage of the spam score of each sender address kept at each pool refresh
+    pub spam_score_retention_percent: u64,
+    /// minimal fee of the operations accepted in the pool
+    pub minimal_fees: Amount,
+    /// whether the minimal fee is raised by a floor following the fullness of the final blocks
+    pub dynamic_fee_floor: bool,
+    /// fullness (in percent) of the final blocks above which the fee floor rises, and below which it decreases
+    pub fee_floor_target_block_fullness_percent: u64,
     pub operation_max_future_start_delay: MassaTime,
     pub operation_pool_refresh_interval: MassaTime,
     pub max_endorsements_pool_size_per_thread: usize,
+    /// number of blocks produced by the node over which the endorsement pool health is computed
+    pub endorsement_health_block_count: usize,
+    /// number of periods after which the denunciation pool forgets an entry, capped by the denunciation expiry
+    pub denunciation_pool_expire_periods: u64,
     pub max_item_return_count: usize,
     /// endorsements channel capacity
     pub broadcast_endorsements_channel_capacity: usize,
     /// operations channel capacity
     pub broadcast_operations_channel_capacity: usize,
+    /// whether admission checks the size and gas limits of incoming operations
+    pub admission_check_structure: bool,
+    /// whether admission verifies the signature of incoming operations
+    pub admission_check_signature: bool,
+    /// whether admission rejects operations that expired before the last final periods
+    pub admission_check_expiry: bool,
+    /// whether admission rejects operations whose sender can't afford their max spending
+    pub admission_check_balance: bool,
+    /// whether admission applies the registered admission policies
+    pub admission_check_policies: bool,
+    /// whether pending operations and endorsements are persisted to be reloaded after a restart
+    pub persist_pending_items: bool,
 }
 
 /// API and server configuration, read from a file configuration.
@@ -155,6 +274,10 @@ pub struct ConsensusSettings {
     pub stats_timespan: MassaTime,
     /// force keep at least this number of final periods in RAM for each thread
     pub force_keep_final_periods: u64,
+    /// force keep at least the final periods of this time span in RAM for each thread, on top of `force_keep_final_periods`
+    pub force_keep_final_duration: Option<MassaTime>,
+    /// file where the final blocks are saved on a clean shutdown, to only download the missing ones on restart
+    pub state_cache_path: Option<PathBuf>,
     /// force keep at least this number of final periods without operations in RAM for each thread
     pub force_keep_final_periods_without_ops: u64,
     /// old blocks are pruned every `block_db_prune_interval`
@@ -165,6 +288,10 @@ pub struct ConsensusSettings {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// finalized blocks channel capacity
+    pub broadcast_finalized_blocks_channel_capacity: usize,
+    /// blockclique reorgs channel capacity
+    pub broadcast_blockclique_reorgs_channel_capacity: usize,
 }
 
 // TODO: Remove one date. Kept for retro compatibility.
@@ -241,6 +368,8 @@ pub struct ProtocolSettings {
     pub bind: SocketAddr,
     /// Ip seen by others. If none the bind ip is used
     pub routable_ip: Option<IpAddr>,
+    /// IPv6 seen by others in addition to `routable_ip`, for dual-stack nodes
+    pub routable_ipv6: Option<Ipv6Addr>,
     /// Time threshold to have a connection to a node
     pub connect_timeout: MassaTime,
     /// Number of tester threads
@@ -251,8 +380,14 @@ pub struct ProtocolSettings {
     pub try_connection_timer: MassaTime,
     /// try connection timer for the same peer
     pub try_connection_timer_same_peer: MassaTime,
-    /// periodically unban every peer
-    pub unban_everyone_timer: MassaTime,
+    /// duration of the first ban of a peer, doubled for each ban it got recently
+    pub peer_ban_duration: MassaTime,
+    /// file where the peer scores and bans are persisted
+    pub peer_store_file: PathBuf,
+    /// interval at which expired bans are lifted, peer scores decay and the peer store is saved
+    pub peer_store_interval: MassaTime,
+    /// percentage of each peer score kept at each peer store interval
+    pub peer_score_retention_percent: u64,
     /// Timeout connection
     pub timeout_connection: MassaTime,
     /// Message timeout
@@ -281,6 +416,14 @@ pub struct GrpcSettings {
     pub accept_http1: bool,
     /// whether to enable CORS. Works only if `accept_http1` is true
     pub enable_cors: bool,
+    /// whether to also serve the API over grpc-web (HTTP/1.1) on `grpc_web_bind`, for browsers
+    pub enable_grpc_web: bool,
+    /// bind for the grpc-web server
+    pub grpc_web_bind: SocketAddr,
+    /// origins allowed to make cross-origin grpc-web requests. Any origin is allowed if empty
+    pub grpc_web_allowed_origins: Vec<String>,
+    /// how long browsers may cache the grpc-web CORS preflight responses
+    pub grpc_web_cors_max_age: MassaTime,
     /// whether to enable gRPC health service
     pub enable_health: bool,
     /// whether to enable gRPC reflection
@@ -325,6 +468,10 @@ pub struct GrpcSettings {
     pub http2_keepalive_interval: Option<MassaTime>,
     /// sets a timeout for receiving an acknowledgement of the keepalive ping. Default is 20 seconds
     pub http2_keepalive_timeout: Option<MassaTime>,
+    /// closes bidirectional streams on which the client sent no message for this duration. Default is no timeout (`None`)
+    pub stream_idle_timeout: Option<MassaTime>,
+    /// closes bidirectional streams open for longer than this duration. Default is no limit (`None`)
+    pub max_stream_lifetime: Option<MassaTime>,
     /// sets whether to use an adaptive flow control. Defaults to false
     pub http2_adaptive_window: Option<bool>,
     /// sets the maximum frame size to use for HTTP2(must be within 16,384 and 16,777,215). If not set, will default from underlying transport
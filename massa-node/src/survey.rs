@@ -124,6 +124,11 @@ impl MassaSurvey {
                                     massa_metrics.set_operations_pool(pool_controller.get_operation_count());
                                     massa_metrics.set_endorsements_pool(pool_controller.get_endorsement_count());
                                     massa_metrics.set_denunciations_pool(pool_controller.get_denunciation_count());
+                                    let endorsement_stats = pool_controller.get_endorsement_pool_stats();
+                                    massa_metrics.set_endorsement_pool_health(
+                                        endorsement_stats.fill_rate.unwrap_or(1.0),
+                                        endorsement_stats.missed_endorsement_count,
+                                    );
 
                                     let count = std::thread::available_parallelism()
                                     .unwrap_or(std::num::NonZeroUsize::MIN)
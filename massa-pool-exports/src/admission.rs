@@ -0,0 +1,54 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Stages of the operation admission pipeline, in the order in which they are applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum OperationAdmissionStage {
+    /// size and gas limits of the operation
+    Structure,
+    /// signature of the operation
+    Signature,
+    /// validity period of the operation with regard to the last final periods
+    Expiry,
+    /// max spending of the operation with regard to the candidate balance of its sender
+    BalancePrecheck,
+    /// custom admission policies registered on the pool
+    Policy,
+    /// insertion in the pool container, subject to the max number of pending operations per address and to the pool capacity
+    Insertion,
+}
+
+impl OperationAdmissionStage {
+    /// All the stages, in pipeline order
+    pub const ALL: [OperationAdmissionStage; 6] = [
+        OperationAdmissionStage::Structure,
+        OperationAdmissionStage::Signature,
+        OperationAdmissionStage::Expiry,
+        OperationAdmissionStage::BalancePrecheck,
+        OperationAdmissionStage::Policy,
+        OperationAdmissionStage::Insertion,
+    ];
+}
+
+/// Counters recorded by a single admission stage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AdmissionStageStats {
+    /// number of operations that entered the stage
+    pub checked: u64,
+    /// number of operations rejected by the stage
+    pub rejected: u64,
+    /// cumulated time spent in the stage
+    pub total_duration: Duration,
+}
+
+impl AdmissionStageStats {
+    /// Number of operations that went through the stage
+    pub fn accepted(&self) -> u64 {
+        self.checked.saturating_sub(self.rejected)
+    }
+}
+
+/// Per-stage statistics of the operation admission pipeline
+pub type OperationAdmissionStats = BTreeMap<OperationAdmissionStage, AdmissionStageStats>;
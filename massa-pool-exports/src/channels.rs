@@ -1,5 +1,8 @@
 use massa_execution_exports::ExecutionController;
-use massa_models::{endorsement::SecureShareEndorsement, operation::SecureShareOperation};
+use massa_models::{
+    endorsement::SecureShareEndorsement,
+    operation::{OperationLifecycleEvent, SecureShareOperation},
+};
 use massa_pos_exports::SelectorController;
 
 /// channels used by the pool worker
@@ -20,4 +23,6 @@ pub struct PoolBroadcasts {
     pub endorsement_sender: tokio::sync::broadcast::Sender<SecureShareEndorsement>,
     /// Broadcast channel for new operations
     pub operation_sender: tokio::sync::broadcast::Sender<SecureShareOperation>,
+    /// Broadcast channel for pending operations leaving the pool
+    pub operation_lifecycle_sender: tokio::sync::broadcast::Sender<OperationLifecycleEvent>,
 }
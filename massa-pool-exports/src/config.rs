@@ -27,8 +27,20 @@ pub struct PoolConfig {
     pub max_operation_pool_size: usize,
     /// max excess on pool size (in-between refreshes)
     pub max_operation_pool_excess_items: usize,
+    /// max number of pending operations per sender address
+    pub max_operations_per_address: usize,
+    /// percentage of the spam score of each sender address kept at each pool refresh
+    pub spam_score_retention_percent: u64,
+    /// minimal fee of the operations accepted in the pool
+    pub minimal_fees: Amount,
+    /// whether the minimal fee is raised by a floor following the fullness of the final blocks
+    pub dynamic_fee_floor: bool,
+    /// fullness (in percent) of the final blocks above which the fee floor rises, and below which it decreases
+    pub fee_floor_target_block_fullness_percent: u64,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
+    /// number of blocks produced by the node over which the endorsement pool health is computed
+    pub endorsement_health_block_count: usize,
     /// max number of endorsements per block
     pub max_block_endorsement_count: u32,
     /// operations channel capacity
@@ -51,8 +63,20 @@ pub struct PoolConfig {
     pub periods_per_cycle: u64,
     /// denunciation expiration (in periods)
     pub denunciation_expire_periods: u64,
+    /// number of periods after which the denunciation pool forgets an entry, capped by `denunciation_expire_periods`
+    pub denunciation_pool_expire_periods: u64,
     /// max number of denunciations that can be included in a block header
     pub max_denunciations_per_block_header: u32,
+    /// whether admission checks the size and gas limits of incoming operations
+    pub admission_check_structure: bool,
+    /// whether admission verifies the signature of incoming operations
+    pub admission_check_signature: bool,
+    /// whether admission rejects operations that expired before the last final periods
+    pub admission_check_expiry: bool,
+    /// whether admission rejects operations whose sender can't afford their max spending
+    pub admission_check_balance: bool,
+    /// whether admission applies the registered admission policies
+    pub admission_check_policies: bool,
     /// last_start_period
     /// * If start all new network: set to 0
     /// * If from snapshot: retrieve from args
@@ -1,14 +1,21 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    address::Address,
     block_id::BlockId,
     denunciation::{Denunciation, DenunciationPrecursor},
     endorsement::EndorsementId,
     operation::OperationId,
     slot::Slot,
+    stats::EndorsementPoolStats,
 };
 use massa_storage::Storage;
 
+use crate::{
+    BlockUsage, DenunciationPoolEntry, FeeRecommendation, OperationAdmissionStats,
+    OperationPoolSummary, PendingOperationInfo,
+};
+
 /// Trait defining a pool controller
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
 pub trait PoolController: Send + Sync {
@@ -24,6 +31,10 @@ pub trait PoolController: Send + Sync {
     /// Asynchronously notify of new consensus final periods. Simply print a warning on failure.
     fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]);
 
+    /// Asynchronously notify of the resources used by new final blocks, from which the fee floor is derived.
+    /// Simply print a warning on failure.
+    fn notify_final_block_usage(&mut self, block_usage: Vec<BlockUsage>);
+
     /// Get operations for block creation.
     fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage);
 
@@ -40,9 +51,31 @@ pub trait PoolController: Send + Sync {
     /// Get the number of endorsements in the pool
     fn get_endorsement_count(&self) -> usize;
 
+    /// Get the health of the endorsement pool: how many endorsements were available for the last blocks
+    /// produced by the node, and which endorsers missed theirs
+    fn get_endorsement_pool_stats(&self) -> EndorsementPoolStats;
+
     /// Get the number of operations in the pool
     fn get_operation_count(&self) -> usize;
 
+    /// Get the per-stage statistics of the operation admission pipeline
+    fn get_operation_admission_stats(&self) -> OperationAdmissionStats;
+
+    /// Get at most `limit` pending operations, from the highest to the lowest priority, skipping the first `offset` ones.
+    /// If `address` is provided, only the operations created by this address are listed.
+    fn get_pending_operations(
+        &self,
+        address: Option<Address>,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<PendingOperationInfo>;
+
+    /// Get summary statistics of the operation pool, listing the `top_senders` addresses with the most pending operations
+    fn get_operation_pool_summary(&self, top_senders: usize) -> OperationPoolSummary;
+
+    /// Get the minimal fee accepted by the pool and the fee likely to get an operation included in the next blocks
+    fn get_fee_recommendation(&self) -> FeeRecommendation;
+
     /// Check if the pool contains a list of endorsements. Returns one boolean per item.
     fn contains_endorsements(&self, endorsements: &[EndorsementId]) -> Vec<bool>;
 
@@ -56,6 +89,9 @@ pub trait PoolController: Send + Sync {
     /// Get the number of denunciations in the pool
     fn get_denunciation_count(&self) -> usize;
 
+    /// Get the entries of the denunciation pool, with the progress of their denunciation, by increasing slot
+    fn get_denunciation_pool_entries(&self) -> Vec<DenunciationPoolEntry>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn PoolController>`.
     fn clone_box(&self) -> Box<dyn PoolController>;
@@ -0,0 +1,27 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{amount::Amount, slot::Slot};
+
+/// Resources used by the operations of a final block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockUsage {
+    /// slot of the block
+    pub slot: Slot,
+    /// number of operations of the block
+    pub operation_count: u32,
+    /// cumulated max gas of the operations of the block
+    pub gas: u64,
+    /// cumulated serialized size of the operations of the block, in bytes
+    pub size: u64,
+}
+
+/// Fees to pay for an operation to enter the pool and to be likely included in a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeRecommendation {
+    /// minimal fee accepted by the pool: the configured minimal fee, raised by the congestion-based floor if enabled
+    pub minimal_fee: Amount,
+    /// fee likely to get an operation included in the next blocks:
+    /// at least the highest floor reachable after one more full block,
+    /// and at least the lowest fee of the pending operations filling the next period of blocks
+    pub recommended_fee: Amount,
+}
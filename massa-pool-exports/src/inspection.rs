@@ -0,0 +1,80 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, amount::Amount, operation::OperationId, slot::Slot};
+
+/// A pending operation of the operation pool, as ranked by the pool
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingOperationInfo {
+    /// ID of the operation
+    pub id: OperationId,
+    /// address of the creator of the operation
+    pub creator_address: Address,
+    /// thread of the creator of the operation
+    pub thread: u8,
+    /// fee paid by the operation
+    pub fee: Amount,
+    /// max gas of the operation
+    pub max_gas: u64,
+    /// serialized size of the operation, in bytes
+    pub size: usize,
+    /// priority score of the operation: raw fee paid per unit of weighted gas,
+    /// weighted gas being its max gas plus the gas equivalent of its size in a block
+    pub score: f64,
+    /// last period at which the operation can be included in a block
+    pub expire_period: u64,
+    /// number of pending operations of the creator of the operation
+    pub creator_operation_count: usize,
+}
+
+/// Number of pending operations whose fee is in a range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeHistogramBucket {
+    /// lowest fee of the range (inclusive)
+    pub min_fee: Amount,
+    /// highest fee of the range (inclusive)
+    pub max_fee: Amount,
+    /// number of pending operations paying a fee in the range
+    pub count: usize,
+}
+
+/// Summary statistics of the operation pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationPoolSummary {
+    /// number of pending operations
+    pub operation_count: usize,
+    /// number of pending operations of each thread
+    pub operations_per_thread: Vec<usize>,
+    /// distribution of the fees of the pending operations, by increasing decimal order of magnitude of the raw fee.
+    /// Empty buckets are omitted.
+    pub fee_histogram: Vec<FeeHistogramBucket>,
+    /// addresses with the most pending operations, with their operation count, biggest first
+    pub top_senders: Vec<(Address, usize)>,
+}
+
+/// Progress of a denunciation pool entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenunciationProgress {
+    /// a single endorsement or block header was seen for the slot (and index): no double-staking detected
+    Accumulating,
+    /// a double-staking was detected and its denunciation waits to be included in a block
+    Pending,
+    /// the denunciation was executed in a candidate block
+    Included,
+    /// the denunciation was executed in a final block
+    Final,
+}
+
+/// An entry of the denunciation pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenunciationPoolEntry {
+    /// slot of the denounced endorsements or block headers
+    pub slot: Slot,
+    /// index of the denounced endorsements, none for block headers
+    pub index: Option<u32>,
+    /// address of the creator of the denounced endorsements or block headers
+    pub address: Address,
+    /// progress of the denunciation
+    pub progress: DenunciationProgress,
+    /// last final period at which the entry is kept in the pool
+    pub expire_period: u64,
+}
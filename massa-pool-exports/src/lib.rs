@@ -5,13 +5,22 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+mod admission;
 mod channels;
 mod config;
 mod controller_traits;
+mod fees;
+mod inspection;
 
+pub use admission::{AdmissionStageStats, OperationAdmissionStage, OperationAdmissionStats};
 pub use channels::{PoolBroadcasts, PoolChannels};
 pub use config::PoolConfig;
 pub use controller_traits::{PoolController, PoolManager};
+pub use fees::{BlockUsage, FeeRecommendation};
+pub use inspection::{
+    DenunciationPoolEntry, DenunciationProgress, FeeHistogramBucket, OperationPoolSummary,
+    PendingOperationInfo,
+};
 
 #[cfg(feature = "testing")]
 pub use controller_traits::MockPoolController;
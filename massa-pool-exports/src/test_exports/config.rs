@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_models::amount::Amount;
 use massa_models::config::{
     DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, MAX_BLOCK_SIZE,
     MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
@@ -19,7 +20,13 @@ impl Default for PoolConfig {
             max_block_size: MAX_BLOCK_SIZE,
             max_operation_pool_size: 32000,
             max_operation_pool_excess_items: 10000,
+            max_operations_per_address: 10000,
+            spam_score_retention_percent: 50,
+            minimal_fees: Amount::zero(),
+            dynamic_fee_floor: false,
+            fee_floor_target_block_fullness_percent: 50,
             max_endorsements_pool_size_per_thread: 1000,
+            endorsement_health_block_count: 100,
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
             operations_channel_size: 1024,
@@ -32,10 +39,16 @@ impl Default for PoolConfig {
             t0: T0,
             periods_per_cycle: PERIODS_PER_CYCLE,
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+            denunciation_pool_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             last_start_period: 0,
             operation_pool_refresh_interval: MassaTime::from_millis(2000),
             operation_max_future_start_delay: T0.saturating_mul(5),
+            admission_check_structure: true,
+            admission_check_signature: false,
+            admission_check_expiry: true,
+            admission_check_balance: true,
+            admission_check_policies: true,
         }
     }
 }
@@ -0,0 +1,214 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Staged admission pipeline for incoming operations.
+//!
+//! Operations entering the pool go through the following stages, in order:
+//! structure, signature, expiry, balance precheck, admission policies and insertion.
+//! Each stage can be toggled in the pool configuration (except insertion)
+//! and records the number of checked and rejected operations along with the time spent.
+
+use massa_execution_exports::ExecutionController;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    operation::OperationId,
+    prehash::{PreHashMap, PreHashSet},
+};
+use massa_pool_exports::{OperationAdmissionStage, OperationAdmissionStats, PoolConfig};
+use std::time::Instant;
+use tracing::debug;
+
+use crate::spam::SpamScores;
+use crate::types::OperationInfo;
+
+/// A custom admission rule applied to operations during the `Policy` stage
+pub(crate) trait OperationAdmissionPolicy: Send + Sync {
+    /// Name of the policy, used in logs
+    fn name(&self) -> &'static str;
+
+    /// Returns an error describing the violation if the operation must not enter the pool
+    fn check(&self, op_info: &OperationInfo) -> Result<(), String>;
+}
+
+/// An operation waiting for admission
+pub(crate) struct AdmissionCandidate {
+    /// operation information
+    pub op_info: OperationInfo,
+}
+
+/// Operation admission pipeline
+pub(crate) struct OperationAdmissionPipeline {
+    /// configuration
+    config: PoolConfig,
+    /// policies applied during the `Policy` stage
+    policies: Vec<Box<dyn OperationAdmissionPolicy>>,
+    /// per-stage statistics
+    stats: OperationAdmissionStats,
+}
+
+impl OperationAdmissionPipeline {
+    /// Creates a pipeline with no admission policy
+    pub fn new(config: PoolConfig) -> Self {
+        OperationAdmissionPipeline {
+            config,
+            policies: Vec::new(),
+            stats: OperationAdmissionStage::ALL
+                .iter()
+                .map(|stage| (*stage, Default::default()))
+                .collect(),
+        }
+    }
+
+    /// Registers a policy applied during the `Policy` stage
+    pub fn add_policy(&mut self, policy: Box<dyn OperationAdmissionPolicy>) {
+        self.policies.push(policy);
+    }
+
+    /// Get the per-stage statistics
+    pub fn get_stats(&self) -> OperationAdmissionStats {
+        self.stats.clone()
+    }
+
+    /// Runs a stage over the candidates, retaining only the ones for which `check` returns true
+    fn run_stage<F>(
+        &mut self,
+        stage: OperationAdmissionStage,
+        candidates: &mut Vec<AdmissionCandidate>,
+        mut check: F,
+    ) where
+        F: FnMut(&AdmissionCandidate) -> bool,
+    {
+        let start = Instant::now();
+        let checked = candidates.len();
+        candidates.retain(|candidate| check(candidate));
+        let rejected = checked.saturating_sub(candidates.len());
+        self.record(stage, checked, rejected, start);
+        if rejected > 0 {
+            debug!(
+                "operation admission: {} operations rejected at stage {:?}",
+                rejected, stage
+            );
+        }
+    }
+
+    /// Records the outcome of a stage
+    pub fn record(
+        &mut self,
+        stage: OperationAdmissionStage,
+        checked: usize,
+        rejected: usize,
+        start: Instant,
+    ) {
+        let stats = self.stats.entry(stage).or_default();
+        stats.checked = stats.checked.saturating_add(checked as u64);
+        stats.rejected = stats.rejected.saturating_add(rejected as u64);
+        stats.total_duration = stats.total_duration.saturating_add(start.elapsed());
+    }
+
+    /// Runs all the enabled filtering stages (everything but insertion) over the candidates
+    /// and returns the ones that are admissible.
+    /// `verify_signature` checks the signature of an operation during the `Signature` stage.
+    /// The senders of the operations rejected as expired or unaffordable get a spam point each.
+    pub fn filter(
+        &mut self,
+        mut candidates: Vec<AdmissionCandidate>,
+        verify_signature: &dyn Fn(&OperationId) -> bool,
+        last_cs_final_periods: &[u64],
+        execution_controller: &dyn ExecutionController,
+        spam_scores: &mut SpamScores,
+    ) -> Vec<AdmissionCandidate> {
+        if self.config.admission_check_structure {
+            let max_block_gas = self.config.max_block_gas;
+            let max_block_size = self.config.max_block_size as usize;
+            self.run_stage(
+                OperationAdmissionStage::Structure,
+                &mut candidates,
+                |candidate| {
+                    candidate.op_info.max_gas <= max_block_gas
+                        && candidate.op_info.size <= max_block_size
+                        && !candidate.op_info.validity_period_range.is_empty()
+                },
+            );
+        }
+
+        if self.config.admission_check_signature {
+            self.run_stage(
+                OperationAdmissionStage::Signature,
+                &mut candidates,
+                |candidate| verify_signature(&candidate.op_info.id),
+            );
+        }
+
+        if self.config.admission_check_expiry {
+            self.run_stage(
+                OperationAdmissionStage::Expiry,
+                &mut candidates,
+                |candidate| {
+                    let last_final_period = last_cs_final_periods
+                        .get(candidate.op_info.thread as usize)
+                        .copied()
+                        .unwrap_or_default();
+                    let valid = *candidate.op_info.validity_period_range.end() > last_final_period;
+                    if !valid {
+                        spam_scores.add(candidate.op_info.creator_address, 1);
+                    }
+                    valid
+                },
+            );
+        }
+
+        if self.config.admission_check_balance && !candidates.is_empty() {
+            let addrs: Vec<Address> = candidates
+                .iter()
+                .map(|candidate| candidate.op_info.creator_address)
+                .collect::<PreHashSet<Address>>()
+                .into_iter()
+                .collect();
+            let balances: PreHashMap<Address, Amount> = execution_controller
+                .get_final_and_candidate_balance(&addrs)
+                .into_iter()
+                .zip(addrs)
+                .filter_map(|((_, c_balance), addr)| c_balance.map(|v| (addr, v)))
+                .collect();
+            self.run_stage(
+                OperationAdmissionStage::BalancePrecheck,
+                &mut candidates,
+                |candidate| {
+                    let affordable = match balances.get(&candidate.op_info.creator_address) {
+                        Some(balance) => &candidate.op_info.max_spending <= balance,
+                        None => false,
+                    };
+                    if !affordable {
+                        spam_scores.add(candidate.op_info.creator_address, 1);
+                    }
+                    affordable
+                },
+            );
+        }
+
+        if self.config.admission_check_policies {
+            let policies = std::mem::take(&mut self.policies);
+            self.run_stage(
+                OperationAdmissionStage::Policy,
+                &mut candidates,
+                |candidate| {
+                    policies.iter().all(|policy| {
+                        if let Err(err) = policy.check(&candidate.op_info) {
+                            debug!(
+                                "operation {} rejected by admission policy {}: {}",
+                                candidate.op_info.id,
+                                policy.name(),
+                                err
+                            );
+                            return false;
+                        }
+                        true
+                    })
+                },
+            );
+            self.policies = policies;
+        }
+
+        candidates
+    }
+}
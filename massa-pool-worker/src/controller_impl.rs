@@ -3,10 +3,14 @@
 //! Pool controller implementation
 
 use massa_models::{
-    block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
-    endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    address::Address, block_id::BlockId, denunciation::Denunciation,
+    denunciation::DenunciationPrecursor, endorsement::EndorsementId, operation::OperationId,
+    slot::Slot, stats::EndorsementPoolStats,
+};
+use massa_pool_exports::{
+    BlockUsage, DenunciationPoolEntry, FeeRecommendation, OperationAdmissionStats,
+    OperationPoolSummary, PendingOperationInfo, PoolConfig, PoolController, PoolManager,
 };
-use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
 use parking_lot::RwLock;
 use std::sync::mpsc::TrySendError;
@@ -27,6 +31,8 @@ pub enum Command {
     AddDenunciationPrecursor(DenunciationPrecursor),
     /// Notify of new final consensus periods
     NotifyFinalCsPeriods(Vec<u64>),
+    /// Notify of the resources used by new final blocks
+    NotifyFinalBlockUsage(Vec<BlockUsage>),
     /// Stop the worker
     Stop,
 }
@@ -171,6 +177,26 @@ impl PoolController for PoolControllerImpl {
         }
     }
 
+    /// Asynchronously notify of the resources used by new final blocks. Simply print a warning on failure.
+    fn notify_final_block_usage(&mut self, block_usage: Vec<BlockUsage>) {
+        match self
+            .operations_input_sender
+            .try_send(Command::NotifyFinalBlockUsage(block_usage))
+        {
+            Err(TrySendError::Disconnected(_)) => {
+                warn!(
+                    "Could not notify operation pool of final block usage: worker is unreachable."
+                );
+            }
+            Err(TrySendError::Full(_)) => {
+                warn!(
+                    "Could not notify operation pool of final block usage: worker channel is full."
+                );
+            }
+            Ok(_) => {}
+        }
+    }
+
     /// get operations for block creation
     fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage) {
         self.operation_pool.read().get_block_operations(slot)
@@ -183,7 +209,7 @@ impl PoolController for PoolControllerImpl {
         target_slot: &Slot,
     ) -> (Vec<Option<EndorsementId>>, Storage) {
         self.endorsement_pool
-            .read()
+            .write()
             .get_block_endorsements(target_slot, target_block)
     }
 
@@ -199,11 +225,44 @@ impl PoolController for PoolControllerImpl {
         self.endorsement_pool.read().len()
     }
 
+    /// Get the health of the endorsement pool over the last blocks produced by the node
+    fn get_endorsement_pool_stats(&self) -> EndorsementPoolStats {
+        self.endorsement_pool.read().get_health_stats()
+    }
+
     /// Get the number of operations in the pool
     fn get_operation_count(&self) -> usize {
         self.operation_pool.read().len()
     }
 
+    /// Get the per-stage statistics of the operation admission pipeline
+    fn get_operation_admission_stats(&self) -> OperationAdmissionStats {
+        self.operation_pool.read().get_admission_stats()
+    }
+
+    /// Get at most `limit` pending operations, from the highest to the lowest priority, skipping the first `offset` ones.
+    /// If `address` is provided, only the operations created by this address are listed.
+    fn get_pending_operations(
+        &self,
+        address: Option<Address>,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<PendingOperationInfo> {
+        self.operation_pool
+            .read()
+            .get_pending_operations(address, offset, limit)
+    }
+
+    /// Get summary statistics of the operation pool, listing the `top_senders` addresses with the most pending operations
+    fn get_operation_pool_summary(&self, top_senders: usize) -> OperationPoolSummary {
+        self.operation_pool.read().get_summary(top_senders)
+    }
+
+    /// Get the minimal fee accepted by the pool and the fee likely to get an operation included in the next blocks
+    fn get_fee_recommendation(&self) -> FeeRecommendation {
+        self.operation_pool.read().get_fee_recommendation()
+    }
+
     /// Check if the pool contains a list of endorsements. Returns one boolean per item.
     fn contains_endorsements(&self, endorsements: &[EndorsementId]) -> Vec<bool> {
         let lck = self.endorsement_pool.read();
@@ -227,6 +286,11 @@ impl PoolController for PoolControllerImpl {
         self.denunciation_pool.read().len()
     }
 
+    /// Get the entries of the denunciation pool, with the progress of their denunciation, by increasing slot
+    fn get_denunciation_pool_entries(&self) -> Vec<DenunciationPoolEntry> {
+        self.denunciation_pool.read().get_entries()
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn PoolController>`,
     fn clone_box(&self) -> Box<dyn PoolController> {
@@ -10,7 +10,7 @@ use massa_models::{
     denunciation::{Denunciation, DenunciationPrecursor},
     timeslots::get_closest_slot_to_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{DenunciationPoolEntry, DenunciationProgress, PoolChannels, PoolConfig};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 
@@ -84,7 +84,7 @@ impl DenunciationPool {
         if Denunciation::is_expired(
             &slot.period,
             self.last_cs_final_periods.iter().min().unwrap_or(&0),
-            &self.config.denunciation_expire_periods,
+            &self.expire_periods(),
         ) {
             // too old - cannot be denounced anymore
             return;
@@ -184,15 +184,61 @@ impl DenunciationPool {
         // cleanup function only when it is needed
     }
 
+    /// Number of periods after which an entry is forgotten
+    fn expire_periods(&self) -> u64 {
+        self.config
+            .denunciation_pool_expire_periods
+            .min(self.config.denunciation_expire_periods)
+    }
+
     /// cleanup internal cache, removing too old denunciation
     fn cleanup_caches(&mut self) {
+        let expire_periods = self.expire_periods();
         cleanup_cache(
             &mut self.denunciations_cache,
             self.last_cs_final_periods.iter().min().unwrap_or(&0),
-            &self.config.denunciation_expire_periods,
+            &expire_periods,
         );
     }
 
+    /// Get the entries of the pool, with the progress of their denunciation, by increasing slot
+    pub fn get_entries(&self) -> Vec<DenunciationPoolEntry> {
+        let expire_periods = self.expire_periods();
+        self.denunciations_cache
+            .iter()
+            .map(|(de_idx, de_status)| {
+                let (public_key, progress) = match de_status {
+                    DenunciationStatus::Accumulating(de_p) => {
+                        (de_p.get_public_key(), DenunciationProgress::Accumulating)
+                    }
+                    DenunciationStatus::DenunciationEmitted(de) => {
+                        let progress = match self
+                            .channels
+                            .execution_controller
+                            .get_denunciation_execution_status(de_idx)
+                        {
+                            (_, true) => DenunciationProgress::Final,
+                            (true, false) => DenunciationProgress::Included,
+                            (false, false) => DenunciationProgress::Pending,
+                        };
+                        (de.get_public_key(), progress)
+                    }
+                };
+                let slot = *de_idx.get_slot();
+                DenunciationPoolEntry {
+                    slot,
+                    index: match de_idx {
+                        DenunciationIndex::BlockHeader { .. } => None,
+                        DenunciationIndex::Endorsement { index, .. } => Some(*index),
+                    },
+                    address: Address::from_public_key(public_key),
+                    progress,
+                    expire_period: slot.period.saturating_add(expire_periods),
+                }
+            })
+            .collect()
+    }
+
     /// get denunciations for block creation
     pub fn get_block_denunciations(&self, target_slot: &Slot) -> Vec<Denunciation> {
         let mut res = Vec::with_capacity(self.config.max_denunciations_per_block_header as usize);
@@ -0,0 +1,68 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Health of the endorsement pool.
+//!
+//! Each time the node produces a block, the pool records how many of the endorsements of the block
+//! it could provide and which drawn endorsers it had no endorsement from.
+//! The statistics are computed over the last produced blocks, so that a staker can notice
+//! when the endorsements reaching its node degrade.
+
+use massa_models::{
+    address::Address,
+    stats::{EndorsementFill, EndorsementPoolStats},
+};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Endorsements available for the last blocks produced by the node
+pub(crate) struct EndorsementHealth {
+    /// max number of produced blocks kept
+    max_block_count: usize,
+    /// number of endorsements of a block
+    endorsement_count: u32,
+    /// endorsements of the last produced blocks, oldest first
+    history: VecDeque<EndorsementFill>,
+}
+
+impl EndorsementHealth {
+    /// Creates an empty history of at most `max_block_count` blocks of `endorsement_count` endorsements
+    pub fn new(max_block_count: usize, endorsement_count: u32) -> Self {
+        EndorsementHealth {
+            max_block_count,
+            endorsement_count,
+            history: VecDeque::with_capacity(max_block_count),
+        }
+    }
+
+    /// Records the endorsements of a produced block, forgetting the oldest block if the history is full
+    pub fn record(&mut self, fill: EndorsementFill) {
+        if self.max_block_count == 0 {
+            return;
+        }
+        if self.history.len() >= self.max_block_count {
+            self.history.pop_front();
+        }
+        self.history.push_back(fill);
+    }
+
+    /// Get the statistics over the recorded blocks
+    pub fn get_stats(&self) -> EndorsementPoolStats {
+        let produced_block_count = self.history.len() as u64;
+        let expected_count = produced_block_count * (self.endorsement_count as u64);
+        let filled_count: u64 = self
+            .history
+            .iter()
+            .map(|fill| fill.endorsement_count as u64)
+            .sum();
+        let mut missed_endorsements: BTreeMap<Address, u64> = BTreeMap::new();
+        for address in self.history.iter().flat_map(|fill| &fill.missed_endorsers) {
+            *missed_endorsements.entry(*address).or_default() += 1;
+        }
+        EndorsementPoolStats {
+            produced_block_count,
+            fill_rate: (expected_count > 0).then(|| filled_count as f64 / expected_count as f64),
+            missed_endorsement_count: expected_count.saturating_sub(filled_count),
+            missed_endorsements,
+            recent_blocks: self.history.iter().cloned().collect(),
+        }
+    }
+}
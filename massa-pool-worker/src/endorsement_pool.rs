@@ -1,20 +1,29 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_db_exports::ShareableMassaDBController;
 use massa_models::{
     block_id::BlockId,
-    endorsement::EndorsementId,
+    endorsement::{EndorsementDeserializer, EndorsementId, SecureShareEndorsement},
     prehash::{CapacityAllocator, PreHashSet},
+    secure_share::{SecureShareDeserializer, SecureShareSerializer},
     slot::Slot,
+    stats::{EndorsementFill, EndorsementPoolStats},
+    timeslots::get_latest_block_slot_at_timestamp,
 };
 use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::{
     collections::{hash_map::Entry, BTreeMap, HashMap},
     sync::Arc,
 };
-use tracing::{trace, warn};
+use tracing::{info, trace, warn};
+
+use crate::endorsement_health::EndorsementHealth;
+use crate::persistence::{PoolPersistence, ENDORSEMENT_IDENT};
 
 pub struct EndorsementPool {
     /// configuration
@@ -38,6 +47,12 @@ pub struct EndorsementPool {
 
     /// staking wallet, to know which addresses we are using to stake
     wallet: Arc<RwLock<Wallet>>,
+
+    /// persistence of the pending endorsements across restarts, if enabled
+    persistence: Option<PoolPersistence<EndorsementId>>,
+
+    /// endorsements available for the last blocks produced by the node
+    health: EndorsementHealth,
 }
 
 impl EndorsementPool {
@@ -46,11 +61,17 @@ impl EndorsementPool {
         storage: &Storage,
         channels: PoolChannels,
         wallet: Arc<RwLock<Wallet>>,
+        db: Option<ShareableMassaDBController>,
     ) -> Self {
         EndorsementPool {
+            persistence: db.map(|db| PoolPersistence::new(db, ENDORSEMENT_IDENT)),
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
             endorsements_indexed: Default::default(),
             endorsements_sorted: vec![Default::default(); config.thread_count as usize],
+            health: EndorsementHealth::new(
+                config.endorsement_health_block_count,
+                config.max_block_endorsement_count,
+            ),
             config,
             storage: storage.clone_without_refs(),
             channels,
@@ -63,6 +84,11 @@ impl EndorsementPool {
         self.storage.get_endorsement_refs().len()
     }
 
+    /// Get the health statistics over the last blocks produced by the node
+    pub fn get_health_stats(&self) -> EndorsementPoolStats {
+        self.health.get_stats()
+    }
+
     /// Checks whether an element is stored in the pool.
     pub fn contains(&self, id: &EndorsementId) -> bool {
         self.storage.get_endorsement_refs().contains(id)
@@ -91,6 +117,66 @@ impl EndorsementPool {
             }
         }
         self.storage.drop_endorsement_refs(&removed);
+
+        // persist the remaining endorsements
+        self.persist();
+    }
+
+    /// Adds back the persisted endorsements that can still be included in a block
+    pub(crate) fn load_persisted(&mut self) {
+        let Some(persistence) = self.persistence.as_mut() else {
+            return;
+        };
+        let serialized_endorsements = persistence.load();
+
+        let current_slot = get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            MassaTime::now().expect("could not get current time"),
+        )
+        .expect("could not get current slot")
+        .unwrap_or_else(|| Slot::new(0, 0));
+        let deserializer = SecureShareDeserializer::new(EndorsementDeserializer::new(
+            self.config.thread_count,
+            self.config.max_block_endorsement_count,
+        ));
+        let endorsements: Vec<SecureShareEndorsement> = serialized_endorsements
+            .iter()
+            .filter_map(|serialized_endorsement| {
+                deserializer
+                    .deserialize::<DeserializeError>(serialized_endorsement)
+                    .ok()
+                    .map(|(_, endorsement)| endorsement)
+            })
+            .filter(|endorsement| endorsement.content.slot >= current_slot)
+            .collect();
+        info!(
+            "reloading {} persisted pending endorsements out of {}",
+            endorsements.len(),
+            serialized_endorsements.len()
+        );
+
+        let mut endorsement_storage = self.storage.clone_without_refs();
+        endorsement_storage.store_endorsements(endorsements);
+        self.add_endorsements(endorsement_storage);
+        self.persist();
+    }
+
+    /// Persists the pending endorsements, if enabled
+    pub(crate) fn persist(&mut self) {
+        let Some(persistence) = self.persistence.as_mut() else {
+            return;
+        };
+        let endorsements = self.storage.read_endorsements();
+        let serializer = SecureShareSerializer::new();
+        persistence.sync(self.storage.get_endorsement_refs(), |endorsement_id| {
+            let mut buffer = Vec::new();
+            serializer
+                .serialize(endorsements.get(endorsement_id)?, &mut buffer)
+                .ok()?;
+            Some(buffer)
+        });
     }
 
     /// Add a list of endorsements to the pool
@@ -210,9 +296,9 @@ impl EndorsementPool {
         self.storage.drop_endorsement_refs(&removed);
     }
 
-    /// get endorsements for block creation
+    /// get endorsements for block creation, recording which ones are missing
     pub fn get_block_endorsements(
-        &self,
+        &mut self,
         slot: &Slot, // slot of the block that will contain the endorsement
         target_block: &BlockId,
     ) -> (Vec<Option<EndorsementId>>, Storage) {
@@ -228,6 +314,30 @@ impl EndorsementPool {
             );
         }
 
+        // record the endorsers drawn for the block whose endorsement is missing
+        let mut missed_endorsers = Vec::new();
+        if endo_ids.iter().any(Option::is_none) {
+            match self.channels.selector.get_selection(*slot) {
+                Ok(selection) => {
+                    missed_endorsers = endo_ids
+                        .iter()
+                        .zip(selection.endorsements.iter())
+                        .filter(|(endo_id, _)| endo_id.is_none())
+                        .map(|(_, address)| *address)
+                        .collect();
+                }
+                Err(err) => warn!(
+                    "could not get the endorsement draws of slot {}: {}",
+                    slot, err
+                ),
+            }
+        }
+        self.health.record(EndorsementFill {
+            slot: *slot,
+            endorsement_count: endo_ids.iter().flatten().count() as u32,
+            missed_endorsers,
+        });
+
         // setup endorsement storage
         let mut endo_storage = self.storage.clone_without_refs();
         let claim_endos: PreHashSet<EndorsementId> =
@@ -0,0 +1,133 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Minimal fee accepted in the operation pool.
+//!
+//! The floor starts at the configured minimal fee. If the dynamic floor is enabled, it follows the fullness
+//! of the final blocks, the same way EIP-1559 moves the base fee: after each final block, the floor is
+//! multiplied by `1 + (fullness - target) / target / 8`, so that it rises while blocks are fuller than
+//! the target and decreases back towards the configured minimal fee while they are emptier.
+//! The fullness of a block is the highest of its gas, size and operation count ratios to the block limits.
+
+use massa_models::amount::Amount;
+use massa_pool_exports::{BlockUsage, PoolConfig};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::admission::OperationAdmissionPolicy;
+use crate::types::OperationInfo;
+
+/// Fullness of a block at the block limits, in per mille
+const FULL_BLOCK_PER_MILLE: u64 = 1000;
+
+/// Denominator of the maximal relative change of the floor per block
+const FLOOR_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Fee floor of the operation pool
+pub(crate) struct FeeFloor {
+    /// configuration
+    config: PoolConfig,
+    /// target fullness of the blocks, in per mille
+    target_per_mille: u64,
+    /// current floor, as a raw amount, shared with the admission policy
+    floor: Arc<AtomicU64>,
+}
+
+impl FeeFloor {
+    /// Creates a floor at the configured minimal fee
+    pub fn new(config: PoolConfig) -> Self {
+        FeeFloor {
+            target_per_mille: (config.fee_floor_target_block_fullness_percent * 10)
+                .clamp(1, FULL_BLOCK_PER_MILLE),
+            floor: Arc::new(AtomicU64::new(config.minimal_fees.to_raw())),
+            config,
+        }
+    }
+
+    /// Get the current floor
+    pub fn get(&self) -> Amount {
+        Amount::from_raw(self.floor.load(Ordering::Relaxed))
+    }
+
+    /// Get the highest floor reachable after one more block
+    pub fn get_next_max(&self) -> Amount {
+        if !self.config.dynamic_fee_floor {
+            return self.get();
+        }
+        Amount::from_raw(self.next_floor(self.floor.load(Ordering::Relaxed), FULL_BLOCK_PER_MILLE))
+    }
+
+    /// Admission policy rejecting the operations paying less than the floor
+    pub fn policy(&self) -> MinimalFeePolicy {
+        MinimalFeePolicy {
+            floor: self.floor.clone(),
+        }
+    }
+
+    /// Moves the floor according to the fullness of new final blocks.
+    /// Does nothing if the dynamic floor is disabled.
+    pub fn notify_final_block_usage(&mut self, mut block_usage: Vec<BlockUsage>) {
+        if !self.config.dynamic_fee_floor {
+            return;
+        }
+        block_usage.sort_unstable_by_key(|usage| usage.slot);
+        let mut floor = self.floor.load(Ordering::Relaxed);
+        for usage in block_usage {
+            floor = self.next_floor(floor, self.fullness(&usage));
+        }
+        self.floor.store(floor, Ordering::Relaxed);
+    }
+
+    /// Fullness of a block, in per mille
+    fn fullness(&self, usage: &BlockUsage) -> u64 {
+        let ratio = |used: u64, limit: u64| {
+            ((used as u128) * (FULL_BLOCK_PER_MILLE as u128) / (limit.max(1) as u128)) as u64
+        };
+        ratio(usage.gas, self.config.max_block_gas)
+            .max(ratio(usage.size, self.config.max_block_size as u64))
+            .max(ratio(
+                usage.operation_count as u64,
+                self.config.max_operations_per_block as u64,
+            ))
+            .min(FULL_BLOCK_PER_MILLE)
+    }
+
+    /// Floor following a block of the given fullness (in per mille)
+    fn next_floor(&self, floor: u64, fullness: u64) -> u64 {
+        let target = self.target_per_mille;
+        let divisor = (target as u128) * (FLOOR_CHANGE_DENOMINATOR as u128);
+        let next = if fullness > target {
+            // rise by at least one raw unit so that a null floor can leave zero
+            let delta = ((floor as u128) * ((fullness - target) as u128) / divisor).max(1);
+            floor.saturating_add(delta.min(u64::MAX as u128) as u64)
+        } else {
+            let delta = (floor as u128) * ((target - fullness) as u128) / divisor;
+            floor.saturating_sub(delta as u64)
+        };
+        next.max(self.config.minimal_fees.to_raw())
+    }
+}
+
+/// Admission policy rejecting the operations paying less than the fee floor
+pub(crate) struct MinimalFeePolicy {
+    /// current floor, as a raw amount
+    floor: Arc<AtomicU64>,
+}
+
+impl OperationAdmissionPolicy for MinimalFeePolicy {
+    fn name(&self) -> &'static str {
+        "minimal_fee"
+    }
+
+    fn check(&self, op_info: &OperationInfo) -> Result<(), String> {
+        let floor = Amount::from_raw(self.floor.load(Ordering::Relaxed));
+        if op_info.fee < floor {
+            return Err(format!(
+                "fee {} is below the minimal fee {}",
+                op_info.fee, floor
+            ));
+        }
+        Ok(())
+    }
+}
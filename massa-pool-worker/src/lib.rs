@@ -5,10 +5,15 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+mod admission;
 mod controller_impl;
 mod denunciation_pool;
+mod endorsement_health;
 mod endorsement_pool;
+mod fee_floor;
 mod operation_pool;
+mod persistence;
+mod spam;
 mod types;
 mod worker;
 
@@ -1,29 +1,53 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_db_exports::ShareableMassaDBController;
 use massa_models::{
     address::Address,
     amount::Amount,
-    operation::OperationId,
-    prehash::{CapacityAllocator, PreHashMap, PreHashSet},
+    config::{
+        MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        MAX_PARAMETERS_SIZE,
+    },
+    operation::{
+        OperationDeserializer, OperationId, OperationLifecycleEvent, OperationLifecycleStatus,
+        SecureShareOperation,
+    },
+    prehash::{PreHashMap, PreHashSet},
+    secure_share::{SecureShareDeserializer, SecureShareSerializer},
     slot::Slot,
     timeslots::get_latest_block_slot_at_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{
+    BlockUsage, FeeHistogramBucket, FeeRecommendation, OperationAdmissionStage,
+    OperationAdmissionStats, OperationPoolSummary, PendingOperationInfo, PoolChannels, PoolConfig,
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
-use tracing::{debug, trace, warn};
+use std::{
+    cmp::max,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Instant,
+};
+use tracing::{debug, info, trace, warn};
 
-use crate::types::OperationInfo;
+use crate::admission::{AdmissionCandidate, OperationAdmissionPipeline};
+use crate::fee_floor::FeeFloor;
+use crate::persistence::{PoolPersistence, OPERATION_IDENT};
+use crate::spam::SpamScores;
+use crate::types::{OperationInfo, OperationPriority};
 
 pub struct OperationPool {
     /// configuration
     config: PoolConfig,
 
-    /// operations map
-    sorted_ops: Vec<OperationInfo>,
+    /// operations, sorted from the highest to the lowest priority (fee per weighted gas)
+    sorted_ops: BTreeMap<Reverse<OperationPriority>, OperationInfo>,
 
     /// storage instance
     pub(crate) storage: Storage,
@@ -36,6 +60,18 @@ pub struct OperationPool {
 
     /// staking wallet, to know which addresses we are using to stake
     wallet: Arc<RwLock<Wallet>>,
+
+    /// admission pipeline applied to incoming operations
+    admission: OperationAdmissionPipeline,
+
+    /// persistence of the pending operations across restarts, if enabled
+    persistence: Option<PoolPersistence<OperationId>>,
+
+    /// spam scores of the senders, deprioritizing their operations
+    spam_scores: SpamScores,
+
+    /// minimal fee of the admitted operations
+    fee_floor: FeeFloor,
 }
 
 impl OperationPool {
@@ -44,14 +80,18 @@ impl OperationPool {
         storage: &Storage,
         channels: PoolChannels,
         wallet: Arc<RwLock<Wallet>>,
+        db: Option<ShareableMassaDBController>,
     ) -> Self {
+        let fee_floor = FeeFloor::new(config);
+        let mut admission = OperationAdmissionPipeline::new(config);
+        admission.add_policy(Box::new(fee_floor.policy()));
         OperationPool {
-            sorted_ops: Vec::with_capacity(
-                config
-                    .max_operation_pool_size
-                    .saturating_add(config.max_operation_pool_excess_items),
-            ),
+            persistence: db.map(|db| PoolPersistence::new(db, OPERATION_IDENT)),
+            sorted_ops: BTreeMap::new(),
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
+            admission,
+            spam_scores: SpamScores::new(config.spam_score_retention_percent),
+            fee_floor,
             config,
             storage: storage.clone_without_refs(),
             channels,
@@ -108,7 +148,7 @@ impl OperationPool {
 
     /// Returns the list of executed ops with a boolean indicating whether they are executed as final.
     fn get_execution_statuses(&self) -> PreHashMap<OperationId, bool> {
-        let op_ids: Vec<OperationId> = self.sorted_ops.iter().map(|op_info| op_info.id).collect();
+        let op_ids: Vec<OperationId> = self.sorted_ops.values().map(|op_info| op_info.id).collect();
         self.channels
             .execution_controller
             .get_ops_exec_status(&op_ids)
@@ -129,7 +169,7 @@ impl OperationPool {
     fn get_sender_balances(&self) -> PreHashMap<Address, Amount> {
         let addrs: Vec<Address> = self
             .sorted_ops
-            .iter()
+            .values()
             .map(|op_info| op_info.creator_address)
             .collect::<PreHashSet<Address>>()
             .into_iter()
@@ -145,6 +185,7 @@ impl OperationPool {
     }
 
     /// Filter out ops that are not of interest.
+    /// The removal of the ops that were executed or expired is notified.
     fn prefilter_ops(
         &mut self,
         exec_statuses: &PreHashMap<OperationId, bool>,
@@ -152,7 +193,8 @@ impl OperationPool {
         sender_balances: &PreHashMap<Address, Amount>,
     ) {
         let mut removed = PreHashSet::default();
-        self.sorted_ops.retain(|op_info| {
+        let mut events = Vec::new();
+        self.sorted_ops.retain(|_, op_info| {
             // filter out ops that use too much resources
             let mut retain = (op_info.max_gas <= self.config.max_block_gas)
                 && (op_info.size <= self.config.max_block_size as usize);
@@ -172,29 +214,75 @@ impl OperationPool {
             }
 
             // filter out ops that spend more than the sender's balance
-            if retain {
-                retain = match sender_balances.get(&op_info.creator_address) {
-                    Some(v) => &op_info.max_spending <= v,
-                    None => false, // filter out ops for which the sender does not exist
-                };
-            }
+            let affordable = match sender_balances.get(&op_info.creator_address) {
+                Some(v) => &op_info.max_spending <= v,
+                None => false, // filter out ops for which the sender does not exist
+            };
+            retain = retain && affordable;
 
             if !retain {
+                let executed = exec_statuses.contains_key(&op_info.id);
+                let expired = self
+                    .last_cs_final_periods
+                    .get(op_info.thread as usize)
+                    .map_or(false, |period| {
+                        op_info.validity_period_range.end() <= period
+                    });
+                let status = if executed {
+                    Some(OperationLifecycleStatus::Included)
+                } else if expired {
+                    Some(OperationLifecycleStatus::Expired)
+                } else {
+                    None
+                };
+                if let Some(status) = status {
+                    events.push(OperationLifecycleEvent {
+                        operation_id: op_info.id,
+                        creator_address: op_info.creator_address,
+                        status,
+                    });
+                }
+                // the sender of an op that expired or became unaffordable without being executed gets a spam point
+                if !executed && (expired || !affordable) {
+                    self.spam_scores.add(op_info.creator_address, 1);
+                }
                 removed.insert(op_info.id);
                 return false;
             }
             true
         });
+        self.notify_lifecycle_events(events);
         // drop from storage
         self.storage.drop_operation_refs(&removed);
     }
 
+    /// Broadcasts lifecycle events of pending operations to active channel subscribers
+    fn notify_lifecycle_events(&self, events: Vec<OperationLifecycleEvent>) {
+        if !self.config.broadcast_enabled {
+            return;
+        }
+        for event in events {
+            if let Err(err) = self
+                .channels
+                .broadcasts
+                .operation_lifecycle_sender
+                .send(event)
+            {
+                trace!(
+                    "error, failed to broadcast lifecycle event of operation {}: {}",
+                    event.operation_id,
+                    err
+                );
+            }
+        }
+    }
+
     /// Eliminate all operations that would cause a sender balance overflow.
-    /// Assumes that the ops are sorted by ascending score.
+    /// Ops are visited from the highest to the lowest priority, so the lowest-paying ones are eliminated first.
     fn eliminate_balance_overflows(&mut self, sender_balances: &PreHashMap<Address, Amount>) {
         let mut balance_cache = PreHashMap::default();
         let mut removed = PreHashSet::default();
-        self.sorted_ops.retain(|op_info| {
+        self.sorted_ops.retain(|_, op_info| {
             let balance = balance_cache
                 .entry(op_info.creator_address)
                 .or_insert_with(|| {
@@ -218,115 +306,54 @@ impl OperationPool {
         self.storage.drop_operation_refs(&removed);
     }
 
-    /// Truncates the container to the max allowed size
+    /// Truncates the container to the max allowed size, evicting the lowest-priority ops
     fn truncate_container(&mut self) {
-        if self.sorted_ops.len() > self.config.max_operation_pool_size {
-            let mut removed = PreHashSet::default();
-            for op_info in self
-                .sorted_ops
-                .iter()
-                .skip(self.config.max_operation_pool_size)
-            {
+        let removed = self.evict_lowest_priority(self.config.max_operation_pool_size);
+        // drop from storage
+        self.storage.drop_operation_refs(&removed);
+    }
+
+    /// Removes the lowest-priority ops until at most `max_len` ops remain,
+    /// notifies their eviction and returns their IDs.
+    /// Their storage references are left to the caller.
+    fn evict_lowest_priority(&mut self, max_len: usize) -> PreHashSet<OperationId> {
+        let mut removed = PreHashSet::default();
+        let mut events = Vec::new();
+        while self.sorted_ops.len() > max_len {
+            if let Some((_, op_info)) = self.sorted_ops.pop_last() {
                 removed.insert(op_info.id);
+                events.push(OperationLifecycleEvent {
+                    operation_id: op_info.id,
+                    creator_address: op_info.creator_address,
+                    status: OperationLifecycleStatus::Evicted,
+                });
             }
-            self.sorted_ops
-                .truncate(self.config.max_operation_pool_size);
-            // drop from storage
-            self.storage.drop_operation_refs(&removed);
         }
+        self.notify_lifecycle_events(events);
+        removed
     }
 
-    /// Score the operations
-    fn score_operations(
-        &self,
-        _exec_statuses: &PreHashMap<OperationId, bool>,
-        pos_draws: &BTreeSet<Slot>,
-    ) -> PreHashMap<OperationId, f32> {
-        let now = MassaTime::now().expect("could not get current time");
-        let now_period = get_latest_block_slot_at_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            now,
-        )
-        .expect("could not get current slot")
-        .map_or(0, |s| s.period);
-
-        let mut scores = PreHashMap::with_capacity(self.sorted_ops.len());
-        for op_info in &self.sorted_ops {
-            // fee factor
-            // (we add 1 to still sort zero-fee ops)
-            let fee_factor = op_info.fee.to_raw().saturating_add(1) as f32;
-
-            // size score:
-            //    0% of block size => score 1
-            //    100% of block size => score 0
-            let size_score = 1.0 - (op_info.size as f32) / (self.config.max_block_size as f32);
-
-            // gas score:
-            //    0% of block gas => score 1
-            //    100% of block gas => score 0
-            let gas_score = 1.0 - (op_info.max_gas as f32) / (self.config.max_block_gas as f32);
-
-            // general resource score (mean of gas and size scores)
-            let epsilon_resource_factor = 0.0001; // avoids zero score when gas and size are a perfect fit in the block
-            let resource_factor = (epsilon_resource_factor + size_score + gas_score)
-                / (2.0 + epsilon_resource_factor);
-
-            // inclusion probability factor
-            //    If we are selected to produce a block in a long time,
-            //    there is exponential likelihood that someone includes the op before us.
-            let tau_inclusion = 2.0; // exponential decay factor
-            let earliest_inclusion_opportunity = pos_draws.iter().find_map(|s| {
-                if s.thread == op_info.thread
-                    && op_info.validity_period_range.contains(&s.period)
-                    && s.period >= now_period.saturating_sub(1)
-                {
-                    Some(s.period)
-                } else {
-                    None
-                }
-            });
-            let inclusion_factor =
-                if let Some(earliest_inclusion_opportunity) = earliest_inclusion_opportunity {
-                    // compute the number of slots other stakers have available to include the op before we do
-                    let foreign_opportunities = earliest_inclusion_opportunity.saturating_sub(max(
-                        now_period.saturating_add(1),
-                        *op_info.validity_period_range.start(),
-                    ));
-                    (-(foreign_opportunities as f32) / tau_inclusion).exp()
-                } else {
-                    // no inclusion opportunity => score 0
-                    0.0
-                };
-
-            /* TODO: re-execution followup
-            // If the op was executed previously, there is still an exponentially decaying chance of its block being cancelled
-            // so that it can be reincluded.
-            // We approximate it with a constant factor for simplicity since we don't have the inclusion slot for now.
-            let reexecution_penalty = 1.0 / 1000.0; // re-execution penalty factor
-            let reexecution_factor = if exec_statuses.contains_key(&op_info.id) {
-                // executed previously
-                reexecution_penalty
-            } else {
-                // not executed previously => score 1
-                1.0
-            };
-            */
-
-            // compute the score as being the product of all the factors and the fee
-            let score = fee_factor * resource_factor * inclusion_factor;
-            //  * reexecution_factor; // TODO: re-execution followup
+    /// Inserts an op in the container at the rank given by its priority and the spam score of its sender
+    fn insert_op(&mut self, mut op_info: OperationInfo) {
+        op_info.spam_score = self.spam_scores.get(&op_info.creator_address);
+        let priority = op_info.priority(self.config.max_block_size, self.config.max_block_gas);
+        self.sorted_ops.insert(Reverse(priority), op_info);
+    }
 
-            // store the score
-            scores.insert(op_info.id, score);
+    /// Re-ranks all the ops with the current spam scores of their senders
+    fn rerank(&mut self) {
+        let sorted_ops = std::mem::take(&mut self.sorted_ops);
+        for op_info in sorted_ops.into_values() {
+            self.insert_op(op_info);
         }
-        scores
     }
 
     /// Refresh the pool.
     /// Note that this function is very heavy and we call it only periodically, timer-based.
     pub(crate) fn refresh(&mut self) {
+        // decay the spam scores
+        self.spam_scores.decay();
+
         // get PoS draws
         let pos_draws = self.get_pos_draws();
 
@@ -339,24 +366,81 @@ impl OperationPool {
         // pre-filter to eliminate obviously uninteresting ops
         self.prefilter_ops(&exec_statuses, &pos_draws, &sender_balances);
 
-        // score operations
-        let scores = self.score_operations(&exec_statuses, &pos_draws);
-
-        // sort by score
-        self.sorted_ops.sort_unstable_by(|op1, op2| {
-            // note1: scores are float => we need to use partial_cmp.
-            // note2: operands are reversed to sort from highest to lowest !
-            scores
-                .get(&op2.id)
-                .partial_cmp(&scores.get(&op1.id))
-                .unwrap_or(Ordering::Equal)
-        });
+        // re-rank the remaining ops with the updated spam scores
+        self.rerank();
 
         // eliminate balance overflows in sorted ops
         self.eliminate_balance_overflows(&sender_balances);
 
         // eliminate container size overflows
         self.truncate_container();
+
+        // persist the remaining ops
+        self.persist();
+    }
+
+    /// Adds back the persisted operations that did not expire
+    pub(crate) fn load_persisted(&mut self) {
+        let Some(persistence) = self.persistence.as_mut() else {
+            return;
+        };
+        let serialized_ops = persistence.load();
+
+        let current_period = get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            MassaTime::now().expect("could not get current time"),
+        )
+        .expect("could not get current slot")
+        .map_or(0, |s| s.period);
+        let deserializer = SecureShareDeserializer::new(OperationDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        ));
+        let ops: Vec<SecureShareOperation> = serialized_ops
+            .iter()
+            .filter_map(|serialized_op| {
+                deserializer
+                    .deserialize::<DeserializeError>(serialized_op)
+                    .ok()
+                    .map(|(_, op)| op)
+            })
+            .filter(|op| op.content.expire_period >= current_period)
+            .collect();
+        info!(
+            "reloading {} persisted pending operations out of {}",
+            ops.len(),
+            serialized_ops.len()
+        );
+
+        let mut ops_storage = self.storage.clone_without_refs();
+        ops_storage.store_operations(ops);
+        self.add_operations(ops_storage);
+        self.persist();
+    }
+
+    /// Persists the pending operations, if enabled
+    pub(crate) fn persist(&mut self) {
+        let Some(persistence) = self.persistence.as_mut() else {
+            return;
+        };
+        let ops = self.storage.read_operations();
+        let serializer = SecureShareSerializer::new();
+        persistence.sync(self.storage.get_op_refs(), |op_id| {
+            let mut buffer = Vec::new();
+            serializer.serialize(ops.get(op_id)?, &mut buffer).ok()?;
+            Some(buffer)
+        });
+    }
+
+    /// Get the per-stage statistics of the admission pipeline
+    pub fn get_admission_stats(&self) -> OperationAdmissionStats {
+        self.admission.get_stats()
     }
 
     /// Get the number of stored elements
@@ -364,6 +448,131 @@ impl OperationPool {
         self.sorted_ops.len()
     }
 
+    /// Number of pending operations of each creator address
+    fn get_creator_operation_counts(&self) -> PreHashMap<Address, usize> {
+        let mut counts = PreHashMap::default();
+        for op_info in self.sorted_ops.values() {
+            *counts.entry(op_info.creator_address).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Get at most `limit` pending operations, from the highest to the lowest priority, skipping the first `offset` ones.
+    /// If `address` is provided, only the operations created by this address are listed.
+    pub fn get_pending_operations(
+        &self,
+        address: Option<Address>,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<PendingOperationInfo> {
+        let creator_counts = self.get_creator_operation_counts();
+        self.sorted_ops
+            .iter()
+            .filter(|(_, op_info)| address.map_or(true, |addr| op_info.creator_address == addr))
+            .skip(offset)
+            .take(limit)
+            .map(|(Reverse(priority), op_info)| PendingOperationInfo {
+                id: op_info.id,
+                creator_address: op_info.creator_address,
+                thread: op_info.thread,
+                fee: op_info.fee,
+                max_gas: op_info.max_gas,
+                size: op_info.size,
+                score: priority.fee as f64 / priority.weight as f64,
+                expire_period: *op_info.validity_period_range.end(),
+                creator_operation_count: creator_counts
+                    .get(&op_info.creator_address)
+                    .copied()
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Get summary statistics of the pool, listing the `top_senders` addresses with the most pending operations
+    pub fn get_summary(&self, top_senders: usize) -> OperationPoolSummary {
+        let mut operations_per_thread = vec![0usize; self.config.thread_count as usize];
+        // fee buckets indexed by decimal order of magnitude of the raw fee, 0 being the bucket of the null fees
+        let mut fee_buckets: BTreeMap<u32, usize> = BTreeMap::new();
+        for op_info in self.sorted_ops.values() {
+            if let Some(count) = operations_per_thread.get_mut(op_info.thread as usize) {
+                *count += 1;
+            }
+            let magnitude = op_info
+                .fee
+                .to_raw()
+                .checked_ilog10()
+                .map_or(0, |log| log + 1);
+            *fee_buckets.entry(magnitude).or_insert(0) += 1;
+        }
+        let fee_histogram = fee_buckets
+            .into_iter()
+            .map(|(magnitude, count)| {
+                let (min_fee, max_fee) = match magnitude.checked_sub(1) {
+                    None => (0, 0),
+                    Some(log) => (
+                        10u64.pow(log),
+                        10u64
+                            .checked_pow(log + 1)
+                            .map_or(u64::MAX, |bound| bound - 1),
+                    ),
+                };
+                FeeHistogramBucket {
+                    min_fee: Amount::from_raw(min_fee),
+                    max_fee: Amount::from_raw(max_fee),
+                    count,
+                }
+            })
+            .collect();
+
+        let mut senders: Vec<(Address, usize)> =
+            self.get_creator_operation_counts().into_iter().collect();
+        senders.sort_unstable_by(|(addr_a, count_a), (addr_b, count_b)| {
+            count_b.cmp(count_a).then_with(|| addr_a.cmp(addr_b))
+        });
+        senders.truncate(top_senders);
+
+        OperationPoolSummary {
+            operation_count: self.sorted_ops.len(),
+            operations_per_thread,
+            fee_histogram,
+            top_senders: senders,
+        }
+    }
+
+    /// Get the minimal fee accepted by the pool and the fee likely to get an operation included in the next blocks
+    pub fn get_fee_recommendation(&self) -> FeeRecommendation {
+        let minimal_fee = self.fee_floor.get();
+        let mut recommended_fee = self.fee_floor.get_next_max();
+        // if the pool holds more operations than the blocks of the next period can include,
+        // match the lowest fee of the operations that would be included
+        let capacity =
+            (self.config.thread_count as usize) * (self.config.max_operations_per_block as usize);
+        if capacity > 0 && self.sorted_ops.len() > capacity {
+            if let Some(fee) = self
+                .sorted_ops
+                .values()
+                .take(capacity)
+                .map(|op_info| op_info.fee)
+                .min()
+            {
+                recommended_fee = max(recommended_fee, fee);
+            }
+        }
+        FeeRecommendation {
+            minimal_fee,
+            recommended_fee,
+        }
+    }
+
+    /// Moves the fee floor according to the resources used by new final blocks
+    pub(crate) fn notify_final_block_usage(&mut self, block_usage: Vec<BlockUsage>) {
+        self.fee_floor.notify_final_block_usage(block_usage);
+        debug!(
+            "notified of new final block usage, minimal fee: {}",
+            self.fee_floor.get()
+        );
+    }
+
     /// Checks whether an element is stored in the pool.
     pub fn contains(&self, id: &OperationId) -> bool {
         self.storage.get_op_refs().contains(id)
@@ -379,61 +588,132 @@ impl OperationPool {
         );
     }
 
-    /// Add a list of operations to the end of the pool.
-    /// They will be cleaned up at the next refresh.
+    /// Add a list of operations to the pool, at the rank given by their priority.
+    /// They go through the admission pipeline first and will be cleaned up at the next refresh.
+    /// If the pool is full, the lowest-paying ops are evicted, new ops paying less than them being dropped.
+    /// The new ops of a sender having the max number of pending ops per address are rejected.
     pub(crate) fn add_operations(&mut self, mut ops_storage: Storage) {
         // List all the new operations
-        let mut new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
+        let candidate_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
 
-        // If there are too many extra operations,
-        // we don't want the container to fill up too much in-between refreshes so we drop any excess.
-        // This is because refreshing the container is very heavy and is only called periodically.
-        let dropped_items = self
-            .sorted_ops
-            .len()
-            .saturating_add(new_op_ids.len())
-            .saturating_sub(self.config.max_operation_pool_size)
-            .saturating_sub(self.config.max_operation_pool_excess_items);
-        for _ in 0..dropped_items {
-            if let Some(id) = new_op_ids.iter().next().copied() {
-                new_op_ids.remove(&id);
-            } else {
-                break;
-            }
-        }
-        if dropped_items > 0 {
-            warn!(
-                "Operation pool excess limit reached. Dropping {} non-scored operations.",
-                dropped_items
-            );
-        }
-
-        // Add the new ops to the container.
-        // Note that the added items are put at the end of the sorted ops
-        // so that they can still be picked for block production before refresh but with low priority
-        // because in that case we don't know anything about their quality.
-        {
+        let mut new_op_ids = PreHashSet::default();
+        let evicted_op_ids = {
             let ops = ops_storage.read_operations();
-            for new_op_id in &new_op_ids {
-                let op = ops
-                    .get(new_op_id)
-                    .expect("operation not found in storage but listed as owned");
+            let candidates = candidate_op_ids
+                .iter()
+                .map(|op_id| {
+                    let op = ops
+                        .get(op_id)
+                        .expect("operation not found in storage but listed as owned");
+                    AdmissionCandidate {
+                        op_info: OperationInfo::from_op(
+                            op,
+                            self.config.operation_validity_periods,
+                            self.config.roll_price,
+                            self.config.thread_count,
+                        ),
+                    }
+                })
+                .collect();
+
+            // run the filtering stages of the admission pipeline
+            let mut admitted = self.admission.filter(
+                candidates,
+                &|op_id| {
+                    ops.get(op_id)
+                        .map_or(false, |op| op.verify_signature().is_ok())
+                },
+                &self.last_cs_final_periods,
+                self.channels.execution_controller.as_ref(),
+                &mut self.spam_scores,
+            );
 
-                // Broadcast operations to active channel subscribers.
-                if self.config.broadcast_enabled {
+            // Insertion stage.
+            // The new ops of a sender are rejected once it has the max number of pending ops per address,
+            // the ones paying the most being kept.
+            // The others are inserted at the rank given by their priority,
+            // so that they can be picked for block production before the next refresh.
+            // If there are too many extra operations, the lowest-paying ones are evicted right away
+            // because refreshing the container is very heavy and is only called periodically.
+            let insertion_start = Instant::now();
+            let checked = admitted.len();
+            admitted.sort_unstable_by_key(|candidate| {
+                Reverse(
+                    candidate
+                        .op_info
+                        .priority(self.config.max_block_size, self.config.max_block_gas),
+                )
+            });
+            let mut sender_op_counts: PreHashMap<Address, usize> = PreHashMap::default();
+            admitted.retain(|candidate| {
+                let address = candidate.op_info.creator_address;
+                let count = sender_op_counts.entry(address).or_insert_with(|| {
+                    ops.get_operations_created_by(&address)
+                        .into_iter()
+                        .flatten()
+                        .filter(|op_id| self.storage.get_op_refs().contains(*op_id))
+                        .count()
+                });
+                if *count >= self.config.max_operations_per_address {
+                    return false;
+                }
+                *count += 1;
+                true
+            });
+            let limited_items = checked.saturating_sub(admitted.len());
+            if limited_items > 0 {
+                debug!(
+                    "{} new operations rejected: their senders reached the max number of pending operations per address",
+                    limited_items
+                );
+            }
+            for AdmissionCandidate { op_info, .. } in admitted {
+                new_op_ids.insert(op_info.id);
+                self.insert_op(op_info);
+            }
+            let removed = self.evict_lowest_priority(
+                self.config
+                    .max_operation_pool_size
+                    .saturating_add(self.config.max_operation_pool_excess_items),
+            );
+            let (dropped, evicted_op_ids): (PreHashSet<OperationId>, PreHashSet<OperationId>) =
+                removed
+                    .into_iter()
+                    .partition(|op_id| new_op_ids.contains(op_id));
+            let dropped_items = dropped.len();
+            if dropped_items > 0 || !evicted_op_ids.is_empty() {
+                warn!(
+                    "Operation pool excess limit reached. Dropping {} new operations and evicting {} pool operations paying less.",
+                    dropped_items,
+                    evicted_op_ids.len()
+                );
+            }
+            new_op_ids.retain(|op_id| !dropped.contains(op_id));
+
+            // Broadcast the added operations to active channel subscribers.
+            if self.config.broadcast_enabled {
+                for op_id in &new_op_ids {
+                    let op = ops
+                        .get(op_id)
+                        .expect("operation not found in storage but listed as owned");
                     if let Err(err) = self.channels.broadcasts.operation_sender.send(op.clone()) {
                         trace!("error, failed to broadcast operations {}: {}", op.id, err);
                     }
                 }
-
-                self.sorted_ops.push(OperationInfo::from_op(
-                    op,
-                    self.config.operation_validity_periods,
-                    self.config.roll_price,
-                    self.config.thread_count,
-                ));
             }
-        }
+            self.admission.record(
+                OperationAdmissionStage::Insertion,
+                checked,
+                limited_items.saturating_add(dropped_items),
+                insertion_start,
+            );
+
+            evicted_op_ids
+        };
+
+        // Drop the evicted ops from storage once the operations lock is released,
+        // as dropping the last reference to an op takes the write lock.
+        self.storage.drop_operation_refs(&evicted_op_ids);
 
         // This will add the new ops to the storage without taking locks.
         // It just take the local references from `ops_storage` if they are not in `self.storage` yet.
@@ -453,6 +733,8 @@ impl OperationPool {
     /// Searches the available operations, and selects the sub-set of operations that:
     /// - fit inside the block
     /// - is the most profitable for block producer
+    ///
+    /// Operations are picked by decreasing fee per weighted gas, the order used for eviction.
     pub fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage) {
         // init list of selected operation IDs
         let mut op_ids = Vec::new();
@@ -465,7 +747,7 @@ impl OperationPool {
         let mut remaining_ops = self.config.max_operations_per_block;
 
         // iterate over pool operations in the right thread, from best to worst
-        for op_info in &self.sorted_ops {
+        for op_info in self.sorted_ops.values() {
             // if we have reached the maximum number of operations, stop
             if remaining_ops == 0 {
                 break;
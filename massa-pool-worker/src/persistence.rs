@@ -0,0 +1,102 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistence of the pending pool items in the `POOL_CF` column family of the database,
+//! so that a node restart does not drop the operations and endorsements that were not included yet.
+//!
+//! Items are keyed by a pool identifier followed by their ID, and stored serialized.
+//! Each pool remembers the items it persisted: when synced, it writes the items it gained
+//! and deletes the ones it lost since the previous sync.
+//! The column family is local to the node: it is neither hashed nor bootstrapped.
+
+use massa_db_exports::{DBBatch, ShareableMassaDBController, POOL_CF};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::prehash::{PreHashSet, PreHashed};
+use massa_models::secure_share::Id;
+use tracing::warn;
+
+/// Prefix of the persisted operations
+pub(crate) const OPERATION_IDENT: u8 = 0u8;
+/// Prefix of the persisted endorsements
+pub(crate) const ENDORSEMENT_IDENT: u8 = 1u8;
+
+/// Items of a pool persisted on disk
+pub(crate) struct PoolPersistence<ID> {
+    /// database holding the items
+    db: ShareableMassaDBController,
+    /// prefix of the keys of the items
+    ident: u8,
+    /// IDs of the items currently persisted
+    persisted: PreHashSet<ID>,
+}
+
+impl<ID> PoolPersistence<ID>
+where
+    ID: Id + PreHashed + Copy + Eq + std::hash::Hash,
+{
+    /// Creates a persistence writing to the `POOL_CF` column family of `db`, under the `ident` prefix
+    pub fn new(db: ShareableMassaDBController, ident: u8) -> Self {
+        PoolPersistence {
+            db,
+            ident,
+            persisted: Default::default(),
+        }
+    }
+
+    /// Reads the serialized persisted items.
+    /// They remain persisted until the next sync, which deletes the ones that did not make it back to the pool.
+    pub fn load(&mut self) -> Vec<Vec<u8>> {
+        let db = self.db.read();
+        let mut items = Vec::new();
+        for (key, value) in db.prefix_iterator_cf(POOL_CF, &[self.ident]) {
+            if key.first() != Some(&self.ident) {
+                break;
+            }
+            let Ok(id_bytes) = <[u8; HASH_SIZE_BYTES]>::try_from(&key[1..]) else {
+                warn!("invalid persisted pool item key: {:?}", key);
+                continue;
+            };
+            self.persisted.insert(ID::new(Hash::from_bytes(&id_bytes)));
+            items.push(value);
+        }
+        items
+    }
+
+    /// Persists the items of `current` that are not persisted yet, serialized by `serialize`,
+    /// and deletes the persisted items that are not in `current` anymore.
+    pub fn sync<F>(&mut self, current: &PreHashSet<ID>, mut serialize: F)
+    where
+        F: FnMut(&ID) -> Option<Vec<u8>>,
+    {
+        let mut batch = DBBatch::new();
+        let mut kept = PreHashSet::default();
+        for id in self.persisted.iter() {
+            if current.contains(id) {
+                kept.insert(*id);
+            } else {
+                batch.insert(self.key(id), None);
+            }
+        }
+        for id in current.iter() {
+            if !kept.contains(id) {
+                if let Some(value) = serialize(id) {
+                    batch.insert(self.key(id), Some(value));
+                    kept.insert(*id);
+                }
+            }
+        }
+        if batch.is_empty() {
+            return;
+        }
+
+        if let Err(err) = self.db.read().write_local_batch(POOL_CF, batch) {
+            warn!("could not persist the pool items: {}", err);
+            return;
+        }
+        self.persisted = kept;
+    }
+
+    /// Key of an item
+    fn key(&self, id: &ID) -> Vec<u8> {
+        [&[self.ident][..], id.get_hash().to_bytes()].concat()
+    }
+}
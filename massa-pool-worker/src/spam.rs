@@ -0,0 +1,53 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Spam scores of the operation senders.
+//!
+//! An address scores a point for each of its operations rejected at admission because it expired
+//! or because its sender could not afford it, and for each of its pending operations that expired
+//! or became unaffordable in the pool without being executed.
+//! The operations of an address are ranked as if their weight was multiplied by 1 + its score,
+//! so that an address flooding the pool with operations that never get executed is deprioritized.
+//! Scores decay at each pool refresh.
+
+use massa_models::{address::Address, prehash::PreHashMap};
+
+/// Spam scores of the sender addresses
+pub(crate) struct SpamScores {
+    /// percentage of each score kept at each decay
+    retention_percent: u64,
+    /// non-zero scores
+    scores: PreHashMap<Address, u64>,
+}
+
+impl SpamScores {
+    /// Creates empty scores, keeping `retention_percent` percent of each score at each decay
+    pub fn new(retention_percent: u64) -> Self {
+        SpamScores {
+            retention_percent: retention_percent.min(100),
+            scores: Default::default(),
+        }
+    }
+
+    /// Get the score of an address
+    pub fn get(&self, address: &Address) -> u64 {
+        self.scores.get(address).copied().unwrap_or_default()
+    }
+
+    /// Adds `points` to the score of an address
+    pub fn add(&mut self, address: Address, points: u64) {
+        if points == 0 {
+            return;
+        }
+        let score = self.scores.entry(address).or_default();
+        *score = score.saturating_add(points);
+    }
+
+    /// Decays all the scores, forgetting the addresses whose score drops to zero
+    pub fn decay(&mut self) {
+        let retention_percent = self.retention_percent;
+        self.scores.retain(|_, score| {
+            *score = ((*score as u128) * (retention_percent as u128) / 100) as u64;
+            *score > 0
+        });
+    }
+}
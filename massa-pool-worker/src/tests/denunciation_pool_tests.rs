@@ -0,0 +1,108 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::{collections::BTreeMap, time::Duration};
+
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    block_id::BlockId,
+    denunciation::{DenunciationIndex, DenunciationPrecursor},
+    endorsement::{Endorsement, EndorsementSerializer},
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
+use massa_pool_exports::{DenunciationPoolEntry, DenunciationProgress, PoolConfig};
+use massa_pos_exports::{MockSelectorController, PosResult, Selection};
+use massa_signature::KeyPair;
+
+use super::tools::{default_mock_execution_controller, pool_test};
+
+/// Selector drawing `address` for everything
+fn mock_selector(address: Address) -> Box<MockSelectorController> {
+    let selection = move |_: Slot| -> PosResult<Selection> {
+        Ok(Selection {
+            producer: address,
+            endorsements: vec![address; 16],
+        })
+    };
+    let mut res = Box::new(MockSelectorController::new());
+    res.expect_clone_box().returning(move || {
+        let mut story = Box::new(MockSelectorController::new());
+        story.expect_get_selection().returning(selection);
+        story
+            .expect_get_available_selections_in_range()
+            .returning(|_, _| Ok(BTreeMap::new()));
+        story
+    });
+    res.expect_get_selection().returning(selection);
+    res
+}
+
+/// Precursor of an endorsement of `block_name` at slot (1, 0)
+fn endorsement_precursor(keypair: &KeyPair, index: u32, block_name: &str) -> DenunciationPrecursor {
+    let content = Endorsement {
+        slot: Slot::new(1, 0),
+        index,
+        endorsed_block: BlockId::generate_from_hash(Hash::compute_from(block_name.as_bytes())),
+    };
+    let endorsement =
+        Endorsement::new_verifiable(content, EndorsementSerializer::new(), keypair).unwrap();
+    DenunciationPrecursor::from(&endorsement)
+}
+
+#[test]
+fn test_denunciation_pool_entries() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&keypair.get_public_key());
+    let mut pool_config = PoolConfig::default();
+    pool_config.denunciation_pool_expire_periods = 5;
+    let thread_count = pool_config.thread_count;
+    let mut execution_controller = default_mock_execution_controller();
+    // the denunciation of index 1 was executed in a candidate block
+    execution_controller
+        .expect_get_denunciation_execution_status()
+        .returning(|de_idx| {
+            (
+                matches!(de_idx, DenunciationIndex::Endorsement { index: 1, .. }),
+                false,
+            )
+        });
+
+    pool_test(
+        pool_config,
+        execution_controller,
+        mock_selector(address),
+        None,
+        |mut pool, _storage| {
+            pool.add_denunciation_precursor(endorsement_precursor(&keypair, 0, "blk1"));
+            pool.add_denunciation_precursor(endorsement_precursor(&keypair, 1, "blk1"));
+            pool.add_denunciation_precursor(endorsement_precursor(&keypair, 1, "blk2"));
+            std::thread::sleep(Duration::from_millis(500));
+            assert_eq!(pool.get_denunciation_count(), 1);
+            assert_eq!(
+                pool.get_denunciation_pool_entries(),
+                vec![
+                    DenunciationPoolEntry {
+                        slot: Slot::new(1, 0),
+                        index: Some(0),
+                        address,
+                        progress: DenunciationProgress::Accumulating,
+                        expire_period: 6,
+                    },
+                    DenunciationPoolEntry {
+                        slot: Slot::new(1, 0),
+                        index: Some(1),
+                        address,
+                        progress: DenunciationProgress::Included,
+                        expire_period: 6,
+                    },
+                ]
+            );
+
+            // entries are forgotten after the configured expiry
+            pool.notify_final_cs_periods(&vec![7; thread_count as usize]);
+            std::thread::sleep(Duration::from_millis(500));
+            assert!(pool.get_denunciation_pool_entries().is_empty());
+        },
+    );
+}
@@ -247,3 +247,40 @@ fn test_get_block_endorsements_works() {
         },
     );
 }
+
+#[test]
+fn test_endorsement_pool_stats() {
+    let sender_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&sender_keypair.get_public_key());
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = default_mock_selector(address.clone());
+
+    pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        Some((address, sender_keypair.clone())),
+        |mut pool, mut storage| {
+            assert_eq!(pool.get_endorsement_pool_stats().fill_rate, None);
+
+            let endorsements = vec![
+                create_endorsement(&sender_keypair, 0, Slot::new(1, 2)),
+                create_endorsement(&sender_keypair, 1, Slot::new(1, 2)),
+            ];
+            storage.store_endorsements(endorsements.clone());
+            pool.add_endorsements(storage.clone());
+            // Allow some time for the pool to add the endorsements
+            std::thread::sleep(Duration::from_secs(2));
+            pool.get_block_endorsements(&endorsements[0].content.endorsed_block, &Slot::new(1, 2));
+
+            // the 14 other endorsers drawn for the block missed their endorsement
+            let stats = pool.get_endorsement_pool_stats();
+            assert_eq!(stats.produced_block_count, 1);
+            assert_eq!(stats.fill_rate, Some(2.0 / 16.0));
+            assert_eq!(stats.missed_endorsement_count, 14);
+            assert_eq!(stats.missed_endorsements.get(&address), Some(&14));
+            assert_eq!(stats.recent_blocks[0].slot, Slot::new(1, 2));
+            assert_eq!(stats.recent_blocks[0].endorsement_count, 2);
+        },
+    );
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+mod denunciation_pool_tests;
 mod endorsement_pool_tests;
 mod operation_pool_tests;
 mod scenario;
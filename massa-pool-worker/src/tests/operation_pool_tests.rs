@@ -12,20 +12,61 @@
 //! Same as classic but we try to add irrelevant operation. (See the definition
 //! chapter below)
 //!
+//! # Admission statistics
+//! Function: [`test_admission_stats`]
+//! Add expired and relevant operations and check the per-stage counters
+//! of the admission pipeline.
+//!
+//! # Fee-based eviction
+//! Function: [`test_fee_based_eviction`]
+//! Fill the pool and check that new operations evict the ones paying less,
+//! and that block operations are picked by decreasing fee per gas.
+//!
+//! # Sender limits
+//! Function: [`test_sender_limits`]
+//! Check that the operations of a sender submitting expired operations are
+//! deprioritized, and that a sender can't exceed the max number of pending
+//! operations per address.
+//!
+//! # Pool inspection
+//! Function: [`test_pool_inspection`]
+//! Add operations of two senders and check the listing of the pending
+//! operations and the summary statistics of the pool.
+//!
+//! # Persistence
+//! Function: [`test_pool_persistence`]
+//! Stop a pool persisting its operations and check that a new pool
+//! reloads the unexpired ones.
+//!
+//! # Fee floor
+//! Function: [`test_fee_floor`]
+//! Raise the fee floor with full blocks and check that operations paying less
+//! are rejected, then lower it back with empty blocks.
+//!
 //! # Definition
 //! Relevant operation: Operation with a validity range corresponding to the
 //! latest period given his own thread. All operation which doesn't fit these
 //! requirements are "irrelevant"
 //!
 use crate::tests::tools::OpGenerator;
+use crate::types::OperationInfo;
 
 use super::tools::{
-    create_some_operations, default_mock_execution_controller, pool_test, PoolTestBoilerPlate,
+    create_some_operations, default_mock_execution_controller, no_draw_selector_controller,
+    no_refresh_pool_config, pool_test, wait_admitted_operations, wait_until, PoolTestBoilerPlate,
+};
+use massa_db_exports::{
+    test_exports::MemoryMassaDB, MassaDBConfig, ShareableMassaDBController, POOL_CF,
 };
-use massa_models::{amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot};
-use massa_pool_exports::PoolConfig;
+use massa_models::{
+    address::Address, amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot,
+};
+use massa_pool_exports::{BlockUsage, FeeHistogramBucket, OperationAdmissionStage, PoolConfig};
 use massa_pos_exports::{MockSelectorController, Selection};
-use std::{collections::BTreeMap, time::Duration};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use parking_lot::RwLock;
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 #[test]
 fn test_add_operation() {
@@ -203,9 +244,19 @@ fn test_pool() {
 
     pool_controller.add_operations(storage);
     std::thread::sleep(Duration::from_secs(3));
-    // // sort from bigger fee to smaller and truncate
+    // // sort from bigger fee per weighted gas to smaller and truncate
     for lst in thread_tx_lists.iter_mut() {
-        lst.reverse();
+        lst.sort_unstable_by_key(|(op, _)| {
+            std::cmp::Reverse(
+                OperationInfo::from_op(
+                    op,
+                    pool_config.operation_validity_periods,
+                    pool_config.roll_price,
+                    pool_config.thread_count,
+                )
+                .priority(pool_config.max_block_size, pool_config.max_block_gas),
+            )
+        });
         lst.truncate(pool_config.max_operations_per_block as usize);
     }
 
@@ -235,3 +286,387 @@ fn test_pool() {
     }
     pool_manager.stop();
 }
+
+/// Test that operations rejected by the admission pipeline are accounted to the right stage.
+#[test]
+fn test_admission_stats() {
+    let pool_config = PoolConfig::default();
+    let thread_count = pool_config.thread_count;
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = no_draw_selector_controller();
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, mut storage| {
+            // expired operations
+            let op_gen = OpGenerator::default().expirery(2);
+            storage.store_operations(create_some_operations(10, &op_gen));
+            // relevant operations
+            let op_gen = OpGenerator::default().expirery(60);
+            storage.store_operations(create_some_operations(5, &op_gen));
+            operation_pool.notify_final_cs_periods(&vec![51; thread_count.into()]);
+            operation_pool.add_operations(storage);
+            wait_admitted_operations(operation_pool.as_ref(), 15);
+
+            let stats = operation_pool.get_operation_admission_stats();
+            let structure = stats[&OperationAdmissionStage::Structure];
+            assert_eq!(structure.checked, 15);
+            assert_eq!(structure.rejected, 0);
+            let expiry = stats[&OperationAdmissionStage::Expiry];
+            assert_eq!(expiry.checked, 15);
+            assert_eq!(expiry.rejected, 10);
+            let insertion = stats[&OperationAdmissionStage::Insertion];
+            assert_eq!(insertion.checked, 5);
+            assert_eq!(insertion.accepted(), 5);
+            // signature verification is disabled by default
+            assert_eq!(stats[&OperationAdmissionStage::Signature].checked, 0);
+        },
+    );
+}
+
+/// Test that a full pool evicts its lowest-paying operations for better-paying ones,
+/// and that blocks are filled by decreasing fee per gas.
+#[test]
+fn test_fee_based_eviction() {
+    let mut pool_config = no_refresh_pool_config();
+    pool_config.max_operation_pool_size = 3;
+    pool_config.max_operation_pool_excess_items = 0;
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = no_draw_selector_controller();
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            // all the operations are created by the same address to be in the same thread
+            let creator = KeyPair::generate(0).unwrap();
+            let op_gen = |fee: u64| {
+                OpGenerator::default()
+                    .creator(creator.clone())
+                    .expirery(10)
+                    .fee(Amount::const_init(fee, 2))
+                    .generate()
+            };
+            let thread = Address::from_public_key(&creator.get_public_key())
+                .get_thread(pool_config.thread_count);
+            let ops: Vec<_> = [10, 20, 30, 5, 40].into_iter().map(op_gen).collect();
+            let op_ids: Vec<OperationId> = ops.iter().map(|op| op.id).collect();
+
+            // fill the pool
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(ops[..3].to_vec());
+            operation_pool.add_operations(ops_storage);
+            wait_admitted_operations(operation_pool.as_ref(), 3);
+            assert_eq!(operation_pool.get_operation_count(), 3);
+
+            // an operation paying less than all the pool is dropped
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(vec![ops[3].clone()]);
+            operation_pool.add_operations(ops_storage);
+            wait_admitted_operations(operation_pool.as_ref(), 4);
+            assert_eq!(
+                operation_pool.contains_operations(&op_ids),
+                vec![true, true, true, false, false]
+            );
+
+            // an operation paying more evicts the lowest-paying one
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(vec![ops[4].clone()]);
+            operation_pool.add_operations(ops_storage);
+            wait_admitted_operations(operation_pool.as_ref(), 5);
+            assert_eq!(
+                operation_pool.contains_operations(&op_ids),
+                vec![false, true, true, false, true]
+            );
+            let insertion =
+                operation_pool.get_operation_admission_stats()[&OperationAdmissionStage::Insertion];
+            assert_eq!(insertion.checked, 5);
+            assert_eq!(insertion.rejected, 1);
+
+            // blocks are filled from the highest to the lowest fee per gas
+            let (block_op_ids, _) = operation_pool.get_block_operations(&Slot::new(1, thread));
+            assert_eq!(block_op_ids, vec![op_ids[4], op_ids[2], op_ids[1]]);
+        },
+    );
+}
+
+/// Test the spam score and the max number of pending operations per address.
+#[test]
+fn test_sender_limits() {
+    let mut pool_config = no_refresh_pool_config();
+    pool_config.max_operations_per_address = 2;
+    let thread_count = pool_config.thread_count;
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = no_draw_selector_controller();
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let spammer = KeyPair::generate(0).unwrap();
+            let sender = KeyPair::generate(0).unwrap();
+            let op_gen = |creator: &KeyPair, expire_period: u64, fee: u64| {
+                OpGenerator::default()
+                    .creator(creator.clone())
+                    .expirery(expire_period)
+                    .fee(Amount::const_init(fee, 2))
+                    .generate()
+            };
+            operation_pool.notify_final_cs_periods(&vec![51; thread_count.into()]);
+
+            // the spammer submits expired operations, then a valid one
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations((1..=3).map(|fee| op_gen(&spammer, 2, fee)).collect());
+            operation_pool.add_operations(ops_storage);
+            let spammer_op = op_gen(&spammer, 60, 40);
+            let sender_op = op_gen(&sender, 60, 20);
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(vec![spammer_op.clone(), sender_op.clone()]);
+            operation_pool.add_operations(ops_storage);
+            wait_admitted_operations(operation_pool.as_ref(), 5);
+
+            // the operation of the spammer ranks below the one of the sender despite paying more
+            let pending = operation_pool.get_pending_operations(None, 0, 10);
+            assert_eq!(
+                pending.iter().map(|op| op.id).collect::<Vec<_>>(),
+                vec![sender_op.id, spammer_op.id]
+            );
+
+            // the sender can only add one more operation, the one paying the most being kept
+            let ops = vec![op_gen(&sender, 60, 10), op_gen(&sender, 60, 30)];
+            let op_ids: Vec<OperationId> = ops.iter().map(|op| op.id).collect();
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(ops);
+            operation_pool.add_operations(ops_storage);
+            wait_admitted_operations(operation_pool.as_ref(), 7);
+            assert_eq!(
+                operation_pool.contains_operations(&op_ids),
+                vec![false, true]
+            );
+            assert_eq!(operation_pool.get_operation_count(), 3);
+            let insertion =
+                operation_pool.get_operation_admission_stats()[&OperationAdmissionStage::Insertion];
+            assert_eq!(insertion.checked, 4);
+            assert_eq!(insertion.rejected, 1);
+        },
+    );
+}
+
+/// Test the listing of the pending operations and the summary of the pool.
+#[test]
+fn test_pool_inspection() {
+    let pool_config = no_refresh_pool_config();
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = no_draw_selector_controller();
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let creator_a = KeyPair::generate(0).unwrap();
+            let creator_b = KeyPair::generate(0).unwrap();
+            let address_a = Address::from_public_key(&creator_a.get_public_key());
+            let address_b = Address::from_public_key(&creator_b.get_public_key());
+            let op_gen = |creator: &KeyPair, fee: u64| {
+                OpGenerator::default()
+                    .creator(creator.clone())
+                    .expirery(10)
+                    .fee(Amount::const_init(fee, 2))
+                    .generate()
+            };
+            let ops = vec![
+                op_gen(&creator_a, 10),
+                op_gen(&creator_a, 20),
+                op_gen(&creator_a, 300),
+                op_gen(&creator_b, 30),
+            ];
+            let op_ids: Vec<OperationId> = ops.iter().map(|op| op.id).collect();
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(ops);
+            operation_pool.add_operations(ops_storage);
+            wait_admitted_operations(operation_pool.as_ref(), 4);
+
+            // operations are listed by decreasing priority
+            let pending = operation_pool.get_pending_operations(None, 0, 10);
+            assert_eq!(
+                pending.iter().map(|op| op.id).collect::<Vec<_>>(),
+                vec![op_ids[2], op_ids[3], op_ids[1], op_ids[0]]
+            );
+            assert!(pending.windows(2).all(|ops| ops[0].score >= ops[1].score));
+            assert_eq!(pending[0].creator_operation_count, 3);
+            assert_eq!(pending[1].creator_operation_count, 1);
+            assert_eq!(pending[1].fee, Amount::const_init(30, 2));
+            assert_eq!(pending[1].expire_period, 10);
+
+            // filtered by address and paginated
+            let pending = operation_pool.get_pending_operations(Some(address_a), 1, 1);
+            assert_eq!(
+                pending.iter().map(|op| op.id).collect::<Vec<_>>(),
+                vec![op_ids[1]]
+            );
+            assert!(operation_pool
+                .get_pending_operations(Some(address_b), 1, 10)
+                .is_empty());
+
+            let summary = operation_pool.get_operation_pool_summary(1);
+            assert_eq!(summary.operation_count, 4);
+            assert_eq!(summary.operations_per_thread.iter().sum::<usize>(), 4);
+            assert!(
+                summary.operations_per_thread
+                    [address_a.get_thread(pool_config.thread_count) as usize]
+                    >= 3
+            );
+            assert_eq!(
+                summary.fee_histogram,
+                vec![
+                    FeeHistogramBucket {
+                        min_fee: Amount::from_raw(100_000_000),
+                        max_fee: Amount::from_raw(999_999_999),
+                        count: 3,
+                    },
+                    FeeHistogramBucket {
+                        min_fee: Amount::from_raw(1_000_000_000),
+                        max_fee: Amount::from_raw(9_999_999_999),
+                        count: 1,
+                    },
+                ]
+            );
+            assert_eq!(summary.top_senders, vec![(address_a, 3)]);
+        },
+    );
+}
+
+/// Test that the pending operations are persisted when the pool stops,
+/// and that only the unexpired ones are reloaded by the next pool.
+#[test]
+fn test_pool_persistence() {
+    let mut pool_config = no_refresh_pool_config();
+    // current period: 20
+    pool_config.genesis_timestamp = MassaTime::now()
+        .unwrap()
+        .saturating_sub(pool_config.t0.saturating_mul(20));
+    let db: ShareableMassaDBController =
+        Arc::new(RwLock::new(Box::new(MemoryMassaDB::new(MassaDBConfig {
+            path: Default::default(),
+            max_history_length: 10,
+            max_history_bytes: None,
+            max_new_elements: 100,
+            max_stream_batch_bytes: None,
+            thread_count: pool_config.thread_count,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
+        }))));
+
+    let expired_op = OpGenerator::default().expirery(10).generate();
+    let pending_op = OpGenerator::default().expirery(60).generate();
+    let op_ids = vec![expired_op.id, pending_op.id];
+
+    let PoolTestBoilerPlate {
+        mut pool_manager,
+        mut pool_controller,
+        storage,
+    } = PoolTestBoilerPlate::pool_test_with_db(
+        pool_config,
+        default_mock_execution_controller(),
+        no_draw_selector_controller(),
+        Some(db.clone()),
+    );
+    let mut ops_storage = storage.clone_without_refs();
+    ops_storage.store_operations(vec![expired_op, pending_op]);
+    pool_controller.add_operations(ops_storage);
+    wait_admitted_operations(pool_controller.as_ref(), 2);
+    assert_eq!(pool_controller.get_operation_count(), 2);
+    pool_manager.stop();
+    assert_eq!(db.read().prefix_iterator_cf(POOL_CF, &[]).count(), 2);
+
+    let PoolTestBoilerPlate {
+        mut pool_manager,
+        pool_controller,
+        ..
+    } = PoolTestBoilerPlate::pool_test_with_db(
+        pool_config,
+        default_mock_execution_controller(),
+        no_draw_selector_controller(),
+        Some(db.clone()),
+    );
+    assert_eq!(
+        pool_controller.contains_operations(&op_ids),
+        vec![false, true]
+    );
+    // the expired operation is not persisted anymore
+    assert_eq!(db.read().prefix_iterator_cf(POOL_CF, &[]).count(), 1);
+    pool_manager.stop();
+}
+
+/// Test the minimal fee and its congestion-based floor.
+#[test]
+fn test_fee_floor() {
+    let mut pool_config = no_refresh_pool_config();
+    pool_config.minimal_fees = Amount::const_init(10, 2);
+    pool_config.dynamic_fee_floor = true;
+    pool_config.fee_floor_target_block_fullness_percent = 50;
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = no_draw_selector_controller();
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let recommendation = operation_pool.get_fee_recommendation();
+            assert_eq!(recommendation.minimal_fee, Amount::from_raw(100_000_000));
+            assert_eq!(
+                recommendation.recommended_fee,
+                Amount::from_raw(112_500_000)
+            );
+
+            // two full blocks raise the floor by 1/8 each
+            let block_usage = |period: u64, operation_count: u32| BlockUsage {
+                slot: Slot::new(period, 0),
+                operation_count,
+                gas: 0,
+                size: 0,
+            };
+            operation_pool.notify_final_block_usage(vec![
+                block_usage(1, pool_config.max_operations_per_block),
+                block_usage(2, pool_config.max_operations_per_block),
+            ]);
+            wait_until(|| {
+                operation_pool.get_fee_recommendation().minimal_fee == Amount::from_raw(126_562_500)
+            });
+
+            // operations paying less than the floor are rejected
+            let op_gen = |fee: u64| {
+                OpGenerator::default()
+                    .expirery(10)
+                    .fee(Amount::const_init(fee, 2))
+                    .generate()
+            };
+            let ops = vec![op_gen(12), op_gen(13)];
+            let op_ids: Vec<OperationId> = ops.iter().map(|op| op.id).collect();
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(ops);
+            operation_pool.add_operations(ops_storage);
+            wait_admitted_operations(operation_pool.as_ref(), 2);
+            assert_eq!(
+                operation_pool.contains_operations(&op_ids),
+                vec![false, true]
+            );
+            let policy =
+                operation_pool.get_operation_admission_stats()[&OperationAdmissionStage::Policy];
+            assert_eq!(policy.rejected, 1);
+
+            // empty blocks bring the floor back to the configured minimal fee
+            operation_pool.notify_final_block_usage((3..6).map(|p| block_usage(p, 0)).collect());
+            wait_until(|| {
+                operation_pool.get_fee_recommendation().minimal_fee == pool_config.minimal_fees
+            });
+        },
+    );
+}
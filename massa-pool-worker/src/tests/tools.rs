@@ -1,9 +1,14 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::start_pool_controller;
 use crossbeam_channel as _;
+use massa_db_exports::ShareableMassaDBController;
 use massa_execution_exports::MockExecutionController;
 use massa_hash::Hash;
 use massa_models::{
@@ -16,10 +21,13 @@ use massa_models::{
     secure_share::SecureShareContent,
     slot::Slot,
 };
-use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolController, PoolManager};
+use massa_pool_exports::{
+    OperationAdmissionStage, PoolBroadcasts, PoolChannels, PoolConfig, PoolController, PoolManager,
+};
 use massa_pos_exports::MockSelectorController as AutoMockSelectorController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_wallet::test_exports::create_test_wallet;
 use parking_lot::RwLock;
 use tokio::sync::broadcast;
@@ -103,6 +111,16 @@ impl PoolTestBoilerPlate {
         cfg: PoolConfig,
         execution_story: Box<MockExecutionController>,
         selector_story: Box<AutoMockSelectorController>,
+    ) -> Self {
+        Self::pool_test_with_db(cfg, execution_story, selector_story, None)
+    }
+
+    /// Same as `pool_test`, persisting the pending items in `db` if given
+    pub fn pool_test_with_db(
+        cfg: PoolConfig,
+        execution_story: Box<MockExecutionController>,
+        selector_story: Box<AutoMockSelectorController>,
+        db: Option<ShareableMassaDBController>,
     ) -> Self {
         let storage: Storage = Storage::create_root();
         let keypair = KeyPair::generate(0).unwrap();
@@ -120,10 +138,12 @@ impl PoolTestBoilerPlate {
                 broadcasts: PoolBroadcasts {
                     endorsement_sender,
                     operation_sender,
+                    operation_lifecycle_sender: broadcast::channel(5000).0,
                 },
                 selector: selector_story,
             },
             wallet,
+            db,
         );
 
         Self {
@@ -162,10 +182,12 @@ pub fn pool_test<F>(
             broadcasts: PoolBroadcasts {
                 endorsement_sender,
                 operation_sender,
+                operation_lifecycle_sender: broadcast::channel(5000).0,
             },
             selector,
         },
         wallet,
+        None,
     );
     test(pool_controller, storage);
     pool_manager.stop();
@@ -211,3 +233,48 @@ pub fn default_mock_execution_controller() -> Box<MockExecutionController> {
     });
     res
 }
+
+/// Pool config whose operations are never refreshed during a test:
+/// a refresh would drop them, the selector giving no PoS draw (see `no_draw_selector_controller`)
+pub fn no_refresh_pool_config() -> PoolConfig {
+    PoolConfig {
+        operation_pool_refresh_interval: MassaTime::from_millis(60000),
+        ..PoolConfig::default()
+    }
+}
+
+/// Create a selector controller that gives no PoS draw
+pub fn no_draw_selector_controller() -> Box<AutoMockSelectorController> {
+    let mut res = Box::new(AutoMockSelectorController::new());
+    res.expect_clone_box().times(2).returning(|| {
+        let mut story = AutoMockSelectorController::new();
+        story
+            .expect_get_available_selections_in_range()
+            .returning(|_, _| Ok(BTreeMap::new()));
+        Box::new(story)
+    });
+    res
+}
+
+/// Wait until `condition` holds, panicking if it still doesn't after a few seconds
+pub fn wait_until<F: Fn() -> bool>(condition: F) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !condition() {
+        assert!(
+            Instant::now() < deadline,
+            "timeout waiting for the pool state"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Wait until the operation pool has run `count` operations through its admission pipeline.
+/// Operations are added asynchronously, each batch being admitted at once.
+pub fn wait_admitted_operations(pool: &dyn PoolController, count: u64) {
+    wait_until(|| {
+        pool.get_operation_admission_stats()
+            .get(&OperationAdmissionStage::Structure)
+            .map_or(0, |stats| stats.checked)
+            >= count
+    });
+}
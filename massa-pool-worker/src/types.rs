@@ -3,7 +3,7 @@ use massa_models::{
     amount::Amount,
     operation::{OperationId, SecureShareOperation},
 };
-use std::ops::RangeInclusive;
+use std::{cmp::max, cmp::Ordering, ops::RangeInclusive};
 
 #[derive(Debug, Clone)]
 pub struct OperationInfo {
@@ -16,6 +16,8 @@ pub struct OperationInfo {
     /// max amount that the op might spend from the sender's balance
     pub max_spending: Amount,
     pub validity_period_range: RangeInclusive<u64>,
+    /// spam score of the sender when the op was ranked in the pool
+    pub spam_score: u64,
 }
 
 impl OperationInfo {
@@ -34,6 +36,54 @@ impl OperationInfo {
             thread: op.content_creator_address.get_thread(thread_count),
             validity_period_range: op.get_validity_range(operation_validity_periods),
             max_spending: op.get_max_spending(roll_price),
+            spam_score: 0,
         }
     }
+
+    /// Priority of the operation in the pool, given the block limits.
+    /// The weight of the op is multiplied by 1 + the spam score of its sender.
+    pub fn priority(&self, max_block_size: u32, max_block_gas: u64) -> OperationPriority {
+        // the size of the op is converted to gas so that ops filling the block by size pay for it too
+        let size_gas = (self.size as u128).saturating_mul(max_block_gas as u128)
+            / max(max_block_size, 1) as u128;
+        let weight = (self.max_gas as u128)
+            .saturating_add(size_gas)
+            .max(1)
+            .saturating_mul((self.spam_score as u128).saturating_add(1));
+        OperationPriority {
+            fee: self.fee.to_raw(),
+            weight: u64::try_from(weight).unwrap_or(u64::MAX),
+            id: self.id,
+        }
+    }
+}
+
+/// Priority of an operation: the fee it pays per unit of weighted gas,
+/// weighted gas being its max gas plus the gas equivalent of its size in a block.
+///
+/// Priorities are compared by fee per weighted gas, ties being broken by operation ID
+/// so that two distinct operations never have the same priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationPriority {
+    /// raw fee of the operation
+    pub fee: u64,
+    /// weighted gas of the operation, at least 1
+    pub weight: u64,
+    /// ID of the operation
+    pub id: OperationId,
+}
+
+impl Ord for OperationPriority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // compare fee / weight ratios without loss of precision
+        (self.fee as u128 * other.weight as u128)
+            .cmp(&(other.fee as u128 * self.weight as u128))
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for OperationPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
@@ -6,6 +6,7 @@ use crate::controller_impl::{Command, PoolManagerImpl};
 use crate::denunciation_pool::DenunciationPool;
 use crate::operation_pool::OperationPool;
 use crate::{controller_impl::PoolControllerImpl, endorsement_pool::EndorsementPool};
+use massa_db_exports::ShareableMassaDBController;
 use massa_pool_exports::PoolConfig;
 use massa_pool_exports::{PoolChannels, PoolController, PoolManager};
 use massa_storage::Storage;
@@ -52,6 +53,7 @@ impl EndorsementPoolThread {
             match self.receiver.recv() {
                 Err(RecvError) => break,
                 Ok(Command::Stop) => {
+                    self.endorsement_pool.write().persist();
                     break;
                 }
                 Ok(Command::AddItems(endorsements)) => {
@@ -105,7 +107,11 @@ impl OperationPoolThread {
             let duration = (start_time + tick).saturating_duration_since(Instant::now());
             if !duration.is_zero() {
                 match self.receiver.recv_timeout(duration) {
-                    Err(RecvTimeoutError::Disconnected) | Ok(Command::Stop) => break,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                    Ok(Command::Stop) => {
+                        self.operation_pool.write().persist();
+                        break;
+                    }
                     Ok(Command::AddItems(operations)) => {
                         self.operation_pool.write().add_operations(operations)
                     }
@@ -113,6 +119,10 @@ impl OperationPoolThread {
                         .operation_pool
                         .write()
                         .notify_final_cs_periods(&final_cs_periods),
+                    Ok(Command::NotifyFinalBlockUsage(block_usage)) => self
+                        .operation_pool
+                        .write()
+                        .notify_final_block_usage(block_usage),
                     Ok(_) => {
                         warn!("OperationPoolThread received an unexpected command");
                         continue;
@@ -175,18 +185,24 @@ impl DenunciationPoolThread {
                     .denunciation_pool
                     .write()
                     .notify_final_cs_periods(&final_cs_periods),
+                Ok(Command::NotifyFinalBlockUsage(_)) => {
+                    warn!("DenunciationPoolThread received an unexpected command");
+                    continue;
+                }
             };
         }
     }
 }
 
-/// Start pool manager and controller
+/// Start pool manager and controller.
+/// If `db` is given, the pending operations and endorsements are persisted in it and reloaded at startup.
 #[allow(clippy::type_complexity)]
 pub fn start_pool_controller(
     config: PoolConfig,
     storage: &Storage,
     channels: PoolChannels,
     wallet: Arc<RwLock<Wallet>>,
+    db: Option<ShareableMassaDBController>,
 ) -> (Box<dyn PoolManager>, Box<dyn PoolController>) {
     let (operations_input_sender, operations_input_receiver) =
         sync_channel(config.operations_channel_size);
@@ -199,13 +215,18 @@ pub fn start_pool_controller(
         storage,
         channels.clone(),
         wallet.clone(),
+        db.clone(),
     )));
     let endorsement_pool = Arc::new(RwLock::new(EndorsementPool::init(
         config,
         storage,
         channels.clone(),
         wallet,
+        db,
     )));
+    // reload the items persisted before the last stop
+    operation_pool.write().load_persisted();
+    endorsement_pool.write().load_persisted();
     let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(config, channels)));
     let controller = PoolControllerImpl {
         _config: config,
@@ -17,6 +17,8 @@ pub struct PoSConfig {
     pub max_production_stats_length: u64,
     /// maximum deferred credits length
     pub max_credit_length: u64,
+    /// number of past cycles whose production stats are kept after leaving the cycle history
+    pub production_stats_history_length: u64,
     /// initial deferred credits file path
     pub initial_deferred_credits_path: Option<PathBuf>,
 }
@@ -7,7 +7,11 @@ use std::collections::BTreeMap;
 
 use crate::PosResult;
 use massa_hash::Hash;
-use massa_models::{address::Address, prehash::PreHashSet, slot::Slot};
+use massa_models::{
+    address::Address,
+    prehash::PreHashSet,
+    slot::{IndexedSlot, Slot},
+};
 
 #[cfg(feature = "testing")]
 use std::collections::{HashMap, VecDeque};
@@ -21,6 +25,15 @@ pub struct Selection {
     pub producer: Address,
 }
 
+/// Block and endorsement slots drawn for an address, sorted by slot
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressSelections {
+    /// Slots at which the address produces a block
+    pub block_draws: Vec<Slot>,
+    /// Slots and indices at which the address produces an endorsement
+    pub endorsement_draws: Vec<IndexedSlot>,
+}
+
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
 /// interface that communicates with the selector worker thread
 pub trait SelectorController: Send + Sync {
@@ -59,6 +72,16 @@ pub trait SelectorController: Send + Sync {
         restrict_to_addresses: Option<&'a PreHashSet<Address>>,
     ) -> PosResult<BTreeMap<Slot, Selection>>;
 
+    /// Get the block and endorsement slots drawn for an address in a cycle range (only lists available cycles)
+    /// # Arguments
+    /// * `address`: address to get the draws for
+    /// * `cycle_range`: range of cycles to get the draws for
+    fn get_address_selections_in_cycle_range(
+        &self,
+        address: &Address,
+        cycle_range: std::ops::RangeInclusive<u64>,
+    ) -> PosResult<AddressSelections>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn SelectorController>`.
     fn clone_box(&self) -> Box<dyn SelectorController>;
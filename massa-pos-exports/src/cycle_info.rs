@@ -195,6 +195,78 @@ impl Deserializer<CycleInfo> for CycleInfoDeserializer {
     }
 }
 
+/// Summary of a completed cycle, broadcast to the subscribers of the cycle completion feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleCompletion {
+    /// the completed cycle
+    pub cycle: u64,
+    /// roll counts at the end of the cycle of the addresses whose roll count changed
+    /// during the cycle (0 when all their rolls were sold)
+    pub roll_changes: BTreeMap<Address, u64>,
+    /// snapshot of the final state hash taken at the end of the cycle
+    pub final_state_hash_snapshot: Option<HashXof<HASH_XOF_SIZE_BYTES>>,
+}
+
+/// Roll distribution statistics of a cycle
+#[derive(Debug, Clone, PartialEq)]
+pub struct StakerDistribution {
+    /// cycle number
+    pub cycle: u64,
+    /// number of addresses owning rolls
+    pub staker_count: u64,
+    /// total number of rolls
+    pub total_rolls: u64,
+    /// number of rolls taken into account by the selector for the cycle,
+    /// `None` if the lookback cycle left the history
+    pub active_rolls: Option<u64>,
+    /// Gini coefficient of the roll counts, 0 meaning perfect equality
+    pub gini_coefficient: f64,
+    /// number of rolls owned by the `top_n` biggest stakers
+    pub top_stakers_rolls: u64,
+}
+
+impl StakerDistribution {
+    /// Computes the distribution statistics of the roll counts of a cycle
+    pub fn new(
+        cycle: u64,
+        roll_counts: &BTreeMap<Address, u64>,
+        active_rolls: Option<u64>,
+        top_n: usize,
+    ) -> Self {
+        let mut counts: Vec<u64> = roll_counts.values().copied().filter(|c| *c > 0).collect();
+        counts.sort_unstable();
+        let total_rolls = counts.iter().fold(0u64, |acc, c| acc.saturating_add(*c));
+
+        // G = 2 * sum(i * x_i) / (n * sum(x_i)) - (n + 1) / n, with x sorted ascending and i starting at 1
+        let n = counts.len() as f64;
+        let gini_coefficient = if counts.is_empty() || total_rolls == 0 {
+            0.0
+        } else {
+            let weighted_sum: f64 = counts
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i as f64 + 1.0) * *c as f64)
+                .sum();
+            2.0 * weighted_sum / (n * total_rolls as f64) - (n + 1.0) / n
+        };
+
+        let top_stakers_rolls = counts
+            .iter()
+            .rev()
+            .take(top_n)
+            .fold(0u64, |acc, c| acc.saturating_add(*c));
+
+        StakerDistribution {
+            cycle,
+            staker_count: counts.len() as u64,
+            total_rolls,
+            active_rolls,
+            gini_coefficient,
+            top_stakers_rolls,
+        }
+    }
+}
+
 /// Block production statistics
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ProductionStats {
@@ -1,25 +1,30 @@
 use crate::{
-    CycleHistoryDeserializer, CycleHistorySerializer, CycleInfo, DeferredCreditsDeserializer,
-    DeferredCreditsSerializer, PoSChanges, PosError, PosResult, ProductionStats,
-    SelectorController,
+    CycleCompletion, CycleHistoryDeserializer, CycleHistorySerializer, CycleInfo,
+    DeferredCreditsDeserializer, DeferredCreditsSerializer, PoSChanges, PosError, PosResult,
+    ProductionStats, SelectorController, StakerDistribution,
 };
 use crate::{DeferredCredits, PoSConfig};
 use bitvec::vec::BitVec;
 use massa_db_exports::{
     DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
     CYCLE_HISTORY_DESER_ERROR, CYCLE_HISTORY_PREFIX, CYCLE_HISTORY_SER_ERROR,
-    DEFERRED_CREDITS_DESER_ERROR, DEFERRED_CREDITS_PREFIX, DEFERRED_CREDITS_SER_ERROR, STATE_CF,
+    DEFERRED_CREDITS_DESER_ERROR, DEFERRED_CREDITS_PREFIX, DEFERRED_CREDITS_SER_ERROR,
+    PRODUCTION_STATS_CF, STATE_CF,
 };
 use massa_hash::{Hash, HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::amount::Amount;
-use massa_models::{address::Address, prehash::PreHashMap, slot::Slot};
+use massa_models::{
+    address::Address,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+};
 use massa_serialization::{DeserializeError, Deserializer, Serializer, U64VarIntSerializer};
 use nom::AsBytes;
 use std::collections::VecDeque;
 use std::ops::Bound::{Excluded, Included};
 use std::ops::RangeBounds;
 use std::{collections::BTreeMap, path::PathBuf};
-use tracing::debug;
+use tracing::{debug, warn};
 
 // General cycle info idents
 const COMPLETE_IDENT: u8 = 0u8;
@@ -33,6 +38,13 @@ const UPPER_LIMIT: u8 = u8::MAX;
 const PROD_STATS_FAIL_IDENT: u8 = 0u8;
 const PROD_STATS_SUCCESS_IDENT: u8 = 1u8;
 
+/// Key of the archived production stats of an address in `PRODUCTION_STATS_CF`.
+///
+/// The cycle is big-endian encoded so that archived cycles are ordered.
+fn production_stats_history_key(cycle: u64, address: &Address) -> Vec<u8> {
+    [&cycle.to_be_bytes()[..], &address.to_prefixed_bytes()[..]].concat()
+}
+
 /// Complete key formatting macro
 #[macro_export]
 macro_rules! complete_key {
@@ -342,6 +354,52 @@ impl PoSFinalState {
         Ok(())
     }
 
+    /// Copies the production stats of a cycle leaving the history into `PRODUCTION_STATS_CF`,
+    /// and prunes the archived cycles older than `production_stats_history_length`.
+    ///
+    /// The archive is local to the node: it is neither hashed nor bootstrapped.
+    fn archive_production_stats(&self, cycle: u64) {
+        if self.config.production_stats_history_length == 0 {
+            return;
+        }
+
+        let mut batch = DBBatch::new();
+        for (address, stats) in self.get_all_production_stats_private(cycle) {
+            let mut serialized_stats = Vec::new();
+            let u64_ser = &self.cycle_info_serializer.cycle_info_serializer.u64_ser;
+            u64_ser
+                .serialize(&stats.block_success_count, &mut serialized_stats)
+                .expect(CYCLE_HISTORY_SER_ERROR);
+            u64_ser
+                .serialize(&stats.block_failure_count, &mut serialized_stats)
+                .expect(CYCLE_HISTORY_SER_ERROR);
+            batch.insert(
+                production_stats_history_key(cycle, &address),
+                Some(serialized_stats),
+            );
+        }
+
+        let db = self.db.read();
+        let min_cycle = cycle
+            .saturating_add(1)
+            .saturating_sub(self.config.production_stats_history_length);
+        for (serialized_key, _) in db.range_iterator_cf(
+            PRODUCTION_STATS_CF,
+            &[],
+            Some(&min_cycle.to_be_bytes()),
+            MassaDirection::Forward,
+        ) {
+            batch.insert(serialized_key, None);
+        }
+
+        if let Err(err) = db.write_local_batch(PRODUCTION_STATS_CF, batch) {
+            warn!(
+                "could not archive the production stats of cycle {}: {}",
+                cycle, err
+            );
+        }
+    }
+
     /// Deletes a given cycle from RocksDB
     pub fn delete_cycle_info(&mut self, cycle: u64, batch: &mut DBBatch) {
         let db = self.db.read();
@@ -459,6 +517,7 @@ impl PoSFinalState {
                 );
                 while self.cycle_history_cache.len() > self.config.cycle_history_length {
                     if let Some((old_cycle, _)) = self.cycle_history_cache.pop_front() {
+                        self.archive_production_stats(old_cycle);
                         self.delete_cycle_info(old_cycle, batch);
                     }
                 }
@@ -539,7 +598,9 @@ impl PoSFinalState {
         }
     }
 
-    /// Feeds the selector targeting a given draw cycle
+    /// Feeds the selector targeting a given draw cycle.
+    /// Called as soon as cycle `draw_cycle - 2` completes, which makes the seed of the draw final:
+    /// the selector then computes the draw in the background.
     pub fn feed_selector(&self, draw_cycle: u64) -> PosResult<()> {
         // get roll lookback
 
@@ -1141,6 +1202,144 @@ impl PoSFinalState {
         }
     }
 
+    /// Gets the production stats of the given addresses for each of the last `cycle_count` cycles,
+    /// reading both the cycle history and the archived production stats.
+    ///
+    /// Cycles in which an address had no production stats are omitted for that address.
+    pub fn get_production_stats_history(
+        &self,
+        addresses: &PreHashSet<Address>,
+        cycle_count: u64,
+    ) -> BTreeMap<u64, PreHashMap<Address, ProductionStats>> {
+        let mut history: BTreeMap<u64, PreHashMap<Address, ProductionStats>> = BTreeMap::new();
+        let (Some((first_cached_cycle, _)), Some((last_cycle, _))) = (
+            self.cycle_history_cache.front(),
+            self.cycle_history_cache.back(),
+        ) else {
+            return history;
+        };
+        if cycle_count == 0 {
+            return history;
+        }
+        let first_cycle = last_cycle.saturating_sub(cycle_count - 1);
+
+        // archived cycles
+        if first_cycle < *first_cached_cycle {
+            let db = self.db.read();
+            let deser = &self
+                .cycle_info_deserializer
+                .cycle_info_deserializer
+                .production_stats_deser;
+            for (serialized_key, serialized_value) in db.range_iterator_cf(
+                PRODUCTION_STATS_CF,
+                &first_cycle.to_be_bytes(),
+                Some(&first_cached_cycle.to_be_bytes()),
+                MassaDirection::Forward,
+            ) {
+                let (cycle_bytes, address_bytes) = serialized_key.split_at(8);
+                let cycle =
+                    u64::from_be_bytes(cycle_bytes.try_into().expect(CYCLE_HISTORY_DESER_ERROR));
+                let (_, address) = deser
+                    .address_deserializer
+                    .deserialize::<DeserializeError>(address_bytes)
+                    .expect(CYCLE_HISTORY_DESER_ERROR);
+                if !addresses.contains(&address) {
+                    continue;
+                }
+                let (rest, block_success_count) = deser
+                    .u64_deserializer
+                    .deserialize::<DeserializeError>(&serialized_value)
+                    .expect(CYCLE_HISTORY_DESER_ERROR);
+                let (_, block_failure_count) = deser
+                    .u64_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .expect(CYCLE_HISTORY_DESER_ERROR);
+                history.entry(cycle).or_default().insert(
+                    address,
+                    ProductionStats {
+                        block_success_count,
+                        block_failure_count,
+                    },
+                );
+            }
+        }
+
+        // cycles still in the history
+        for (cycle, _) in self.cycle_history_cache.iter() {
+            if *cycle < first_cycle {
+                continue;
+            }
+            let mut stats = self.get_all_production_stats_private(*cycle);
+            stats.retain(|address, _| addresses.contains(address));
+            if !stats.is_empty() {
+                history.insert(*cycle, stats);
+            }
+        }
+
+        history
+    }
+
+    /// Summarizes a completed cycle for the cycle completion feed.
+    ///
+    /// Roll changes are relative to the previous cycle, or to the initial rolls for cycle 0.
+    /// Returns `None` if the cycle is incomplete or not in the history.
+    pub fn get_cycle_completion(&self, cycle: u64) -> Option<CycleCompletion> {
+        let index = self.get_cycle_index(cycle)?;
+        if !self.cycle_history_cache[index].1 {
+            return None;
+        }
+
+        let roll_counts = self.get_all_roll_counts(cycle);
+        let previous_roll_counts = match cycle.checked_sub(1) {
+            Some(previous_cycle) if self.get_cycle_index(previous_cycle).is_some() => {
+                self.get_all_roll_counts(previous_cycle)
+            }
+            // the previous cycle left the history: report every roll count
+            Some(_) => BTreeMap::new(),
+            None => self.initial_rolls.clone(),
+        };
+        let mut roll_changes: BTreeMap<Address, u64> = roll_counts
+            .iter()
+            .filter(|(addr, count)| previous_roll_counts.get(addr) != Some(count))
+            .map(|(addr, count)| (*addr, *count))
+            .collect();
+        for addr in previous_roll_counts.keys() {
+            if !roll_counts.contains_key(addr) {
+                roll_changes.insert(*addr, 0);
+            }
+        }
+
+        Some(CycleCompletion {
+            cycle,
+            roll_changes,
+            final_state_hash_snapshot: self.get_cycle_history_final_state_hash_snapshot(cycle),
+        })
+    }
+
+    /// Computes the roll distribution statistics of every cycle in the history, oldest first.
+    ///
+    /// `top_n` is the number of biggest stakers whose rolls are summed up in `top_stakers_rolls`.
+    pub fn get_staker_distribution_history(&self, top_n: usize) -> Vec<StakerDistribution> {
+        self.cycle_history_cache
+            .iter()
+            .map(|(cycle, _)| {
+                let active_rolls = match cycle.checked_sub(3) {
+                    Some(lookback_cycle) if self.get_cycle_index(lookback_cycle).is_some() => {
+                        Some(self.get_all_roll_counts(lookback_cycle).values().sum())
+                    }
+                    Some(_) => None,
+                    None => Some(self.initial_rolls.values().sum()),
+                };
+                StakerDistribution::new(
+                    *cycle,
+                    &self.get_all_roll_counts(*cycle),
+                    active_rolls,
+                    top_n,
+                )
+            })
+            .collect()
+    }
+
     /// Check if a cycle is complete (all slots finalized)
     pub fn is_cycle_complete(&self, cycle: u64) -> Option<bool> {
         let key = complete_key!(self.cycle_history_cycle_prefix(cycle));
@@ -1625,7 +1824,7 @@ mod tests {
         use massa_db_worker::MassaDB;
         use massa_models::config::constants::{
             MAX_DEFERRED_CREDITS_LENGTH, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
-            POS_SAVED_CYCLES,
+            POS_PRODUCTION_STATS_HISTORY_CYCLES, POS_SAVED_CYCLES,
         };
         use parking_lot::RwLock;
         use std::str::FromStr;
@@ -1666,6 +1865,7 @@ mod tests {
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
             initial_deferred_credits_path: Some(initial_deferred_credits_file.path().to_path_buf()),
         };
 
@@ -1674,8 +1874,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: tempdir.path().to_path_buf(),
             max_history_length: 10,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: 2,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -1760,15 +1965,14 @@ mod tests {
     fn test_pos_cache_recomputation() {
         use crate::MockSelectorController;
         use crate::PoSFinalState;
+        use massa_db_exports::test_exports::MemoryMassaDB;
         use massa_db_exports::{MassaDBConfig, MassaDBController};
-        use massa_db_worker::MassaDB;
         use massa_models::config::constants::{
             MAX_DEFERRED_CREDITS_LENGTH, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
-            POS_SAVED_CYCLES,
+            POS_PRODUCTION_STATS_HISTORY_CYCLES, POS_SAVED_CYCLES,
         };
         use parking_lot::RwLock;
         use std::sync::Arc;
-        use tempfile::TempDir;
 
         let pos_config = PoSConfig {
             periods_per_cycle: 2,
@@ -1777,19 +1981,24 @@ mod tests {
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
             initial_deferred_credits_path: None,
         };
 
         // initialize the database and pos_state
-        let tempdir = TempDir::new().expect("cannot create temp directory");
         let db_config = MassaDBConfig {
-            path: tempdir.path().to_path_buf(),
+            path: PathBuf::from("memory_db"),
             max_history_length: 10,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: 2,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db = Arc::new(RwLock::new(
-            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+            Box::new(MemoryMassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
         ));
         let selector_controller = Box::new(MockSelectorController::new());
         let init_seed = Hash::compute_from(b"");
@@ -1876,7 +2085,7 @@ mod tests {
         use massa_db_worker::MassaDB;
         use massa_models::config::constants::{
             MAX_DEFERRED_CREDITS_LENGTH, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
-            POS_SAVED_CYCLES,
+            POS_PRODUCTION_STATS_HISTORY_CYCLES, POS_SAVED_CYCLES,
         };
         use massa_signature::KeyPair;
         use parking_lot::RwLock;
@@ -1890,6 +2099,7 @@ mod tests {
             max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
             max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
             max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            production_stats_history_length: POS_PRODUCTION_STATS_HISTORY_CYCLES,
             initial_deferred_credits_path: None,
         };
 
@@ -1898,8 +2108,13 @@ mod tests {
         let db_config = MassaDBConfig {
             path: tempdir.path().to_path_buf(),
             max_history_length: 10,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: 2,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -2040,4 +2255,27 @@ mod tests {
 
         assert_eq!(cycle_info_a, cycle_info_b, "cycle_info mismatch");
     }
+
+    #[test]
+    fn test_staker_distribution() {
+        use std::str::FromStr;
+
+        let addr_a =
+            Address::from_str("AU12pAcVUzsgUBJHaYSAtDKVTYnUT9NorBDjoDovMfAFTLFa16MNa").unwrap();
+        let addr_b =
+            Address::from_str("AU1wN8rn4SkwYSTDF3dHFY4U28KtsqKL1NnEjDZhHnHEy6cEQm53").unwrap();
+
+        let equal =
+            StakerDistribution::new(5, &BTreeMap::from([(addr_a, 2), (addr_b, 2)]), None, 1);
+        assert_eq!(equal.staker_count, 2);
+        assert_eq!(equal.total_rolls, 4);
+        assert_eq!(equal.top_stakers_rolls, 2);
+        assert!(equal.gini_coefficient.abs() < 1e-9);
+
+        let unequal =
+            StakerDistribution::new(5, &BTreeMap::from([(addr_a, 1), (addr_b, 3)]), Some(4), 1);
+        assert_eq!(unequal.top_stakers_rolls, 3);
+        assert_eq!(unequal.active_rolls, Some(4));
+        assert!((unequal.gini_coefficient - 0.25).abs() < 1e-9);
+    }
 }
@@ -7,8 +7,14 @@ use std::collections::BTreeMap;
 
 use crate::{Command, DrawCachePtr};
 use massa_hash::Hash;
-use massa_models::{address::Address, prehash::PreHashSet, slot::Slot};
-use massa_pos_exports::{PosError, PosResult, Selection, SelectorController, SelectorManager};
+use massa_models::{
+    address::Address,
+    prehash::PreHashSet,
+    slot::{IndexedSlot, Slot},
+};
+use massa_pos_exports::{
+    AddressSelections, PosError, PosResult, Selection, SelectorController, SelectorManager,
+};
 #[cfg(feature = "testing")]
 use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::SyncSender;
@@ -186,6 +192,51 @@ impl SelectorController for SelectorControllerImpl {
         Ok(res)
     }
 
+    /// Get the block and endorsement slots drawn for an address in a cycle range (only lists available cycles):
+    /// # Arguments
+    /// * `address`: address to get the draws for
+    /// * `cycle_range`: target cycles of the draws (from included, to included)
+    fn get_address_selections_in_cycle_range(
+        &self,
+        address: &Address,
+        cycle_range: std::ops::RangeInclusive<u64>,
+    ) -> PosResult<AddressSelections> {
+        // take lock
+        let (_cache_cv, cache_lock) = &*self.cache;
+        let cache_guard = cache_lock.read();
+        let cache = cache_guard.as_ref().map_err(|err| err.clone())?;
+
+        // restrict the range to the available cycles
+        let Some(available_cycles) = cache.get_available_cycles_range() else {
+            return Ok(AddressSelections::default());
+        };
+        let first_cycle = std::cmp::max(*cycle_range.start(), *available_cycles.start());
+        let last_cycle = std::cmp::min(*cycle_range.end(), *available_cycles.end());
+
+        // get the draws
+        let mut res = AddressSelections::default();
+        for cycle in first_cycle..=last_cycle {
+            let Some(cycle_draws) = cache.get(cycle) else {
+                continue;
+            };
+            for (slot, selection) in cycle_draws.draws.iter() {
+                if &selection.producer == address {
+                    res.block_draws.push(*slot);
+                }
+                for (index, endorser) in selection.endorsements.iter().enumerate() {
+                    if endorser == address {
+                        res.endorsement_draws
+                            .push(IndexedSlot { slot: *slot, index });
+                    }
+                }
+            }
+        }
+        res.block_draws.sort_unstable();
+        res.endorsement_draws
+            .sort_unstable_by_key(|indexed_slot| (indexed_slot.slot, indexed_slot.index));
+        Ok(res)
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn SelectorController>`,
     /// see `massa-pos-exports/controller_traits.rs`
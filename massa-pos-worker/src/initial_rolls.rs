@@ -0,0 +1,222 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Validation and generation of the initial rolls file used at genesis
+
+use massa_models::address::Address;
+use massa_pos_exports::{PosError, PosResult};
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+/// Issues found in an initial rolls distribution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InitialRollsReport {
+    /// addresses listed more than once (the last count is the one kept)
+    pub duplicate_addresses: Vec<Address>,
+    /// addresses listed with 0 rolls
+    pub zero_roll_addresses: Vec<Address>,
+    /// total rolls of each thread
+    pub rolls_per_thread: Vec<u64>,
+    /// threads whose total rolls differ from the mean by more than half of it
+    pub imbalanced_threads: Vec<u8>,
+}
+
+impl InitialRollsReport {
+    /// Whether the distribution has no issue
+    pub fn is_valid(&self) -> bool {
+        self.duplicate_addresses.is_empty()
+            && self.zero_roll_addresses.is_empty()
+            && self.imbalanced_threads.is_empty()
+    }
+}
+
+impl fmt::Display for InitialRollsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "no issue found");
+        }
+        let mut issues = Vec::new();
+        if !self.duplicate_addresses.is_empty() {
+            issues.push(format!(
+                "duplicate addresses: {}",
+                self.duplicate_addresses
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if !self.zero_roll_addresses.is_empty() {
+            issues.push(format!(
+                "addresses with 0 rolls: {}",
+                self.zero_roll_addresses
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if !self.imbalanced_threads.is_empty() {
+            issues.push(format!(
+                "imbalanced threads {:?} (rolls per thread: {:?})",
+                self.imbalanced_threads, self.rolls_per_thread
+            ));
+        }
+        write!(f, "{}", issues.join("; "))
+    }
+}
+
+/// Every entry of an initial rolls file, in order and duplicates included
+struct InitialRollsEntries(Vec<(Address, u64)>);
+
+impl<'de> Deserialize<'de> for InitialRollsEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = InitialRollsEntries;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map from addresses to roll counts")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+                while let Some(entry) = map.next_entry::<Address, u64>()? {
+                    entries.push(entry);
+                }
+                Ok(InitialRollsEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// Checks an initial rolls distribution given as a list of entries
+pub fn check_initial_rolls(entries: &[(Address, u64)], thread_count: u8) -> InitialRollsReport {
+    let mut report = InitialRollsReport {
+        rolls_per_thread: vec![0; thread_count as usize],
+        ..Default::default()
+    };
+
+    let mut seen = BTreeSet::new();
+    let mut duplicates = BTreeSet::new();
+    for (address, _) in entries {
+        if !seen.insert(*address) {
+            duplicates.insert(*address);
+        }
+    }
+    report.duplicate_addresses = duplicates.into_iter().collect();
+
+    // only the last count of a duplicate address is kept, as when loading the file
+    let rolls: BTreeMap<Address, u64> = entries.iter().copied().collect();
+    for (address, count) in &rolls {
+        if *count == 0 {
+            report.zero_roll_addresses.push(*address);
+        }
+        let thread = address.get_thread(thread_count) as usize;
+        report.rolls_per_thread[thread] = report.rolls_per_thread[thread].saturating_add(*count);
+    }
+
+    let total: u64 = report.rolls_per_thread.iter().sum();
+    if thread_count > 0 && total > 0 {
+        let mean = total / thread_count as u64;
+        let tolerance = mean / 2;
+        report.imbalanced_threads = report
+            .rolls_per_thread
+            .iter()
+            .enumerate()
+            .filter(|(_, rolls)| rolls.abs_diff(mean) > tolerance)
+            .map(|(thread, _)| thread as u8)
+            .collect();
+    }
+
+    report
+}
+
+/// Loads an initial rolls file and checks its content.
+///
+/// The returned rolls are the ones the node would use: for duplicate addresses the last count is kept.
+pub fn load_initial_rolls(
+    path: &Path,
+    thread_count: u8,
+) -> PosResult<(BTreeMap<Address, u64>, InitialRollsReport)> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| PosError::RollsFileLoadingError(format!("error opening file: {}", err)))?;
+    let InitialRollsEntries(entries) = serde_json::from_str(&content).map_err(|err| {
+        PosError::RollsFileLoadingError(format!("error while deserializing: {}", err))
+    })?;
+    let report = check_initial_rolls(&entries, thread_count);
+    Ok((entries.into_iter().collect(), report))
+}
+
+/// Generates an initial rolls distribution of `total_rolls` rolls over the given addresses.
+///
+/// Rolls are first split evenly between the threads that have at least one address,
+/// then evenly between the addresses of each thread. Addresses that would get 0 rolls are left out.
+pub fn generate_initial_rolls(
+    addresses: &[Address],
+    total_rolls: u64,
+    thread_count: u8,
+) -> BTreeMap<Address, u64> {
+    let mut addresses_per_thread: BTreeMap<u8, BTreeSet<Address>> = BTreeMap::new();
+    for address in addresses {
+        addresses_per_thread
+            .entry(address.get_thread(thread_count))
+            .or_default()
+            .insert(*address);
+    }
+
+    let mut rolls = BTreeMap::new();
+    let thread_shares = split_evenly(total_rolls, addresses_per_thread.len());
+    for (thread_addresses, thread_rolls) in addresses_per_thread.values().zip(thread_shares) {
+        let address_shares = split_evenly(thread_rolls, thread_addresses.len());
+        for (address, count) in thread_addresses.iter().zip(address_shares) {
+            if count > 0 {
+                rolls.insert(*address, count);
+            }
+        }
+    }
+    rolls
+}
+
+/// Splits `total` into `parts` shares differing by at most 1, largest first
+fn split_evenly(total: u64, parts: usize) -> Vec<u64> {
+    if parts == 0 {
+        return Vec::new();
+    }
+    let parts_u64 = parts as u64;
+    let (share, remainder) = (total / parts_u64, total % parts_u64);
+    (0..parts_u64)
+        .map(|i| share + u64::from(i < remainder))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_generated_initial_rolls_are_valid() {
+        let thread_count = 4;
+        // enough addresses for every thread to get at least one with high probability
+        let addresses: Vec<Address> = (0..64)
+            .map(|_| Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key()))
+            .collect();
+
+        let rolls = generate_initial_rolls(&addresses, 1003, thread_count);
+        assert_eq!(rolls.values().sum::<u64>(), 1003);
+        let entries: Vec<(Address, u64)> = rolls.into_iter().collect();
+        assert!(check_initial_rolls(&entries, thread_count).is_valid());
+
+        let mut faulty = entries.clone();
+        faulty.push((entries[0].0, 0));
+        let report = check_initial_rolls(&faulty, thread_count);
+        assert_eq!(report.duplicate_addresses, vec![entries[0].0]);
+        assert_eq!(report.zero_roll_addresses, vec![entries[0].0]);
+    }
+}
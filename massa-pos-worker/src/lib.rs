@@ -2,6 +2,7 @@
 
 mod controller;
 mod draw;
+mod initial_rolls;
 mod worker;
 
 use massa_hash::Hash;
@@ -88,6 +89,10 @@ pub(crate) type DrawCachePtr = Arc<(RwLockCondvar, RwLock<PosResult<DrawCache>>)
 /// Start thread selector
 pub use worker::start_selector_worker;
 
+pub use initial_rolls::{
+    check_initial_rolls, generate_initial_rolls, load_initial_rolls, InitialRollsReport,
+};
+
 // an RwLock condvar
 #[derive(Default)]
 struct RwLockCondvar {
@@ -107,6 +107,11 @@ impl SelectorThread {
     ///
     /// While a `Stop` command isn't sent, pop `input_data` and compute
     /// draws for future cycle.
+    ///
+    /// The final state feeds the draw of cycle C + 2 as soon as cycle C completes,
+    /// which is when the seed of that draw becomes final (see `PoSFinalState::feed_selector`):
+    /// the draws are thus computed in this thread two cycles before they are needed, off the critical path.
+    /// They are computed without holding the cache lock, and each result is then added to the cache at once.
     fn run(self) -> PosResult<()> {
         loop {
             let Ok(Command::DrawInput {
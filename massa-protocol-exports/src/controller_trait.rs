@@ -6,7 +6,7 @@ use std::net::SocketAddr;
 use crate::error::ProtocolError;
 use crate::BootstrapPeers;
 
-use crate::PeerId;
+use crate::{PeerId, PeerRecord, PeerRecordUpdate};
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::NetworkStats;
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
@@ -78,6 +78,15 @@ pub trait ProtocolController: Send + Sync {
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Get the persisted records of the peers (score, bans, handshake failures)
+    fn get_peer_records(&self) -> Result<Vec<(PeerId, PeerRecord)>, ProtocolError>;
+
+    /// Apply manual changes to the persisted records of the peers
+    fn update_peer_records(
+        &self,
+        updates: Vec<(PeerId, PeerRecordUpdate)>,
+    ) -> Result<(), ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
@@ -0,0 +1,31 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::net::SocketAddr;
+
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+
+/// Trust state of a peer, persisted across restarts
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerRecord {
+    /// address of the last connection with the peer
+    pub addr: Option<SocketAddr>,
+    /// behaviour score: lowered by bans and handshake failures, raised by successful handshakes,
+    /// and decaying towards zero over time
+    pub score: i64,
+    /// last time a handshake with the peer succeeded
+    pub last_seen: Option<MassaTime>,
+    /// time at which the ban of the peer ends, none if the peer is not banned
+    pub ban_expiry: Option<MassaTime>,
+    /// number of handshake failures since the last successful handshake
+    pub handshake_failures: u64,
+}
+
+/// Manual change to a peer record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerRecordUpdate {
+    /// overwrite the score of the peer
+    SetScore(i64),
+    /// forget everything about the peer, lifting its ban
+    Forget,
+}
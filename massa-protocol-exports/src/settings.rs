@@ -2,7 +2,7 @@
 
 use std::{
     collections::HashMap,
-    net::{IpAddr, SocketAddr},
+    net::{IpAddr, Ipv6Addr, SocketAddr},
     path::PathBuf,
 };
 
@@ -148,8 +148,14 @@ pub struct ProtocolConfig {
     pub try_connection_timer: MassaTime,
     /// try connection timer same peer
     pub try_connection_timer_same_peer: MassaTime,
-    /// periodically unban every peer
-    pub unban_everyone_timer: MassaTime,
+    /// duration of the first ban of a peer, doubled for each ban it got recently
+    pub peer_ban_duration: MassaTime,
+    /// file where the peer scores and bans are persisted
+    pub peer_store_file: PathBuf,
+    /// interval at which expired bans are lifted, peer scores decay and the peer store is saved
+    pub peer_store_interval: MassaTime,
+    /// percentage of each peer score kept at each peer store interval
+    pub peer_score_retention_percent: u64,
     /// Max in connections
     pub max_in_connections: usize,
     /// Timeout connection
@@ -162,6 +168,8 @@ pub struct ProtocolConfig {
     pub read_write_limit_bytes_per_second: u128,
     /// Optional routable ip
     pub routable_ip: Option<IpAddr>,
+    /// Optional routable IPv6 announced in addition to `routable_ip` by dual-stack nodes
+    pub routable_ipv6: Option<Ipv6Addr>,
     /// debug prints
     pub debug: bool,
     /// Peers categories infos
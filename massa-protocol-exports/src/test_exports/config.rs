@@ -81,8 +81,15 @@ impl Default for ProtocolConfig {
             read_write_limit_bytes_per_second: 1024 * 1000,
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
-            unban_everyone_timer: MassaTime::from_millis(ONE_DAY_MS),
+            peer_ban_duration: MassaTime::from_millis(ONE_DAY_MS),
+            peer_store_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
+            peer_store_interval: MassaTime::from_millis(1000),
+            peer_score_retention_percent: 99,
             routable_ip: None,
+            routable_ipv6: None,
             max_in_connections: 10,
             debug: true,
             peers_categories: HashMap::default(),
@@ -21,7 +21,7 @@ use tracing::{debug, warn};
 use crate::handlers::peer_handler::models::{ConnectionMetadata, PeerDB};
 use crate::{
     handlers::peer_handler::models::{InitialPeers, PeerState, SharedPeerDB},
-    ip::to_canonical,
+    ip::{routable_ips, select_listener, to_canonical},
     worker::ProtocolChannels,
 };
 use crate::{handlers::peer_handler::PeerManagementHandler, messages::MessagesHandler};
@@ -145,6 +145,7 @@ pub(crate) fn start_connectivity_thread(
                 sender_operations_propagation_ext.clone(),
                 protocol_channels.operation_handler_propagation.1.clone(),
                 peer_management_handler.sender.command_sender.clone(),
+                mip_store.clone(),
                 massa_metrics.clone(),
             );
             let mut endorsement_handler = EndorsementHandler::new(
@@ -186,7 +187,7 @@ pub(crate) fn start_connectivity_thread(
 
             let tick_metrics = tick(massa_metrics.tick_delay);
             let tick_try_connect = tick(config.try_connection_timer.to_duration());
-            let tick_unban_everyone = tick(config.unban_everyone_timer.to_duration());
+            let tick_peer_store = tick(config.peer_store_interval.to_duration());
 
             //Try to connect to peers
             loop {
@@ -207,6 +208,7 @@ pub(crate) fn start_connectivity_thread(
                                 println!("Stopped block handler");
                                 peer_management_handler.stop();
                                 println!("Stopped peer handler");
+                                peer_db.read().save_records(&config.peer_store_file);
                                 break;
                             },
                             Ok(ConnectivityCommand::GetStats { responder }) => {
@@ -259,6 +261,8 @@ pub(crate) fn start_connectivity_thread(
 
                         // Get all the addresses we can connect to, without any filter or prioritization done yet
                         let mut addresses_can_connect  = Vec::new();
+                        // used to pick, for each peer, a listener in an IP family we are reachable on
+                        let our_ips = routable_ips(&config);
                         {
                             let peer_db_read = peer_db.read();
                             for (peer_id, peer_info) in &peer_db_read.peers {
@@ -286,7 +290,7 @@ pub(crate) fn start_connectivity_thread(
                                             continue;
                                         }
 
-                                        if let Some((addr, _)) = last_announce.listeners.iter().next() {
+                                        if let Some(addr) = &select_listener(&last_announce.listeners, &our_ips) {
                                             let canonical_ip = to_canonical(addr.ip());
                                             let mut allowed_local_ips = false;
                                             // Check if the peer is in a category and we didn't reached out target yet
@@ -380,14 +384,10 @@ pub(crate) fn start_connectivity_thread(
                             }
                         }
                     }
-                    recv(tick_unban_everyone) -> _ => {
-                        debug!("Periodic unban of every peer");
-                        let mut peer_db_write = peer_db.write();
-                        for (peer_id, peer_status) in &peer_db_write.peers.clone() {
-                            if peer_status.state == PeerState::Banned {
-                                peer_db_write.unban_peer(peer_id);
-                            }
-                        }
+                    recv(tick_peer_store) -> _ => {
+                        debug!("Refreshing and saving the peer store");
+                        peer_db.write().refresh_records(config.peer_score_retention_percent);
+                        peer_db.read().save_records(&config.peer_store_file);
                     }
                 }
             }
@@ -17,6 +17,7 @@ use crate::{
         peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
     },
     messages::{Message, MessagesSerializer},
+    sig_verifier::find_verified_headers,
     wrap_network::ActiveConnectionsTrait,
 };
 use crossbeam::{
@@ -63,6 +64,9 @@ use super::{
     BlockMessageSerializer,
 };
 
+/// Maximum number of queued headers whose signatures are verified as one batch
+const HEADERS_BATCH_MAX_LEN: usize = 64;
+
 /// Info about a block we've seen
 #[derive(Debug, Clone)]
 pub(crate) struct BlockInfo {
@@ -73,6 +77,8 @@ pub(crate) struct BlockInfo {
     /// Operations and endorsements contained in the block,
     /// if we've received them already, and none otherwise.
     pub(crate) storage: Storage,
+    /// Whether the share of operations found locally was already accounted in the metrics
+    pub(crate) reconstruction_recorded: bool,
 }
 
 impl BlockInfo {
@@ -81,6 +87,7 @@ impl BlockInfo {
             header,
             operation_ids: None,
             storage,
+            reconstruction_recorded: false,
         }
     }
 }
@@ -155,8 +162,42 @@ impl RetrievalThread {
                                    self.update_block_retrieval();
                                 }
                                 BlockMessage::Header(header) => {
-                                    self.on_block_header_received(peer_id.clone(), header);
+                                    let mut headers = vec![(peer_id.clone(), header)];
+                                    let mut next_message = None;
+                                    // gather the headers already queued to verify their signatures as one batch
+                                    while headers.len() < HEADERS_BATCH_MAX_LEN {
+                                        let Ok((peer_id, message)) = self.receiver_network.try_recv() else {
+                                            break;
+                                        };
+                                        match block_message_deserializer.deserialize::<DeserializeError>(&message) {
+                                            Ok((rest, _)) if !rest.is_empty() => {
+                                                warn!("Error: block message not fully consumed");
+                                            }
+                                            Ok((_, BlockMessage::Header(header))) => {
+                                                headers.push((peer_id, header));
+                                            }
+                                            Ok((_, message)) => {
+                                                next_message = Some((peer_id, message));
+                                                break;
+                                            }
+                                            Err(err) => {
+                                                warn!("Error in deserializing block message: {:?}", err);
+                                            }
+                                        }
+                                    }
+                                    self.on_block_headers_received(headers);
                                     self.update_block_retrieval();
+                                    // process the message that ended the batch after the headers to keep the reception order
+                                    match next_message {
+                                        Some((peer_id, BlockMessage::DataRequest{block_id, block_info})) => {
+                                            self.on_ask_for_block_info_received(peer_id, block_id, block_info);
+                                        }
+                                        Some((peer_id, BlockMessage::DataResponse{block_id, block_info})) => {
+                                            self.on_block_info_received(peer_id, block_id, block_info);
+                                            self.update_block_retrieval();
+                                        }
+                                        Some((_, BlockMessage::Header(_))) | None => {}
+                                    }
                                 }
                             }
                         },
@@ -392,7 +433,7 @@ impl RetrievalThread {
         match block_info {
             BlockInfoReply::Header(header) => {
                 // Verify and send it consensus
-                self.on_block_header_received(from_peer_id, header);
+                self.on_block_header_received(from_peer_id, header, false);
             }
             BlockInfoReply::OperationIds(operation_list) => {
                 // Ask for missing operations ids and print a warning if there is no header for
@@ -416,14 +457,45 @@ impl RetrievalThread {
         }
     }
 
+    /// On a batch of block headers received from nodes.
+    ///
+    /// The signatures of the headers not seen yet and of their endorsements are verified in parallel as one batch,
+    /// then the headers are processed in their reception order.
+    /// If the batch fails to verify, it is bisected to isolate the faulty headers:
+    /// they are verified again on their own and their senders are banned.
+    fn on_block_headers_received(&mut self, headers: Vec<(PeerId, SecuredHeader)>) {
+        let verified_headers: PreHashSet<BlockId> = {
+            let cache_read = self.cache.read();
+            let unchecked_headers: Vec<&SecuredHeader> = headers
+                .iter()
+                .map(|(_, header)| header)
+                .filter(|header| cache_read.checked_headers.peek(&header.id).is_none())
+                .collect();
+            find_verified_headers(&unchecked_headers)
+                .into_iter()
+                .map(|header| header.id)
+                .collect()
+        };
+        for (from_peer_id, header) in headers {
+            let signatures_verified = verified_headers.contains(&header.id);
+            self.on_block_header_received(from_peer_id, header, signatures_verified);
+        }
+    }
+
     /// On block header received from a node.
-    fn on_block_header_received(&mut self, from_peer_id: PeerId, header: SecuredHeader) {
+    /// `signatures_verified` is set if the signatures of the header and its endorsements were already verified.
+    fn on_block_header_received(
+        &mut self,
+        from_peer_id: PeerId,
+        header: SecuredHeader,
+        signatures_verified: bool,
+    ) {
         debug!("received header {} from {}", header.id, from_peer_id);
 
         let block_id = header.id;
 
         // Check header and update knowledge info
-        let is_new = match self.note_header_from_peer(&header, &from_peer_id) {
+        let is_new = match self.note_header_from_peer(&header, &from_peer_id, signatures_verified) {
             Ok(is_new) => is_new,
             Err(err) => {
                 warn!(
@@ -505,7 +577,7 @@ impl RetrievalThread {
     /// - Not genesis
     /// - Compatible version
     /// - Can compute a `BlockId`
-    /// - Valid signature
+    /// - Valid signature, unless `signatures_verified` is set
     /// - All endorsement are valid
     /// - Endorsements have unique indices
     /// - Endorsement slots match that of the block
@@ -514,6 +586,7 @@ impl RetrievalThread {
         &mut self,
         header: &SecuredHeader,
         from_peer_id: &PeerId,
+        signatures_verified: bool,
     ) -> Result<bool, ProtocolError> {
         // refuse genesis blocks
         if header.content.slot.period == 0 || header.content.parents.is_empty() {
@@ -575,6 +648,7 @@ impl RetrievalThread {
         // check endorsements
         if let Err(err) = note_endorsements_from_peer(
             header.content.endorsements.clone(),
+            signatures_verified,
             from_peer_id,
             &self.endorsement_cache,
             self.selector_controller.as_ref(),
@@ -590,12 +664,14 @@ impl RetrievalThread {
         };
 
         // check header signature
-        if let Err(err) = header.verify_signature() {
-            return Err(ProtocolError::InvalidBlock(format!(
-                "invalid header signature: {}",
-                err
-            )));
-        };
+        if !signatures_verified {
+            if let Err(err) = header.verify_signature() {
+                return Err(ProtocolError::InvalidBlock(format!(
+                    "invalid header signature: {}",
+                    err
+                )));
+            };
+        }
 
         // check endorsement integrity within the context of the header
         let mut used_endorsement_indices: HashSet<u32> =
@@ -882,6 +958,7 @@ impl RetrievalThread {
             &from_peer_id,
             &mut self.sender_propagation_ops,
             &mut self.pool_controller,
+            &self.mip_store,
         ) {
             warn!(
                 "Peer id {} sent us operations for block id {} but they failed validity checks: {}",
@@ -917,6 +994,9 @@ impl RetrievalThread {
             self.cache
                 .write()
                 .insert_peer_known_block(&from_peer_id, &[block_id], false);
+
+            // the missing operations will be asked to another peer
+            self.massa_metrics.inc_block_ops_fetch_fallbacks();
         }
     }
 
@@ -965,6 +1045,15 @@ impl RetrievalThread {
                         .write()
                         .insert_peer_known_block(peer_id, &[*block_id], false);
 
+                    // if the peer was asked for operations, they will be asked to another peer
+                    if self
+                        .block_wishlist
+                        .get(block_id)
+                        .map_or(false, |info| info.operation_ids.is_some())
+                    {
+                        self.massa_metrics.inc_block_ops_fetch_fallbacks();
+                    }
+
                     // We mark the block for removal from the asked_blocks list.
                     // This prevents us from re-detecting the timeout many times.
                     to_remove_from_asked_blocks.push(*block_id);
@@ -1143,6 +1232,16 @@ impl RetrievalThread {
         // Gather all the ops in storage
         let claimed_ops = wishlist_info.storage.claim_operation_refs(&op_id_set);
 
+        // At the first gathering, the claimed ops are the ones the block could be rebuilt from
+        // without fetching anything: account for them and for the ones that will be fetched.
+        if !wishlist_info.reconstruction_recorded {
+            wishlist_info.reconstruction_recorded = true;
+            self.massa_metrics.inc_block_reconstruction(
+                claimed_ops.len(),
+                op_id_set.len().saturating_sub(claimed_ops.len()),
+            );
+        }
+
         // Mark the ops we already know about as checked by us,
         // this is used to refresh our knowledge cache in case it had expired.
         if !claimed_ops.is_empty() {
@@ -120,6 +120,7 @@ impl RetrievalThread {
                 debug!("Received endorsement message: Endorsement from {}", peer_id);
                 if let Err(err) = note_endorsements_from_peer(
                     endorsements,
+                    false,
                     &peer_id,
                     &self.cache,
                     self.selector_controller.as_ref(),
@@ -159,10 +160,11 @@ impl RetrievalThread {
 /// Does not ban if the endorsement is invalid
 ///
 /// Checks performed:
-/// - Valid signature.
+/// - Valid signature, unless `signatures_verified` is set because it was already checked in a batch.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn note_endorsements_from_peer(
     endorsements: Vec<SecureShareEndorsement>,
+    signatures_verified: bool,
     from_peer_id: &PeerId,
     cache: &SharedEndorsementCache,
     selector_controller: &dyn SelectorController,
@@ -193,18 +195,20 @@ pub(crate) fn note_endorsements_from_peer(
     }
 
     // Batch signature verification
-    verify_sigs_batch(
-        &new_endorsements
-            .values()
-            .map(|endorsement| {
-                (
-                    endorsement.compute_signed_hash(),
-                    endorsement.signature,
-                    endorsement.content_creator_pub_key,
-                )
-            })
-            .collect::<Vec<_>>(),
-    )?;
+    if !signatures_verified {
+        verify_sigs_batch(
+            &new_endorsements
+                .values()
+                .map(|endorsement| {
+                    (
+                        endorsement.compute_signed_hash(),
+                        endorsement.signature,
+                        endorsement.content_creator_pub_key,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )?;
+    }
 
     // Check PoS draws
     for endorsement in new_endorsements.values() {
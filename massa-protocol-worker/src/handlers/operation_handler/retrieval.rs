@@ -5,7 +5,7 @@ use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{
-    operation::{OperationPrefixId, OperationPrefixIds, SecureShareOperation},
+    operation::{OperationPrefixId, OperationPrefixIds, OperationType, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     secure_share::Id,
     slot::Slot,
@@ -17,6 +17,7 @@ use massa_protocol_exports::{ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::{MassaTime, TimeError};
+use massa_versioning::versioning::{MipComponent, MipStore};
 use schnellru::{ByLength, LruMap};
 
 use crate::{
@@ -60,6 +61,7 @@ pub struct RetrievalThread {
     receiver_ext: MassaReceiver<OperationHandlerRetrievalCommand>,
     operation_message_serializer: MessagesSerializer,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
+    mip_store: MipStore,
     _massa_metrics: MassaMetrics,
 }
 
@@ -106,7 +108,8 @@ impl RetrievalThread {
                                         ops,
                                         &peer_id,
                                         &mut self.internal_sender,
-                                        &mut self.pool_controller
+                                        &mut self.pool_controller,
+                                        &self.mip_store,
                                     ) {
                                         warn!("peer {} sent us critically incorrect operation, which may be an attack attempt by the remote peer or a loss of sync between us and the remote peer. Err = {}", peer_id, err);
 
@@ -368,6 +371,7 @@ pub(crate) fn note_operations_from_peer(
     source_peer_id: &PeerId,
     ops_propagation_sender: &mut MassaSender<OperationHandlerPropagationCommand>,
     pool_controller: &mut Box<dyn PoolController>,
+    mip_store: &MipStore,
 ) -> Result<(), ProtocolError> {
     massa_trace!("protocol.protocol_worker.note_operations_from_peer", { "peer": source_peer_id, "operations": operations });
     let now = MassaTime::now().expect("could not get current time");
@@ -405,6 +409,16 @@ pub(crate) fn note_operations_from_peer(
             )));
         };
 
+        // quit if op is a roll transfer and roll transfers are not active yet
+        if matches!(operation.content.op, OperationType::RollTransfer { .. })
+            && mip_store.get_latest_component_version_at(&MipComponent::RollTransfer, now) == 0
+        {
+            return Err(ProtocolError::InvalidOperationError(format!(
+                "Operation {} is a roll transfer but roll transfers are not active yet",
+                operation.id
+            )));
+        }
+
         // add to new operations
         new_operations.insert(operation.id, operation);
     }
@@ -475,6 +489,7 @@ pub fn start_retrieval_thread(
     receiver_ext: MassaReceiver<OperationHandlerRetrievalCommand>,
     internal_sender: MassaSender<OperationHandlerPropagationCommand>,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
+    mip_store: MipStore,
     massa_metrics: MassaMetrics,
 ) -> JoinHandle<()> {
     std::thread::Builder::new()
@@ -499,6 +514,7 @@ pub fn start_retrieval_thread(
                     .with_operation_message_serializer(OperationMessageSerializer::new()),
                 op_batch_buffer: VecDeque::new(),
                 peer_cmd_sender,
+                mip_store,
                 _massa_metrics: massa_metrics,
             };
             retrieval_thread.run();
@@ -170,17 +170,24 @@ impl Deserializer<Announcement> for AnnouncementDeserializer {
 }
 
 impl Announcement {
+    /// Announces each of our listeners on each of our routable IPs,
+    /// so that dual-stack nodes are reachable on both IPv4 and IPv6
     pub fn new(
-        mut listeners: HashMap<SocketAddr, TransportType>,
-        routable_ip: Option<IpAddr>,
+        listeners: HashMap<SocketAddr, TransportType>,
+        routable_ips: &[IpAddr],
         keypair: &KeyPair,
     ) -> PeerNetResult<Self> {
         let mut buf: Vec<u8> = vec![];
         let length_serializer = U64VarIntSerializer::new();
-        //TODO: Hacky to fix and adapt to support multiple ip/listeners
-        if routable_ip.is_none() {
-            listeners = HashMap::default()
-        }
+        // without routable IP, we don't know how others can reach us: no listener is announced
+        let listeners: HashMap<SocketAddr, TransportType> = routable_ips
+            .iter()
+            .flat_map(|ip| {
+                listeners
+                    .iter()
+                    .map(|(addr, transport)| (SocketAddr::new(*ip, addr.port()), *transport))
+            })
+            .collect();
         length_serializer
             .serialize(&(listeners.len() as u64), &mut buf)
             .map_err(|err| {
@@ -188,8 +195,7 @@ impl Announcement {
                     .error("Announcement serialization", Some(err.to_string()))
             })?;
         for listener in &listeners {
-            let ip = routable_ip.unwrap_or_else(|| listener.0.ip());
-            let ip_bytes = match ip {
+            let ip_bytes = match listener.0.ip() {
                 IpAddr::V4(ip) => {
                     buf.push(4);
                     ip.octets().to_vec()
@@ -238,8 +244,14 @@ mod tests {
         let mut listeners = HashMap::new();
         listeners.insert("127.0.0.1:8081".parse().unwrap(), TransportType::Tcp);
         listeners.insert("127.0.0.1:8082".parse().unwrap(), TransportType::Quic);
+        let routable_ips = ["1.2.3.4".parse().unwrap(), "2001:db8::1".parse().unwrap()];
         let announcement =
-            Announcement::new(listeners, None, &KeyPair::generate(0).unwrap()).unwrap();
+            Announcement::new(listeners, &routable_ips, &KeyPair::generate(0).unwrap()).unwrap();
+        // each listener is announced on both the IPv4 and the IPv6
+        assert_eq!(announcement.listeners.len(), 4);
+        assert!(announcement
+            .listeners
+            .contains_key(&"[2001:db8::1]:8082".parse().unwrap()));
         let announcement_serializer = AnnouncementSerializer::new();
         let announcement_deserializer =
             AnnouncementDeserializer::new(AnnouncementDeserializerArgs { max_listeners: 100 });
@@ -251,5 +263,16 @@ mod tests {
             .deserialize::<DeserializeError>(&buf)
             .unwrap();
         assert_eq!(announcement, announcement_deserialized);
+
+        // without routable IP, no listener is announced
+        let announcement = Announcement::new(
+            [("[::]:31244".parse().unwrap(), TransportType::Tcp)]
+                .into_iter()
+                .collect(),
+            &[],
+            &KeyPair::generate(0).unwrap(),
+        )
+        .unwrap();
+        assert!(announcement.listeners.is_empty());
     }
 }
@@ -27,6 +27,7 @@ use tracing::log::{debug, error, info, warn};
 
 use crate::context::Context;
 use crate::handlers::peer_handler::models::PeerState;
+use crate::ip::routable_ips;
 use crate::messages::{Message, MessagesHandler, MessagesSerializer};
 use crate::wrap_network::ActiveConnectionsTrait;
 
@@ -52,6 +53,7 @@ use self::{
 mod announcement;
 mod messages;
 pub mod models;
+mod peer_store;
 mod tester;
 
 pub(crate) use messages::{PeerManagementMessage, PeerManagementMessageSerializer};
@@ -138,7 +140,7 @@ impl PeerManagementHandler {
                                     active_connections.shutdown_connection(&peer_id);
 
                                     // update peer_db
-                                    peer_db.write().ban_peer(&peer_id);
+                                    peer_db.write().ban_peer(&peer_id, config.peer_ban_duration);
                                 }
                             },
                              Ok(PeerManagementCmd::Unban(peer_ids)) => {
@@ -148,10 +150,13 @@ impl PeerManagementHandler {
                             },
                              Ok(PeerManagementCmd::GetBootstrapPeers { responder }) => {
                                 let mut peers = peer_db.read().get_rand_peers_to_send(100);
-                                // Add myself
-                                if let Some(routable_ip) = config.routable_ip {
-                                    let listeners = config.listeners.iter().map(|(addr, ty)| {
-                                        (SocketAddr::new(routable_ip, addr.port()), *ty)
+                                // Add myself, with a listener on each of our routable IPs
+                                let our_ips = routable_ips(&config);
+                                if !our_ips.is_empty() {
+                                    let listeners = our_ips.iter().flat_map(|ip| {
+                                        config.listeners.iter().map(|(addr, ty)| {
+                                            (SocketAddr::new(*ip, addr.port()), *ty)
+                                        })
                                     }).collect();
                                     peers.push((peer_id.clone(), listeners));
                                 }
@@ -159,6 +164,18 @@ impl PeerManagementHandler {
                                     warn!("error sending bootstrap peers: {:?}", err);
                                 }
                              },
+                             Ok(PeerManagementCmd::GetPeerRecords { responder }) => {
+                                let records = peer_db.read().get_records();
+                                if let Err(err) = responder.try_send(records) {
+                                    warn!("error sending peer records: {:?}", err);
+                                }
+                             },
+                             Ok(PeerManagementCmd::UpdatePeerRecords(updates)) => {
+                                let mut peer_db_write = peer_db.write();
+                                for (peer_id, update) in updates {
+                                    peer_db_write.update_record(&peer_id, update);
+                                }
+                             },
                              Ok(PeerManagementCmd::Stop) => {
                                 while let Ok(_msg) = test_receiver.try_recv() {
                                     // nothing to do just clean the channel
@@ -179,11 +196,9 @@ impl PeerManagementHandler {
                                 }
                             };
                             // check if peer is banned
-                            if let Some(peer) = peer_db.read().peers.get(&peer_id) {
-                                if peer.state == PeerState::Banned {
-                                    warn!("Banned peer sent us a message: {:?}", peer_id);
-                                    continue;
-                                }
+                            if peer_db.read().is_banned(&peer_id) {
+                                warn!("Banned peer sent us a message: {:?}", peer_id);
+                                continue;
                             }
                             let (rest, message) = match message_deserializer
                                 .deserialize::<DeserializeError>(&message) {
@@ -330,7 +345,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
         bytes.push(0);
         let listeners_announcement = Announcement::new(
             listeners.clone(),
-            self.config.routable_ip,
+            &routable_ips(&self.config),
             &context.our_keypair,
         )
         .unwrap();
@@ -362,13 +377,11 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                     Some(format!("Failed to deserialize peer id: {}", err)),
                 )
             })?;
-        {
-            let peer_db_read = self.peer_db.read();
-            if let Some(info) = peer_db_read.peers.get(&peer_id) {
-                if info.state == PeerState::Banned {
-                    debug!("Banned peer tried to connect: {:?}", peer_id);
-                }
-            }
+        if self.peer_db.read().is_banned(&peer_id) {
+            debug!("Banned peer tried to connect: {:?}", peer_id);
+            self.handshake_fail(&addr);
+            return Err(PeerNetError::HandshakeError
+                .error("Massa Handshake", Some(String::from("Peer is banned"))));
         }
 
         let res = {
@@ -516,6 +529,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                         .entry(addr)
                         .or_insert(ConnectionMetadata::default())
                         .success();
+                    peer_db_write.record_handshake_success(peer_id, addr);
                     peer_db_write
                         .peers
                         .entry(peer_id.clone())
@@ -549,6 +563,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                         .entry(addr)
                         .or_insert(ConnectionMetadata::default())
                         .failure();
+                    peer_db_write.record_handshake_failure(&peer_id, addr);
                     peer_db_write.peers.entry(peer_id).and_modify(|info| {
                         //TODO: Add the peerdb but for now impossible as we don't have announcement and we need one to place in peerdb
                         info.state = PeerState::HandshakeFailed;
@@ -1,5 +1,5 @@
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolError};
+use massa_protocol_exports::{BootstrapPeers, PeerId, PeerRecord, PeerRecordUpdate};
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
@@ -13,7 +13,7 @@ use tracing::log::info;
 
 use super::announcement::Announcement;
 
-const THREE_DAYS_MS: u64 = 3 * 24 * 60 * 60 * 1_000;
+pub(crate) const THREE_DAYS_MS: u64 = 3 * 24 * 60 * 60 * 1_000;
 
 pub type InitialPeers = HashMap<PeerId, HashMap<SocketAddr, TransportType>>;
 
@@ -154,6 +154,8 @@ pub struct PeerDB {
     pub try_connect_history: HashMap<SocketAddr, ConnectionMetadata>,
     /// peers currently tested
     pub peers_in_test: HashSet<SocketAddr>,
+    /// persisted trust state of the peers, see `peer_store`
+    pub records: HashMap<PeerId, PeerRecord>,
 }
 
 pub type SharedPeerDB = Arc<RwLock<PeerDB>>;
@@ -182,6 +184,10 @@ pub enum PeerManagementCmd {
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
+    GetPeerRecords {
+        responder: MassaSender<Vec<(PeerId, PeerRecord)>>,
+    },
+    UpdatePeerRecords(Vec<(PeerId, PeerRecordUpdate)>),
     Stop,
 }
 
@@ -191,16 +197,27 @@ pub struct PeerManagementChannel {
 }
 
 impl PeerDB {
-    pub fn ban_peer(&mut self, peer_id: &PeerId) {
+    /// Bans a peer for `ban_duration`, or longer if it was banned recently
+    pub fn ban_peer(&mut self, peer_id: &PeerId, ban_duration: MassaTime) {
+        let expiry = self.record_ban(peer_id, ban_duration);
         if let Some(peer) = self.peers.get_mut(peer_id) {
             peer.state = PeerState::Banned;
-            info!("Banned peer: {:?}", peer_id);
+            info!(
+                "Banned peer: {:?} until {}",
+                peer_id,
+                expiry.format_instant()
+            );
         } else {
-            info!("Tried to ban unknown peer: {:?}", peer_id);
+            info!(
+                "Banned unknown peer: {:?} until {}",
+                peer_id,
+                expiry.format_instant()
+            );
         };
     }
 
     pub fn unban_peer(&mut self, peer_id: &PeerId) {
+        self.record_unban(peer_id);
         if let Some(peer) = self.peers.get_mut(peer_id) {
             // We set the state to HandshakeFailed to force the peer to be tested again
             peer.state = PeerState::HandshakeFailed;
@@ -279,14 +296,9 @@ impl PeerDB {
     }
 
     pub fn get_banned_peer_count(&self) -> u64 {
-        self.peers
+        self.records
             .values()
-            .filter(|peer| peer.state == PeerState::Banned)
+            .filter(|record| record.ban_expiry.is_some())
             .count() as u64
     }
-
-    // Flush PeerDB to disk ?
-    fn _flush(&self) -> Result<(), ProtocolError> {
-        unimplemented!()
-    }
 }
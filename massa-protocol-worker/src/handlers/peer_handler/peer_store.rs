@@ -0,0 +1,229 @@
+//! Persistent trust state of the peers.
+//!
+//! Each peer that got banned or that shook hands with us has a `PeerRecord`, saved as JSON in the
+//! `peer_store_file` so that bans survive a restart. Scoring rules:
+//! * a ban costs `BAN_PENALTY` points. It lasts `peer_ban_duration`, doubled for each started
+//!   `BAN_PENALTY` of negative score the peer still carries (at most `MAX_BAN_DOUBLINGS` times),
+//!   so that repeat offenders get longer bans
+//! * a successful handshake earns a point, up to `MAX_SCORE`, and resets the handshake failure count
+//! * a failed handshake increments the handshake failure count
+//! * at each `peer_store_interval`, expired bans are lifted and the scores of the peers that are not banned
+//!   keep `peer_score_retention_percent` percent of their value.
+//!   Records with a null score, no ban and no handshake in the last three days are forgotten.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+
+use massa_protocol_exports::{PeerId, PeerRecord, PeerRecordUpdate, ProtocolError};
+use massa_time::MassaTime;
+use tracing::log::{info, warn};
+
+use super::models::{PeerDB, PeerState, THREE_DAYS_MS};
+
+/// Score lost by a peer at each ban
+const BAN_PENALTY: i64 = 100;
+/// Highest score a peer can reach with successful handshakes
+const MAX_SCORE: i64 = 100;
+/// Maximum number of times the base ban duration is doubled
+const MAX_BAN_DOUBLINGS: i64 = 6;
+
+/// Reads the peer records persisted in `path`, an absent file meaning no record
+fn load_peer_records(path: &Path) -> Result<HashMap<PeerId, PeerRecord>, ProtocolError> {
+    if !path.is_file() {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+impl PeerDB {
+    /// Creates a peer database knowing the peer records persisted in `path`
+    pub fn load(path: &Path) -> Self {
+        let records = load_peer_records(path).unwrap_or_else(|err| {
+            warn!("could not load the peer store {}: {}", path.display(), err);
+            HashMap::new()
+        });
+        info!(
+            "Loaded {} peer records, {} banned peers",
+            records.len(),
+            records.values().filter(|r| r.ban_expiry.is_some()).count()
+        );
+        PeerDB {
+            records,
+            ..Default::default()
+        }
+    }
+
+    /// Persists the peer records in `path`
+    pub fn save_records(&self, path: &Path) {
+        let write = || -> Result<(), ProtocolError> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // write a temporary file first so that a crash never leaves a truncated store
+            let tmp_path = path.with_extension("tmp");
+            std::fs::write(&tmp_path, serde_json::to_string_pretty(&self.records)?)?;
+            std::fs::rename(&tmp_path, path)?;
+            Ok(())
+        };
+        if let Err(err) = write() {
+            warn!("could not save the peer store {}: {}", path.display(), err);
+        }
+    }
+
+    /// Whether a peer is currently banned
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.records
+            .get(peer_id)
+            .map_or(false, |record| record.ban_expiry.is_some())
+    }
+
+    /// Records a ban of a peer, returning its expiry
+    pub(crate) fn record_ban(&mut self, peer_id: &PeerId, ban_duration: MassaTime) -> MassaTime {
+        let now = MassaTime::now().expect("Unable to get MassaTime::now");
+        let record = self.records.entry(peer_id.clone()).or_default();
+        let debt = record.score.min(0).saturating_neg();
+        let doublings = ((debt + BAN_PENALTY - 1) / BAN_PENALTY).min(MAX_BAN_DOUBLINGS);
+        let expiry = now.saturating_add(ban_duration.saturating_mul(1u64 << doublings));
+        // a ban already running longer is kept
+        record.ban_expiry = Some(record.ban_expiry.map_or(expiry, |prev| prev.max(expiry)));
+        record.score = record.score.saturating_sub(BAN_PENALTY);
+        expiry
+    }
+
+    /// Records the lift of the ban of a peer
+    pub(crate) fn record_unban(&mut self, peer_id: &PeerId) {
+        if let Some(record) = self.records.get_mut(peer_id) {
+            record.ban_expiry = None;
+        }
+    }
+
+    /// Records a successful handshake with a peer
+    pub fn record_handshake_success(&mut self, peer_id: &PeerId, addr: SocketAddr) {
+        let record = self.records.entry(peer_id.clone()).or_default();
+        record.addr = Some(addr);
+        record.last_seen = Some(MassaTime::now().expect("Unable to get MassaTime::now"));
+        record.score = record.score.saturating_add(1).min(MAX_SCORE);
+        record.handshake_failures = 0;
+    }
+
+    /// Records a failed handshake with a peer
+    pub fn record_handshake_failure(&mut self, peer_id: &PeerId, addr: SocketAddr) {
+        let record = self.records.entry(peer_id.clone()).or_default();
+        record.addr = Some(addr);
+        record.handshake_failures = record.handshake_failures.saturating_add(1);
+    }
+
+    /// Lifts the expired bans, decays the scores of the peers that are not banned
+    /// and forgets the records that are not worth keeping
+    pub fn refresh_records(&mut self, retention_percent: u64) {
+        let now = MassaTime::now().expect("Unable to get MassaTime::now");
+        let min_last_seen = MassaTime::from_millis(now.to_millis().saturating_sub(THREE_DAYS_MS));
+        let retention_percent = retention_percent.min(100) as i64;
+        let mut unbanned = Vec::new();
+        self.records.retain(|peer_id, record| {
+            if let Some(expiry) = record.ban_expiry {
+                if expiry > now {
+                    return true;
+                }
+                record.ban_expiry = None;
+                unbanned.push(peer_id.clone());
+            }
+            record.score = record.score.saturating_mul(retention_percent) / 100;
+            record.score != 0 || record.last_seen.map_or(false, |t| t >= min_last_seen)
+        });
+        for peer_id in unbanned {
+            info!("Ban of peer {:?} expired", peer_id);
+            self.set_unbanned_state(&peer_id);
+        }
+    }
+
+    /// Applies a manual change to the record of a peer
+    pub fn update_record(&mut self, peer_id: &PeerId, update: PeerRecordUpdate) {
+        match update {
+            PeerRecordUpdate::SetScore(score) => {
+                self.records.entry(peer_id.clone()).or_default().score = score;
+            }
+            PeerRecordUpdate::Forget => {
+                if self.records.remove(peer_id).is_some() {
+                    self.set_unbanned_state(peer_id);
+                }
+            }
+        }
+    }
+
+    /// Get the records of all the peers, lowest scores first
+    pub fn get_records(&self) -> Vec<(PeerId, PeerRecord)> {
+        let mut records: Vec<(PeerId, PeerRecord)> = self
+            .records
+            .iter()
+            .map(|(peer_id, record)| (peer_id.clone(), record.clone()))
+            .collect();
+        records.sort_by_key(|(_, record)| record.score);
+        records
+    }
+
+    /// Puts a peer that is not banned anymore back in a state where it will be tested again
+    fn set_unbanned_state(&mut self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            if peer.state == PeerState::Banned {
+                peer.state = PeerState::HandshakeFailed;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use massa_protocol_exports::{PeerId, PeerRecordUpdate};
+    use massa_signature::KeyPair;
+    use massa_time::MassaTime;
+    use tempfile::TempDir;
+
+    use super::{PeerDB, BAN_PENALTY};
+
+    #[test]
+    fn test_peer_store_bans_persist_and_escalate() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("peers").join("peer_store.json");
+        let offender = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let other = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let ban_duration = MassaTime::from_millis(60_000);
+
+        let mut peer_db = PeerDB::load(&path);
+        assert!(peer_db.records.is_empty());
+
+        // first ban: base duration
+        let now = MassaTime::now().unwrap();
+        let first_expiry = peer_db.record_ban(&offender, ban_duration);
+        assert!(first_expiry >= now.saturating_add(ban_duration));
+        assert!(first_expiry < now.saturating_add(ban_duration.saturating_mul(2)));
+        assert!(peer_db.is_banned(&offender));
+        assert!(!peer_db.is_banned(&other));
+
+        // repeat offense while the score still carries the first ban: doubled duration
+        peer_db.record_unban(&offender);
+        let second_expiry = peer_db.record_ban(&offender, ban_duration);
+        assert!(second_expiry >= now.saturating_add(ban_duration.saturating_mul(2)));
+        assert_eq!(peer_db.records[&offender].score, -2 * BAN_PENALTY);
+
+        // the ban and the score survive a restart
+        peer_db.record_handshake_failure(&other, "127.0.0.1:31244".parse().unwrap());
+        peer_db.save_records(&path);
+        let mut peer_db = PeerDB::load(&path);
+        assert!(peer_db.is_banned(&offender));
+        assert_eq!(peer_db.records[&other].handshake_failures, 1);
+
+        // the running ban is not lifted and the score of a banned peer does not decay
+        peer_db.refresh_records(50);
+        assert!(peer_db.is_banned(&offender));
+        assert_eq!(peer_db.records[&offender].score, -2 * BAN_PENALTY);
+        // a record with a null score and no recent handshake is forgotten
+        assert!(!peer_db.records.contains_key(&other));
+
+        // forgetting a peer lifts its ban
+        peer_db.update_record(&offender, PeerRecordUpdate::Forget);
+        assert!(!peer_db.is_banned(&offender));
+        assert_eq!(peer_db.get_banned_peer_count(), 0);
+    }
+}
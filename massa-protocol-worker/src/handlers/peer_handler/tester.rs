@@ -6,7 +6,10 @@ use std::{
     time::Duration,
 };
 
-use crate::{ip::to_canonical, messages::MessagesHandler};
+use crate::{
+    ip::{routable_ips, to_canonical},
+    messages::MessagesHandler,
+};
 use massa_channel::{receiver::MassaReceiver, sender::MassaSender, MassaChannel};
 use massa_metrics::MassaMetrics;
 use massa_models::version::VersionDeserializer;
@@ -130,12 +133,9 @@ impl Tester {
             let res = {
                 {
                     // check if peer is banned
-                    let peer_db_read = peer_db.read();
-                    if let Some(info) = peer_db_read.peers.get(&peer_id) {
-                        if info.state == super::PeerState::Banned {
-                            return Err(PeerNetError::HandshakeError
-                                .error("Tester Handshake", Some(String::from("Peer is banned"))));
-                        }
+                    if peer_db.read().is_banned(&peer_id) {
+                        return Err(PeerNetError::HandshakeError
+                            .error("Tester Handshake", Some(String::from("Peer is banned"))));
                     }
                 }
 
@@ -383,12 +383,10 @@ impl Tester {
                                                 continue 'main_loop;
                                             }
 
-                                            //Don't test our proper ip
-                                            if let Some(ip) = protocol_config.routable_ip {
-                                                if to_canonical(ip) == ip_canonical {
-                                                    db.write().peers_in_test.remove(addr);
-                                                    continue 'main_loop;
-                                                }
+                                            //Don't test our proper ips
+                                            if routable_ips(&protocol_config).into_iter().any(|ip| to_canonical(ip) == ip_canonical) {
+                                                db.write().peers_in_test.remove(addr);
+                                                continue 'main_loop;
                                             }
                                             debug!("testing peer {} listener addr: {}", &listener.0, &addr);
 
@@ -449,12 +447,10 @@ impl Tester {
                                 continue;
                             }
                         }
-                        //Don't test our proper ip
-                        if let Some(ip) = protocol_config.routable_ip {
-                            if to_canonical(ip) == ip_canonical {
-                                db.write().peers_in_test.remove(&listener);
-                                continue;
-                            }
+                        //Don't test our proper ips
+                        if routable_ips(&protocol_config).into_iter().any(|ip| to_canonical(ip) == ip_canonical) {
+                            db.write().peers_in_test.remove(&listener);
+                            continue;
                         }
                         debug!("testing listener addr: {}", &listener);
 
@@ -1,4 +1,8 @@
-use std::net::IpAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+
+use massa_protocol_exports::ProtocolConfig;
+use peernet::transports::TransportType;
 
 // TODO: Use std one when stable
 pub(crate) fn to_canonical(ip: IpAddr) -> IpAddr {
@@ -12,3 +16,82 @@ pub(crate) fn to_canonical(ip: IpAddr) -> IpAddr {
         }
     }
 }
+
+/// IPs on which the other peers can reach us: the routable IP,
+/// and the routable IPv6 of dual-stack nodes when it is a different one
+pub(crate) fn routable_ips(config: &ProtocolConfig) -> Vec<IpAddr> {
+    let mut ips: Vec<IpAddr> = config.routable_ip.into_iter().collect();
+    if let Some(ipv6) = config.routable_ipv6.map(IpAddr::V6) {
+        if !ips.contains(&ipv6) {
+            ips.push(ipv6);
+        }
+    }
+    ips
+}
+
+/// Picks the listener to connect to among the ones announced by a peer.
+/// The listeners of an IP family we are reachable on come first, so that IPv6-only nodes
+/// connect to the IPv6 listener of dual-stack peers. Ties are broken by address for a stable choice.
+pub(crate) fn select_listener(
+    listeners: &HashMap<SocketAddr, TransportType>,
+    local_ips: &[IpAddr],
+) -> Option<SocketAddr> {
+    listeners
+        .keys()
+        .min_by_key(|addr| {
+            let is_ipv4 = to_canonical(addr.ip()).is_ipv4();
+            let reachable = local_ips
+                .iter()
+                .any(|ip| to_canonical(*ip).is_ipv4() == is_ipv4);
+            (!reachable, **addr)
+        })
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::net::{IpAddr, SocketAddr};
+
+    use massa_protocol_exports::ProtocolConfig;
+    use peernet::transports::TransportType;
+
+    use super::{routable_ips, select_listener, to_canonical};
+
+    #[test]
+    fn test_dual_stack_addresses() {
+        let mapped: IpAddr = "::ffff:1.2.3.4".parse().unwrap();
+        assert_eq!(to_canonical(mapped), "1.2.3.4".parse::<IpAddr>().unwrap());
+
+        let ipv4: IpAddr = "1.2.3.4".parse().unwrap();
+        let ipv6: IpAddr = "2001:db8::1".parse().unwrap();
+        let config = ProtocolConfig {
+            routable_ip: Some(ipv4),
+            routable_ipv6: Some("2001:db8::1".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(routable_ips(&config), vec![ipv4, ipv6]);
+        // the same IPv6 given twice is announced once
+        let config = ProtocolConfig {
+            routable_ip: Some(ipv6),
+            ..config
+        };
+        assert_eq!(routable_ips(&config), vec![ipv6]);
+
+        let v4_listener: SocketAddr = "1.2.3.5:31244".parse().unwrap();
+        let v6_listener: SocketAddr = "[2001:db8::2]:31244".parse().unwrap();
+        let listeners: HashMap<SocketAddr, TransportType> = [
+            (v4_listener, TransportType::Tcp),
+            (v6_listener, TransportType::Tcp),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(select_listener(&listeners, &[ipv6]), Some(v6_listener));
+        assert_eq!(select_listener(&listeners, &[ipv4]), Some(v4_listener));
+        assert_eq!(select_listener(&listeners, &[]), Some(v4_listener));
+        // an IPv6-only node still tries the IPv4 listener of an IPv4-only peer
+        let listeners = [(v4_listener, TransportType::Tcp)].into_iter().collect();
+        assert_eq!(select_listener(&listeners, &[ipv6]), Some(v4_listener));
+        assert_eq!(select_listener(&HashMap::new(), &[ipv6]), None);
+    }
+}
@@ -3,6 +3,7 @@
 //! Optimized batch signature verifier
 
 use massa_hash::Hash;
+use massa_models::block_header::SecuredHeader;
 use massa_protocol_exports::ProtocolError;
 use massa_signature::{verify_signature_batch, PublicKey, Signature};
 use rayon::{prelude::ParallelIterator, slice::ParallelSlice};
@@ -28,3 +29,42 @@ pub fn verify_sigs_batch(ops: &[(Hash, Signature, PublicKey)]) -> Result<(), Pro
         .try_for_each(verify_signature_batch)
         .map_err(|_err| ProtocolError::WrongSignature)
 }
+
+/// Verifies the signatures of a batch of headers and of their endorsements in parallel.
+/// Returns an error if at least one of them fails to verify.
+pub fn verify_headers_batch(headers: &[&SecuredHeader]) -> Result<(), ProtocolError> {
+    let sigs: Vec<(Hash, Signature, PublicKey)> = headers
+        .iter()
+        .flat_map(|header| {
+            std::iter::once((
+                header.compute_signed_hash(),
+                header.signature,
+                header.content_creator_pub_key,
+            ))
+            .chain(header.content.endorsements.iter().map(|endorsement| {
+                (
+                    endorsement.compute_signed_hash(),
+                    endorsement.signature,
+                    endorsement.content_creator_pub_key,
+                )
+            }))
+        })
+        .collect();
+    verify_sigs_batch(&sigs)
+}
+
+/// Finds the headers whose signatures, and those of their endorsements, verify.
+/// A batch that fails to verify is split in halves until the faulty headers are isolated,
+/// so that one bad header does not force every other header of the batch to be verified on its own.
+pub fn find_verified_headers<'a>(headers: &[&'a SecuredHeader]) -> Vec<&'a SecuredHeader> {
+    if headers.is_empty() || verify_headers_batch(headers).is_ok() {
+        return headers.to_vec();
+    }
+    if headers.len() == 1 {
+        return Vec::new();
+    }
+    let (first_half, second_half) = headers.split_at(headers.len() / 2);
+    let mut verified = find_verified_headers(first_half);
+    verified.extend(find_verified_headers(second_half));
+    verified
+}
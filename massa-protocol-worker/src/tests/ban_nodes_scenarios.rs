@@ -34,7 +34,7 @@ fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
     let mut protocol_config = ProtocolConfig::default();
     protocol_config.thread_count = 2;
     protocol_config.initial_peers = "./src/tests/empty_initial_peers.json".to_string().into();
-    protocol_config.unban_everyone_timer = MassaTime::from_millis(5000);
+    protocol_config.peer_ban_duration = MassaTime::from_millis(5000);
     let block_creator = KeyPair::generate(0).unwrap();
     let block = tools::create_block(&block_creator);
     let mut block_bad_public_key = block.clone();
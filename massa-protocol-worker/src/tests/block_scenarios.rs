@@ -166,6 +166,138 @@ fn test_full_ask_block_workflow() {
     )
 }
 
+#[test]
+fn test_ask_missing_operations_to_another_node() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+
+    let mut protocol_config = ProtocolConfig::default();
+    protocol_config.thread_count = 2;
+    protocol_config.initial_peers = "./src/tests/empty_initial_peers.json".to_string().into();
+
+    let block_creator = KeyPair::generate(0).unwrap();
+    let op_1 = tools::create_operation_with_expire_period(&block_creator, 5);
+    let op_2 = tools::create_operation_with_expire_period(&block_creator, 5);
+    let op_thread = op_1
+        .content_creator_address
+        .get_thread(protocol_config.thread_count);
+    let block = tools::create_block_with_operations(
+        &block_creator,
+        Slot::new(1, op_thread),
+        vec![op_1.clone(), op_2.clone()],
+    );
+
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_clone_box()
+        .returning(|| Box::new(MockConsensusController::new()));
+    consensus_controller
+        .expect_register_block_header()
+        .return_once(move |block_id, _| {
+            assert_eq!(block_id, block.id);
+        });
+    consensus_controller.expect_register_block().return_once(
+        move |block_id, _, block_storage, _| {
+            assert_eq!(block_id, block.id);
+            let received_block = block_storage.read_blocks().get(&block_id).cloned().unwrap();
+            assert_eq!(received_block.content.operations, block.content.operations);
+        },
+    );
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller.expect_clone_box().returning(|| {
+        let mut pool_controller = Box::new(MockPoolController::new());
+        pool_controller.expect_add_operations().returning(|_| {});
+        pool_controller
+    });
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_clone_box()
+        .returning(|| Box::new(MockSelectorController::new()));
+    protocol_test(
+        &protocol_config,
+        consensus_controller,
+        pool_controller,
+        selector_controller,
+        move |mut network_controller, _storage, protocol_controller| {
+            //1. Create 2 nodes
+            let node_a_keypair = KeyPair::generate(0).unwrap();
+            let node_b_keypair = KeyPair::generate(0).unwrap();
+            let (node_a_peer_id, node_a) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_a_keypair.get_public_key()));
+            let (node_b_peer_id, node_b) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_b_keypair.get_public_key()));
+
+            //2. Node A sends the block header and we want the block
+            network_controller
+                .send_from_peer(
+                    &node_a_peer_id,
+                    Message::Block(Box::new(BlockMessage::Header(block.content.header.clone()))),
+                )
+                .unwrap();
+            protocol_controller
+                .send_wishlist_delta(
+                    vec![(block.id, Some(block.content.header.clone()))]
+                        .into_iter()
+                        .collect(),
+                    PreHashSet::<BlockId>::default(),
+                )
+                .unwrap();
+
+            //3. Node A is asked for the operation IDs, then for the operations
+            assert!(matches!(
+                assert_hash_asked_to_node(&node_a, &block.id),
+                AskForBlockInfo::OperationIds
+            ));
+            network_controller
+                .send_from_peer(
+                    &node_a_peer_id,
+                    Message::Block(Box::new(BlockMessage::DataResponse {
+                        block_id: block.id,
+                        block_info: BlockInfoReply::OperationIds(vec![op_1.id, op_2.id]),
+                    })),
+                )
+                .unwrap();
+            assert!(matches!(
+                assert_hash_asked_to_node(&node_a, &block.id),
+                AskForBlockInfo::Operations(_)
+            ));
+
+            //4. Node A only sends one of the operations
+            network_controller
+                .send_from_peer(
+                    &node_a_peer_id,
+                    Message::Block(Box::new(BlockMessage::DataResponse {
+                        block_id: block.id,
+                        block_info: BlockInfoReply::Operations(vec![op_1]),
+                    })),
+                )
+                .unwrap();
+
+            //5. Only the missing operation is asked to node B
+            match assert_hash_asked_to_node(&node_b, &block.id) {
+                AskForBlockInfo::Operations(operations) => {
+                    assert_eq!(operations, vec![op_2.id]);
+                }
+                _ => panic!("Node B wasn't asked for the missing operation"),
+            }
+
+            //6. Node B sends it and the block is complete
+            network_controller
+                .send_from_peer(
+                    &node_b_peer_id,
+                    Message::Block(Box::new(BlockMessage::DataResponse {
+                        block_id: block.id,
+                        block_info: BlockInfoReply::Operations(vec![op_2]),
+                    })),
+                )
+                .unwrap();
+        },
+    )
+}
+
 #[test]
 fn test_empty_block() {
     let default_panic = std::panic::take_hook();
@@ -24,6 +24,7 @@ mod in_block_operations_scenarios;
 mod mock_network;
 mod operations_scenarios;
 mod peer_priorization;
+mod sig_verifier;
 mod tools;
 
 #[test]
@@ -0,0 +1,30 @@
+use massa_protocol_exports::test_exports::tools::create_block;
+use massa_signature::KeyPair;
+
+use crate::sig_verifier::find_verified_headers;
+
+#[test]
+fn test_find_verified_headers() {
+    let mut headers: Vec<_> = (0..7)
+        .map(|_| create_block(&KeyPair::generate(0).unwrap()).content.header)
+        .collect();
+    // the signatures of two headers are swapped: both fail to verify
+    let signature = headers[2].signature;
+    headers[2].signature = headers[5].signature;
+    headers[5].signature = signature;
+
+    let header_refs: Vec<_> = headers.iter().collect();
+    let verified: Vec<_> = find_verified_headers(&header_refs)
+        .into_iter()
+        .map(|header| header.id)
+        .collect();
+    let expected: Vec<_> = headers
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != 2 && *index != 5)
+        .map(|(_, header)| header.id)
+        .collect();
+    assert_eq!(verified, expected);
+
+    assert!(find_verified_headers(&[]).is_empty());
+}
@@ -188,7 +188,7 @@ pub fn start_protocol_controller(
     massa_metrics: MassaMetrics,
 ) -> Result<(Box<dyn ProtocolManager>, KeyPair, NodeId), ProtocolError> {
     debug!("starting protocol controller");
-    let peer_db = Arc::new(RwLock::new(PeerDB::default()));
+    let peer_db = Arc::new(RwLock::new(PeerDB::load(&config.peer_store_file)));
 
     let (sender_operations, receiver_operations) = MassaChannel::new(
         "sender_operations".to_string(),
@@ -283,20 +283,10 @@ pub fn start_protocol_controller(
                 (
                     initial_peers_infos
                         .iter()
-                        .filter_map(|info| {
-                            if info.1.category == *category_name {
-                                //TODO: Adapt for multiple listeners
-                                Some(
-                                    info.1
-                                        .listeners
-                                        .iter()
-                                        .next()
-                                        .map(|addr| to_canonical(addr.0.ip()))
-                                        .unwrap(),
-                                )
-                            } else {
-                                None
-                            }
+                        .filter(|info| info.1.category == *category_name)
+                        // dual-stack peers have a listener per IP family
+                        .flat_map(|info| {
+                            info.1.listeners.keys().map(|addr| to_canonical(addr.ip()))
                         })
                         .collect(),
                     PeerNetCategoryInfo {
@@ -344,20 +334,9 @@ pub fn start_protocol_controller(
                     (
                         initial_peers_infos
                             .iter()
-                            .filter_map(|info| {
-                                if info.1.category == *category_name {
-                                    //TODO: Adapt for multiple listeners
-                                    Some(
-                                        info.1
-                                            .listeners
-                                            .iter()
-                                            .next()
-                                            .map(|addr| to_canonical(addr.0.ip()))
-                                            .unwrap(),
-                                    )
-                                } else {
-                                    None
-                                }
+                            .filter(|info| info.1.category == *category_name)
+                            .flat_map(|info| {
+                                info.1.listeners.keys().map(|addr| to_canonical(addr.ip()))
                             })
                             .collect(),
                         *infos,
@@ -17,13 +17,29 @@ use jsonrpsee_ws_client as _;
 use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_api_exports::{
-    address::AddressInfo,
-    block::{BlockInfo, BlockSummary},
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    address::{AddressDeferredCredits, AddressInfo, AddressProductionStats},
+    block::{
+        BlockGraphFormat, BlockInfo, BlockSummary, DiscardedBlockInfo, DiscardedBlocksFilter,
+        ExportedBlockGraph,
+    },
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeysInput, DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        AbiCallProfile, AddressTransfer, AsyncPoolFilter, AsyncPoolMessageInfo,
+        BytecodeValidationResponse, CacheSizes, CacheSizesUpdate, EventsTruncation,
+        ExecuteReadOnlyResponse, GasEstimationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{BootstrapSessionAudit, DbIntegrityReport, NodeStatus, PeerStoreEntry},
     operation::{OperationInfo, OperationInput},
+    page::PageRequest,
+    pool::{
+        AdmissionStageInfo, DenunciationPoolItem, FeeRecommendationInfo, PendingOperation,
+        PoolSummary,
+    },
+    rolls::StakersStatistics,
+    versioning::MipStatusInfo,
     TimeInterval,
 };
 use massa_models::secure_share::SecureShare;
@@ -32,18 +48,21 @@ use massa_models::{
     block::FilledBlock,
     block_header::BlockHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{BlockcliqueReorg, Clique},
     composite::PubkeySig,
     endorsement::EndorsementId,
-    execution::EventFilter,
+    execution::{EventFilter, TransferFilter},
     node::NodeId,
-    operation::{Operation, OperationId},
+    operation::{Operation, OperationId, OperationLifecycleEvent, OperationLifecycleFilter},
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+    stats::ThroughputBucket,
     version::Version,
 };
 use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
 use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_time::MassaTime;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use thiserror::Error;
@@ -221,6 +240,32 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns the persisted trust state of the peers, lowest scores first
+    pub async fn node_get_peer_store(&self) -> RpcResult<Vec<PeerStoreEntry>> {
+        self.http_client
+            .request("node_get_peer_store", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Overwrite the score of given node id(s)
+    /// No confirmation to expect.
+    pub async fn node_set_peer_scores(&self, scores: Vec<(NodeId, i64)>) -> RpcResult<()> {
+        self.http_client
+            .request("node_set_peer_scores", rpc_params![scores])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Forget everything persisted about given node id(s), lifting their ban
+    /// No confirmation to expect.
+    pub async fn node_forget_peers(&self, ids: Vec<NodeId>) -> RpcResult<()> {
+        self.http_client
+            .request("node_forget_peers", rpc_params![ids])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns node peers whitelist IP address(es).
     pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         self.http_client
@@ -302,6 +347,137 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns the last entries of the audit log of the bootstrap server, the most recent last
+    pub async fn node_bootstrap_audit_log(
+        &self,
+        count: Option<usize>,
+    ) -> RpcResult<Vec<BootstrapSessionAudit>> {
+        self.http_client
+            .request("node_bootstrap_audit_log", rpc_params![count])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Exports the block graph of the node, as a structured snapshot by default
+    pub async fn node_export_block_graph(
+        &self,
+        format: Option<BlockGraphFormat>,
+    ) -> RpcResult<ExportedBlockGraph> {
+        self.http_client
+            .request("node_export_block_graph", rpc_params![format])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the discarded blocks kept by the node, optionally filtered by reason and creator
+    pub async fn node_get_discarded_blocks(
+        &self,
+        filter: Option<DiscardedBlocksFilter>,
+    ) -> RpcResult<Vec<DiscardedBlockInfo>> {
+        self.http_client
+            .request("node_get_discarded_blocks", rpc_params![filter])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Recomputes the final state hash from the database and reports the invalid entries
+    pub async fn node_verify_db_integrity(&self) -> RpcResult<DbIntegrityReport> {
+        self.http_client
+            .request("node_verify_db_integrity", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Starts compacting a whole column family of the final state database in the background
+    pub async fn node_trigger_compaction(&self, cf: String) -> RpcResult<()> {
+        self.http_client
+            .request("node_trigger_compaction", rpc_params![cf])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Gets the production stats of the given addresses for each of the last `cycle_count` cycles
+    pub async fn node_get_production_stats(
+        &self,
+        addresses: Vec<Address>,
+        cycle_count: u64,
+    ) -> RpcResult<Vec<AddressProductionStats>> {
+        self.http_client
+            .request(
+                "node_get_production_stats",
+                rpc_params![addresses, cycle_count],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Gets the call count and time of each host ABI function over the last `slot_count` executed slots
+    pub async fn node_get_abi_profile(&self, slot_count: usize) -> RpcResult<Vec<AbiCallProfile>> {
+        self.http_client
+            .request("node_get_abi_profile", rpc_params![slot_count])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Gets the current sizes of the execution caches
+    pub async fn node_get_cache_sizes(&self) -> RpcResult<CacheSizes> {
+        self.http_client
+            .request("node_get_cache_sizes", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Resizes the execution caches, the omitted sizes being left unchanged
+    pub async fn node_set_cache_sizes(&self, update: CacheSizesUpdate) -> RpcResult<CacheSizes> {
+        self.http_client
+            .request("node_set_cache_sizes", rpc_params![update])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Gets the pending operations of the pool, from the highest to the lowest priority,
+    /// optionally restricted to the operations created by an address
+    pub async fn node_get_pending_operations(
+        &self,
+        address: Option<Address>,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<PendingOperation>> {
+        self.http_client
+            .request(
+                "node_get_pending_operations",
+                rpc_params![address, page_request],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Gets the summary of the operation pool, with the `top_senders` addresses with the most pending operations
+    pub async fn node_get_operation_pool_summary(
+        &self,
+        top_senders: usize,
+    ) -> RpcResult<PoolSummary> {
+        self.http_client
+            .request("node_get_operation_pool_summary", rpc_params![top_senders])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Gets the entries of the denunciation pool and the progress of their denunciation
+    pub async fn node_get_denunciation_pool(&self) -> RpcResult<Vec<DenunciationPoolItem>> {
+        self.http_client
+            .request("node_get_denunciation_pool", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Gets the statistics of each stage of the operation admission pipeline
+    pub async fn node_get_operation_admission_stats(&self) -> RpcResult<Vec<AdmissionStageInfo>> {
+        self.http_client
+            .request("node_get_operation_admission_stats", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     ////////////////
     // public-api //
     ////////////////
@@ -316,6 +492,45 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// get the status of every MIP known by the node, with its vote ratio
+    pub async fn get_mip_status(&self) -> RpcResult<Vec<MipStatusInfo>> {
+        self.http_client
+            .request("get_mip_status", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// get the MIPs that are new or whose state changed since a previous poll
+    pub async fn get_versioning_announcements(
+        &self,
+        previous: Vec<MipStatusInfo>,
+    ) -> RpcResult<Vec<MipStatusInfo>> {
+        self.http_client
+            .request("get_versioning_announcements", rpc_params![previous])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// minimal fee accepted by the operation pool and fee likely to get an operation included in the next blocks
+    pub async fn get_fee_recommendation(&self) -> RpcResult<FeeRecommendationInfo> {
+        self.http_client
+            .request("get_fee_recommendation", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// number of final executed operations over the last `window`, split in buckets of `resolution`
+    pub async fn get_throughput_history(
+        &self,
+        window: MassaTime,
+        resolution: MassaTime,
+    ) -> RpcResult<Vec<ThroughputBucket>> {
+        self.http_client
+            .request("get_throughput_history", rpc_params![window, resolution])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     pub(crate) async fn _get_cliques(&self) -> RpcResult<Vec<Clique>> {
         self.http_client
             .request("get_cliques", rpc_params![])
@@ -333,6 +548,15 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns the roll distribution statistics of every cycle in the final PoS history,
+    /// `top_n` being the number of biggest stakers whose share is computed.
+    pub async fn get_stakers_statistics(&self, top_n: u64) -> RpcResult<Vec<StakersStatistics>> {
+        self.http_client
+            .request("get_stakers_statistics", rpc_params![top_n])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns operation(s) information associated to a given list of operation(s) ID(s).
     pub async fn get_operations(
         &self,
@@ -374,6 +598,51 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get the native coin transfers spending or crediting an address
+    pub async fn get_address_transfers(
+        &self,
+        filter: TransferFilter,
+    ) -> RpcResult<Vec<AddressTransfer>> {
+        self.http_client
+            .request("get_address_transfers", rpc_params![filter])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the native coin transfers that happened between two slots (inclusive)
+    pub async fn get_slot_transfers(
+        &self,
+        start: Slot,
+        end: Slot,
+    ) -> RpcResult<Vec<AddressTransfer>> {
+        self.http_client
+            .request("get_slot_transfers", rpc_params![start, end])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the messages of the asynchronous pool matching a filter
+    pub async fn get_async_messages(
+        &self,
+        filter: AsyncPoolFilter,
+    ) -> RpcResult<Vec<AsyncPoolMessageInfo>> {
+        self.http_client
+            .request("get_async_messages", rpc_params![filter])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Tell whether final events matching the filter may have been pruned by the node
+    pub async fn get_sc_output_events_truncation(
+        &self,
+        filter: EventFilter,
+    ) -> RpcResult<EventsTruncation> {
+        self.http_client
+            .request("get_sc_output_events_truncation", rpc_params![filter])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get the block graph within the specified time interval.
     /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
     pub(crate) async fn _get_graph_interval(
@@ -394,6 +663,22 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// get the deferred credits of an address whose slot is between `start_slot` and `end_slot` (both included)
+    pub async fn get_deferred_credits(
+        &self,
+        address: Address,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> RpcResult<AddressDeferredCredits> {
+        self.http_client
+            .request(
+                "get_deferred_credits",
+                rpc_params![address, start_slot, end_slot],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get datastore entries
     pub async fn get_datastore_entries(
         &self,
@@ -405,6 +690,17 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Gets pages of datastore keys, selected by prefix and key range
+    pub async fn get_datastore_keys(
+        &self,
+        input: Vec<DatastoreKeysInput>,
+    ) -> RpcResult<Vec<DatastoreKeysOutput>> {
+        self.http_client
+            .request("get_datastore_keys", rpc_params![input])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     // User (interaction with the node)
 
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
@@ -453,6 +749,68 @@ impl RpcClient {
                 to_error_obj("missing return value on execute_read_only_call".to_owned())
             })
     }
+
+    /// execute a batch of read only SC calls against the same state
+    pub async fn execute_read_only_call_batch(
+        &self,
+        read_only_calls: Vec<ReadOnlyCall>,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
+        self.http_client
+            .request::<Vec<ExecuteReadOnlyResponse>, Vec<Vec<ReadOnlyCall>>>(
+                "execute_read_only_call_batch",
+                vec![read_only_calls],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// estimate the `max_gas` needed by a bytecode execution
+    pub async fn estimate_gas_bytecode(
+        &self,
+        read_only_execution: ReadOnlyBytecodeExecution,
+    ) -> RpcResult<GasEstimationResponse> {
+        self.http_client
+            .request::<Vec<GasEstimationResponse>, Vec<Vec<ReadOnlyBytecodeExecution>>>(
+                "estimate_gas_bytecode",
+                vec![vec![read_only_execution]],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))?
+            .pop()
+            .ok_or_else(|| to_error_obj("missing return value on estimate_gas_bytecode".to_owned()))
+    }
+
+    /// estimate the `max_gas` needed by a SC call
+    pub async fn estimate_gas_call(
+        &self,
+        read_only_execution: ReadOnlyCall,
+    ) -> RpcResult<GasEstimationResponse> {
+        self.http_client
+            .request::<Vec<GasEstimationResponse>, Vec<Vec<ReadOnlyCall>>>(
+                "estimate_gas_call",
+                vec![vec![read_only_execution]],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))?
+            .pop()
+            .ok_or_else(|| to_error_obj("missing return value on estimate_gas_call".to_owned()))
+    }
+
+    /// check a bytecode against the limits of the execution engine without executing it
+    pub async fn validate_bytecode(
+        &self,
+        bytecode: Vec<u8>,
+    ) -> RpcResult<BytecodeValidationResponse> {
+        self.http_client
+            .request::<Vec<BytecodeValidationResponse>, Vec<Vec<Vec<u8>>>>(
+                "validate_bytecode",
+                vec![vec![bytecode]],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))?
+            .pop()
+            .ok_or_else(|| to_error_obj("missing return value on validate_bytecode".to_owned()))
+    }
 }
 
 /// Client V2
@@ -610,6 +968,23 @@ impl RpcClientV2 {
         }
     }
 
+    /// Blockclique changes that demoted non-final blocks of the previous blockclique.
+    pub async fn subscribe_blockclique_reorgs(
+        &self,
+    ) -> Result<Subscription<BlockcliqueReorg>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_blockclique_reorgs",
+                    rpc_params![],
+                    "unsubscribe_blockclique_reorgs",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
+
     /// New produced operations.
     pub async fn subscribe_new_operations(
         &self,
@@ -626,6 +1001,25 @@ impl RpcClientV2 {
             Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
         }
     }
+
+    /// Pending operations leaving the pool because they were included in a block, expired or were evicted,
+    /// restricted to the operations and senders of the filter if any.
+    pub async fn subscribe_operation_lifecycle(
+        &self,
+        filter: Option<OperationLifecycleFilter>,
+    ) -> Result<Subscription<OperationLifecycleEvent>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_operation_lifecycle",
+                    rpc_params![filter],
+                    "unsubscribe_operation_lifecycle",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
 }
 
 fn http_client_from_url(url: &str, http_config: &HttpConfig) -> HttpClient<HttpBackend> {
@@ -5,12 +5,17 @@
 //!
 //! The `Storage` structure also has lists of object references held by the current instance of `Storage`.
 //! When no instance of `Storage` claims a reference to a given object anymore, that object is automatically removed from storage.
+//!
+//! Each instance of `Storage` belongs to an owner (typically the module it was given to),
+//! and the numbers of references held by each owner can be queried with `Storage::get_usage`.
+//! In claim tracking mode, the source code location of each claim is also recorded to help finding leaks.
 
 #![warn(missing_docs)]
 
 mod block_indexes;
 mod endorsement_indexes;
 mod operation_indexes;
+mod usage;
 
 #[cfg(test)]
 mod tests;
@@ -26,10 +31,17 @@ use massa_models::{
     operation::{OperationId, SecureShareOperation},
 };
 use operation_indexes::OperationIndexes;
-use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::panic::Location;
 use std::{collections::hash_map, sync::Arc};
+use usage::{
+    record_claims, remove_claims, set_claims_owner, ClaimRecords, ClaimTracking, OwnerCounters,
+    StorageUsage,
+};
+pub use usage::{StorageClaim, StorageClaims, StorageOwnerUsage, DEFAULT_STORAGE_OWNER};
 
 /// A storage system for objects (blocks, operations...), shared by various components.
 pub struct Storage {
@@ -53,6 +65,17 @@ pub struct Storage {
     local_used_ops: PreHashSet<OperationId>,
     /// locally used endorsement references
     local_used_endorsements: PreHashSet<EndorsementId>,
+
+    /// global reference accounting
+    usage: Arc<StorageUsage>,
+    /// owner of the local references
+    owner: String,
+    /// reference counters of the owner
+    owner_counters: Arc<OwnerCounters>,
+    /// identifier of this instance in the claim records
+    instance_id: u64,
+    /// local references currently accounted in the counters of the owner
+    counted_usage: StorageOwnerUsage,
 }
 
 impl Debug for Storage {
@@ -63,6 +86,7 @@ impl Debug for Storage {
 }
 
 impl Clone for Storage {
+    #[track_caller]
     fn clone(&self) -> Self {
         let mut res = Self::clone_without_refs(self);
 
@@ -87,6 +111,11 @@ impl Clone for Storage {
             &mut res.local_used_endorsements,
         );
 
+        res.record_claims(|t| &t.blocks, &self.local_used_blocks);
+        res.record_claims(|t| &t.operations, &self.local_used_ops);
+        res.record_claims(|t| &t.endorsements, &self.local_used_endorsements);
+        res.update_usage();
+
         res
     }
 }
@@ -97,7 +126,24 @@ impl Storage {
     /// - At the top of the test in tests
     /// All others instances of Storage must be cloned from this one using `clone()` or `clone_without_refs()`.
     pub fn create_root() -> Storage {
+        Storage::new_root(false)
+    }
+
+    /// Creates a new root `Storage` instance (see `create_root`) that also records
+    /// where each reference is claimed, so that `get_claims` can report which code keeps objects alive.
+    /// This has a significant cost and is meant for debugging only.
+    pub fn create_root_with_claim_tracking() -> Storage {
+        Storage::new_root(true)
+    }
+
+    fn new_root(track_claims: bool) -> Storage {
+        let usage = Arc::new(StorageUsage::new(track_claims));
         Storage {
+            owner: DEFAULT_STORAGE_OWNER.to_string(),
+            owner_counters: usage.owner_counters(DEFAULT_STORAGE_OWNER),
+            instance_id: usage.new_instance_id(),
+            usage,
+            counted_usage: Default::default(),
             blocks: Default::default(),
             operations: Default::default(),
             endorsements: Default::default(),
@@ -125,28 +171,147 @@ impl Storage {
             local_used_ops: Default::default(),
             local_used_blocks: Default::default(),
             local_used_endorsements: Default::default(),
+
+            usage: self.usage.clone(),
+            owner: self.owner.clone(),
+            owner_counters: self.owner_counters.clone(),
+            instance_id: self.usage.new_instance_id(),
+            counted_usage: Default::default(),
+        }
+    }
+
+    /// Sets the owner of the references held by this instance and by its future clones,
+    /// typically the name of the module it is given to.
+    pub fn with_owner(mut self, owner: &str) -> Self {
+        let new_counters = self.usage.owner_counters(owner);
+        self.owner_counters
+            .update(&self.counted_usage, &Default::default());
+        new_counters.update(&Default::default(), &self.counted_usage);
+        self.owner_counters = new_counters;
+        self.owner = owner.to_string();
+        if let Some(tracking) = &self.usage.tracking {
+            set_claims_owner(
+                &tracking.blocks,
+                &self.local_used_blocks,
+                self.instance_id,
+                owner,
+            );
+            set_claims_owner(
+                &tracking.operations,
+                &self.local_used_ops,
+                self.instance_id,
+                owner,
+            );
+            set_claims_owner(
+                &tracking.endorsements,
+                &self.local_used_endorsements,
+                self.instance_id,
+                owner,
+            );
+        }
+        self
+    }
+
+    /// get the owner of the references held by this instance
+    pub fn get_owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// Gets the numbers of references held by the instances of each owner
+    pub fn get_usage(&self) -> BTreeMap<String, StorageOwnerUsage> {
+        self.usage.get_usage()
+    }
+
+    /// Gets, for each object still referenced, where its references were claimed.
+    /// Returns `None` if the root was not created with `create_root_with_claim_tracking`.
+    pub fn get_claims(&self) -> Option<StorageClaims> {
+        self.usage.get_claims()
+    }
+
+    /// internal helper to account for the changes of the local references in the counters of the owner
+    fn update_usage(&mut self) {
+        let current = StorageOwnerUsage {
+            blocks: self.local_used_blocks.len(),
+            operations: self.local_used_ops.len(),
+            endorsements: self.local_used_endorsements.len(),
+        };
+        self.owner_counters.update(&self.counted_usage, &current);
+        self.counted_usage = current;
+    }
+
+    /// internal helper to record the location of the claims of local references, if claim tracking is enabled
+    #[track_caller]
+    fn record_claims<'a, IdT: 'a + Eq + Hash + PreHashed + Copy>(
+        &self,
+        records: fn(&ClaimTracking) -> &Mutex<ClaimRecords<IdT>>,
+        ids: impl IntoIterator<Item = &'a IdT>,
+    ) {
+        if let Some(tracking) = &self.usage.tracking {
+            let claim = StorageClaim {
+                owner: self.owner.clone(),
+                location: Location::caller(),
+            };
+            record_claims(records(tracking), ids, self.instance_id, &claim);
+        }
+    }
+
+    /// internal helper to forget the claims of dropped local references, if claim tracking is enabled
+    fn remove_claims<'a, IdT: 'a + Eq + Hash + PreHashed + Copy>(
+        &self,
+        records: fn(&ClaimTracking) -> &Mutex<ClaimRecords<IdT>>,
+        ids: impl IntoIterator<Item = &'a IdT>,
+    ) {
+        if let Some(tracking) = &self.usage.tracking {
+            remove_claims(records(tracking), ids, self.instance_id);
         }
     }
 
     /// Efficiently extends the current Storage by consuming the refs of another storage.
+    #[track_caller]
     pub fn extend(&mut self, mut other: Storage) {
         // Take ownership ot `other`'s references.
         // Objects owned by both require a counter decrement and are handled when `other` is dropped.
-        other
-            .local_used_ops
-            .retain(|id| !self.local_used_ops.insert(*id));
-
-        other
-            .local_used_blocks
-            .retain(|id| !self.local_used_blocks.insert(*id));
-
-        other
-            .local_used_endorsements
-            .retain(|id| !self.local_used_endorsements.insert(*id));
+        let mut taken_ops = Vec::new();
+        other.local_used_ops.retain(|id| {
+            let taken = self.local_used_ops.insert(*id);
+            if taken {
+                taken_ops.push(*id);
+            }
+            !taken
+        });
+
+        let mut taken_blocks = Vec::new();
+        other.local_used_blocks.retain(|id| {
+            let taken = self.local_used_blocks.insert(*id);
+            if taken {
+                taken_blocks.push(*id);
+            }
+            !taken
+        });
+
+        let mut taken_endorsements = Vec::new();
+        other.local_used_endorsements.retain(|id| {
+            let taken = self.local_used_endorsements.insert(*id);
+            if taken {
+                taken_endorsements.push(*id);
+            }
+            !taken
+        });
+
+        // transfer the accounting of the taken references
+        other.remove_claims(|t| &t.operations, &taken_ops);
+        other.remove_claims(|t| &t.blocks, &taken_blocks);
+        other.remove_claims(|t| &t.endorsements, &taken_endorsements);
+        other.update_usage();
+        self.record_claims(|t| &t.operations, &taken_ops);
+        self.record_claims(|t| &t.blocks, &taken_blocks);
+        self.record_claims(|t| &t.endorsements, &taken_endorsements);
+        self.update_usage();
     }
 
     /// Efficiently splits off a subset of the reference ownership into a new Storage object.
     /// Panics if some of the refs are not owned by the source.
+    #[track_caller]
     pub fn split_off(
         &mut self,
         blocks: &PreHashSet<BlockId>,
@@ -186,6 +351,16 @@ impl Storage {
             })
             .collect();
 
+        // transfer the accounting of the split references
+        self.remove_claims(|t| &t.blocks, blocks);
+        self.remove_claims(|t| &t.operations, operations);
+        self.remove_claims(|t| &t.endorsements, endorsements);
+        self.update_usage();
+        res.record_claims(|t| &t.blocks, blocks);
+        res.record_claims(|t| &t.operations, operations);
+        res.record_claims(|t| &t.endorsements, endorsements);
+        res.update_usage();
+
         res
     }
 
@@ -209,6 +384,7 @@ impl Storage {
 
     /// Claim block references.
     /// Returns the set of block refs that were found and claimed.
+    #[track_caller]
     pub fn claim_block_refs(&mut self, ids: &PreHashSet<BlockId>) -> PreHashSet<BlockId> {
         let mut claimed = PreHashSet::with_capacity(ids.len());
 
@@ -216,13 +392,16 @@ impl Storage {
             return claimed;
         }
 
-        let owners = &mut self.block_owners.write();
+        let mut owners = self.block_owners.write();
 
         // check that all IDs are owned
         claimed.extend(ids.iter().filter(|id| owners.contains_key(id)));
 
         // effectively add local ownership on the refs
-        Storage::internal_claim_refs(&claimed, owners, &mut self.local_used_blocks);
+        Storage::internal_claim_refs(&claimed, &mut owners, &mut self.local_used_blocks);
+        drop(owners);
+        self.record_claims(|t| &t.blocks, &claimed);
+        self.update_usage();
 
         claimed
     }
@@ -258,6 +437,9 @@ impl Storage {
                 }
             }
         }
+        drop(owners);
+        self.remove_claims(|t| &t.blocks, ids);
+        self.update_usage();
         // if there are orphaned objects, remove them from storage
         if !orphaned_ids.is_empty() {
             let mut blocks = self.blocks.write();
@@ -269,6 +451,7 @@ impl Storage {
 
     /// Store a block
     /// Note that this also claims a local reference to the block
+    #[track_caller]
     pub fn store_block(&mut self, block: SecureShareBlock) {
         let id = block.id;
         let mut owners = self.block_owners.write();
@@ -280,10 +463,15 @@ impl Storage {
             &mut owners,
             &mut self.local_used_blocks,
         );
+        drop(blocks);
+        drop(owners);
+        self.record_claims(|t| &t.blocks, [&id]);
+        self.update_usage();
     }
 
     /// Claim operation references.
     /// Returns the set of operation refs that were found and claimed.
+    #[track_caller]
     pub fn claim_operation_refs(
         &mut self,
         ids: &PreHashSet<OperationId>,
@@ -294,13 +482,16 @@ impl Storage {
             return claimed;
         }
 
-        let owners = &mut self.operation_owners.write();
+        let mut owners = self.operation_owners.write();
 
         // check that all IDs are owned
         claimed.extend(ids.iter().filter(|id| owners.contains_key(id)));
 
         // effectively add local ownership on the refs
-        Storage::internal_claim_refs(&claimed, owners, &mut self.local_used_ops);
+        Storage::internal_claim_refs(&claimed, &mut owners, &mut self.local_used_ops);
+        drop(owners);
+        self.record_claims(|t| &t.operations, &claimed);
+        self.update_usage();
 
         claimed
     }
@@ -342,6 +533,9 @@ impl Storage {
                 }
             }
         }
+        drop(owners);
+        self.remove_claims(|t| &t.operations, ids);
+        self.update_usage();
         // if there are orphaned objects, remove them from storage
         if !orphaned_ids.is_empty() {
             let mut ops = self.operations.write();
@@ -353,6 +547,7 @@ impl Storage {
 
     /// Store operations
     /// Claims a local reference to the added operation
+    #[track_caller]
     pub fn store_operations(&mut self, operations: Vec<SecureShareOperation>) {
         if operations.is_empty() {
             return;
@@ -364,6 +559,10 @@ impl Storage {
             op_store.insert(op);
         }
         Storage::internal_claim_refs(&ids, &mut owners, &mut self.local_used_ops);
+        drop(op_store);
+        drop(owners);
+        self.record_claims(|t| &t.operations, &ids);
+        self.update_usage();
     }
 
     /// Gets a read reference to the operations index
@@ -383,6 +582,7 @@ impl Storage {
 
     /// Claim endorsement references.
     /// Returns the set of operation refs that were found and claimed.
+    #[track_caller]
     pub fn claim_endorsement_refs(
         &mut self,
         ids: &PreHashSet<EndorsementId>,
@@ -393,13 +593,16 @@ impl Storage {
             return claimed;
         }
 
-        let owners = &mut self.endorsement_owners.write();
+        let mut owners = self.endorsement_owners.write();
 
         // check that all IDs are owned
         claimed.extend(ids.iter().filter(|id| owners.contains_key(id)));
 
         // effectively add local ownership on the refs
-        Storage::internal_claim_refs(&claimed, owners, &mut self.local_used_endorsements);
+        Storage::internal_claim_refs(&claimed, &mut owners, &mut self.local_used_endorsements);
+        drop(owners);
+        self.record_claims(|t| &t.endorsements, &claimed);
+        self.update_usage();
         claimed
     }
 
@@ -440,6 +643,9 @@ impl Storage {
                 }
             }
         }
+        drop(owners);
+        self.remove_claims(|t| &t.endorsements, ids);
+        self.update_usage();
         // if there are orphaned objects, remove them from storage
         if !orphaned_ids.is_empty() {
             let mut endos = self.endorsements.write();
@@ -451,6 +657,7 @@ impl Storage {
 
     /// Store endorsements
     /// Claims local references to the added endorsements
+    #[track_caller]
     pub fn store_endorsements(&mut self, endorsements: Vec<SecureShareEndorsement>) {
         if endorsements.is_empty() {
             return;
@@ -462,6 +669,10 @@ impl Storage {
             endo_store.insert(endorsement);
         }
         Storage::internal_claim_refs(&ids, &mut owners, &mut self.local_used_endorsements);
+        drop(endo_store);
+        drop(owners);
+        self.record_claims(|t| &t.endorsements, &ids);
+        self.update_usage();
     }
 }
 
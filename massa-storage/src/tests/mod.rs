@@ -1,3 +1,4 @@
 mod basic;
 mod indexes;
 mod references;
+mod usage;
@@ -0,0 +1,60 @@
+use crate::{Storage, StorageOwnerUsage, DEFAULT_STORAGE_OWNER};
+use massa_factory_exports::test_exports::create_empty_block;
+use massa_models::{prehash::PreHashSet, slot::Slot};
+use massa_signature::KeyPair;
+
+#[test]
+fn test_usage_per_owner() {
+    let mut storage = Storage::create_root();
+    let block = create_empty_block(&KeyPair::generate(0).unwrap(), &Slot::new(0, 0));
+    storage.store_block(block.clone());
+
+    let consensus_storage = storage.clone().with_owner("consensus");
+    let mut protocol_storage = storage.clone_without_refs().with_owner("protocol");
+    let usage = storage.get_usage();
+    assert_eq!(
+        usage[DEFAULT_STORAGE_OWNER],
+        StorageOwnerUsage {
+            blocks: 1,
+            ..Default::default()
+        }
+    );
+    assert_eq!(usage["consensus"].blocks, 1);
+    assert_eq!(usage["protocol"].blocks, 0);
+
+    // references moved between instances are accounted to their new owner
+    let mut ids = PreHashSet::default();
+    ids.insert(block.id);
+    protocol_storage.extend(storage.split_off(&ids, &Default::default(), &Default::default()));
+    let usage = consensus_storage.get_usage();
+    assert_eq!(usage[DEFAULT_STORAGE_OWNER].blocks, 0);
+    assert_eq!(usage["protocol"].blocks, 1);
+
+    drop(protocol_storage);
+    drop(consensus_storage);
+    assert_eq!(storage.get_usage()["consensus"].blocks, 0);
+    assert!(storage.read_blocks().get(&block.id).is_none());
+}
+
+#[test]
+fn test_claim_tracking() {
+    let mut storage = Storage::create_root();
+    let block = create_empty_block(&KeyPair::generate(0).unwrap(), &Slot::new(0, 0));
+    storage.store_block(block.clone());
+    assert!(storage.get_claims().is_none());
+
+    let mut storage = Storage::create_root_with_claim_tracking();
+    let observer = storage.clone_without_refs();
+    storage.store_block(block.clone());
+    let consensus_storage = storage.clone().with_owner("consensus");
+    drop(storage);
+
+    let claims = consensus_storage.get_claims().unwrap();
+    let block_claims = &claims.blocks[&block.id];
+    assert_eq!(block_claims.len(), 1);
+    assert_eq!(block_claims[0].owner, "consensus");
+    assert_eq!(block_claims[0].location.file(), file!());
+
+    drop(consensus_storage);
+    assert!(observer.get_claims().unwrap().blocks.is_empty());
+}
@@ -0,0 +1,212 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//!
+//! Accounting of the references held by the `Storage` instances of each owner (module),
+//! and optional tracking of where each reference still held was claimed,
+//! to find out which module keeps objects alive.
+
+use massa_models::prehash::{PreHashMap, PreHashed};
+use massa_models::{block_id::BlockId, endorsement::EndorsementId, operation::OperationId};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::panic::Location;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Owner of the `Storage` created by `Storage::create_root`, inherited by its clones
+pub const DEFAULT_STORAGE_OWNER: &str = "root";
+
+/// Numbers of object references held by `Storage` instances
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageOwnerUsage {
+    /// number of block references
+    pub blocks: usize,
+    /// number of operation references
+    pub operations: usize,
+    /// number of endorsement references
+    pub endorsements: usize,
+}
+
+/// Where a reference still held was claimed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageClaim {
+    /// owner of the `Storage` instance holding the reference
+    pub owner: String,
+    /// source code location of the claim (store, claim, clone, extend or split)
+    pub location: &'static Location<'static>,
+}
+
+/// References held by the `Storage` instances, per object, recorded in claim tracking mode
+#[derive(Debug, Clone, Default)]
+pub struct StorageClaims {
+    /// claims of the stored blocks
+    pub blocks: PreHashMap<BlockId, Vec<StorageClaim>>,
+    /// claims of the stored operations
+    pub operations: PreHashMap<OperationId, Vec<StorageClaim>>,
+    /// claims of the stored endorsements
+    pub endorsements: PreHashMap<EndorsementId, Vec<StorageClaim>>,
+}
+
+/// Reference counters of an owner, shared by its `Storage` instances
+#[derive(Debug, Default)]
+pub(crate) struct OwnerCounters {
+    blocks: AtomicUsize,
+    operations: AtomicUsize,
+    endorsements: AtomicUsize,
+}
+
+impl OwnerCounters {
+    /// Replaces the `before` references of an instance by its `after` references
+    pub(crate) fn update(&self, before: &StorageOwnerUsage, after: &StorageOwnerUsage) {
+        if before == after {
+            return;
+        }
+        // add first so that the counters never go below zero
+        self.blocks.fetch_add(after.blocks, Ordering::Relaxed);
+        self.blocks.fetch_sub(before.blocks, Ordering::Relaxed);
+        self.operations
+            .fetch_add(after.operations, Ordering::Relaxed);
+        self.operations
+            .fetch_sub(before.operations, Ordering::Relaxed);
+        self.endorsements
+            .fetch_add(after.endorsements, Ordering::Relaxed);
+        self.endorsements
+            .fetch_sub(before.endorsements, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> StorageOwnerUsage {
+        StorageOwnerUsage {
+            blocks: self.blocks.load(Ordering::Relaxed),
+            operations: self.operations.load(Ordering::Relaxed),
+            endorsements: self.endorsements.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Claims of the references to objects of a given kind, per object then per `Storage` instance
+pub(crate) type ClaimRecords<IdT> = PreHashMap<IdT, HashMap<u64, StorageClaim>>;
+
+/// Records of the claims of all the references held
+#[derive(Debug, Default)]
+pub(crate) struct ClaimTracking {
+    pub(crate) blocks: Mutex<ClaimRecords<BlockId>>,
+    pub(crate) operations: Mutex<ClaimRecords<OperationId>>,
+    pub(crate) endorsements: Mutex<ClaimRecords<EndorsementId>>,
+}
+
+/// Records that the `instance` claimed references to `ids`
+pub(crate) fn record_claims<'a, IdT: 'a + Eq + Hash + PreHashed + Copy>(
+    records: &Mutex<ClaimRecords<IdT>>,
+    ids: impl IntoIterator<Item = &'a IdT>,
+    instance: u64,
+    claim: &StorageClaim,
+) {
+    let mut records = records.lock();
+    for id in ids {
+        records
+            .entry(*id)
+            .or_default()
+            .insert(instance, claim.clone());
+    }
+}
+
+/// Forgets the claims of `instance` on the references to `ids`
+pub(crate) fn remove_claims<'a, IdT: 'a + Eq + Hash + PreHashed + Copy>(
+    records: &Mutex<ClaimRecords<IdT>>,
+    ids: impl IntoIterator<Item = &'a IdT>,
+    instance: u64,
+) {
+    let mut records = records.lock();
+    for id in ids {
+        if let Some(claims) = records.get_mut(id) {
+            claims.remove(&instance);
+            if claims.is_empty() {
+                records.remove(id);
+            }
+        }
+    }
+}
+
+/// Sets the owner of the claims of `instance` on the references to `ids`
+pub(crate) fn set_claims_owner<'a, IdT: 'a + Eq + Hash + PreHashed + Copy>(
+    records: &Mutex<ClaimRecords<IdT>>,
+    ids: impl IntoIterator<Item = &'a IdT>,
+    instance: u64,
+    owner: &str,
+) {
+    let mut records = records.lock();
+    for id in ids {
+        if let Some(claim) = records
+            .get_mut(id)
+            .and_then(|claims| claims.get_mut(&instance))
+        {
+            claim.owner = owner.to_string();
+        }
+    }
+}
+
+/// Lists the claims of each object
+fn list_claims<IdT: Eq + Hash + PreHashed + Copy>(
+    records: &Mutex<ClaimRecords<IdT>>,
+) -> PreHashMap<IdT, Vec<StorageClaim>> {
+    records
+        .lock()
+        .iter()
+        .map(|(id, claims)| (*id, claims.values().cloned().collect()))
+        .collect()
+}
+
+/// Reference accounting shared by all the `Storage` instances cloned from the same root
+#[derive(Debug, Default)]
+pub(crate) struct StorageUsage {
+    /// counters of each owner
+    owners: RwLock<BTreeMap<String, Arc<OwnerCounters>>>,
+    /// next identifier of a `Storage` instance
+    next_instance_id: AtomicU64,
+    /// claim records, if claim tracking is enabled
+    pub(crate) tracking: Option<ClaimTracking>,
+}
+
+impl StorageUsage {
+    pub(crate) fn new(track_claims: bool) -> Self {
+        StorageUsage {
+            tracking: track_claims.then(ClaimTracking::default),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the counters of `owner`, creating them if needed
+    pub(crate) fn owner_counters(&self, owner: &str) -> Arc<OwnerCounters> {
+        if let Some(counters) = self.owners.read().get(owner) {
+            return counters.clone();
+        }
+        self.owners
+            .write()
+            .entry(owner.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Gets a new `Storage` instance identifier
+    pub(crate) fn new_instance_id(&self) -> u64 {
+        self.next_instance_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Gets the numbers of references held by each owner
+    pub(crate) fn get_usage(&self) -> BTreeMap<String, StorageOwnerUsage> {
+        self.owners
+            .read()
+            .iter()
+            .map(|(owner, counters)| (owner.clone(), counters.get()))
+            .collect()
+    }
+
+    /// Lists the claims of the references held, if claim tracking is enabled
+    pub(crate) fn get_claims(&self) -> Option<StorageClaims> {
+        self.tracking.as_ref().map(|tracking| StorageClaims {
+            blocks: list_claims(&tracking.blocks),
+            operations: list_claims(&tracking.operations),
+            endorsements: list_claims(&tracking.endorsements),
+        })
+    }
+}
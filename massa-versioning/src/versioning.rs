@@ -45,6 +45,10 @@ pub enum MipComponent {
     Block,
     VM,
     FinalStateHashKind,
+    // Roll transfer operations are accepted once this component is active
+    RollTransfer,
+    // Unsatisfying stakers get a grace period before their rolls are sold once this component is active
+    RollSaleGracePeriod,
     #[doc(hidden)]
     #[num_enum(default)]
     __Nonexhaustive,
@@ -550,6 +554,38 @@ impl MipState {
     }
 }
 
+/// Status of a MIP as seen by the MIP store
+#[derive(Clone, Debug, PartialEq)]
+pub struct MipStatus {
+    /// MIP info
+    pub mip_info: MipInfo,
+    /// current state
+    pub state: ComponentStateTypeId,
+    /// ratio of the last considered blocks announcing the MIP version
+    pub vote_ratio: Ratio<u64>,
+    /// when the MIP becomes active (only known once LockedIn)
+    pub activation_at: Option<MassaTime>,
+}
+
+/// Returns the statuses of `current` that are new or whose state changed compared to `previous`.
+/// Vote ratio changes alone are not reported as they change with every block.
+pub fn get_versioning_announcements(
+    previous: &[MipStatus],
+    current: &[MipStatus],
+) -> Vec<MipStatus> {
+    current
+        .iter()
+        .filter(|status| {
+            !previous.iter().any(|prev| {
+                prev.mip_info == status.mip_info
+                    && prev.state == status.state
+                    && prev.activation_at == status.activation_at
+            })
+        })
+        .cloned()
+        .collect()
+}
+
 /// Error returned by MipStateHistory::state_at
 #[allow(missing_docs)]
 #[derive(Error, Debug, PartialEq)]
@@ -673,6 +709,21 @@ impl MipStore {
             .collect()
     }
 
+    /// Retrieve the status of every MIP, including its current vote ratio and activation time
+    pub fn get_mip_status_with_votes(&self) -> Vec<MipStatus> {
+        let guard = self.0.read();
+        guard
+            .store
+            .iter()
+            .map(|(mip_info, mip_state)| MipStatus {
+                mip_info: mip_info.clone(),
+                state: ComponentStateTypeId::from(&mip_state.state),
+                vote_ratio: guard.get_vote_ratio(mip_info.version),
+                activation_at: mip_state.activation_at(mip_info),
+            })
+            .collect()
+    }
+
     // Network restart
     pub fn is_consistent_with_shutdown_period(
         &self,
@@ -731,6 +782,14 @@ impl MipStore {
         guard.extend_from_db(db)
     }
 
+    /// Replace the store by what is written on the disk, e.g. after the versioning was re-synced
+    pub fn reset_from_db(&self, db: ShareableMassaDBController) -> Result<(), ExtendFromDbError> {
+        let mut guard = self.0.write();
+        let store_raw = MipStoreRaw::try_from_db(db, guard.stats.config.clone())?;
+        *guard = store_raw;
+        Ok(())
+    }
+
     pub fn reset_db(&self, db: ShareableMassaDBController) {
         {
             let mut guard = db.write();
@@ -1064,6 +1123,20 @@ impl MipStoreRaw {
         self.advance_states_on_updated_stats(slot_timestamp);
     }
 
+    /// Ratio of the last considered blocks announcing the given network version
+    fn get_vote_ratio(&self, version: u32) -> Ratio<u64> {
+        let block_count_considered = self.stats.config.block_count_considered as u64;
+        if block_count_considered == 0 {
+            return Ratio::zero();
+        }
+        let network_version_count = *self
+            .stats
+            .network_version_counters
+            .get(&version)
+            .unwrap_or(&0);
+        Ratio::new(network_version_count, block_count_considered)
+    }
+
     /// Used internally by `update_network_version_stats`
     fn advance_states_on_updated_stats(&mut self, slot_timestamp: MassaTime) {
         for (mi, state) in self.store.iter_mut() {
@@ -2472,8 +2545,13 @@ mod test {
         let db_config = MassaDBConfig {
             path: temp_dir.path().to_path_buf(),
             max_history_length: 100,
+            max_history_bytes: None,
             max_new_elements: 100,
+            max_stream_batch_bytes: None,
             thread_count: THREAD_COUNT,
+            tuning: Default::default(),
+            backup_retention: Default::default(),
+            archival: Default::default(),
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -2587,6 +2665,12 @@ mod test {
         // println!("st1_raw: {:?}", st1_raw);
         // println!("st2_raw: {:?}", st2_raw);
         assert_eq!(st1_raw, st2_raw);
+
+        // Step 5: a store that diverged from the disk is replaced by it
+        let mip_store_3 = MipStore::try_from(([(mi_2.clone(), ms_2.clone())], mip_stats_config))
+            .expect("Cannot create an empty MIP store");
+        mip_store_3.reset_from_db(db.clone()).unwrap();
+        assert_eq!(mip_store_3.0.read().deref(), st1_raw);
     }
 
     #[test]
@@ -2648,4 +2732,56 @@ mod test {
         assert_eq!(mip_store.stats.network_version_counters.get(&1), Some(&1));
         assert_eq!(mip_store.stats.network_version_counters.get(&2), Some(&1));
     }
+
+    #[test]
+    fn test_mip_status_with_votes() {
+        // Test vote ratios and announcements computed from the MIP store stats
+
+        let genesis_timestamp = MassaTime::from_millis(0);
+        let get_slot_ts =
+            |slot| get_block_slot_timestamp(THREAD_COUNT, T0, genesis_timestamp, slot).unwrap();
+
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: 4,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let timeout = MassaTime::now()
+            .unwrap()
+            .saturating_add(MassaTime::from_millis(50_000));
+        let mi_1 = MipInfo {
+            name: "MIP-0001".to_string(),
+            version: 1,
+            components: BTreeMap::from([(MipComponent::Address, 1)]),
+            start: MassaTime::from_millis(2),
+            timeout,
+            activation_delay: MassaTime::from_millis(100),
+        };
+        let ms_1 = advance_state_until(ComponentState::started(Ratio::zero()), &mi_1);
+        let mip_store_raw =
+            MipStoreRaw::try_from(([(mi_1.clone(), ms_1)], mip_stats_config)).unwrap();
+        let mut mip_store = MipStore(Arc::new(RwLock::new(mip_store_raw)));
+
+        let statuses_0 = mip_store.get_mip_status_with_votes();
+        assert_eq!(statuses_0.len(), 1);
+        assert_eq!(statuses_0[0].state, ComponentStateTypeId::Started);
+        assert_eq!(statuses_0[0].vote_ratio, Ratio::zero());
+        assert_eq!(statuses_0[0].activation_at, None);
+
+        // 1 block out of 4 announces version 1: vote ratio changes but state does not
+        mip_store.update_network_version_stats(get_slot_ts(Slot::new(1, 0)), Some((0, Some(1))));
+        let statuses_1 = mip_store.get_mip_status_with_votes();
+        assert_eq!(statuses_1[0].vote_ratio, Ratio::new(1, 4));
+        assert_eq!(statuses_1[0].state, ComponentStateTypeId::Started);
+        assert!(get_versioning_announcements(&statuses_0, &statuses_1).is_empty());
+
+        // 3 blocks out of 4: the MIP is locked in and announced
+        mip_store.update_network_version_stats(get_slot_ts(Slot::new(1, 1)), Some((0, Some(1))));
+        mip_store.update_network_version_stats(get_slot_ts(Slot::new(2, 0)), Some((0, Some(1))));
+        let statuses_2 = mip_store.get_mip_status_with_votes();
+        assert_eq!(statuses_2[0].vote_ratio, Ratio::new(3, 4));
+        assert_eq!(statuses_2[0].state, ComponentStateTypeId::LockedIn);
+        assert!(statuses_2[0].activation_at.is_some());
+        let announcements = get_versioning_announcements(&statuses_1, &statuses_2);
+        assert_eq!(announcements, statuses_2);
+    }
 }